@@ -0,0 +1,34 @@
+//! Build script: embeds the git hash of the source tree as `PDTSP_GIT_HASH`
+//! so that `RunInfo` does not need to spawn git at run time.
+
+use std::process::Command;
+
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=PDTSP_GIT_HASH");
+
+    // Rebuild when HEAD moves or the current branch gets a new commit
+    if let Some(git_dir) = git(&["rev-parse", "--git-dir"]) {
+        println!("cargo:rerun-if-changed={}/HEAD", git_dir);
+        if let Some(head_ref) = git(&["symbolic-ref", "-q", "HEAD"]) {
+            println!("cargo:rerun-if-changed={}/{}", git_dir, head_ref);
+        }
+    }
+
+    // An explicit value (e.g. from a release pipeline without .git) wins
+    let hash = std::env::var("PDTSP_GIT_HASH")
+        .ok()
+        .filter(|h| !h.is_empty())
+        .or_else(|| git(&["rev-parse", "--short", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=PDTSP_GIT_HASH={}", hash);
+}
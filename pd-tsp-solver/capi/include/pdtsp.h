@@ -0,0 +1,81 @@
+#ifndef PDTSP_H
+#define PDTSP_H
+
+/* Generated by cbindgen from src/lib.rs, do not edit. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Opaque instance handle
+typedef struct PdtspInstance PdtspInstance;
+
+// Opaque solution handle
+typedef struct PdtspSolution PdtspSolution;
+
+// Message of the last error on this thread, or NULL. The pointer is valid
+// until the next failing call on the same thread.
+const char *pdtsp_last_error(void);
+
+// Load an instance (TSP-LIB, or JSON when the extension is `.json`).
+// Returns NULL on error.
+//
+// # Safety
+// `path` must be NULL or a valid NUL-terminated string.
+struct PdtspInstance *pdtsp_load_instance(const char *path);
+
+// Number of nodes of an instance, depot included
+//
+// # Safety
+// `instance` must be a handle returned by `pdtsp_load_instance`.
+size_t pdtsp_instance_dimension(const struct PdtspInstance *instance);
+
+// Solve an instance. `options_json` is NULL, empty or a JSON object with
+// the fields `algorithm`, `cost_function`, `alpha`, `beta`, `time_limit`,
+// `seed` and `max_profit`. Returns NULL on error.
+//
+// # Safety
+// `instance` must be a handle returned by `pdtsp_load_instance` and
+// `options_json` NULL or a valid NUL-terminated string.
+struct PdtspSolution *pdtsp_solve(const struct PdtspInstance *instance, const char *options_json);
+
+// Copy up to `capacity` node ids of the tour (starting at the depot 0) into
+// `out` and return the tour length, or -1 if `solution` is NULL. Call with
+// `out` NULL to query the length.
+//
+// # Safety
+// `solution` must be a handle returned by `pdtsp_solve` and `out` NULL or
+// valid for `capacity` writes.
+ptrdiff_t pdtsp_solution_tour(const struct PdtspSolution *solution, size_t *out, size_t capacity);
+
+// Travel cost of a solution (infinite when no tour was found)
+//
+// # Safety
+// `solution` must be a handle returned by `pdtsp_solve`.
+double pdtsp_solution_cost(const struct PdtspSolution *solution);
+
+// Total profit collected by a solution
+//
+// # Safety
+// `solution` must be a handle returned by `pdtsp_solve`.
+int32_t pdtsp_solution_profit(const struct PdtspSolution *solution);
+
+// Whether a solution respects the capacity
+//
+// # Safety
+// `solution` must be a handle returned by `pdtsp_solve`.
+bool pdtsp_solution_feasible(const struct PdtspSolution *solution);
+
+// Release a solution; NULL is ignored
+//
+// # Safety
+// `solution` must be NULL or a handle returned by `pdtsp_solve`, not yet freed.
+void pdtsp_free(struct PdtspSolution *solution);
+
+// Release an instance; NULL is ignored
+//
+// # Safety
+// `instance` must be NULL or a handle returned by `pdtsp_load_instance`, not yet freed.
+void pdtsp_free_instance(struct PdtspInstance *instance);
+
+#endif /* PDTSP_H */
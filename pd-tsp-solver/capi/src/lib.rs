@@ -0,0 +1,242 @@
+//! C API for embedding the solver in C/C++ programs.
+//!
+//! Instances and solutions are opaque handles owned by the caller and
+//! released with `pdtsp_free_instance` / `pdtsp_free`. Functions that can
+//! fail return NULL (or a negative count) and leave a message readable with
+//! `pdtsp_last_error`; a panic inside the solver is caught at the boundary
+//! and reported the same way instead of unwinding into C. The header
+//! `include/pdtsp.h` is generated with
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/pdtsp.h src/lib.rs
+//! ```
+//!
+//! and the library is built as `target/release/libpdtsp.{so,a}`.
+//! Algorithms and options are those of the browser API (see `wasm`).
+
+use pd_tsp_solver::wasm::{configure, parse_options, run};
+use pd_tsp_solver::{PDTSPInstance, Solution};
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// Opaque instance handle
+pub struct PdtspInstance(PDTSPInstance);
+
+/// Opaque solution handle
+pub struct PdtspSolution(Solution);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Run the body of an exported function, returning `on_panic` and setting
+/// the last error if it panics
+fn guard<T>(on_panic: T, body: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown error".to_string());
+        set_last_error(format!("Solver panicked: {}", message));
+        on_panic
+    })
+}
+
+/// Read a C string argument; NULL reads as an empty string
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Ok("");
+    }
+    CStr::from_ptr(s).to_str().map_err(|e| format!("Invalid UTF-8 argument: {}", e))
+}
+
+/// Message of the last error on this thread, or NULL. The pointer is valid
+/// until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn pdtsp_last_error() -> *const c_char {
+    guard(ptr::null(), || LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr())))
+}
+
+/// Load an instance (TSP-LIB, or JSON when the extension is `.json`).
+/// Returns NULL on error.
+///
+/// # Safety
+/// `path` must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pdtsp_load_instance(path: *const c_char) -> *mut PdtspInstance {
+    guard(ptr::null_mut(), || match read_str(path).and_then(PDTSPInstance::from_file) {
+        Ok(instance) => Box::into_raw(Box::new(PdtspInstance(instance))),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    })
+}
+
+/// Number of nodes of an instance, depot included
+///
+/// # Safety
+/// `instance` must be a handle returned by `pdtsp_load_instance`.
+#[no_mangle]
+pub unsafe extern "C" fn pdtsp_instance_dimension(instance: *const PdtspInstance) -> usize {
+    guard(0, || instance.as_ref().map_or(0, |instance| instance.0.dimension))
+}
+
+/// Solve an instance. `options_json` is NULL, empty or a JSON object with
+/// the fields `algorithm`, `cost_function`, `alpha`, `beta`, `time_limit`,
+/// `seed` and `max_profit`. Returns NULL on error.
+///
+/// # Safety
+/// `instance` must be a handle returned by `pdtsp_load_instance` and
+/// `options_json` NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pdtsp_solve(instance: *const PdtspInstance, options_json: *const c_char) -> *mut PdtspSolution {
+    guard(ptr::null_mut(), || {
+        let Some(instance) = instance.as_ref() else {
+            set_last_error("Null instance".to_string());
+            return ptr::null_mut();
+        };
+        let solved = read_str(options_json).and_then(parse_options).and_then(|options| {
+            let mut configured = instance.0.clone();
+            configure(&mut configured, &options)?;
+            run(&configured, &options)
+        });
+        match solved {
+            Ok(solution) => Box::into_raw(Box::new(PdtspSolution(solution))),
+            Err(e) => {
+                set_last_error(e);
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Copy up to `capacity` node ids of the tour (starting at the depot 0) into
+/// `out` and return the tour length, or -1 if `solution` is NULL. Call with
+/// `out` NULL to query the length.
+///
+/// # Safety
+/// `solution` must be a handle returned by `pdtsp_solve` and `out` NULL or
+/// valid for `capacity` writes.
+#[no_mangle]
+pub unsafe extern "C" fn pdtsp_solution_tour(solution: *const PdtspSolution, out: *mut usize, capacity: usize) -> isize {
+    guard(-1, || {
+        let Some(solution) = solution.as_ref() else {
+            return -1;
+        };
+        let tour = &solution.0.tour;
+        if !out.is_null() {
+            ptr::copy_nonoverlapping(tour.as_ptr(), out, tour.len().min(capacity));
+        }
+        tour.len() as isize
+    })
+}
+
+/// Travel cost of a solution (infinite when no tour was found)
+///
+/// # Safety
+/// `solution` must be a handle returned by `pdtsp_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn pdtsp_solution_cost(solution: *const PdtspSolution) -> f64 {
+    guard(f64::INFINITY, || solution.as_ref().map_or(f64::INFINITY, |solution| solution.0.cost))
+}
+
+/// Total profit collected by a solution
+///
+/// # Safety
+/// `solution` must be a handle returned by `pdtsp_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn pdtsp_solution_profit(solution: *const PdtspSolution) -> i32 {
+    guard(0, || solution.as_ref().map_or(0, |solution| solution.0.total_profit))
+}
+
+/// Whether a solution respects the capacity
+///
+/// # Safety
+/// `solution` must be a handle returned by `pdtsp_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn pdtsp_solution_feasible(solution: *const PdtspSolution) -> bool {
+    guard(false, || solution.as_ref().is_some_and(|solution| solution.0.feasible))
+}
+
+/// Release a solution; NULL is ignored
+///
+/// # Safety
+/// `solution` must be NULL or a handle returned by `pdtsp_solve`, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn pdtsp_free(solution: *mut PdtspSolution) {
+    guard((), || {
+        if !solution.is_null() {
+            drop(Box::from_raw(solution));
+        }
+    })
+}
+
+/// Release an instance; NULL is ignored
+///
+/// # Safety
+/// `instance` must be NULL or a handle returned by `pdtsp_load_instance`, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn pdtsp_free_instance(instance: *mut PdtspInstance) {
+    guard((), || {
+        if !instance.is_null() {
+            drop(Box::from_raw(instance));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c_api_round_trip() {
+        let file = std::env::temp_dir().join(format!("pdtsp_capi_test_{}.tsp", std::process::id()));
+        std::fs::write(&file, "NAME: tiny\nDIMENSION: 4\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 10 0\n3 10 10\n4 0 10\nDEMAND_SECTION\n1 0\n2 4\n3 -4\n4 0\nEOF\n").unwrap();
+        let path = CString::new(file.to_string_lossy().as_bytes()).unwrap();
+        let options = CString::new(r#"{ "algorithm": "nn" }"#).unwrap();
+        unsafe {
+            let instance = pdtsp_load_instance(path.as_ptr());
+            assert!(!instance.is_null());
+            assert_eq!(pdtsp_instance_dimension(instance), 4);
+
+            let solution = pdtsp_solve(instance, options.as_ptr());
+            assert!(!solution.is_null());
+            let len = pdtsp_solution_tour(solution, ptr::null_mut(), 0);
+            assert_eq!(len, 4);
+            let mut tour = vec![usize::MAX; len as usize];
+            assert_eq!(pdtsp_solution_tour(solution, tour.as_mut_ptr(), tour.len()), len);
+            assert_eq!(tour, (*solution).0.tour);
+            assert_eq!(tour[0], 0);
+            assert!(pdtsp_solution_cost(solution).is_finite());
+            pdtsp_free(solution);
+
+            let bad = CString::new(r#"{ "algorithm": "simplex" }"#).unwrap();
+            assert!(pdtsp_solve(instance, bad.as_ptr()).is_null());
+            let message = CStr::from_ptr(pdtsp_last_error()).to_str().unwrap();
+            assert!(message.contains("Unknown algorithm"));
+            pdtsp_free_instance(instance);
+            std::fs::remove_file(&file).ok();
+
+            let missing = CString::new("no/such/file.tsp").unwrap();
+            assert!(pdtsp_load_instance(missing.as_ptr()).is_null());
+        }
+    }
+
+    #[test]
+    fn test_panic_is_reported() {
+        let result = guard(-1, || -> isize { panic!("boom") });
+        assert_eq!(result, -1);
+        let message = unsafe { CStr::from_ptr(pdtsp_last_error()) }.to_str().unwrap();
+        assert_eq!(message, "Solver panicked: boom");
+    }
+}
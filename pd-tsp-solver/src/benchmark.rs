@@ -5,16 +5,29 @@
 
 use crate::instance::PDTSPInstance;
 use crate::solution::Solution;
+use crate::run_info::RunInfo;
+use crate::config::SolverConfig;
+use crate::best_known::{problem_key, BestKnownRegistry};
+use crate::run_record::{self, RunRecord};
+use crate::evaluation::evaluate;
+use crate::visualization::Visualizer;
+use crate::pool::RoutePool;
 use crate::heuristics::construction::*;
 use crate::heuristics::local_search::*;
 use crate::heuristics::genetic::{GeneticAlgorithm, GAConfig, MemeticAlgorithm};
 use crate::heuristics::aco::{AntColonyOptimization, ACOConfig, MaxMinAntSystem};
 use crate::exact::{GurobiSolver, GurobiConfig, ExactResult};
+use crate::exact::bounds::lagrangian_1tree_bound;
+use crate::heuristics::stopping::Deadline;
+use crate::heuristics::rng::RngFactory;
 
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
 
 /// Result of running a single algorithm on an instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,12 +46,28 @@ pub struct AlgorithmResult {
     pub feasible: bool,
     /// Computation time in seconds
     pub time: f64,
+    /// CPU time in seconds (if measured)
+    #[serde(default)]
+    pub cpu_time: Option<f64>,
+    /// Growth of the resident set size of the process during the run in MiB,
+    /// from its size at the start to its peak (if measured: serial runs only)
+    #[serde(default)]
+    pub peak_rss: Option<f64>,
     /// Number of iterations (if applicable)
     pub iterations: Option<usize>,
     /// Gap to best known (if available)
     pub gap_to_best: Option<f64>,
     /// Lower bound (if available)
     pub lower_bound: Option<f64>,
+    /// Gap to the lower bound in percent (if available)
+    #[serde(default)]
+    pub gap_to_bound: Option<f64>,
+    /// Emissions estimate (if a vehicle profile is attached)
+    #[serde(default)]
+    pub emissions: Option<f64>,
+    /// Convergence trace of the run (not exported to CSV)
+    #[serde(skip)]
+    pub convergence: Vec<(f64, f64)>,
 }
 
 /// Aggregated statistics for an algorithm
@@ -58,12 +87,137 @@ pub struct AlgorithmStatistics {
     pub worst_cost: f64,
     /// Standard deviation of cost
     pub std_cost: f64,
+    /// Median cost
+    #[serde(default)]
+    pub median_cost: f64,
+    /// Interquartile range of cost
+    #[serde(default)]
+    pub iqr_cost: f64,
     /// Average time
     pub avg_time: f64,
+    /// Median time
+    #[serde(default)]
+    pub median_time: f64,
+    /// Interquartile range of time
+    #[serde(default)]
+    pub iqr_time: f64,
     /// Total time
     pub total_time: f64,
+    /// Median CPU time (if measured)
+    #[serde(default)]
+    pub median_cpu_time: Option<f64>,
+    /// Largest resident set size growth over the runs in MiB (if measured)
+    #[serde(default)]
+    pub max_peak_rss: Option<f64>,
     /// Average gap to best known
     pub avg_gap: Option<f64>,
+    /// Average optimality gap to the instance's lower bound in percent
+    /// (if a bound is known)
+    #[serde(default)]
+    pub avg_gap_to_bound: Option<f64>,
+    /// Average emissions (if a vehicle profile is attached)
+    #[serde(default)]
+    pub avg_emissions: Option<f64>,
+}
+
+/// Algorithm family of a benchmark job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    /// All construction heuristics
+    Construction,
+    /// All local searches from the best construction
+    LocalSearch,
+    SimulatedAnnealing,
+    TabuSearch,
+    IteratedLocalSearch,
+    Genetic,
+    Memetic,
+    AntColony,
+    MaxMinAntSystem,
+}
+
+impl JobKind {
+    /// Every job kind, in recording order
+    pub const ALL: [JobKind; 9] = [
+        JobKind::Construction,
+        JobKind::LocalSearch,
+        JobKind::SimulatedAnnealing,
+        JobKind::TabuSearch,
+        JobKind::IteratedLocalSearch,
+        JobKind::Genetic,
+        JobKind::Memetic,
+        JobKind::AntColony,
+        JobKind::MaxMinAntSystem,
+    ];
+    
+    /// Short name of the kind in algorithm selections (`--algorithms`)
+    pub fn key(self) -> &'static str {
+        match self {
+            JobKind::Construction => "construction",
+            JobKind::LocalSearch => "local-search",
+            JobKind::SimulatedAnnealing => "sa",
+            JobKind::TabuSearch => "tabu",
+            JobKind::IteratedLocalSearch => "ils",
+            JobKind::Genetic => "ga",
+            JobKind::Memetic => "memetic",
+            JobKind::AntColony => "aco",
+            JobKind::MaxMinAntSystem => "mmas",
+        }
+    }
+}
+
+/// Key of the exact solver in algorithm selections
+pub const EXACT_KEY: &str = "exact";
+
+/// Key of the general VNS in algorithm selections (run by `compare` only)
+pub const GVNS_KEY: &str = "gvns";
+
+/// Former names of algorithm keys, still accepted in selections
+const KEY_ALIASES: [(&str, &str); 1] = [("vnd", "local-search")];
+
+/// Check an algorithm selection against the known keys (`JobKind::key`,
+/// `EXACT_KEY`, `GVNS_KEY`, whichever the command runs); names are case
+/// insensitive and returned in lower case, aliases as their key
+pub fn parse_algorithm_selection(names: &[String], known: &[&str]) -> Result<Vec<String>, String> {
+    names.iter().map(|name| {
+        let key = name.trim().to_lowercase();
+        let key = KEY_ALIASES.iter().find(|(alias, _)| *alias == key).map_or(key, |(_, key)| key.to_string());
+        if known.contains(&key.as_str()) {
+            Ok(key)
+        } else {
+            Err(format!("Unknown algorithm '{}' (expected one of: {})", name, known.join(", ")))
+        }
+    }).collect()
+}
+
+/// Runs of one algorithm in a benchmark, with their own seeds, time limit
+/// and parameters. A campaign lists them (see `campaign::CampaignSpec`);
+/// otherwise each selected algorithm gets the settings of `BenchmarkConfig`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlgorithmRuns {
+    pub kind: JobKind,
+    /// Name of the results in place of the standard one ("SA" in "SA-run3");
+    /// the construction and local search results get it as a prefix
+    pub label: Option<String>,
+    /// Seeds of the runs; deterministic algorithms run once
+    pub seeds: Vec<u64>,
+    /// Time limit per run in seconds
+    pub time_limit: f64,
+    /// Parameter overrides
+    pub solver: SolverConfig,
+}
+
+/// Unit of work of a parallel benchmark: one algorithm run on one instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchmarkJob {
+    /// Index of the instance
+    pub instance: usize,
+    /// Index of the algorithm's runs in the plan (see `Benchmark::plan`)
+    pub algorithm: usize,
+    /// Algorithm to run
+    pub kind: JobKind,
+    /// Seed of a stochastic run
+    pub seed: Option<u64>,
 }
 
 /// Benchmark configuration
@@ -79,10 +233,57 @@ pub struct BenchmarkConfig {
     pub exact_time_limit: f64,
     /// Run in parallel
     pub parallel: bool,
+    /// Worker threads of a parallel run (0 = one per CPU)
+    pub jobs: usize,
     /// Save intermediate results
     pub save_results: bool,
     /// Output directory
     pub output_dir: String,
+    /// Seeds and parameter overrides of the metaheuristics
+    pub solver: SolverConfig,
+    /// Best known solutions file, loaded at start and saved after each instance
+    pub best_known_path: Option<PathBuf>,
+    /// Unrecorded runs of the construction and local search jobs of the first
+    /// instance, made before any measured run
+    pub warmup_runs: usize,
+    /// Keys of the algorithms to run (see `JobKind::key` and `EXACT_KEY`);
+    /// empty runs them all. The exact solver also needs `run_exact`.
+    pub algorithms: Vec<String>,
+    /// Pool the routes of every run of an instance and add the best
+    /// combination found by set partitioning (see `RoutePool`)
+    pub set_partitioning: bool,
+    /// Algorithms of a campaign with their own settings, in recording order;
+    /// when set, they replace `algorithms`, `num_runs`, `time_limit` and
+    /// `solver` for the runs
+    pub campaign: Vec<AlgorithmRuns>,
+}
+
+impl BenchmarkConfig {
+    /// Whether the algorithm with this key is selected
+    pub fn selects(&self, key: &str) -> bool {
+        self.algorithms.is_empty() || self.algorithms.iter().any(|k| k == key)
+    }
+    
+    /// Runs of every algorithm: the campaign's, or the selected algorithms
+    /// with the configured seeds (0..num_runs by default), time limit and
+    /// parameters
+    pub fn plan(&self) -> Vec<AlgorithmRuns> {
+        if !self.campaign.is_empty() {
+            return self.campaign.clone();
+        }
+        let seeds: Vec<u64> = self.solver.seeds.clone()
+            .unwrap_or_else(|| (0..self.num_runs as u64).collect());
+        JobKind::ALL.iter()
+            .filter(|kind| self.selects(kind.key()))
+            .map(|&kind| AlgorithmRuns {
+                kind,
+                label: None,
+                seeds: seeds.clone(),
+                time_limit: self.time_limit,
+                solver: self.solver.clone(),
+            })
+            .collect()
+    }
 }
 
 impl Default for BenchmarkConfig {
@@ -93,8 +294,15 @@ impl Default for BenchmarkConfig {
             run_exact: false,
             exact_time_limit: 300.0,
             parallel: true,
+            jobs: 0,
             save_results: true,
             output_dir: "results".to_string(),
+            solver: SolverConfig::default(),
+            best_known_path: None,
+            warmup_runs: 1,
+            algorithms: Vec::new(),
+            set_partitioning: false,
+            campaign: Vec::new(),
         }
     }
 }
@@ -102,16 +310,52 @@ impl Default for BenchmarkConfig {
 /// Benchmarking engine
 pub struct Benchmark {
     config: BenchmarkConfig,
+    /// Runs of every algorithm, indexed by `BenchmarkJob::algorithm`
+    plan: Vec<AlgorithmRuns>,
     results: Vec<AlgorithmResult>,
     best_known: HashMap<String, f64>,
+    /// Best known solutions across sessions
+    registry: BestKnownRegistry,
+    /// Best lower bound per instance
+    lower_bounds: HashMap<String, f64>,
+    /// Detailed record of every run
+    records: Vec<RunRecord>,
+    run_info: RunInfo,
+    /// Whether the warm-up runs are done
+    warmed_up: bool,
+    /// Routes of the runs of each instance, for set partitioning
+    route_pools: HashMap<String, RoutePool>,
+    /// Why `best_known_path` could not be read; the file is then never overwritten
+    registry_error: Option<String>,
+    /// Whether runs measure their memory; off while runs share the process
+    measure_memory: bool,
 }
 
 impl Benchmark {
     pub fn new(config: BenchmarkConfig) -> Self {
+        // Stochastic runs use seeds 0..num_runs, so the base seed is 0
+        let run_info = RunInfo::capture(0, &format!("{:?}", config));
+        let (registry, registry_error) = match config.best_known_path.as_ref().map(BestKnownRegistry::load) {
+            Some(Err(e)) => {
+                tracing::warn!(error = %e, "Best known registry unreadable; it will not be overwritten");
+                (BestKnownRegistry::new(), Some(e))
+            }
+            Some(Ok(registry)) => (registry, None),
+            None => (BestKnownRegistry::new(), None),
+        };
         Benchmark {
+            plan: config.plan(),
             config,
             results: Vec::new(),
             best_known: HashMap::new(),
+            registry,
+            lower_bounds: HashMap::new(),
+            records: Vec::new(),
+            run_info,
+            warmed_up: false,
+            route_pools: HashMap::new(),
+            registry_error,
+            measure_memory: true,
         }
     }
     
@@ -120,215 +364,266 @@ impl Benchmark {
         self.best_known.insert(instance_name.to_string(), cost);
     }
     
+    /// Lower the best known cost of an instance, in this session and in the
+    /// persistent registry. Tours that skip nodes are not recorded.
+    fn update_best_known(&mut self, instance: &PDTSPInstance, cost: f64, algorithm: &str, tour: &[usize]) {
+        // The MIP tour may repeat the depot at the end
+        let tour = match tour.split_last() {
+            Some((0, rest)) if rest.len() + 1 > instance.dimension => rest,
+            _ => tour,
+        };
+        if !evaluate(instance, tour).valid {
+            return;
+        }
+        let best = self.best_known.entry(instance.name.clone()).or_insert(f64::INFINITY);
+        *best = best.min(cost);
+        self.registry.update(&problem_key(instance), cost, algorithm, tour);
+    }
+    
+    /// Take the instance's entry of the persistent registry as best known cost
+    fn load_best_known(&mut self, instance: &PDTSPInstance) {
+        if let Some(cost) = self.registry.cost(&problem_key(instance)) {
+            let best = self.best_known.entry(instance.name.clone()).or_insert(f64::INFINITY);
+            *best = best.min(cost);
+        }
+    }
+    
+    /// Recompute the gap to the best known cost of the instance's results
+    /// from `first` on, once the instance's best is final
+    fn apply_best_known(&mut self, instance_name: &str, first: usize) {
+        let Some(&best) = self.best_known.get(instance_name) else {
+            return;
+        };
+        for result in &mut self.results[first..] {
+            if result.instance == instance_name && result.algorithm != "Gurobi-Exact" {
+                result.gap_to_best = Some((result.cost - best) / best * 100.0);
+            }
+        }
+    }
+    
+    /// Save the registry to `best_known_path`, if set; refuses to replace a
+    /// file that could not be read at start, as its entries would be lost
+    pub fn save_best_known(&self) -> Result<(), String> {
+        match (&self.config.best_known_path, &self.registry_error) {
+            (Some(path), Some(e)) => Err(format!("Not overwriting {:?}: {}", path, e)),
+            (Some(path), None) => self.registry.save(path),
+            (None, _) => Ok(()),
+        }
+    }
+    
+    /// Persistent best known solutions
+    pub fn registry(&self) -> &BestKnownRegistry {
+        &self.registry
+    }
+    
     /// Run all construction heuristics on an instance
     pub fn run_construction_heuristics(&mut self, instance: &PDTSPInstance) {
+        for solution in self.construction_solutions(instance) {
+            self.record_result(instance, &solution, None);
+        }
+    }
+    
+    /// Run all local search methods on an initial solution
+    pub fn run_local_search(&mut self, instance: &PDTSPInstance, initial: Solution) {
+        for solution in self.local_search_solutions(instance, &initial, self.config.time_limit, &self.config.solver) {
+            self.record_result(instance, &solution, None);
+        }
+    }
+    
+    /// Runs of every algorithm, in recording order
+    pub fn plan(&self) -> &[AlgorithmRuns] {
+        &self.plan
+    }
+    
+    /// Run the metaheuristics of the plan on an instance
+    pub fn run_metaheuristics(&mut self, instance: &PDTSPInstance) {
+        for job in self.instance_jobs(0) {
+            if matches!(job.kind, JobKind::Construction | JobKind::LocalSearch) {
+                continue;
+            }
+            for solution in self.execute(instance, &job) {
+                self.record_result(instance, &solution, Some(&job));
+            }
+        }
+    }
+    
+    /// Jobs of the planned algorithms on an instance, in recording order
+    pub fn instance_jobs(&self, instance: usize) -> Vec<BenchmarkJob> {
+        let mut jobs = Vec::new();
+        for (algorithm, runs) in self.plan.iter().enumerate() {
+            let job = |seed| BenchmarkJob { instance, algorithm, kind: runs.kind, seed };
+            match runs.kind {
+                // Deterministic algorithms run once
+                JobKind::Construction | JobKind::LocalSearch | JobKind::TabuSearch => jobs.push(job(None)),
+                _ => jobs.extend(runs.seeds.iter().map(|&s| job(Some(s)))),
+            }
+        }
+        jobs
+    }
+    
+    /// Construction and local search jobs repeated `warmup_runs` times for
+    /// each of `threads` worker threads, unless the warm-up is done
+    fn warmup_jobs(&mut self, threads: usize) -> Vec<BenchmarkJob> {
+        if std::mem::replace(&mut self.warmed_up, true) {
+            return Vec::new();
+        }
+        let jobs: Vec<BenchmarkJob> = self.instance_jobs(0).into_iter()
+            .filter(|job| matches!(job.kind, JobKind::Construction | JobKind::LocalSearch))
+            .collect();
+        (0..self.config.warmup_runs * threads).flat_map(|_| jobs.iter().cloned()).collect()
+    }
+    
+    /// Solutions of a job; stochastic jobs without seed use seed 0
+    pub fn execute(&self, instance: &PDTSPInstance, job: &BenchmarkJob) -> Vec<Solution> {
+        let runs = &self.plan[job.algorithm];
+        let mut solutions = match job.kind {
+            JobKind::Construction => self.construction_solutions(instance),
+            JobKind::LocalSearch => {
+                let initial = self.get_initial_solution(instance);
+                self.local_search_solutions(instance, &initial, runs.time_limit, &runs.solver)
+            }
+            _ => return vec![self.metaheuristic_solution(instance, runs, job.seed.unwrap_or(0))],
+        };
+        if let Some(label) = &runs.label {
+            for solution in &mut solutions {
+                solution.algorithm = format!("{}/{}", label, solution.algorithm);
+            }
+        }
+        solutions
+    }
+    
+    /// Solutions of all construction heuristics
+    fn construction_solutions(&self, instance: &PDTSPInstance) -> Vec<Solution> {
         let heuristics: Vec<Box<dyn ConstructionHeuristic + Send + Sync>> = vec![
             Box::new(NearestNeighborHeuristic::new()),
             Box::new(GreedyInsertionHeuristic::new()),
             Box::new(GreedyInsertionHeuristic::farthest()),
             Box::new(SavingsHeuristic::new()),
-            Box::new(SweepHeuristic::new()),
+            Box::new(SweepHeuristic::rotated()),
             Box::new(RegretInsertionHeuristic::new(2)),
             Box::new(RegretInsertionHeuristic::new(3)),
             Box::new(ClusterFirstHeuristic::new()),
         ];
         
-        for heuristic in heuristics {
-            let solution = heuristic.construct(instance);
-            self.record_result(instance, &solution);
-        }
+        heuristics.iter().map(|heuristic| cpu_timed(self.measure_memory, || heuristic.construct(instance))).collect()
     }
     
-    /// Run all local search methods on an initial solution
-    pub fn run_local_search(&mut self, instance: &PDTSPInstance, initial: Solution) {
+    /// Solutions of all local search methods from an initial solution; the
+    /// `[vnd]` parameters apply to VND
+    fn local_search_solutions(&self, instance: &PDTSPInstance, initial: &Solution, time_limit: f64, params: &SolverConfig) -> Vec<Solution> {
+        let mut vnd = VND::with_standard_operators().with_deadline(Deadline::after(time_limit));
+        params.apply_vnd(&mut vnd);
         let searches: Vec<(&str, Box<dyn LocalSearch + Send + Sync>)> = vec![
             ("2-Opt", Box::new(TwoOptSearch::new())),
             ("Swap", Box::new(SwapSearch::new())),
             ("Relocation", Box::new(RelocationSearch::new())),
             ("Or-Opt", Box::new(OrOptSearch::new())),
-            ("VND", Box::new(VND::with_standard_operators())),
+            ("VND", Box::new(vnd)),
         ];
         
-        for (name, search) in searches {
-            let mut solution = initial.clone();
-            let start = std::time::Instant::now();
-            search.improve(instance, &mut solution);
-            solution.computation_time = start.elapsed().as_secs_f64();
+        searches.into_iter().map(|(name, search)| {
+            let mut solution = cpu_timed(self.measure_memory, || {
+                let mut solution = initial.clone();
+                let start = web_time::Instant::now();
+                search.improve(instance, &mut solution);
+                solution.computation_time = start.elapsed().as_secs_f64();
+                solution
+            });
             solution.algorithm = format!("{} + {}", initial.algorithm, name);
-            self.record_result(instance, &solution);
-        }
+            solution
+        }).collect()
     }
     
-    /// Run metaheuristics on an instance
-    pub fn run_metaheuristics(&mut self, instance: &PDTSPInstance) {
-        
-        for seed in 0..self.config.num_runs {
-            let mut sa = SimulatedAnnealing::new();
-            sa.seed = seed as u64;
-            
-            let mut solution = self.get_initial_solution(instance);
-            let start = std::time::Instant::now();
-            sa.improve(instance, &mut solution);
-            solution.computation_time = start.elapsed().as_secs_f64();
-            solution.algorithm = format!("SA-run{}", seed);
-            self.record_result(instance, &solution);
-        }
-        
-        
-        let ts = TabuSearch::new();
-        let mut solution = self.get_initial_solution(instance);
-        let start = std::time::Instant::now();
-        ts.improve(instance, &mut solution);
-        solution.computation_time = start.elapsed().as_secs_f64();
-        solution.algorithm = "TabuSearch".to_string();
-        self.record_result(instance, &solution);
-        
-        
-        for seed in 0..self.config.num_runs {
-            let mut ils = IteratedLocalSearch::new();
-            ils.seed = seed as u64;
-            
-            let mut solution = self.get_initial_solution(instance);
-            let start = std::time::Instant::now();
-            ils.improve(instance, &mut solution);
-            solution.computation_time = start.elapsed().as_secs_f64();
-            solution.algorithm = format!("ILS-run{}", seed);
-            self.record_result(instance, &solution);
-        }
-        
-        
-        for seed in 0..self.config.num_runs {
-            let ga_config = GAConfig {
-            seed: seed as u64,
-            population_size: 50,
-            max_generations: 200,
-            time_limit: self.config.time_limit,
-            ..Default::default()
-            };
-
-            let mut ga = GeneticAlgorithm::new(instance.clone(), ga_config);
-            let solution = ga.run();
-
-            let mut result = AlgorithmResult {
-            algorithm: format!("GA-run{}", seed),
-            instance: instance.name.clone(),
-            dimension: instance.dimension,
-            capacity: instance.capacity,
-            cost: solution.cost,
-            feasible: solution.feasible,
-            time: solution.computation_time,
-            iterations: solution.iterations,
-            gap_to_best: None,
-            lower_bound: None,
-            };
-
-            if let Some(&best) = self.best_known.get(&instance.name) {
-            result.gap_to_best = Some((result.cost - best) / best * 100.0);
-            }
-
-            self.results.push(result);
-        }
-        
-        for seed in 0..self.config.num_runs {
-            let ga_config = GAConfig {
-                seed: seed as u64,
-                time_limit: self.config.time_limit,
-                ..Default::default()
-            };
-            
-            let mut ma = MemeticAlgorithm::with_config(instance.clone(), ga_config);
-            let solution = ma.run();
-            
-            let mut result = AlgorithmResult {
-                algorithm: format!("MA-run{}", seed),
-                instance: instance.name.clone(),
-                dimension: instance.dimension,
-                capacity: instance.capacity,
-                cost: solution.cost,
-                feasible: solution.feasible,
-                time: solution.computation_time,
-                iterations: solution.iterations,
-                gap_to_best: None,
-                lower_bound: None,
-            };
-            
-            if let Some(&best) = self.best_known.get(&instance.name) {
-                result.gap_to_best = Some((result.cost - best) / best * 100.0);
-            }
-            
-            self.results.push(result);
-        }
-        
+    /// Solution of one metaheuristic run, stopped at the time limit
+    fn metaheuristic_solution(&self, instance: &PDTSPInstance, runs: &AlgorithmRuns, seed: u64) -> Solution {
+        let params = &runs.solver;
+        let time_limit = runs.time_limit;
+        let deadline = Deadline::after(time_limit);
+        let rng = RngFactory::new(seed);
+        // The label replaces the standard name, the run suffix stays
+        let run_name = |name: &str, seed: Option<u64>| {
+            let name = runs.label.as_deref().unwrap_or(name);
+            seed.map_or_else(|| name.to_string(), |seed| format!("{}-run{}", name, seed))
+        };
         
-        for seed in 0..self.config.num_runs {
-            let aco_config = ACOConfig {
-                seed: seed as u64,
+        let improve = |search: &dyn LocalSearch| {
+            let mut solution = MultiStartConstruction::with_all_heuristics_seeded(&rng).construct(instance);
+            cpu_timed(self.measure_memory, || {
+                let start = web_time::Instant::now();
+                search.improve(instance, &mut solution);
+                solution.computation_time = start.elapsed().as_secs_f64();
+                solution
+            })
+        };
+        let ga_config = |mut ga_config: GAConfig, stream: &str| {
+            params.apply_ga(&mut ga_config);
+            ga_config.seed = rng.derive(stream);
+            ga_config.time_limit = time_limit;
+            ga_config
+        };
+        let aco_config = || {
+            let mut aco_config = ACOConfig {
                 num_ants: 15,
                 max_iterations: 100,
-                time_limit: self.config.time_limit,
                 ..Default::default()
             };
-            
-            let mut aco = AntColonyOptimization::new(instance.clone(), aco_config);
-            let solution = aco.run();
-            
-            let mut result = AlgorithmResult {
-                algorithm: format!("ACO-run{}", seed),
-                instance: instance.name.clone(),
-                dimension: instance.dimension,
-                capacity: instance.capacity,
-                cost: solution.cost,
-                feasible: solution.feasible,
-                time: solution.computation_time,
-                iterations: solution.iterations,
-                gap_to_best: None,
-                lower_bound: None,
-            };
-            
-            if let Some(&best) = self.best_known.get(&instance.name) {
-                result.gap_to_best = Some((result.cost - best) / best * 100.0);
-            }
-            
-            self.results.push(result);
-        }
-        
+            params.apply_aco(&mut aco_config);
+            aco_config.seed = rng.derive("aco");
+            aco_config.time_limit = time_limit;
+            aco_config
+        };
         
-        for seed in 0..self.config.num_runs {
-            let aco_config = ACOConfig {
-                seed: seed as u64,
-                num_ants: 15,
-                max_iterations: 100,
-                time_limit: self.config.time_limit,
-                ..Default::default()
-            };
-            
-            let mut mmas = MaxMinAntSystem::new(instance.clone(), aco_config);
-            let solution = mmas.run();
-            
-            let mut result = AlgorithmResult {
-                algorithm: format!("MMAS-run{}", seed),
-                instance: instance.name.clone(),
-                dimension: instance.dimension,
-                capacity: instance.capacity,
-                cost: solution.cost,
-                feasible: solution.feasible,
-                time: solution.computation_time,
-                iterations: solution.iterations,
-                gap_to_best: None,
-                lower_bound: None,
-            };
-            
-            if let Some(&best) = self.best_known.get(&instance.name) {
-                result.gap_to_best = Some((result.cost - best) / best * 100.0);
+        let (name, mut solution) = match runs.kind {
+            JobKind::SimulatedAnnealing => {
+                let mut sa = SimulatedAnnealing::new();
+                params.apply_sa(&mut sa);
+                sa.seed = rng.derive("sa");
+                sa.deadline = deadline;
+                (run_name("SA", Some(seed)), improve(&sa))
             }
-            
-            self.results.push(result);
-        }
+            JobKind::TabuSearch => {
+                let mut ts = TabuSearch::new();
+                params.apply_tabu(&mut ts);
+                ts.seed = rng.derive("tabu");
+                ts.deadline = deadline;
+                (run_name("TabuSearch", None), improve(&ts))
+            }
+            JobKind::IteratedLocalSearch => {
+                let mut ils = IteratedLocalSearch::new();
+                params.apply_ils(&mut ils);
+                ils.seed = rng.derive("ils");
+                ils.deadline = deadline;
+                (run_name("ILS", Some(seed)), improve(&ils))
+            }
+            JobKind::Genetic => {
+                let config = ga_config(GAConfig {
+                    population_size: 50,
+                    max_generations: 200,
+                    ..Default::default()
+                }, "ga");
+                (run_name("GA", Some(seed)), cpu_timed(self.measure_memory, || GeneticAlgorithm::new(instance.clone(), config).run()))
+            }
+            JobKind::Memetic => {
+                let config = ga_config(GAConfig::default(), "memetic");
+                (run_name("MA", Some(seed)), cpu_timed(self.measure_memory, || MemeticAlgorithm::with_config(instance.clone(), config).run()))
+            }
+            // The ants run on the rayon threads, so the CPU time of this
+            // thread would miss most of the work: only the memory is measured
+            JobKind::AntColony => {
+                (run_name("ACO", Some(seed)), memory_sampled(self.measure_memory, || AntColonyOptimization::new(instance.clone(), aco_config()).run()))
+            }
+            JobKind::MaxMinAntSystem => {
+                (run_name("MMAS", Some(seed)), memory_sampled(self.measure_memory, || MaxMinAntSystem::new(instance.clone(), aco_config()).run()))
+            }
+            JobKind::Construction | JobKind::LocalSearch => unreachable!("not a metaheuristic job"),
+        };
+        solution.algorithm = name;
+        solution
     }
     
     /// Run exact solver on instance
     pub fn run_exact(&mut self, instance: &PDTSPInstance) -> Option<ExactResult> {
-        if !self.config.run_exact {
+        if !self.config.run_exact || !self.config.selects(EXACT_KEY) {
             return None;
         }
         
@@ -351,8 +646,10 @@ impl Benchmark {
             Ok(result) => {
                 
                 if result.solution.feasible {
-                    self.best_known.insert(instance.name.clone(), result.upper_bound);
+                    self.update_best_known(instance, result.upper_bound, "Gurobi-Exact", &result.solution.tour);
                 }
+                let bound = self.lower_bounds.entry(instance.name.clone()).or_insert(f64::NEG_INFINITY);
+                *bound = bound.max(result.lower_bound);
                 
                 let alg_result = AlgorithmResult {
                     algorithm: "Gurobi-Exact".to_string(),
@@ -362,16 +659,23 @@ impl Benchmark {
                     cost: result.upper_bound,
                     feasible: result.solution.feasible,
                     time: result.solution.computation_time,
+                    cpu_time: None,
+                    peak_rss: None,
                     iterations: None,
                     gap_to_best: Some(result.gap * 100.0),
                     lower_bound: Some(result.lower_bound),
+                    gap_to_bound: None,
+                    emissions: instance.tour_emissions(&result.solution.tour),
+                    convergence: Vec::new(),
                 };
                 
                 self.results.push(alg_result);
+                let config = self.config_snapshot(instance, None);
+                self.records.push(RunRecord::from_solution(instance, &result.solution, None, config));
                 Some(result)
             }
             Err(e) => {
-                log::error!("Gurobi solver failed: {}", e);
+                tracing::error!(instance = %instance.name, error = %e, "Gurobi solver failed");
                 None
             }
         }
@@ -379,35 +683,146 @@ impl Benchmark {
     
     /// Run full benchmark on an instance
     pub fn run_full_benchmark(&mut self, instance: &PDTSPInstance) {
-        log::info!("Running benchmark on instance: {}", instance.name);
-        
-        
-        self.run_construction_heuristics(instance);
-        
+        let _span = tracing::info_span!("benchmark", instance = %instance.name).entered();
+        tracing::info!(dimension = instance.dimension, "Running benchmark");
+        let first_result = self.results.len();
         
-        let best_construction = self.get_initial_solution(instance);
-        self.run_local_search(instance, best_construction);
+        self.compute_lower_bound(instance);
+        self.load_best_known(instance);
         
+        let warmup = self.warmup_jobs(1);
+        if !warmup.is_empty() {
+            tracing::info!(jobs = warmup.len(), "Warming up");
+        }
+        for job in &warmup {
+            self.execute(instance, job);
+        }
         
-        self.run_metaheuristics(instance);
-        
+        for job in self.instance_jobs(0) {
+            for solution in self.execute(instance, &job) {
+                self.record_result(instance, &solution, Some(&job));
+            }
+        }
         
+        self.run_set_partitioning(instance);
         self.run_exact(instance);
+        self.finish_instance(instance, first_result);
+    }
+    
+    /// Set partitioning over the routes pooled from the instance's runs; the
+    /// combination is recorded as a `SetPartitioning` result
+    fn run_set_partitioning(&mut self, instance: &PDTSPInstance) {
+        let Some(pool) = self.route_pools.remove(&instance.name) else {
+            return;
+        };
+        let start = web_time::Instant::now();
+        match pool.best_combination(instance, Deadline::after(self.config.time_limit)) {
+            Some(mut solution) => {
+                solution.computation_time = start.elapsed().as_secs_f64();
+                tracing::info!(routes = pool.len(), cost = solution.cost, "Set partitioning");
+                self.record_result(instance, &solution, None);
+            }
+            None => tracing::warn!(routes = pool.len(), "Set partitioning found no feasible combination"),
+        }
+        // Routes of the combination itself are not pooled again
+        self.route_pools.remove(&instance.name);
+    }
+    
+    /// Fill in the bounds and gaps of an instance's results and save the registry
+    fn finish_instance(&mut self, instance: &PDTSPInstance, first_result: usize) {
+        self.apply_lower_bound(&instance.name, first_result);
+        self.apply_best_known(&instance.name, first_result);
+        if let Err(e) = self.save_best_known() {
+            tracing::warn!(error = %e, "Cannot save the best known registry");
+        }
     }
     
-    /// Run benchmark on multiple instances
+    /// Compute the Lagrangian 1-tree lower bound of an instance
+    pub fn compute_lower_bound(&mut self, instance: &PDTSPInstance) -> f64 {
+        let bound = lagrangian_1tree_bound(instance);
+        let entry = self.lower_bounds.entry(instance.name.clone()).or_insert(f64::NEG_INFINITY);
+        *entry = entry.max(bound);
+        *entry
+    }
+    
+    /// Fill the lower bound and gap of the instance's results from `first` on
+    fn apply_lower_bound(&mut self, instance_name: &str, first: usize) {
+        let Some(&bound) = self.lower_bounds.get(instance_name) else {
+            return;
+        };
+        for result in &mut self.results[first..] {
+            if result.instance != instance_name {
+                continue;
+            }
+            result.lower_bound = Some(result.lower_bound.map_or(bound, |b| b.max(bound)));
+            if result.feasible && bound > 0.0 {
+                result.gap_to_bound = Some((result.cost - bound) / result.cost.abs().max(1e-9) * 100.0);
+            }
+        }
+    }
+    
+    /// Run benchmark on multiple instances. In parallel mode, the
+    /// (instance, algorithm, seed) jobs of all instances run on a pool of
+    /// `jobs` worker threads; results are recorded in job order, as in a
+    /// serial run. The exact solver, which is multi-threaded itself, runs
+    /// after the pool. Concurrent runs share the process, so their memory is
+    /// not measured.
     pub fn run_on_instances(&mut self, instances: &[PDTSPInstance]) {
-        if self.config.parallel {
-            
-            
+        if !self.config.parallel || self.config.jobs == 1 {
             for instance in instances {
                 self.run_full_benchmark(instance);
             }
-        } else {
-            for instance in instances {
-                self.run_full_benchmark(instance);
+            return;
+        }
+        
+        let pool = match rayon::ThreadPoolBuilder::new().num_threads(self.config.jobs).build() {
+            Ok(pool) => pool,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to build the thread pool, running serially");
+                for instance in instances {
+                    self.run_full_benchmark(instance);
+                }
+                return;
+            }
+        };
+        
+        let first_result = self.results.len();
+        for instance in instances {
+            self.compute_lower_bound(instance);
+            self.load_best_known(instance);
+        }
+        
+        // Every worker thread gets its share of the warm-up runs
+        let warmup = self.warmup_jobs(pool.current_num_threads());
+        if !warmup.is_empty() && !instances.is_empty() {
+            tracing::info!(jobs = warmup.len(), "Warming up");
+            let this = &*self;
+            pool.install(|| warmup.par_iter().for_each(|job| {
+                this.execute(&instances[0], job);
+            }));
+        }
+        
+        let jobs: Vec<BenchmarkJob> = (0..instances.len()).flat_map(|i| self.instance_jobs(i)).collect();
+        tracing::info!(jobs = jobs.len(), threads = pool.current_num_threads(), "Running benchmark jobs");
+        self.measure_memory = pool.current_num_threads() == 1;
+        let this = &*self;
+        let outputs: Vec<Vec<Solution>> = pool.install(|| {
+            jobs.par_iter()
+                .map(|job| this.execute(&instances[job.instance], job))
+                .collect()
+        });
+        self.measure_memory = true;
+        
+        for (job, solutions) in jobs.iter().zip(outputs) {
+            for solution in &solutions {
+                self.record_result(&instances[job.instance], solution, Some(job));
             }
         }
+        for instance in instances {
+            self.run_set_partitioning(instance);
+            self.run_exact(instance);
+            self.finish_instance(instance, first_result);
+        }
     }
     
     /// Get initial solution using multi-start construction
@@ -416,8 +831,19 @@ impl Benchmark {
         multi.construct(instance)
     }
     
-    /// Record a result
-    fn record_result(&mut self, instance: &PDTSPInstance, solution: &Solution) {
+    /// Record a result of a job (`None` for runs outside the plan); a
+    /// feasible solution better than the best known one updates the registry
+    fn record_result(&mut self, instance: &PDTSPInstance, solution: &Solution, job: Option<&BenchmarkJob>) {
+        if solution.feasible {
+            self.update_best_known(instance, solution.cost, &solution.algorithm, &solution.tour);
+        }
+        // Infeasible tours may still have feasible routes
+        if self.config.set_partitioning {
+            self.route_pools.entry(instance.name.clone()).or_default().offer_tour(instance, &solution.tour);
+        }
+        let config = self.config_snapshot(instance, job);
+        self.records.push(RunRecord::from_solution(instance, solution, job.and_then(|job| job.seed), config));
+        
         let mut result = AlgorithmResult {
             algorithm: solution.algorithm.clone(),
             instance: instance.name.clone(),
@@ -426,9 +852,14 @@ impl Benchmark {
             cost: solution.cost,
             feasible: solution.feasible,
             time: solution.computation_time,
+            cpu_time: solution.cpu_time,
+            peak_rss: solution.peak_rss,
             iterations: solution.iterations,
             gap_to_best: None,
             lower_bound: None,
+            gap_to_bound: None,
+            emissions: instance.tour_emissions(&solution.tour),
+            convergence: solution.convergence.clone(),
         };
         
         if let Some(&best) = self.best_known.get(&instance.name) {
@@ -438,6 +869,22 @@ impl Benchmark {
         self.results.push(result);
     }
     
+    /// Configuration snapshot stored in the run records of an instance: the
+    /// settings of the job's algorithm, or the benchmark's
+    fn config_snapshot(&self, instance: &PDTSPInstance, job: Option<&BenchmarkJob>) -> serde_json::Value {
+        let (time_limit, solver) = match job {
+            Some(job) => (self.plan[job.algorithm].time_limit, &self.plan[job.algorithm].solver),
+            None => (self.config.time_limit, &self.config.solver),
+        };
+        serde_json::json!({
+            "cost_function": instance.cost_function,
+            "alpha": instance.alpha,
+            "beta": instance.beta,
+            "time_limit": time_limit,
+            "solver": solver,
+        })
+    }
+    
     /// Compute statistics for each algorithm
     pub fn compute_statistics(&self) -> Vec<AlgorithmStatistics> {
         let mut stats_map: HashMap<String, Vec<&AlgorithmResult>> = HashMap::new();
@@ -477,12 +924,42 @@ impl Benchmark {
             
             let avg_time = times.iter().sum::<f64>() / times.len() as f64;
             let total_time = times.iter().sum::<f64>();
+            let (median_cost, iqr_cost) = median_and_iqr(costs.clone());
+            let (median_time, iqr_time) = median_and_iqr(times);
+            let cpu_times: Vec<f64> = feasible_results.iter()
+                .filter_map(|r| r.cpu_time)
+                .collect();
+            let median_cpu_time = if !cpu_times.is_empty() {
+                Some(median_and_iqr(cpu_times).0)
+            } else {
+                None
+            };
+            let max_peak_rss = results.iter()
+                .filter_map(|r| r.peak_rss)
+                .reduce(f64::max);
             
             let avg_gap = if !gaps.is_empty() {
                 Some(gaps.iter().sum::<f64>() / gaps.len() as f64)
             } else {
                 None
             };
+            let bound_gaps: Vec<f64> = feasible_results.iter()
+                .filter_map(|r| r.gap_to_bound)
+                .collect();
+            let avg_gap_to_bound = if !bound_gaps.is_empty() {
+                Some(bound_gaps.iter().sum::<f64>() / bound_gaps.len() as f64)
+            } else {
+                None
+            };
+            
+            let emissions: Vec<f64> = feasible_results.iter()
+                .filter_map(|r| r.emissions)
+                .collect();
+            let avg_emissions = if !emissions.is_empty() {
+                Some(emissions.iter().sum::<f64>() / emissions.len() as f64)
+            } else {
+                None
+            };
             
             statistics.push(AlgorithmStatistics {
                 algorithm: algo,
@@ -492,9 +969,17 @@ impl Benchmark {
                 best_cost,
                 worst_cost,
                 std_cost,
+                median_cost,
+                iqr_cost,
                 avg_time,
+                median_time,
+                iqr_time,
                 total_time,
+                median_cpu_time,
+                max_peak_rss,
                 avg_gap,
+                avg_gap_to_bound,
+                avg_emissions,
             });
         }
         
@@ -506,6 +991,7 @@ impl Benchmark {
     
     /// Export results to CSV
     pub fn export_to_csv<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        self.run_info.write_sidecar(&path)?;
         let file = File::create(path)?;
         let mut writer = csv::Writer::from_writer(file);
         
@@ -519,6 +1005,7 @@ impl Benchmark {
     
     /// Export statistics to CSV
     pub fn export_statistics_csv<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        self.run_info.write_sidecar(&path)?;
         let file = File::create(path)?;
         let mut writer = csv::Writer::from_writer(file);
         
@@ -542,30 +1029,51 @@ impl Benchmark {
         let stats = self.compute_statistics();
         
         report.push_str("Algorithm Performance Summary:\n");
-        report.push_str("-".repeat(80).as_str());
+        report.push_str("-".repeat(93).as_str());
         report.push('\n');
-        report.push_str(&format!("{:<25} {:>10} {:>12} {:>12} {:>12} {:>10}\n",
-            "Algorithm", "Feasible", "Avg Cost", "Best Cost", "Avg Gap%", "Avg Time"));
-        report.push_str("-".repeat(80).as_str());
+        report.push_str(&format!("{:<25} {:>10} {:>12} {:>12} {:>12} {:>12} {:>10}\n",
+            "Algorithm", "Feasible", "Avg Cost", "Best Cost", "Avg Gap%", "LB Gap%", "Avg Time"));
+        report.push_str("-".repeat(93).as_str());
         report.push('\n');
         
         for stat in &stats {
             let gap_str = stat.avg_gap
                 .map(|g| format!("{:.2}%", g))
                 .unwrap_or_else(|| "-".to_string());
+            let bound_gap_str = stat.avg_gap_to_bound
+                .map(|g| format!("{:.2}%", g))
+                .unwrap_or_else(|| "-".to_string());
             
-            report.push_str(&format!("{:<25} {:>10} {:>12.2} {:>12.2} {:>12} {:>10.4}\n",
+            report.push_str(&format!("{:<25} {:>10} {:>12.2} {:>12.2} {:>12} {:>12} {:>10.4}\n",
                 stat.algorithm,
                 format!("{}/{}", stat.num_feasible, stat.num_instances),
                 stat.avg_cost,
                 stat.best_cost,
                 gap_str,
+                bound_gap_str,
                 stat.avg_time));
         }
         
-        report.push_str("-".repeat(80).as_str());
+        report.push_str("-".repeat(93).as_str());
         report.push('\n');
         
+        report.push_str("\nTiming (median and interquartile range, seconds):\n");
+        for stat in &stats {
+            let cpu_str = stat.median_cpu_time
+                .map(|t| format!(", CPU {:.4}", t))
+                .unwrap_or_default();
+            report.push_str(&format!("  {}: {:.4} (IQR {:.4}){}\n",
+                stat.algorithm, stat.median_time, stat.iqr_time, cpu_str));
+        }
+        
+        if stats.iter().any(|s| s.avg_emissions.is_some()) {
+            report.push_str("\nAverage Emissions:\n");
+            for stat in &stats {
+                if let Some(emissions) = stat.avg_emissions {
+                    report.push_str(&format!("  {}: {:.2}\n", stat.algorithm, emissions));
+                }
+            }
+        }
         
         report.push_str("\nBest Solutions per Instance:\n");
         
@@ -589,6 +1097,8 @@ impl Benchmark {
                 instance, best_result.cost, best_result.algorithm));
         }
         
+        report.push_str(&format!("\nRun: {}\n", self.run_info.summary()));
+        
         report
     }
     
@@ -597,10 +1107,499 @@ impl Benchmark {
         &self.results
     }
     
+    /// Detailed record of every run
+    pub fn run_records(&self) -> &[RunRecord] {
+        &self.records
+    }
+    
+    /// Write the run records as JSON Lines
+    pub fn export_run_records<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        run_record::write_jsonl(path, &self.records)
+    }
+    
+    /// Metadata of this benchmark run
+    pub fn run_info(&self) -> &RunInfo {
+        &self.run_info
+    }
+    
+    /// Get the lower bounds computed so far
+    pub fn lower_bounds(&self) -> &HashMap<String, f64> {
+        &self.lower_bounds
+    }
+    
     /// Get best known values
     pub fn best_known(&self) -> &HashMap<String, f64> {
         &self.best_known
     }
+    
+    /// Performance ratios of each algorithm over the instances; the runs of a
+    /// stochastic algorithm ("SA-run0", "SA-run1", ...) count as one solver
+    pub fn performance_ratios(&self) -> Vec<(String, Vec<f64>)> {
+        let entries: Vec<(String, String, f64)> = self.results.iter()
+            .map(|r| (
+                r.instance.clone(),
+                solver_name(&r.algorithm).to_string(),
+                if r.feasible { r.cost } else { f64::INFINITY },
+            ))
+            .collect();
+        performance_ratios(&entries)
+    }
+    
+    /// Time each run took to reach the target cost of its instance, the best
+    /// cost known on it times (1 + `target_gap`). The time is read from the
+    /// run's convergence trace when it has one, otherwise a run reaches the
+    /// target at its end if its final cost does. Returns the sorted times of
+    /// each solver, infinite for runs that missed the target.
+    pub fn time_to_target(&self, target_gap: f64) -> Vec<(String, Vec<f64>)> {
+        let mut targets: HashMap<&str, f64> = HashMap::new();
+        for r in self.results.iter().filter(|r| r.feasible) {
+            let best = targets.entry(&r.instance).or_insert(f64::INFINITY);
+            *best = best.min(r.cost);
+        }
+        for (instance, &known) in &self.best_known {
+            if let Some(best) = targets.get_mut(instance.as_str()) {
+                *best = best.min(known);
+            }
+        }
+        
+        let mut times: Vec<(String, Vec<f64>)> = Vec::new();
+        for r in &self.results {
+            let solver = solver_name(&r.algorithm);
+            let time = match targets.get(r.instance.as_str()) {
+                Some(&best) if r.feasible => {
+                    let target = best + target_gap * best.abs();
+                    r.convergence.iter()
+                        .find(|&&(_, cost)| cost <= target + 1e-9)
+                        .map(|&(t, _)| t)
+                        .or_else(|| (r.cost <= target + 1e-9).then_some(r.time))
+                        .unwrap_or(f64::INFINITY)
+                }
+                _ => f64::INFINITY,
+            };
+            match times.iter_mut().find(|(name, _)| name == solver) {
+                Some((_, values)) => values.push(time),
+                None => times.push((solver.to_string(), vec![time])),
+            }
+        }
+        
+        for (_, values) in times.iter_mut() {
+            values.sort_by(|a, b| a.total_cmp(b));
+        }
+        times
+    }
+    
+    /// Write the Dolan-Moré performance profiles and the time-to-target CDFs
+    /// (CSV data and SVG plots) to a directory; returns the written files
+    pub fn export_performance_profiles<P: AsRef<Path>>(&self, dir: P, target_gap: f64) -> std::io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        let viz = Visualizer::new();
+        let mut written = Vec::new();
+        
+        let ratios = self.performance_ratios();
+        let path = dir.join("performance_profile.csv");
+        std::fs::write(&path, performance_profile_csv(&ratios))?;
+        self.run_info.write_sidecar(&path)?;
+        written.push(path);
+        let path = dir.join("performance_profile.svg");
+        viz.save_svg(&viz.generate_performance_profile_svg(&ratios), &path)?;
+        written.push(path);
+        
+        let times = self.time_to_target(target_gap);
+        let path = dir.join("time_to_target.csv");
+        let mut csv = String::from("algorithm,time,probability\n");
+        for (solver, values) in &times {
+            for (k, t) in values.iter().enumerate().filter(|(_, t)| t.is_finite()) {
+                csv.push_str(&format!("{},{:.6},{:.4}\n", solver, t, (k + 1) as f64 / values.len() as f64));
+            }
+        }
+        std::fs::write(&path, csv)?;
+        self.run_info.write_sidecar(&path)?;
+        written.push(path);
+        let path = dir.join("time_to_target.svg");
+        viz.save_svg(&viz.generate_time_to_target_svg(&times, target_gap), &path)?;
+        written.push(path);
+        
+        Ok(written)
+    }
+}
+
+/// Run `solve` and record the CPU time this thread spent on it and, if
+/// `measure_memory`, the memory growth of the process
+fn cpu_timed(measure_memory: bool, solve: impl FnOnce() -> Solution) -> Solution {
+    memory_sampled(measure_memory, || {
+        let start = thread_cpu_time();
+        let mut solution = solve();
+        solution.cpu_time = start.zip(thread_cpu_time()).map(|(start, end)| end - start);
+        solution
+    })
+}
+
+/// Run `solve` and, if `measure_memory`, record the growth of the resident
+/// set size of the process (see `RssSampler`)
+fn memory_sampled(measure_memory: bool, solve: impl FnOnce() -> Solution) -> Solution {
+    if !measure_memory {
+        return solve();
+    }
+    let sampler = RssSampler::start();
+    let mut solution = solve();
+    solution.peak_rss = sampler.stop();
+    solution
+}
+
+/// Peaks of the samplers in progress, raised by the monitor thread
+static SAMPLERS: Mutex<Vec<Weak<AtomicU64>>> = Mutex::new(Vec::new());
+/// Thread sampling the resident set size for every sampler in progress
+static MONITOR: OnceLock<std::thread::Thread> = OnceLock::new();
+
+/// Growth of the resident set size of the process from the start of the
+/// sampling to its peak. A single monitor thread, started with the first
+/// sampler and parked while none is in progress, samples the size every
+/// `RssSampler::INTERVAL`. The size is that of the whole process: memory
+/// freed by other threads meanwhile hides part of the growth and memory they
+/// allocate adds to it, so the growth measures one run only when no other
+/// runs at the same time (the benchmark leaves it out of parallel runs).
+pub struct RssSampler {
+    /// Size at the start and peak so far, in bytes
+    baseline: u64,
+    peak: Option<Arc<AtomicU64>>,
+}
+
+impl RssSampler {
+    /// Time between two samples
+    pub const INTERVAL: std::time::Duration = std::time::Duration::from_millis(5);
+    
+    /// Start sampling; nothing is sampled where the size cannot be read
+    pub fn start() -> Self {
+        let Some(baseline) = resident_set_size() else {
+            return RssSampler { baseline: 0, peak: None };
+        };
+        let peak = Arc::new(AtomicU64::new(baseline));
+        SAMPLERS.lock().unwrap_or_else(|e| e.into_inner()).push(Arc::downgrade(&peak));
+        MONITOR.get_or_init(|| std::thread::spawn(Self::monitor).thread().clone()).unpark();
+        RssSampler { baseline, peak: Some(peak) }
+    }
+    
+    /// Loop of the monitor thread
+    fn monitor() {
+        loop {
+            let active: Vec<Arc<AtomicU64>> = {
+                let mut samplers = SAMPLERS.lock().unwrap_or_else(|e| e.into_inner());
+                samplers.retain(|peak| peak.strong_count() > 0);
+                samplers.iter().filter_map(Weak::upgrade).collect()
+            };
+            if active.is_empty() {
+                std::thread::park();
+                continue;
+            }
+            if let Some(size) = resident_set_size() {
+                active.iter().for_each(|peak| { peak.fetch_max(size, Ordering::Relaxed); });
+            }
+            drop(active);
+            std::thread::park_timeout(Self::INTERVAL);
+        }
+    }
+    
+    /// Take a sample now, in addition to the periodic ones
+    pub fn sample(&self) {
+        if let (Some(peak), Some(size)) = (&self.peak, resident_set_size()) {
+            peak.fetch_max(size, Ordering::Relaxed);
+        }
+    }
+    
+    /// Stop sampling and return the growth in MiB, or `None` if unmeasured
+    pub fn stop(self) -> Option<f64> {
+        self.sample();
+        let peak = self.peak.as_ref()?.load(Ordering::Relaxed);
+        Some(peak.saturating_sub(self.baseline) as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// Resident set size of the process in bytes, or `None` where it cannot
+/// be read (only Linux's /proc is supported)
+pub fn resident_set_size() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        // statm lists sizes in pages: total, resident, ...
+        let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+        let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+        // SAFETY: sysconf has no preconditions
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        (page_size > 0).then(|| pages * page_size as u64)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// CPU time consumed by the calling thread in seconds, or `None` where it
+/// cannot be measured. Unlike the wall time, it leaves out the time the
+/// thread waits for the CPU, which makes it less sensitive to a loaded
+/// machine; work done on other threads is not counted.
+pub fn thread_cpu_time() -> Option<f64> {
+    #[cfg(unix)]
+    {
+        let mut time = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+        // SAFETY: `time` is a valid timespec to write to
+        let status = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) };
+        (status == 0).then_some(time.tv_sec as f64 + time.tv_nsec as f64 * 1e-9)
+    }
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// Median and interquartile range of non-empty `values`, with linear
+/// interpolation between order statistics
+pub fn median_and_iqr(mut values: Vec<f64>) -> (f64, f64) {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let quantile = |q: f64| {
+        let rank = q * (values.len() - 1) as f64;
+        let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+        values[low] + (values[high] - values[low]) * (rank - low as f64)
+    };
+    (quantile(0.5), quantile(0.75) - quantile(0.25))
+}
+
+/// Solver of a result: the algorithm name without its "-runN" suffix
+pub(crate) fn solver_name(algorithm: &str) -> &str {
+    match algorithm.rsplit_once("-run") {
+        Some((name, run)) if !run.is_empty() && run.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => algorithm,
+    }
+}
+
+/// Dolan-Moré performance ratios.
+///
+/// `entries` holds one `(problem, solver, cost)` triple per run, with an
+/// infinite cost for runs that found no feasible solution. A solver is scored
+/// on a problem by its mean feasible cost, and its ratio is that score over the
+/// best score on the problem (infinite if it never solved it). Problems that no
+/// solver solved are dropped. Returns the sorted ratios of each solver, in
+/// order of first appearance.
+pub fn performance_ratios(entries: &[(String, String, f64)]) -> Vec<(String, Vec<f64>)> {
+    let mut solvers: Vec<String> = Vec::new();
+    let mut problems: Vec<String> = Vec::new();
+    let mut sums: HashMap<(String, String), (f64, usize)> = HashMap::new();
+    
+    for (problem, solver, cost) in entries {
+        if !solvers.contains(solver) {
+            solvers.push(solver.clone());
+        }
+        if !problems.contains(problem) {
+            problems.push(problem.clone());
+        }
+        if cost.is_finite() {
+            let entry = sums.entry((problem.clone(), solver.clone())).or_insert((0.0, 0));
+            entry.0 += cost;
+            entry.1 += 1;
+        }
+    }
+    
+    let score = |problem: &String, solver: &String| {
+        sums.get(&(problem.clone(), solver.clone()))
+            .map(|&(sum, count)| sum / count as f64)
+            .unwrap_or(f64::INFINITY)
+    };
+    
+    let mut ratios: Vec<(String, Vec<f64>)> = solvers.iter().map(|s| (s.clone(), Vec::new())).collect();
+    for problem in &problems {
+        let best = solvers.iter().map(|s| score(problem, s)).fold(f64::INFINITY, f64::min);
+        if !best.is_finite() {
+            continue;
+        }
+        for (solver, values) in ratios.iter_mut() {
+            let value = score(problem, solver);
+            values.push(if best > 0.0 { value / best } else if value > 0.0 { f64::INFINITY } else { 1.0 });
+        }
+    }
+    
+    for (_, values) in ratios.iter_mut() {
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    }
+    
+    ratios
+}
+
+/// Fraction of problems a solver solves within a factor `tau` of the best,
+/// given its sorted performance ratios
+pub fn performance_profile_at(ratios: &[f64], tau: f64) -> f64 {
+    if ratios.is_empty() {
+        return 0.0;
+    }
+    ratios.iter().filter(|&&r| r <= tau).count() as f64 / ratios.len() as f64
+}
+
+/// Performance profiles as CSV: one row per breakpoint tau, one column per solver
+pub fn performance_profile_csv(ratios: &[(String, Vec<f64>)]) -> String {
+    let mut taus: Vec<f64> = ratios.iter()
+        .flat_map(|(_, r)| r.iter().cloned())
+        .filter(|r| r.is_finite())
+        .collect();
+    taus.push(1.0);
+    taus.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    taus.dedup();
+
+    let mut csv = String::from("tau");
+    for (solver, _) in ratios {
+        csv.push_str(&format!(",{}", solver));
+    }
+    csv.push('\n');
+    for tau in taus {
+        csv.push_str(&format!("{:.6}", tau));
+        for (_, r) in ratios {
+            csv.push_str(&format!(",{:.4}", performance_profile_at(r, tau)));
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Best solution of a solver restarted until `budget` seconds have passed.
+/// Every restart receives the deadline of the whole budget and its own seed
+/// (`seed + (k << 32)` for restart `k`), so all solvers get the same
+/// wall-clock time however fast a single run is. The returned convergence
+/// trace is the best feasible cost found versus elapsed time over all
+/// restarts, and `iterations` the number of restarts. A budget that is not
+/// finite gives a single run.
+pub fn restart_until_budget(budget: f64, seed: u64, mut solve: impl FnMut(u64, Deadline) -> Solution) -> Solution {
+    let start = web_time::Instant::now();
+    let deadline = Deadline::after_from(start, budget);
+    let mut best: Option<Solution> = None;
+    let mut trace: Vec<(f64, f64)> = Vec::new();
+    let mut best_cost = f64::INFINITY;
+    let mut restarts = 0;
+    
+    loop {
+        let offset = start.elapsed().as_secs_f64();
+        let solution = solve(seed.wrapping_add((restarts as u64) << 32), deadline);
+        restarts += 1;
+        
+        if solution.feasible {
+            let end = (start.elapsed().as_secs_f64(), solution.cost);
+            for (t, cost) in solution.convergence.iter().map(|&(t, c)| (offset + t, c)).chain([end]) {
+                if cost < best_cost {
+                    best_cost = cost;
+                    trace.push((t, cost));
+                }
+            }
+        }
+        let improves = match &best {
+            None => true,
+            Some(b) => solution.feasible && (!b.feasible || solution.cost < b.cost),
+        };
+        if improves {
+            best = Some(solution);
+        }
+        
+        if deadline.expired() || !budget.is_finite() {
+            break;
+        }
+    }
+    
+    let mut best = best.unwrap_or_default();
+    best.computation_time = start.elapsed().as_secs_f64();
+    best.iterations = Some(restarts);
+    best.convergence = trace;
+    best
+}
+
+/// Cost and feasibility of a fixed tour in one perturbed scenario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioResult {
+    pub seed: u64,
+    pub cost: f64,
+    pub feasible: bool,
+    /// Total load excess outside [0, capacity] along the tour
+    pub load_violation: i64,
+}
+
+/// Behavior of a fixed tour across perturbed scenarios of an instance
+/// (`PDTSPInstance::perturb`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobustnessReport {
+    pub nominal_cost: f64,
+    pub nominal_feasible: bool,
+    pub coordinate_noise: f64,
+    pub demand_noise: f64,
+    pub scenarios: Vec<ScenarioResult>,
+}
+
+impl RobustnessReport {
+    /// Fraction of the scenarios in which the tour stays feasible
+    pub fn feasible_fraction(&self) -> f64 {
+        if self.scenarios.is_empty() {
+            return 0.0;
+        }
+        self.scenarios.iter().filter(|s| s.feasible).count() as f64 / self.scenarios.len() as f64
+    }
+    
+    pub fn mean_cost(&self) -> f64 {
+        self.scenarios.iter().map(|s| s.cost).sum::<f64>() / self.scenarios.len().max(1) as f64
+    }
+    
+    pub fn cost_std(&self) -> f64 {
+        let mean = self.mean_cost();
+        let n = self.scenarios.len();
+        if n < 2 {
+            return 0.0;
+        }
+        (self.scenarios.iter().map(|s| (s.cost - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+    }
+    
+    pub fn worst_cost(&self) -> f64 {
+        self.scenarios.iter().map(|s| s.cost).fold(f64::NEG_INFINITY, f64::max)
+    }
+    
+    /// One line per scenario: seed, cost, feasible, load_violation
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("seed,cost,feasible,load_violation\n");
+        for s in &self.scenarios {
+            csv.push_str(&format!("{},{:.4},{},{}\n", s.seed, s.cost, s.feasible, s.load_violation));
+        }
+        csv
+    }
+}
+
+impl std::fmt::Display for RobustnessReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Scenarios: {} (coordinate noise {}, demand noise {})", self.scenarios.len(), self.coordinate_noise, self.demand_noise)?;
+        writeln!(f, "Nominal cost: {:.2} (feasible: {})", self.nominal_cost, self.nominal_feasible)?;
+        writeln!(f, "Feasible in: {:.1}% of the scenarios", 100.0 * self.feasible_fraction())?;
+        write!(f, "Cost: mean {:.2}, std {:.2}, worst {:.2}", self.mean_cost(), self.cost_std(), self.worst_cost())
+    }
+}
+
+/// Evaluate a fixed tour on `scenarios` perturbations of the instance, with
+/// seeds `seed`, `seed + 1`, ... (in parallel; the report is in seed order)
+pub fn evaluate_robustness(
+    instance: &PDTSPInstance,
+    tour: &[usize],
+    scenarios: usize,
+    seed: u64,
+    coordinate_noise: f64,
+    demand_noise: f64,
+) -> RobustnessReport {
+    let results = (0..scenarios as u64)
+        .into_par_iter()
+        .map(|k| {
+            let scenario = instance.perturb(seed.wrapping_add(k), coordinate_noise, demand_noise);
+            ScenarioResult {
+                seed: seed.wrapping_add(k),
+                cost: scenario.tour_cost(tour),
+                feasible: scenario.is_feasible(tour),
+                load_violation: scenario.load_violation(tour.iter().copied()),
+            }
+        })
+        .collect();
+    RobustnessReport {
+        nominal_cost: instance.tour_cost(tour),
+        nominal_feasible: instance.is_feasible(tour),
+        coordinate_noise,
+        demand_noise,
+        scenarios: results,
+    }
 }
 
 /// Helper function to load instances from a directory
@@ -633,4 +1632,278 @@ mod tests {
         let config = BenchmarkConfig::default();
         assert_eq!(config.num_runs, 5);
     }
+    
+    #[test]
+    fn test_parallel_run_matches_serial_order() {
+        use crate::instance::{DistanceMatrix, DistanceMode, Node};
+        
+        let instance = |name: &str, shift: f64| {
+            let nodes = vec![
+                Node::new(0, 0.0, 0.0, 0, 0),
+                Node::new(1, 4.0 + shift, 1.0, 3, 0),
+                Node::new(2, 6.0, 5.0 - shift, -2, 0),
+                Node::new(3, 1.0, 7.0, 2, 0),
+                Node::new(4, -3.0, 4.0 + shift, -3, 0),
+                Node::new(5, 2.0, 3.0, 1, 0),
+            ];
+            let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+            PDTSPInstance {
+                beta: 0.0,
+                ..PDTSPInstance::from_parts(name, 5, nodes, distance_matrix)
+            }
+        };
+        let instances = vec![instance("a", 0.0), instance("b", 1.0)];
+        let run = |parallel: bool| {
+            let mut benchmark = Benchmark::new(BenchmarkConfig {
+                num_runs: 2,
+                time_limit: 0.05,
+                parallel,
+                jobs: 3,
+                ..Default::default()
+            });
+            benchmark.run_on_instances(&instances);
+            // Concurrent runs share the process: their memory is not measured
+            assert_eq!(
+                benchmark.results().iter().any(|r| r.peak_rss.is_some()),
+                !parallel && cfg!(target_os = "linux"),
+            );
+            benchmark.results().iter()
+                .map(|r| (r.instance.clone(), r.algorithm.clone(), r.lower_bound.is_some()))
+                .collect::<Vec<_>>()
+        };
+        
+        let serial = run(false);
+        assert_eq!(serial.len(), 2 * (8 + 5 + 1 + 2 * 6));
+        assert_eq!(run(true), serial);
+    }
+    
+    #[test]
+    fn test_restart_until_budget() {
+        let mut seeds = Vec::new();
+        let best = restart_until_budget(0.05, 7, |seed, deadline| {
+            seeds.push(seed);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            assert!(deadline.remaining() <= 0.05);
+            let mut solution = Solution::new();
+            solution.feasible = true;
+            solution.cost = [120.0, 100.0, 110.0][(seeds.len() - 1) % 3];
+            solution.convergence = vec![(0.0, 130.0), (0.001, solution.cost)];
+            solution
+        });
+        
+        assert!(seeds.len() >= 3 && seeds.len() < 20);
+        assert_eq!(best.iterations, Some(seeds.len()));
+        assert_eq!((seeds[0], seeds[1]), (7, 7 + (1 << 32)));
+        assert_eq!(best.cost, 100.0);
+        assert!(best.computation_time >= 0.05);
+        let costs: Vec<f64> = best.convergence.iter().map(|&(_, c)| c).collect();
+        assert_eq!(costs, vec![130.0, 120.0, 100.0]);
+        assert!(best.convergence.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+    
+    #[test]
+    fn test_performance_ratios() {
+        let entry = |p: &str, s: &str, c: f64| (p.to_string(), s.to_string(), c);
+        let entries = vec![
+            entry("p1", "A", 100.0),
+            entry("p1", "A", 120.0),
+            entry("p1", "B", 100.0),
+            entry("p2", "A", 50.0),
+            entry("p2", "B", f64::INFINITY),
+            entry("p3", "A", f64::INFINITY),
+            entry("p3", "B", f64::INFINITY),
+        ];
+        
+        let ratios = performance_ratios(&entries);
+        assert_eq!(ratios.len(), 2);
+        assert_eq!(ratios[0].0, "A");
+        // p3 is unsolved and dropped; A averages 110 on p1
+        assert_eq!(ratios[0].1, vec![1.0, 1.1]);
+        assert_eq!(ratios[1].1, vec![1.0, f64::INFINITY]);
+        
+        assert_eq!(performance_profile_at(&ratios[0].1, 1.0), 0.5);
+        assert_eq!(performance_profile_at(&ratios[0].1, 1.2), 1.0);
+        assert_eq!(performance_profile_at(&ratios[1].1, 1e9), 0.5);
+    }
+    
+    #[test]
+    fn test_time_to_target() {
+        let result = |algorithm: &str, instance: &str, cost: f64, time: f64, convergence: Vec<(f64, f64)>| AlgorithmResult {
+            algorithm: algorithm.to_string(),
+            instance: instance.to_string(),
+            dimension: 10,
+            capacity: 10,
+            cost,
+            feasible: cost.is_finite(),
+            time,
+            cpu_time: None,
+            peak_rss: None,
+            iterations: None,
+            gap_to_best: None,
+            lower_bound: None,
+            gap_to_bound: None,
+            emissions: None,
+            convergence,
+        };
+        let mut benchmark = Benchmark::new(BenchmarkConfig::default());
+        benchmark.results = vec![
+            result("SA-run0", "p1", 100.0, 5.0, vec![(0.5, 130.0), (2.0, 104.0), (4.0, 100.0)]),
+            result("SA-run1", "p1", 110.0, 5.0, vec![(1.0, 110.0)]),
+            result("Greedy", "p1", 104.5, 0.1, Vec::new()),
+            result("Greedy", "p2", f64::INFINITY, 0.1, Vec::new()),
+        ];
+        
+        // Runs are grouped by solver; the target on p1 is 105
+        let times = benchmark.time_to_target(0.05);
+        assert_eq!(times.len(), 2);
+        assert_eq!(times[0], ("SA".to_string(), vec![2.0, f64::INFINITY]));
+        assert_eq!(times[1], ("Greedy".to_string(), vec![0.1, f64::INFINITY]));
+        
+        // SA averages 105 over its runs; p2 is unsolved and dropped
+        let ratios = benchmark.performance_ratios();
+        assert_eq!(ratios[0].0, "SA");
+        assert_eq!(ratios[0].1, vec![105.0 / 104.5]);
+        assert_eq!(ratios[1].1, vec![1.0]);
+        assert_eq!(solver_name("Greedy + 2-Opt"), "Greedy + 2-Opt");
+    }
+    
+    #[test]
+    fn test_robustness_over_perturbed_scenarios() {
+        let text = "NAME: r\nDIMENSION: 7\nCAPACITY: 6\nNODE_COORD_SECTION\n1 0 0\n2 4 1\n3 6 5\n4 1 7\n5 -3 4\n6 2 3\n7 5 -2\nDEMAND_SECTION\n1 0\n2 3\n3 -2\n4 2\n5 -3\n6 4\n7 -4\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let tour = vec![0, 1, 2, 3, 4, 5, 6];
+        assert!(instance.is_feasible(&tour));
+        
+        // Without noise every scenario is the base instance
+        let calm = evaluate_robustness(&instance, &tour, 5, 3, 0.0, 0.0);
+        assert_eq!(calm.feasible_fraction(), 1.0);
+        assert!(calm.scenarios.iter().all(|s| s.cost == calm.nominal_cost));
+        
+        // Demand noise keeps the geometry, and the tour breaks in some scenarios
+        let noisy = evaluate_robustness(&instance, &tour, 40, 3, 0.0, 0.5);
+        assert!(noisy.scenarios.iter().all(|s| (s.cost - noisy.nominal_cost).abs() < 1e-9));
+        assert!(noisy.feasible_fraction() < 1.0);
+        assert!(noisy.scenarios.iter().all(|s| s.feasible == (s.load_violation == 0)));
+        assert_eq!(noisy.scenarios[1].seed, 4);
+        let again = evaluate_robustness(&instance, &tour, 40, 3, 0.0, 0.5);
+        assert_eq!(again.scenarios, noisy.scenarios);
+        
+        let moved = evaluate_robustness(&instance, &tour, 10, 3, 0.1, 0.0);
+        assert!(moved.cost_std() > 0.0);
+        assert_eq!(moved.to_csv().lines().count(), 11);
+        let scenario = instance.perturb(3, 0.1, 0.5);
+        assert!(scenario.nodes.iter().zip(&instance.nodes).all(|(a, b)| (a.x - b.x).abs() <= 0.9 + 1e-9 && a.demand.signum() == b.demand.signum()));
+    }
+    
+    #[test]
+    fn test_timing_statistics() {
+        assert_eq!(median_and_iqr(vec![4.0, 1.0, 3.0, 2.0]), (2.5, 1.5));
+        assert_eq!(median_and_iqr(vec![7.0]), (7.0, 0.0));
+        
+        let start = thread_cpu_time();
+        let mut x = 0u64;
+        for i in 0..1_000_000u64 {
+            x = std::hint::black_box(x.wrapping_mul(31).wrapping_add(i));
+        }
+        if let (Some(start), Some(end)) = (start, thread_cpu_time()) {
+            assert!(end > start);
+        }
+        
+        let text = "NAME: t\nDIMENSION: 5\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 1 1\n4 0 1\n5 2 2\nDEMAND_SECTION\n1 0\n2 2\n3 -2\n4 1\n5 -1\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let mut benchmark = Benchmark::new(BenchmarkConfig { warmup_runs: 2, ..Default::default() });
+        // Warm-up runs are made once and never recorded
+        assert_eq!(benchmark.warmup_jobs(3).len(), 2 * 3 * 2);
+        assert!(benchmark.warmup_jobs(3).is_empty());
+        benchmark.run_construction_heuristics(&instance);
+        let stats = benchmark.compute_statistics();
+        assert!(!stats.is_empty());
+        assert!(stats.iter().all(|s| s.iqr_time == 0.0 && s.median_time == s.avg_time));
+        assert_eq!(stats[0].median_cpu_time.is_some(), cfg!(unix));
+        assert_eq!(stats[0].max_peak_rss.is_some(), cfg!(target_os = "linux"));
+    }
+    
+    #[test]
+    fn test_gap_to_lower_bound() {
+        let text = "NAME: g\nDIMENSION: 6\nCAPACITY: 5\nNODE_COORD_SECTION\n1 0 0\n2 4 1\n3 6 5\n4 1 7\n5 -3 4\n6 2 3\nDEMAND_SECTION\n1 0\n2 3\n3 -2\n4 2\n5 -3\n6 0\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let mut benchmark = Benchmark::new(BenchmarkConfig::default());
+        let bound = benchmark.compute_lower_bound(&instance);
+        assert!(bound > 0.0);
+        benchmark.run_construction_heuristics(&instance);
+        benchmark.finish_instance(&instance, 0);
+        
+        // Every heuristic row carries the bound and its optimality gap
+        for result in benchmark.results() {
+            assert_eq!(result.lower_bound, Some(bound));
+            if result.feasible {
+                let gap = result.gap_to_bound.unwrap();
+                assert!((gap - (result.cost - bound) / result.cost * 100.0).abs() < 1e-9);
+                assert!(gap >= -1e-9);
+            }
+        }
+        let stats = benchmark.compute_statistics();
+        assert!(stats.iter().filter(|s| s.num_feasible > 0).all(|s| s.avg_gap_to_bound.is_some()));
+        assert!(benchmark.generate_report().contains("LB Gap%"));
+    }
+    
+    #[test]
+    fn test_set_partitioning_result() {
+        let text = "NAME: sp\nDIMENSION: 6\nCAPACITY: 5\nDISTANCE: 20\nNODE_COORD_SECTION\n1 0 0\n2 4 1\n3 6 5\n4 1 7\n5 -3 4\n6 2 3\nDEMAND_SECTION\n1 0\n2 1\n3 1\n4 1\n5 1\n6 1\nEOF\n";
+        // Pickups only, so that every split of a tour at the route length
+        // limit stays within the capacity
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let mut benchmark = Benchmark::new(BenchmarkConfig { set_partitioning: true, ..Default::default() });
+        benchmark.run_construction_heuristics(&instance);
+        assert!(benchmark.results().iter().all(|r| r.algorithm != "SetPartitioning"));
+        assert!(!benchmark.route_pools[&instance.name].is_empty());
+        benchmark.run_set_partitioning(&instance);
+        
+        // The constructions skip customers or exceed the route length limit,
+        // but their routes combine into a complete feasible tour
+        let result = benchmark.results().last().unwrap();
+        assert_eq!(result.algorithm, "SetPartitioning");
+        assert!(result.feasible);
+        assert!(benchmark.route_pools.is_empty());
+    }
+    
+    #[test]
+    fn test_peak_rss_sampling() {
+        // Other tests allocate and free memory at the same time, so only a
+        // growth well above their noise is checked, sampled while the block
+        // is alive instead of waiting for the monitor thread
+        let sampler = RssSampler::start();
+        // Touch every page so that the block becomes resident
+        let block = std::hint::black_box(vec![1u8; 256 << 20]);
+        sampler.sample();
+        drop(block);
+        let growth = sampler.stop();
+        assert_eq!(growth.is_some(), cfg!(target_os = "linux"));
+        if let Some(growth) = growth {
+            assert!(growth >= 128.0, "growth {} MiB", growth);
+        }
+    }
+    
+    #[test]
+    fn test_algorithm_selection() {
+        let known: Vec<&str> = JobKind::ALL.iter().map(|k| k.key()).chain([EXACT_KEY]).collect();
+        let names = vec!["GA".to_string(), "ils".to_string(), " aco".to_string()];
+        let algorithms = parse_algorithm_selection(&names, &known).unwrap();
+        assert_eq!(algorithms, vec!["ga", "ils", "aco"]);
+        assert!(parse_algorithm_selection(&["gurobi".to_string()], &known).is_err());
+        assert_eq!(parse_algorithm_selection(&["VND".to_string()], &known).unwrap(), vec!["local-search"]);
+        
+        let benchmark = Benchmark::new(BenchmarkConfig { num_runs: 3, algorithms, ..Default::default() });
+        let kinds: Vec<JobKind> = benchmark.instance_jobs(0).iter().map(|job| job.kind).collect();
+        assert_eq!(kinds.len(), 3 * 3);
+        assert!(kinds.iter().all(|k| matches!(k, JobKind::Genetic | JobKind::IteratedLocalSearch | JobKind::AntColony)));
+        assert_eq!(kinds[0], JobKind::IteratedLocalSearch);
+        // Without construction or local search, nothing is warmed up
+        let mut benchmark = benchmark;
+        assert!(benchmark.warmup_jobs(2).is_empty());
+        
+        let all = Benchmark::new(BenchmarkConfig { num_runs: 2, ..Default::default() });
+        assert_eq!(all.instance_jobs(0).len(), 3 + 2 * 6);
+        assert!(all.config.selects(EXACT_KEY));
+    }
 }
@@ -0,0 +1,199 @@
+//! Persistent registry of best known solutions.
+//!
+//! `best_known.json` maps a problem key to the best feasible cost found on
+//! it so far, with the algorithm and tour that reached it. `Benchmark` loads
+//! it at start, updates it whenever a run improves an entry and saves it
+//! after each instance, so gaps to the best known cost carry across sessions.
+//!
+//! The best cost of an instance depends on its cost function, load weights,
+//! vehicle profile, distances (rounding, road matrix or arc graph), service
+//! times, route length limit and whether the tour is open, which are all
+//! part of the key (see `problem_key`).
+
+use crate::instance::{CostFunction, DistanceMode, PDTSPInstance, Rounding};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Best known solution of one problem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BestKnown {
+    /// Best feasible cost
+    pub cost: f64,
+    /// Algorithm that found it
+    pub algorithm: String,
+    /// Tour reaching the cost
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tour: Vec<usize>,
+    /// When it was found (RFC 3339)
+    pub found: String,
+}
+
+/// Best known solutions, keyed by problem
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BestKnownRegistry {
+    entries: BTreeMap<String, BestKnown>,
+}
+
+/// Registry key of an instance under its current cost function
+pub fn problem_key(instance: &PDTSPInstance) -> String {
+    let mut key = match instance.cost_function {
+        CostFunction::Distance => instance.name.clone(),
+        CostFunction::Quadratic => format!("{}/quadratic(alpha={},beta={})", instance.name, instance.alpha, instance.beta),
+        CostFunction::LinearLoad => format!("{}/linear-load(alpha={})", instance.name, instance.alpha),
+        CostFunction::Duration => format!("{}/duration", instance.name),
+    };
+    if let Some(vehicle) = &instance.vehicle {
+        key.push_str(&format!("/vehicle={}", vehicle.name));
+    }
+    if instance.distance_matrix.rounding() != Rounding::Exact {
+        key.push_str(&format!("/rounding={:?}", instance.distance_matrix.rounding()).to_lowercase());
+    }
+    // Road matrices, explicit weights and arc graphs do not follow from the coordinates
+    if has_own_distances(instance) {
+        let n = instance.dimension;
+        let distances = (0..n).flat_map(|i| (0..n).map(move |j| instance.distance(i, j)));
+        key.push_str(&format!("/matrix={:016x}", fingerprint(distances)));
+    }
+    if instance.has_service_times() {
+        key.push_str(&format!("/service={:016x}", fingerprint(instance.nodes.iter().map(|node| node.service_time))));
+    }
+    if let Some(limit) = instance.max_route_length {
+        key.push_str(&format!("/max-route-length={}", limit));
+    }
+    if instance.open_tour {
+        key.push_str("/open");
+    }
+    key
+}
+
+/// Whether some travel cost differs from the (rounded) Euclidean distance
+/// between the coordinates of its nodes
+fn has_own_distances(instance: &PDTSPInstance) -> bool {
+    if instance.distance_matrix.mode() == DistanceMode::OnDemand {
+        return false;
+    }
+    let rounding = instance.distance_matrix.rounding();
+    let nodes = &instance.nodes;
+    (0..instance.dimension).any(|i| {
+        (0..instance.dimension).any(|j| {
+            let euclidean = rounding.apply((nodes[i].x - nodes[j].x).hypot(nodes[i].y - nodes[j].y));
+            // Single-precision matrices round the distances
+            (instance.distance(i, j) - euclidean).abs() > 1e-6 * euclidean.max(1.0)
+        })
+    })
+}
+
+/// FNV-1a hash of a sequence of numbers
+fn fingerprint(values: impl Iterator<Item = f64>) -> u64 {
+    values.flat_map(|value| value.to_bits().to_le_bytes()).fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl BestKnownRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a registry; a missing file gives an empty registry
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        serde_json::from_str(&text).map_err(|e| format!("Invalid best known registry {:?}: {}", path, e))
+    }
+
+    /// Write the registry as pretty-printed JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to write {:?}: {}", path.as_ref(), e))
+    }
+
+    /// Best known solution of a problem
+    pub fn get(&self, key: &str) -> Option<&BestKnown> {
+        self.entries.get(key)
+    }
+
+    /// Best known cost of a problem
+    pub fn cost(&self, key: &str) -> Option<f64> {
+        self.entries.get(key).map(|entry| entry.cost)
+    }
+
+    /// Record a feasible solution; returns true if it improves the entry
+    pub fn update(&mut self, key: &str, cost: f64, algorithm: &str, tour: &[usize]) -> bool {
+        if !cost.is_finite() || self.cost(key).is_some_and(|best| best <= cost) {
+            return false;
+        }
+        self.entries.insert(key.to_string(), BestKnown {
+            cost,
+            algorithm: algorithm.to_string(),
+            tour: tour.to_vec(),
+            found: chrono::Local::now().to_rfc3339(),
+        });
+        true
+    }
+
+    /// Number of problems in the registry
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the entries in key order
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &BestKnown)> {
+        self.entries.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_keeps_best_and_persists() {
+        let mut registry = BestKnownRegistry::new();
+        assert!(registry.update("n20mosA", 4000.0, "GA", &[0, 2, 1]));
+        assert!(!registry.update("n20mosA", 4100.0, "SA", &[0, 1, 2]));
+        assert!(!registry.update("n20mosA", f64::INFINITY, "SA", &[]));
+        assert!(registry.update("n20mosA", 3900.0, "ILS", &[0, 1, 2]));
+        assert!(registry.update("n20mosA/linear-load(alpha=0.1)", 4500.0, "GA", &[0, 1, 2]));
+        assert_eq!(registry.get("n20mosA").unwrap().algorithm, "ILS");
+        assert_eq!(registry.len(), 2);
+
+        let path = std::env::temp_dir().join("pdtsp_best_known_test.json");
+        registry.save(&path).unwrap();
+        let loaded = BestKnownRegistry::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded, registry);
+        assert!(BestKnownRegistry::load(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_problem_key_separates_variants() {
+        let text = "NAME: k\nDIMENSION: 3\nCAPACITY: 5\nNODE_COORD_SECTION\n1 0 0\n2 3 0\n3 0 4\nDEMAND_SECTION\n1 0\n2 1\n3 -1\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        assert_eq!(problem_key(&instance), "k");
+
+        let mut variant = instance.clone();
+        variant.open_tour = true;
+        variant.max_route_length = Some(20.0);
+        variant.set_service_time(1.0);
+        assert_eq!(problem_key(&variant).split('/').count(), 4);
+
+        let mut road = instance.clone();
+        road.distance_matrix = crate::instance::DistanceMatrix::from_rows(vec![vec![0.0, 5.0, 6.0], vec![5.0, 0.0, 7.0], vec![6.0, 7.0, 0.0]]).unwrap();
+        let mut other_road = road.clone();
+        other_road.distance_matrix = crate::instance::DistanceMatrix::from_rows(vec![vec![0.0, 5.0, 6.0], vec![5.0, 0.0, 8.0], vec![6.0, 8.0, 0.0]]).unwrap();
+        assert!(problem_key(&road).starts_with("k/matrix="));
+        assert_ne!(problem_key(&road), problem_key(&other_road));
+    }
+}
@@ -0,0 +1,301 @@
+//! Benchmark campaigns.
+//!
+//! A `CampaignSpec` (TOML) describes a `benchmark` run: the instance files,
+//! and the algorithms in recording order, each with its own parameters,
+//! seeds or run count and time limit. `benchmark --campaign` runs it in
+//! place of the standard algorithm set, so an experiment can be reproduced
+//! from its file alone.
+//!
+//! ```toml
+//! instances = ["../benchmark_n20/*.tsp", "../Datasets/TS2004t2/n100*.tsp"]
+//! output = "results/campaign"
+//! config = "tuned.toml"
+//! seeds = [0, 1, 2]
+//! time_limit = 10.0
+//!
+//! [[algorithms]]
+//! key = "construction"
+//!
+//! [[algorithms]]
+//! key = "sa"
+//!
+//! [[algorithms]]
+//! key = "sa"
+//! label = "SA-slow"
+//! runs = 10
+//! time_limit = 30.0
+//! params.sa = { cooling_rate = 0.999 }
+//!
+//! [[algorithms]]
+//! key = "exact"
+//! time_limit = 300.0
+//! ```
+//!
+//! `config` is a solver configuration (see `SolverConfig`) shared by all
+//! algorithms: its cost function and instance settings apply to every
+//! instance, and its seeds and time limit are the defaults of the campaign.
+//! The `params` of an algorithm replace whole sections of it (`[sa]`,
+//! `[vnd]`, ...). An algorithm runs with its own `seeds` or `runs` (seeds
+//! 0..runs), otherwise the campaign's, otherwise the configuration's
+//! (0..5 by default); the same goes for `time_limit` (60 s by default). The
+//! exact solver only takes a time limit (300 s by default).
+//!
+//! Relative paths are resolved against the directory of the spec file.
+
+use crate::benchmark::{parse_algorithm_selection, AlgorithmRuns, BenchmarkConfig, JobKind, EXACT_KEY};
+use crate::config::SolverConfig;
+use crate::experiment::expand_instance_patterns;
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// One algorithm of a campaign
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CampaignAlgorithm {
+    /// Algorithm key (see `JobKind::key`, and `EXACT_KEY`)
+    pub key: String,
+    /// Name of its results in place of the standard one, needed to tell
+    /// apart several entries of the same algorithm
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Seeds of the runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seeds: Option<Vec<u64>>,
+    /// Number of runs, with seeds 0..runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runs: Option<usize>,
+    /// Time limit per run in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit: Option<f64>,
+    /// Algorithm sections replacing those of the shared configuration
+    #[serde(default)]
+    pub params: SolverConfig,
+}
+
+/// Declarative description of a benchmark campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CampaignSpec {
+    /// Instance files or glob patterns
+    pub instances: Vec<String>,
+    /// Output directory of the results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+    /// Solver configuration shared by all algorithms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// Seeds of the runs of every algorithm
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seeds: Option<Vec<u64>>,
+    /// Number of runs of every algorithm, with seeds 0..runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runs: Option<usize>,
+    /// Time limit per run in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit: Option<f64>,
+    /// Algorithms, in recording order
+    pub algorithms: Vec<CampaignAlgorithm>,
+    /// Directory of the spec file, against which relative paths are resolved
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+/// Seeds given either as a list or as a run count
+fn check_seeds(owner: &str, seeds: &Option<Vec<u64>>, runs: Option<usize>) -> Result<(), String> {
+    if seeds.is_some() && runs.is_some() {
+        return Err(format!("{} sets both seeds and runs", owner));
+    }
+    if seeds.as_ref().is_some_and(|seeds| seeds.is_empty()) || runs == Some(0) {
+        return Err(format!("{} has no run", owner));
+    }
+    Ok(())
+}
+
+fn check_time_limit(owner: &str, time_limit: Option<f64>) -> Result<(), String> {
+    match time_limit {
+        Some(t) if t.is_nan() || t <= 0.0 => Err(format!("Time limit of {} must be positive, got {}", owner, t)),
+        _ => Ok(()),
+    }
+}
+
+/// Seeds from a list or a run count, if either is set
+fn seeds_of(seeds: &Option<Vec<u64>>, runs: Option<usize>) -> Option<Vec<u64>> {
+    seeds.clone().or_else(|| runs.map(|runs| (0..runs as u64).collect()))
+}
+
+impl CampaignSpec {
+    /// Parse and validate a spec from TOML text
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let spec: CampaignSpec = toml::from_str(text)
+            .map_err(|e| format!("Invalid campaign spec: {}", e))?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Load a spec; relative paths in it are resolved against its directory
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {:?}: {}", path.as_ref(), e))?;
+        let mut spec = Self::from_toml(&text)?;
+        spec.base_dir = path.as_ref().parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(spec)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.instances.is_empty() || self.algorithms.is_empty() {
+            return Err("Campaign needs at least one instance and algorithm".to_string());
+        }
+        check_seeds("The campaign", &self.seeds, self.runs)?;
+        check_time_limit("the campaign", self.time_limit)?;
+
+        let keys = self.algorithm_keys()?;
+        let mut names = Vec::new();
+        for (key, algorithm) in keys.iter().zip(&self.algorithms) {
+            let owner = format!("Algorithm '{}'", algorithm.label.as_deref().unwrap_or(key));
+            check_seeds(&owner, &algorithm.seeds, algorithm.runs)?;
+            check_time_limit(&owner, algorithm.time_limit)?;
+            if algorithm.params.has_run_settings() {
+                return Err(format!("{} params may only set algorithm sections", owner));
+            }
+            algorithm.params.validate()?;
+            if key == EXACT_KEY && (algorithm.seeds.is_some() || algorithm.runs.is_some() || algorithm.label.is_some()) {
+                return Err("The exact solver only takes a time limit".to_string());
+            }
+            let name = algorithm.label.as_deref().unwrap_or(key);
+            if names.contains(&name) {
+                return Err(format!("{} is listed twice; give the entries distinct labels", owner));
+            }
+            names.push(name);
+        }
+        Ok(())
+    }
+
+    /// Keys of the algorithms, checked and in lower case
+    fn algorithm_keys(&self) -> Result<Vec<String>, String> {
+        let known: Vec<&str> = JobKind::ALL.iter().map(|kind| kind.key()).chain([EXACT_KEY]).collect();
+        let names: Vec<String> = self.algorithms.iter().map(|algorithm| algorithm.key.clone()).collect();
+        parse_algorithm_selection(&names, &known)
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        self.base_dir.join(path)
+    }
+
+    /// Output directory, resolved against the spec directory
+    pub fn output_dir(&self) -> Option<PathBuf> {
+        self.output.as_deref().map(|output| self.resolve(output))
+    }
+
+    /// Expand the instance patterns into a sorted list of files
+    pub fn instance_paths(&self) -> Result<Vec<PathBuf>, String> {
+        expand_instance_patterns(&self.base_dir, &self.instances)
+    }
+
+    /// Shared solver configuration, loaded from `config`
+    pub fn solver_config(&self) -> Result<SolverConfig, String> {
+        match &self.config {
+            Some(path) => SolverConfig::from_file(self.resolve(path)),
+            None => Ok(SolverConfig::default()),
+        }
+    }
+
+    /// Benchmark settings of the campaign: the shared configuration, the
+    /// runs of every algorithm and the exact solver, if listed
+    pub fn benchmark_config(&self) -> Result<BenchmarkConfig, String> {
+        let defaults = BenchmarkConfig::default();
+        let mut solver = self.solver_config()?;
+        if let Some(seeds) = seeds_of(&self.seeds, self.runs) {
+            solver.seeds = Some(seeds);
+        }
+        let seeds = solver.seeds.clone().unwrap_or_else(|| (0..defaults.num_runs as u64).collect());
+        let time_limit = self.time_limit.or(solver.time_limit).unwrap_or(defaults.time_limit);
+
+        let mut config = BenchmarkConfig {
+            num_runs: seeds.len(),
+            time_limit,
+            ..defaults
+        };
+        for (key, algorithm) in self.algorithm_keys()?.iter().zip(&self.algorithms) {
+            let Some(&kind) = JobKind::ALL.iter().find(|kind| kind.key() == key) else {
+                // The exact solver
+                config.run_exact = true;
+                config.exact_time_limit = algorithm.time_limit.unwrap_or(config.exact_time_limit);
+                continue;
+            };
+            config.campaign.push(AlgorithmRuns {
+                kind,
+                label: algorithm.label.clone(),
+                seeds: seeds_of(&algorithm.seeds, algorithm.runs).unwrap_or_else(|| seeds.clone()),
+                time_limit: algorithm.time_limit.unwrap_or(time_limit),
+                solver: solver.overridden_by(&algorithm.params),
+            });
+        }
+        config.solver = solver;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_campaign_spec() {
+        let text = r#"
+            instances = ["*.tsp"]
+            seeds = [3, 4]
+            time_limit = 2.0
+
+            [[algorithms]]
+            key = "construction"
+
+            [[algorithms]]
+            key = "sa"
+
+            [[algorithms]]
+            key = "SA"
+            label = "SA-slow"
+            runs = 3
+            time_limit = 5.0
+            params.sa = { cooling_rate = 0.999 }
+
+            [[algorithms]]
+            key = "exact"
+            time_limit = 30.0
+        "#;
+        let spec = CampaignSpec::from_toml(text).unwrap();
+        let config = spec.benchmark_config().unwrap();
+        assert!(config.run_exact);
+        assert_eq!(config.exact_time_limit, 30.0);
+        assert_eq!(config.campaign.len(), 3);
+
+        let slow = &config.campaign[2];
+        assert_eq!((slow.kind, slow.label.as_deref()), (JobKind::SimulatedAnnealing, Some("SA-slow")));
+        assert_eq!((slow.seeds.clone(), slow.time_limit), (vec![0, 1, 2], 5.0));
+        assert_eq!(slow.solver.sa.as_ref().and_then(|sa| sa.cooling_rate), Some(0.999));
+        let default = &config.campaign[1];
+        assert_eq!((default.seeds.clone(), default.time_limit), (vec![3, 4], 2.0));
+        assert!(default.solver.sa.is_none());
+
+        // Jobs follow the campaign: one construction job, then each seed
+        let benchmark = crate::benchmark::Benchmark::new(config);
+        let jobs = benchmark.instance_jobs(0);
+        assert_eq!(jobs.len(), 1 + 2 + 3);
+        assert_eq!(jobs.iter().map(|job| job.algorithm).collect::<Vec<_>>(), vec![0, 1, 1, 2, 2, 2]);
+
+        let invalid = [
+            "instances = []\n[[algorithms]]\nkey = \"sa\"",
+            "instances = [\"*.tsp\"]\nalgorithms = []",
+            "instances = [\"*.tsp\"]\n[[algorithms]]\nkey = \"gurobi\"",
+            "instances = [\"*.tsp\"]\nseeds = [0]\nruns = 2\n[[algorithms]]\nkey = \"sa\"",
+            "instances = [\"*.tsp\"]\n[[algorithms]]\nkey = \"sa\"\ntime_limit = 0.0",
+            "instances = [\"*.tsp\"]\n[[algorithms]]\nkey = \"sa\"\n[[algorithms]]\nkey = \"sa\"",
+            "instances = [\"*.tsp\"]\n[[algorithms]]\nkey = \"sa\"\nparams = { cost_function = \"quadratic\" }",
+            "instances = [\"*.tsp\"]\n[[algorithms]]\nkey = \"exact\"\nruns = 2",
+        ];
+        for text in invalid {
+            assert!(CampaignSpec::from_toml(text).is_err(), "{}", text);
+        }
+    }
+}
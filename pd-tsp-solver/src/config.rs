@@ -0,0 +1,590 @@
+//! Solver parameter files.
+//!
+//! A `SolverConfig` (TOML, or YAML for `.yaml`/`.yml` files) overrides the
+//! default parameters of the metaheuristics, as well as the cost function,
+//! seeds and time limit of a run. Every field is optional, so a file only
+//! lists the values it changes; options given on the command line take
+//! precedence. The `tune` command writes its best configuration in this
+//! format, and `solve --config` and `benchmark --config` load it.
+//!
+//! ```toml
+//! cost_function = "quadratic"
+//! alpha = 0.1
+//! beta = 0.01
+//! max_route_length = 5000.0
+//! seeds = [0, 1, 2]
+//!
+//! [vnd]
+//! operators = ["two-opt", "or-opt", "relocation", "swap"]
+//! ordering = "adaptive"
+//! cross_exchange = true
+//!
+//! [sa]
+//! cooling_rate = 0.99
+//! iterations_per_temp = 200
+//!
+//! [ga]
+//! crossover_type = "feasible-insertion"
+//!
+//! [aco]
+//! strategy = "max-min"
+//! evaporation_rate = 0.2
+//!
+//! [pipeline]
+//! construction = { type = "grasp", restarts = 20 }
+//! improvements = [{ type = "vnd" }, { type = "sa", cooling_rate = 0.95 }]
+//! ```
+
+use crate::experiment::parse_cost_function;
+use crate::heuristics::acceptance::Acceptance;
+use crate::heuristics::aco::{ACOConfig, PheromoneStrategy};
+use crate::heuristics::genetic::{CrossoverType, GAConfig, MutationType, SelectionType};
+use crate::heuristics::local_search::{CapacityPenalty, CrossExchangeSearch, Diversification, IteratedLocalSearch, LoadShiftSearch, SimulatedAnnealing, TabuSearch, TwoHOptSearch, VNDOperator, VNDOrdering, VND};
+use crate::heuristics::hybrid::TimeBudget;
+use crate::heuristics::pipeline::Pipeline;
+use crate::heuristics::vns::GeneralVNS;
+use crate::instance::PDTSPInstance;
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Copy every parameter that is set onto the target
+macro_rules! override_fields {
+    ($params:expr, $target:expr, [$($field:ident),* $(,)?]) => {
+        $(
+            if let Some(value) = $params.$field {
+                $target.$field = value;
+            }
+        )*
+    };
+}
+
+/// Variable neighborhood descent parameters: the operator sequence, their
+/// ordering and operators added after the sequence
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VNDParams {
+    /// Operator sequence, replacing the standard one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operators: Option<Vec<VNDOperator>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ordering: Option<VNDOrdering>,
+    /// Exchange pairs of segments (`CrossExchangeSearch`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cross_exchange: Option<bool>,
+    /// Best 2-opt or Or-opt move by load-dependent delta (`TwoHOptSearch`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub two_h_opt: Option<bool>,
+    /// Move heavy pickups later and deliveries earlier (`LoadShiftSearch`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_shift: Option<bool>,
+}
+
+impl VNDParams {
+    pub fn apply(&self, vnd: &mut VND) {
+        if let Some(operators) = &self.operators {
+            vnd.set_operators(operators);
+        }
+        if let Some(ordering) = self.ordering {
+            vnd.ordering = ordering;
+        }
+        if self.cross_exchange == Some(true) {
+            vnd.add_operator(CrossExchangeSearch::first_improvement());
+        }
+        if self.two_h_opt == Some(true) {
+            vnd.add_operator(TwoHOptSearch::new());
+        }
+        if self.load_shift == Some(true) {
+            vnd.add_operator(LoadShiftSearch::first_improvement());
+        }
+    }
+}
+
+/// Simulated annealing parameters; the fields mirror `SimulatedAnnealing`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SAParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_temp: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_temp: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooling_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iterations_per_temp: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_temperature: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_acceptance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_acceptance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub penalty: Option<CapacityPenalty>,
+}
+
+impl SAParams {
+    pub fn apply(&self, sa: &mut SimulatedAnnealing) {
+        override_fields!(self, sa, [
+            initial_temp, final_temp, cooling_rate, iterations_per_temp,
+            auto_temperature, target_acceptance, final_acceptance,
+        ]);
+        if let Some(penalty) = self.penalty {
+            sa.penalty = Some(penalty);
+        }
+    }
+}
+
+/// Tabu search parameters; the fields mirror `TabuSearch`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TabuParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenure: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_iterations: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_no_improve: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidate_neighbors: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diversification: Option<Diversification>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_weight: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart_strength: Option<usize>,
+}
+
+impl TabuParams {
+    pub fn apply(&self, ts: &mut TabuSearch) {
+        override_fields!(self, ts, [
+            tenure, max_iterations, max_no_improve, candidate_neighbors,
+            sample_size, diversification, frequency_weight, restart_strength,
+        ]);
+    }
+}
+
+/// Iterated local search parameters; the fields mirror `IteratedLocalSearch`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ILSParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub perturbation_strength: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_perturbation_strength: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub escalate_after: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_iterations: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_no_improve: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acceptance: Option<Acceptance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acceptance_temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart_after: Option<usize>,
+}
+
+impl ILSParams {
+    pub fn apply(&self, ils: &mut IteratedLocalSearch) {
+        override_fields!(self, ils, [
+            perturbation_strength, max_perturbation_strength, escalate_after,
+            max_iterations, max_no_improve, acceptance, acceptance_temperature,
+            history_length, restart_after,
+        ]);
+    }
+}
+
+/// Genetic and memetic algorithm parameters; the fields mirror `GAConfig`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GAParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub population_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_generations: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_no_improve: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crossover_prob: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mutation_prob: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elite_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tournament_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crossover_type: Option<CrossoverType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mutation_type: Option<MutationType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection_type: Option<SelectionType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_local_search: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_search_prob: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_mutation: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub biased_fitness: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n_closest: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub immigrants: Option<usize>,
+}
+
+impl GAParams {
+    pub fn apply(&self, config: &mut GAConfig) {
+        override_fields!(self, config, [
+            population_size, max_generations, max_no_improve, crossover_prob,
+            mutation_prob, elite_count, tournament_size, crossover_type,
+            mutation_type, selection_type, use_local_search, local_search_prob,
+            adaptive_mutation, biased_fitness, n_closest, immigrants,
+        ]);
+    }
+}
+
+/// Ant colony parameters, shared by every pheromone strategy; the fields
+/// mirror `ACOConfig`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ACOParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_ants: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_iterations: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_no_improve: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alpha: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub beta: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evaporation_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q0: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_decay: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_local_search: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<PheromoneStrategy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank_width: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elitist_weight: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stagnation_iterations: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_branching_factor: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidate_neighbors: Option<usize>,
+}
+
+impl ACOParams {
+    pub fn apply(&self, config: &mut ACOConfig) {
+        override_fields!(self, config, [
+            num_ants, max_iterations, max_no_improve, alpha, beta,
+            evaporation_rate, q0, local_decay, use_local_search, strategy,
+            rank_width, elitist_weight, stagnation_iterations, min_branching_factor,
+            candidate_neighbors,
+        ]);
+    }
+}
+
+/// Run settings and parameter overrides of the algorithms
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SolverConfig {
+    /// Cost function: "distance", "quadratic", "linear-load" or "duration"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_function: Option<String>,
+    /// Linear load weight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha: Option<f64>,
+    /// Quadratic load weight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beta: Option<f64>,
+    /// Maximum length of every route (between depot visits)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_route_length: Option<f64>,
+    /// Service time of every customer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_time: Option<f64>,
+    /// Largest demand served in one visit; larger demands are split over
+    /// several visits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split_demand: Option<i32>,
+    /// Seeds: `solve` uses the first one, `benchmark` runs each stochastic
+    /// algorithm once per seed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seeds: Option<Vec<u64>>,
+    /// Time limit per run in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit: Option<f64>,
+    /// Variable neighborhood descent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vnd: Option<VNDParams>,
+    /// Simulated annealing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sa: Option<SAParams>,
+    /// Tabu search
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tabu: Option<TabuParams>,
+    /// Iterated local search
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ils: Option<ILSParams>,
+    /// Genetic and memetic algorithms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ga: Option<GAParams>,
+    /// Ant colony variants (ACO, MMAS, rank-based and elitist AS)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aco: Option<ACOParams>,
+    /// Stages run by the hybrid algorithm in place of multi-start, VND and ILS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<Pipeline>,
+}
+
+impl SolverConfig {
+    /// Parse and validate a configuration from TOML text
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let config: SolverConfig = toml::from_str(text)
+            .map_err(|e| format!("Invalid solver config: {}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parse and validate a configuration from YAML text
+    pub fn from_yaml(text: &str) -> Result<Self, String> {
+        let config: SolverConfig = serde_yaml::from_str(text)
+            .map_err(|e| format!("Invalid solver config: {}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load a configuration file; `.yaml` and `.yml` files are read as YAML,
+    /// anything else as TOML
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {:?}: {}", path.as_ref(), e))?;
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("yaml") | Some("yml") => Self::from_yaml(&text),
+            _ => Self::from_toml(&text),
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if let Some(name) = &self.cost_function {
+            parse_cost_function(name)?;
+        }
+        if self.seeds.as_ref().is_some_and(|seeds| seeds.is_empty()) {
+            return Err("Solver config lists no seed".to_string());
+        }
+        if let Some(t) = self.service_time.filter(|t| t.is_nan() || *t < 0.0) {
+            return Err(format!("Service time must be non-negative, got {}", t));
+        }
+        if let Some(s) = self.split_demand.filter(|s| *s <= 0) {
+            return Err(format!("Split demand size must be positive, got {}", s));
+        }
+        if let Some(l) = self.max_route_length.filter(|l| l.is_nan() || *l <= 0.0) {
+            return Err(format!("Route length limit must be positive, got {}", l));
+        }
+        if let Some(t) = self.time_limit.filter(|t| t.is_nan() || *t <= 0.0) {
+            return Err(format!("Time limit must be positive, got {}", t));
+        }
+        if let Some(pipeline) = &self.pipeline {
+            pipeline.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Whether the configuration sets anything besides the algorithm
+    /// sections: cost function, instance changes, seeds or time limit
+    pub fn has_run_settings(&self) -> bool {
+        self.cost_function.is_some() || self.alpha.is_some() || self.beta.is_some()
+            || self.max_route_length.is_some() || self.service_time.is_some()
+            || self.split_demand.is_some() || self.seeds.is_some() || self.time_limit.is_some()
+    }
+
+    /// This configuration with every value and algorithm section set in
+    /// `overrides` replaced by the overriding one (sections are replaced
+    /// whole, not merged field by field)
+    pub fn overridden_by(&self, overrides: &SolverConfig) -> SolverConfig {
+        let mut merged = self.clone();
+        macro_rules! replace_set {
+            ($($field:ident),* $(,)?) => {
+                $(
+                    if overrides.$field.is_some() {
+                        merged.$field = overrides.$field.clone();
+                    }
+                )*
+            };
+        }
+        replace_set!(
+            cost_function, alpha, beta, max_route_length, service_time, split_demand,
+            seeds, time_limit, vnd, sa, tabu, ils, ga, aco, pipeline,
+        );
+        merged
+    }
+
+    /// First configured seed
+    pub fn seed(&self) -> Option<u64> {
+        self.seeds.as_ref().and_then(|seeds| seeds.first().copied())
+    }
+
+    /// Set the cost function, load weights, route length limit, service
+    /// times and split demands of an instance, where configured
+    pub fn apply_cost_function(&self, instance: &mut PDTSPInstance) -> Result<(), String> {
+        if let Some(name) = &self.cost_function {
+            instance.cost_function = parse_cost_function(name)?;
+        }
+        if let Some(alpha) = self.alpha {
+            instance.alpha = alpha;
+        }
+        if let Some(beta) = self.beta {
+            instance.beta = beta;
+        }
+        if let Some(limit) = self.max_route_length {
+            instance.max_route_length = Some(limit);
+        }
+        if let Some(time) = self.service_time {
+            instance.set_service_time(time);
+        }
+        if let Some(size) = self.split_demand {
+            instance.split_demands(Some(size))?;
+        }
+        Ok(())
+    }
+
+    /// Serialize the configuration to TOML
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|e| format!("Failed to serialize solver config: {}", e))
+    }
+
+    /// Write the configuration to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        std::fs::write(&path, self.to_toml()?)
+            .map_err(|e| format!("Failed to write {:?}: {}", path.as_ref(), e))
+    }
+
+    pub fn apply_vnd(&self, vnd: &mut VND) {
+        if let Some(params) = &self.vnd {
+            params.apply(vnd);
+        }
+    }
+
+    pub fn apply_sa(&self, sa: &mut SimulatedAnnealing) {
+        if let Some(params) = &self.sa {
+            params.apply(sa);
+        }
+    }
+
+    pub fn apply_tabu(&self, ts: &mut TabuSearch) {
+        if let Some(params) = &self.tabu {
+            params.apply(ts);
+        }
+    }
+
+    /// ILS parameters, and the `[vnd]` settings for its descents
+    pub fn apply_ils(&self, ils: &mut IteratedLocalSearch) {
+        if let Some(vnd) = &self.vnd {
+            ils.vnd = vnd.clone();
+        }
+        if let Some(params) = &self.ils {
+            params.apply(ils);
+        }
+    }
+
+    /// The `[vnd]` settings for the descents of GVNS
+    pub fn apply_vns(&self, vns: &mut GeneralVNS) {
+        if let Some(vnd) = &self.vnd {
+            vns.vnd = vnd.clone();
+        }
+    }
+
+    /// The configured pipeline, or the hybrid one with `budget`; the `[vnd]`
+    /// settings apply to its stages unless the pipeline sets its own
+    pub fn pipeline(&self, budget: TimeBudget) -> Pipeline {
+        let mut pipeline = self.pipeline.clone().unwrap_or_else(|| Pipeline::hybrid(budget));
+        if pipeline.vnd.is_none() {
+            pipeline.vnd = self.vnd.clone();
+        }
+        pipeline
+    }
+
+    pub fn apply_ga(&self, config: &mut GAConfig) {
+        if let Some(params) = &self.ga {
+            params.apply(config);
+        }
+    }
+
+    pub fn apply_aco(&self, config: &mut ACOConfig) {
+        if let Some(params) = &self.aco {
+            params.apply(config);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_round_trip() {
+        let config = SolverConfig::from_toml("[sa]\ncooling_rate = 0.9\n\n[aco]\nnum_ants = 7\n").unwrap();
+        let mut aco = ACOConfig::default();
+        config.apply_aco(&mut aco);
+        assert_eq!(aco.num_ants, 7);
+        assert_eq!(aco.alpha, ACOConfig::default().alpha);
+
+        let mut sa = SimulatedAnnealing::new();
+        config.apply_sa(&mut sa);
+        assert_eq!(sa.cooling_rate, 0.9);
+
+        assert_eq!(SolverConfig::from_toml(&config.to_toml().unwrap()).unwrap(), config);
+        assert!(SolverConfig::from_toml("[sa]\ncooling = 0.9\n").is_err());
+        assert!(SolverConfig::from_toml("cost_function = \"cubic\"\n").is_err());
+
+        // The same configuration in TOML and YAML, with enum-valued parameters
+        let toml_config = SolverConfig::from_toml(
+            "cost_function = \"linear-load\"\nseeds = [3, 4]\n\n[ga]\ncrossover_type = \"pmx\"\n\n[tabu]\ndiversification = \"elite-restart\"\n",
+        ).unwrap();
+        let yaml_config = SolverConfig::from_yaml(
+            "cost_function: linear-load\nseeds: [3, 4]\nga:\n  crossover_type: pmx\ntabu:\n  diversification: elite-restart\n",
+        ).unwrap();
+        assert_eq!(toml_config, yaml_config);
+        assert_eq!(toml_config.seed(), Some(3));
+
+        let mut ga = GAConfig::default();
+        toml_config.apply_ga(&mut ga);
+        assert_eq!(ga.crossover_type, CrossoverType::PMX);
+        let mut ts = TabuSearch::new();
+        toml_config.apply_tabu(&mut ts);
+        assert_eq!(ts.diversification, Diversification::EliteRestart);
+
+        let vnd_config = SolverConfig::from_toml("[vnd]\ncross_exchange = true\nload_shift = true\n").unwrap();
+        assert_eq!(vnd_config.vnd, Some(VNDParams { cross_exchange: Some(true), load_shift: Some(true), ..Default::default() }));
+        assert_eq!(SolverConfig::from_toml(&vnd_config.to_toml().unwrap()).unwrap(), vnd_config);
+        
+        let ordered = SolverConfig::from_toml("[vnd]\noperators = [\"or-opt\", \"two-h-opt\"]\nordering = \"adaptive\"\n").unwrap();
+        let mut vnd = VND::with_standard_operators();
+        ordered.apply_vnd(&mut vnd);
+        assert_eq!(vnd.operator_names(), vec!["Or-Opt", "2h-Opt"]);
+        assert_eq!(vnd.ordering, VNDOrdering::Adaptive);
+        assert!(SolverConfig::from_toml("[vnd]\noperators = [\"three-opt\"]\n").is_err());
+
+        // The descents of ILS, GVNS and the hybrid stages use the same settings
+        let vnd_params = ordered.vnd.clone().unwrap();
+        let mut ils = IteratedLocalSearch::new();
+        ordered.apply_ils(&mut ils);
+        assert_eq!(ils.vnd, vnd_params);
+        let mut vns = GeneralVNS::new();
+        ordered.apply_vns(&mut vns);
+        assert_eq!(vns.vnd, vnd_params);
+        assert_eq!(ordered.pipeline(TimeBudget::default()).vnd, Some(vnd_params));
+    }
+}
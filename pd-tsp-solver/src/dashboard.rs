@@ -0,0 +1,462 @@
+//! Self-contained HTML dashboard for comparison and benchmark results.
+//!
+//! The generated page embeds everything it needs (CSS, a few lines of
+//! JavaScript for table sorting, inline SVG charts) so it can be opened
+//! directly in a browser without any tooling.
+
+use crate::benchmark::AlgorithmResult;
+use crate::run_info::RunInfo;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A single algorithm run shown on the dashboard
+#[derive(Debug, Clone)]
+pub struct DashboardRun {
+    /// Algorithm name
+    pub algorithm: String,
+    /// Instance name
+    pub instance: String,
+    /// Run index
+    pub run: usize,
+    /// Solution cost
+    pub cost: f64,
+    /// Computation time in seconds
+    pub time: f64,
+    /// Whether the solution is feasible
+    pub feasible: bool,
+    /// Convergence curve as (time in seconds, best cost) points, if recorded
+    pub convergence: Vec<(f64, f64)>,
+}
+
+/// HTML dashboard builder
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    /// Page title
+    pub title: String,
+    /// All runs to display
+    pub runs: Vec<DashboardRun>,
+    /// Tour thumbnails as (label, relative link to image)
+    pub thumbnails: Vec<(String, String)>,
+    /// Metadata of the run that produced these results
+    pub run_info: Option<RunInfo>,
+}
+
+/// Five-number summary used for box plots
+struct BoxStats {
+    min: f64,
+    q1: f64,
+    median: f64,
+    q3: f64,
+    max: f64,
+}
+
+impl Dashboard {
+    pub fn new(title: &str) -> Self {
+        Dashboard {
+            title: title.to_string(),
+            runs: Vec::new(),
+            thumbnails: Vec::new(),
+            run_info: None,
+        }
+    }
+
+    /// Build a dashboard from benchmark results
+    pub fn from_results(title: &str, results: &[AlgorithmResult]) -> Self {
+        let mut dashboard = Dashboard::new(title);
+        let mut run_counter: BTreeMap<(String, String), usize> = BTreeMap::new();
+
+        for r in results {
+            let counter = run_counter.entry((r.algorithm.clone(), r.instance.clone())).or_default();
+            dashboard.add_run(DashboardRun {
+                algorithm: r.algorithm.clone(),
+                instance: r.instance.clone(),
+                run: *counter,
+                cost: r.cost,
+                time: r.time,
+                feasible: r.feasible,
+                convergence: r.convergence.clone(),
+            });
+            *counter += 1;
+        }
+
+        dashboard
+    }
+
+    /// Add a run
+    pub fn add_run(&mut self, run: DashboardRun) {
+        self.runs.push(run);
+    }
+
+    /// Add a link to a tour thumbnail image
+    pub fn add_thumbnail(&mut self, label: &str, link: &str) {
+        self.thumbnails.push((label.to_string(), link.to_string()));
+    }
+
+    /// Render the complete HTML page
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+
+        html.push_str(&format!(r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<title>{title}</title>
+<style>
+    body {{ font-family: Arial, sans-serif; margin: 2em; color: #2c3e50; background: #ecf0f1; }}
+    h1, h2 {{ color: #2c3e50; }}
+    table {{ border-collapse: collapse; background: white; margin-bottom: 2em; }}
+    th, td {{ border: 1px solid #bdc3c7; padding: 4px 10px; text-align: right; }}
+    th {{ background: #34495e; color: white; cursor: pointer; user-select: none; }}
+    td:first-child, th:first-child, td:nth-child(2), th:nth-child(2) {{ text-align: left; }}
+    .infeasible {{ color: #e74c3c; }}
+    .chart {{ background: white; margin-bottom: 2em; }}
+    .thumbs a {{ display: inline-block; margin: 0.5em; text-align: center; }}
+    .thumbs img {{ width: 200px; border: 1px solid #bdc3c7; display: block; }}
+    footer {{ font-size: 0.8em; color: #7f8c8d; }}
+</style>
+<script>
+function sortTable(th) {{
+    const table = th.closest('table');
+    const idx = Array.from(th.parentNode.children).indexOf(th);
+    const asc = th.dataset.asc !== 'true';
+    th.dataset.asc = asc;
+    const rows = Array.from(table.tBodies[0].rows);
+    rows.sort((a, b) => {{
+        const x = a.cells[idx].dataset.v ?? a.cells[idx].textContent;
+        const y = b.cells[idx].dataset.v ?? b.cells[idx].textContent;
+        const nx = parseFloat(x), ny = parseFloat(y);
+        const cmp = (!isNaN(nx) && !isNaN(ny)) ? nx - ny : x.localeCompare(y);
+        return asc ? cmp : -cmp;
+    }});
+    rows.forEach(r => table.tBodies[0].appendChild(r));
+}}
+</script>
+</head>
+<body>
+<h1>{title}</h1>
+"##, title = escape_html(&self.title)));
+
+        html.push_str(&self.summary_table());
+        // Costs of different instances are not comparable: one chart each
+        let instances = self.instances();
+        for instance in &instances {
+            let suffix = if instances.len() > 1 { format!(": {}", instance) } else { String::new() };
+            html.push_str(&self.box_plots(instance, &suffix));
+            html.push_str(&self.convergence_chart(instance, &suffix));
+        }
+        html.push_str(&self.thumbnail_section());
+        html.push_str(&self.runs_table());
+
+        if let Some(info) = &self.run_info {
+            html.push_str(&format!("<footer>{}</footer>\n", escape_html(&info.summary())));
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Save the dashboard to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_html())
+    }
+
+    /// Instances of the runs (in first-seen order)
+    fn instances(&self) -> Vec<String> {
+        let mut instances: Vec<String> = Vec::new();
+        for run in &self.runs {
+            if !instances.contains(&run.instance) {
+                instances.push(run.instance.clone());
+            }
+        }
+        instances
+    }
+
+    /// Feasible costs on an instance grouped by algorithm (in first-seen order)
+    fn costs_by_algorithm(&self, instance: &str) -> Vec<(String, Vec<f64>)> {
+        let mut grouped: Vec<(String, Vec<f64>)> = Vec::new();
+
+        for run in self.runs.iter().filter(|r| r.instance == instance) {
+            let idx = match grouped.iter().position(|(name, _)| *name == run.algorithm) {
+                Some(idx) => idx,
+                None => {
+                    grouped.push((run.algorithm.clone(), Vec::new()));
+                    grouped.len() - 1
+                }
+            };
+            if run.feasible {
+                grouped[idx].1.push(run.cost);
+            }
+        }
+
+        grouped
+    }
+
+    fn summary_table(&self) -> String {
+        let mut html = String::from("<h2>Summary</h2>\n<table>\n<thead><tr>");
+        for header in ["Instance", "Algorithm", "Runs", "Feasible", "Best", "Average", "Worst", "Avg Time (s)"] {
+            html.push_str(&format!("<th onclick=\"sortTable(this)\">{}</th>", header));
+        }
+        html.push_str("</tr></thead>\n<tbody>\n");
+
+        for instance in self.instances() {
+            for (name, costs) in self.costs_by_algorithm(&instance) {
+                let runs: Vec<&DashboardRun> = self.runs.iter()
+                    .filter(|r| r.instance == instance && r.algorithm == name)
+                    .collect();
+                let avg_time = runs.iter().map(|r| r.time).sum::<f64>() / runs.len().max(1) as f64;
+
+                if costs.is_empty() {
+                    html.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>0</td><td>-</td><td>-</td><td>-</td><td>{:.4}</td></tr>\n",
+                        escape_html(&instance), escape_html(&name), runs.len(), avg_time
+                    ));
+                    continue;
+                }
+
+                let best = costs.iter().cloned().fold(f64::INFINITY, f64::min);
+                let worst = costs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                let avg = costs.iter().sum::<f64>() / costs.len() as f64;
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.2}</td><td>{:.2}</td><td>{:.4}</td></tr>\n",
+                    escape_html(&instance), escape_html(&name), runs.len(), costs.len(), best, avg, worst, avg_time
+                ));
+            }
+        }
+
+        html.push_str("</tbody>\n</table>\n");
+        html
+    }
+
+    fn runs_table(&self) -> String {
+        let mut html = String::from("<h2>All Runs</h2>\n<table>\n<thead><tr>");
+        for header in ["Algorithm", "Instance", "Run", "Cost", "Time (s)", "Feasible"] {
+            html.push_str(&format!("<th onclick=\"sortTable(this)\">{}</th>", header));
+        }
+        html.push_str("</tr></thead>\n<tbody>\n");
+
+        for run in &self.runs {
+            let class = if run.feasible { "" } else { " class=\"infeasible\"" };
+            html.push_str(&format!(
+                "<tr{}><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.4}</td><td>{}</td></tr>\n",
+                class, escape_html(&run.algorithm), escape_html(&run.instance),
+                run.run, run.cost, run.time, run.feasible
+            ));
+        }
+
+        html.push_str("</tbody>\n</table>\n");
+        html
+    }
+
+    /// Horizontal box plots of the feasible costs on an instance, one row
+    /// per algorithm; `suffix` follows the heading
+    fn box_plots(&self, instance: &str, suffix: &str) -> String {
+        let groups: Vec<(String, BoxStats)> = self.costs_by_algorithm(instance)
+            .into_iter()
+            .filter_map(|(name, costs)| box_stats(&costs).map(|s| (name, s)))
+            .collect();
+
+        if groups.is_empty() {
+            return String::new();
+        }
+
+        let lo = groups.iter().map(|(_, s)| s.min).fold(f64::INFINITY, f64::min);
+        let hi = groups.iter().map(|(_, s)| s.max).fold(f64::NEG_INFINITY, f64::max);
+        let range = if hi > lo { hi - lo } else { 1.0 };
+
+        let label_width = 150.0;
+        let plot_width = 600.0;
+        let row_height = 30.0;
+        let width = label_width + plot_width + 40.0;
+        let height = groups.len() as f64 * row_height + 40.0;
+        let sx = |v: f64| label_width + (v - lo) / range * plot_width;
+
+        let mut svg = format!(
+            "<h2>Cost Distribution{}</h2>\n<svg class=\"chart\" width=\"{}\" height=\"{}\" xmlns=\"http://www.w3.org/2000/svg\">\n",
+            escape_html(suffix), width, height
+        );
+
+        for (i, (name, s)) in groups.iter().enumerate() {
+            let y = 20.0 + i as f64 * row_height;
+            let mid = y + row_height / 2.0;
+            svg.push_str(&format!(
+                "<text x=\"5\" y=\"{:.1}\" font-size=\"12\">{}</text>\n",
+                mid + 4.0, escape_html(name)
+            ));
+            svg.push_str(&format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#2c3e50\"/>\n",
+                sx(s.min), mid, sx(s.max), mid
+            ));
+            svg.push_str(&format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#3498db\" stroke=\"#2c3e50\"><title>min {:.2} / q1 {:.2} / median {:.2} / q3 {:.2} / max {:.2}</title></rect>\n",
+                sx(s.q1), y + 5.0, (sx(s.q3) - sx(s.q1)).max(1.0), row_height - 10.0,
+                s.min, s.q1, s.median, s.q3, s.max
+            ));
+            svg.push_str(&format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#e74c3c\" stroke-width=\"2\"/>\n",
+                sx(s.median), y + 5.0, sx(s.median), y + row_height - 5.0
+            ));
+        }
+
+        let axis_y = height - 10.0;
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"10\">{:.2}</text>\n<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"10\" text-anchor=\"end\">{:.2}</text>\n",
+            label_width, axis_y, lo, label_width + plot_width, axis_y, hi
+        ));
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Best-cost-over-time curves for the runs on an instance that recorded
+    /// a convergence trace; `suffix` follows the heading
+    fn convergence_chart(&self, instance: &str, suffix: &str) -> String {
+        let traced: Vec<&DashboardRun> = self.runs.iter()
+            .filter(|r| r.instance == instance && !r.convergence.is_empty())
+            .collect();
+        if traced.is_empty() {
+            return String::new();
+        }
+
+        let points = traced.iter().flat_map(|r| r.convergence.iter());
+        let (mut t_max, mut c_min, mut c_max) = (0.0f64, f64::INFINITY, f64::NEG_INFINITY);
+        for &(t, c) in points {
+            t_max = t_max.max(t);
+            c_min = c_min.min(c);
+            c_max = c_max.max(c);
+        }
+        let t_range = if t_max > 0.0 { t_max } else { 1.0 };
+        let c_range = if c_max > c_min { c_max - c_min } else { 1.0 };
+
+        let (width, height, margin) = (760.0, 360.0, 40.0);
+        let palette = ["#3498db", "#e74c3c", "#2ecc71", "#9b59b6", "#f39c12", "#1abc9c", "#34495e"];
+        let algorithms: Vec<String> = self.costs_by_algorithm(instance).into_iter().map(|(n, _)| n).collect();
+
+        let mut svg = format!(
+            "<h2>Convergence{}</h2>\n<svg class=\"chart\" width=\"{}\" height=\"{}\" xmlns=\"http://www.w3.org/2000/svg\">\n",
+            escape_html(suffix), width, height
+        );
+
+        for run in traced {
+            let color_idx = algorithms.iter().position(|a| *a == run.algorithm).unwrap_or(0);
+            let path: Vec<String> = run.convergence.iter().map(|&(t, c)| {
+                let x = margin + t / t_range * (width - 2.0 * margin);
+                let y = height - margin - (c - c_min) / c_range * (height - 2.0 * margin);
+                format!("{:.1},{:.1}", x, y)
+            }).collect();
+            svg.push_str(&format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\" opacity=\"0.7\"><title>{} run {}</title></polyline>\n",
+                path.join(" "), palette[color_idx % palette.len()], escape_html(&run.algorithm), run.run
+            ));
+        }
+
+        for (i, name) in algorithms.iter().enumerate() {
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\" fill=\"{}\">{}</text>\n",
+                width - 150.0, margin + i as f64 * 14.0, palette[i % palette.len()], escape_html(name)
+            ));
+        }
+        svg.push_str(&format!(
+            "<text x=\"{m}\" y=\"{:.1}\" font-size=\"10\">0s</text>\n<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"10\" text-anchor=\"end\">{:.2}s</text>\n<text x=\"5\" y=\"{m}\" font-size=\"10\">{:.2}</text>\n<text x=\"5\" y=\"{:.1}\" font-size=\"10\">{:.2}</text>\n",
+            height - 10.0, width - margin, height - 10.0, t_max, c_max, height - margin, c_min, m = margin
+        ));
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn thumbnail_section(&self) -> String {
+        if self.thumbnails.is_empty() {
+            return String::new();
+        }
+
+        let mut html = String::from("<h2>Tours</h2>\n<div class=\"thumbs\">\n");
+        for (label, link) in &self.thumbnails {
+            html.push_str(&format!(
+                "<a href=\"{link}\"><img src=\"{link}\" alt=\"{label}\">{label}</a>\n",
+                link = escape_html(link), label = escape_html(label)
+            ));
+        }
+        html.push_str("</div>\n");
+        html
+    }
+}
+
+/// Compute min/quartiles/max (linear interpolation between order statistics)
+fn box_stats(values: &[f64]) -> Option<BoxStats> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let quantile = |q: f64| {
+        let pos = q * (sorted.len() - 1) as f64;
+        let lo = pos.floor() as usize;
+        let hi = pos.ceil() as usize;
+        sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+    };
+
+    Some(BoxStats {
+        min: sorted[0],
+        q1: quantile(0.25),
+        median: quantile(0.5),
+        q3: quantile(0.75),
+        max: sorted[sorted.len() - 1],
+    })
+}
+
+/// Escape text for inclusion in HTML
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dashboard_html() {
+        let mut dashboard = Dashboard::new("Test <dashboard>");
+        for (i, cost) in [10.0, 12.0, 11.0, 15.0].iter().enumerate() {
+            dashboard.add_run(DashboardRun {
+                algorithm: "SA".to_string(),
+                instance: "inst".to_string(),
+                run: i,
+                cost: *cost,
+                time: 0.1,
+                feasible: true,
+                convergence: vec![(0.0, cost + 5.0), (0.1, *cost)],
+            });
+        }
+
+        let stats = box_stats(&[10.0, 12.0, 11.0, 15.0]).unwrap();
+        assert_eq!(stats.min, 10.0);
+        assert_eq!(stats.median, 11.5);
+        assert_eq!(stats.max, 15.0);
+
+        let html = dashboard.to_html();
+        assert!(html.contains("Test &lt;dashboard&gt;"));
+        assert!(html.contains("Cost Distribution"));
+        assert!(html.contains("Convergence"));
+        assert!(html.ends_with("</html>\n"));
+        assert!(!html.contains("Cost Distribution: inst"));
+
+        // Runs of another instance get their own summary row and charts
+        dashboard.add_run(DashboardRun {
+            algorithm: "SA".to_string(),
+            instance: "large".to_string(),
+            run: 0,
+            cost: 1000.0,
+            time: 0.2,
+            feasible: true,
+            convergence: Vec::new(),
+        });
+        let html = dashboard.to_html();
+        assert!(html.contains("<td>inst</td><td>SA</td><td>4</td><td>4</td><td>10.00</td>"));
+        assert!(html.contains("<td>large</td><td>SA</td><td>1</td><td>1</td><td>1000.00</td>"));
+        assert!(html.contains("Cost Distribution: inst") && html.contains("Cost Distribution: large"));
+        assert!(html.contains("Convergence: inst") && !html.contains("Convergence: large"));
+    }
+}
@@ -0,0 +1,559 @@
+//! Distance matrix storage for PD-TSP instances.
+//!
+//! Distances are stored row-major in a single contiguous allocation, either
+//! as `f64` or as `f32` (half the memory, useful for 5000+ node instances),
+//! or computed on demand from the node coordinates (EUC_2D) without any
+//! O(n²) storage (see `instance::InstanceBackend`, which pairs the latter
+//! with a `kdtree::KdTree` for nearest-neighbor queries).
+//!
+//! Any other source of distances (e.g. a road network) can be plugged in by
+//! implementing `DistanceProvider` and wrapping it with
+//! `DistanceMatrix::from_provider`; solvers only ever see a `DistanceMatrix`.
+//!
+//! Euclidean distances are exact floats by default; `Rounding` selects the
+//! TSPLIB integer conventions (`nint` for EUC_2D, ceiling for CEIL_2D) so
+//! that costs can be compared with published integer-rounded optima.
+
+use crate::instance::Node;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+pub mod road;
+pub mod graph;
+pub mod kdtree;
+#[cfg(feature = "osrm")]
+pub mod osrm;
+
+/// Source of travel distances between nodes
+pub trait DistanceProvider: Send + Sync + std::fmt::Debug {
+    /// Distance from `i` to `j`
+    fn distance(&self, i: usize, j: usize) -> f64;
+    /// Number of nodes covered
+    fn node_count(&self) -> usize;
+    /// Short description of the provider
+    fn name(&self) -> &str {
+        "external"
+    }
+    /// Whether every arc exists; missing arcs have an infinite distance
+    fn is_complete(&self) -> bool {
+        true
+    }
+}
+
+/// How distances are stored
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum DistanceMode {
+    /// Full matrix in double precision
+    #[default]
+    Dense,
+    /// Full matrix in single precision
+    DenseF32,
+    /// Euclidean distances computed from coordinates on every lookup
+    OnDemand,
+    /// Distances supplied by an external `DistanceProvider`
+    External,
+}
+
+/// Rounding applied to Euclidean distances
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rounding {
+    /// Exact floating-point distances
+    #[default]
+    Exact,
+    /// Nearest integer, as TSPLIB EUC_2D (`nint`)
+    Nint,
+    /// Next integer up, as TSPLIB CEIL_2D
+    Ceil,
+}
+
+impl Rounding {
+    #[inline]
+    pub fn apply(&self, distance: f64) -> f64 {
+        match self {
+            Rounding::Exact => distance,
+            Rounding::Nint => (distance + 0.5).floor(),
+            Rounding::Ceil => distance.ceil(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rounding::Exact => "exact",
+            Rounding::Nint => "nint",
+            Rounding::Ceil => "ceil",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Storage {
+    F64(Vec<f64>),
+    F32(Vec<f32>),
+    Coords(Vec<(f64, f64)>),
+    Provider(Arc<dyn DistanceProvider>),
+}
+
+/// Flat n×n distance matrix
+#[derive(Debug, Clone)]
+pub struct DistanceMatrix {
+    n: usize,
+    storage: Storage,
+    rounding: Rounding,
+}
+
+impl Default for DistanceMatrix {
+    fn default() -> Self {
+        DistanceMatrix { n: 0, storage: Storage::F64(Vec::new()), rounding: Rounding::Exact }
+    }
+}
+
+impl DistanceMatrix {
+    /// Zero-filled double precision matrix of size n×n
+    pub fn new(n: usize) -> Self {
+        DistanceMatrix { n, storage: Storage::F64(vec![0.0; n * n]), rounding: Rounding::Exact }
+    }
+
+    /// Euclidean distances between nodes, stored according to `mode`
+    pub fn from_nodes(nodes: &[Node], mode: DistanceMode) -> Self {
+        Self::from_nodes_rounded(nodes, mode, Rounding::Exact)
+    }
+
+    /// Euclidean distances between nodes with the given rounding
+    pub fn from_nodes_rounded(nodes: &[Node], mode: DistanceMode, rounding: Rounding) -> Self {
+        let n = nodes.len();
+        let coords: Vec<(f64, f64)> = nodes.iter().map(|node| (node.x, node.y)).collect();
+        let euclidean = |a, b| rounding.apply(euclidean(a, b));
+
+        let storage = match mode {
+            // External distances cannot be derived from coordinates
+            DistanceMode::Dense | DistanceMode::External => {
+                let mut data = vec![0.0; n * n];
+                for i in 0..n {
+                    for j in 0..n {
+                        data[i * n + j] = euclidean(coords[i], coords[j]);
+                    }
+                }
+                Storage::F64(data)
+            }
+            DistanceMode::DenseF32 => {
+                let mut data = vec![0.0f32; n * n];
+                for i in 0..n {
+                    for j in 0..n {
+                        data[i * n + j] = euclidean(coords[i], coords[j]) as f32;
+                    }
+                }
+                Storage::F32(data)
+            }
+            DistanceMode::OnDemand => Storage::Coords(coords),
+        };
+
+        DistanceMatrix { n, storage, rounding }
+    }
+
+    /// Build a double precision matrix from nested rows (must be square)
+    pub fn from_rows(rows: Vec<Vec<f64>>) -> Result<Self, String> {
+        let n = rows.len();
+        let mut data = Vec::with_capacity(n * n);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != n {
+                return Err(format!("Row {} has {} entries, expected {}", i, row.len(), n));
+            }
+            data.extend(row);
+        }
+        Ok(DistanceMatrix { n, storage: Storage::F64(data), rounding: Rounding::Exact })
+    }
+
+    /// Wrap an external distance provider
+    pub fn from_provider(provider: Arc<dyn DistanceProvider>) -> Self {
+        DistanceMatrix { n: provider.node_count(), storage: Storage::Provider(provider), rounding: Rounding::Exact }
+    }
+
+    /// Read a square matrix from a text file, one row per line, with entries
+    /// separated by commas, semicolons or whitespace. Empty lines and lines
+    /// starting with `#` are ignored.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Cannot open distance matrix: {}", e))?;
+
+        let mut rows = Vec::new();
+        for (line_no, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let row = line
+                .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                .filter(|field| !field.is_empty())
+                .map(|field| field.parse::<f64>()
+                    .map_err(|_| format!("Invalid distance '{}' on line {}", field, line_no + 1)))
+                .collect::<Result<Vec<f64>, String>>()?;
+            rows.push(row);
+        }
+
+        Self::from_rows(rows)
+    }
+
+    /// Write the matrix in the format read by `from_file`
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let mut content = String::new();
+        for i in 0..self.n {
+            let row: Vec<String> = (0..self.n).map(|j| self.get(i, j).to_string()).collect();
+            content.push_str(&row.join(" "));
+            content.push('\n');
+        }
+        std::fs::write(&path, content).map_err(|e| format!("Cannot write distance matrix: {}", e))
+    }
+
+    /// Distance from `i` to `j`
+    #[inline]
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        match &self.storage {
+            Storage::F64(data) => data[i * self.n + j],
+            Storage::F32(data) => data[i * self.n + j] as f64,
+            Storage::Coords(coords) => self.rounding.apply(euclidean(coords[i], coords[j])),
+            Storage::Provider(provider) => provider.distance(i, j),
+        }
+    }
+
+    /// Set the distance from `i` to `j`.
+    /// Panics for on-demand and external matrices, which have no storage.
+    #[inline]
+    pub fn set(&mut self, i: usize, j: usize, value: f64) {
+        match &mut self.storage {
+            Storage::F64(data) => data[i * self.n + j] = value,
+            Storage::F32(data) => data[i * self.n + j] = value as f32,
+            Storage::Coords(_) => panic!("Cannot set distances of an on-demand matrix"),
+            Storage::Provider(_) => panic!("Cannot set distances of an external provider"),
+        }
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Whether the matrix is empty
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Whether some arcs are missing (sparse neighbor graph)
+    #[inline]
+    pub fn is_restricted(&self) -> bool {
+        matches!(&self.storage, Storage::Provider(provider) if !provider.is_complete())
+    }
+
+    /// Storage mode of this matrix
+    pub fn mode(&self) -> DistanceMode {
+        match self.storage {
+            Storage::F64(_) => DistanceMode::Dense,
+            Storage::F32(_) => DistanceMode::DenseF32,
+            Storage::Coords(_) => DistanceMode::OnDemand,
+            Storage::Provider(_) => DistanceMode::External,
+        }
+    }
+
+    /// Rounding of the Euclidean distances (always `Exact` for matrices read
+    /// from rows, files or providers)
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Check the triangle inequality and the symmetry of the distances.
+    /// Every triple is checked up to `METRIC_SAMPLE` of them, beyond that a
+    /// fixed-seed random sample of that size (pairs likewise); missing arcs
+    /// of restricted providers are skipped.
+    pub fn analyze_metric(&self) -> MetricReport {
+        let n = self.n;
+        let mut report = MetricReport { rounding: self.rounding, ..Default::default() };
+        if n < 2 {
+            report.exhaustive = true;
+            return report;
+        }
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        let check_pair = |report: &mut MetricReport, i: usize, j: usize| {
+            let (a, b) = (self.get(i, j), self.get(j, i));
+            if a.is_finite() && b.is_finite() {
+                report.pairs_checked += 1;
+                if (a - b).abs() > 1e-9 * (1.0 + a.abs()) {
+                    report.asymmetric_pairs += 1;
+                }
+            }
+        };
+        if (n as u64) * (n as u64) <= METRIC_SAMPLE {
+            for i in 0..n {
+                for j in i + 1..n {
+                    check_pair(&mut report, i, j);
+                }
+            }
+        } else {
+            for _ in 0..METRIC_SAMPLE {
+                let (i, j) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                if i != j {
+                    check_pair(&mut report, i, j);
+                }
+            }
+        }
+
+        let check_triple = |report: &mut MetricReport, i: usize, j: usize, k: usize| {
+            let (direct, first, second) = (self.get(i, k), self.get(i, j), self.get(j, k));
+            if !(direct.is_finite() && first.is_finite() && second.is_finite()) {
+                return;
+            }
+            report.triples_checked += 1;
+            let excess = direct - first - second;
+            if excess > 1e-9 * (1.0 + direct.abs()) {
+                report.violations += 1;
+                if excess > report.max_violation {
+                    report.max_violation = excess;
+                    report.worst_triple = Some((i, j, k));
+                }
+            }
+        };
+        report.exhaustive = (n as u64).pow(3) <= METRIC_SAMPLE;
+        if report.exhaustive {
+            for i in 0..n {
+                for j in 0..n {
+                    for k in 0..n {
+                        if i != j && j != k && i != k {
+                            check_triple(&mut report, i, j, k);
+                        }
+                    }
+                }
+            }
+        } else if n >= 3 {
+            for _ in 0..METRIC_SAMPLE {
+                let (i, j, k) = (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(0..n));
+                if i != j && j != k && i != k {
+                    check_triple(&mut report, i, j, k);
+                }
+            }
+        }
+        report
+    }
+
+    /// Distances between visits of the nodes: visit `v` stands for node
+    /// `original[v]`, and two visits of the same node are at distance zero.
+    /// Stored distances are copied, coordinates repeated and providers
+    /// wrapped, so the rounding, the missing arcs and the source of the
+    /// distances are kept.
+    pub fn with_visits(&self, original: &[usize]) -> DistanceMatrix {
+        let n = original.len();
+        let distance = |k: usize| {
+            let (i, j) = (original[k / n], original[k % n]);
+            if i == j { 0.0 } else { self.get(i, j) }
+        };
+        let storage = match &self.storage {
+            Storage::F64(_) => Storage::F64((0..n * n).map(distance).collect()),
+            Storage::F32(_) => Storage::F32((0..n * n).map(|k| distance(k) as f32).collect()),
+            Storage::Coords(coords) => Storage::Coords(original.iter().map(|&i| coords[i]).collect()),
+            Storage::Provider(provider) => Storage::Provider(Arc::new(VisitDistances {
+                inner: Arc::clone(provider),
+                original: original.to_vec(),
+            })),
+        };
+        DistanceMatrix { n, storage, rounding: self.rounding }
+    }
+
+    /// Approximate memory used by the stored distances, in bytes
+    pub fn memory_bytes(&self) -> usize {
+        match &self.storage {
+            Storage::F64(data) => data.len() * std::mem::size_of::<f64>(),
+            Storage::F32(data) => data.len() * std::mem::size_of::<f32>(),
+            Storage::Coords(coords) => coords.len() * std::mem::size_of::<(f64, f64)>(),
+            Storage::Provider(_) => 0,
+        }
+    }
+}
+
+impl DistanceProvider for DistanceMatrix {
+    fn distance(&self, i: usize, j: usize) -> f64 {
+        self.get(i, j)
+    }
+
+    fn node_count(&self) -> usize {
+        self.n
+    }
+
+    fn name(&self) -> &str {
+        match &self.storage {
+            Storage::F64(_) => "dense",
+            Storage::F32(_) => "dense-f32",
+            Storage::Coords(_) => "euclidean",
+            Storage::Provider(provider) => provider.name(),
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        !self.is_restricted()
+    }
+}
+
+/// Provider over visits of the nodes of another provider (see
+/// `DistanceMatrix::with_visits`)
+#[derive(Debug)]
+struct VisitDistances {
+    inner: Arc<dyn DistanceProvider>,
+    original: Vec<usize>,
+}
+
+impl DistanceProvider for VisitDistances {
+    fn distance(&self, i: usize, j: usize) -> f64 {
+        let (i, j) = (self.original[i], self.original[j]);
+        if i == j { 0.0 } else { self.inner.distance(i, j) }
+    }
+
+    fn node_count(&self) -> usize {
+        self.original.len()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.inner.is_complete()
+    }
+}
+
+/// Number of triples (and pairs) checked exhaustively by `analyze_metric`
+/// before it switches to sampling
+pub const METRIC_SAMPLE: u64 = 8_000_000;
+
+/// Triangle inequality and symmetry of a distance matrix
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricReport {
+    /// Rounding of the analyzed distances
+    pub rounding: Rounding,
+    /// Whether every pair and triple was checked (otherwise a sample)
+    pub exhaustive: bool,
+    pub pairs_checked: u64,
+    /// Pairs with d(i, j) != d(j, i)
+    pub asymmetric_pairs: u64,
+    pub triples_checked: u64,
+    /// Triples with d(i, k) > d(i, j) + d(j, k)
+    pub violations: u64,
+    /// Largest excess d(i, k) - d(i, j) - d(j, k)
+    pub max_violation: f64,
+    /// Triple (i, j, k) of the largest excess
+    pub worst_triple: Option<(usize, usize, usize)>,
+}
+
+impl MetricReport {
+    /// Whether the checked distances are symmetric and satisfy the triangle inequality
+    pub fn is_metric(&self) -> bool {
+        self.violations == 0 && self.asymmetric_pairs == 0
+    }
+}
+
+impl std::fmt::Display for MetricReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scope = if self.exhaustive { "all" } else { "sampled" };
+        writeln!(f, "Rounding: {}", self.rounding.name())?;
+        writeln!(f, "Symmetry: {} asymmetric pairs out of {} ({})", self.asymmetric_pairs, self.pairs_checked, scope)?;
+        write!(f, "Triangle inequality: {} violations out of {} triples ({})", self.violations, self.triples_checked, scope)?;
+        if let Some((i, j, k)) = self.worst_triple {
+            write!(f, "\n  Worst: d({0}, {2}) exceeds d({0}, {1}) + d({1}, {2}) by {3:.4}", i, j, k, self.max_violation)?;
+        }
+        Ok(())
+    }
+}
+
+#[inline]
+fn euclidean(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let dx = a.0 - b.0;
+    let dy = a.1 - b.1;
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_modes_agree() {
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 3.0, 4.0, 0, 0),
+            Node::new(2, -1.0, 2.5, 0, 0),
+        ];
+
+        let dense = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        let single = DistanceMatrix::from_nodes(&nodes, DistanceMode::DenseF32);
+        let lazy = DistanceMatrix::from_nodes(&nodes, DistanceMode::OnDemand);
+
+        assert!((dense.get(0, 1) - 5.0).abs() < 1e-10);
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((dense.get(i, j) - single.get(i, j)).abs() < 1e-5);
+                assert_eq!(dense.get(i, j), lazy.get(i, j));
+            }
+        }
+        assert_eq!(single.memory_bytes() * 2, dense.memory_bytes());
+        assert!(DistanceMatrix::from_rows(vec![vec![0.0, 1.0], vec![1.0]]).is_err());
+    }
+
+    #[derive(Debug)]
+    struct Manhattan(Vec<(f64, f64)>);
+
+    impl DistanceProvider for Manhattan {
+        fn distance(&self, i: usize, j: usize) -> f64 {
+            (self.0[i].0 - self.0[j].0).abs() + (self.0[i].1 - self.0[j].1).abs()
+        }
+
+        fn node_count(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    #[test]
+    fn test_external_provider_and_file_round_trip() {
+        let provider = Arc::new(Manhattan(vec![(0.0, 0.0), (3.0, 4.0), (-1.0, 2.5)]));
+        let matrix = DistanceMatrix::from_provider(provider);
+        assert_eq!(matrix.len(), 3);
+        assert_eq!(matrix.mode(), DistanceMode::External);
+        assert_eq!(matrix.get(0, 1), 7.0);
+
+        let path = std::env::temp_dir().join("pdtsp_distance_matrix_test.txt");
+        matrix.save_to_file(&path).unwrap();
+        let loaded = DistanceMatrix::from_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_eq!(loaded.get(i, j), matrix.get(i, j));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rounding_and_metric_analysis() {
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 1.4, 0.0, 0, 0),
+            Node::new(2, 2.8, 0.0, 0, 0),
+        ];
+        let exact = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        assert!(exact.analyze_metric().is_metric());
+        assert!(exact.analyze_metric().exhaustive);
+
+        // nint(1.4) + nint(1.4) < nint(2.8): rounding breaks the triangle inequality
+        for mode in [DistanceMode::Dense, DistanceMode::OnDemand] {
+            let rounded = DistanceMatrix::from_nodes_rounded(&nodes, mode, Rounding::Nint);
+            assert_eq!(rounded.get(0, 2), 3.0);
+            assert_eq!(rounded.rounding(), Rounding::Nint);
+            let report = rounded.analyze_metric();
+            assert_eq!(report.violations, 2);
+            assert_eq!(report.max_violation, 1.0);
+            assert!(report.to_string().contains("Worst"));
+        }
+        assert_eq!(DistanceMatrix::from_nodes_rounded(&nodes, DistanceMode::DenseF32, Rounding::Ceil).get(0, 1), 2.0);
+
+        let asymmetric = DistanceMatrix::from_rows(vec![vec![0.0, 1.0], vec![2.0, 0.0]]).unwrap();
+        assert_eq!(asymmetric.analyze_metric().asymmetric_pairs, 1);
+    }
+}
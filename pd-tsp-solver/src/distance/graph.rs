@@ -0,0 +1,281 @@
+//! Sparse neighbor graphs for geographically constrained instances.
+//!
+//! When only some arcs may be travelled (corridors, one-way streets, ...) the
+//! instance distances come from a `NeighborGraph`, in one of two ways:
+//!
+//! - `RestrictedArcs`: missing arcs have an infinite distance and the
+//!   instance reports them as unavailable (`PDTSPInstance::has_arc`), so that
+//!   feasibility checks reject tours and moves that use them and constructions
+//!   only follow existing arcs;
+//! - `ShortestPathCompletion`: a missing arc is replaced by the shortest path
+//!   in the graph, computed for all pairs (Floyd–Warshall) or per source on
+//!   demand (Dijkstra), which turns the graph back into a complete instance.
+//!
+//! Arc files use the same layout as road matrices (`from,to,distance`, node
+//! ids as in the instance file), but only list the existing arcs.
+
+use super::road::{read_records, RoadMetric};
+use super::{DistanceMatrix, DistanceProvider};
+use crate::instance::Node;
+use ordered_float::OrderedFloat;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Directed graph of the permitted arcs
+#[derive(Debug, Clone)]
+pub struct NeighborGraph {
+    /// Outgoing arcs (target, length) of each node
+    adjacency: Vec<Vec<(usize, f64)>>,
+}
+
+impl NeighborGraph {
+    /// Graph on `n` nodes without arcs
+    pub fn new(n: usize) -> Self {
+        NeighborGraph { adjacency: vec![Vec::new(); n] }
+    }
+
+    /// Add (or shorten) the arc from `i` to `j`
+    pub fn add_arc(&mut self, i: usize, j: usize, length: f64) {
+        match self.adjacency[i].iter_mut().find(|(target, _)| *target == j) {
+            Some(arc) => arc.1 = arc.1.min(length),
+            None => self.adjacency[i].push((j, length)),
+        }
+    }
+
+    /// Load the arc list of a CSV or Parquet file for the given nodes
+    pub fn from_file<P: AsRef<Path>>(path: P, nodes: &[Node]) -> Result<Self, String> {
+        let records = read_records(path.as_ref(), RoadMetric::Distance)?;
+        let index_of = |id: usize| -> Result<usize, String> {
+            nodes.iter().position(|node| node.id + 1 == id)
+                .ok_or_else(|| format!("Unknown node id {} in arc list", id))
+        };
+
+        let mut graph = NeighborGraph::new(nodes.len());
+        for (from_id, to_id, length) in records {
+            if !length.is_finite() || length < 0.0 {
+                return Err(format!("Invalid length {} for arc {} -> {}", length, from_id, to_id));
+            }
+            let (i, j) = (index_of(from_id)?, index_of(to_id)?);
+            if i != j {
+                graph.add_arc(i, j, length);
+            }
+        }
+
+        if !graph.is_strongly_connected() {
+            return Err("Neighbor graph is not strongly connected: no tour can visit every node".to_string());
+        }
+        Ok(graph)
+    }
+
+    /// Number of nodes
+    pub fn node_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    /// Number of arcs
+    pub fn arc_count(&self) -> usize {
+        self.adjacency.iter().map(Vec::len).sum()
+    }
+
+    /// Length of the arc from `i` to `j`, if it exists
+    pub fn arc(&self, i: usize, j: usize) -> Option<f64> {
+        self.adjacency[i].iter().find(|(target, _)| *target == j).map(|&(_, length)| length)
+    }
+
+    /// Outgoing arcs of `i`
+    pub fn neighbors(&self, i: usize) -> &[(usize, f64)] {
+        &self.adjacency[i]
+    }
+
+    /// Whether every node can reach every other node
+    pub fn is_strongly_connected(&self) -> bool {
+        let n = self.node_count();
+        if n == 0 {
+            return true;
+        }
+        let reversed = {
+            let mut reversed = NeighborGraph::new(n);
+            for (i, arcs) in self.adjacency.iter().enumerate() {
+                for &(j, length) in arcs {
+                    reversed.adjacency[j].push((i, length));
+                }
+            }
+            reversed
+        };
+        self.reachable_from(0) == n && reversed.reachable_from(0) == n
+    }
+
+    fn reachable_from(&self, source: usize) -> usize {
+        let mut seen = vec![false; self.node_count()];
+        let mut stack = vec![source];
+        seen[source] = true;
+        let mut count = 1;
+        while let Some(i) = stack.pop() {
+            for &(j, _) in &self.adjacency[i] {
+                if !seen[j] {
+                    seen[j] = true;
+                    count += 1;
+                    stack.push(j);
+                }
+            }
+        }
+        count
+    }
+
+    /// Shortest distances from `source` to every node (Dijkstra)
+    pub fn shortest_distances(&self, source: usize) -> Vec<f64> {
+        let mut dist = vec![f64::INFINITY; self.node_count()];
+        let mut heap = BinaryHeap::new();
+        dist[source] = 0.0;
+        heap.push(Reverse((OrderedFloat(0.0), source)));
+
+        while let Some(Reverse((OrderedFloat(d), i))) = heap.pop() {
+            if d > dist[i] {
+                continue;
+            }
+            for &(j, length) in &self.adjacency[i] {
+                if d + length < dist[j] {
+                    dist[j] = d + length;
+                    heap.push(Reverse((OrderedFloat(dist[j]), j)));
+                }
+            }
+        }
+        dist
+    }
+}
+
+/// Graph distances where missing arcs are forbidden (infinite distance)
+#[derive(Debug)]
+pub struct RestrictedArcs {
+    matrix: DistanceMatrix,
+}
+
+impl RestrictedArcs {
+    pub fn new(graph: &NeighborGraph) -> Self {
+        let n = graph.node_count();
+        let mut matrix = DistanceMatrix::new(n);
+        for i in 0..n {
+            for j in (0..n).filter(|&j| j != i) {
+                matrix.set(i, j, graph.arc(i, j).unwrap_or(f64::INFINITY));
+            }
+        }
+        RestrictedArcs { matrix }
+    }
+}
+
+impl DistanceProvider for RestrictedArcs {
+    fn distance(&self, i: usize, j: usize) -> f64 {
+        self.matrix.get(i, j)
+    }
+
+    fn node_count(&self) -> usize {
+        self.matrix.len()
+    }
+
+    fn name(&self) -> &str {
+        "restricted-arcs"
+    }
+
+    fn is_complete(&self) -> bool {
+        false
+    }
+}
+
+/// How shortest paths are computed
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CompletionMethod {
+    /// All pairs at once, O(n³) time and O(n²) memory
+    FloydWarshall,
+    /// One Dijkstra per source node, the first time a distance from it is needed
+    Dijkstra,
+}
+
+/// Graph distances where missing arcs are replaced by shortest paths
+#[derive(Debug)]
+pub struct ShortestPathCompletion {
+    graph: NeighborGraph,
+    /// Distance rows, filled eagerly (Floyd–Warshall) or lazily (Dijkstra)
+    rows: Vec<OnceLock<Vec<f64>>>,
+}
+
+impl ShortestPathCompletion {
+    pub fn new(graph: NeighborGraph, method: CompletionMethod) -> Self {
+        let n = graph.node_count();
+        let rows: Vec<OnceLock<Vec<f64>>> = (0..n).map(|_| OnceLock::new()).collect();
+
+        if method == CompletionMethod::FloydWarshall {
+            let mut dist = vec![vec![f64::INFINITY; n]; n];
+            for (i, row) in dist.iter_mut().enumerate() {
+                row[i] = 0.0;
+                for &(j, length) in graph.neighbors(i) {
+                    row[j] = row[j].min(length);
+                }
+            }
+            for k in 0..n {
+                let via = dist[k].clone();
+                for row in dist.iter_mut() {
+                    let to_k = row[k];
+                    if to_k.is_infinite() {
+                        continue;
+                    }
+                    for (d, &from_k) in row.iter_mut().zip(&via) {
+                        if to_k + from_k < *d {
+                            *d = to_k + from_k;
+                        }
+                    }
+                }
+            }
+            for (cell, row) in rows.iter().zip(dist) {
+                let _ = cell.set(row);
+            }
+        }
+
+        ShortestPathCompletion { graph, rows }
+    }
+}
+
+impl DistanceProvider for ShortestPathCompletion {
+    fn distance(&self, i: usize, j: usize) -> f64 {
+        self.rows[i].get_or_init(|| self.graph.shortest_distances(i))[j]
+    }
+
+    fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    fn name(&self) -> &str {
+        "shortest-path"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restricted_and_completed_graph() {
+        // Directed ring 0 -> 1 -> 2 -> 3 -> 0 plus a shortcut 0 -> 2
+        let mut graph = NeighborGraph::new(4);
+        for (i, j, length) in [(0, 1, 1.0), (1, 2, 2.0), (2, 3, 3.0), (3, 0, 4.0), (0, 2, 2.5)] {
+            graph.add_arc(i, j, length);
+        }
+        assert!(graph.is_strongly_connected());
+
+        let restricted = RestrictedArcs::new(&graph);
+        assert_eq!(restricted.distance(0, 2), 2.5);
+        assert!(restricted.distance(2, 0).is_infinite());
+        assert!(!DistanceMatrix::from_provider(std::sync::Arc::new(restricted)).is_complete());
+
+        let floyd = ShortestPathCompletion::new(graph.clone(), CompletionMethod::FloydWarshall);
+        let dijkstra = ShortestPathCompletion::new(graph, CompletionMethod::Dijkstra);
+        assert_eq!(floyd.distance(2, 1), 8.0);
+        assert_eq!(floyd.distance(1, 0), 9.0);
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(floyd.distance(i, j), dijkstra.distance(i, j));
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Static 2-d tree over node coordinates.
+//!
+//! Built once in O(n log² n) and answering k-nearest-neighbor queries in
+//! about O(k log n), so candidate lists can be computed on instances far
+//! too large for a full distance matrix. The tree is stored implicitly: each
+//! subtree is a contiguous slice of `order` split at its middle element.
+
+use crate::instance::Node;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// k-d tree over 2-d points, indexed like the slice it was built from
+#[derive(Debug, Clone, Default)]
+pub struct KdTree {
+    points: Vec<(f64, f64)>,
+    order: Vec<usize>,
+}
+
+impl KdTree {
+    pub fn new(points: Vec<(f64, f64)>) -> Self {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        build(&points, &mut order, 0);
+        KdTree { points, order }
+    }
+
+    /// Tree over the coordinates of the nodes, indexed by node position
+    pub fn from_nodes(nodes: &[Node]) -> Self {
+        Self::new(nodes.iter().map(|node| (node.x, node.y)).collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The `k` points closest to `query`, closest first (ties by index),
+    /// leaving out `exclude`
+    pub fn nearest(&self, query: (f64, f64), k: usize, exclude: Option<usize>) -> Vec<usize> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        self.search(query, k, exclude, 0, self.order.len(), 0, &mut heap);
+        heap.into_sorted_vec().into_iter().map(|c| c.1).collect()
+    }
+
+    /// The `k` nearest other points of point `i`
+    pub fn nearest_to(&self, i: usize, k: usize) -> Vec<usize> {
+        self.nearest(self.points[i], k, Some(i))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &self,
+        query: (f64, f64),
+        k: usize,
+        exclude: Option<usize>,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        heap: &mut BinaryHeap<Candidate>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let index = self.order[mid];
+        let point = self.points[index];
+        if exclude != Some(index) {
+            let (dx, dy) = (query.0 - point.0, query.1 - point.1);
+            let candidate = Candidate(dx * dx + dy * dy, index);
+            if heap.len() < k {
+                heap.push(candidate);
+            } else if heap.peek().is_some_and(|worst| candidate < *worst) {
+                heap.pop();
+                heap.push(candidate);
+            }
+        }
+
+        let diff = if depth.is_multiple_of(2) { query.0 - point.0 } else { query.1 - point.1 };
+        let (near, far) = if diff < 0.0 { ((lo, mid), (mid + 1, hi)) } else { ((mid + 1, hi), (lo, mid)) };
+        self.search(query, k, exclude, near.0, near.1, depth + 1, heap);
+        if heap.len() < k || heap.peek().is_some_and(|worst| diff * diff <= worst.0) {
+            self.search(query, k, exclude, far.0, far.1, depth + 1, heap);
+        }
+    }
+}
+
+/// Arrange `order` so that the middle element splits the slice on the
+/// coordinate of this depth, then recurse on both halves
+fn build(points: &[(f64, f64)], order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+    let mid = order.len() / 2;
+    let key = |i: &usize| if depth.is_multiple_of(2) { points[*i].0 } else { points[*i].1 };
+    order.select_nth_unstable_by(mid, |a, b| key(a).total_cmp(&key(b)));
+    let (left, right) = order.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+/// Squared distance and point index, ordered by distance then index
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate(f64, usize);
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_nearest_matches_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        // Integer coordinates produce many ties
+        let points: Vec<(f64, f64)> = (0..500)
+            .map(|_| (rng.gen_range(0..40) as f64, rng.gen_range(0..40) as f64))
+            .collect();
+        let tree = KdTree::new(points.clone());
+        assert_eq!(tree.len(), 500);
+
+        for i in (0..500).step_by(7) {
+            for k in [1, 5, 30] {
+                let mut brute: Vec<Candidate> = (0..points.len())
+                    .filter(|&j| j != i)
+                    .map(|j| {
+                        let (dx, dy) = (points[i].0 - points[j].0, points[i].1 - points[j].1);
+                        Candidate(dx * dx + dy * dy, j)
+                    })
+                    .collect();
+                brute.sort();
+                let expected: Vec<usize> = brute.iter().take(k).map(|c| c.1).collect();
+                assert_eq!(tree.nearest_to(i, k), expected);
+            }
+        }
+        assert_eq!(tree.nearest((0.0, 0.0), 600, None).len(), 500);
+        assert!(KdTree::new(Vec::new()).nearest((0.0, 0.0), 3, None).is_empty());
+    }
+}
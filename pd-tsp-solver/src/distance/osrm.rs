@@ -0,0 +1,103 @@
+//! Road distances from an OSRM server (feature `osrm`).
+//!
+//! The full table is requested once through the OSRM `table` service and can
+//! be cached to a file, so that repeated runs on the same instance do not hit
+//! the server again. Node coordinates are interpreted as (longitude, latitude).
+
+use super::{DistanceMatrix, DistanceProvider};
+use crate::instance::Node;
+use std::path::PathBuf;
+
+/// Road-network distances fetched from an OSRM server
+#[derive(Debug)]
+pub struct OsrmProvider {
+    matrix: DistanceMatrix,
+}
+
+/// Settings of an OSRM request
+#[derive(Debug, Clone)]
+pub struct OsrmConfig {
+    /// Server URL, e.g. `http://localhost:5000`
+    pub base_url: String,
+    /// Routing profile (`driving`, `cycling`, `foot`)
+    pub profile: String,
+    /// File the table is read from / written to
+    pub cache: Option<PathBuf>,
+}
+
+impl Default for OsrmConfig {
+    fn default() -> Self {
+        OsrmConfig {
+            base_url: "http://localhost:5000".to_string(),
+            profile: "driving".to_string(),
+            cache: None,
+        }
+    }
+}
+
+impl OsrmProvider {
+    /// Load the distances from the cache if present, otherwise query the server
+    /// (and fill the cache)
+    pub fn fetch(config: &OsrmConfig, nodes: &[Node]) -> Result<Self, String> {
+        if let Some(cache) = config.cache.as_ref().filter(|path| path.exists()) {
+            let matrix = DistanceMatrix::from_file(cache)?;
+            if matrix.len() != nodes.len() {
+                return Err(format!("Cached table {:?} has {} nodes, expected {}", cache, matrix.len(), nodes.len()));
+            }
+            return Ok(OsrmProvider { matrix });
+        }
+
+        let coordinates: Vec<String> = nodes.iter().map(|node| format!("{},{}", node.x, node.y)).collect();
+        let url = format!(
+            "{}/table/v1/{}/{}?annotations=distance",
+            config.base_url.trim_end_matches('/'), config.profile, coordinates.join(";")
+        );
+        let body = ureq::get(&url).call()
+            .map_err(|e| format!("OSRM request failed: {}", e))?
+            .into_string()
+            .map_err(|e| format!("Cannot read OSRM response: {}", e))?;
+        let matrix = parse_table(&body, nodes.len())?;
+
+        if let Some(cache) = &config.cache {
+            matrix.save_to_file(cache)?;
+        }
+        Ok(OsrmProvider { matrix })
+    }
+}
+
+/// Extract the `distances` table of an OSRM response
+fn parse_table(body: &str, n: usize) -> Result<DistanceMatrix, String> {
+    let response: serde_json::Value = serde_json::from_str(body)
+        .map_err(|e| format!("Invalid OSRM response: {}", e))?;
+    if response["code"] != "Ok" {
+        return Err(format!("OSRM error: {}", response["message"].as_str().unwrap_or("unknown")));
+    }
+
+    let rows = response["distances"].as_array().ok_or("OSRM response has no distance table")?;
+    let rows = rows.iter().enumerate().map(|(i, row)| {
+        row.as_array().ok_or(format!("OSRM row {} is not an array", i))?
+            .iter().enumerate()
+            .map(|(j, d)| d.as_f64().ok_or(format!("No route from node {} to node {}", i, j)))
+            .collect::<Result<Vec<f64>, String>>()
+    }).collect::<Result<Vec<Vec<f64>>, String>>()?;
+
+    let matrix = DistanceMatrix::from_rows(rows)?;
+    if matrix.len() != n {
+        return Err(format!("OSRM table has {} nodes, expected {}", matrix.len(), n));
+    }
+    Ok(matrix)
+}
+
+impl DistanceProvider for OsrmProvider {
+    fn distance(&self, i: usize, j: usize) -> f64 {
+        self.matrix.get(i, j)
+    }
+
+    fn node_count(&self) -> usize {
+        self.matrix.len()
+    }
+
+    fn name(&self) -> &str {
+        "osrm"
+    }
+}
@@ -0,0 +1,222 @@
+//! Externally computed road-network matrices.
+//!
+//! A road matrix is read from a "long" table with one row per arc, keyed by
+//! the node ids of the instance file (1-based, as in TSPLIB):
+//!
+//! ```text
+//! from,to,distance,time
+//! 1,2,1520.4,131.0
+//! 2,1,1498.2,125.5
+//! ```
+//!
+//! CSV files are always supported; Parquet files need the `parquet` feature.
+//! The table is checked against the node list: every id must exist, every
+//! ordered pair of distinct nodes must appear exactly once and values must be
+//! finite and non-negative.
+
+use super::{DistanceMatrix, DistanceProvider};
+use crate::instance::Node;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Which column of the table is used as the travel cost
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum RoadMetric {
+    /// Travel distance (column `distance`)
+    #[default]
+    Distance,
+    /// Travel time (column `time` or `duration`)
+    Time,
+}
+
+impl RoadMetric {
+    fn matches(&self, column: &str) -> bool {
+        let column = column.trim().to_lowercase();
+        match self {
+            RoadMetric::Distance => column == "distance",
+            RoadMetric::Time => column == "time" || column == "duration",
+        }
+    }
+}
+
+/// Road-network distances between the nodes of an instance
+#[derive(Debug)]
+pub struct RoadMatrix {
+    matrix: DistanceMatrix,
+    metric: RoadMetric,
+}
+
+impl RoadMatrix {
+    /// Load a CSV or Parquet table (by extension) for the given nodes
+    pub fn from_file<P: AsRef<Path>>(path: P, nodes: &[Node], metric: RoadMetric) -> Result<Self, String> {
+        let records = read_records(path.as_ref(), metric)?;
+        Self::from_records(&records, nodes, metric)
+    }
+
+    /// Build the matrix from `(from_id, to_id, value)` records
+    pub fn from_records(records: &[(usize, usize, f64)], nodes: &[Node], metric: RoadMetric) -> Result<Self, String> {
+        let n = nodes.len();
+        let index_of = |id: usize| -> Result<usize, String> {
+            nodes.iter().position(|node| node.id + 1 == id)
+                .ok_or_else(|| format!("Unknown node id {} in road matrix", id))
+        };
+
+        let mut matrix = DistanceMatrix::new(n);
+        let mut seen = vec![false; n * n];
+        for &(from_id, to_id, value) in records {
+            let (i, j) = (index_of(from_id)?, index_of(to_id)?);
+            if i == j {
+                continue;
+            }
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("Invalid value {} for arc {} -> {}", value, from_id, to_id));
+            }
+            if std::mem::replace(&mut seen[i * n + j], true) {
+                return Err(format!("Arc {} -> {} appears more than once", from_id, to_id));
+            }
+            matrix.set(i, j, value);
+        }
+
+        let missing: Vec<(usize, usize)> = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| i != j && !seen[i * n + j])
+            .collect();
+        if let Some(&(i, j)) = missing.first() {
+            return Err(format!(
+                "Road matrix is missing {} of {} arcs (e.g. {} -> {})",
+                missing.len(), n * (n - 1), nodes[i].id + 1, nodes[j].id + 1
+            ));
+        }
+
+        Ok(RoadMatrix { matrix, metric })
+    }
+
+    /// Metric the matrix was built from
+    pub fn metric(&self) -> RoadMetric {
+        self.metric
+    }
+}
+
+impl DistanceProvider for RoadMatrix {
+    fn distance(&self, i: usize, j: usize) -> f64 {
+        self.matrix.get(i, j)
+    }
+
+    fn node_count(&self) -> usize {
+        self.matrix.len()
+    }
+
+    fn name(&self) -> &str {
+        match self.metric {
+            RoadMetric::Distance => "road-distance",
+            RoadMetric::Time => "road-time",
+        }
+    }
+}
+
+/// Find the `from`, `to` and metric columns in a header
+fn column_indices<'a>(headers: impl Iterator<Item = &'a str>, metric: RoadMetric) -> Result<(usize, usize, usize), String> {
+    let headers: Vec<String> = headers.map(|h| h.trim().to_lowercase()).collect();
+    let find = |name: &str| headers.iter().position(|h| h == name)
+        .ok_or_else(|| format!("Road matrix has no '{}' column", name));
+    let value = headers.iter().position(|h| metric.matches(h))
+        .ok_or_else(|| format!("Road matrix has no column for {:?}", metric))?;
+    Ok((find("from")?, find("to")?, value))
+}
+
+/// Read the `(from_id, to_id, value)` records of a CSV or Parquet table
+pub(crate) fn read_records(path: &Path, metric: RoadMetric) -> Result<Vec<(usize, usize, f64)>, String> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("parquet") => read_parquet(path, metric),
+        _ => read_csv(path, metric),
+    }
+}
+
+fn read_csv(path: &Path, metric: RoadMetric) -> Result<Vec<(usize, usize, f64)>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| format!("Cannot open road matrix: {}", e))?;
+    let headers = reader.headers().map_err(|e| format!("Invalid road matrix header: {}", e))?.clone();
+    let (from, to, value) = column_indices(headers.iter(), metric)?;
+
+    let mut records = Vec::new();
+    for (line, row) in reader.records().enumerate() {
+        let row = row.map_err(|e| format!("Invalid road matrix row {}: {}", line + 2, e))?;
+        let field = |k: usize| row.get(k).unwrap_or("");
+        let parse_id = |k: usize| field(k).parse::<usize>()
+            .map_err(|_| format!("Invalid node id '{}' on row {}", field(k), line + 2));
+        let parsed = field(value).parse::<f64>()
+            .map_err(|_| format!("Invalid value '{}' on row {}", field(value), line + 2))?;
+        records.push((parse_id(from)?, parse_id(to)?, parsed));
+    }
+    Ok(records)
+}
+
+#[cfg(feature = "parquet")]
+fn read_parquet(path: &Path, metric: RoadMetric) -> Result<Vec<(usize, usize, f64)>, String> {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::Field;
+
+    let as_f64 = |field: &Field| -> Option<f64> {
+        match *field {
+            Field::Byte(v) => Some(v as f64),
+            Field::Short(v) => Some(v as f64),
+            Field::Int(v) => Some(v as f64),
+            Field::Long(v) => Some(v as f64),
+            Field::UByte(v) => Some(v as f64),
+            Field::UShort(v) => Some(v as f64),
+            Field::UInt(v) => Some(v as f64),
+            Field::ULong(v) => Some(v as f64),
+            Field::Float(v) => Some(v as f64),
+            Field::Double(v) => Some(v),
+            _ => None,
+        }
+    };
+
+    let file = std::fs::File::open(path).map_err(|e| format!("Cannot open road matrix: {}", e))?;
+    let reader = SerializedFileReader::new(file).map_err(|e| format!("Invalid Parquet file: {}", e))?;
+    let schema = reader.metadata().file_metadata().schema_descr_ptr();
+    let (from, to, value) = column_indices(schema.columns().iter().map(|c| c.name()), metric)?;
+
+    let mut records = Vec::new();
+    let rows = reader.get_row_iter(None).map_err(|e| format!("Cannot read Parquet rows: {}", e))?;
+    for (k, row) in rows.enumerate() {
+        let row = row.map_err(|e| format!("Invalid Parquet row {}: {}", k, e))?;
+        let fields: Vec<Option<f64>> = row.get_column_iter().map(|(_, field)| as_f64(field)).collect();
+        match (fields[from], fields[to], fields[value]) {
+            (Some(i), Some(j), Some(v)) if i >= 0.0 && j >= 0.0 => records.push((i as usize, j as usize, v)),
+            _ => return Err(format!("Invalid or missing values in Parquet row {}", k)),
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(not(feature = "parquet"))]
+fn read_parquet(_path: &Path, _metric: RoadMetric) -> Result<Vec<(usize, usize, f64)>, String> {
+    Err("Parquet road matrices require the `parquet` feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_road_matrix_csv_and_checks() {
+        let nodes: Vec<Node> = (0..3).map(|i| Node::new(i, i as f64, 0.0, 0, 0)).collect();
+        let path = std::env::temp_dir().join("pdtsp_road_matrix_test.csv");
+        std::fs::write(&path, "from,to,distance,time\n1,2,10,1\n2,1,12,2\n1,3,5,3\n3,1,6,4\n2,3,7,5\n3,2,8,6\n").unwrap();
+
+        let road = RoadMatrix::from_file(&path, &nodes, RoadMetric::Distance).unwrap();
+        assert_eq!(road.distance(1, 0), 12.0);
+        assert_eq!(road.distance(0, 0), 0.0);
+        let time = RoadMatrix::from_file(&path, &nodes, RoadMetric::Time).unwrap();
+        assert_eq!(time.distance(2, 1), 6.0);
+        std::fs::remove_file(&path).ok();
+
+        // Missing arc, unknown node, duplicate arc
+        assert!(RoadMatrix::from_records(&[(1, 2, 1.0)], &nodes, RoadMetric::Distance).is_err());
+        assert!(RoadMatrix::from_records(&[(1, 4, 1.0)], &nodes, RoadMetric::Distance).is_err());
+        assert!(RoadMatrix::from_records(&[(1, 2, 1.0), (1, 2, 2.0)], &nodes, RoadMetric::Distance).is_err());
+    }
+}
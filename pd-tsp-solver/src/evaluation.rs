@@ -0,0 +1,188 @@
+//! Batched tour evaluation.
+//!
+//! `evaluate_many` validates and scores many candidate tours in parallel, for
+//! callers that run their own search (e.g. RL agents) and need a fast oracle.
+//! Each tour is scored in a single pass over its prefix loads, which yields
+//! the length, the load-dependent surcharge, the load-distance product and
+//! capacity feasibility at once.
+
+use crate::instance::{CostFunction, PDTSPInstance};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Score of one candidate tour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Evaluation {
+    /// Whether the tour is a valid permutation starting at the depot
+    pub valid: bool,
+    /// Reason the tour is invalid
+    pub error: Option<String>,
+    /// Whether the capacity constraints hold
+    pub feasible: bool,
+    /// Travel cost (equals `tour_cost`)
+    pub cost: f64,
+    /// Euclidean tour length
+    pub length: f64,
+    /// Total profit collected
+    pub profit: i32,
+    /// Objective value Z = profit - cost
+    pub objective: f64,
+    /// Peak load along the tour
+    pub max_load: i32,
+    /// Position of the first capacity violation, if any
+    pub first_violation: Option<usize>,
+}
+
+impl Evaluation {
+    fn invalid(error: String) -> Self {
+        Evaluation {
+            valid: false,
+            error: Some(error),
+            feasible: false,
+            cost: f64::INFINITY,
+            length: f64::INFINITY,
+            profit: 0,
+            objective: f64::NEG_INFINITY,
+            max_load: 0,
+            first_violation: None,
+        }
+    }
+}
+
+/// Check that a tour starts at the depot and visits every node exactly once
+fn validate(instance: &PDTSPInstance, tour: &[usize]) -> Result<(), String> {
+    if tour.len() != instance.dimension {
+        return Err(format!("Tour has {} nodes, expected {}", tour.len(), instance.dimension));
+    }
+    if tour[0] != 0 {
+        return Err(format!("Tour starts at node {} instead of the depot", tour[0]));
+    }
+    let mut seen = vec![false; instance.dimension];
+    for &node in tour {
+        if node >= instance.dimension {
+            return Err(format!("Node {} out of range 0..{}", node, instance.dimension));
+        }
+        if seen[node] {
+            return Err(format!("Node {} visited more than once", node));
+        }
+        seen[node] = true;
+    }
+    Ok(())
+}
+
+/// Validate and score a single tour
+pub fn evaluate(instance: &PDTSPInstance, tour: &[usize]) -> Evaluation {
+    if let Err(e) = validate(instance, tour) {
+        return Evaluation::invalid(e);
+    }
+
+    let n = tour.len();
+    let mut load = instance.starting_load();
+    let mut max_load = load;
+    let mut first_violation = None;
+    let mut length = 0.0;
+    let mut surcharge = 0.0;
+    let mut load_distance = 0.0;
+    // An open tour ends at its last node
+    let arcs = if instance.open_tour { n - 1 } else { n };
+
+    for i in 0..arcs {
+        let from = tour[i];
+        let to = tour[(i + 1) % n];
+        let dist = instance.distance(from, to);
+        let l = load as f64;
+
+        length += dist;
+        load_distance += l.abs() * dist;
+        surcharge += match instance.cost_function {
+            CostFunction::Distance => 0.0,
+            CostFunction::Quadratic => instance.alpha * l + instance.beta * l * l,
+            CostFunction::LinearLoad => instance.alpha * l.abs(),
+            CostFunction::Duration => instance.nodes[to].service_time,
+        };
+
+        // The return arc ends the tour, so its load is not checked
+        if i + 1 < n {
+            load += instance.nodes[to].demand;
+            max_load = max_load.max(load);
+            if first_violation.is_none() && (load < 0 || load > instance.capacity) {
+                first_violation = Some(i + 1);
+            }
+        }
+    }
+
+    let base = length + surcharge;
+    let cost = match &instance.vehicle {
+        None => base,
+        Some(vehicle) => vehicle.tour_cost(base, load_distance),
+    };
+    let feasible = first_violation.is_none()
+        && (!instance.is_multi_commodity() || instance.commodity_loads_feasible(tour))
+        && instance.arcs_exist(tour, !instance.open_tour)
+        && instance.route_lengths_feasible(tour)
+        && instance.chance_feasible(tour);
+    let profit = instance.tour_profit(tour);
+
+    Evaluation {
+        valid: true,
+        error: None,
+        feasible,
+        cost,
+        length,
+        profit,
+        objective: profit as f64 - cost,
+        max_load,
+        first_violation,
+    }
+}
+
+/// Validate and score many tours in parallel; results are in input order
+pub fn evaluate_many(instance: &PDTSPInstance, tours: &[Vec<usize>]) -> Vec<Evaluation> {
+    tours.par_iter().map(|tour| evaluate(instance, tour)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::{DistanceMatrix, DistanceMode, Node};
+    use rand::prelude::*;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_evaluate_many_matches_instance() {
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 1.0, 0.0, 4, 7),
+            Node::new(2, 2.0, 1.0, -3, 3),
+            Node::new(3, 0.0, 2.0, 2, 5),
+            Node::new(4, 1.0, 3.0, -3, 1),
+            Node::new(5, 3.0, 3.0, 1, 2),
+        ];
+        let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        let mut instance = PDTSPInstance::from_parts("eval", 5, nodes, distance_matrix);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let mut tours: Vec<Vec<usize>> = (0..50).map(|_| {
+            let mut tour: Vec<usize> = (1..6).collect();
+            tour.shuffle(&mut rng);
+            tour.insert(0, 0);
+            tour
+        }).collect();
+        tours.push(vec![0, 1, 1, 2, 3, 4]);
+        tours.push(vec![1, 0, 2, 3, 4, 5]);
+
+        for cost_function in [CostFunction::Distance, CostFunction::Quadratic, CostFunction::LinearLoad] {
+            instance.cost_function = cost_function;
+            let results = evaluate_many(&instance, &tours);
+            assert_eq!(results.len(), tours.len());
+
+            for (tour, eval) in tours.iter().zip(&results).take(50) {
+                assert!(eval.valid);
+                assert!((eval.cost - instance.tour_cost(tour)).abs() < 1e-9);
+                assert_eq!(eval.feasible, instance.is_feasible(tour));
+                assert_eq!(eval.profit, instance.tour_profit(tour));
+            }
+            assert!(!results[50].valid && !results[51].valid);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Lower bounds that need no LP solver.
+//!
+//! `lagrangian_1tree_bound` is the Held–Karp bound: a minimum 1-tree (a
+//! spanning tree over the customers plus the two cheapest depot edges) is a
+//! relaxation of the tour, and Lagrange multipliers on the degree-2
+//! constraints, tuned by subgradient optimization, tighten it. Arc costs are
+//! symmetrized with min(d(i, j), d(j, i)), so the bound stays valid on
+//! asymmetric or restricted matrices.
+//!
+//! Capacity is relaxed; the load-dependent surcharge is bounded separately
+//! from the least load each arc can carry: the depot arc carries the starting
+//! load, and the arc leaving a pickup node carries at least its demand.
+
+use crate::instance::{CostFunction, PDTSPInstance};
+
+/// Subgradient iterations of the Held–Karp bound
+const MAX_ITERATIONS: usize = 1000;
+
+/// Iterations without improvement before the step size is halved
+const HALVING_PERIOD: usize = 30;
+
+/// Lower bound on the cost of any feasible tour (Held–Karp 1-tree bound on
+/// the length, plus a bound on the load-dependent surcharge). Vehicle
+/// profiles are assumed to have non-negative cost coefficients.
+pub fn lagrangian_1tree_bound(instance: &PDTSPInstance) -> f64 {
+    let n = instance.dimension;
+    let mut cost: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| instance.distance(i, j).min(instance.distance(j, i))).collect())
+        .collect();
+    // An open tour is a closed tour whose return arc is free: relaxing every
+    // depot edge to zero keeps the bound valid
+    if instance.open_tour {
+        cost.iter_mut().for_each(|row| row[0] = 0.0);
+        cost[0].iter_mut().for_each(|c| *c = 0.0);
+    }
+
+    let length = held_karp_bound(&cost);
+    let leaving: Vec<f64> = (1..n)
+        .map(|i| min_surcharge(instance, instance.nodes[i].demand.max(0) as f64))
+        .collect();
+    let mut surcharge = min_surcharge(instance, instance.starting_load() as f64) + leaving.iter().sum::<f64>();
+    // No arc leaves the last node of an open tour
+    if instance.open_tour {
+        surcharge -= leaving.iter().copied().fold(0.0, f64::max);
+    }
+
+    // Every node is served once in a tour without depot revisits
+    let service = match instance.cost_function {
+        CostFunction::Duration => instance.tour_service_time(&(0..n).collect::<Vec<_>>()),
+        _ => 0.0,
+    };
+    let base = length + surcharge + service;
+    match &instance.vehicle {
+        None => base,
+        Some(vehicle) => vehicle.tour_cost(base, 0.0),
+    }
+}
+
+/// Least surcharge of an arc carrying a load in [lo, capacity]
+fn min_surcharge(instance: &PDTSPInstance, lo: f64) -> f64 {
+    let (alpha, beta) = (instance.alpha, instance.beta);
+    let hi = (instance.capacity as f64).max(lo);
+    let f = |w: f64| match instance.cost_function {
+        CostFunction::Distance => 0.0,
+        CostFunction::Quadratic => alpha * w + beta * w * w,
+        CostFunction::LinearLoad => alpha * w.abs(),
+        CostFunction::Duration => 0.0,
+    };
+
+    let mut best = f(lo).min(f(hi));
+    if instance.cost_function == CostFunction::Quadratic && beta > 0.0 {
+        let vertex = -alpha / (2.0 * beta);
+        if vertex > lo && vertex < hi {
+            best = best.min(f(vertex));
+        }
+    }
+    best
+}
+
+/// Held–Karp bound on the length of a tour for a symmetric cost matrix
+fn held_karp_bound(cost: &[Vec<f64>]) -> f64 {
+    let n = cost.len();
+    if n < 3 {
+        return if n == 2 { 2.0 * cost[0][1] } else { 0.0 };
+    }
+
+    let upper = nearest_neighbor_length(cost);
+    let mut pi = vec![0.0; n];
+    let mut degree = vec![0usize; n];
+    let mut best = f64::NEG_INFINITY;
+    let mut lambda = 2.0;
+    let mut since_improvement = 0;
+
+    for _ in 0..MAX_ITERATIONS {
+        let tree = one_tree(cost, &pi, &mut degree);
+        if !tree.is_finite() {
+            return tree;
+        }
+        let bound = tree - 2.0 * pi.iter().sum::<f64>();
+        if bound > best {
+            best = bound;
+            since_improvement = 0;
+        } else {
+            since_improvement += 1;
+            if since_improvement >= HALVING_PERIOD {
+                lambda /= 2.0;
+                since_improvement = 0;
+            }
+        }
+
+        // A 1-tree where every node has degree 2 is an optimal tour
+        let norm: f64 = degree.iter().map(|&d| (d as f64 - 2.0).powi(2)).sum();
+        if norm == 0.0 || lambda < 1e-4 {
+            break;
+        }
+        let gap = if upper.is_finite() { (upper - bound).max(1e-9) } else { bound.abs().max(1.0) };
+        let step = lambda * gap / norm;
+        for (p, &d) in pi.iter_mut().zip(&degree) {
+            *p += step * (d as f64 - 2.0);
+        }
+    }
+
+    best
+}
+
+/// Weight of the minimum 1-tree under costs c(i, j) + pi[i] + pi[j], with
+/// node 0 as the special node; fills in the node degrees
+fn one_tree(cost: &[Vec<f64>], pi: &[f64], degree: &mut [usize]) -> f64 {
+    let n = cost.len();
+    let weight = |i: usize, j: usize| cost[i][j] + pi[i] + pi[j];
+    degree.iter_mut().for_each(|d| *d = 0);
+
+    // Prim's algorithm over the customers
+    let mut in_tree = vec![false; n];
+    let mut key = vec![f64::INFINITY; n];
+    let mut parent = vec![usize::MAX; n];
+    key[1] = 0.0;
+    let mut total = 0.0;
+
+    for _ in 1..n {
+        let u = (1..n)
+            .filter(|&v| !in_tree[v])
+            .min_by(|&a, &b| key[a].total_cmp(&key[b]))
+            .unwrap();
+        if !key[u].is_finite() {
+            return f64::INFINITY;
+        }
+        in_tree[u] = true;
+        total += key[u];
+        if parent[u] != usize::MAX {
+            degree[u] += 1;
+            degree[parent[u]] += 1;
+        }
+        for v in 1..n {
+            if !in_tree[v] && weight(u, v) < key[v] {
+                key[v] = weight(u, v);
+                parent[v] = u;
+            }
+        }
+    }
+
+    // The two cheapest edges at the depot
+    let mut depot: Vec<usize> = (1..n).collect();
+    depot.sort_by(|&a, &b| weight(0, a).total_cmp(&weight(0, b)));
+    for &v in &depot[..2] {
+        total += weight(0, v);
+        degree[0] += 1;
+        degree[v] += 1;
+    }
+
+    total
+}
+
+/// Length of the nearest-neighbor tour from node 0
+fn nearest_neighbor_length(cost: &[Vec<f64>]) -> f64 {
+    let n = cost.len();
+    let mut visited = vec![false; n];
+    visited[0] = true;
+    let mut current = 0;
+    let mut length = 0.0;
+
+    for _ in 1..n {
+        let next = (0..n)
+            .filter(|&v| !visited[v])
+            .min_by(|&a, &b| cost[current][a].total_cmp(&cost[current][b]))
+            .unwrap();
+        length += cost[current][next];
+        visited[next] = true;
+        current = next;
+    }
+    length + cost[current][0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact::dynamic_programming;
+    use crate::instance::{DistanceMatrix, DistanceMode, Node};
+
+    #[test]
+    fn test_bound_below_optimum() {
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 4.0, 1.0, 3, 0),
+            Node::new(2, 6.0, 5.0, -2, 0),
+            Node::new(3, 1.0, 7.0, 2, 0),
+            Node::new(4, -3.0, 4.0, -3, 0),
+            Node::new(5, 2.0, 3.0, 1, 0),
+            Node::new(6, -1.0, -2.0, -1, 0),
+        ];
+        let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        let mut instance = PDTSPInstance {
+            alpha: 0.5,
+            beta: 0.2,
+            ..PDTSPInstance::from_parts("bound", 4, nodes, distance_matrix)
+        };
+
+        for cost_function in [CostFunction::Distance, CostFunction::Quadratic, CostFunction::LinearLoad] {
+            instance.cost_function = cost_function;
+            let optimum = dynamic_programming::solve(&instance).unwrap().cost;
+            let bound = lagrangian_1tree_bound(&instance);
+            assert!(bound <= optimum + 1e-6, "{:?}: {} > {}", cost_function, bound, optimum);
+            assert!(bound >= 0.8 * optimum, "{:?}: {} too weak for {}", cost_function, bound, optimum);
+        }
+
+        // On the corners of a square the bound is the optimal tour
+        let square: Vec<Vec<f64>> = vec![
+            vec![0.0, 1.0, 2f64.sqrt(), 1.0],
+            vec![1.0, 0.0, 1.0, 2f64.sqrt()],
+            vec![2f64.sqrt(), 1.0, 0.0, 1.0],
+            vec![1.0, 2f64.sqrt(), 1.0, 0.0],
+        ];
+        assert!((held_karp_bound(&square) - 4.0).abs() < 1e-9);
+    }
+}
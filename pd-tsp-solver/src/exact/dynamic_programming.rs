@@ -0,0 +1,262 @@
+//! Held–Karp dynamic programming solver for small PD-TSP instances.
+//!
+//! States are (set of visited customers, last customer). Because the vehicle
+//! load after visiting a set of customers only depends on the set itself, a
+//! whole subset can be discarded as soon as its load leaves `[0, capacity]`,
+//! and the load-dependent cost functions remain exact under the DP. The same
+//! holds per commodity for multi-commodity instances.
+//!
+//! Memory and time grow as O(2^n · n), so this is limited to
+//! [`MAX_DIMENSION`] nodes. It needs no external solver, which makes it
+//! convenient for validating heuristics in tests.
+
+use crate::instance::{CostFunction, PDTSPInstance};
+use crate::solution::Solution;
+use web_time::Instant;
+
+/// Largest instance dimension (depot included) accepted by the solver
+pub const MAX_DIMENSION: usize = 20;
+
+/// Cost of travelling from `i` to `j` while carrying `load`, under the
+/// instance's active cost function and vehicle profile (fixed cost excluded)
+pub(crate) fn arc_cost(instance: &PDTSPInstance, i: usize, j: usize, load: i32) -> f64 {
+    // The DP only returns to the depot at the end, which open tours skip
+    if j == 0 && instance.open_tour {
+        return 0.0;
+    }
+    travel_cost(instance, i, j, load)
+}
+
+/// `arc_cost` of an arc that is travelled even on an open tour (e.g. into
+/// an intermediate depot visit)
+pub(crate) fn travel_cost(instance: &PDTSPInstance, i: usize, j: usize, load: i32) -> f64 {
+    let dist = instance.distance(i, j);
+    let load = load as f64;
+    let base = match instance.cost_function {
+        CostFunction::Distance => dist,
+        CostFunction::Quadratic => dist + instance.alpha * load + instance.beta * load * load,
+        CostFunction::LinearLoad => dist + instance.alpha * load.abs(),
+        CostFunction::Duration => dist + instance.nodes[j].service_time,
+    };
+    match &instance.vehicle {
+        None => base,
+        Some(vehicle) => vehicle.cost_per_distance * base + vehicle.cost_per_load_unit * load.abs() * dist,
+    }
+}
+
+/// Solve the instance to optimality with bitmask dynamic programming.
+///
+/// Returns an error if the instance is too large, has a side constraint the
+/// recursion leaves out (see `check_supported`) or has no feasible tour.
+pub fn solve(instance: &PDTSPInstance) -> Result<Solution, String> {
+    let start = Instant::now();
+    let n = instance.dimension;
+
+    super::check_supported(instance, "Dynamic programming")?;
+    if n == 0 {
+        return Err("Empty instance".to_string());
+    }
+    if n > MAX_DIMENSION {
+        return Err(format!(
+            "Instance has {} nodes, dynamic programming is limited to {}",
+            n, MAX_DIMENSION
+        ));
+    }
+
+    if n == 1 {
+        let mut solution = Solution::from_tour(instance, vec![0], "HeldKarpDP");
+        solution.computation_time = start.elapsed().as_secs_f64();
+        return Ok(solution);
+    }
+
+    // Customers 1..n are mapped to bits 0..m
+    let m = n - 1;
+    let num_masks = 1usize << m;
+    let start_load = instance.starting_load();
+
+    // Load after visiting each subset, built incrementally from the lowest bit
+    let mut load = vec![start_load; num_masks];
+    for mask in 1..num_masks {
+        let bit = mask.trailing_zeros() as usize;
+        load[mask] = load[mask & (mask - 1)] + instance.nodes[bit + 1].demand;
+    }
+    let mut mask_ok: Vec<bool> = load.iter().map(|&l| l >= 0 && l <= instance.capacity).collect();
+
+    // Per-commodity loads are subset-determined too, so they prune the same way
+    if let Some(commodities) = &instance.commodities {
+        let start_loads = instance.commodity_starting_loads();
+        let mut commodity_load = vec![0i32; num_masks];
+        for (k, &start) in start_loads.iter().enumerate() {
+            commodity_load[0] = start;
+            for mask in 1..num_masks {
+                let bit = mask.trailing_zeros() as usize;
+                commodity_load[mask] = commodity_load[mask & (mask - 1)] + commodities.demands[bit + 1][k];
+                if commodity_load[mask] < 0 || commodity_load[mask] > commodities.capacities[k] {
+                    mask_ok[mask] = false;
+                }
+            }
+        }
+    }
+    let feasible = |mask: usize| mask_ok[mask];
+
+    let mut cost = vec![f64::INFINITY; num_masks * m];
+    let mut parent = vec![u8::MAX; num_masks * m];
+    let mut states = 0usize;
+
+    for j in 0..m {
+        let mask = 1 << j;
+        if feasible(mask) {
+            cost[mask * m + j] = arc_cost(instance, 0, j + 1, start_load);
+        }
+    }
+
+    for mask in 1..num_masks {
+        if !feasible(mask) {
+            continue;
+        }
+
+        for last in 0..m {
+            let current = cost[mask * m + last];
+            if mask & (1 << last) == 0 || current == f64::INFINITY {
+                continue;
+            }
+            states += 1;
+
+            for next in 0..m {
+                if mask & (1 << next) != 0 {
+                    continue;
+                }
+                let next_mask = mask | (1 << next);
+                if !feasible(next_mask) {
+                    continue;
+                }
+
+                let candidate = current + arc_cost(instance, last + 1, next + 1, load[mask]);
+                let idx = next_mask * m + next;
+                if candidate < cost[idx] {
+                    cost[idx] = candidate;
+                    parent[idx] = last as u8;
+                }
+            }
+        }
+    }
+
+    let full = num_masks - 1;
+    let best_last = (0..m)
+        .filter(|&last| cost[full * m + last] < f64::INFINITY)
+        .min_by(|&a, &b| {
+            let ca = cost[full * m + a] + arc_cost(instance, a + 1, 0, load[full]);
+            let cb = cost[full * m + b] + arc_cost(instance, b + 1, 0, load[full]);
+            ca.partial_cmp(&cb).unwrap()
+        })
+        .ok_or_else(|| "No feasible tour exists".to_string())?;
+
+    // Walk parents back from the final state
+    let mut tour = Vec::with_capacity(n);
+    let mut mask = full;
+    let mut last = best_last;
+    loop {
+        tour.push(last + 1);
+        let prev = parent[mask * m + last];
+        mask &= !(1 << last);
+        if prev == u8::MAX {
+            break;
+        }
+        last = prev as usize;
+    }
+    tour.push(0);
+    tour.reverse();
+
+    let mut solution = Solution::from_tour(instance, tour, "HeldKarpDP");
+    solution.computation_time = start.elapsed().as_secs_f64();
+    solution.iterations = Some(states);
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::{DistanceMatrix, Node};
+
+    fn create_test_instance(cost_function: CostFunction) -> PDTSPInstance {
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 3.0, 0.0, 4, 0),
+            Node::new(2, 6.0, 1.0, -3, 0),
+            Node::new(3, 5.0, 5.0, 5, 0),
+            Node::new(4, 1.0, 6.0, -4, 0),
+            Node::new(5, -2.0, 3.0, -2, 0),
+            Node::new(6, 2.0, 2.0, 3, 0),
+        ];
+
+        let mut instance = PDTSPInstance {
+            cost_function,
+            beta: 0.05,
+            ..PDTSPInstance::from_parts("dp-test", 6, nodes, DistanceMatrix::default())
+        };
+
+        let n = instance.dimension;
+        instance.distance_matrix = DistanceMatrix::new(n);
+        for i in 0..n {
+            for j in 0..n {
+                let dx = instance.nodes[i].x - instance.nodes[j].x;
+                let dy = instance.nodes[i].y - instance.nodes[j].y;
+                instance.distance_matrix.set(i, j, (dx * dx + dy * dy).sqrt());
+            }
+        }
+
+        instance
+    }
+
+    /// Exhaustive search over all permutations, for cross-checking
+    fn brute_force(instance: &PDTSPInstance) -> f64 {
+        fn permute(instance: &PDTSPInstance, tour: &mut Vec<usize>, k: usize, best: &mut f64) {
+            if k == tour.len() {
+                if instance.is_feasible(tour) {
+                    *best = best.min(instance.tour_cost(tour));
+                }
+                return;
+            }
+            for i in k..tour.len() {
+                tour.swap(k, i);
+                permute(instance, tour, k + 1, best);
+                tour.swap(k, i);
+            }
+        }
+
+        let mut tour: Vec<usize> = (0..instance.dimension).collect();
+        let mut best = f64::INFINITY;
+        permute(instance, &mut tour, 1, &mut best);
+        best
+    }
+
+    #[test]
+    fn test_dp_matches_brute_force() {
+        for cost_function in [CostFunction::Distance, CostFunction::Quadratic, CostFunction::LinearLoad] {
+            let instance = create_test_instance(cost_function);
+            let solution = solve(&instance).unwrap();
+
+            assert!(solution.feasible);
+            assert_eq!(solution.tour[0], 0);
+            assert_eq!(solution.tour.len(), instance.dimension);
+            assert!((solution.cost - brute_force(&instance)).abs() < 1e-9);
+        }
+        
+        let mut instance = create_test_instance(CostFunction::Quadratic);
+        instance.set_vehicle_profile(crate::vehicle::VehicleProfile {
+            fixed_cost: 25.0,
+            cost_per_distance: 1.5,
+            cost_per_load_unit: 0.3,
+            ..Default::default()
+        });
+        let solution = solve(&instance).unwrap();
+        assert!((solution.cost - brute_force(&instance)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dp_rejects_route_limits() {
+        let mut instance = create_test_instance(CostFunction::Distance);
+        instance.max_route_length = Some(1e9);
+        assert!(solve(&instance).is_err());
+    }
+}
@@ -7,13 +7,36 @@
 //! - Binary variables x[i][j] for edges
 //! - Continuous variables u[i] for MTZ subtour elimination
 //! - Continuous variables q[i] for cumulative load
+//!
+//! Every node is left exactly once, carrying load q[i], so the load-dependent
+//! surcharge of the tour is sum_i f(q[i]). The linear-load cost adds alpha to
+//! the objective coefficient of q[i]; the quadratic cost alpha*W + beta*W² is
+//! either passed to Gurobi as a convex quadratic objective or replaced by its
+//! piecewise-linear interpolation (`quadratic_breakpoints`), modelled with
+//! one epigraph variable per node bounded below by every secant line.
+//!
+//! With `profit_objective`, visiting a customer becomes optional: binary
+//! variables y[i] mark visited nodes, the degree constraints of node i are
+//! x(in) = x(out) = y[i], and the model maximizes sum_i profit_i*y[i] minus
+//! the travel cost (the profitable tour problem).
+//!
+//! `solve_with_callbacks` drops the MTZ constraints on larger instances and
+//! instead separates subtour elimination constraints lazily: every integer
+//! solution found by Gurobi is split into connected components and each
+//! subtour S gets the cut sum_{i,j in S} x[i][j] <= |S| - 1.
 
 #[cfg(feature = "gurobi")]
 use crate::instance::{PDTSPInstance, CostFunction};
 #[cfg(feature = "gurobi")]
 use crate::solution::Solution;
 #[cfg(feature = "gurobi")]
+use crate::progress::{notify, ProgressEvent, ProgressHandler};
+#[cfg(feature = "gurobi")]
 use grb::prelude::*;
+#[cfg(feature = "gurobi")]
+use grb::callback::{CbResult, Where};
+#[cfg(feature = "gurobi")]
+use super::{connected_components, prepare_warm_start};
 
 /// Gurobi solver configuration
 #[derive(Debug, Clone)]
@@ -28,6 +51,12 @@ pub struct GurobiConfig {
     pub verbose: bool,
     /// Use warm start from heuristic solution
     pub warm_start: Option<Vec<usize>>,
+    /// Breakpoints of the piecewise-linear approximation of the quadratic
+    /// load cost over [0, capacity] (0: native quadratic objective)
+    pub quadratic_breakpoints: usize,
+    /// Maximize collected profit minus travel cost, with optional visits.
+    /// Objective values and bounds are then reported as cost - profit.
+    pub profit_objective: bool,
 }
 
 impl Default for GurobiConfig {
@@ -38,6 +67,8 @@ impl Default for GurobiConfig {
             threads: 0,
             verbose: false,
             warm_start: None,
+            quadratic_breakpoints: 0,
+            profit_objective: false,
         }
     }
 }
@@ -64,19 +95,155 @@ pub struct ExactResult {
 /// Gurobi-based exact solver for PD-TSP
 pub struct GurobiSolver {
     config: GurobiConfig,
+    on_progress: Option<ProgressHandler>,
 }
 
 impl GurobiSolver {
     pub fn new(config: GurobiConfig) -> Self {
-        GurobiSolver { config }
+        GurobiSolver { config, on_progress: None }
+    }
+    
+    /// Receive a progress event when the solve finishes
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+        self.on_progress = Some(ProgressHandler::new(callback));
+    }
+    
+    /// Visit variables y[i] of the profit objective (y[0] = 1), whose
+    /// objective coefficient is minus the node's profit
+    fn add_visit_variables(&self, model: &mut Model, instance: &PDTSPInstance) -> Result<Option<Vec<Var>>, String> {
+        if !self.config.profit_objective {
+            return Ok(None);
+        }
+        
+        let mut y = Vec::with_capacity(instance.dimension);
+        for i in 0..instance.dimension {
+            let var = add_binvar!(model,
+                name: &format!("y_{}", i),
+                obj: -(instance.nodes[i].profit as f64)
+            ).map_err(|e| format!("Failed to add variable y[{}]: {}", i, e))?;
+            y.push(var);
+        }
+        model.add_constr("depot_visited", c!(y[0] == 1.0))
+            .map_err(|e| format!("Failed to add depot visit constraint: {}", e))?;
+        
+        Ok(Some(y))
+    }
+    
+    /// Inject the configured warm start tour as a complete MIP start: arcs
+    /// x, positions u, loads q and visits y, all consistent with the tour
+    /// once it has been checked (and if needed repaired) for feasibility
+    fn set_warm_start(&self, model: &mut Model, instance: &PDTSPInstance, x: &[Vec<Var>], u: Option<&[Var]>, q: &[Var], y: Option<&[Var]>) -> Result<(), String> {
+        let Some(ref warm_tour) = self.config.warm_start else {
+            return Ok(());
+        };
+        let Some(tour) = prepare_warm_start(instance, warm_tour, self.config.profit_objective) else {
+            if self.config.verbose {
+                tracing::warn!("Warm start tour is infeasible and could not be repaired; ignoring it");
+            }
+            return Ok(());
+        };
+        let n = instance.dimension;
+        let m = tour.len();
+        let set = |model: &mut Model, var: &Var, value: f64| {
+            model.set_obj_attr(attr::Start, var, value)
+                .map_err(|e| format!("Failed to set warm start value: {}", e))
+        };
+        
+        for row in x {
+            for var in row {
+                set(model, var, 0.0)?;
+            }
+        }
+        for k in 0..m {
+            set(model, &x[tour[k]][tour[(k + 1) % m]], 1.0)?;
+        }
+        
+        let mut load = instance.starting_load() as f64;
+        for (k, &node) in tour.iter().enumerate() {
+            if node != 0 {
+                load += instance.nodes[node].demand as f64;
+            }
+            if let Some(u) = u {
+                set(model, &u[node], k as f64)?;
+            }
+            set(model, &q[node], load)?;
+        }
+        
+        if let Some(y) = y {
+            let mut visited = vec![0.0; n];
+            for &node in &tour {
+                visited[node] = 1.0;
+            }
+            for (var, &value) in y.iter().zip(&visited) {
+                set(model, var, value)?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Add the load-dependent surcharge sum_i f(q[i]) to the objective,
+    /// whose arc-length part is already set on the x variables
+    fn set_load_objective(&self, model: &mut Model, instance: &PDTSPInstance, x: &[Vec<Var>], q: &[Var]) -> Result<(), String> {
+        let n = instance.dimension;
+        let (alpha, beta) = (instance.alpha, instance.beta);
+        
+        match instance.cost_function {
+            // Service times add the same constant to every tour
+            CostFunction::Distance | CostFunction::Duration => {}
+            CostFunction::LinearLoad => {
+                // Loads are non-negative, so alpha*|W| = alpha*W
+                for var in q {
+                    model.set_obj_attr(attr::Obj, var, alpha)
+                        .map_err(|e| format!("Failed to set load cost: {}", e))?;
+                }
+            }
+            CostFunction::Quadratic if self.config.quadratic_breakpoints == 0 => {
+                let arcs: Expr = (0..n)
+                    .flat_map(|i| (0..n).map(move |j| (i, j)))
+                    .filter(|&(i, j)| i != j)
+                    .map(|(i, j)| arc_objective(instance, i, j) * x[i][j])
+                    .grb_sum();
+                let loads: Expr = q.iter()
+                    .map(|&w| alpha * w + beta * (w * w))
+                    .grb_sum();
+                model.set_objective(arcs + loads, ModelSense::Minimize)
+                    .map_err(|e| format!("Failed to set quadratic objective: {}", e))?;
+            }
+            CostFunction::Quadratic => {
+                let cost = |w: f64| alpha * w + beta * w * w;
+                let capacity = instance.capacity as f64;
+                let segments = self.config.quadratic_breakpoints.max(2) - 1;
+                let breakpoints: Vec<f64> = (0..=segments)
+                    .map(|k| capacity * k as f64 / segments as f64)
+                    .collect();
+                
+                for (i, &w) in q.iter().enumerate() {
+                    let s = add_ctsvar!(model,
+                        name: &format!("s_{}", i),
+                        bounds: ..,
+                        obj: 1.0
+                    ).map_err(|e| format!("Failed to add variable s[{}]: {}", i, e))?;
+                    
+                    for (k, pair) in breakpoints.windows(2).enumerate() {
+                        let (a, b) = (pair[0], pair[1]);
+                        let slope = (cost(b) - cost(a)) / (b - a);
+                        model.add_constr(
+                            &format!("pwl_{}_{}", i, k),
+                            c!(s >= cost(a) + slope * (w - a))
+                        ).map_err(|e| format!("Failed to add piecewise-linear constraint: {}", e))?;
+                    }
+                }
+            }
+        }
+        
+        Ok(())
     }
     
     /// Solve PD-TSP to optimality (or near-optimality)
     pub fn solve(&self, instance: &PDTSPInstance) -> Result<ExactResult, String> {
-        if instance.cost_function == CostFunction::Quadratic {
-            return Err("Gurobi exact solver does not support quadratic load-dependent cost. Use linear cost or heuristics.".to_string());
-        }
-        let start = std::time::Instant::now();
+        super::check_supported(instance, "The Gurobi model")?;
+        let start = web_time::Instant::now();
         let n = instance.dimension;
         
         // Simplified TSP formulation:
@@ -107,7 +274,7 @@ impl GurobiSolver {
         for i in 0..n {
             let mut row = Vec::with_capacity(n);
             for j in 0..n {
-                let dist = instance.distance(i, j);
+                let dist = arc_objective(instance, i, j);
                 let var = add_binvar!(model, 
                     name: &format!("x_{}_{}", i, j),
                     obj: dist
@@ -137,21 +304,27 @@ impl GurobiSolver {
             q.push(var);
         }
         
+        let y = self.add_visit_variables(&mut model, instance)?;
+        
         model.update()
             .map_err(|e| format!("Failed to update model: {}", e))?;
         
-        // Flow conservation: each customer visited exactly once
+        // Flow conservation: each customer visited exactly once (or y[j] times)
         for j in 1..n {
             let expr_in: Expr = (0..n).filter(|&i| i != j)
                 .map(|i| x[i][j])
                 .grb_sum();
-            model.add_constr(&format!("in_{}", j), c!(expr_in == 1.0))
-                .map_err(|e| format!("Failed to add in-degree constraint: {}", e))?;
-            
             let expr_out: Expr = (0..n).filter(|&k| k != j)
                 .map(|k| x[j][k])
                 .grb_sum();
-            model.add_constr(&format!("out_{}", j), c!(expr_out == 1.0))
+            let (in_constr, out_constr) = match &y {
+                Some(y) => (c!(expr_in == y[j]), c!(expr_out == y[j])),
+                None => (c!(expr_in == 1.0), c!(expr_out == 1.0)),
+            };
+            
+            model.add_constr(&format!("in_{}", j), in_constr)
+                .map_err(|e| format!("Failed to add in-degree constraint: {}", e))?;
+            model.add_constr(&format!("out_{}", j), out_constr)
                 .map_err(|e| format!("Failed to add out-degree constraint: {}", e))?;
         }
         
@@ -223,24 +396,13 @@ impl GurobiSolver {
         
         // For edges TO depot: no specific constraint (load can be anything feasible)
         
+        // The arc leaving the depot carries the starting load
+        model.add_constr("depot_load", c!(q[0] == initial_load))
+            .map_err(|e| format!("Failed to add depot load constraint: {}", e))?;
+        self.set_load_objective(&mut model, instance, &x, &q)?;
+        
         // Warm start
-        if let Some(ref warm_tour) = self.config.warm_start {
-            for i in 0..n {
-                for j in 0..n {
-                    model.set_obj_attr(attr::Start, &x[i][j], 0.0)
-                        .map_err(|e| format!("Failed to initialize warm start: {}", e))?;
-                }
-            }
-
-            for w in warm_tour.windows(2) {
-                let u = w[0];
-                let v = w[1];
-                if u < n && v < n {
-                    model.set_obj_attr(attr::Start, &x[u][v], 1.0)
-                        .map_err(|e| format!("Failed to set warm start edge: {}", e))?;
-                }
-            }
-        }
+        self.set_warm_start(&mut model, instance, &x, Some(&u[..]), &q, y.as_deref())?;
         
         model.update()
             .map_err(|e| format!("Failed to update model before optimization: {}", e))?;
@@ -267,7 +429,7 @@ impl GurobiSolver {
         if status == Status::Infeasible {
             let _ = model.compute_iis();
             let _ = model.write("gurobi_iis.ilp");
-            eprintln!("Gurobi reported infeasible model; IIS written to gurobi_iis.ilp");
+            tracing::warn!("Gurobi reported infeasible model; IIS written to gurobi_iis.ilp");
         }
         
         // Extract solution
@@ -330,6 +492,11 @@ impl GurobiSolver {
         let mut solution = Solution::from_tour(instance, tour, "Gurobi-Exact");
         solution.computation_time = start.elapsed().as_secs_f64();
         
+        notify(&self.on_progress, || {
+            ProgressEvent::new("Gurobi-Exact", nodes.max(0) as usize, obj_val, solution.computation_time)
+                .with_message(format!("{} (gap {:.4})", status_str, gap))
+        });
+        
         Ok(ExactResult {
             solution,
             lower_bound,
@@ -343,10 +510,7 @@ impl GurobiSolver {
     
     /// Solve with callback for lazy constraints (more efficient subtour elimination)
     pub fn solve_with_callbacks(&self, instance: &PDTSPInstance) -> Result<ExactResult, String> {
-        // Do not support quadratic cost in callback solver either
-        if instance.cost_function == CostFunction::Quadratic {
-            return Err("Gurobi exact solver does not support quadratic load-dependent cost. Use linear cost or heuristics.".to_string());
-        }
+        super::check_supported(instance, "The Gurobi model")?;
         // For smaller instances, use the simpler MTZ formulation
         if instance.dimension <= 50 {
             return self.solve(instance);
@@ -354,8 +518,9 @@ impl GurobiSolver {
         
         // For larger instances, use lazy constraint callback
         // This is more efficient as it only adds subtour elimination constraints when needed
+        // (the load constraints alone do not prevent subtours through zero-demand nodes)
         
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
         let n = instance.dimension;
         
         let env = Env::new("")
@@ -385,7 +550,7 @@ impl GurobiSolver {
             for j in 0..n {
                 let var = add_binvar!(model, 
                     name: &format!("x_{}_{}", i, j),
-                    obj: instance.distance(i, j)
+                    obj: arc_objective(instance, i, j)
                 ).map_err(|e| format!("Failed to add variable: {}", e))?;
                 row.push(var);
             }
@@ -402,6 +567,8 @@ impl GurobiSolver {
             q.push(var);
         }
         
+        let y = self.add_visit_variables(&mut model, instance)?;
+        
         model.update()
             .map_err(|e| format!("Failed to update model: {}", e))?;
         
@@ -411,7 +578,11 @@ impl GurobiSolver {
                 .filter(|(i, _)| *i != j)
                 .map(|(_, row)| row[j])
                 .grb_sum();
-            model.add_constr(&format!("in_{}", j), c!(expr == 1.0))
+            let constr = match &y {
+                Some(y) => c!(expr == y[j]),
+                None => c!(expr == 1.0),
+            };
+            model.add_constr(&format!("in_{}", j), constr)
                 .map_err(|e| format!("Failed to add constraint: {}", e))?;
         }
         
@@ -420,7 +591,11 @@ impl GurobiSolver {
                 .filter(|(j, _)| *j != i)
                 .map(|(_, &var)| var)
                 .grb_sum();
-            model.add_constr(&format!("out_{}", i), c!(expr == 1.0))
+            let constr = match &y {
+                Some(y) => c!(expr == y[i]),
+                None => c!(expr == 1.0),
+            };
+            model.add_constr(&format!("out_{}", i), constr)
                 .map_err(|e| format!("Failed to add constraint: {}", e))?;
         }
         
@@ -429,26 +604,61 @@ impl GurobiSolver {
                 .map_err(|e| format!("Failed to add constraint: {}", e))?;
         }
         
-        // Load constraints
+        // Load constraints: the vehicle leaves the depot with the starting
+        // load, and the load is propagated along every customer arc
+        model.add_constr("start_load", c!(q[0] == instance.starting_load() as f64))
+            .map_err(|e| format!("Failed to add constraint: {}", e))?;
         let big_m = 2.0 * instance.capacity as f64;
         for i in 0..n {
-            for j in 0..n {
+            for j in 1..n {
                 if i != j {
                     let demand_j = instance.nodes[j].demand as f64;
                     model.add_constr(
                         &format!("ld_{}_{}", i, j),
                         c!(q[j] >= q[i] + demand_j - big_m * (1.0 - x[i][j]))
                     ).map_err(|e| format!("Failed to add constraint: {}", e))?;
+                    model.add_constr(
+                        &format!("lu_{}_{}", i, j),
+                        c!(q[j] <= q[i] + demand_j + big_m * (1.0 - x[i][j]))
+                    ).map_err(|e| format!("Failed to add constraint: {}", e))?;
                 }
             }
         }
         
+        self.set_load_objective(&mut model, instance, &x, &q)?;
+        self.set_warm_start(&mut model, instance, &x, None, &q, y.as_deref())?;
+        
         model.update()
             .map_err(|e| format!("Failed to update model: {}", e))?;
         
-        // Optimize (without explicit callback for simplicity - using MTZ for now)
-        // A full implementation would use Gurobi's callback API
-        model.optimize()
+        // Separate subtour elimination constraints on every integer solution
+        let mut cuts = 0usize;
+        let mut callback = |w: Where| -> CbResult {
+            if let Where::MIPSol(ctx) = w {
+                let values = ctx.get_solution(x.iter().flatten())?;
+                let edges: Vec<(usize, usize)> = (0..n)
+                    .flat_map(|i| (0..n).map(move |j| (i, j)))
+                    .filter(|&(i, j)| i != j && values[i * n + j] > 0.5)
+                    .collect();
+                
+                // Cutting the components away from the depot is enough; with the
+                // profit objective the depot's component is a legitimate tour
+                for component in connected_components(n, &edges) {
+                    if component.len() < 2 || component.contains(&0) {
+                        continue;
+                    }
+                    let inside: Expr = component.iter()
+                        .flat_map(|&i| component.iter().filter(move |&&j| j != i).map(move |&j| (i, j)))
+                        .map(|(i, j)| x[i][j])
+                        .grb_sum();
+                    ctx.add_lazy(c!(inside <= (component.len() - 1) as f64))?;
+                    cuts += 1;
+                }
+            }
+            Ok(())
+        };
+        
+        model.optimize_with_callback(&mut callback)
             .map_err(|e| format!("Optimization failed: {}", e))?;
         
         let status = model.status()
@@ -457,6 +667,8 @@ impl GurobiSolver {
         let status_str = match status {
             Status::Optimal => "Optimal",
             Status::TimeLimit => "TimeLimit",
+            Status::Infeasible => "Infeasible",
+            Status::InfOrUnbd => "InfeasibleOrUnbounded",
             _ => "Unknown",
         };
         
@@ -477,16 +689,18 @@ impl GurobiSolver {
             tour.push(0);
             let mut current = 0;
             
+            // Follow edges until the tour returns to the depot (early when
+            // the profit objective skips nodes)
             for _ in 0..n - 1 {
-                for j in 0..n {
-                    if j != current {
-                        let val = model.get_obj_attr(attr::X, &x[current][j]).unwrap_or(0.0);
-                        if val > 0.5 {
-                            tour.push(j);
-                            current = j;
-                            break;
-                        }
+                let next = (0..n)
+                    .filter(|&j| j != current)
+                    .find(|&j| model.get_obj_attr(attr::X, &x[current][j]).unwrap_or(0.0) > 0.5);
+                match next {
+                    Some(j) if j != 0 => {
+                        tour.push(j);
+                        current = j;
                     }
+                    _ => break,
                 }
             }
         } else {
@@ -500,6 +714,11 @@ impl GurobiSolver {
         let mut solution = Solution::from_tour(instance, tour, "Gurobi-Callback");
         solution.computation_time = start.elapsed().as_secs_f64();
         
+        notify(&self.on_progress, || {
+            ProgressEvent::new("Gurobi-Callback", nodes.max(0) as usize, obj_val, solution.computation_time)
+                .with_message(format!("{} (gap {:.4}, {} subtour cuts)", status_str, gap, cuts))
+        });
+        
         Ok(ExactResult {
             solution,
             lower_bound,
@@ -512,6 +731,16 @@ impl GurobiSolver {
     }
 }
 
+/// Objective coefficient of the arc from `i` to `j`: the return arc of an
+/// open tour is not travelled
+fn arc_objective(instance: &PDTSPInstance, i: usize, j: usize) -> f64 {
+    if instance.open_tour && j == 0 {
+        0.0
+    } else {
+        instance.distance(i, j)
+    }
+}
+
 /// Compute lower bound using LP relaxation
 pub fn compute_lp_bound(instance: &PDTSPInstance) -> Result<f64, String> {
     let n = instance.dimension;
@@ -532,7 +761,7 @@ pub fn compute_lp_bound(instance: &PDTSPInstance) -> Result<f64, String> {
             let var = add_ctsvar!(model, 
                 name: &format!("x_{}_{}", i, j),
                 bounds: 0.0..1.0,
-                obj: instance.distance(i, j)
+                obj: arc_objective(instance, i, j)
             ).map_err(|e| format!("Failed to add variable: {}", e))?;
             row.push(var);
         }
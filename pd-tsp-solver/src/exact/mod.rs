@@ -1,5 +1,93 @@
 //! Exact solvers module.
 
+pub mod bounds;
+pub mod dynamic_programming;
+pub mod set_partitioning;
+pub mod subproblem;
+
+use crate::heuristics::local_search::{LocalSearch, VND};
+use crate::instance::PDTSPInstance;
+use crate::solution::Solution;
+
+/// Reject instances with side constraints the exact models leave out: a
+/// route length limit or a chance constraint, which the tours they return as
+/// optimal could break
+pub fn check_supported(instance: &PDTSPInstance, solver: &str) -> Result<(), String> {
+	if instance.has_route_limit() {
+		return Err(format!("{} does not support route length limits", solver));
+	}
+	if instance.has_chance_constraint() {
+		return Err(format!("{} does not support stochastic demands", solver));
+	}
+	Ok(())
+}
+
+/// Turn a heuristic tour into a MIP start. The tour is rotated to start at
+/// the depot, repeated and out-of-range nodes are dropped and, unless
+/// `allow_partial`, missing nodes are appended. An infeasible complete tour
+/// gets one VND pass; returns None if the tour is still infeasible.
+pub fn prepare_warm_start(instance: &PDTSPInstance, tour: &[usize], allow_partial: bool) -> Option<Vec<usize>> {
+	let n = instance.dimension;
+	let mut seen = vec![false; n];
+	let mut start: Vec<usize> = Vec::with_capacity(n);
+	for &node in tour {
+		if node < n && !seen[node] {
+			seen[node] = true;
+			start.push(node);
+		}
+	}
+	match start.iter().position(|&node| node == 0) {
+		Some(depot) => start.rotate_left(depot),
+		None => start.insert(0, 0),
+	}
+	if !allow_partial {
+		start.extend((1..n).filter(|&node| !seen[node]));
+	}
+
+	if instance.is_feasible(&start) {
+		return Some(start);
+	}
+	if start.len() != n {
+		return None;
+	}
+	let mut solution = Solution::from_tour(instance, start, "warm-start");
+	VND::with_standard_operators().improve(instance, &mut solution);
+	solution.feasible.then_some(solution.tour)
+}
+
+/// Connected components of the graph on nodes `0..n` formed by the selected
+/// edges, found with union-find. A complete tour yields a single component;
+/// any other component is a subtour.
+pub fn connected_components(n: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+	fn find(parent: &mut [usize], mut i: usize) -> usize {
+		while parent[i] != i {
+			parent[i] = parent[parent[i]];
+			i = parent[i];
+		}
+		i
+	}
+
+	let mut parent: Vec<usize> = (0..n).collect();
+	for &(i, j) in edges {
+		let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+		if a != b {
+			parent[a] = b;
+		}
+	}
+
+	let mut components: Vec<Vec<usize>> = Vec::new();
+	let mut index = vec![usize::MAX; n];
+	for i in 0..n {
+		let root = find(&mut parent, i);
+		if index[root] == usize::MAX {
+			index[root] = components.len();
+			components.push(Vec::new());
+		}
+		components[index[root]].push(i);
+	}
+	components
+}
+
 // When built with the `gurobi` feature, expose the real implementation
 #[cfg(feature = "gurobi")]
 mod gurobi;
@@ -10,6 +98,7 @@ pub use gurobi::*;
 #[cfg(not(feature = "gurobi"))]
 mod gurobi_stub {
 	use crate::instance::PDTSPInstance;
+	use crate::progress::{ProgressEvent, ProgressHandler};
 	use crate::solution::Solution;
 
 	#[derive(Debug, Clone)]
@@ -19,11 +108,13 @@ mod gurobi_stub {
 		pub threads: i32,
 		pub verbose: bool,
 		pub warm_start: Option<Vec<usize>>,
+		pub quadratic_breakpoints: usize,
+		pub profit_objective: bool,
 	}
 
 	impl Default for GurobiConfig {
 		fn default() -> Self {
-			GurobiConfig { time_limit: 3600.0, mip_gap: 1e-6, threads: 0, verbose: false, warm_start: None }
+			GurobiConfig { time_limit: 3600.0, mip_gap: 1e-6, threads: 0, verbose: false, warm_start: None, quadratic_breakpoints: 0, profit_objective: false }
 		}
 	}
 
@@ -38,11 +129,17 @@ mod gurobi_stub {
 		pub nodes_explored: i64,
 	}
 
-	pub struct GurobiSolver { pub config: GurobiConfig }
+	pub struct GurobiSolver { pub config: GurobiConfig, on_progress: Option<ProgressHandler> }
 
 	impl GurobiSolver {
-		pub fn new(config: GurobiConfig) -> Self { GurobiSolver { config } }
+		pub fn new(config: GurobiConfig) -> Self { GurobiSolver { config, on_progress: None } }
+		pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+			self.on_progress = Some(ProgressHandler::new(callback));
+		}
 		pub fn solve(&self, _instance: &PDTSPInstance) -> Result<ExactResult, String> {
+			crate::progress::notify(&self.on_progress, || {
+				ProgressEvent::new("Gurobi-Exact", 0, f64::INFINITY, 0.0).with_message("feature not enabled")
+			});
 			Err("Gurobi feature not enabled in this build".to_string())
 		}
 	}
@@ -50,3 +147,45 @@ mod gurobi_stub {
 
 #[cfg(not(feature = "gurobi"))]
 pub use gurobi_stub::*;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_connected_components() {
+		// A tour over all nodes is a single component
+		let tour = [(0, 2), (2, 1), (1, 3), (3, 0)];
+		assert_eq!(connected_components(4, &tour), vec![vec![0, 1, 2, 3]]);
+
+		// Two subtours
+		let split = [(0, 1), (1, 0), (2, 4), (4, 3), (3, 2)];
+		assert_eq!(connected_components(5, &split), vec![vec![0, 1], vec![2, 3, 4]]);
+	}
+
+	#[test]
+	fn test_prepare_warm_start() {
+		use crate::instance::{DistanceMatrix, DistanceMode, Node};
+
+		let nodes = vec![
+			Node::new(0, 0.0, 0.0, 0, 0),
+			Node::new(1, 1.0, 0.0, 2, 0),
+			Node::new(2, 1.0, 1.0, -2, 0),
+			Node::new(3, 0.0, 1.0, 0, 0),
+		];
+		let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+		let instance = PDTSPInstance {
+			alpha: 0.0,
+			beta: 0.0,
+			..PDTSPInstance::from_parts("warm", 2, nodes, distance_matrix)
+		};
+
+		// Rotated, with a trailing depot, a duplicate and a missing node
+		assert_eq!(prepare_warm_start(&instance, &[1, 2, 0, 2, 0], false), Some(vec![0, 1, 2, 3]));
+		assert_eq!(prepare_warm_start(&instance, &[0, 1, 2], true), Some(vec![0, 1, 2]));
+		// Delivering first is repaired on complete tours only
+		let repaired = prepare_warm_start(&instance, &[0, 2, 1, 3], false).unwrap();
+		assert!(instance.is_feasible(&repaired));
+		assert_eq!(prepare_warm_start(&instance, &[0, 2, 1], true), None);
+	}
+}
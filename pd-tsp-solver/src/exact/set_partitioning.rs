@@ -0,0 +1,184 @@
+//! Exact set partitioning by branch and bound.
+//!
+//! Select columns (routes) covering every element (customer) exactly once at
+//! minimum total cost. This is the master problem of route based
+//! matheuristics, whose columns are routes collected from heuristic runs.
+//! The search branches on the uncovered element with the fewest compatible
+//! columns and tries them cheapest first. A node is pruned when its cost plus
+//! a lower bound on the rest reaches the incumbent: every uncovered element is
+//! charged the smallest share `cost / size` of a column covering it, and the
+//! shares of a column add up to its cost.
+
+use crate::heuristics::stopping::Deadline;
+
+/// Default limit on the number of branch and bound nodes
+pub const DEFAULT_MAX_NODES: usize = 1_000_000;
+
+/// Best partition found by the search
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetPartitioningResult {
+    /// Indices of the selected columns
+    pub columns: Vec<usize>,
+    /// Total cost of the selected columns
+    pub cost: f64,
+    /// Whether the search completed, proving the partition optimal
+    pub optimal: bool,
+    /// Branch and bound nodes explored
+    pub nodes: usize,
+}
+
+struct Search<'a> {
+    columns: &'a [Vec<usize>],
+    costs: &'a [f64],
+    /// Columns covering each element, cheapest first
+    covering: Vec<Vec<usize>>,
+    /// Smallest per-element share of a column covering each element
+    share: Vec<f64>,
+    covered: Vec<bool>,
+    chosen: Vec<usize>,
+    best: Option<(Vec<usize>, f64)>,
+    nodes: usize,
+    max_nodes: usize,
+    deadline: Deadline,
+    aborted: bool,
+}
+
+impl Search<'_> {
+    fn compatible(&self, column: usize) -> bool {
+        self.columns[column].iter().all(|&e| !self.covered[e])
+    }
+
+    fn branch(&mut self, cost: f64, bound: f64) {
+        self.nodes += 1;
+        if self.nodes > self.max_nodes || (self.nodes.is_multiple_of(1024) && self.deadline.expired()) {
+            self.aborted = true;
+        }
+        if self.aborted {
+            return;
+        }
+        if self.best.as_ref().is_some_and(|(_, best)| cost + bound >= best - 1e-9) {
+            return;
+        }
+
+        // Uncovered element with the fewest compatible columns
+        let mut branching: Option<(usize, usize)> = None;
+        for e in (0..self.covered.len()).filter(|&e| !self.covered[e]) {
+            let count = self.covering[e].iter().filter(|&&c| self.compatible(c)).count();
+            if branching.is_none_or(|(_, fewest)| count < fewest) {
+                branching = Some((e, count));
+            }
+            if count == 0 {
+                return;
+            }
+        }
+        let Some((element, _)) = branching else {
+            self.best = Some((self.chosen.clone(), cost));
+            return;
+        };
+
+        let candidates: Vec<usize> = self.covering[element].iter().copied().filter(|&c| self.compatible(c)).collect();
+        for column in candidates {
+            let released: f64 = self.columns[column].iter().map(|&e| self.share[e]).sum();
+            self.columns[column].iter().for_each(|&e| self.covered[e] = true);
+            self.chosen.push(column);
+            self.branch(cost + self.costs[column], bound - released);
+            self.chosen.pop();
+            self.columns[column].iter().for_each(|&e| self.covered[e] = false);
+            if self.aborted {
+                return;
+            }
+        }
+    }
+}
+
+/// Cheapest selection of `columns` (sets of elements below `num_elements`,
+/// with their `costs`) covering every element exactly once, or `None` if no
+/// partition was found. Columns that are empty, repeat an element or name an
+/// unknown one are ignored. The search stops after `max_nodes` nodes or at
+/// the deadline, returning the best partition found so far.
+pub fn solve_set_partitioning(
+    columns: &[Vec<usize>],
+    costs: &[f64],
+    num_elements: usize,
+    max_nodes: usize,
+    deadline: Deadline,
+) -> Option<SetPartitioningResult> {
+    let mut covering = vec![Vec::new(); num_elements];
+    let mut share = vec![f64::INFINITY; num_elements];
+    for (c, column) in columns.iter().enumerate() {
+        let mut elements = column.clone();
+        elements.sort_unstable();
+        elements.dedup();
+        if column.is_empty() || elements.len() != column.len() || elements.iter().any(|&e| e >= num_elements) {
+            continue;
+        }
+        for &e in column {
+            covering[e].push(c);
+            share[e] = share[e].min(costs[c] / column.len() as f64);
+        }
+    }
+    for list in &mut covering {
+        list.sort_by(|&a, &b| costs[a].total_cmp(&costs[b]));
+    }
+
+    let mut search = Search {
+        columns,
+        costs,
+        covering,
+        covered: vec![false; num_elements],
+        chosen: Vec::new(),
+        best: None,
+        nodes: 0,
+        max_nodes,
+        deadline,
+        aborted: false,
+        share,
+    };
+    let bound = search.share.iter().sum();
+    if bound == f64::INFINITY {
+        // Some element is covered by no column
+        return None;
+    }
+    search.branch(0.0, bound);
+
+    let (mut selected, cost) = search.best?;
+    selected.sort_unstable();
+    Some(SetPartitioningResult {
+        columns: selected,
+        cost,
+        optimal: !search.aborted,
+        nodes: search.nodes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_partitioning() {
+        let columns = vec![
+            vec![0, 1, 2, 3],
+            vec![0, 1],
+            vec![2, 3],
+            vec![0, 2],
+            vec![1, 3],
+            vec![1, 2],
+            vec![0, 0],
+            vec![4],
+        ];
+        let costs = [10.0, 4.0, 5.0, 3.0, 3.0, 1.0, 0.0, 0.0];
+        let result = solve_set_partitioning(&columns, &costs, 4, DEFAULT_MAX_NODES, Deadline::none()).unwrap();
+        assert_eq!(result.columns, vec![3, 4]);
+        assert_eq!(result.cost, 6.0);
+        assert!(result.optimal);
+
+        // The cheap pair {1, 2} leaves {0} and {3}, which no column covers
+        assert!(solve_set_partitioning(&columns[5..], &costs[5..], 4, DEFAULT_MAX_NODES, Deadline::none()).is_none());
+
+        // A node limit returns the first partition found, unproven
+        let limited = solve_set_partitioning(&columns, &costs, 4, 3, Deadline::none()).unwrap();
+        assert!(!limited.optimal);
+        assert!(limited.cost >= 6.0);
+    }
+}
@@ -0,0 +1,267 @@
+//! Exact reoptimization of part of a tour.
+//!
+//! The nodes at a set of *free* positions are taken out of the tour and put
+//! back optimally. The other nodes of the segment spanned by the free
+//! positions keep their relative order, and the tour outside the segment is
+//! fixed. This is the subproblem of local branching style matheuristics: a
+//! window of consecutive free positions is a Held–Karp path between two fixed
+//! endpoints, and scattered free positions are reinserted among the fixed
+//! nodes of their segment.
+//!
+//! The subproblem is solved by dynamic programming over (fixed nodes placed,
+//! set of free nodes placed, last node). As in the Held–Karp solver, the load
+//! after a state only depends on the nodes it has placed, so the capacity is
+//! checked per state and load-dependent cost functions stay exact. This only
+//! holds without a depot visit inside the segment, so such segments are
+//! refused; depot visits before the segment reset the entry load. Other
+//! constraints (commodities, route length) are left to the caller.
+
+use super::dynamic_programming::{arc_cost, travel_cost};
+use crate::instance::PDTSPInstance;
+
+/// Largest number of free positions of a subproblem
+pub const MAX_FREE_NODES: usize = 12;
+
+/// Largest number of DP states of a subproblem; wider segments are refused
+pub const MAX_STATES: usize = 1 << 22;
+
+/// Best tour obtained by reinserting the nodes at the `free` positions of
+/// `tour` into their segment, or `None` if no arrangement respects the
+/// capacity. The depot at position 0 cannot be free.
+///
+/// Returns an error if there are more than [`MAX_FREE_NODES`] free positions,
+/// a position is invalid, the segment visits the depot or it needs more than
+/// [`MAX_STATES`] states.
+pub fn reoptimize_positions(instance: &PDTSPInstance, tour: &[usize], free: &[usize]) -> Result<Option<Vec<usize>>, String> {
+    let mut free = free.to_vec();
+    free.sort_unstable();
+    free.dedup();
+    let (Some(&first), Some(&last)) = (free.first(), free.last()) else {
+        return Ok(Some(tour.to_vec()));
+    };
+    if first == 0 || last >= tour.len() {
+        return Err(format!("Free positions must lie in 1..{}", tour.len()));
+    }
+    let k = free.len();
+    if k > MAX_FREE_NODES {
+        return Err(format!("{} free positions, the subproblem is limited to {}", k, MAX_FREE_NODES));
+    }
+
+    if tour[first..=last].contains(&0) {
+        return Err("The segment of the free positions visits the depot".to_string());
+    }
+
+    let free_nodes: Vec<usize> = free.iter().map(|&p| tour[p]).collect();
+    let fixed: Vec<usize> = (first..=last)
+        .filter(|p| free.binary_search(p).is_err())
+        .map(|p| tour[p])
+        .collect();
+    let m = fixed.len();
+    let num_masks = 1usize << k;
+    let width = k + 1;
+    let num_states = (m + 1) * num_masks * width;
+    if num_states > MAX_STATES {
+        return Err(format!("The segment needs {} states, more than {}", num_states, MAX_STATES));
+    }
+
+    let before = tour[first - 1];
+    let after = tour.get(last + 1).copied();
+    let demand = |node: usize| instance.nodes[node].demand;
+    // The vehicle is emptied at every intermediate depot visit
+    let entry_load = match tour[1..first].iter().rposition(|&node| node == 0) {
+        Some(visit) => tour[visit + 2..first].iter().map(|&node| demand(node)).sum::<i32>(),
+        None => instance.starting_load() + tour[1..first].iter().map(|&node| demand(node)).sum::<i32>(),
+    };
+
+    // Load after placing i fixed nodes and the free nodes of a mask
+    let mut fixed_load = vec![entry_load; m + 1];
+    for i in 0..m {
+        fixed_load[i + 1] = fixed_load[i] + demand(fixed[i]);
+    }
+    let mut free_load = vec![0i32; num_masks];
+    for mask in 1..num_masks {
+        let bit = mask.trailing_zeros() as usize;
+        free_load[mask] = free_load[mask & (mask - 1)] + demand(free_nodes[bit]);
+    }
+    let load = |i: usize, mask: usize| fixed_load[i] + free_load[mask];
+    let feasible = |i: usize, mask: usize| (0..=instance.capacity).contains(&load(i, mask));
+
+    // The last node is free node `l` for l < k, else the last fixed node
+    // placed (the node before the segment when none is)
+    let node = |i: usize, l: usize| match l {
+        l if l < k => free_nodes[l],
+        _ if i == 0 => before,
+        _ => fixed[i - 1],
+    };
+    let index = |i: usize, mask: usize, l: usize| (i * num_masks + mask) * width + l;
+    let mut cost = vec![f64::INFINITY; num_states];
+    let mut parent = vec![usize::MAX; num_states];
+    cost[index(0, 0, k)] = 0.0;
+
+    // Every move places one more node, so (i, mask) increases
+    for i in 0..=m {
+        for mask in 0..num_masks {
+            if !feasible(i, mask) {
+                continue;
+            }
+            for l in 0..width {
+                let state = index(i, mask, l);
+                let current = cost[state];
+                if current == f64::INFINITY {
+                    continue;
+                }
+                let from = node(i, l);
+                let departure_load = load(i, mask);
+                let mut relax = |to: usize, next_state: usize| {
+                    let candidate = current + arc_cost(instance, from, to, departure_load);
+                    if candidate < cost[next_state] {
+                        cost[next_state] = candidate;
+                        parent[next_state] = state;
+                    }
+                };
+                if let Some(&next) = fixed.get(i) {
+                    if feasible(i + 1, mask) {
+                        relax(next, index(i + 1, mask, k));
+                    }
+                }
+                for (f, &next) in free_nodes.iter().enumerate() {
+                    if mask & (1 << f) == 0 && feasible(i, mask | (1 << f)) {
+                        relax(next, index(i, mask | (1 << f), f));
+                    }
+                }
+            }
+        }
+    }
+
+    let full = num_masks - 1;
+    let exit_load = load(m, full);
+    // The arc out of the segment, into the next node or back to the depot
+    let exit = |from: usize| match after {
+        Some(after) => travel_cost(instance, from, after, exit_load),
+        None => arc_cost(instance, from, 0, exit_load),
+    };
+    let best = (0..width)
+        .map(|l| index(m, full, l))
+        .filter(|&state| cost[state] < f64::INFINITY)
+        .min_by(|&a, &b| {
+            let ca = cost[a] + exit(node(m, a % width));
+            let cb = cost[b] + exit(node(m, b % width));
+            ca.total_cmp(&cb)
+        });
+    let Some(mut state) = best else {
+        return Ok(None);
+    };
+
+    // Walk parents back to the empty state
+    let mut segment = Vec::with_capacity(m + k);
+    while parent[state] != usize::MAX {
+        let l = state % width;
+        let i = state / width / num_masks;
+        segment.push(node(i, l));
+        state = parent[state];
+    }
+    segment.reverse();
+
+    let mut result = Vec::with_capacity(tour.len());
+    result.extend_from_slice(&tour[..first]);
+    result.extend(segment);
+    result.extend_from_slice(&tour[last + 1..]);
+    Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact::dynamic_programming;
+    use crate::instance::CostFunction;
+
+    const TSP: &str = "NAME: sub\nDIMENSION: 8\nCAPACITY: 6\nNODE_COORD_SECTION\n1 0 0\n2 3 0\n3 6 1\n4 5 5\n5 1 6\n6 -2 3\n7 2 2\n8 4 -3\nDEMAND_SECTION\n1 0\n2 4\n3 -3\n4 5\n5 -4\n6 -2\n7 3\n8 -3\nEOF\n";
+
+    #[test]
+    fn test_all_free_matches_held_karp() {
+        for cost_function in [CostFunction::Distance, CostFunction::Quadratic] {
+            let mut instance = PDTSPInstance::from_tsplib_str(TSP).unwrap();
+            instance.cost_function = cost_function;
+            instance.alpha = 0.1;
+            instance.beta = 0.05;
+            let optimum = dynamic_programming::solve(&instance).unwrap();
+
+            let tour: Vec<usize> = (0..instance.dimension).collect();
+            let free: Vec<usize> = (1..instance.dimension).collect();
+            let best = reoptimize_positions(&instance, &tour, &free).unwrap().unwrap();
+            assert!(instance.is_feasible(&best));
+            assert!((instance.tour_cost(&best) - optimum.cost).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_window_and_scattered_positions() {
+        let instance = PDTSPInstance::from_tsplib_str(TSP).unwrap();
+        let tour = dynamic_programming::solve(&instance).unwrap().tour;
+        let cost = instance.tour_cost(&tour);
+
+        // Reoptimizing part of an optimal tour gives an equally good tour
+        // and leaves the fixed part untouched
+        let window = reoptimize_positions(&instance, &tour, &[2, 3, 4]).unwrap().unwrap();
+        assert!((instance.tour_cost(&window) - cost).abs() < 1e-9);
+        assert_eq!((&window[..2], &window[5..]), (&tour[..2], &tour[5..]));
+
+        // Scattered positions keep the order of the fixed nodes between them
+        let scattered = reoptimize_positions(&instance, &tour, &[1, 6]).unwrap().unwrap();
+        assert!((instance.tour_cost(&scattered) - cost).abs() < 1e-9);
+        let fixed = |t: &[usize]| -> Vec<usize> { t.iter().copied().filter(|&n| n != tour[1] && n != tour[6]).collect() };
+        assert_eq!(fixed(&scattered), fixed(&tour));
+
+        assert!(reoptimize_positions(&instance, &tour, &[0, 1]).is_err());
+        assert!(reoptimize_positions(&instance, &tour, &(1..14).collect::<Vec<_>>()).is_err());
+    }
+
+    fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+        if items.len() <= 1 {
+            return vec![items.to_vec()];
+        }
+        (0..items.len()).flat_map(|i| {
+            let mut rest = items.to_vec();
+            let item = rest.remove(i);
+            permutations(&rest).into_iter().map(move |mut p| {
+                p.insert(0, item);
+                p
+            })
+        }).collect()
+    }
+
+    #[test]
+    fn test_segment_after_depot_visit() {
+        // Node 7 delivers less than node 6 picks up, so the load on reaching
+        // the depot visit is dropped there
+        let mut instance = PDTSPInstance::from_tsplib_str(&TSP.replace("8 -3\nEOF", "8 -1\nEOF")).unwrap();
+        let tour = vec![0, 6, 7, 0, 1, 2, 3, 4, 5];
+        for (cost_function, open_tour) in [(CostFunction::Distance, false), (CostFunction::Quadratic, false), (CostFunction::Quadratic, true)] {
+            instance.cost_function = cost_function;
+            instance.alpha = 0.1;
+            instance.beta = 0.05;
+            instance.open_tour = open_tour;
+            for free in [vec![4, 5, 6, 7, 8], vec![5, 6, 7]] {
+                // Every order of the window, by enumeration
+                let nodes: Vec<usize> = free.iter().map(|&p| tour[p]).collect();
+                let best = permutations(&nodes).into_iter().map(|order| {
+                    let mut t = tour.clone();
+                    for (&p, &node) in free.iter().zip(&order) {
+                        t[p] = node;
+                    }
+                    t
+                }).filter(|t| instance.is_feasible(t))
+                    .map(|t| instance.tour_cost(&t))
+                    .min_by(f64::total_cmp);
+                let found = reoptimize_positions(&instance, &tour, &free).unwrap();
+                assert_eq!(found.is_some(), best.is_some());
+                if let (Some(found), Some(best)) = (found, best) {
+                    assert!(instance.is_feasible(&found));
+                    assert!((instance.tour_cost(&found) - best).abs() < 1e-9);
+                }
+            }
+            // Segments through the depot visit are refused
+            assert!(reoptimize_positions(&instance, &tour, &[2, 4]).is_err());
+        }
+    }
+}
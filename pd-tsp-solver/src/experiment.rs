@@ -0,0 +1,923 @@
+//! Declarative experiment campaigns.
+//!
+//! An `ExperimentSpec` (TOML) lists the instances, algorithms, seeds, cost
+//! functions and time budgets of a campaign. `Experiment::run` executes every
+//! combination and `write_artifacts` regenerates the full artifact set of the
+//! report from the runs: raw results (CSV/JSON), statistics, LaTeX tables,
+//! performance profiles, best-tour figures and a markdown summary.
+//!
+//! ```toml
+//! name = "final-report"
+//! output = "report/experiments"
+//! instances = ["../benchmark_n20/*.tsp"]
+//! algorithms = ["greedy", "vnd", "sa", "ils", "ga"]
+//! seeds = [0, 1, 2, 3, 4]
+//! cost_functions = ["distance", "quadratic"]
+//! budgets = [10.0]
+//! ```
+//!
+//! Relative paths are resolved against the directory of the spec file.
+
+use crate::benchmark::{performance_profile_csv, performance_ratios};
+use crate::config::SolverConfig;
+use crate::exact::{dynamic_programming, GurobiConfig, GurobiSolver};
+use crate::heuristics::aco::{ACOConfig, AntColonyOptimization, MaxMinAntSystem, PheromoneStrategy};
+use crate::heuristics::construction::*;
+use crate::heuristics::genetic::{GAConfig, GeneticAlgorithm, MemeticAlgorithm};
+use crate::heuristics::hybrid::TimeBudget;
+use crate::heuristics::local_search::*;
+use crate::heuristics::matheuristic::WindowReoptimization;
+use crate::heuristics::popmusic::Popmusic;
+use crate::heuristics::profit_density::ProfitDensityHeuristic;
+use crate::heuristics::rng::RngFactory;
+use crate::heuristics::stopping::Deadline;
+use crate::heuristics::vns::GeneralVNS;
+use crate::instance::{CostFunction, PDTSPInstance};
+use crate::run_info::RunInfo;
+use crate::solution::Solution;
+use crate::visualization::Visualizer;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use web_time::Instant;
+
+/// Algorithm names accepted in a spec (same names as the `solve` command)
+pub const ALGORITHMS: &[&str] = &[
+    "nn", "greedy", "savings", "sweep", "regret", "grasp", "cluster-first",
+    "christofides", "multi-start", "profit-density", "two-opt", "vnd", "ejection-chain", "window-reopt", "popmusic", "sa",
+    "threshold-accepting", "record-to-record", "tabu", "ils", "vns", "ga", "memetic", "aco", "mmas", "rank-as", "elitist-as", "hybrid", "exact", "dp",
+];
+
+/// Declarative description of an experiment campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentSpec {
+    /// Campaign name, used in titles and table labels
+    pub name: String,
+    /// Output directory of the artifacts
+    #[serde(default = "default_output")]
+    pub output: PathBuf,
+    /// Instance files or glob patterns
+    pub instances: Vec<String>,
+    /// Algorithm names (see `ALGORITHMS`)
+    pub algorithms: Vec<String>,
+    /// Seeds of the repeated runs
+    #[serde(default = "default_seeds")]
+    pub seeds: Vec<u64>,
+    /// Cost functions: "distance", "quadratic" or "linear-load"
+    #[serde(default = "default_cost_functions")]
+    pub cost_functions: Vec<String>,
+    /// Linear load weight
+    #[serde(default = "default_alpha")]
+    pub alpha: f64,
+    /// Quadratic load weight
+    #[serde(default)]
+    pub beta: f64,
+    /// Time budgets per run in seconds; each budget is a separate solver
+    #[serde(default = "default_budgets")]
+    pub budgets: Vec<f64>,
+    /// Maximum random profit per node (0 keeps the instance profits)
+    #[serde(default)]
+    pub max_profit: i32,
+    /// Seed of the random profits, shared by all runs
+    #[serde(default = "default_profit_seed")]
+    pub profit_seed: u64,
+    /// Directory of the spec file, against which relative paths are resolved
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+fn default_output() -> PathBuf {
+    PathBuf::from("experiment")
+}
+
+fn default_seeds() -> Vec<u64> {
+    vec![0]
+}
+
+fn default_cost_functions() -> Vec<String> {
+    vec!["distance".to_string()]
+}
+
+fn default_alpha() -> f64 {
+    0.1
+}
+
+fn default_budgets() -> Vec<f64> {
+    vec![60.0]
+}
+
+fn default_profit_seed() -> u64 {
+    42
+}
+
+/// Parse a cost function name as written in a spec
+pub fn parse_cost_function(name: &str) -> Result<CostFunction, String> {
+    match name.to_ascii_lowercase().replace('_', "-").as_str() {
+        "distance" => Ok(CostFunction::Distance),
+        "quadratic" => Ok(CostFunction::Quadratic),
+        "linear-load" => Ok(CostFunction::LinearLoad),
+        "duration" => Ok(CostFunction::Duration),
+        _ => Err(format!("Unknown cost function '{}'", name)),
+    }
+}
+
+impl ExperimentSpec {
+    /// Parse and validate a spec from TOML text
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let spec: ExperimentSpec = toml::from_str(text)
+            .map_err(|e| format!("Invalid experiment spec: {}", e))?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Load a spec; relative paths in it are resolved against its directory
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {:?}: {}", path.as_ref(), e))?;
+        let mut spec = Self::from_toml(&text)?;
+        spec.base_dir = path.as_ref().parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(spec)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.instances.is_empty() || self.algorithms.is_empty() || self.seeds.is_empty() {
+            return Err("Spec needs at least one instance, algorithm and seed".to_string());
+        }
+        if self.cost_functions.is_empty() || self.budgets.is_empty() {
+            return Err("Spec needs at least one cost function and budget".to_string());
+        }
+        for algorithm in &self.algorithms {
+            if !ALGORITHMS.contains(&algorithm.as_str()) {
+                return Err(format!("Unknown algorithm '{}' (expected one of {})", algorithm, ALGORITHMS.join(", ")));
+            }
+        }
+        for name in &self.cost_functions {
+            parse_cost_function(name)?;
+        }
+        if let Some(b) = self.budgets.iter().find(|b| b.is_nan() || **b <= 0.0) {
+            return Err(format!("Budgets must be positive, got {}", b));
+        }
+        Ok(())
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.base_dir.join(path)
+        }
+    }
+
+    /// Output directory, resolved against the spec directory
+    pub fn output_dir(&self) -> PathBuf {
+        self.resolve(&self.output)
+    }
+
+    /// Expand the instance patterns into a sorted list of files.
+    /// A pattern that matches nothing is an error, to catch typos.
+    pub fn instance_paths(&self) -> Result<Vec<PathBuf>, String> {
+        expand_instance_patterns(&self.base_dir, &self.instances)
+    }
+
+    /// Solver label of an algorithm under a budget; the budget is only part of
+    /// the label when the spec compares several budgets
+    pub fn solver_label(&self, algorithm: &str, budget: f64) -> String {
+        if self.budgets.len() > 1 {
+            format!("{}@{}s", algorithm, budget)
+        } else {
+            algorithm.to_string()
+        }
+    }
+}
+
+/// Expand instance files or glob patterns, relative ones resolved against
+/// `base_dir`, into a sorted list of files. A pattern that matches nothing is
+/// an error, to catch typos.
+pub fn expand_instance_patterns(base_dir: &Path, patterns: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        let full = base_dir.join(pattern);
+        let matches: Vec<PathBuf> = glob::glob(&full.to_string_lossy())
+            .map_err(|e| format!("Invalid instance pattern '{}': {}", pattern, e))?
+            .filter_map(Result::ok)
+            .filter(|p| p.is_file())
+            .collect();
+        if matches.is_empty() {
+            return Err(format!("Instance pattern '{}' matches no file", pattern));
+        }
+        paths.extend(matches);
+    }
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Run an algorithm by name with the same settings as the `solve` command
+pub fn run_algorithm(name: &str, instance: &PDTSPInstance, seed: u64, time_limit: f64) -> Result<Solution, String> {
+    run_algorithm_with_config(name, instance, seed, time_limit, &SolverConfig::default())
+}
+
+/// Run an algorithm by name, with parameter overrides for the tunable ones
+pub fn run_algorithm_with_config(
+    name: &str,
+    instance: &PDTSPInstance,
+    seed: u64,
+    time_limit: f64,
+    params: &SolverConfig,
+) -> Result<Solution, String> {
+    let rng = RngFactory::new(seed);
+    let local_search = |improve: &dyn Fn(&mut Solution) -> bool| {
+        let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
+        let mut sol = multi.construct(instance);
+        improve(&mut sol);
+        sol
+    };
+
+    let solution = match name {
+        "nn" => NearestNeighborHeuristic::new().construct(instance),
+        "greedy" => GreedyInsertionHeuristic::new().construct(instance),
+        "savings" => SavingsHeuristic::new().construct(instance),
+        "sweep" => SweepHeuristic::rotated().construct(instance),
+        "regret" => RegretInsertionHeuristic::new(3).construct(instance),
+        "grasp" => GraspConstruction::with_params(20, rng.derive("grasp")).construct(instance),
+        "cluster-first" => ClusterFirstHeuristic::new().construct(instance),
+        "christofides" => ChristofidesHeuristic::new().construct(instance),
+        "multi-start" => MultiStartConstruction::with_all_heuristics_seeded(&rng).construct(instance),
+        "profit-density" => ProfitDensityHeuristic::new().construct(instance),
+        "two-opt" => local_search(&|sol| TwoOptSearch::new().improve(instance, sol)),
+        "vnd" => local_search(&|sol| {
+            let mut vnd = VND::with_standard_operators().with_deadline(Deadline::after(time_limit));
+            params.apply_vnd(&mut vnd);
+            vnd.improve(instance, sol)
+        }),
+        "ejection-chain" => local_search(&|sol| {
+            let mut vnd = VND::with_standard_operators().with_deadline(Deadline::after(time_limit));
+            params.apply_vnd(&mut vnd);
+            vnd.add_operator(EjectionChainSearch::new());
+            vnd.improve(instance, sol)
+        }),
+        "window-reopt" => local_search(&|sol| {
+            let deadline = Deadline::after(time_limit);
+            let mut vnd = VND::with_standard_operators().with_deadline(deadline);
+            params.apply_vnd(&mut vnd);
+            let improved = vnd.improve(instance, sol);
+            WindowReoptimization::new().with_deadline(deadline).improve(instance, sol) || improved
+        }),
+        "popmusic" => local_search(&|sol| {
+            Popmusic::new().with_deadline(Deadline::after(time_limit)).improve(instance, sol)
+        }),
+        "sa" => local_search(&|sol| {
+            let mut sa = SimulatedAnnealing::new();
+            params.apply_sa(&mut sa);
+            sa.seed = rng.derive("sa");
+            sa.improve(instance, sol)
+        }),
+        "threshold-accepting" => local_search(&|sol| {
+            let mut ta = ThresholdAccepting::new();
+            ta.seed = rng.derive("threshold-accepting");
+            ta.deadline = Deadline::after(time_limit);
+            ta.improve(instance, sol)
+        }),
+        "record-to-record" => local_search(&|sol| {
+            let mut rrt = RecordToRecord::new();
+            rrt.seed = rng.derive("record-to-record");
+            rrt.deadline = Deadline::after(time_limit);
+            rrt.improve(instance, sol)
+        }),
+        "tabu" => local_search(&|sol| {
+            let mut ts = TabuSearch::new();
+            params.apply_tabu(&mut ts);
+            ts.seed = rng.derive("tabu");
+            ts.improve(instance, sol)
+        }),
+        "ils" => local_search(&|sol| {
+            let mut ils = IteratedLocalSearch::new();
+            params.apply_ils(&mut ils);
+            ils.seed = rng.derive("ils");
+            ils.deadline = Deadline::after(time_limit);
+            ils.improve(instance, sol)
+        }),
+        "vns" => local_search(&|sol| {
+            let mut vns = GeneralVNS::new();
+            params.apply_vns(&mut vns);
+            vns.seed = rng.derive("vns");
+            vns.time_limit = time_limit;
+            vns.improve(instance, sol)
+        }),
+        "ga" => {
+            let mut config = GAConfig {
+                population_size: 50,
+                max_generations: 200,
+                ..Default::default()
+            };
+            params.apply_ga(&mut config);
+            config.seed = rng.derive("ga");
+            config.time_limit = time_limit;
+            GeneticAlgorithm::new(instance.clone(), config).run()
+        }
+        "memetic" => {
+            let mut config = GAConfig::default();
+            params.apply_ga(&mut config);
+            config.seed = rng.derive("memetic");
+            config.time_limit = time_limit;
+            MemeticAlgorithm::with_config(instance.clone(), config).run()
+        }
+        "aco" | "mmas" | "rank-as" | "elitist-as" => {
+            let mut config = ACOConfig { max_iterations: 200, ..Default::default() };
+            params.apply_aco(&mut config);
+            config.seed = rng.derive("aco");
+            config.time_limit = time_limit;
+            match name {
+                "mmas" => MaxMinAntSystem::new(instance.clone(), config).run(),
+                "rank-as" => {
+                    config.strategy = PheromoneStrategy::RankBased;
+                    AntColonyOptimization::new(instance.clone(), config).run()
+                }
+                "elitist-as" => {
+                    config.strategy = PheromoneStrategy::Elitist;
+                    AntColonyOptimization::new(instance.clone(), config).run()
+                }
+                _ => AntColonyOptimization::new(instance.clone(), config).run(),
+            }
+        }
+        "hybrid" => {
+            let mut solution = params.pipeline(TimeBudget::default()).solve(instance, time_limit, seed).0;
+            solution.algorithm = "Hybrid".to_string();
+            solution
+        }
+        "exact" => {
+            let config = GurobiConfig { time_limit, ..Default::default() };
+            GurobiSolver::new(config).solve(instance)?.solution
+        }
+        "dp" => dynamic_programming::solve(instance)?,
+        _ => return Err(format!("Unknown algorithm '{}'", name)),
+    };
+
+    Ok(solution)
+}
+
+/// One run of a campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentRecord {
+    /// Instance name
+    pub instance: String,
+    /// Instance dimension
+    pub dimension: usize,
+    /// Cost function name as given in the spec
+    pub cost_function: String,
+    /// Solver label (algorithm, plus budget when several are compared)
+    pub algorithm: String,
+    /// Time budget in seconds
+    pub budget: f64,
+    /// Run seed
+    pub seed: u64,
+    /// Solution cost (infinite if the run failed)
+    pub cost: f64,
+    /// Whether the solution is feasible
+    pub feasible: bool,
+    /// Wall-clock time in seconds
+    pub time: f64,
+    /// Total profit collected
+    pub profit: i32,
+    /// Gap to the best feasible cost found on the problem, in percent
+    pub gap_to_best: Option<f64>,
+    /// Error message if the algorithm failed
+    pub error: Option<String>,
+}
+
+impl ExperimentRecord {
+    /// Key of the problem (instance and cost function) the run belongs to
+    pub fn problem(&self) -> String {
+        format!("{}/{}", self.instance, self.cost_function)
+    }
+}
+
+/// Aggregated runs of one solver on one problem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentStatistics {
+    /// Instance name
+    pub instance: String,
+    /// Instance dimension
+    pub dimension: usize,
+    /// Cost function name
+    pub cost_function: String,
+    /// Solver label
+    pub algorithm: String,
+    /// Number of runs
+    pub runs: usize,
+    /// Number of feasible runs
+    pub feasible: usize,
+    /// Mean feasible cost
+    pub mean_cost: Option<f64>,
+    /// Standard deviation of the feasible costs
+    pub std_cost: Option<f64>,
+    /// Best feasible cost
+    pub best_cost: Option<f64>,
+    /// Mean gap to the best cost found on the problem, in percent
+    pub mean_gap: Option<f64>,
+    /// Mean wall-clock time in seconds
+    pub mean_time: f64,
+}
+
+/// Overall performance of one solver across the problems of a cost function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolverSummary {
+    /// Cost function name
+    pub cost_function: String,
+    /// Solver label
+    pub algorithm: String,
+    /// Fraction of feasible runs
+    pub feasible_rate: f64,
+    /// Mean gap to the best cost found, over the feasible runs, in percent
+    pub mean_gap: Option<f64>,
+    /// Number of problems on which the solver has the best mean cost
+    pub wins: usize,
+    /// Mean wall-clock time in seconds
+    pub mean_time: f64,
+}
+
+/// Best solution found on one problem, kept for the figures
+#[derive(Debug, Clone)]
+struct ProblemBest {
+    instance: PDTSPInstance,
+    cost_function: String,
+    algorithm: String,
+    solution: Solution,
+}
+
+/// Runs a spec and writes its artifacts
+pub struct Experiment {
+    spec: ExperimentSpec,
+    records: Vec<ExperimentRecord>,
+    best: Vec<ProblemBest>,
+    run_info: RunInfo,
+}
+
+impl Experiment {
+    pub fn new(spec: ExperimentSpec) -> Self {
+        let config = toml::to_string(&spec).unwrap_or_else(|_| format!("{:?}", spec));
+        let run_info = RunInfo::capture(spec.seeds[0], &config);
+        Experiment {
+            spec,
+            records: Vec::new(),
+            best: Vec::new(),
+            run_info,
+        }
+    }
+
+    /// Execute every (instance, cost function, algorithm, budget, seed) run
+    pub fn run(&mut self) -> Result<(), String> {
+        let paths = self.spec.instance_paths()?;
+        let total = paths.len() * self.spec.cost_functions.len() * self.spec.algorithms.len()
+            * self.spec.budgets.len() * self.spec.seeds.len();
+        let mut done = 0;
+
+        for path in &paths {
+            let mut base = PDTSPInstance::from_file(path)?;
+            // The file stem is a shorter label than the NAME field for tables and figures
+            if let Some(stem) = path.file_stem() {
+                base.name = stem.to_string_lossy().to_string();
+            }
+            if self.spec.max_profit > 0 {
+                base.assign_random_profits(self.spec.profit_seed, self.spec.max_profit);
+            }
+
+            for cost_name in &self.spec.cost_functions {
+                let mut instance = base.clone();
+                instance.cost_function = parse_cost_function(cost_name)?;
+                instance.alpha = self.spec.alpha;
+                instance.beta = self.spec.beta;
+                let mut best: Option<ProblemBest> = None;
+
+                for algorithm in &self.spec.algorithms {
+                    for &budget in &self.spec.budgets {
+                        let label = self.spec.solver_label(algorithm, budget);
+                        for &seed in &self.spec.seeds {
+                            done += 1;
+                            let start = Instant::now();
+                            let result = run_algorithm(algorithm, &instance, seed, budget);
+                            let time = start.elapsed().as_secs_f64();
+
+                            let record = match &result {
+                                Ok(sol) => ExperimentRecord {
+                                    instance: instance.name.clone(),
+                                    dimension: instance.dimension,
+                                    cost_function: cost_name.clone(),
+                                    algorithm: label.clone(),
+                                    budget,
+                                    seed,
+                                    cost: sol.cost,
+                                    feasible: sol.feasible,
+                                    time,
+                                    profit: sol.total_profit,
+                                    gap_to_best: None,
+                                    error: None,
+                                },
+                                Err(e) => ExperimentRecord {
+                                    instance: instance.name.clone(),
+                                    dimension: instance.dimension,
+                                    cost_function: cost_name.clone(),
+                                    algorithm: label.clone(),
+                                    budget,
+                                    seed,
+                                    cost: f64::INFINITY,
+                                    feasible: false,
+                                    time,
+                                    profit: 0,
+                                    gap_to_best: None,
+                                    error: Some(e.clone()),
+                                },
+                            };
+
+                            match &record.error {
+                                Some(e) => tracing::warn!(done, total, instance = %instance.name, cost_function = %cost_name,
+                                    algorithm = %label, seed, error = %e, "Run failed"),
+                                None => tracing::info!(done, total, instance = %instance.name, cost_function = %cost_name,
+                                    algorithm = %label, seed, cost = record.cost, feasible = record.feasible, time, "Run finished"),
+                            }
+
+                            if let Ok(sol) = result {
+                                if sol.feasible && best.as_ref().is_none_or(|b| sol.cost < b.solution.cost) {
+                                    best = Some(ProblemBest {
+                                        instance: instance.clone(),
+                                        cost_function: cost_name.clone(),
+                                        algorithm: label.clone(),
+                                        solution: sol,
+                                    });
+                                }
+                            }
+                            self.records.push(record);
+                        }
+                    }
+                }
+
+                if let Some(best) = best {
+                    self.best.push(best);
+                }
+            }
+        }
+
+        self.compute_gaps();
+        Ok(())
+    }
+
+    /// Fill the gap of every feasible run to the best cost on its problem
+    fn compute_gaps(&mut self) {
+        let mut best: HashMap<String, f64> = HashMap::new();
+        for r in self.records.iter().filter(|r| r.feasible) {
+            let entry = best.entry(r.problem()).or_insert(f64::INFINITY);
+            *entry = entry.min(r.cost);
+        }
+        for r in self.records.iter_mut().filter(|r| r.feasible) {
+            let b = best[&r.problem()];
+            r.gap_to_best = Some(if b.abs() > 1e-12 { (r.cost - b) / b.abs() * 100.0 } else { 0.0 });
+        }
+    }
+
+    /// All runs
+    pub fn records(&self) -> &[ExperimentRecord] {
+        &self.records
+    }
+
+    /// Metadata of this campaign
+    pub fn run_info(&self) -> &RunInfo {
+        &self.run_info
+    }
+
+    /// Solver labels in spec order
+    fn solvers(&self) -> Vec<String> {
+        let mut labels = Vec::new();
+        for algorithm in &self.spec.algorithms {
+            for &budget in &self.spec.budgets {
+                labels.push(self.spec.solver_label(algorithm, budget));
+            }
+        }
+        labels
+    }
+
+    /// Problems (instance, dimension, cost function) in run order
+    fn problems(&self) -> Vec<(String, usize, String)> {
+        let mut problems: Vec<(String, usize, String)> = Vec::new();
+        for r in &self.records {
+            if !problems.iter().any(|(i, _, c)| *i == r.instance && *c == r.cost_function) {
+                problems.push((r.instance.clone(), r.dimension, r.cost_function.clone()));
+            }
+        }
+        problems
+    }
+
+    /// Per-problem, per-solver statistics
+    pub fn compute_statistics(&self) -> Vec<ExperimentStatistics> {
+        let mut statistics = Vec::new();
+
+        for (instance, dimension, cost_function) in self.problems() {
+            for algorithm in self.solvers() {
+                let runs: Vec<&ExperimentRecord> = self.records.iter()
+                    .filter(|r| r.instance == instance && r.cost_function == cost_function && r.algorithm == algorithm)
+                    .collect();
+                if runs.is_empty() {
+                    continue;
+                }
+                let costs: Vec<f64> = runs.iter().filter(|r| r.feasible).map(|r| r.cost).collect();
+                let gaps: Vec<f64> = runs.iter().filter_map(|r| r.gap_to_best).collect();
+                let mean = |v: &[f64]| if v.is_empty() { None } else { Some(v.iter().sum::<f64>() / v.len() as f64) };
+
+                let mean_cost = mean(&costs);
+                let std_cost = mean_cost.map(|m| {
+                    (costs.iter().map(|c| (c - m).powi(2)).sum::<f64>() / costs.len() as f64).sqrt()
+                });
+
+                statistics.push(ExperimentStatistics {
+                    instance: instance.clone(),
+                    dimension,
+                    cost_function: cost_function.clone(),
+                    algorithm,
+                    runs: runs.len(),
+                    feasible: costs.len(),
+                    mean_cost,
+                    std_cost,
+                    best_cost: costs.iter().cloned().reduce(f64::min),
+                    mean_gap: mean(&gaps),
+                    mean_time: runs.iter().map(|r| r.time).sum::<f64>() / runs.len() as f64,
+                });
+            }
+        }
+
+        statistics
+    }
+
+    /// Per-cost-function summary of each solver
+    pub fn compute_summary(&self) -> Vec<SolverSummary> {
+        let statistics = self.compute_statistics();
+        let mut summary = Vec::new();
+
+        for cost_function in &self.spec.cost_functions {
+            let stats: Vec<&ExperimentStatistics> = statistics.iter()
+                .filter(|s| s.cost_function == *cost_function)
+                .collect();
+
+            // Best mean cost of each instance, for the win counts
+            let mut best: HashMap<&str, f64> = HashMap::new();
+            for s in &stats {
+                if let Some(m) = s.mean_cost {
+                    let entry = best.entry(s.instance.as_str()).or_insert(f64::INFINITY);
+                    *entry = entry.min(m);
+                }
+            }
+
+            for algorithm in self.solvers() {
+                let runs: Vec<&ExperimentRecord> = self.records.iter()
+                    .filter(|r| r.cost_function == *cost_function && r.algorithm == algorithm)
+                    .collect();
+                if runs.is_empty() {
+                    continue;
+                }
+                let gaps: Vec<f64> = runs.iter().filter_map(|r| r.gap_to_best).collect();
+                let wins = stats.iter()
+                    .filter(|s| s.algorithm == algorithm)
+                    .filter(|s| s.mean_cost.is_some_and(|m| m <= best[s.instance.as_str()] + 1e-9))
+                    .count();
+
+                summary.push(SolverSummary {
+                    cost_function: cost_function.clone(),
+                    algorithm,
+                    feasible_rate: runs.iter().filter(|r| r.feasible).count() as f64 / runs.len() as f64,
+                    mean_gap: if gaps.is_empty() { None } else { Some(gaps.iter().sum::<f64>() / gaps.len() as f64) },
+                    wins,
+                    mean_time: runs.iter().map(|r| r.time).sum::<f64>() / runs.len() as f64,
+                });
+            }
+        }
+
+        summary
+    }
+
+    /// Performance ratios of each solver over all problems
+    pub fn performance_ratios(&self) -> Vec<(String, Vec<f64>)> {
+        let entries: Vec<(String, String, f64)> = self.records.iter()
+            .map(|r| (r.problem(), r.algorithm.clone(), if r.feasible { r.cost } else { f64::INFINITY }))
+            .collect();
+        performance_ratios(&entries)
+    }
+
+    /// LaTeX tables: mean cost per instance and solver for each cost function
+    /// (best in bold), then the solver summary
+    pub fn generate_latex_tables(&self) -> String {
+        let statistics = self.compute_statistics();
+        let solvers = self.solvers();
+        let mut tex = String::new();
+
+        tex.push_str(&format!("% Generated by pd-tsp-solver report-run\n% {}\n\n", self.run_info.summary()));
+
+        for cost_function in &self.spec.cost_functions {
+            tex.push_str("\\begin{table}[ht]\n\\centering\n");
+            tex.push_str(&format!(
+                "\\caption{{{} -- mean cost over {} seed(s), {} cost}}\n",
+                latex_escape(&self.spec.name), self.spec.seeds.len(), latex_escape(cost_function)
+            ));
+            tex.push_str(&format!("\\label{{tab:{}-{}}}\n", label_slug(&self.spec.name), label_slug(cost_function)));
+            tex.push_str(&format!("\\begin{{tabular}}{{lr{}}}\n\\hline\n", "r".repeat(solvers.len())));
+            tex.push_str("Instance & $n$");
+            for solver in &solvers {
+                tex.push_str(&format!(" & {}", latex_escape(solver)));
+            }
+            tex.push_str(" \\\\\n\\hline\n");
+
+            for (instance, dimension, _) in self.problems().iter().filter(|(_, _, c)| c == cost_function) {
+                let row: Vec<Option<f64>> = solvers.iter().map(|solver| {
+                    statistics.iter()
+                        .find(|s| s.instance == *instance && s.cost_function == *cost_function && s.algorithm == *solver)
+                        .and_then(|s| s.mean_cost)
+                }).collect();
+                let best = row.iter().flatten().cloned().fold(f64::INFINITY, f64::min);
+
+                tex.push_str(&format!("{} & {}", latex_escape(instance), dimension));
+                for cell in &row {
+                    match cell {
+                        Some(c) if *c <= best + 1e-9 => tex.push_str(&format!(" & \\textbf{{{:.2}}}", c)),
+                        Some(c) => tex.push_str(&format!(" & {:.2}", c)),
+                        None => tex.push_str(" & --"),
+                    }
+                }
+                tex.push_str(" \\\\\n");
+            }
+            tex.push_str("\\hline\n\\end{tabular}\n\\end{table}\n\n");
+        }
+
+        tex.push_str("\\begin{table}[ht]\n\\centering\n");
+        tex.push_str(&format!("\\caption{{{} -- solver summary}}\n", latex_escape(&self.spec.name)));
+        tex.push_str(&format!("\\label{{tab:{}-summary}}\n", label_slug(&self.spec.name)));
+        tex.push_str("\\begin{tabular}{llrrrr}\n\\hline\n");
+        tex.push_str("Cost & Solver & Feasible (\\%) & Mean gap (\\%) & Wins & Mean time (s) \\\\\n\\hline\n");
+        for s in self.compute_summary() {
+            tex.push_str(&format!(
+                "{} & {} & {:.1} & {} & {} & {:.3} \\\\\n",
+                latex_escape(&s.cost_function), latex_escape(&s.algorithm), s.feasible_rate * 100.0,
+                s.mean_gap.map(|g| format!("{:.2}", g)).unwrap_or_else(|| "--".to_string()),
+                s.wins, s.mean_time
+            ));
+        }
+        tex.push_str("\\hline\n\\end{tabular}\n\\end{table}\n");
+
+        tex
+    }
+
+    /// Markdown summary of the campaign, linking the other artifacts
+    pub fn generate_summary(&self, figures: &[(String, String)]) -> String {
+        let mut md = String::new();
+
+        md.push_str(&format!("# {}\n\n", self.spec.name));
+        md.push_str(&format!("{}\n\n", self.run_info.summary()));
+        md.push_str(&format!(
+            "{} runs: {} problem(s) x {} solver(s) x {} seed(s).\n\n",
+            self.records.len(), self.problems().len(), self.solvers().len(), self.spec.seeds.len()
+        ));
+
+        md.push_str("## Solver summary\n\n");
+        md.push_str("| Cost | Solver | Feasible | Mean gap | Wins | Mean time (s) |\n");
+        md.push_str("|---|---|---:|---:|---:|---:|\n");
+        for s in self.compute_summary() {
+            md.push_str(&format!(
+                "| {} | {} | {:.1}% | {} | {} | {:.3} |\n",
+                s.cost_function, s.algorithm, s.feasible_rate * 100.0,
+                s.mean_gap.map(|g| format!("{:.2}%", g)).unwrap_or_else(|| "-".to_string()),
+                s.wins, s.mean_time
+            ));
+        }
+
+        let failures: Vec<&ExperimentRecord> = self.records.iter().filter(|r| r.error.is_some()).collect();
+        if !failures.is_empty() {
+            md.push_str(&format!("\n{} run(s) failed, see `results.csv`.\n", failures.len()));
+        }
+
+        md.push_str("\n## Performance profile\n\n![Performance profile](performance_profile.svg)\n");
+
+        md.push_str("\n## Best tours\n\n");
+        for (caption, file) in figures {
+            md.push_str(&format!("- [{}]({})\n", caption, file));
+        }
+
+        md.push_str("\n## Artifacts\n\n");
+        md.push_str("- `results.csv`, `results.json`: one row per run\n");
+        md.push_str("- `statistics.csv`: per instance, cost function and solver\n");
+        md.push_str("- `tables.tex`: LaTeX tables\n");
+        md.push_str("- `performance_profile.csv`, `performance_profile.svg`: Dolan-Moré profiles\n");
+        md.push_str("- `figures/`: best tour of each problem\n");
+
+        md.push_str("\n## Spec\n\n```toml\n");
+        md.push_str(&toml::to_string(&self.spec).unwrap_or_default());
+        md.push_str("```\n");
+
+        md
+    }
+
+    /// Write every artifact to the output directory; returns the written files
+    pub fn write_artifacts(&self) -> Result<Vec<PathBuf>, String> {
+        let dir = self.spec.output_dir();
+        let figures_dir = dir.join("figures");
+        std::fs::create_dir_all(&figures_dir)
+            .map_err(|e| format!("Failed to create {:?}: {}", figures_dir, e))?;
+        let io_err = |path: &Path| { let path = path.to_path_buf(); move |e: std::io::Error| format!("Failed to write {:?}: {}", path, e) };
+        let mut written = Vec::new();
+
+        let path = dir.join("results.csv");
+        write_csv(&path, &self.records).map_err(io_err(&path))?;
+        self.run_info.write_sidecar(&path).map_err(io_err(&path))?;
+        written.push(path);
+
+        let path = dir.join("results.json");
+        let json = serde_json::to_string_pretty(&self.records).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(io_err(&path))?;
+        written.push(path);
+
+        let path = dir.join("statistics.csv");
+        write_csv(&path, &self.compute_statistics()).map_err(io_err(&path))?;
+        written.push(path);
+
+        let path = dir.join("tables.tex");
+        std::fs::write(&path, self.generate_latex_tables()).map_err(io_err(&path))?;
+        written.push(path);
+
+        let ratios = self.performance_ratios();
+        let path = dir.join("performance_profile.csv");
+        std::fs::write(&path, performance_profile_csv(&ratios)).map_err(io_err(&path))?;
+        written.push(path);
+
+        let viz = Visualizer::new();
+        let path = dir.join("performance_profile.svg");
+        viz.save_svg(&viz.generate_performance_profile_svg(&ratios), &path).map_err(io_err(&path))?;
+        written.push(path);
+
+        let mut figures = Vec::new();
+        for best in &self.best {
+            let file = format!("{}_{}.svg", file_slug(&best.instance.name), file_slug(&best.cost_function));
+            let path = figures_dir.join(&file);
+            let mut solution = best.solution.clone();
+            solution.run_info = Some(self.run_info.clone());
+            viz.save_svg(&viz.generate_svg(&best.instance, &solution), &path).map_err(io_err(&path))?;
+            figures.push((
+                format!("{} ({}): {:.2} by {}", best.instance.name, best.cost_function, best.solution.cost, best.algorithm),
+                format!("figures/{}", file),
+            ));
+            written.push(path);
+        }
+
+        let path = dir.join("summary.md");
+        std::fs::write(&path, self.generate_summary(&figures)).map_err(io_err(&path))?;
+        written.push(path);
+
+        Ok(written)
+    }
+}
+
+fn write_csv<T: Serialize>(path: &Path, rows: &[T]) -> std::io::Result<()> {
+    let mut writer = csv::Writer::from_writer(File::create(path)?);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()
+}
+
+/// Profile table: one row per breakpoint tau, one column per solver
+fn latex_escape(text: &str) -> String {
+    text.replace('\\', "\\textbackslash{}")
+        .replace('_', "\\_")
+        .replace('&', "\\&")
+        .replace('%', "\\%")
+        .replace('#', "\\#")
+}
+
+fn label_slug(text: &str) -> String {
+    text.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' }).collect()
+}
+
+fn file_slug(text: &str) -> String {
+    text.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_parsing() {
+        let spec = ExperimentSpec::from_toml(r#"
+            name = "test"
+            instances = ["a.tsp"]
+            algorithms = ["greedy", "vnd"]
+            cost_functions = ["distance", "linear_load"]
+            budgets = [1.0, 5.0]
+        "#).unwrap();
+        assert_eq!(spec.seeds, vec![0]);
+        assert_eq!(spec.output, PathBuf::from("experiment"));
+        assert_eq!(spec.solver_label("vnd", 5.0), "vnd@5s");
+        assert_eq!(parse_cost_function(&spec.cost_functions[1]), Ok(CostFunction::LinearLoad));
+
+        assert!(ExperimentSpec::from_toml("name = \"x\"\ninstances = [\"a\"]\nalgorithms = [\"magic\"]").is_err());
+        assert!(ExperimentSpec::from_toml("name = \"x\"\ninstances = [\"a\"]\nalgorithms = [\"nn\"]\nseed = 3").is_err());
+        assert!(ExperimentSpec::from_toml("name = \"x\"\ninstances = [\"a\"]\nalgorithms = [\"nn\"]\nbudgets = [0.0]").is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! Acceptance criteria for Iterated Local Search.
+//!
+//! After each perturbation and local search, ILS decides whether the new
+//! local optimum replaces the current one. Accepting only improvements
+//! intensifies around the first good basin; the other criteria let the
+//! search drift through slightly worse local optima. The best tour is kept
+//! separately whatever the criterion.
+
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// Trait for ILS acceptance criteria; a criterion is created for each run
+pub trait AcceptanceCriterion {
+    /// Whether a local optimum of cost `candidate` replaces the current one
+    /// (cost `current`); `best` is the best cost found so far
+    fn accept(&mut self, candidate: f64, current: f64, best: f64, rng: &mut ChaCha8Rng) -> bool;
+    fn name(&self) -> &str;
+}
+
+/// Accept strict improvements of the current tour
+pub struct Better;
+
+impl AcceptanceCriterion for Better {
+    fn accept(&mut self, candidate: f64, current: f64, _best: f64, _rng: &mut ChaCha8Rng) -> bool {
+        candidate < current
+    }
+
+    fn name(&self) -> &str {
+        "Better"
+    }
+}
+
+/// Accept every new local optimum
+pub struct RandomWalk;
+
+impl AcceptanceCriterion for RandomWalk {
+    fn accept(&mut self, _candidate: f64, _current: f64, _best: f64, _rng: &mut ChaCha8Rng) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        "RandomWalk"
+    }
+}
+
+/// Accept a worse local optimum with probability exp(-delta / T), the
+/// temperature being multiplied by `cooling` after each decision
+pub struct AnnealingAcceptance {
+    pub temperature: f64,
+    pub cooling: f64,
+}
+
+impl AnnealingAcceptance {
+    pub fn new(temperature: f64, cooling: f64) -> Self {
+        AnnealingAcceptance { temperature, cooling }
+    }
+}
+
+impl AcceptanceCriterion for AnnealingAcceptance {
+    fn accept(&mut self, candidate: f64, current: f64, _best: f64, rng: &mut ChaCha8Rng) -> bool {
+        let delta = candidate - current;
+        let accept = delta < 0.0 || (self.temperature > 0.0 && rng.gen::<f64>() < (-delta / self.temperature).exp());
+        self.temperature *= self.cooling;
+        accept
+    }
+
+    fn name(&self) -> &str {
+        "Annealing"
+    }
+}
+
+/// Late acceptance hill climbing: accept a local optimum no worse than the
+/// current one, or than the current one `length` decisions ago
+pub struct LateAcceptance {
+    length: usize,
+    history: Vec<f64>,
+    step: usize,
+}
+
+impl LateAcceptance {
+    pub fn new(length: usize) -> Self {
+        LateAcceptance {
+            length: length.max(1),
+            history: Vec::new(),
+            step: 0,
+        }
+    }
+}
+
+impl AcceptanceCriterion for LateAcceptance {
+    fn accept(&mut self, candidate: f64, current: f64, _best: f64, _rng: &mut ChaCha8Rng) -> bool {
+        // The history starts filled with the first current cost
+        if self.history.is_empty() {
+            self.history.resize(self.length, current);
+        }
+        let slot = self.step % self.history.len();
+        let accept = candidate <= current || candidate <= self.history[slot];
+        self.history[slot] = if accept { candidate } else { current };
+        self.step += 1;
+        accept
+    }
+
+    fn name(&self) -> &str {
+        "LateAcceptance"
+    }
+}
+
+/// Acceptance criterion of `IteratedLocalSearch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Acceptance {
+    /// Strict improvements only
+    Better,
+    /// Every new local optimum
+    RandomWalk,
+    /// Worse local optima with a decreasing probability
+    Annealing,
+    /// Late acceptance hill climbing
+    LateAcceptance,
+}
+
+impl Acceptance {
+    /// Criterion for a run of at most `max_iterations` decisions from a tour
+    /// of cost `initial_cost`. The annealing temperature starts at
+    /// `temperature` times that cost and is cooled down to 1% of it by the
+    /// last iteration; `history_length` is the late acceptance window.
+    pub fn criterion(
+        &self,
+        initial_cost: f64,
+        max_iterations: usize,
+        temperature: f64,
+        history_length: usize,
+    ) -> Box<dyn AcceptanceCriterion + Send> {
+        match self {
+            Acceptance::Better => Box::new(Better),
+            Acceptance::RandomWalk => Box::new(RandomWalk),
+            Acceptance::Annealing => {
+                let cooling = 0.01f64.powf(1.0 / max_iterations.max(1) as f64);
+                let start = if initial_cost.is_finite() { temperature * initial_cost } else { 0.0 };
+                Box::new(AnnealingAcceptance::new(start, cooling))
+            }
+            Acceptance::LateAcceptance => Box::new(LateAcceptance::new(history_length)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acceptance_criteria() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        assert!(!Better.accept(100.0, 100.0, 90.0, &mut rng));
+        assert!(Better.accept(99.0, 100.0, 90.0, &mut rng));
+        assert!(RandomWalk.accept(150.0, 100.0, 90.0, &mut rng));
+
+        // A cold annealing acceptance behaves like Better
+        let mut cold = AnnealingAcceptance::new(0.0, 0.9);
+        assert!(!cold.accept(100.1, 100.0, 90.0, &mut rng));
+        let mut hot = AnnealingAcceptance::new(1e9, 0.5);
+        assert!(hot.accept(101.0, 100.0, 90.0, &mut rng));
+        assert_eq!(hot.temperature, 5e8);
+
+        // Late acceptance compares with the current cost two decisions ago
+        let mut late = LateAcceptance::new(2);
+        assert!(late.accept(95.0, 100.0, 95.0, &mut rng));
+        // Worse than the current tour, but not than the initial one
+        assert!(late.accept(99.0, 95.0, 95.0, &mut rng));
+        assert!(!late.accept(99.5, 99.0, 95.0, &mut rng));
+    }
+}
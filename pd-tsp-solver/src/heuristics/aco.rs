@@ -1,15 +1,55 @@
 //! Ant Colony Optimization for PD-TSP.
 //! 
 //! This module implements the Ant Colony System (ACS) algorithm
-//! with capacity-aware path construction.
+//! with capacity-aware path construction, along with the Max-Min,
+//! rank-based and elitist Ant System pheromone strategies. All strategies
+//! share the construction and run loop and differ only in their
+//! pheromone deposit and evaporation rules.
+//!
+//! The ants of an iteration are built (and improved by local search) in
+//! parallel, each from its own RNG stream derived from the seed, the
+//! iteration and the ant index; pheromone updates are applied afterwards
+//! in ant order, so a run is reproducible for a given seed.
 
 use crate::instance::PDTSPInstance;
 use crate::solution::Solution;
 use crate::heuristics::local_search::{LocalSearch, VND};
+use crate::progress::{notify, ProgressEvent, ProgressHandler};
 // (no construction fallback used any more)
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 use ordered_float::OrderedFloat;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Pheromone update rules
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PheromoneStrategy {
+    /// Ant Colony System: local decay after each ant, best-so-far deposit
+    AntColonySystem,
+    /// Max-Min Ant System: iteration-best deposit, trails bounded by [tau_min, tau_max],
+    /// reset to tau_max when the search stagnates
+    MaxMin,
+    /// Rank-based Ant System: the best `rank_width - 1` ants deposit by rank,
+    /// plus the best-so-far tour with weight `rank_width`
+    RankBased,
+    /// Elitist Ant System: every ant deposits, plus the best-so-far tour
+    /// with weight `elitist_weight`
+    Elitist,
+}
+
+impl PheromoneStrategy {
+    /// Name reported in solutions
+    pub fn name(&self) -> &'static str {
+        match self {
+            PheromoneStrategy::AntColonySystem => "ACO",
+            PheromoneStrategy::MaxMin => "MMAS",
+            PheromoneStrategy::RankBased => "RankAS",
+            PheromoneStrategy::Elitist => "ElitistAS",
+        }
+    }
+}
 
 /// ACO configuration parameters
 #[derive(Debug, Clone)]
@@ -30,7 +70,7 @@ pub struct ACOConfig {
     pub initial_pheromone: f64,
     /// Pheromone deposit factor
     pub q: f64,
-    /// Exploitation probability (q0 in ACS)
+    /// Exploitation probability (q0 in ACS, 0 gives the random-proportional rule)
     pub q0: f64,
     /// Local pheromone decay
     pub local_decay: f64,
@@ -40,6 +80,41 @@ pub struct ACOConfig {
     pub seed: u64,
     /// Time limit in seconds for the ACO run
     pub time_limit: f64,
+    /// Pheromone deposit and evaporation rules
+    pub strategy: PheromoneStrategy,
+    /// Number of ranks in the rank-based Ant System
+    pub rank_width: usize,
+    /// Weight of the best-so-far tour in the elitist Ant System
+    pub elitist_weight: f64,
+    /// Upper trail bound in MMAS (None: 1 / (rho * best cost), updated on improvement)
+    pub tau_max: Option<f64>,
+    /// Lower trail bound in MMAS (None: tau_max / 50)
+    pub tau_min: Option<f64>,
+    /// MMAS restarts after this many consecutive iterations whose best ant
+    /// matches the global best (0 disables this test)
+    pub stagnation_iterations: usize,
+    /// MMAS also restarts once the average 0.05-branching factor of the
+    /// trails falls below this value (2 means fully converged, 0 disables)
+    pub min_branching_factor: f64,
+    /// Nearest neighbors whose arcs keep their own trail (0 = every arc on
+    /// instances below `AUTO_CANDIDATE_THRESHOLD` nodes, `AUTO_CANDIDATES`
+    /// above)
+    pub candidate_neighbors: usize,
+}
+
+impl ACOConfig {
+    /// Instance size from which the trails default to candidate lists
+    pub const AUTO_CANDIDATE_THRESHOLD: usize = 200;
+    /// Candidate neighbors on large instances when none are set
+    pub const AUTO_CANDIDATES: usize = 20;
+    
+    /// Candidate neighbors on an instance of `n` nodes (0 = every arc)
+    pub fn candidates(&self, n: usize) -> usize {
+        match self.candidate_neighbors {
+            0 if n >= Self::AUTO_CANDIDATE_THRESHOLD => Self::AUTO_CANDIDATES,
+            k => k,
+        }
+    }
 }
 
 impl Default for ACOConfig {
@@ -58,6 +133,146 @@ impl Default for ACOConfig {
             use_local_search: true,
             seed: 42,
             time_limit: 60.0,
+            strategy: PheromoneStrategy::AntColonySystem,
+            rank_width: 6,
+            elitist_weight: 5.0,
+            tau_max: None,
+            tau_min: None,
+            stagnation_iterations: 20,
+            min_branching_factor: 2.05,
+            candidate_neighbors: 0,
+        }
+    }
+}
+
+/// Heuristic value (inverse distance) of an arc
+fn inverse_distance(instance: &PDTSPInstance, i: usize, j: usize) -> f64 {
+    let dist = instance.distance(i, j);
+    if dist > 0.0 { 1.0 / dist } else { 1e6 }
+}
+
+/// Pheromone trails and heuristic values of the arcs
+///
+/// Without candidate lists both are full n x n matrices. With them, only the
+/// arcs from each node to its nearest neighbors are stored: every other arc
+/// shares the single trail `rest`, which evaporates, is bounded and reset like
+/// the others but receives no deposit, and its heuristic value is computed
+/// when needed. Trails are symmetric: an arc stored from both ends is updated
+/// at both.
+enum Trails {
+    Dense {
+        pheromone: Vec<Vec<f64>>,
+        heuristic: Vec<Vec<f64>>,
+    },
+    Sparse {
+        neighbors: Vec<Vec<usize>>,
+        /// Trail and heuristic value of the arc to each neighbor
+        pheromone: Vec<Vec<f64>>,
+        heuristic: Vec<Vec<f64>>,
+        rest: f64,
+    },
+}
+
+impl Trails {
+    /// Every trail at `initial`, with `candidates` neighbors per node (0 = dense)
+    fn new(instance: &PDTSPInstance, initial: f64, candidates: usize) -> Self {
+        let n = instance.dimension;
+        if candidates == 0 || candidates + 1 >= n {
+            let heuristic = (0..n)
+                .map(|i| (0..n).map(|j| if i != j { inverse_distance(instance, i, j) } else { 0.0 }).collect())
+                .collect();
+            return Trails::Dense { pheromone: vec![vec![initial; n]; n], heuristic };
+        }
+        
+        let neighbors = instance.nearest_neighbors(candidates);
+        let pheromone = neighbors.iter().map(|list| vec![initial; list.len()]).collect();
+        let heuristic = neighbors.iter().enumerate()
+            .map(|(i, list)| list.iter().map(|&j| inverse_distance(instance, i, j)).collect())
+            .collect();
+        Trails::Sparse { neighbors, pheromone, heuristic, rest: initial }
+    }
+    
+    /// Trail of the arc `(i, j)`
+    fn get(&self, i: usize, j: usize) -> f64 {
+        match self {
+            Trails::Dense { pheromone, .. } => pheromone[i][j],
+            Trails::Sparse { neighbors, pheromone, rest, .. } => {
+                if let Some(p) = neighbors[i].iter().position(|&k| k == j) {
+                    pheromone[i][p]
+                } else if let Some(p) = neighbors[j].iter().position(|&k| k == i) {
+                    pheromone[j][p]
+                } else {
+                    *rest
+                }
+            }
+        }
+    }
+    
+    /// Apply `f` to the trail of the edge `{i, j}`; no-op for an edge outside
+    /// the candidate lists
+    fn update(&mut self, i: usize, j: usize, f: impl Fn(f64) -> f64) {
+        match self {
+            Trails::Dense { pheromone, .. } => {
+                pheromone[i][j] = f(pheromone[i][j]);
+                pheromone[j][i] = pheromone[i][j];
+            }
+            Trails::Sparse { neighbors, pheromone, .. } => {
+                for (a, b) in [(i, j), (j, i)] {
+                    if let Some(p) = neighbors[a].iter().position(|&k| k == b) {
+                        pheromone[a][p] = f(pheromone[a][p]);
+                    }
+                }
+            }
+        }
+    }
+    
+    /// Apply `f` to every trail
+    fn for_each_mut(&mut self, f: impl Fn(&mut f64)) {
+        match self {
+            Trails::Dense { pheromone, .. } => pheromone.iter_mut().flatten().for_each(f),
+            Trails::Sparse { pheromone, rest, .. } => {
+                pheromone.iter_mut().flatten().for_each(&f);
+                f(rest);
+            }
+        }
+    }
+    
+    /// Arcs leaving `i` with their trail and heuristic value: every arc when
+    /// dense, the candidate arcs otherwise
+    fn arcs(&self, i: usize) -> Vec<(usize, f64, f64)> {
+        match self {
+            Trails::Dense { pheromone, heuristic } => {
+                (0..pheromone.len()).map(|j| (j, pheromone[i][j], heuristic[i][j])).collect()
+            }
+            Trails::Sparse { neighbors, pheromone, heuristic, .. } => {
+                neighbors[i].iter().enumerate().map(|(p, &j)| (j, pheromone[i][p], heuristic[i][p])).collect()
+            }
+        }
+    }
+    
+    /// Whether `arcs` already lists every arc
+    fn is_dense(&self) -> bool {
+        matches!(self, Trails::Dense { .. })
+    }
+    
+    /// Trails incident to `i` and the number of further arcs sharing `rest`
+    fn incident(&self, i: usize) -> (Vec<f64>, usize, f64) {
+        match self {
+            Trails::Dense { pheromone, .. } => {
+                let row = (0..pheromone.len()).filter(|&j| j != i).map(|j| pheromone[i][j]).collect();
+                (row, 0, 0.0)
+            }
+            Trails::Sparse { neighbors, pheromone, rest, .. } => {
+                (pheromone[i].clone(), neighbors.len() - 1 - neighbors[i].len(), *rest)
+            }
+        }
+    }
+    
+    #[cfg(test)]
+    fn values(&self) -> Vec<f64> {
+        match self {
+            Trails::Dense { pheromone, .. } => pheromone.iter().flatten().copied().collect(),
+            Trails::Sparse { pheromone, rest, .. } => pheromone.iter().flatten().copied().chain([*rest]).collect(),
         }
     }
 }
@@ -66,61 +281,124 @@ impl Default for ACOConfig {
 pub struct AntColonyOptimization {
     config: ACOConfig,
     instance: PDTSPInstance,
-    pheromone: Vec<Vec<f64>>,
-    heuristic: Vec<Vec<f64>>,
+    trails: Trails,
     best_tour: Vec<usize>,
     best_cost: f64,
-    rng: ChaCha8Rng,
+    /// Trail bounds (Max-Min strategy only)
+    tau_max: f64,
+    tau_min: f64,
+    /// Consecutive iterations whose best ant matched the global best
+    stagnation: usize,
+    /// Number of pheromone reinitializations performed
+    restarts: usize,
+    on_progress: Option<ProgressHandler>,
 }
 
 impl AntColonyOptimization {
     pub fn new(instance: PDTSPInstance, config: ACOConfig) -> Self {
         let n = instance.dimension;
         
-        // Initialize pheromone matrix
-        let pheromone = vec![vec![config.initial_pheromone; n]; n];
-        
-        // Initialize heuristic information (inverse distance)
-        let mut heuristic = vec![vec![0.0; n]; n];
-        for i in 0..n {
-            for j in 0..n {
-                if i != j {
-                    let dist = instance.distance(i, j);
-                    heuristic[i][j] = if dist > 0.0 { 1.0 / dist } else { 1e6 };
-                }
-            }
-        }
+        // Max-Min starts every trail at tau_max, from an initial cost estimate
+        let (tau_max, tau_min) = Self::tau_bounds(&config, 1000.0);
+        let initial = match config.strategy {
+            PheromoneStrategy::MaxMin => tau_max,
+            _ => config.initial_pheromone,
+        };
         
-        let rng = ChaCha8Rng::seed_from_u64(config.seed);
+        let trails = Trails::new(&instance, initial, config.candidates(n));
         
         AntColonyOptimization {
             config,
             instance,
-            pheromone,
-            heuristic,
+            trails,
             best_tour: Vec::new(),
             best_cost: f64::INFINITY,
-            rng,
+            tau_max,
+            tau_min,
+            stagnation: 0,
+            restarts: 0,
+            on_progress: None,
         }
     }
     
+    /// MMAS trail bounds for a given best cost, unless fixed by the config
+    fn tau_bounds(config: &ACOConfig, best_cost: f64) -> (f64, f64) {
+        let tau_max = config.tau_max.unwrap_or(1.0 / (config.evaporation_rate * best_cost));
+        let tau_min = config.tau_min.unwrap_or(tau_max / 50.0);
+        (tau_max, tau_min.min(tau_max))
+    }
+    
+    /// Number of pheromone reinitializations performed by MMAS
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+    
+    /// Average lambda-branching factor: per node, the number of incident
+    /// trails above tau_min + lambda * (max trail - min trail). With candidate
+    /// lists, the arcs of a node outside its list count with the shared trail.
+    fn branching_factor(&self, lambda: f64) -> f64 {
+        let n = self.instance.dimension;
+        if n < 2 {
+            return 0.0;
+        }
+        
+        let mut total = 0;
+        for i in 0..n {
+            let (row, others, rest) = self.trails.incident(i);
+            let shared = if others > 0 { rest } else { f64::NAN };
+            let min = row.iter().fold(shared, |a, &t| a.min(t));
+            let max = row.iter().fold(shared, |a, &t| a.max(t));
+            let cutoff = min + lambda * (max - min);
+            total += row.iter().filter(|&&t| t >= cutoff).count();
+            if others > 0 && rest >= cutoff {
+                total += others;
+            }
+        }
+        total as f64 / n as f64
+    }
+    
+    /// Reinitialize every MMAS trail to tau_max when the search stagnates
+    fn restart_if_stagnating(&mut self) {
+        let stuck = self.config.stagnation_iterations > 0
+            && self.stagnation >= self.config.stagnation_iterations;
+        let converged = self.config.min_branching_factor > 0.0
+            && self.branching_factor(0.05) < self.config.min_branching_factor;
+        
+        if stuck || converged {
+            let tau_max = self.tau_max;
+            self.trails.for_each_mut(|t| *t = tau_max);
+            self.stagnation = 0;
+            self.restarts += 1;
+        }
+    }
+    
+    /// Receive a progress event after every iteration
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+        self.on_progress = Some(ProgressHandler::new(callback));
+    }
+    
+    /// RNG stream of one ant in one iteration
+    fn ant_rng(&self, iteration: usize, ant: usize) -> ChaCha8Rng {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.config.seed);
+        rng.set_stream(((iteration as u64) << 32) | ant as u64);
+        rng
+    }
+    
     /// Construct a solution for one ant
-    fn construct_solution(&mut self) -> Vec<usize> {
+    fn construct_solution(&self, rng: &mut ChaCha8Rng) -> Vec<usize> {
         let n = self.instance.dimension;
         let mut tour = vec![0]; // Start at depot
         let mut visited = vec![false; n];
         visited[0] = true;
         
-        let mut current = 0;
         // Vehicle starts with initial load (depot demands processed)
         let mut current_load = self.instance.starting_load();
         
         while tour.len() < n {
-            if let Some(next) = self.select_next_node(current, &visited, current_load) {
+            if let Some(next) = self.select_next_node(&tour, &visited, current_load, rng) {
                 tour.push(next);
                 visited[next] = true;
                 current_load += self.instance.nodes[next].demand;
-                current = next;
             } else {
                 // No feasible node found - terminate construction early
                 break;
@@ -130,28 +408,26 @@ impl AntColonyOptimization {
         tour
     }
     
-    /// Select next node using ACS rule
+    /// Select next node using ACS rule, among the candidate arcs of the
+    /// current node first and all nodes when none of them is usable.
     /// Returns None if no feasible unvisited node exists
-    fn select_next_node(&mut self, current: usize, visited: &[bool], current_load: i32) -> Option<usize> {
+    fn select_next_node(&self, tour: &[usize], visited: &[bool], current_load: i32, rng: &mut ChaCha8Rng) -> Option<usize> {
         let n = self.instance.dimension;
+        let current = *tour.last().unwrap();
+        let usable = |j: usize| !visited[j] && self.instance.can_append(tour, current_load, j);
+        let score = |tau: f64, eta: f64| tau.powf(self.config.alpha) * eta.powf(self.config.beta);
         
         // Calculate probabilities for feasible unvisited nodes
-        let mut candidates: Vec<(usize, f64)> = Vec::new();
-        
-        for j in 0..n {
-            if visited[j] {
-                continue;
-            }
-            
-            // Check capacity feasibility
-            let new_load = current_load + self.instance.nodes[j].demand;
-            if new_load < 0 || new_load > self.instance.capacity {
-                continue;
-            }
-            
-            let tau = self.pheromone[current][j].powf(self.config.alpha);
-            let eta = self.heuristic[current][j].powf(self.config.beta);
-            candidates.push((j, tau * eta));
+        let mut candidates: Vec<(usize, f64)> = self.trails.arcs(current).into_iter()
+            .filter(|&(j, _, _)| usable(j))
+            .map(|(j, tau, eta)| (j, score(tau, eta)))
+            .collect();
+        
+        if candidates.is_empty() && !self.trails.is_dense() {
+            candidates = (0..n)
+                .filter(|&j| usable(j))
+                .map(|j| (j, score(self.trails.get(current, j), inverse_distance(&self.instance, current, j))))
+                .collect();
         }
         
         if candidates.is_empty() {
@@ -160,7 +436,7 @@ impl AntColonyOptimization {
         }
         
         // ACS decision rule
-        if self.rng.gen::<f64>() < self.config.q0 {
+        if rng.gen::<f64>() < self.config.q0 {
             // Exploitation: choose best
             candidates.iter()
                 .max_by_key(|&&(_, prob)| OrderedFloat(prob))
@@ -168,7 +444,7 @@ impl AntColonyOptimization {
         } else {
             // Exploration: roulette wheel
             let total: f64 = candidates.iter().map(|&(_, p)| p).sum();
-            let mut pick = self.rng.gen::<f64>() * total;
+            let mut pick = rng.gen::<f64>() * total;
             
             for &(j, prob) in &candidates {
                 pick -= prob;
@@ -181,268 +457,215 @@ impl AntColonyOptimization {
         }
     }
     
+    /// Build one ant's tour and improve it; None if the tour is infeasible
+    fn build_ant(&self, vnd: &VND, rng: &mut ChaCha8Rng) -> Option<(Vec<usize>, f64)> {
+        let tour = self.construct_solution(rng);
+        
+        if !self.instance.is_feasible(&tour) {
+            return None;
+        }
+        
+        let mut cost = self.instance.tour_cost(&tour);
+        let mut final_tour = tour.clone();
+        
+        // Apply local search
+        if self.config.use_local_search {
+            let mut solution = Solution::from_tour(&self.instance, tour, "ACO-temp");
+            vnd.improve(&self.instance, &mut solution);
+            
+            if solution.feasible {
+                final_tour = solution.tour;
+                cost = solution.cost;
+            }
+        }
+        
+        Some((final_tour, cost))
+    }
+    
     /// Local pheromone update (ACS)
     fn local_pheromone_update(&mut self, tour: &[usize]) {
         let n = tour.len();
         let tau0 = self.config.initial_pheromone;
         
+        let decay = self.config.local_decay;
+        
         for i in 0..n {
             let from = tour[i];
             let to = tour[(i + 1) % n];
             
-            self.pheromone[from][to] = 
-                (1.0 - self.config.local_decay) * self.pheromone[from][to] 
-                + self.config.local_decay * tau0;
-            self.pheromone[to][from] = self.pheromone[from][to];
+            self.trails.update(from, to, |t| (1.0 - decay) * t + decay * tau0);
         }
     }
     
-    /// Global pheromone update
-    fn global_pheromone_update(&mut self) {
-        let n = self.instance.dimension;
-        
+    /// Add `amount` to the trail of every edge of a closed tour
+    fn deposit(&mut self, tour: &[usize], amount: f64) {
+        let m = tour.len();
+        for i in 0..m {
+            let from = tour[i];
+            let to = tour[(i + 1) % m];
+            
+            self.trails.update(from, to, |t| t + amount);
+        }
+    }
+    
+    /// Global pheromone update: evaporation, then the strategy's deposits.
+    /// `ants` holds the iteration's tours sorted by increasing cost.
+    fn global_pheromone_update(&mut self, ants: &[(Vec<usize>, f64)], no_improve: usize) {
         // Evaporation
-        for i in 0..n {
-            for j in 0..n {
-                self.pheromone[i][j] *= 1.0 - self.config.evaporation_rate;
+        let rho = self.config.evaporation_rate;
+        self.trails.for_each_mut(|t| *t *= 1.0 - rho);
+        
+        let q = self.config.q;
+        let best_tour = std::mem::take(&mut self.best_tour);
+        let has_best = !best_tour.is_empty();
+        
+        match self.config.strategy {
+            PheromoneStrategy::AntColonySystem => {
+                // Deposit by best ant
+                if has_best {
+                    self.deposit(&best_tour, q / self.best_cost);
+                }
             }
-        }
-        
-        // Deposit by best ant
-        if !self.best_tour.is_empty() {
-            let delta = self.config.q / self.best_cost;
-            
-            let m = self.best_tour.len();
-            for i in 0..m {
-                let from = self.best_tour[i];
-                let to = self.best_tour[(i + 1) % m];
+            PheromoneStrategy::MaxMin => {
+                // Deposit by iteration best, or by global best once stuck
+                if no_improve > 10 && has_best {
+                    self.deposit(&best_tour, q / self.best_cost);
+                } else if let Some((tour, cost)) = ants.first() {
+                    self.deposit(tour, q / cost);
+                }
                 
-                self.pheromone[from][to] += delta;
-                self.pheromone[to][from] += delta;
+                // Apply bounds
+                let (tau_min, tau_max) = (self.tau_min, self.tau_max);
+                self.trails.for_each_mut(|t| *t = t.max(tau_min).min(tau_max));
+            }
+            PheromoneStrategy::RankBased => {
+                let w = self.config.rank_width.max(1);
+                for (rank, (tour, cost)) in ants.iter().take(w - 1).enumerate() {
+                    self.deposit(tour, (w - 1 - rank) as f64 * q / cost);
+                }
+                if has_best {
+                    self.deposit(&best_tour, w as f64 * q / self.best_cost);
+                }
+            }
+            PheromoneStrategy::Elitist => {
+                for (tour, cost) in ants {
+                    self.deposit(tour, q / cost);
+                }
+                if has_best {
+                    self.deposit(&best_tour, self.config.elitist_weight * q / self.best_cost);
+                }
             }
         }
+        
+        self.best_tour = best_tour;
     }
     
     /// Run ACO algorithm
     pub fn run(&mut self) -> Solution {
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
         let vnd = VND::with_standard_operators();
+        let mut convergence = Vec::new();
+        let name = self.config.strategy.name();
         
         let mut no_improve = 0;
         let mut iteration = 0;
         
         while iteration < self.config.max_iterations && no_improve < self.config.max_no_improve
             && start.elapsed().as_secs_f64() < self.config.time_limit {
-            let mut iteration_best_tour = Vec::new();
-            let mut iteration_best_cost = f64::INFINITY;
+            // Each ant constructs a solution; results keep the ant order
+            let this = &*self;
+            let mut ants: Vec<(Vec<usize>, f64)> = (0..self.config.num_ants)
+                .into_par_iter()
+                .filter_map(|ant| this.build_ant(&vnd, &mut this.ant_rng(iteration, ant)))
+                .collect();
             
-            // Each ant constructs a solution
-            for _ in 0..self.config.num_ants {
-                let tour = self.construct_solution();
-                
-                if !self.instance.is_feasible(&tour) {
-                    continue;
-                }
-                
-                let mut cost = self.instance.tour_length(&tour);
-                let mut final_tour = tour.clone();
-                
-                // Apply local search
-                if self.config.use_local_search {
-                    let mut solution = Solution::from_tour(&self.instance, tour, "ACO-temp");
-                    vnd.improve(&self.instance, &mut solution);
-                    
-                    if solution.feasible {
-                        final_tour = solution.tour;
-                        cost = solution.cost;
-                    }
-                }
-                
-                // Local pheromone update
-                self.local_pheromone_update(&final_tour);
-                
-                // Track iteration best
-                if cost < iteration_best_cost {
-                    iteration_best_cost = cost;
-                    iteration_best_tour = final_tour;
+            // Local pheromone update
+            if self.config.strategy == PheromoneStrategy::AntColonySystem {
+                for (tour, _) in &ants {
+                    self.local_pheromone_update(tour);
                 }
             }
+            ants.sort_by_key(|(_, cost)| OrderedFloat(*cost));
             
             // Update global best
-            if iteration_best_cost < self.best_cost {
-                self.best_cost = iteration_best_cost;
-                self.best_tour = iteration_best_tour;
-                no_improve = 0;
-            } else {
-                no_improve += 1;
+            match ants.first() {
+                Some((_, cost)) if (*cost - self.best_cost).abs() <= 1e-9 * cost.abs().max(1.0) => {
+                    self.stagnation += 1;
+                    no_improve += 1;
+                }
+                Some((tour, cost)) if *cost < self.best_cost => {
+                    self.best_cost = *cost;
+                    self.best_tour = tour.clone();
+                    convergence.push((start.elapsed().as_secs_f64(), self.best_cost));
+                    no_improve = 0;
+                    self.stagnation = 0;
+                    
+                    // Update tau bounds
+                    (self.tau_max, self.tau_min) = Self::tau_bounds(&self.config, self.best_cost);
+                }
+                _ => {
+                    self.stagnation = 0;
+                    no_improve += 1;
+                }
             }
             
             // Global pheromone update
-            self.global_pheromone_update();
+            self.global_pheromone_update(&ants, no_improve);
+            if self.config.strategy == PheromoneStrategy::MaxMin {
+                self.restart_if_stagnating();
+            }
             
             iteration += 1;
+            notify(&self.on_progress, || {
+                ProgressEvent::new(name, iteration, self.best_cost, start.elapsed().as_secs_f64())
+            });
         }
         
         // If no feasible solution found, return an empty/infeasible solution (no fallback)
         if self.best_tour.is_empty() {
             let mut solution = Solution::new();
-            solution.algorithm = "ACO".to_string();
+            solution.algorithm = name.to_string();
             solution.computation_time = start.elapsed().as_secs_f64();
             solution.iterations = Some(iteration);
             return solution;
         }
         
-        let mut solution = Solution::from_tour(&self.instance, self.best_tour.clone(), "ACO");
+        let mut solution = Solution::from_tour(&self.instance, self.best_tour.clone(), name);
         solution.computation_time = start.elapsed().as_secs_f64();
         solution.iterations = Some(iteration);
+        solution.convergence = convergence;
         
         solution
     }
     
     /// Get best solution found
     pub fn best_solution(&self) -> Solution {
-        Solution::from_tour(&self.instance, self.best_tour.clone(), "ACO")
+        Solution::from_tour(&self.instance, self.best_tour.clone(), self.config.strategy.name())
     }
 }
 
 /// Max-Min Ant System variant
 pub struct MaxMinAntSystem {
     aco: AntColonyOptimization,
-    tau_max: f64,
-    tau_min: f64,
 }
 
 impl MaxMinAntSystem {
     pub fn new(instance: PDTSPInstance, config: ACOConfig) -> Self {
-        let tau_max = 1.0 / (config.evaporation_rate * 1000.0); // Initial estimate
-        let tau_min = tau_max / 50.0;
-        
-        let mut aco = AntColonyOptimization::new(instance, config);
-        
-        // Initialize pheromone to tau_max
-        let n = aco.instance.dimension;
-        for i in 0..n {
-            for j in 0..n {
-                aco.pheromone[i][j] = tau_max;
-            }
-        }
+        let config = ACOConfig { strategy: PheromoneStrategy::MaxMin, ..config };
         
         MaxMinAntSystem {
-            aco,
-            tau_max,
-            tau_min,
+            aco: AntColonyOptimization::new(instance, config),
         }
     }
     
+    /// Receive a progress event after every iteration
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+        self.aco.set_progress_callback(callback);
+    }
+    
     /// Run MMAS algorithm
     pub fn run(&mut self) -> Solution {
-        let start = std::time::Instant::now();
-        let vnd = VND::with_standard_operators();
-        
-        let mut no_improve = 0;
-        let mut iteration = 0;
-        
-        while iteration < self.aco.config.max_iterations && no_improve < self.aco.config.max_no_improve
-            && start.elapsed().as_secs_f64() < self.aco.config.time_limit {
-            let mut iteration_best_tour = Vec::new();
-            let mut iteration_best_cost = f64::INFINITY;
-            
-            for _ in 0..self.aco.config.num_ants {
-                let tour = self.aco.construct_solution();
-                
-                if !self.aco.instance.is_feasible(&tour) {
-                    continue;
-                }
-                
-                let mut cost = self.aco.instance.tour_length(&tour);
-                let mut final_tour = tour.clone();
-                
-                if self.aco.config.use_local_search {
-                    let mut solution = Solution::from_tour(&self.aco.instance, tour, "MMAS-temp");
-                    vnd.improve(&self.aco.instance, &mut solution);
-                    
-                    if solution.feasible {
-                        final_tour = solution.tour;
-                        cost = solution.cost;
-                    }
-                }
-                
-                if cost < iteration_best_cost {
-                    iteration_best_cost = cost;
-                    iteration_best_tour = final_tour;
-                }
-            }
-            
-            // Update best
-            if iteration_best_cost < self.aco.best_cost {
-                self.aco.best_cost = iteration_best_cost;
-                self.aco.best_tour = iteration_best_tour.clone();
-                no_improve = 0;
-                
-                // Update tau bounds
-                self.tau_max = 1.0 / (self.aco.config.evaporation_rate * self.aco.best_cost);
-                self.tau_min = self.tau_max / 50.0;
-            } else {
-                no_improve += 1;
-            }
-            
-            // Pheromone update with bounds
-            let n = self.aco.instance.dimension;
-            
-            // Evaporation
-            for i in 0..n {
-                for j in 0..n {
-                    self.aco.pheromone[i][j] *= 1.0 - self.aco.config.evaporation_rate;
-                }
-            }
-            
-            // Deposit by best (iteration best or global best)
-            let update_tour = if no_improve > 10 {
-                &self.aco.best_tour
-            } else {
-                &iteration_best_tour
-            };
-            
-            if !update_tour.is_empty() {
-                let cost = self.aco.instance.tour_length(update_tour);
-                let delta = self.aco.config.q / cost;
-                
-                let m = update_tour.len();
-                for i in 0..m {
-                    let from = update_tour[i];
-                    let to = update_tour[(i + 1) % m];
-                    
-                    self.aco.pheromone[from][to] += delta;
-                    self.aco.pheromone[to][from] += delta;
-                }
-            }
-            
-            // Apply bounds
-            for i in 0..n {
-                for j in 0..n {
-                    self.aco.pheromone[i][j] = self.aco.pheromone[i][j]
-                        .max(self.tau_min)
-                        .min(self.tau_max);
-                }
-            }
-            
-            iteration += 1;
-        }
-        
-        // If no feasible solution found, return an empty/infeasible solution (no fallback)
-        if self.aco.best_tour.is_empty() {
-            let mut solution = Solution::new();
-            solution.algorithm = "MMAS".to_string();
-            solution.computation_time = start.elapsed().as_secs_f64();
-            solution.iterations = Some(iteration);
-            return solution;
-        }
-        
-        let mut solution = Solution::from_tour(&self.aco.instance, self.aco.best_tour.clone(), "MMAS");
-        solution.computation_time = start.elapsed().as_secs_f64();
-        solution.iterations = Some(iteration);
-        
-        solution
+        self.aco.run()
     }
 }
 
@@ -452,7 +675,7 @@ mod tests {
     use crate::instance::Node;
     
     fn create_test_instance() -> PDTSPInstance {
-        use crate::instance::CostFunction;
+        use crate::instance::DistanceMatrix;
         
         let nodes = vec![
             Node::new(0, 0.0, 0.0, 0, 0),
@@ -461,25 +684,14 @@ mod tests {
             Node::new(3, 1.0, 1.0, -2, 0),
         ];
         
-        let mut instance = PDTSPInstance {
-            cost_function: CostFunction::Distance,
-            alpha: 0.1,
-            beta: 0.5,
-            name: "test".to_string(),
-            comment: "test".to_string(),
-            dimension: 4,
-            capacity: 10,
-            nodes: nodes.clone(),
-            distance_matrix: Vec::new(),
-            return_depot_demand: 0,
-        };
+        let mut instance = PDTSPInstance::from_parts("test", 10, nodes.clone(), DistanceMatrix::default());
         
-        instance.distance_matrix = vec![vec![0.0; 4]; 4];
+        instance.distance_matrix = DistanceMatrix::new(4);
         for i in 0..4 {
             for j in 0..4 {
                 let dx = instance.nodes[i].x - instance.nodes[j].x;
                 let dy = instance.nodes[i].y - instance.nodes[j].y;
-                instance.distance_matrix[i][j] = (dx * dx + dy * dy).sqrt();
+                instance.distance_matrix.set(i, j, (dx * dx + dy * dy).sqrt());
             }
         }
         
@@ -500,4 +712,119 @@ mod tests {
         
         assert!(solution.feasible);
     }
+    
+    #[test]
+    fn test_pheromone_strategies() {
+        for strategy in [
+            PheromoneStrategy::MaxMin,
+            PheromoneStrategy::RankBased,
+            PheromoneStrategy::Elitist,
+        ] {
+            let config = ACOConfig {
+                num_ants: 5,
+                max_iterations: 10,
+                use_local_search: false,
+                strategy,
+                ..Default::default()
+            };
+            
+            let mut aco = AntColonyOptimization::new(create_test_instance(), config);
+            let solution = aco.run();
+            
+            assert!(solution.feasible);
+            assert_eq!(solution.algorithm, strategy.name());
+            assert!(aco.trails.values().iter().all(|t| t.is_finite() && *t >= 0.0));
+        }
+    }
+    
+    #[test]
+    fn test_mmas_restarts_on_stagnation() {
+        let config = ACOConfig {
+            num_ants: 5,
+            max_iterations: 30,
+            max_no_improve: 30,
+            use_local_search: false,
+            tau_max: Some(2.0),
+            tau_min: Some(0.1),
+            stagnation_iterations: 5,
+            ..Default::default()
+        };
+        
+        let mut mmas = MaxMinAntSystem::new(create_test_instance(), config);
+        let solution = mmas.run();
+        
+        assert!(solution.feasible);
+        assert!(mmas.aco.restarts() > 0);
+        assert!(mmas.aco.trails.values().iter().all(|&t| (0.1..=2.0).contains(&t)));
+    }
+    
+    #[test]
+    fn test_candidate_lists_on_large_instances() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        // Customers on a circle, alternating pickups and deliveries
+        let n = ACOConfig::AUTO_CANDIDATE_THRESHOLD + 40;
+        let mut instance = create_test_instance();
+        instance.nodes = (0..n).map(|i| {
+            let angle = i as f64 * std::f64::consts::TAU / n as f64;
+            let demand = if i == 0 { 0 } else if i % 2 == 1 { 1 } else { -1 };
+            Node::new(i, 1000.0 * angle.cos(), 1000.0 * angle.sin(), demand, 0)
+        }).collect();
+        instance.dimension = n;
+        instance.capacity = 2;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::OnDemand);
+        
+        for strategy in [PheromoneStrategy::AntColonySystem, PheromoneStrategy::MaxMin] {
+            let config = ACOConfig {
+                num_ants: 4,
+                max_iterations: 3,
+                use_local_search: false,
+                strategy,
+                ..Default::default()
+            };
+            let mut aco = AntColonyOptimization::new(instance.clone(), config);
+            
+            // Only the candidate arcs keep a trail
+            match &aco.trails {
+                Trails::Sparse { neighbors, pheromone, heuristic, .. } => {
+                    assert!(neighbors.iter().all(|list| list.len() == ACOConfig::AUTO_CANDIDATES));
+                    assert!(pheromone.iter().zip(heuristic).all(|(p, h)| p.len() == ACOConfig::AUTO_CANDIDATES && h.len() == p.len()));
+                }
+                Trails::Dense { .. } => panic!("dense trails on {} nodes", n),
+            }
+            
+            let solution = aco.run();
+            assert!(solution.feasible && solution.is_complete(&instance));
+            assert!(aco.trails.values().iter().all(|t| t.is_finite() && *t > 0.0));
+            // Deposits reach the stored arcs of the best tour, not the others
+            let (a, b) = (solution.tour[1], solution.tour[2]);
+            assert!(aco.trails.get(a, b) > aco.trails.get(0, n / 2));
+            assert_eq!(aco.trails.get(a, b), aco.trails.get(b, a));
+        }
+        
+        // Small instances keep the full matrices unless candidates are set
+        let small = AntColonyOptimization::new(create_test_instance(), ACOConfig::default());
+        assert!(small.trails.is_dense());
+        let config = ACOConfig { candidate_neighbors: 1, num_ants: 5, max_iterations: 10, ..Default::default() };
+        let mut sparse = AntColonyOptimization::new(create_test_instance(), config);
+        assert!(!sparse.trails.is_dense());
+        assert!(sparse.run().feasible);
+    }
+    
+    #[test]
+    fn test_parallel_ants_are_reproducible() {
+        let config = ACOConfig {
+            num_ants: 8,
+            max_iterations: 5,
+            q0: 0.0,
+            use_local_search: false,
+            ..Default::default()
+        };
+        
+        let first = AntColonyOptimization::new(create_test_instance(), config.clone()).run();
+        let second = AntColonyOptimization::new(create_test_instance(), config).run();
+        
+        assert_eq!(first.tour, second.tour);
+        assert_eq!(first.iterations, second.iterations);
+    }
 }
@@ -1,5 +1,10 @@
 use crate::instance::PDTSPInstance;
 use crate::solution::Solution;
+use crate::pool::{EdgeFrequencies, SolutionPool};
+use crate::heuristics::stopping::Deadline;
+use crate::heuristics::rng::RngFactory;
+use crate::heuristics::local_search::{LocalSearch, RelocationSearch, TwoOptSearch};
+use crate::heuristics::repair::{make_feasible, relocate_violations};
 use ordered_float::OrderedFloat;
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
@@ -7,6 +12,13 @@ use std::collections::HashSet;
 
 pub trait ConstructionHeuristic {
     fn construct(&self, instance: &PDTSPInstance) -> Solution;
+    
+    /// Construct, starting no further restart or heuristic once `deadline`
+    /// has passed. Single-pass heuristics always complete their tour.
+    fn construct_until(&self, instance: &PDTSPInstance, _deadline: Deadline) -> Solution {
+        self.construct(instance)
+    }
+    
     fn name(&self) -> &str;
 }
 
@@ -36,21 +48,21 @@ impl NearestNeighborHeuristic {
         }
     }
     
-    fn can_add_node(&self, instance: &PDTSPInstance, current_load: i32, node: usize) -> bool {
-        let new_load = current_load + instance.nodes[node].demand;
-        new_load >= 0 && new_load <= instance.capacity
+    fn can_add_node(&self, instance: &PDTSPInstance, tour: &[usize], current_load: i32, node: usize) -> bool {
+        instance.can_append(tour, current_load, node)
     }
     
     fn find_nearest(&self, 
         instance: &PDTSPInstance, 
-        current: usize, 
+        tour: &[usize],
         visited: &HashSet<usize>,
         current_load: i32,
         rng: &mut ChaCha8Rng
     ) -> Option<usize> {
+        let current = *tour.last().unwrap();
         let mut candidates: Vec<(usize, f64)> = (0..instance.dimension)
             .filter(|&n| !visited.contains(&n))
-            .filter(|&n| self.can_add_node(instance, current_load, n))
+            .filter(|&n| self.can_add_node(instance, tour, current_load, n))
             .map(|n| (n, instance.distance(current, n)))
             .collect();
         
@@ -79,23 +91,21 @@ impl Default for NearestNeighborHeuristic {
 
 impl ConstructionHeuristic for NearestNeighborHeuristic {
     fn construct(&self, instance: &PDTSPInstance) -> Solution {
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
         let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
         
         let mut tour = vec![0]; // Start at depot
         let mut visited = HashSet::new();
         visited.insert(0);
         
-        let mut current = 0;
         // Vehicle loads initial cargo and processes depot demand
         let mut current_load = instance.starting_load();
         
         while visited.len() < instance.dimension {
-            if let Some(next) = self.find_nearest(instance, current, &visited, current_load, &mut rng) {
+            if let Some(next) = self.find_nearest(instance, &tour, &visited, current_load, &mut rng) {
                 tour.push(next);
                 visited.insert(next);
                 current_load += instance.nodes[next].demand;
-                current = next;
             } else {
                 break;
             }
@@ -126,6 +136,11 @@ impl ConstructionHeuristic for NearestNeighborHeuristic {
 /// 
 /// Starts with a partial tour and repeatedly inserts the node
 /// that causes the minimum increase in tour length.
+/// 
+/// Cheapest insertion keeps candidate insertions in a priority queue and
+/// only re-evaluates what the last insertion touched (the two new edges, and
+/// lazily any popped entry whose edge vanished or became load-infeasible),
+/// which takes construction to roughly O(n² log n).
 pub struct GreedyInsertionHeuristic {
     pub farthest_insertion: bool,
 }
@@ -163,7 +178,7 @@ impl GreedyInsertionHeuristic {
     }
     
     /// Find best insertion for a node
-    fn find_best_insertion(&self, instance: &PDTSPInstance, tour: &[usize], node: usize) -> Option<(usize, f64)> {
+    pub(crate) fn find_best_insertion(&self, instance: &PDTSPInstance, tour: &[usize], node: usize) -> Option<(usize, f64)> {
         let mut best_pos = None;
         let mut best_cost = f64::INFINITY;
         
@@ -187,10 +202,161 @@ impl Default for GreedyInsertionHeuristic {
     }
 }
 
+/// Partial tour with cached prefix loads and suffix load extremes, so that
+/// the capacity feasibility of an insertion is checked in O(1)
+struct InsertionTour {
+    tour: Vec<usize>,
+    /// Position of each node in the tour (`usize::MAX` if not inserted)
+    pos_of: Vec<usize>,
+    /// Load after visiting `tour[i]`
+    loads: Vec<i32>,
+    suffix_max: Vec<i32>,
+    suffix_min: Vec<i32>,
+}
+
+impl InsertionTour {
+    fn new(instance: &PDTSPInstance, tour: Vec<usize>) -> Self {
+        let mut state = InsertionTour {
+            tour,
+            pos_of: vec![usize::MAX; instance.dimension],
+            loads: Vec::new(),
+            suffix_max: Vec::new(),
+            suffix_min: Vec::new(),
+        };
+        state.refresh(instance);
+        state
+    }
+    
+    fn refresh(&mut self, instance: &PDTSPInstance) {
+        let n = self.tour.len();
+        self.loads.clear();
+        let mut load = instance.starting_load();
+        for (i, &node) in self.tour.iter().enumerate() {
+            if i > 0 {
+                load += instance.nodes[node].demand;
+            }
+            self.loads.push(load);
+            self.pos_of[node] = i;
+        }
+        
+        self.suffix_max = vec![i32::MIN; n + 1];
+        self.suffix_min = vec![i32::MAX; n + 1];
+        for i in (0..n).rev() {
+            self.suffix_max[i] = self.suffix_max[i + 1].max(self.loads[i]);
+            self.suffix_min[i] = self.suffix_min[i + 1].min(self.loads[i]);
+        }
+    }
+    
+    /// Successor of `node` in the (cyclic) tour, if `node` is in the tour
+    fn next_of(&self, node: usize) -> Option<usize> {
+        let pos = self.pos_of[node];
+        (pos != usize::MAX).then(|| self.tour[(pos + 1) % self.tour.len()])
+    }
+    
+    /// Whether `node` can be inserted after position `pos` without a load violation
+    fn can_insert(&self, instance: &PDTSPInstance, pos: usize, node: usize) -> bool {
+        let d = instance.nodes[node].demand;
+        let load = self.loads[pos] + d;
+        if load < 0 || load > instance.capacity {
+            return false;
+        }
+        if self.suffix_max[pos + 1] != i32::MIN
+            && (self.suffix_max[pos + 1] + d > instance.capacity || self.suffix_min[pos + 1] + d < 0)
+        {
+            return false;
+        }
+        let next = self.tour[(pos + 1) % self.tour.len()];
+        if !instance.has_arc(self.tour[pos], node) || !instance.has_arc(node, next) {
+            return false;
+        }
+        if instance.is_multi_commodity() || instance.has_route_limit() {
+            let mut test_tour = self.tour.clone();
+            test_tour.insert(pos + 1, node);
+            return instance.commodity_loads_feasible(&test_tour) && instance.route_lengths_feasible(&test_tour);
+        }
+        true
+    }
+    
+    fn insertion_cost(&self, instance: &PDTSPInstance, prev: usize, next: usize, node: usize) -> f64 {
+        instance.distance(prev, node) + instance.distance(node, next) - instance.distance(prev, next)
+    }
+    
+    fn insert_after(&mut self, instance: &PDTSPInstance, prev: usize, node: usize) {
+        let pos = self.pos_of[prev];
+        self.tour.insert(pos + 1, node);
+        self.refresh(instance);
+    }
+}
+
+/// Min-heap entry of a candidate insertion: (cost, node, prev, next)
+type InsertionEntry = std::cmp::Reverse<(OrderedFloat<f64>, usize, usize, usize)>;
+
+impl GreedyInsertionHeuristic {
+    /// Cheapest insertion driven by a min-heap of (cost, node, prev, next)
+    /// entries, one per uninserted node and tour edge. Entries whose edge was
+    /// split by an earlier insertion are dropped when popped; entries that are
+    /// currently load-infeasible are parked and requeued after the next
+    /// insertion, since shifted loads may make them feasible again. The first
+    /// valid feasible entry popped is therefore the cheapest feasible insertion,
+    /// exactly as in a full rescan.
+    fn construct_cheapest(&self, instance: &PDTSPInstance, initial: usize) -> Vec<usize> {
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
+        
+        let mut state = InsertionTour::new(instance, vec![0, initial]);
+        let mut inserted = vec![false; instance.dimension];
+        inserted[0] = true;
+        inserted[initial] = true;
+        
+        let mut heap: BinaryHeap<InsertionEntry> = BinaryHeap::new();
+        for node in (1..instance.dimension).filter(|&n| !inserted[n]) {
+            for (prev, next) in [(0, initial), (initial, 0)] {
+                let cost = state.insertion_cost(instance, prev, next, node);
+                heap.push(Reverse((OrderedFloat(cost), node, prev, next)));
+            }
+        }
+        
+        let mut parked: Vec<InsertionEntry> = Vec::new();
+        while let Some(entry) = heap.pop() {
+            let Reverse((_, node, prev, next)) = entry;
+            if inserted[node] || state.next_of(prev) != Some(next) {
+                continue;
+            }
+            if !state.can_insert(instance, state.pos_of[prev], node) {
+                parked.push(entry);
+                continue;
+            }
+            
+            state.insert_after(instance, prev, node);
+            inserted[node] = true;
+            
+            // The split edge prev -> next is replaced by prev -> node -> next
+            for u in (1..instance.dimension).filter(|&u| !inserted[u]) {
+                for (p, q) in [(prev, node), (node, next)] {
+                    let cost = state.insertion_cost(instance, p, q, u);
+                    heap.push(Reverse((OrderedFloat(cost), u, p, q)));
+                }
+            }
+            heap.extend(parked.drain(..));
+        }
+        
+        state.tour
+    }
+}
+
 impl ConstructionHeuristic for GreedyInsertionHeuristic {
     fn construct(&self, instance: &PDTSPInstance) -> Solution {
-        let start = std::time::Instant::now();
-        
+        let start = web_time::Instant::now();
+        
+        if !self.farthest_insertion && instance.dimension > 1 {
+            let initial = (1..instance.dimension)
+                .min_by_key(|&n| OrderedFloat(instance.distance(0, n)))
+                .unwrap();
+            let tour = self.construct_cheapest(instance, initial);
+            let mut solution = Solution::from_tour(instance, tour, self.name());
+            solution.computation_time = start.elapsed().as_secs_f64();
+            return solution;
+        }
         
         let mut tour = vec![0];
         let mut unvisited: HashSet<usize> = (1..instance.dimension).collect();
@@ -289,7 +455,7 @@ impl Default for SavingsHeuristic {
 
 impl ConstructionHeuristic for SavingsHeuristic {
     fn construct(&self, instance: &PDTSPInstance) -> Solution {
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
         
         
         let mut savings: Vec<(usize, usize, f64)> = Vec::new();
@@ -416,18 +582,59 @@ impl ConstructionHeuristic for SavingsHeuristic {
 /// 
 /// Sorts nodes by polar angle from depot and constructs a tour
 /// following this order while respecting capacity.
+///
+/// The angular order is a cycle: with `rotations` above 1 the sweep is
+/// repeated from that many evenly spaced starting nodes (all of them when
+/// 0), in both directions, and the best tour is returned (feasible tours
+/// first), each candidate optionally polished by a first-improvement 2-opt.
+/// `new` is a single sweep, cheap enough for the multi-start portfolios;
+/// `rotated` is the thorough variant of the standalone algorithm.
 pub struct SweepHeuristic {
     /// Starting angle for the sweep
     pub start_angle: f64,
+    /// Number of rotations of the angular order tried (1 = single sweep,
+    /// 0 = every node as start)
+    pub rotations: usize,
+    /// Improve each candidate with 2-opt before comparing them
+    pub polish: bool,
 }
 
 impl SweepHeuristic {
+    /// Rotations tried by `rotated`
+    pub const DEFAULT_ROTATIONS: usize = 16;
+    
+    /// Single sweep from angle 0
     pub fn new() -> Self {
-        SweepHeuristic { start_angle: 0.0 }
+        SweepHeuristic {
+            start_angle: 0.0,
+            rotations: 1,
+            polish: false,
+        }
+    }
+    
+    /// `DEFAULT_ROTATIONS` sweeps in both directions, each polished by 2-opt
+    pub fn rotated() -> Self {
+        Self::new().with_rotations(Self::DEFAULT_ROTATIONS).with_polish(true)
     }
     
+    /// Single sweep starting at `angle`
     pub fn with_start_angle(angle: f64) -> Self {
-        SweepHeuristic { start_angle: angle }
+        SweepHeuristic {
+            start_angle: angle,
+            ..Self::new()
+        }
+    }
+    
+    /// Try `rotations` starting nodes in both directions (0 = all of them)
+    pub fn with_rotations(mut self, rotations: usize) -> Self {
+        self.rotations = rotations;
+        self
+    }
+    
+    /// Polish every candidate with 2-opt
+    pub fn with_polish(mut self, polish: bool) -> Self {
+        self.polish = polish;
+        self
     }
     
     /// Calculate polar angle from depot to node
@@ -444,6 +651,50 @@ impl SweepHeuristic {
             normalized
         }
     }
+    
+    /// Follow `order`, appending nodes while the load allows it; the skipped
+    /// nodes are then inserted at their cheapest feasible position (or their
+    /// cheapest position if none is feasible)
+    fn sweep_tour(&self, instance: &PDTSPInstance, order: &[usize]) -> Vec<usize> {
+        let mut tour = vec![0];
+        // Vehicle loads initial cargo and processes depot demand
+        let mut current_load = instance.starting_load();
+        let mut remaining: Vec<usize> = Vec::new();
+        
+        for &node in order {
+            if instance.can_append(&tour, current_load, node) {
+                tour.push(node);
+                current_load += instance.nodes[node].demand;
+            } else {
+                remaining.push(node);
+            }
+        }
+        
+        for node in remaining {
+            let mut best_feasible: Option<(f64, usize)> = None;
+            let mut best_any: Option<(f64, usize)> = None;
+            for pos in 1..=tour.len() {
+                let prev = tour[pos - 1];
+                let next = tour[pos % tour.len()];
+                let delta = instance.distance(prev, node) + instance.distance(node, next) - instance.distance(prev, next);
+                if best_any.is_none_or(|(d, _)| delta < d) {
+                    best_any = Some((delta, pos));
+                }
+                if best_feasible.is_none_or(|(d, _)| delta < d) {
+                    tour.insert(pos, node);
+                    if instance.is_feasible(&tour) {
+                        best_feasible = Some((delta, pos));
+                    }
+                    tour.remove(pos);
+                }
+            }
+            if let Some((_, pos)) = best_feasible.or(best_any) {
+                tour.insert(pos, node);
+            }
+        }
+        
+        tour
+    }
 }
 
 impl Default for SweepHeuristic {
@@ -454,49 +705,42 @@ impl Default for SweepHeuristic {
 
 impl ConstructionHeuristic for SweepHeuristic {
     fn construct(&self, instance: &PDTSPInstance) -> Solution {
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
         
         
         let mut nodes: Vec<usize> = (1..instance.dimension).collect();
         nodes.sort_by_key(|&n| OrderedFloat(self.polar_angle(instance, n)));
         
+        let n = nodes.len();
+        let offsets: Vec<usize> = if self.rotations == 0 || self.rotations >= n {
+            (0..n.max(1)).collect()
+        } else {
+            (0..self.rotations).map(|r| r * n / self.rotations).collect()
+        };
         
-        let mut tour = vec![0];
-        // Vehicle loads initial cargo and processes depot demand
-        let mut current_load = instance.starting_load();
-        let mut remaining: Vec<usize> = Vec::new();
-        
-        for node in nodes {
-            let new_load = current_load + instance.nodes[node].demand;
-            if new_load >= 0 && new_load <= instance.capacity {
-                tour.push(node);
-                current_load = new_load;
-            } else {
-                remaining.push(node);
-            }
-        }
-        
-        
-        for node in remaining {
-            let mut inserted = false;
-            for pos in 1..=tour.len() {
-                let mut test_tour = tour.clone();
-                test_tour.insert(pos, node);
-                
-                if instance.is_feasible(&test_tour) {
-                    tour.insert(pos, node);
-                    inserted = true;
-                    break;
+        let directions: &[bool] = if self.rotations == 1 { &[false] } else { &[false, true] };
+        let two_opt = TwoOptSearch::first_improvement().with_dont_look_bits(true);
+        let mut best: Option<Solution> = None;
+        for offset in offsets {
+            for &reversed in directions {
+                let mut order: Vec<usize> = nodes[offset..].iter().chain(&nodes[..offset]).copied().collect();
+                if reversed {
+                    order.reverse();
+                }
+                let mut candidate = Solution::from_tour(instance, self.sweep_tour(instance, &order), self.name());
+                if self.polish {
+                    two_opt.improve(instance, &mut candidate);
+                }
+                let better = best.as_ref().is_none_or(|b| {
+                    (candidate.feasible && !b.feasible) || (candidate.feasible == b.feasible && candidate.cost < b.cost - 1e-9)
+                });
+                if better {
+                    best = Some(candidate);
                 }
-            }
-            
-            if !inserted {
-                
-                tour.push(node);
             }
         }
         
-        let mut solution = Solution::from_tour(instance, tour, self.name());
+        let mut solution = best.unwrap_or_else(|| Solution::from_tour(instance, vec![0], self.name()));
         solution.computation_time = start.elapsed().as_secs_f64();
         solution
     }
@@ -558,6 +802,12 @@ impl RegretInsertionHeuristic {
                 }
             }
             
+            if feasible && (instance.is_multi_commodity() || instance.has_route_limit()) {
+                let mut test_tour = tour.to_vec();
+                test_tour.insert(pos + 1, node);
+                feasible = instance.commodity_loads_feasible(&test_tour) && instance.route_lengths_feasible(&test_tour);
+            }
+            
             if feasible {
                 costs.push((pos, cost));
             }
@@ -587,7 +837,7 @@ impl RegretInsertionHeuristic {
 
 impl ConstructionHeuristic for RegretInsertionHeuristic {
     fn construct(&self, instance: &PDTSPInstance) -> Solution {
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
         
         
         let mut tour = vec![0];
@@ -654,7 +904,7 @@ impl DeliverEarliestHeuristic {
 
 impl ConstructionHeuristic for DeliverEarliestHeuristic {
     fn construct(&self, instance: &PDTSPInstance) -> Solution {
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
         let mut tour = vec![0];
         let mut visited: std::collections::HashSet<usize> = std::collections::HashSet::new();
         visited.insert(0);
@@ -665,10 +915,7 @@ impl ConstructionHeuristic for DeliverEarliestHeuristic {
             // prefer feasible delivery nodes (demand < 0) closest to current
             let mut candidates: Vec<(usize, f64)> = (1..instance.dimension)
                 .filter(|&n| !visited.contains(&n))
-                .filter(|&n| {
-                    let nl = load + instance.nodes[n].demand;
-                    nl >= 0 && nl <= instance.capacity
-                })
+                .filter(|&n| instance.can_append(&tour, load, n))
                 .map(|n| (n, instance.distance(current, n)))
                 .collect();
 
@@ -720,7 +967,7 @@ impl PickupHighProfitHeuristic {
 
 impl ConstructionHeuristic for PickupHighProfitHeuristic {
     fn construct(&self, instance: &PDTSPInstance) -> Solution {
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
         let mut tour = vec![0];
         let mut visited: std::collections::HashSet<usize> = std::collections::HashSet::new();
         visited.insert(0);
@@ -730,10 +977,7 @@ impl ConstructionHeuristic for PickupHighProfitHeuristic {
         while visited.len() < instance.dimension {
             let mut candidates: Vec<(usize, f64)> = (1..instance.dimension)
                 .filter(|&n| !visited.contains(&n))
-                .filter(|&n| {
-                    let nl = load + instance.nodes[n].demand;
-                    nl >= 0 && nl <= instance.capacity
-                })
+                .filter(|&n| instance.can_append(&tour, load, n))
                 .map(|n| {
                     let dist = instance.distance(current, n);
                     let profit = instance.nodes[n].profit.max(1) as f64;
@@ -766,83 +1010,130 @@ impl ConstructionHeuristic for PickupHighProfitHeuristic {
 /// 
 /// First clusters nodes based on proximity and demand balance,
 /// then optimizes the visiting order within constraints.
+///
+/// Clustering is an iterated k-means in which each assignment also pays for
+/// the imbalance it adds to the net demand of the cluster, so that clusters
+/// mix pickups and deliveries and can be visited without exceeding the
+/// capacity. With `num_clusters` set to 0, k is chosen from the capacity
+/// tightness: about one cluster per twice the capacity of total demand.
 pub struct ClusterFirstHeuristic {
-    /// Number of clusters
+    /// Number of clusters (0 = chosen from the capacity tightness)
     pub num_clusters: usize,
+    /// Maximum number of k-means iterations
+    pub max_iterations: usize,
+    /// Weight of the demand-balance term against the spatial term
+    pub balance_weight: f64,
 }
 
 impl ClusterFirstHeuristic {
     pub fn new() -> Self {
-        ClusterFirstHeuristic { num_clusters: 4 }
+        ClusterFirstHeuristic {
+            num_clusters: 0,
+            max_iterations: 50,
+            balance_weight: 10.0,
+        }
     }
     
     pub fn with_clusters(num_clusters: usize) -> Self {
-        ClusterFirstHeuristic { num_clusters }
+        ClusterFirstHeuristic { num_clusters, ..Self::new() }
     }
     
-    /// Simple k-means clustering
+    /// Set the weight of the demand-balance term (0 = plain k-means)
+    pub fn with_balance_weight(mut self, balance_weight: f64) -> Self {
+        self.balance_weight = balance_weight;
+        self
+    }
+    
+    /// Set the maximum number of k-means iterations
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+    
+    /// Number of clusters used on `instance`
+    pub fn cluster_count(&self, instance: &PDTSPInstance) -> usize {
+        let n = instance.dimension.saturating_sub(1);
+        if self.num_clusters > 0 {
+            return self.num_clusters.min(n).max(1);
+        }
+        // A cluster with balanced net demand and at most twice the capacity
+        // of total demand can be visited without overloading the vehicle
+        let total: i64 = instance.nodes[1..].iter().map(|node| node.demand.unsigned_abs() as i64).sum();
+        let capacity = instance.capacity.max(1) as i64;
+        let k = (total + 2 * capacity - 1) / (2 * capacity);
+        (k as usize).clamp(1, n.max(1))
+    }
+    
+    /// Demand-balanced k-means over the customers
     fn cluster_nodes(&self, instance: &PDTSPInstance) -> Vec<Vec<usize>> {
         let n = instance.dimension - 1; // Exclude depot
-        let k = self.num_clusters.min(n);
-        
-        
-        let mut centroids: Vec<(f64, f64)> = Vec::new();
-        let step = n / k;
-        for i in 0..k {
-            let node_idx = 1 + i * step;
-            centroids.push((instance.nodes[node_idx].x, instance.nodes[node_idx].y));
+        if n == 0 {
+            return Vec::new();
         }
-        
-        let mut clusters = vec![Vec::new(); k];
-        
-        
-        for i in 1..instance.dimension {
-            let mut min_dist = f64::INFINITY;
-            let mut best_cluster = 0;
-            
-            for (c, &(cx, cy)) in centroids.iter().enumerate() {
-                let dx = instance.nodes[i].x - cx;
-                let dy = instance.nodes[i].y - cy;
-                let dist = (dx * dx + dy * dy).sqrt();
-                
-                if dist < min_dist {
-                    min_dist = dist;
-                    best_cluster = c;
-                }
+        let k = self.cluster_count(instance);
+        let point = |i: usize| (instance.nodes[i].x, instance.nodes[i].y);
+        let sq_dist = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2);
+        
+        // Farthest-first seeding, starting from the customer farthest from the depot
+        let mut centroids: Vec<(f64, f64)> = Vec::with_capacity(k);
+        let mut nearest = vec![f64::INFINITY; instance.dimension];
+        let mut next = (1..instance.dimension)
+            .max_by(|&a, &b| sq_dist(point(a), point(0)).total_cmp(&sq_dist(point(b), point(0))))
+            .unwrap();
+        while centroids.len() < k {
+            centroids.push(point(next));
+            for (i, d) in nearest.iter_mut().enumerate().skip(1) {
+                *d = d.min(sq_dist(point(i), point(next)));
             }
-            
-            clusters[best_cluster].push(i);
+            next = (1..instance.dimension).max_by(|&a, &b| nearest[a].total_cmp(&nearest[b])).unwrap();
         }
         
-        
-        for (c, cluster) in clusters.iter().enumerate() {
-            if !cluster.is_empty() {
-                let sum_x: f64 = cluster.iter().map(|&n| instance.nodes[n].x).sum();
-                let sum_y: f64 = cluster.iter().map(|&n| instance.nodes[n].y).sum();
-                centroids[c] = (sum_x / cluster.len() as f64, sum_y / cluster.len() as f64);
+        // The spatial term is scaled by the typical within-cluster spread,
+        // the balance term by the capacity
+        let mean = (1..instance.dimension).fold((0.0, 0.0), |acc, i| (acc.0 + point(i).0, acc.1 + point(i).1));
+        let mean = (mean.0 / n as f64, mean.1 / n as f64);
+        let spread = ((1..instance.dimension).map(|i| sq_dist(point(i), mean)).sum::<f64>() / (n * k) as f64).max(1e-9);
+        let capacity = instance.capacity.max(1) as f64;
+        
+        // Large demands are placed first, while the clusters are still balanced
+        let mut order: Vec<usize> = (1..instance.dimension).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(instance.nodes[i].demand.abs()));
+        
+        let mut assignment = vec![usize::MAX; instance.dimension];
+        for _ in 0..self.max_iterations.max(1) {
+            let mut net = vec![0.0; k];
+            let mut changed = false;
+            for &i in &order {
+                let demand = instance.nodes[i].demand as f64;
+                let best = (0..k)
+                    .map(|c| {
+                        let imbalance = ((net[c] + demand).powi(2) - net[c] * net[c]) / (capacity * capacity);
+                        (sq_dist(point(i), centroids[c]) / spread + self.balance_weight * imbalance, c)
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0))
+                    .map(|(_, c)| c)
+                    .unwrap();
+                net[best] += demand;
+                changed |= assignment[i] != best;
+                assignment[i] = best;
             }
-        }
-        
-        
-        clusters = vec![Vec::new(); k];
-        for i in 1..instance.dimension {
-            let mut min_dist = f64::INFINITY;
-            let mut best_cluster = 0;
             
-            for (c, &(cx, cy)) in centroids.iter().enumerate() {
-                let dx = instance.nodes[i].x - cx;
-                let dy = instance.nodes[i].y - cy;
-                let dist = (dx * dx + dy * dy).sqrt();
-                
-                if dist < min_dist {
-                    min_dist = dist;
-                    best_cluster = c;
+            for (c, centroid) in centroids.iter_mut().enumerate() {
+                let members: Vec<usize> = (1..instance.dimension).filter(|&i| assignment[i] == c).collect();
+                if !members.is_empty() {
+                    let sum = members.iter().fold((0.0, 0.0), |acc, &i| (acc.0 + point(i).0, acc.1 + point(i).1));
+                    *centroid = (sum.0 / members.len() as f64, sum.1 / members.len() as f64);
                 }
             }
-            
-            clusters[best_cluster].push(i);
+            if !changed {
+                break;
+            }
         }
         
+        let mut clusters = vec![Vec::new(); k];
+        for i in 1..instance.dimension {
+            clusters[assignment[i]].push(i);
+        }
         clusters
     }
     
@@ -874,7 +1165,7 @@ impl Default for ClusterFirstHeuristic {
 
 impl ConstructionHeuristic for ClusterFirstHeuristic {
     fn construct(&self, instance: &PDTSPInstance) -> Solution {
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
         
         let clusters = self.cluster_nodes(instance);
         
@@ -965,6 +1256,9 @@ impl ConstructionHeuristic for ClusterFirstHeuristic {
                         tour2.push(n);
                     }
                 }
+                if let Ok(repaired) = make_feasible(instance, &tour2) {
+                    tour2 = repaired;
+                }
 
                 let mut solution = Solution::from_tour(instance, tour2, self.name());
                 solution.computation_time = start.elapsed().as_secs_f64();
@@ -984,26 +1278,459 @@ impl ConstructionHeuristic for ClusterFirstHeuristic {
 
  
 
+/// Christofides-style Construction
+/// 
+/// Builds a minimum spanning tree (Prim), pairs its odd-degree vertices
+/// with a greedy minimum-weight matching, follows an Eulerian circuit of
+/// the tree plus matching and shortcuts repeated vertices. The capacity is
+/// ignored up to this point: the violations of the better orientation are
+/// then repaired by relocating nodes, each step taking the relocation that
+/// most reduces the load violation, and the feasible tour is finished by
+/// `RelocationSearch`.
+pub struct ChristofidesHeuristic {
+    /// Maximum number of repair relocations
+    pub max_repair_moves: usize,
+}
+
+impl ChristofidesHeuristic {
+    pub fn new() -> Self {
+        ChristofidesHeuristic {
+            max_repair_moves: 10_000,
+        }
+    }
+    
+    /// Parent of each node in a minimum spanning tree rooted at the depot
+    fn spanning_tree(&self, instance: &PDTSPInstance) -> Vec<usize> {
+        let n = instance.dimension;
+        let mut in_tree = vec![false; n];
+        let mut key = vec![f64::INFINITY; n];
+        let mut parent = vec![0; n];
+        key[0] = 0.0;
+        
+        for _ in 0..n {
+            let Some(u) = (0..n).filter(|&v| !in_tree[v]).min_by(|&a, &b| key[a].total_cmp(&key[b])) else {
+                break;
+            };
+            in_tree[u] = true;
+            for v in 0..n {
+                if !in_tree[v] {
+                    let d = instance.distance(u, v);
+                    if d < key[v] {
+                        key[v] = d;
+                        parent[v] = u;
+                    }
+                }
+            }
+        }
+        parent
+    }
+    
+    /// Greedy matching of the odd-degree vertices: pairs are taken by
+    /// increasing distance
+    fn odd_matching(&self, instance: &PDTSPInstance, degree: &[usize]) -> Vec<(usize, usize)> {
+        let odd: Vec<usize> = (0..degree.len()).filter(|&v| degree[v] % 2 == 1).collect();
+        let mut pairs: Vec<(f64, usize, usize)> = Vec::with_capacity(odd.len() * odd.len() / 2);
+        for (k, &u) in odd.iter().enumerate() {
+            for &v in &odd[k + 1..] {
+                pairs.push((instance.distance(u, v), u, v));
+            }
+        }
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+        
+        let mut matched = vec![false; degree.len()];
+        let mut matching = Vec::with_capacity(odd.len() / 2);
+        for (_, u, v) in pairs {
+            if !matched[u] && !matched[v] {
+                matched[u] = true;
+                matched[v] = true;
+                matching.push((u, v));
+            }
+        }
+        matching
+    }
+    
+    /// Eulerian circuit from the depot (Hierholzer) with repeated vertices
+    /// shortcut
+    fn shortcut_circuit(&self, n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (id, &(u, v)) in edges.iter().enumerate() {
+            adjacency[u].push(id);
+            adjacency[v].push(id);
+        }
+        let mut used = vec![false; edges.len()];
+        let mut next_edge = vec![0; n];
+        let mut stack = vec![0];
+        let mut circuit = Vec::with_capacity(edges.len() + 1);
+        
+        while let Some(&u) = stack.last() {
+            while next_edge[u] < adjacency[u].len() && used[adjacency[u][next_edge[u]]] {
+                next_edge[u] += 1;
+            }
+            if next_edge[u] == adjacency[u].len() {
+                circuit.push(u);
+                stack.pop();
+            } else {
+                let id = adjacency[u][next_edge[u]];
+                used[id] = true;
+                let (a, b) = edges[id];
+                stack.push(if a == u { b } else { a });
+            }
+        }
+        
+        let mut visited = vec![false; n];
+        circuit.into_iter().filter(|&v| !std::mem::replace(&mut visited[v], true)).collect()
+    }
+    
+    /// Relocate nodes while some relocation reduces the load violation
+    /// (ties broken by cost), for tours without intermediate depot visits
+    fn repair(&self, instance: &PDTSPInstance, tour: &mut Vec<usize>) {
+        relocate_violations(instance, tour, self.max_repair_moves);
+    }
+}
+
+impl Default for ChristofidesHeuristic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConstructionHeuristic for ChristofidesHeuristic {
+    fn construct(&self, instance: &PDTSPInstance) -> Solution {
+        let start = web_time::Instant::now();
+        let n = instance.dimension;
+        if n <= 3 {
+            let mut solution = Solution::from_tour(instance, (0..n).collect(), self.name());
+            solution.computation_time = start.elapsed().as_secs_f64();
+            return solution;
+        }
+        
+        let parent = self.spanning_tree(instance);
+        let mut edges: Vec<(usize, usize)> = (1..n).map(|v| (parent[v], v)).collect();
+        let mut degree = vec![0; n];
+        for &(u, v) in &edges {
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+        edges.extend(self.odd_matching(instance, &degree));
+        let forward = self.shortcut_circuit(n, &edges);
+        
+        // Keep the orientation with the lower load violation, then cost
+        let mut backward = forward.clone();
+        backward[1..].reverse();
+        let key = |tour: &Vec<usize>| (instance.load_violation(tour.iter().copied()), OrderedFloat(instance.tour_cost(tour)));
+        let mut tour = if key(&backward) < key(&forward) { backward } else { forward };
+        
+        self.repair(instance, &mut tour);
+        let mut solution = Solution::from_tour(instance, tour, self.name());
+        if solution.feasible {
+            RelocationSearch::new().improve(instance, &mut solution);
+        }
+        solution.computation_time = start.elapsed().as_secs_f64();
+        solution
+    }
+    
+    fn name(&self) -> &str {
+        "Christofides"
+    }
+}
+
+ 
+
+/// GRASP Construction with reactive alpha
+/// 
+/// Randomized greedy insertion: at each step the node to insert is drawn
+/// uniformly from a restricted candidate list (RCL) containing every node
+/// whose best insertion cost is within `alpha` of the cheapest one
+/// (`alpha = 0` is pure greedy, `alpha = 1` is pure random). The construction
+/// is restarted several times and, in reactive mode, the probability of each
+/// alpha value is periodically re-weighted by the quality of the tours it built.
+pub struct GraspConstruction {
+    /// Candidate alpha values
+    pub alphas: Vec<f64>,
+    /// Number of randomized constructions
+    pub restarts: usize,
+    /// Adapt alpha probabilities over restarts
+    pub reactive: bool,
+    /// Restarts between two probability updates
+    pub update_period: usize,
+    /// Amplification exponent of the reactive update
+    pub amplification: f64,
+    /// Random seed
+    pub seed: u64,
+}
+
+impl GraspConstruction {
+    pub fn new() -> Self {
+        GraspConstruction {
+            alphas: vec![0.05, 0.1, 0.2, 0.3, 0.5],
+            restarts: 20,
+            reactive: true,
+            update_period: 5,
+            amplification: 10.0,
+            seed: 42,
+        }
+    }
+    
+    pub fn with_params(restarts: usize, seed: u64) -> Self {
+        GraspConstruction {
+            restarts,
+            seed,
+            ..Self::new()
+        }
+    }
+    
+    /// Non-reactive GRASP with a single alpha value
+    pub fn fixed_alpha(alpha: f64, restarts: usize, seed: u64) -> Self {
+        GraspConstruction {
+            alphas: vec![alpha.clamp(0.0, 1.0)],
+            restarts,
+            reactive: false,
+            seed,
+            ..Self::new()
+        }
+    }
+    
+    /// Build one tour by randomized greedy insertion with the given alpha
+    fn build_tour(&self, instance: &PDTSPInstance, alpha: f64, rng: &mut ChaCha8Rng) -> Vec<usize> {
+        let greedy = GreedyInsertionHeuristic::new();
+        let mut tour = vec![0];
+        let mut unvisited: Vec<usize> = (1..instance.dimension).collect();
+        
+        while !unvisited.is_empty() {
+            let candidates: Vec<(usize, usize, f64)> = unvisited.iter()
+                .filter_map(|&node| greedy.find_best_insertion(instance, &tour, node).map(|(pos, cost)| (node, pos, cost)))
+                .collect();
+            if candidates.is_empty() {
+                break;
+            }
+            
+            let c_min = candidates.iter().map(|c| c.2).fold(f64::INFINITY, f64::min);
+            let c_max = candidates.iter().map(|c| c.2).fold(f64::NEG_INFINITY, f64::max);
+            let threshold = c_min + alpha * (c_max - c_min);
+            let rcl: Vec<&(usize, usize, f64)> = candidates.iter().filter(|c| c.2 <= threshold + 1e-9).collect();
+            
+            let &(node, pos, _) = rcl[rng.gen_range(0..rcl.len())];
+            tour.insert(pos + 1, node);
+            unvisited.retain(|&n| n != node);
+        }
+        
+        tour
+    }
+    
+    /// Reactive update: q_k = (best / avg_k)^amplification, normalized
+    fn update_probabilities(&self, probabilities: &mut [f64], best: f64, sums: &[f64], counts: &[usize]) {
+        let q: Vec<f64> = sums.iter().zip(counts)
+            .map(|(&sum, &count)| if count == 0 { 1.0 } else { (best / (sum / count as f64)).powf(self.amplification) })
+            .collect();
+        let total: f64 = q.iter().sum();
+        if total > 0.0 {
+            for (p, qk) in probabilities.iter_mut().zip(&q) {
+                *p = qk / total;
+            }
+        }
+    }
+}
+
+impl Default for GraspConstruction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConstructionHeuristic for GraspConstruction {
+    fn construct(&self, instance: &PDTSPInstance) -> Solution {
+        self.construct_until(instance, Deadline::none())
+    }
+    
+    /// The first restart always runs
+    fn construct_until(&self, instance: &PDTSPInstance, deadline: Deadline) -> Solution {
+        let start = web_time::Instant::now();
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        
+        let k = self.alphas.len().max(1);
+        let alphas = if self.alphas.is_empty() { vec![0.2] } else { self.alphas.clone() };
+        let mut probabilities = vec![1.0 / k as f64; k];
+        let mut sums = vec![0.0; k];
+        let mut counts = vec![0usize; k];
+        
+        let mut best: Option<Solution> = None;
+        let mut restarts = 0;
+        
+        for restart in 0..self.restarts.max(1) {
+            if restart > 0 && deadline.expired() {
+                break;
+            }
+            restarts += 1;
+            let r: f64 = rng.gen();
+            let mut acc = 0.0;
+            let idx = probabilities.iter()
+                .position(|&p| { acc += p; r < acc })
+                .unwrap_or(k - 1);
+            
+            let tour = self.build_tour(instance, alphas[idx], &mut rng);
+            let complete = tour.len() == instance.dimension;
+            let mut solution = Solution::from_tour(instance, tour, self.name());
+            // A dead end leaves nodes unvisited; such a tour is not a solution
+            solution.feasible &= complete;
+            
+            if complete && solution.feasible {
+                sums[idx] += solution.cost;
+                counts[idx] += 1;
+            }
+            
+            // Prefer complete feasible tours, then longer partial tours
+            let better = match &best {
+                None => true,
+                Some(b) => {
+                    let key = |s: &Solution| (s.feasible, s.tour.len());
+                    key(&solution) > key(b) || (key(&solution) == key(b) && solution.cost < b.cost)
+                }
+            };
+            if better {
+                best = Some(solution);
+            }
+            
+            if self.reactive && (restart + 1) % self.update_period.max(1) == 0 {
+                if let Some(b) = best.as_ref().filter(|b| b.feasible) {
+                    self.update_probabilities(&mut probabilities, b.cost, &sums, &counts);
+                }
+            }
+        }
+        
+        let mut solution = best.unwrap_or_default();
+        solution.computation_time = start.elapsed().as_secs_f64();
+        solution.iterations = Some(restarts);
+        solution
+    }
+    
+    fn name(&self) -> &str {
+        "GRASP"
+    }
+}
+
+ 
+
+/// Edge-Frequency Construction
+/// 
+/// Randomized nearest neighbor biased toward the edges of elite tours: the
+/// next node is drawn among the capacity-feasible ones with probability
+/// proportional to `(1 + weight * f) / d`, where `f` is the fraction of the
+/// elite tours using the edge and `d` its length. Restarting from such tours
+/// keeps the structure shared by good solutions while varying the rest.
+pub struct EdgeFrequencyHeuristic {
+    /// Edge frequencies in the elite tours
+    pub frequency: EdgeFrequencies,
+    /// Bias toward frequent edges (0 = randomized nearest neighbor)
+    pub weight: f64,
+    pub seed: u64,
+}
+
+impl EdgeFrequencyHeuristic {
+    pub fn new(frequency: EdgeFrequencies, seed: u64) -> Self {
+        EdgeFrequencyHeuristic { frequency, weight: 10.0, seed }
+    }
+    
+    /// Frequencies of the members of an elite pool
+    pub fn from_pool(pool: &SolutionPool, dimension: usize, seed: u64) -> Self {
+        Self::new(pool.edge_frequencies(dimension), seed)
+    }
+    
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+    
+}
+
+impl ConstructionHeuristic for EdgeFrequencyHeuristic {
+    fn construct(&self, instance: &PDTSPInstance) -> Solution {
+        let start = web_time::Instant::now();
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        
+        let mut tour = vec![0];
+        let mut visited = vec![false; instance.dimension];
+        visited[0] = true;
+        let mut load = instance.starting_load();
+        
+        while tour.len() < instance.dimension {
+            let current = *tour.last().unwrap();
+            let candidates: Vec<(usize, f64)> = (1..instance.dimension)
+                .filter(|&n| !visited[n] && instance.can_append(&tour, load, n))
+                .map(|n| {
+                    let bias = 1.0 + self.weight * self.frequency.get(current, n);
+                    (n, bias / instance.distance(current, n).max(1e-9))
+                })
+                .collect();
+            if candidates.is_empty() {
+                break;
+            }
+            let total: f64 = candidates.iter().map(|&(_, score)| score).sum();
+            let mut draw = rng.gen::<f64>() * total;
+            let next = candidates.iter()
+                .find(|&&(_, score)| {
+                    draw -= score;
+                    draw <= 0.0
+                })
+                .map_or(candidates[candidates.len() - 1].0, |&(n, _)| n);
+            tour.push(next);
+            visited[next] = true;
+            load += instance.nodes[next].demand;
+        }
+        
+        // Nodes left out when no feasible extension exists make the tour partial
+        let mut solution = Solution::from_tour(instance, tour, self.name());
+        solution.computation_time = start.elapsed().as_secs_f64();
+        solution
+    }
+    
+    fn name(&self) -> &str {
+        "EdgeFrequency"
+    }
+}
+
+ 
+
 /// Multi-Start Construction
 /// 
 /// Runs multiple construction heuristics and returns the best result.
 pub struct MultiStartConstruction {
     heuristics: Vec<Box<dyn ConstructionHeuristic + Send + Sync>>,
+    /// No further heuristic is started once passed (the first always runs)
+    pub deadline: Deadline,
 }
 
 impl MultiStartConstruction {
     pub fn new() -> Self {
         MultiStartConstruction {
             heuristics: Vec::new(),
+            deadline: Deadline::none(),
         }
     }
     
     pub fn with_all_heuristics() -> Self {
+        Self::standard([1, 2, 3, 42, 42, 42])
+    }
+    
+    /// All heuristics, with the randomized ones seeded from `rng`
+    pub fn with_all_heuristics_seeded(rng: &RngFactory) -> Self {
+        Self::standard([
+            rng.derive_indexed("nn-randomized", 0),
+            rng.derive_indexed("nn-randomized", 1),
+            rng.derive_indexed("nn-randomized", 2),
+            rng.derive("deliver-earliest"),
+            rng.derive("pickup-high-profit"),
+            rng.derive("grasp"),
+        ])
+    }
+    
+    /// Seeds of the randomized heuristics, in order: three randomized nearest
+    /// neighbours, deliver-earliest, pickup-high-profit and GRASP
+    fn standard(seeds: [u64; 6]) -> Self {
         let heuristics: Vec<Box<dyn ConstructionHeuristic + Send + Sync>> = vec![
             Box::new(NearestNeighborHeuristic::new()),
-            Box::new(NearestNeighborHeuristic::randomized(1)),
-            Box::new(NearestNeighborHeuristic::randomized(2)),
-            Box::new(NearestNeighborHeuristic::randomized(3)),
+            Box::new(NearestNeighborHeuristic::randomized(seeds[0])),
+            Box::new(NearestNeighborHeuristic::randomized(seeds[1])),
+            Box::new(NearestNeighborHeuristic::randomized(seeds[2])),
             Box::new(GreedyInsertionHeuristic::new()),
             Box::new(GreedyInsertionHeuristic::farthest()),
             Box::new(SavingsHeuristic::new()),
@@ -1017,11 +1744,19 @@ impl MultiStartConstruction {
             Box::new(ClusterFirstHeuristic::new()),
             Box::new(ClusterFirstHeuristic::with_clusters(3)),
             Box::new(ClusterFirstHeuristic::with_clusters(5)),
-            Box::new(DeliverEarliestHeuristic::new()),
-            Box::new(PickupHighProfitHeuristic::new()),
+            Box::new(ChristofidesHeuristic::new()),
+            Box::new(DeliverEarliestHeuristic::with_seed(seeds[3])),
+            Box::new(PickupHighProfitHeuristic::with_seed(seeds[4])),
+            Box::new(GraspConstruction::with_params(5, seeds[5])),
         ];
         
-        MultiStartConstruction { heuristics }
+        MultiStartConstruction { heuristics, deadline: Deadline::none() }
+    }
+    
+    /// Stop starting heuristics once `deadline` has passed
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = deadline;
+        self
     }
     
     pub fn add_heuristic<H: ConstructionHeuristic + Send + Sync + 'static>(&mut self, h: H) {
@@ -1037,12 +1772,20 @@ impl Default for MultiStartConstruction {
 
 impl ConstructionHeuristic for MultiStartConstruction {
     fn construct(&self, instance: &PDTSPInstance) -> Solution {
-        let start = std::time::Instant::now();
+        self.construct_until(instance, Deadline::none())
+    }
+    
+    fn construct_until(&self, instance: &PDTSPInstance, deadline: Deadline) -> Solution {
+        let start = web_time::Instant::now();
+        let deadline = self.deadline.earliest(deadline);
         
         let mut best_solution = Solution::new();
         
-        for heuristic in &self.heuristics {
-            let solution = heuristic.construct(instance);
+        for (k, heuristic) in self.heuristics.iter().enumerate() {
+            if k > 0 && deadline.expired() {
+                break;
+            }
+            let solution = heuristic.construct_until(instance, deadline);
 
             // Ignore trivial depot-only solutions; prefer non-trivial feasible starts
             if solution.feasible && solution.cost < best_solution.cost && solution.tour.len() > 1 {
@@ -1113,7 +1856,7 @@ mod tests {
     use super::*;
     
     fn create_test_instance() -> PDTSPInstance {
-        use crate::instance::CostFunction;
+        use crate::instance::DistanceMatrix;
         
         let nodes = vec![
             crate::instance::Node::new(0, 0.0, 0.0, 0, 0),
@@ -1122,25 +1865,14 @@ mod tests {
             crate::instance::Node::new(3, 1.0, 1.0, 0, 0),
         ];
         
-        let mut instance = PDTSPInstance {
-            cost_function: CostFunction::Distance,
-            alpha: 0.1,
-            beta: 0.5,
-            name: "test".to_string(),
-            comment: "test instance".to_string(),
-            dimension: 4,
-            capacity: 10,
-            nodes: nodes.clone(),
-            distance_matrix: Vec::new(),
-            return_depot_demand: 0,
-        };
+        let mut instance = PDTSPInstance::from_parts("test", 10, nodes.clone(), DistanceMatrix::default());
         
-        instance.distance_matrix = vec![vec![0.0; 4]; 4];
+        instance.distance_matrix = DistanceMatrix::new(4);
         for i in 0..4 {
             for j in 0..4 {
                 let dx = instance.nodes[i].x - instance.nodes[j].x;
                 let dy = instance.nodes[i].y - instance.nodes[j].y;
-                instance.distance_matrix[i][j] = (dx * dx + dy * dy).sqrt();
+                instance.distance_matrix.set(i, j, (dx * dx + dy * dy).sqrt());
             }
         }
         
@@ -1165,4 +1897,201 @@ mod tests {
         
         assert_eq!(solution.tour.len(), 4);
     }
+    
+    #[test]
+    fn test_cheapest_insertion_matches_rescan() {
+        use crate::instance::{DistanceMatrix, DistanceMode, Node};
+        
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let nodes: Vec<Node> = (0..40)
+            .map(|i| {
+                let demand = if i == 0 { 0 } else { rng.gen_range(-4..=4) };
+                Node::new(i, rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), demand, 0)
+            })
+            .collect();
+        let mut instance = create_test_instance();
+        instance.dimension = nodes.len();
+        instance.capacity = 8;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        instance.nodes = nodes;
+        
+        // Reference: rescan every node and position at each step
+        let greedy = GreedyInsertionHeuristic::new();
+        let initial = (1..instance.dimension)
+            .min_by_key(|&n| OrderedFloat(instance.distance(0, n)))
+            .unwrap();
+        let mut tour = vec![0, initial];
+        loop {
+            let best = (1..instance.dimension)
+                .filter(|n| !tour.contains(n))
+                .filter_map(|n| greedy.find_best_insertion(&instance, &tour, n).map(|(pos, cost)| (n, pos, cost)))
+                .min_by_key(|&(_, _, cost)| OrderedFloat(cost));
+            match best {
+                Some((node, pos, _)) => tour.insert(pos + 1, node),
+                None => break,
+            }
+        }
+        
+        let solution = greedy.construct(&instance);
+        assert_eq!(solution.tour, tour);
+    }
+    
+    #[test]
+    fn test_grasp_construction() {
+        let instance = create_test_instance();
+        let solution = GraspConstruction::with_params(6, 7).construct(&instance);
+        assert_eq!(solution.tour.len(), 4);
+        assert!(solution.feasible);
+        
+        // Fully random insertion order still yields a complete tour
+        let random = GraspConstruction::fixed_alpha(1.0, 3, 3).construct(&instance);
+        assert_eq!(random.tour.len(), 4);
+        assert!(random.feasible);
+    }
+    
+    #[test]
+    fn test_cluster_first_balances_net_demand() {
+        use crate::instance::{DistanceMatrix, DistanceMode, Node};
+        
+        // Pickups on the left half, deliveries on the right half
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        let nodes: Vec<Node> = (0..61)
+            .map(|i| {
+                let x: f64 = rng.gen_range(0.0..100.0);
+                let demand = if i == 0 { 0 } else if x < 50.0 { 3 } else { -3 };
+                Node::new(i, x, rng.gen_range(0.0..100.0), demand, 0)
+            })
+            .collect();
+        let mut instance = create_test_instance();
+        instance.dimension = nodes.len();
+        instance.capacity = 12;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        instance.nodes = nodes;
+        
+        let heuristic = ClusterFirstHeuristic::new();
+        assert_eq!(heuristic.cluster_count(&instance), 8);
+        let max_imbalance = |clusters: &[Vec<usize>]| clusters.iter()
+            .map(|c| c.iter().map(|&i| instance.nodes[i].demand).sum::<i32>().abs())
+            .max()
+            .unwrap();
+        let balanced = heuristic.cluster_nodes(&instance);
+        let geometric = ClusterFirstHeuristic::new().with_balance_weight(0.0).cluster_nodes(&instance);
+        
+        let mut members: Vec<usize> = balanced.iter().flatten().copied().collect();
+        members.sort();
+        assert_eq!(members, (1..61).collect::<Vec<_>>());
+        assert!(max_imbalance(&balanced) < max_imbalance(&geometric));
+        let strict = ClusterFirstHeuristic::new().with_balance_weight(50.0).cluster_nodes(&instance);
+        assert!(max_imbalance(&strict) <= instance.capacity);
+        
+        let solution = heuristic.construct(&instance);
+        assert!(solution.is_complete(&instance));
+    }
+    
+    #[test]
+    fn test_sweep_rotations_never_worse_than_single_sweep() {
+        use crate::instance::{DistanceMatrix, DistanceMode, Node};
+        
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let nodes: Vec<Node> = (0..41)
+            .map(|i| {
+                let demand = if i == 0 { 0 } else { rng.gen_range(-3..=3) };
+                Node::new(i, rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0), demand, 0)
+            })
+            .collect();
+        let mut instance = create_test_instance();
+        instance.dimension = nodes.len();
+        instance.capacity = 6;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        instance.nodes = nodes;
+        
+        let single = SweepHeuristic::with_start_angle(0.0).construct(&instance);
+        let all = SweepHeuristic::new().with_rotations(0).with_polish(true).construct(&instance);
+        assert!(all.is_complete(&instance));
+        assert!(all.feasible || !single.feasible);
+        if all.feasible == single.feasible {
+            assert!(all.cost <= single.cost + 1e-9);
+        }
+    }
+    
+    #[test]
+    fn test_christofides_repairs_capacity() {
+        use crate::instance::{DistanceMatrix, DistanceMode, Node};
+        
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        let mut nodes: Vec<Node> = (0..61)
+            .map(|i| {
+                let demand = if i == 0 { 0 } else { rng.gen_range(-4..=4) };
+                Node::new(i, rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), demand, 0)
+            })
+            .collect();
+        // Depot balances the customers, as in the Mosheiov instances
+        nodes[0].demand = -nodes.iter().map(|node| node.demand).sum::<i32>();
+        let mut instance = create_test_instance();
+        instance.dimension = nodes.len();
+        instance.capacity = 8;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        instance.nodes = nodes;
+        
+        let heuristic = ChristofidesHeuristic::new();
+        let parent = heuristic.spanning_tree(&instance);
+        let mut edges: Vec<(usize, usize)> = (1..61).map(|v| (parent[v], v)).collect();
+        let mut degree = vec![0; 61];
+        for &(u, v) in &edges {
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+        edges.extend(heuristic.odd_matching(&instance, &degree));
+        let tour = heuristic.shortcut_circuit(61, &edges);
+        assert!(instance.load_violation(tour.iter().copied()) > 0);
+        
+        let solution = heuristic.construct(&instance);
+        assert!(solution.is_complete(&instance));
+        assert!(solution.feasible);
+    }
+    
+    #[test]
+    fn test_edge_frequency_follows_elite_edges() {
+        use crate::instance::{DistanceMatrix, DistanceMode, Node};
+        use crate::heuristics::local_search::IteratedLocalSearch;
+        
+        // Customers on a circle, the depot at its center
+        let nodes: Vec<Node> = (0..13)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::TAU / 12.0;
+                let (x, y) = if i == 0 { (0.0, 0.0) } else { (10.0 * angle.cos(), 10.0 * angle.sin()) };
+                Node::new(i, x, y, if i == 0 { 0 } else if i % 2 == 1 { 1 } else { -1 }, 0)
+            })
+            .collect();
+        let mut instance = create_test_instance();
+        instance.dimension = nodes.len();
+        instance.capacity = 2;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        instance.nodes = nodes;
+        
+        let elite: Vec<usize> = (0..13).collect();
+        let mut pool = SolutionPool::new(3);
+        pool.offer(&Solution::from_tour(&instance, elite.clone(), "elite"));
+        let frequency = pool.edge_frequencies(13);
+        assert_eq!(frequency.get(6, 5), 1.0);
+        assert_eq!(frequency.get(5, 7), 0.0);
+        assert_eq!(frequency.len(), 13);
+        
+        // Without bias, the tours stray from the elite; with a strong bias,
+        // they mostly follow it
+        let strays = |weight: f64| -> usize {
+            (0..20).map(|seed| {
+                let solution = EdgeFrequencyHeuristic::from_pool(&pool, 13, seed).with_weight(weight).construct(&instance);
+                assert!(solution.feasible && solution.is_complete(&instance));
+                crate::pool::broken_pairs_distance(&solution.tour, &elite)
+            }).sum()
+        };
+        assert!(strays(1000.0) < strays(0.0));
+        
+        let mut ils = IteratedLocalSearch::with_params(2, 30, 30);
+        ils.restart_after = 3;
+        let mut solution = NearestNeighborHeuristic::new().construct(&instance);
+        ils.improve(&instance, &mut solution);
+        assert!(solution.feasible);
+    }
 }
@@ -16,15 +16,36 @@ use crate::heuristics::construction::{
     SweepHeuristic,
     RegretInsertionHeuristic,
     ClusterFirstHeuristic,
+    EdgeFrequencyHeuristic,
     MultiStartConstruction,
 };
-use crate::heuristics::local_search::{LocalSearch, VND};
+use crate::pool::edge_frequencies;
+use crate::heuristics::local_search::{LocalSearch, VNDOperator, VND};
 use crate::heuristics::profit_density::ProfitDensityHeuristic;
+use crate::heuristics::rng::RngFactory;
+use crate::heuristics::repair::make_feasible_within;
+use crate::progress::{notify, ProgressEvent, ProgressHandler};
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 use ordered_float::OrderedFloat;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
+/// Relocations and ejections allowed to repair an infeasible offspring;
+/// children needing more are discarded rather than paying an O(n³) repair
+const REPAIR_MOVES: usize = 16;
+const REPAIR_EJECTIONS: usize = 8;
+
+/// VND applied to individuals: the standard operators without depot
+/// reinsertion, since the crossovers work on permutations of the nodes and
+/// an intermediate depot visit would make a tour longer than `dimension`
+fn permutation_vnd() -> VND {
+    let operators: Vec<VNDOperator> = VNDOperator::STANDARD.into_iter()
+        .filter(|&op| op != VNDOperator::DepotReinsertion)
+        .collect();
+    VND::with_operators(&operators)
+}
+
 /// Individual in the genetic algorithm population
 #[derive(Debug, Clone)]
 pub struct Individual {
@@ -45,7 +66,7 @@ impl Individual {
         let travel_cost = instance.tour_cost(&tour);
         let total_profit = instance.tour_profit(&tour);
         let objective = total_profit as f64 - travel_cost;
-        let feasible = instance.is_feasible(&tour);
+        let feasible = instance.is_feasible_strict(&tour);
         let fitness = if feasible { objective } else { objective - 1e9 }; // heavy penalty
 
         Individual {
@@ -63,20 +84,30 @@ impl Individual {
 }
 
 /// Crossover operator types
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum CrossoverType {
     /// Order Crossover (OX)
     OrderCrossover,
     /// Partially Mapped Crossover (PMX)
+    #[serde(rename = "pmx")]
     PMX,
     /// Edge Recombination Crossover
     EdgeRecombination,
     /// Cycle Crossover
     CycleCrossover,
+    /// Load-feasible segment of parent 1, completed by capacity-checked
+    /// cheapest insertion of the remaining nodes in parent 2's order
+    FeasibleInsertion,
+    /// Edge Assembly Crossover: parent 1 with the edges of an AB-cycle
+    /// swapped for parent 2's, subtours merged with capacity checks
+    #[serde(rename = "eax")]
+    EAX,
 }
 
 /// Mutation operator types
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum MutationType {
     /// Swap two random nodes
     Swap,
@@ -91,7 +122,8 @@ pub enum MutationType {
 }
 
 /// Selection method types
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum SelectionType {
     /// Tournament selection
     Tournament,
@@ -134,6 +166,16 @@ pub struct GAConfig {
     pub time_limit: f64,
     /// Adaptive mutation (increase when stuck)
     pub adaptive_mutation: bool,
+    /// Survivor selection by biased fitness (cost rank + diversity rank)
+    /// over parents and offspring, removing clones first
+    pub biased_fitness: bool,
+    /// Number of closest individuals used to measure an individual's diversity
+    pub n_closest: usize,
+    /// Record the best tour at each improvement in `Solution::snapshots`
+    pub record_snapshots: bool,
+    /// Worst individuals replaced every generation by edge-frequency
+    /// constructions over the elite (0 = none)
+    pub immigrants: usize,
 }
 
 impl Default for GAConfig {
@@ -154,8 +196,175 @@ impl Default for GAConfig {
             seed: 42,
             time_limit: 60.0,
             adaptive_mutation: true,
+            biased_fitness: true,
+            n_closest: 3,
+            record_snapshots: false,
+            immigrants: 0,
+        }
+    }
+}
+
+/// Broken-pairs distance between two tours: the fraction of the edges of `a`
+/// (undirected, closing arc included) that do not appear in `b`
+pub fn broken_pairs_distance(a: &[usize], b: &[usize]) -> f64 {
+    if a.is_empty() {
+        return 0.0;
+    }
+    let size = a.iter().chain(b).max().map_or(0, |&m| m + 1);
+    let mut succ = vec![usize::MAX; size];
+    let mut pred = vec![usize::MAX; size];
+    for k in 0..b.len() {
+        let next = b[(k + 1) % b.len()];
+        succ[b[k]] = next;
+        pred[next] = b[k];
+    }
+
+    let broken = (0..a.len())
+        .filter(|&k| {
+            let (x, y) = (a[k], a[(k + 1) % a.len()]);
+            succ[x] != y && pred[x] != y
+        })
+        .count();
+    broken as f64 / a.len() as f64
+}
+
+/// E-sets tried per EAX crossover
+const EAX_TRIES: usize = 5;
+/// Near neighbors scanned when merging EAX subtours
+const EAX_NEIGHBORS: usize = 10;
+/// Cheapest exchanges checked for load feasibility on the last EAX merge
+const EAX_REPAIR_CANDIDATES: usize = 20;
+
+/// Whether `tour` visits each of the `n` nodes exactly once
+fn is_permutation(tour: &[usize], n: usize) -> bool {
+    let mut seen = vec![false; n];
+    tour.len() == n && tour.iter().all(|&v| v < n && !std::mem::replace(&mut seen[v], true))
+}
+
+/// Both neighbors of each node in a tour
+fn tour_adjacency(tour: &[usize]) -> Vec<[usize; 2]> {
+    let n = tour.len();
+    let mut adjacency = vec![[0; 2]; n];
+    for k in 0..n {
+        adjacency[tour[k]] = [tour[(k + n - 1) % n], tour[(k + 1) % n]];
+    }
+    adjacency
+}
+
+/// AB-cycles of two tours over the same nodes: closed walks alternating an
+/// edge of `a` not in `b` and an edge of `b` not in `a`, which together use
+/// each such edge once. A cycle `[v0, v1, ..., v2m-1]` has the `a` edges
+/// (v0, v1), (v2, v3), ... and the `b` edges (v1, v2), ..., (v2m-1, v0).
+fn ab_cycles(a: &[usize], b: &[usize], rng: &mut ChaCha8Rng) -> Vec<Vec<usize>> {
+    let n = a.len();
+    let (adj_a, adj_b) = (tour_adjacency(a), tour_adjacency(b));
+    // Remaining edges of each side, shared edges excluded
+    let mut remaining: [Vec<Vec<usize>>; 2] = [
+        (0..n).map(|v| adj_a[v].iter().copied().filter(|w| !adj_b[v].contains(w)).collect()).collect(),
+        (0..n).map(|v| adj_b[v].iter().copied().filter(|w| !adj_a[v].contains(w)).collect()).collect(),
+    ];
+    
+    let mut cycles = Vec::new();
+    loop {
+        let starts: Vec<usize> = (0..n).filter(|&v| !remaining[0][v].is_empty()).collect();
+        let Some(&start) = starts.choose(rng) else {
+            break;
+        };
+        let mut path = vec![start];
+        loop {
+            // Even positions leave by an `a` edge, odd ones by a `b` edge
+            let side = (path.len() - 1) % 2;
+            let v = path[path.len() - 1];
+            if remaining[side][v].is_empty() {
+                break;
+            }
+            let k = rng.gen_range(0..remaining[side][v].len());
+            let w = remaining[side][v].swap_remove(k);
+            if let Some(k) = remaining[side][w].iter().position(|&x| x == v) {
+                remaining[side][w].swap_remove(k);
+            }
+            path.push(w);
+            if side == 1 {
+                let closing = (0..path.len() - 1).step_by(2).find(|&j| path[j] == w);
+                if let Some(j) = closing {
+                    cycles.push(path[j..path.len() - 1].to_vec());
+                    path.truncate(j + 1);
+                    if path.len() == 1 && remaining[0][start].is_empty() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    cycles
+}
+
+/// Replace the `a` edges of an AB-cycle by its `b` edges
+fn apply_e_set(adjacency: &mut [[usize; 2]], cycle: &[usize]) {
+    let m = cycle.len();
+    for k in (0..m).step_by(2) {
+        let (x, y) = (cycle[k], cycle[k + 1]);
+        remove_neighbor(adjacency, x, y);
+        remove_neighbor(adjacency, y, x);
+    }
+    for k in (1..m).step_by(2) {
+        let (x, y) = (cycle[k], cycle[(k + 1) % m]);
+        add_neighbor(adjacency, x, y);
+        add_neighbor(adjacency, y, x);
+    }
+}
+
+fn remove_neighbor(adjacency: &mut [[usize; 2]], v: usize, w: usize) {
+    if let Some(slot) = adjacency[v].iter_mut().find(|x| **x == w) {
+        *slot = usize::MAX;
+    }
+}
+
+fn add_neighbor(adjacency: &mut [[usize; 2]], v: usize, w: usize) {
+    if let Some(slot) = adjacency[v].iter_mut().find(|x| **x == usize::MAX) {
+        *slot = w;
+    }
+}
+
+/// Replace edges (u, u2) and (v, v2) by (u, v) and (u2, v2)
+fn exchange(adjacency: &mut [[usize; 2]], u: usize, u2: usize, v: usize, v2: usize) {
+    for (x, old, new) in [(u, u2, v), (u2, u, v2), (v, v2, u), (v2, v, u2)] {
+        remove_neighbor(adjacency, x, old);
+        add_neighbor(adjacency, x, new);
+    }
+}
+
+/// Subtour of each node in a 2-regular graph, and the number of subtours
+fn subtour_labels(adjacency: &[[usize; 2]]) -> (Vec<usize>, usize) {
+    let mut labels = vec![usize::MAX; adjacency.len()];
+    let mut count = 0;
+    for start in 0..adjacency.len() {
+        if labels[start] != usize::MAX {
+            continue;
+        }
+        let mut stack = vec![start];
+        while let Some(v) = stack.pop() {
+            if labels[v] == usize::MAX {
+                labels[v] = count;
+                stack.extend(adjacency[v].iter().copied().filter(|&w| w < adjacency.len()));
+            }
         }
+        count += 1;
+    }
+    (labels, count)
+}
+
+/// Tour of a Hamiltonian cycle, from the depot toward its first neighbor
+fn adjacency_tour(adjacency: &[[usize; 2]]) -> Vec<usize> {
+    let mut tour = vec![0];
+    let (mut previous, mut current) = (0, adjacency[0][0]);
+    while current != 0 && tour.len() < adjacency.len() {
+        tour.push(current);
+        let next = if adjacency[current][0] == previous { adjacency[current][1] } else { adjacency[current][0] };
+        previous = current;
+        current = next;
     }
+    tour
 }
 
 /// Genetic Algorithm implementation
@@ -169,6 +378,10 @@ pub struct GeneticAlgorithm {
     no_improve_count: usize,
     current_mutation_prob: f64,
     time_limit: f64,
+    start_time: web_time::Instant,
+    on_progress: Option<ProgressHandler>,
+    /// Nearest neighbors of each node, computed on the first EAX crossover
+    neighbors: Vec<Vec<usize>>,
 }
 
 impl GeneticAlgorithm {
@@ -187,19 +400,41 @@ impl GeneticAlgorithm {
             no_improve_count: 0,
             current_mutation_prob,
             time_limit,
+            start_time: web_time::Instant::now(),
+            on_progress: None,
+            neighbors: Vec::new(),
         }
     }
     
+    /// Receive a progress event after every generation
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+        self.on_progress = Some(ProgressHandler::new(callback));
+    }
+    
+    /// Cost of the best individual so far (infinity before initialization)
+    fn best_cost(&self) -> f64 {
+        self.best_individual.as_ref().map(|b| b.cost()).unwrap_or(f64::INFINITY)
+    }
+    
+    /// Emit a progress event for the current generation
+    fn report(&self, message: impl FnOnce() -> String) {
+        notify(&self.on_progress, || {
+            ProgressEvent::new("GA", self.generation, self.best_cost(), self.start_time.elapsed().as_secs_f64())
+                .with_generation(self.generation)
+                .with_message(message())
+        });
+    }
+    
     /// Initialize population using various construction heuristics
     fn initialize_population(&mut self) {
         self.population.clear();
-        
+        let rng = RngFactory::new(self.config.seed);
         
         let constructions: Vec<Box<dyn ConstructionHeuristic + Send + Sync>> = vec![
             Box::new(NearestNeighborHeuristic::new()),
-            Box::new(NearestNeighborHeuristic::randomized(1)),
-            Box::new(NearestNeighborHeuristic::randomized(2)),
-            Box::new(NearestNeighborHeuristic::randomized(3)),
+            Box::new(NearestNeighborHeuristic::randomized(rng.derive_indexed("init-nn", 0))),
+            Box::new(NearestNeighborHeuristic::randomized(rng.derive_indexed("init-nn", 1))),
+            Box::new(NearestNeighborHeuristic::randomized(rng.derive_indexed("init-nn", 2))),
             Box::new(GreedyInsertionHeuristic::new()),
             Box::new(GreedyInsertionHeuristic::farthest()),
             Box::new(SavingsHeuristic::new()),
@@ -234,7 +469,7 @@ impl GeneticAlgorithm {
             };
 
             if !candidate.feasible {
-                let vnd = VND::with_standard_operators();
+                let vnd = permutation_vnd();
                 vnd.improve(&self.instance, &mut candidate);
             }
 
@@ -249,7 +484,7 @@ impl GeneticAlgorithm {
         
         
         for seed in 0..(self.config.population_size / 3).max(1) {
-            let nn = NearestNeighborHeuristic::randomized(seed as u64 + self.config.seed);
+            let nn = NearestNeighborHeuristic::randomized(rng.derive_indexed("init-nn", seed as u64 + 3));
             let sol = nn.construct(&self.instance);
 
             let mut candidate = if sol.tour.len() == self.instance.dimension - 1 {
@@ -265,7 +500,7 @@ impl GeneticAlgorithm {
             };
 
             if !candidate.feasible {
-                let vnd = VND::with_standard_operators();
+                let vnd = permutation_vnd();
                 vnd.improve(&self.instance, &mut candidate);
             }
             if candidate.tour.len() == self.instance.dimension && candidate.feasible {
@@ -311,19 +546,19 @@ impl GeneticAlgorithm {
         
         let feasible_count = self.population.iter().filter(|i| i.feasible).count();
         let infeasible_count = self.population.len().saturating_sub(feasible_count);
-        println!(
-            "[GA] Initialized population: {} (feasible: {}, infeasible: {})",
+        self.report(|| format!(
+            "Initialized population: {} (feasible: {}, infeasible: {})",
             self.population.len(),
             feasible_count,
             infeasible_count
-        );
+        ));
 
         // If no feasible individuals were produced by the heuristics, attempt to
         // generate feasible solutions using a multi-start construction + local search
         if feasible_count == 0 {
             let mut attempts = 0;
             let max_attempts = self.config.population_size * 5;
-            let multi = MultiStartConstruction::with_all_heuristics();
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
 
             while self.population.len() < self.config.population_size && attempts < max_attempts {
                     let sol = multi.construct(&self.instance);
@@ -342,7 +577,7 @@ impl GeneticAlgorithm {
                     };
 
                     if !candidate.feasible {
-                        let vnd = VND::with_standard_operators();
+                        let vnd = permutation_vnd();
                         vnd.improve(&self.instance, &mut candidate);
                     }
 
@@ -359,12 +594,12 @@ impl GeneticAlgorithm {
 
             let feasible_count = self.population.iter().filter(|i| i.feasible).count();
             let infeasible_count = self.population.len().saturating_sub(feasible_count);
-            println!(
-                "[GA] After fallback initialization: {} (feasible: {}, infeasible: {})",
+            self.report(|| format!(
+                "After fallback initialization: {} (feasible: {}, infeasible: {})",
                 self.population.len(),
                 feasible_count,
                 infeasible_count
-            );
+            ));
         }
     }
     
@@ -638,6 +873,217 @@ impl GeneticAlgorithm {
         child
     }
     
+    /// Feasibility-aware crossover: keep the longest load-feasible prefix of a
+    /// random segment of parent 1, then insert the other nodes in parent 2's
+    /// order at their cheapest position that keeps the (aggregate) load within
+    /// capacity. Nodes with no such position are retried after the others;
+    /// those still stuck go to their cheapest position regardless.
+    fn feasible_insertion_crossover(&mut self, parent1: &[usize], parent2: &[usize]) -> Vec<usize> {
+        let n = parent1.len();
+        if n < 4 {
+            return parent1.to_vec();
+        }
+        
+        let start = self.rng.gen_range(1..n);
+        let end = self.rng.gen_range(start..n);
+        
+        let mut child = vec![0];
+        let mut load = self.instance.starting_load();
+        for &node in &parent1[start..=end] {
+            if node == 0 {
+                break;
+            }
+            load += self.instance.nodes[node].demand;
+            if load < 0 || load > self.instance.capacity {
+                break;
+            }
+            child.push(node);
+        }
+        
+        let mut in_child = vec![false; n];
+        for &node in &child {
+            in_child[node] = true;
+        }
+        let mut pending: Vec<usize> = parent2.iter()
+            .copied()
+            .filter(|&node| node < n && !in_child[node])
+            .collect();
+        
+        while !pending.is_empty() {
+            let before = pending.len();
+            let mut stuck = Vec::new();
+            for node in pending {
+                match self.cheapest_insertion(&child, node) {
+                    (Some(pos), _) => child.insert(pos + 1, node),
+                    (None, _) => stuck.push(node),
+                }
+            }
+            if stuck.len() == before {
+                for node in stuck {
+                    let (_, pos) = self.cheapest_insertion(&child, node);
+                    child.insert(pos + 1, node);
+                }
+                break;
+            }
+            pending = stuck;
+        }
+        
+        if child.len() != n {
+            return parent1.to_vec();
+        }
+        child
+    }
+    
+    /// Cheapest position to insert `node` after in a partial tour, as
+    /// (cheapest load-feasible position, cheapest position overall)
+    fn cheapest_insertion(&self, child: &[usize], node: usize) -> (Option<usize>, usize) {
+        let capacity = self.instance.capacity;
+        let m = child.len();
+        
+        // loads[k] is the load after visiting child[k]; inserting after k
+        // shifts every later load by the node's demand
+        let mut loads = Vec::with_capacity(m);
+        let mut load = self.instance.starting_load();
+        loads.push(load);
+        for &c in &child[1..] {
+            load += self.instance.nodes[c].demand;
+            loads.push(load);
+        }
+        let mut suffix_min = vec![i32::MAX; m + 1];
+        let mut suffix_max = vec![i32::MIN; m + 1];
+        for k in (0..m).rev() {
+            suffix_min[k] = suffix_min[k + 1].min(loads[k]);
+            suffix_max[k] = suffix_max[k + 1].max(loads[k]);
+        }
+        
+        let d = self.instance.nodes[node].demand;
+        let mut best_feasible: Option<(usize, f64)> = None;
+        let mut best_any = (0, f64::INFINITY);
+        for pos in 0..m {
+            let prev = child[pos];
+            let next = child[(pos + 1) % m];
+            let cost = self.instance.distance(prev, node) + self.instance.distance(node, next)
+                - self.instance.distance(prev, next);
+            
+            let feasible = (0..=capacity).contains(&(loads[pos] + d))
+                && suffix_min[pos + 1].saturating_add(d) >= 0
+                && suffix_max[pos + 1].saturating_add(d) <= capacity
+                && (!self.instance.has_route_limit() || {
+                    let mut extended = child.to_vec();
+                    extended.insert(pos + 1, node);
+                    self.instance.route_lengths_feasible(&extended)
+                });
+            if feasible && best_feasible.is_none_or(|(_, c)| cost < c) {
+                best_feasible = Some((pos, cost));
+            }
+            if cost < best_any.1 {
+                best_any = (pos, cost);
+            }
+        }
+        
+        (best_feasible.map(|(pos, _)| pos), best_any.0)
+    }
+    
+    /// Edge Assembly Crossover (Nagata). The edges of one parent only,
+    /// alternately of parent 1 and parent 2, split into AB-cycles; a child
+    /// is parent 1 with the parent-1 edges of one AB-cycle (the E-set)
+    /// replaced by its parent-2 edges. The subtours this creates are merged
+    /// by the cheapest 2-opt style exchanges, the last merge preferring one
+    /// that gives a load-feasible tour in either direction. Up to
+    /// `EAX_TRIES` E-sets are tried; the shortest feasible child wins, or
+    /// the shortest child when none is feasible.
+    fn eax_crossover(&mut self, parent1: &[usize], parent2: &[usize]) -> Vec<usize> {
+        let n = parent1.len();
+        if n < 5 || !is_permutation(parent1, n) || !is_permutation(parent2, n) {
+            return parent1.to_vec();
+        }
+        if self.neighbors.len() != n {
+            self.neighbors = self.instance.nearest_neighbors(EAX_NEIGHBORS);
+        }
+        let mut cycles = ab_cycles(parent1, parent2, &mut self.rng);
+        cycles.shuffle(&mut self.rng);
+        
+        let mut best: Option<(bool, f64, Vec<usize>)> = None;
+        for cycle in cycles.iter().take(EAX_TRIES) {
+            let mut adjacency = tour_adjacency(parent1);
+            apply_e_set(&mut adjacency, cycle);
+            let child = self.merge_subtours(adjacency);
+            let feasible = self.instance.is_feasible(&child);
+            let cost = self.instance.tour_cost(&child);
+            if best.as_ref().is_none_or(|(f, c, _)| (feasible, -cost) > (*f, -*c)) {
+                best = Some((feasible, cost, child));
+            }
+        }
+        best.map_or_else(|| parent1.to_vec(), |(_, _, child)| child)
+    }
+    
+    /// Merge the cycles of a 2-regular graph into one tour starting at the
+    /// depot, always merging the smallest subtour into another one
+    fn merge_subtours(&self, mut adjacency: Vec<[usize; 2]>) -> Vec<usize> {
+        loop {
+            let (labels, count) = subtour_labels(&adjacency);
+            if count == 1 {
+                return self.oriented_tour(&adjacency).unwrap_or_else(|| adjacency_tour(&adjacency));
+            }
+            let mut sizes = vec![0; count];
+            labels.iter().for_each(|&l| sizes[l] += 1);
+            let smallest = (0..count).min_by_key(|&l| sizes[l]).unwrap();
+            
+            // Exchanges (u, u'), (v, v') -> (u, v), (u', v') with u in the
+            // smallest subtour and v among its near neighbors elsewhere
+            let members: Vec<usize> = (0..adjacency.len()).filter(|&u| labels[u] == smallest).collect();
+            let mut exchanges: Vec<(f64, usize, usize, usize, usize)> = Vec::new();
+            for pass in 0..2 {
+                for &u in &members {
+                    let candidates: Vec<usize> = if pass == 0 {
+                        self.neighbors[u].clone()
+                    } else {
+                        (0..adjacency.len()).collect()
+                    };
+                    for v in candidates.into_iter().filter(|&v| labels[v] != smallest) {
+                        for u2 in adjacency[u] {
+                            for v2 in adjacency[v] {
+                                let delta = self.instance.distance(u, v) + self.instance.distance(u2, v2)
+                                    - self.instance.distance(u, u2) - self.instance.distance(v, v2);
+                                exchanges.push((delta, u, u2, v, v2));
+                            }
+                        }
+                    }
+                }
+                if !exchanges.is_empty() {
+                    break;
+                }
+            }
+            exchanges.sort_by(|a, b| a.0.total_cmp(&b.0));
+            
+            // The last merge decides the tour: take the cheapest exchange
+            // giving a feasible one, if any among the cheapest few
+            let last = count == 2;
+            let choice = if last {
+                exchanges.iter().take(EAX_REPAIR_CANDIDATES).position(|&(_, u, u2, v, v2)| {
+                    let mut merged = adjacency.clone();
+                    exchange(&mut merged, u, u2, v, v2);
+                    self.oriented_tour(&merged).is_some()
+                }).unwrap_or(0)
+            } else {
+                0
+            };
+            let (_, u, u2, v, v2) = exchanges[choice];
+            exchange(&mut adjacency, u, u2, v, v2);
+        }
+    }
+    
+    /// The tour of a Hamiltonian cycle in the direction that is feasible,
+    /// if either is
+    fn oriented_tour(&self, adjacency: &[[usize; 2]]) -> Option<Vec<usize>> {
+        let tour = adjacency_tour(adjacency);
+        if self.instance.is_feasible(&tour) {
+            return Some(tour);
+        }
+        let reversed: Vec<usize> = std::iter::once(0).chain(tour[1..].iter().rev().copied()).collect();
+        self.instance.is_feasible(&reversed).then_some(reversed)
+    }
+    
     /// Perform crossover using configured method
     fn crossover(&mut self, parent1: &Individual, parent2: &Individual) -> Individual {
         if self.rng.gen::<f64>() > self.config.crossover_prob {
@@ -649,6 +1095,8 @@ impl GeneticAlgorithm {
             CrossoverType::PMX => self.pmx_crossover(&parent1.tour, &parent2.tour),
             CrossoverType::EdgeRecombination => self.edge_recombination(&parent1.tour, &parent2.tour),
             CrossoverType::CycleCrossover => self.cycle_crossover(&parent1.tour, &parent2.tour),
+            CrossoverType::FeasibleInsertion => self.feasible_insertion_crossover(&parent1.tour, &parent2.tour),
+            CrossoverType::EAX => self.eax_crossover(&parent1.tour, &parent2.tour),
         };
         
         Individual::new(child_tour, &self.instance)
@@ -749,7 +1197,7 @@ impl GeneticAlgorithm {
     
     /// Apply local search to improve an individual
     fn apply_local_search(&self, individual: &mut Individual) {
-        let vnd = VND::with_standard_operators();
+        let vnd = permutation_vnd();
         let mut solution = Solution::from_tour(&self.instance, individual.tour.clone(), "GA-LS");
         
         vnd.improve(&self.instance, &mut solution);
@@ -767,6 +1215,7 @@ impl GeneticAlgorithm {
                 .take(self.config.elite_count)
                 .cloned()
         );
+        let elite_count = new_population.len();
         
         
         let mut attempts: usize = 0;
@@ -789,6 +1238,9 @@ impl GeneticAlgorithm {
                 new_population.push(offspring);
                 
                 attempts = 0; // reset attempts on success
+            } else if let Ok(tour) = make_feasible_within(&self.instance, &offspring.tour, REPAIR_MOVES, REPAIR_EJECTIONS) {
+                new_population.push(Individual::new(tour, &self.instance));
+                attempts = 0;
             } else if new_population.len() < self.config.population_size.saturating_sub(10) {
                 
                 self.apply_local_search(&mut offspring);
@@ -806,37 +1258,47 @@ impl GeneticAlgorithm {
                 if attempts > max_attempts {
                     
                     if let Some(best) = self.population.first().cloned().or_else(|| self.best_individual.clone()) {
-                        println!("[GA] max_attempts exceeded ({}). Cloning best individual to fill population.", attempts);
+                        self.report(|| format!("max_attempts exceeded ({}). Cloning best individual to fill population.", attempts));
                         while new_population.len() < self.config.population_size {
                             new_population.push(best.clone());
                         }
                     } else {
                         
-                        println!("[GA] max_attempts exceeded but no best individual found; accepting infeasible offspring.");
+                        self.report(|| "max_attempts exceeded but no best individual found; accepting infeasible offspring.".to_string());
                         new_population.push(offspring);
                     }
                     break;
                 } else {
                     
                     if self.rng.gen::<f64>() < 0.05 {
-                        println!("[GA] Accepting infeasible offspring to diversify (attempt {}).", attempts);
+                        self.report(|| format!("Accepting infeasible offspring to diversify (attempt {}).", attempts));
                         new_population.push(offspring);
                     }
 
                     
                     if attempts % 50 == 0 {
-                        println!(
-                            "[GA] evolve attempts={} new_population={}/{}",
+                        self.report(|| format!(
+                            "evolve attempts={} new_population={}/{}",
                             attempts,
                             new_population.len(),
                             self.config.population_size
-                        );
+                        ));
                     }
                 }
             }
         }
         
+        if self.config.biased_fitness {
+            // Parents compete with the offspring; the elites are already among the parents
+            let mut pool = std::mem::take(&mut self.population);
+            pool.extend(new_population.into_iter().skip(elite_count));
+            new_population = self.select_survivors(pool);
+        }
+        
         new_population.sort_by_key(|ind| OrderedFloat(-ind.fitness));
+        if self.config.immigrants > 0 {
+            self.immigrate(&mut new_population);
+        }
         
         if let Some(best) = new_population.first() {
             if let Some(ref current_best) = self.best_individual {
@@ -863,27 +1325,122 @@ impl GeneticAlgorithm {
         self.generation += 1;
     }
     
+    /// Replace the worst individuals of a sorted population by feasible
+    /// tours built from the edges of its elite
+    fn immigrate(&mut self, population: &mut Vec<Individual>) {
+        let elite = self.config.elite_count.max(2).min(population.len());
+        let count = self.config.immigrants.min(population.len() - elite);
+        let frequency = edge_frequencies(population[..elite].iter().map(|ind| ind.tour.as_slice()), self.instance.dimension);
+        let mut heuristic = EdgeFrequencyHeuristic::new(frequency, 0);
+        let vnd = permutation_vnd();
+        let mut immigrants = Vec::new();
+        for _ in 0..count {
+            heuristic.seed = self.rng.gen();
+            let mut candidate = heuristic.construct(&self.instance);
+            vnd.improve(&self.instance, &mut candidate);
+            if candidate.feasible && candidate.tour.len() == self.instance.dimension {
+                immigrants.push(Individual::new(candidate.tour, &self.instance));
+            }
+        }
+        population.truncate(population.len() - immigrants.len());
+        population.extend(immigrants);
+        population.sort_by_key(|ind| OrderedFloat(-ind.fitness));
+    }
+    
+    /// Trim a pool down to the population size by repeatedly removing the
+    /// individual with the worst biased fitness, clones first (as in HGS)
+    fn select_survivors(&self, pool: Vec<Individual>) -> Vec<Individual> {
+        let p = pool.len();
+        let target = self.config.population_size;
+        if p <= target {
+            return pool;
+        }
+
+        let mut dist = vec![vec![0.0; p]; p];
+        for i in 0..p {
+            for j in i + 1..p {
+                let d = broken_pairs_distance(&pool[i].tour, &pool[j].tour);
+                dist[i][j] = d;
+                dist[j][i] = d;
+            }
+        }
+
+        let mut alive: Vec<usize> = (0..p).collect();
+        while alive.len() > target {
+            let m = alive.len();
+            let scale = (m - 1).max(1) as f64;
+
+            let mut by_cost = alive.clone();
+            by_cost.sort_by_key(|&i| OrderedFloat(-pool[i].fitness));
+            let mut cost_rank = vec![0.0; p];
+            for (rank, &i) in by_cost.iter().enumerate() {
+                cost_rank[i] = rank as f64 / scale;
+            }
+
+            // Mean distance to the closest individuals: larger is more diverse
+            let mut diversity = vec![0.0; p];
+            for &i in &alive {
+                let mut d: Vec<f64> = alive.iter()
+                    .filter(|&&j| j != i)
+                    .map(|&j| dist[i][j])
+                    .collect();
+                let k = self.config.n_closest.clamp(1, d.len());
+                d.select_nth_unstable_by_key(k - 1, |&x| OrderedFloat(x));
+                diversity[i] = d[..k].iter().sum::<f64>() / k as f64;
+            }
+            let mut by_diversity = alive.clone();
+            by_diversity.sort_by_key(|&i| OrderedFloat(-diversity[i]));
+            let mut diversity_rank = vec![0.0; p];
+            for (rank, &i) in by_diversity.iter().enumerate() {
+                diversity_rank[i] = rank as f64 / scale;
+            }
+
+            let elite_weight = 1.0 - self.config.elite_count as f64 / m as f64;
+            let biased = |i: usize| cost_rank[i] + elite_weight.max(0.0) * diversity_rank[i];
+            let is_clone = |i: usize| alive.iter().any(|&j| j != i && dist[i][j] == 0.0);
+
+            let clones: Vec<usize> = alive.iter().copied().filter(|&i| is_clone(i)).collect();
+            let candidates = if clones.is_empty() { &alive } else { &clones };
+            let worst = *candidates.iter()
+                .max_by_key(|&&i| OrderedFloat(biased(i)))
+                .unwrap();
+            alive.retain(|&i| i != worst);
+        }
+
+        let mut pool: Vec<Option<Individual>> = pool.into_iter().map(Some).collect();
+        alive.into_iter().filter_map(|i| pool[i].take()).collect()
+    }
+    
     /// Run the genetic algorithm
     pub fn run(&mut self) -> Solution {
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
+        self.start_time = start;
         
         self.initialize_population();
         
+        let mut convergence = vec![(start.elapsed().as_secs_f64(), self.best_cost())];
+        let mut snapshots = Vec::new();
+        self.snapshot(&mut snapshots, convergence[0].0);
+        
         while self.generation < self.config.max_generations 
             && self.no_improve_count < self.config.max_no_improve 
             && start.elapsed().as_secs_f64() < self.time_limit
         {
             self.evolve();
+            
+            let cost = self.best_cost();
+            if cost < convergence.last().map_or(f64::INFINITY, |&(_, c)| c) {
+                let elapsed = start.elapsed().as_secs_f64();
+                convergence.push((elapsed, cost));
+                self.snapshot(&mut snapshots, elapsed);
+            }
 
             if let Some(ref best) = self.best_individual {
-                println!(
-                    "[GA] Gen {}  Best cost {:.3}  Feasible {}  Diversity {:.2}  Elapsed {:.2}s",
-                    self.generation,
-                    best.cost(),
+                self.report(|| format!(
+                    "Feasible {}  Diversity {:.2}",
                     best.feasible,
-                    self.population_diversity(),
-                    start.elapsed().as_secs_f64()
-                );
+                    self.population_diversity()
+                ));
             }
         }
         
@@ -893,10 +1450,19 @@ impl GeneticAlgorithm {
         let mut solution = Solution::from_tour(&self.instance, best.tour.clone(), "GeneticAlgorithm");
         solution.computation_time = start.elapsed().as_secs_f64();
         solution.iterations = Some(self.generation);
+        solution.convergence = convergence;
+        solution.snapshots = snapshots;
         
         solution
     }
     
+    /// Record the best tour, if snapshots are requested
+    fn snapshot(&self, snapshots: &mut Vec<(f64, Vec<usize>)>, elapsed: f64) {
+        if let (true, Some(best)) = (self.config.record_snapshots, &self.best_individual) {
+            snapshots.push((elapsed, best.tour.clone()));
+        }
+    }
+    
     /// Get current best solution
     pub fn best_solution(&self) -> Option<Solution> {
         self.best_individual.as_ref().map(|ind| {
@@ -967,6 +1533,11 @@ impl MemeticAlgorithm {
         }
     }
     
+    /// Receive a progress event after every generation
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+        self.ga.set_progress_callback(callback);
+    }
+    
     pub fn run(&mut self) -> Solution {
         let mut solution = self.ga.run();
         
@@ -984,7 +1555,7 @@ mod tests {
     use crate::instance::Node;
     
     fn create_test_instance() -> PDTSPInstance {
-        use crate::instance::CostFunction;
+        use crate::instance::DistanceMatrix;
         
         let nodes = vec![
             Node::new(0, 0.0, 0.0, 0, 0),
@@ -994,25 +1565,14 @@ mod tests {
             Node::new(4, 2.0, 1.0, 0, 0),
         ];
         
-        let mut instance = PDTSPInstance {
-            cost_function: CostFunction::Distance,
-            alpha: 0.1,
-            beta: 0.5,
-            name: "test".to_string(),
-            comment: "test".to_string(),
-            dimension: 5,
-            capacity: 10,
-            nodes: nodes.clone(),
-            distance_matrix: Vec::new(),
-            return_depot_demand: 0,
-        };
+        let mut instance = PDTSPInstance::from_parts("test", 10, nodes.clone(), DistanceMatrix::default());
         
-        instance.distance_matrix = vec![vec![0.0; 5]; 5];
+        instance.distance_matrix = DistanceMatrix::new(5);
         for i in 0..5 {
             for j in 0..5 {
                 let dx = instance.nodes[i].x - instance.nodes[j].x;
                 let dy = instance.nodes[i].y - instance.nodes[j].y;
-                instance.distance_matrix[i][j] = (dx * dx + dy * dy).sqrt();
+                instance.distance_matrix.set(i, j, (dx * dx + dy * dy).sqrt());
             }
         }
         
@@ -1034,4 +1594,131 @@ mod tests {
         assert!(solution.feasible);
         assert_eq!(solution.tour.len(), 5);
     }
+    
+    #[test]
+    fn test_feasible_insertion_crossover() {
+        let instance = create_test_instance();
+        let config = GAConfig { crossover_type: CrossoverType::FeasibleInsertion, ..Default::default() };
+        let mut ga = GeneticAlgorithm::new(instance.clone(), config);
+        
+        // Both parents deliver before picking up; the children must not
+        let parent1 = vec![0, 2, 1, 3, 4];
+        let parent2 = vec![0, 3, 4, 2, 1];
+        assert!(!instance.is_feasible(&parent1) && !instance.is_feasible(&parent2));
+        for _ in 0..20 {
+            let child = ga.feasible_insertion_crossover(&parent1, &parent2);
+            let mut sorted = child.clone();
+            sorted.sort();
+            assert_eq!(sorted, vec![0, 1, 2, 3, 4]);
+            assert_eq!(child[0], 0);
+            assert!(instance.is_feasible(&child));
+        }
+    }
+    
+    #[test]
+    fn test_biased_fitness_survivor_selection() {
+        assert_eq!(broken_pairs_distance(&[0, 1, 2, 3, 4], &[0, 1, 2, 3, 4]), 0.0);
+        // Same cycle traversed backwards and rotated
+        assert_eq!(broken_pairs_distance(&[0, 1, 2, 3, 4], &[2, 1, 0, 4, 3]), 0.0);
+        // Swapping 1 and 2 breaks edges 0-1 and 2-3
+        assert!((broken_pairs_distance(&[0, 1, 2, 3, 4], &[0, 2, 1, 3, 4]) - 0.4).abs() < 1e-12);
+
+        let instance = create_test_instance();
+        let config = GAConfig { population_size: 3, elite_count: 1, ..Default::default() };
+        let ga = GeneticAlgorithm::new(instance.clone(), config);
+        let pool: Vec<Individual> = [
+            vec![0, 1, 2, 3, 4],
+            vec![0, 1, 2, 3, 4],
+            vec![0, 4, 3, 2, 1],
+            vec![0, 1, 3, 2, 4],
+            vec![0, 1, 4, 2, 3],
+        ].into_iter().map(|t| Individual::new(t, &instance)).collect();
+
+        let survivors = ga.select_survivors(pool);
+        assert_eq!(survivors.len(), 3);
+        for i in 0..survivors.len() {
+            for j in i + 1..survivors.len() {
+                assert!(broken_pairs_distance(&survivors[i].tour, &survivors[j].tour) > 0.0);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_eax_crossover() {
+        let mut text = "NAME: eax\nDIMENSION: 20\nCAPACITY: 10\nNODE_COORD_SECTION\n".to_string();
+        text.extend((1..=20).map(|i| format!("{} {} {}\n", i, i * 7 % 20, i * 13 % 20)));
+        text.push_str("DEMAND_SECTION\n");
+        text.extend((1..=20).map(|i| format!("{} {}\n", i, if i == 1 { 0 } else if i % 2 == 0 { 2 } else { -2 })));
+        let instance = PDTSPInstance::from_tsplib_str(&(text + "EOF\n")).unwrap();
+        let config = GAConfig { crossover_type: CrossoverType::EAX, ..Default::default() };
+        let mut ga = GeneticAlgorithm::new(instance.clone(), config);
+        
+        let parent1: Vec<usize> = (0..20).collect();
+        let parent2: Vec<usize> = std::iter::once(0).chain((1..20).rev().step_by(2)).chain((2..20).step_by(2)).collect();
+        assert!(instance.is_feasible(&parent1));
+        
+        // The AB-cycles alternate parent edges and use each differing edge once
+        let (adj1, adj2) = (tour_adjacency(&parent1), tour_adjacency(&parent2));
+        let cycles = ab_cycles(&parent1, &parent2, &mut ga.rng);
+        let mut a_edges = 0;
+        for cycle in &cycles {
+            let m = cycle.len();
+            assert!(m >= 4 && m % 2 == 0);
+            for k in 0..m {
+                let (x, y) = (cycle[k], cycle[(k + 1) % m]);
+                let (own, other) = if k % 2 == 0 { (&adj1, &adj2) } else { (&adj2, &adj1) };
+                assert!(own[x].contains(&y) && !other[x].contains(&y));
+            }
+            a_edges += m / 2;
+        }
+        assert_eq!(a_edges, (0..20).map(|v| adj1[v].iter().filter(|w| !adj2[v].contains(w)).count()).sum::<usize>() / 2);
+        
+        for _ in 0..10 {
+            let child = ga.eax_crossover(&parent1, &parent2);
+            assert!(is_permutation(&child, 20));
+            assert_eq!(child[0], 0);
+            assert!(instance.is_feasible(&child));
+        }
+    }
+    
+    #[test]
+    fn test_crossovers_after_local_search() {
+        // Alternating pickups and deliveries filling the vehicle: tours that
+        // do not alternate exceed the capacity, which the depot reinsertion
+        // operator would fix by adding depot visits
+        let mut text = "NAME: alt\nDIMENSION: 13\nCAPACITY: 3\nNODE_COORD_SECTION\n".to_string();
+        text.extend((1..=13).map(|i| format!("{} {} {}\n", i, i * 5 % 13, i * 8 % 13)));
+        text.push_str("DEMAND_SECTION\n1 0\n");
+        text.extend((2..=13).map(|i| format!("{} {}\n", i, if i % 2 == 0 { 3 } else { -3 })));
+        let instance = PDTSPInstance::from_tsplib_str(&(text + "EOF\n")).unwrap();
+        // Pickups first, then deliveries
+        let pickups_first: Vec<usize> = std::iter::once(0).chain((1..13).step_by(2)).chain((2..13).step_by(2)).collect();
+        let reversed: Vec<usize> = std::iter::once(0).chain(pickups_first[1..].iter().rev().copied()).collect();
+        
+        for crossover_type in [
+            CrossoverType::OrderCrossover,
+            CrossoverType::PMX,
+            CrossoverType::EdgeRecombination,
+            CrossoverType::CycleCrossover,
+            CrossoverType::FeasibleInsertion,
+            CrossoverType::EAX,
+        ] {
+            let config = GAConfig { crossover_type, ..Default::default() };
+            let mut ga = GeneticAlgorithm::new(instance.clone(), config);
+            let mut parent1 = Individual::new(pickups_first.clone(), &instance);
+            let mut parent2 = Individual::new(reversed.clone(), &instance);
+            ga.apply_local_search(&mut parent1);
+            ga.apply_local_search(&mut parent2);
+            for parent in [&parent1, &parent2] {
+                assert_eq!(parent.tour.len(), 13, "{:?}: {:?}", crossover_type, parent.tour);
+            }
+            for _ in 0..10 {
+                let mut child = ga.crossover(&parent1, &parent2);
+                ga.apply_local_search(&mut child);
+                let mut sorted = child.tour.clone();
+                sorted.sort();
+                assert_eq!(sorted, (0..13).collect::<Vec<_>>(), "{:?}", crossover_type);
+            }
+        }
+    }
 }
@@ -0,0 +1,124 @@
+//! Budget-aware hybrid solver.
+//!
+//! The hybrid pipeline runs multi-start construction, VND and ILS in turn.
+//! Each stage gets a fraction of the total time limit, enforced through a
+//! `Deadline`; the deadlines are cumulative, so time left over by a stage
+//! that finishes early goes to the following ones. It is run as a
+//! `Pipeline`, which composes other stages the same way.
+
+use crate::heuristics::pipeline::{Pipeline, Solver};
+use crate::instance::PDTSPInstance;
+use crate::solution::Solution;
+
+/// Share of the time limit given to each stage (normalized by their sum)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeBudget {
+    /// Multi-start construction
+    pub construction: f64,
+    /// Variable neighborhood descent
+    pub vnd: f64,
+    /// Iterated local search
+    pub ils: f64,
+}
+
+impl Default for TimeBudget {
+    fn default() -> Self {
+        TimeBudget {
+            construction: 0.2,
+            vnd: 0.3,
+            ils: 0.5,
+        }
+    }
+}
+
+impl TimeBudget {
+    /// Budget from a list of fractions (construction, VND, ILS)
+    pub fn from_fractions(fractions: &[f64]) -> Result<Self, String> {
+        let [construction, vnd, ils] = fractions else {
+            return Err(format!("Expected 3 budget fractions (construction, vnd, ils), got {}", fractions.len()));
+        };
+        if fractions.iter().any(|f| !f.is_finite() || *f < 0.0) || fractions.iter().sum::<f64>() <= 0.0 {
+            return Err("Budget fractions must be non-negative with a positive sum".to_string());
+        }
+        Ok(TimeBudget { construction: *construction, vnd: *vnd, ils: *ils })
+    }
+}
+
+/// Wall-clock time spent in each stage, in seconds
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StageTimes {
+    pub stages: Vec<(String, f64)>,
+}
+
+impl std::fmt::Display for StageTimes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self.stages.iter().map(|(name, t)| format!("{} {:.3}s", name, t)).collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Multi-start construction, VND and ILS within a global time limit
+pub struct HybridSolver {
+    /// Total time limit in seconds
+    pub time_limit: f64,
+    /// Share of the time limit per stage
+    pub budget: TimeBudget,
+    /// Random seed
+    pub seed: u64,
+}
+
+impl HybridSolver {
+    pub fn new() -> Self {
+        HybridSolver {
+            time_limit: 60.0,
+            budget: TimeBudget::default(),
+            seed: 42,
+        }
+    }
+    
+    pub fn with_params(time_limit: f64, budget: TimeBudget, seed: u64) -> Self {
+        HybridSolver { time_limit, budget, seed }
+    }
+    
+    /// Run the three stages and report the time spent in each
+    pub fn solve(&self, instance: &PDTSPInstance) -> (Solution, StageTimes) {
+        let (mut solution, times) = Pipeline::hybrid(self.budget).solve(instance, self.time_limit, self.seed);
+        solution.algorithm = "Hybrid".to_string();
+        (solution, times)
+    }
+}
+
+impl Default for HybridSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Solver for HybridSolver {
+    /// The three stages within `time_limit` seconds from `seed`, with this
+    /// solver's budget
+    fn solve(&self, instance: &PDTSPInstance, time_limit: f64, seed: u64) -> Solution {
+        HybridSolver { time_limit, seed, ..*self }.solve(instance).0
+    }
+    
+    fn name(&self) -> &str {
+        "Hybrid"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::stopping::Deadline;
+
+    #[test]
+    fn test_budget_fractions() {
+        let budget = TimeBudget::from_fractions(&[1.0, 1.0, 2.0]).unwrap();
+        assert_eq!(Pipeline::hybrid(budget).stage_ends(), [0.25, 0.5, 1.0]);
+        assert!(TimeBudget::from_fractions(&[0.5, 0.5]).is_err());
+        assert!(TimeBudget::from_fractions(&[0.0, 0.0, 0.0]).is_err());
+        assert!(TimeBudget::from_fractions(&[-1.0, 1.0, 1.0]).is_err());
+        assert!(Deadline::after(0.0).expired());
+        assert!(!Deadline::after(f64::INFINITY).expired());
+    }
+}
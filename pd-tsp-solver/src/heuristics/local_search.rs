@@ -5,16 +5,38 @@
 //! - Or-opt (segment relocation)
 //! - Node swap
 //! - Node insertion/relocation
+//! - Ejection chains
+//! - Depot reinsertion (intermediate unloading visits)
+//! - Simulated annealing, threshold accepting and record-to-record travel
 //! - Lin-Kernighan style moves
 
-use crate::instance::PDTSPInstance;
-use crate::solution::Solution;
+use crate::config::VNDParams;
+use crate::instance::{CostFunction, DistanceMode, PDTSPInstance};
+use crate::solution::{cross_exchange, relocate_segment, DeltaEvaluator, EvaluatorTables, Move, Solution, StandardMove};
+use crate::progress::{notify, ProgressEvent, ProgressHandler};
+use crate::heuristics::acceptance::Acceptance;
+use crate::heuristics::perturbation::{DoubleBridge, Perturbation};
+use crate::heuristics::statistics::OperatorRun;
+use crate::heuristics::stopping::Deadline;
+use crate::heuristics::construction::{ConstructionHeuristic, EdgeFrequencyHeuristic};
+use crate::pool::SolutionPool;
+use crate::tour::{TourRep, TwoLevelList};
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
 /// Trait for local search improvement methods
 pub trait LocalSearch {
     fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool;
+    
+    /// Improve, stopping once `deadline` has passed (at the earlier of it and
+    /// the search's own deadline). Searches without a stopping checkpoint,
+    /// such as a single descent of one operator, run to completion.
+    fn improve_until(&self, instance: &PDTSPInstance, solution: &mut Solution, _deadline: Deadline) -> bool {
+        self.improve(instance, solution)
+    }
+    
     fn name(&self) -> &str;
 }
 
@@ -24,103 +46,321 @@ pub trait LocalSearch {
 /// 
 /// Reverses segments of the tour to reduce total distance
 /// while maintaining capacity constraints.
+///
+/// With don't-look bits (opt-in, see `with_dont_look_bits`) the search
+/// keeps a queue of active nodes: a node is scanned for improving moves
+/// involving its candidate neighbors and is dropped once the scan fails,
+/// until a move changes one of its arcs. Each scan then costs O(k), so a
+/// descent is near-linear in the tour size instead of quadratic per pass.
+/// On large Euclidean instances with on-demand distances the queue runs on
+/// a `TwoLevelList`, so a move also costs O(√n) instead of a linear array
+/// reversal.
 pub struct TwoOptSearch {
     /// Use first improvement instead of best improvement
     pub first_improvement: bool,
-    /// Maximum iterations without improvement
-    pub max_no_improve: usize,
+    /// Skip nodes whose arcs did not change since their last failed scan
+    pub dont_look_bits: bool,
+    /// Nearest neighbors paired with each node by the don't-look-bits scan
+    /// (0 = all nodes on tours below `AUTO_CANDIDATE_THRESHOLD` nodes,
+    /// `AUTO_CANDIDATES` above)
+    pub candidate_neighbors: usize,
+    /// Minimum tour length for the two-level list representation (0 = never)
+    pub two_level_threshold: usize,
 }
 
 impl TwoOptSearch {
+    /// Tour length from which the don't-look-bits scan defaults to candidate lists
+    pub const AUTO_CANDIDATE_THRESHOLD: usize = 200;
+    /// Candidate neighbors of the scan on large tours when none are set
+    pub const AUTO_CANDIDATES: usize = 10;
+    
     pub fn new() -> Self {
         TwoOptSearch {
             first_improvement: false,
-            max_no_improve: 10,
+            dont_look_bits: false,
+            candidate_neighbors: 0,
+            two_level_threshold: 1000,
         }
     }
     
     pub fn first_improvement() -> Self {
         TwoOptSearch {
             first_improvement: true,
-            max_no_improve: 10,
+            ..Self::new()
         }
     }
     
-    /// Check if 2-opt move maintains feasibility
-    fn is_feasible_move(&self, instance: &PDTSPInstance, tour: &[usize], i: usize, j: usize) -> bool {
-        
-        let mut new_tour = tour.to_vec();
-        new_tour[i + 1..=j].reverse();
-        instance.is_feasible(&new_tour)
+    /// Scan with don't-look bits (always first improvement per node)
+    pub fn with_dont_look_bits(mut self, dont_look_bits: bool) -> Self {
+        self.dont_look_bits = dont_look_bits;
+        self
     }
-}
-
-impl Default for TwoOptSearch {
-    fn default() -> Self {
-        Self::new()
+    
+    /// Restrict the don't-look-bits scan to the `k` nearest neighbors of each node
+    pub fn with_candidates(mut self, k: usize) -> Self {
+        self.candidate_neighbors = k;
+        self
     }
-}
-
-impl LocalSearch for TwoOptSearch {
-    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+    
+    /// Candidate neighbors of the scan on a tour of `n` nodes (0 = all nodes)
+    fn candidates(&self, n: usize) -> usize {
+        match self.candidate_neighbors {
+            0 if n >= Self::AUTO_CANDIDATE_THRESHOLD => Self::AUTO_CANDIDATES,
+            k => k,
+        }
+    }
+    
+    /// Use the two-level list representation from `threshold` nodes on (0 = never)
+    pub fn with_two_level_threshold(mut self, threshold: usize) -> Self {
+        self.two_level_threshold = threshold;
+        self
+    }
+    
+    /// Whether the don't-look-bits descent can run on a `TwoLevelList` (see
+    /// `two_level_applicable`)
+    fn uses_two_level(&self, instance: &PDTSPInstance, solution: &Solution) -> bool {
+        self.candidates(solution.tour.len()) > 0 && two_level_applicable(instance, solution, self.two_level_threshold)
+    }
+    
+    /// Classic scan of all position pairs, one evaluator per pass (refreshed
+    /// from the first reversed position)
+    fn improve_full_scan(&self, instance: &PDTSPInstance, solution: &mut Solution, run: &mut OperatorRun) -> bool {
         let n = solution.tour.len();
-        if n < 3 { return false; }
-        
-        let mut improved = true;
         let mut total_improved = false;
-        let mut no_improve_count = 0;
-        let mut total_iterations = 0;
         let max_total_iterations = 50; // Limit total iterations
+        let (mut tables, mut changed_from) = (EvaluatorTables::default(), 0);
         
-        while improved && no_improve_count < self.max_no_improve && total_iterations < max_total_iterations {
-            improved = false;
-            let mut best_delta = 0.0;
-            let mut best_i = 0;
-            let mut best_j = 0;
-            total_iterations += 1;
+        for _ in 0..max_total_iterations {
+            let mut best: Option<(f64, usize, usize)> = None;
             
-            for i in 0..n - 2 {
+            let evaluator = DeltaEvaluator::refresh(instance, &solution.tour, std::mem::take(&mut tables), changed_from);
+            'scan: for i in 0..n - 2 {
                 for j in i + 2..n {
                     if i == 0 && j == n - 1 {
                         continue; // Skip if it would just reverse entire tour
                     }
                     
-                    let delta = solution.two_opt_delta(instance, i, j);
-                    
-                    if delta < -1e-9 {
-                        if self.is_feasible_move(instance, &solution.tour, i, j) {
-                            if self.first_improvement {
-                                solution.apply_two_opt(i, j);
-                                solution.cost += delta;
-                                improved = true;
-                                total_improved = true;
-                                no_improve_count = 0;
-                                break;
-                            } else if delta < best_delta {
-                                best_delta = delta;
-                                best_i = i;
-                                best_j = j;
-                            }
+                    let delta = evaluator.two_opt_delta(i, j);
+                    run.evaluated += 1;
+                    if delta < best.map_or(-1e-9, |(d, _, _)| d) && evaluator.two_opt_feasible(i, j) {
+                        best = Some((delta, i, j));
+                        if self.first_improvement {
+                            break 'scan;
+                        }
+                    }
+                }
+            }
+            
+            match best {
+                Some((delta, i, j)) => {
+                    tables = evaluator.into_tables();
+                    solution.apply_two_opt(i, j);
+                    solution.cost += delta;
+                    changed_from = i + 1;
+                    run.applied += 1;
+                    total_improved = true;
+                }
+                None => break,
+            }
+        }
+        
+        total_improved
+    }
+    
+    /// Descent over a queue of the nodes whose don't-look bit is off. The
+    /// evaluator is only refreshed after a move, from its first reversed
+    /// position, so failed scans cost O(k).
+    fn improve_dont_look_bits(&self, instance: &PDTSPInstance, solution: &mut Solution, run: &mut OperatorRun) -> bool {
+        let n = solution.tour.len();
+        let k = self.candidates(n);
+        let neighbors = if k > 0 {
+            instance.nearest_neighbors(k)
+        } else {
+            vec![(0..instance.dimension).collect()]
+        };
+        let partners = |a: usize| if k > 0 { &neighbors[a] } else { &neighbors[0] };
+        let mut position = vec![usize::MAX; instance.dimension];
+        for (pos, &node) in solution.tour.iter().enumerate() {
+            position[node] = pos;
+        }
+        
+        let mut active: VecDeque<usize> = solution.tour.iter().copied().collect();
+        let mut queued = vec![false; instance.dimension];
+        for &node in &solution.tour {
+            queued[node] = true;
+        }
+        let mut total_improved = false;
+        let (mut tables, mut changed_from) = (EvaluatorTables::default(), 0);
+        
+        loop {
+            let tour = &solution.tour;
+            let evaluator = DeltaEvaluator::refresh(instance, tour, std::mem::take(&mut tables), changed_from);
+            let mut found = None;
+            
+            while let Some(a) = active.pop_front() {
+                queued[a] = false;
+                let i = position[a];
+                
+                // 2-opt moves creating the arc (a, b), with a as the tail of
+                // the first new arc or as the head of the second one
+                let mut best: Option<(f64, usize, usize)> = None;
+                for &b in partners(a) {
+                    let p = position[b];
+                    if b == a || p == usize::MAX {
+                        continue;
+                    }
+                    let tail = if p > i { (i, p) } else { (p, i) };
+                    let head = (i > 0 && p > 0).then(|| (tail.0 - 1, tail.1 - 1));
+                    for (x, y) in std::iter::once(tail).chain(head) {
+                        if y < x + 2 || (x == 0 && y == n - 1) {
+                            continue;
+                        }
+                        let delta = evaluator.two_opt_delta(x, y);
+                        run.evaluated += 1;
+                        if delta < best.map_or(-1e-9, |(d, _, _)| d) && evaluator.two_opt_feasible(x, y) {
+                            best = Some((delta, x, y));
                         }
                     }
                 }
-                if improved && self.first_improvement {
+                
+                if best.is_some() {
+                    found = best;
                     break;
                 }
             }
             
-            if !self.first_improvement && best_delta < -1e-9 {
-                solution.apply_two_opt(best_i, best_j);
-                solution.cost += best_delta;
-                improved = true;
-                total_improved = true;
-                no_improve_count = 0;
-            } else if !improved {
-                no_improve_count += 1;
+            let Some((delta, x, y)) = found else {
+                break;
+            };
+            let touched = [tour[x], tour[x + 1], tour[y], tour[(y + 1) % n]];
+            tables = evaluator.into_tables();
+            solution.apply_two_opt(x, y);
+            solution.cost += delta;
+            changed_from = x + 1;
+            run.applied += 1;
+            total_improved = true;
+            for pos in x + 1..=y {
+                position[solution.tour[pos]] = pos;
+            }
+            for node in touched {
+                if !queued[node] {
+                    queued[node] = true;
+                    active.push_back(node);
+                }
+            }
+        }
+        
+        total_improved
+    }
+    
+    /// Don't-look-bits descent on a `TwoLevelList`. A move replacing the arcs
+    /// (a, a') and (b, b') by (a, b) and (a', b') reverses the path between
+    /// them; it is undone when the load check of the new tour fails.
+    fn improve_two_level(&self, instance: &PDTSPInstance, solution: &mut Solution, run: &mut OperatorRun) -> bool {
+        let neighbors = instance.nearest_neighbors(self.candidates(solution.tour.len()));
+        let d = |a: usize, b: usize| instance.distance(a, b);
+        let mut tour = TwoLevelList::from_instance(instance, &solution.tour);
+        let mut active: VecDeque<usize> = solution.tour.iter().copied().collect();
+        let mut queued = vec![false; instance.dimension];
+        for &node in &solution.tour {
+            queued[node] = true;
+        }
+        let mut total_improved = false;
+        
+        while let Some(a) = active.pop_front() {
+            queued[a] = false;
+            let mut touched = None;
+            'scan: for forward in [true, false] {
+                let a2 = if forward { tour.next(a) } else { tour.prev(a) };
+                let removed = d(a, a2);
+                for &b in &neighbors[a] {
+                    // Neighbors come closest first: no later one can gain
+                    if d(a, b) >= removed {
+                        break;
+                    }
+                    let b2 = if forward { tour.next(b) } else { tour.prev(b) };
+                    if b == a2 || b2 == a {
+                        continue;
+                    }
+                    run.evaluated += 1;
+                    if d(a, b) + d(a2, b2) - removed - d(b, b2) >= -1e-9 {
+                        continue;
+                    }
+                    let (from, to) = if forward { (a2, b) } else { (a, b2) };
+                    tour.reverse(from, to);
+                    if tour.load_feasible(instance) {
+                        touched = Some([a, a2, b, b2]);
+                        break 'scan;
+                    }
+                    tour.reverse(to, from);
+                }
             }
+            
+            let Some(touched) = touched else {
+                continue;
+            };
+            run.applied += 1;
+            total_improved = true;
+            for node in touched {
+                if !queued[node] {
+                    queued[node] = true;
+                    active.push_back(node);
+                }
+            }
+        }
+        
+        if total_improved {
+            solution.tour = tour.to_vec();
+            solution.cost = instance.tour_cost(&solution.tour);
         }
+        total_improved
+    }
+}
+
+/// Whether a search on `solution` can run on a `TwoLevelList` from
+/// `threshold` nodes on (0 = never): its deltas are plain symmetric
+/// distances, its feasibility check is the single-commodity load profile
+/// from the depot, and the list indexes each node once, so the tour must
+/// visit every node exactly once (no intermediate depot visit)
+fn two_level_applicable(instance: &PDTSPInstance, solution: &Solution, threshold: usize) -> bool {
+    threshold > 0
+        && solution.tour.len() >= threshold
+        && solution.tour.len() == instance.dimension
+        && solution.feasible
+        && instance.cost_function == CostFunction::Distance
+        && instance.vehicle.is_none()
+        && !instance.is_multi_commodity()
+        && !instance.has_route_limit()
+        && !instance.has_chance_constraint()
+        && !instance.open_tour
+        && instance.distance_matrix.mode() == DistanceMode::OnDemand
+        && !instance.distance_matrix.is_restricted()
+        && instance.is_complete_tour(&solution.tour)
+}
+
+impl Default for TwoOptSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSearch for TwoOptSearch {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        let n = solution.tour.len();
+        if n < 3 { return false; }
+        
+        let mut run = OperatorRun::start(solution);
+        // Depot revisits break the node -> position map of the queue
+        let total_improved = if self.dont_look_bits && self.uses_two_level(instance, solution) {
+            self.improve_two_level(instance, solution, &mut run)
+        } else if self.dont_look_bits && !solution.tour[1..].contains(&0) {
+            self.improve_dont_look_bits(instance, solution, &mut run)
+        } else {
+            self.improve_full_scan(instance, solution, &mut run)
+        };
         
         solution.validate(instance);
+        run.finish(self.name(), solution);
         total_improved
     }
     
@@ -137,12 +377,16 @@ impl LocalSearch for TwoOptSearch {
 
 /// Or-Opt Local Search
 /// 
-/// Relocates segments of 1, 2, or 3 consecutive nodes to other positions.
+/// Relocates segments of 1, 2, or 3 consecutive nodes to other positions,
+/// both forward and backward in the tour, optionally reversing the segment
+/// on reinsertion.
 pub struct OrOptSearch {
     /// Maximum segment length to consider
     pub max_segment_length: usize,
     /// Use first improvement
     pub first_improvement: bool,
+    /// Also try reinserting each segment in reverse order
+    pub allow_reversal: bool,
 }
 
 impl OrOptSearch {
@@ -150,6 +394,7 @@ impl OrOptSearch {
         OrOptSearch {
             max_segment_length: 3,
             first_improvement: false,
+            allow_reversal: false,
         }
     }
     
@@ -157,102 +402,14 @@ impl OrOptSearch {
         OrOptSearch {
             max_segment_length: 3,
             first_improvement: true,
+            allow_reversal: false,
         }
     }
     
-    /// Calculate delta for relocating a segment
-    fn segment_relocation_delta(
-        &self,
-        instance: &PDTSPInstance,
-        tour: &[usize],
-        seg_start: usize,
-        seg_len: usize,
-        insert_pos: usize
-    ) -> f64 {
-        let n = tour.len();
-        let seg_end = seg_start + seg_len - 1;
-        
-        
-        if insert_pos >= seg_start && insert_pos <= seg_end + 1 {
-            return 0.0;
-        }
-        
-        let prev_seg = if seg_start == 0 { n - 1 } else { seg_start - 1 };
-        let next_seg = (seg_end + 1) % n;
-        
-        
-        let removal_cost = -instance.distance(tour[prev_seg], tour[seg_start])
-            - instance.distance(tour[seg_end], tour[next_seg])
-            + instance.distance(tour[prev_seg], tour[next_seg]);
-        
-        
-        let prev_insert = if insert_pos == 0 { n - 1 } else { insert_pos - 1 };
-        
-        
-        let actual_prev = if prev_insert >= seg_start && prev_insert <= seg_end {
-            prev_seg
-        } else if prev_insert > seg_end {
-            tour[(prev_insert - seg_len + n) % n]
-        } else {
-            tour[prev_insert]
-        };
-        
-        let actual_next = if insert_pos >= seg_start && insert_pos <= seg_end {
-            tour[next_seg]
-        } else if insert_pos > seg_end {
-            tour[(insert_pos - seg_len + n) % n]
-        } else {
-            tour[insert_pos % n]
-        };
-        
-        let insertion_cost = instance.distance(actual_prev, tour[seg_start])
-            + instance.distance(tour[seg_end], actual_next)
-            - instance.distance(actual_prev, actual_next);
-        
-        removal_cost + insertion_cost
-    }
-    
-    /// Check if segment relocation maintains feasibility
-    fn is_feasible_relocation(
-        &self,
-        instance: &PDTSPInstance,
-        tour: &[usize],
-        seg_start: usize,
-        seg_len: usize,
-        insert_pos: usize
-    ) -> bool {
-        let mut new_tour = Vec::with_capacity(tour.len());
-        
-        
-        let segment: Vec<usize> = tour[seg_start..seg_start + seg_len].to_vec();
-        
-        for (i, &node) in tour.iter().enumerate() {
-            if i == insert_pos && insert_pos < seg_start {
-                new_tour.extend(&segment);
-            }
-            if i < seg_start || i >= seg_start + seg_len {
-                new_tour.push(node);
-            }
-            if i == insert_pos && insert_pos > seg_start + seg_len {
-                new_tour.extend(&segment);
-            }
-        }
-        
-        if insert_pos == tour.len() {
-            new_tour.extend(&segment);
-        }
-        
-        instance.is_feasible(&new_tour)
-    }
-    
-    /// Apply segment relocation
-    fn apply_relocation(&self, tour: &mut Vec<usize>, seg_start: usize, seg_len: usize, insert_pos: usize) {
-        let segment: Vec<usize> = tour.drain(seg_start..seg_start + seg_len).collect();
-        let adj_pos = if insert_pos > seg_start { insert_pos - seg_len } else { insert_pos };
-        
-        for (i, node) in segment.into_iter().enumerate() {
-            tour.insert(adj_pos + i, node);
-        }
+    /// Enable or disable segment reversal on reinsertion
+    pub fn with_reversal(mut self, allow_reversal: bool) -> Self {
+        self.allow_reversal = allow_reversal;
+        self
     }
 }
 
@@ -267,6 +424,8 @@ impl LocalSearch for OrOptSearch {
         let n = solution.tour.len();
         if n < 3 { return false; }
         
+        let orientations: &[bool] = if self.allow_reversal { &[false, true] } else { &[false] };
+        let mut run = OperatorRun::start(solution);
         let mut improved = true;
         let mut total_improved = false;
         let mut iterations = 0;
@@ -274,63 +433,56 @@ impl LocalSearch for OrOptSearch {
         
         while improved && iterations < max_iterations {
             improved = false;
-            let mut best_delta = 0.0;
-            let mut best_seg_start = 0;
-            let mut best_seg_len = 1;
-            let mut best_insert_pos = 0;
+            let mut best: Option<(f64, Vec<usize>)> = None;
             iterations += 1;
+            let evaluator = DeltaEvaluator::new(instance, &solution.tour);
             
-            for seg_len in 1..=self.max_segment_length.min(n - 1) {
-                for seg_start in 0..n - seg_len + 1 {
-                    
-                    if solution.tour[seg_start] == 0 {
+            'search: for seg_len in 1..=self.max_segment_length.min(n - 2) {
+                for seg_start in 1..=n - seg_len {
+                    if solution.tour[seg_start..seg_start + seg_len].contains(&0) {
                         continue;
                     }
                     
-                    for insert_pos in 0..=n - seg_len {
-                        if insert_pos >= seg_start && insert_pos <= seg_start + seg_len {
+                    // Insert after any node outside the segment, before or after it
+                    for after in 0..n {
+                        if after + 1 >= seg_start && after < seg_start + seg_len {
                             continue;
                         }
                         
-                        let delta = self.segment_relocation_delta(
-                            instance, &solution.tour, seg_start, seg_len, insert_pos
-                        );
-                        
-                        if delta < -1e-9 {
-                            if self.is_feasible_relocation(instance, &solution.tour, seg_start, seg_len, insert_pos) {
+                        for &reversed in orientations {
+                            if reversed && seg_len == 1 {
+                                continue;
+                            }
+                            
+                            let delta = evaluator.or_opt_delta(seg_start, seg_len, after, reversed);
+                            run.evaluated += 1;
+                            if delta >= -1e-9 || best.as_ref().is_some_and(|(d, _)| delta >= *d) {
+                                continue;
+                            }
+                            
+                            if evaluator.or_opt_feasible(seg_start, seg_len, after, reversed) {
+                                let new_tour = relocate_segment(&solution.tour, seg_start, seg_len, after, reversed);
+                                best = Some((delta, new_tour));
                                 if self.first_improvement {
-                                    self.apply_relocation(&mut solution.tour, seg_start, seg_len, insert_pos);
-                                    solution.cost += delta;
-                                    improved = true;
-                                    total_improved = true;
-                                    break;
-                                } else if delta < best_delta {
-                                    best_delta = delta;
-                                    best_seg_start = seg_start;
-                                    best_seg_len = seg_len;
-                                    best_insert_pos = insert_pos;
+                                    break 'search;
                                 }
                             }
                         }
                     }
-                    if improved && self.first_improvement {
-                        break;
-                    }
-                }
-                if improved && self.first_improvement {
-                    break;
                 }
             }
             
-            if !self.first_improvement && best_delta < -1e-9 {
-                self.apply_relocation(&mut solution.tour, best_seg_start, best_seg_len, best_insert_pos);
-                solution.cost += best_delta;
+            if let Some((delta, new_tour)) = best {
+                solution.tour = new_tour;
+                solution.cost += delta;
+                run.applied += 1;
                 improved = true;
                 total_improved = true;
             }
         }
         
         solution.validate(instance);
+        run.finish(self.name(), solution);
         total_improved
     }
     
@@ -341,46 +493,63 @@ impl LocalSearch for OrOptSearch {
 
  
 
-/// Node Swap Local Search
+/// CROSS-exchange Local Search
 /// 
-/// Swaps pairs of nodes to improve tour quality.
-pub struct SwapSearch {
+/// Exchanges two non-overlapping segments of 1 to 3 consecutive nodes,
+/// optionally reversing either of them. Unlike Or-opt, both segments move,
+/// which changes the load over the whole span between them by the difference
+/// of their demands: under load-dependent costs this reaches tours that
+/// 2-opt and Or-opt moves cannot.
+pub struct CrossExchangeSearch {
+    /// Maximum length of each segment
+    pub max_segment_length: usize,
     /// Use first improvement
     pub first_improvement: bool,
+    /// Also try reinserting the segments in reverse order
+    pub allow_reversal: bool,
 }
 
-impl SwapSearch {
+impl CrossExchangeSearch {
     pub fn new() -> Self {
-        SwapSearch {
+        CrossExchangeSearch {
+            max_segment_length: 3,
             first_improvement: false,
+            allow_reversal: true,
         }
     }
     
     pub fn first_improvement() -> Self {
-        SwapSearch {
+        CrossExchangeSearch {
             first_improvement: true,
+            ..Self::new()
         }
     }
     
-    /// Check if swap maintains feasibility
-    fn is_feasible_swap(&self, instance: &PDTSPInstance, tour: &[usize], i: usize, j: usize) -> bool {
-        let mut new_tour = tour.to_vec();
-        new_tour.swap(i, j);
-        instance.is_feasible(&new_tour)
+    /// Enable or disable segment reversal
+    pub fn with_reversal(mut self, allow_reversal: bool) -> Self {
+        self.allow_reversal = allow_reversal;
+        self
     }
 }
 
-impl Default for SwapSearch {
+impl Default for CrossExchangeSearch {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl LocalSearch for SwapSearch {
+impl LocalSearch for CrossExchangeSearch {
     fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
         let n = solution.tour.len();
         if n < 3 { return false; }
         
+        let orientations: &[(bool, bool)] = if self.allow_reversal {
+            &[(false, false), (true, false), (false, true), (true, true)]
+        } else {
+            &[(false, false)]
+        };
+        let max_len = self.max_segment_length.min(n - 2);
+        let mut run = OperatorRun::start(solution);
         let mut improved = true;
         let mut total_improved = false;
         let mut iterations = 0;
@@ -388,233 +557,880 @@ impl LocalSearch for SwapSearch {
         
         while improved && iterations < max_iterations {
             improved = false;
-            let mut best_delta = 0.0;
-            let mut best_i = 0;
-            let mut best_j = 0;
+            let mut best: Option<(f64, Vec<usize>)> = None;
             iterations += 1;
+            let evaluator = DeltaEvaluator::new(instance, &solution.tour);
+            let tour = &solution.tour;
             
-            for i in 1..n - 1 {
-                for j in i + 1..n {
-                    // Don't swap depot
-                    if solution.tour[i] == 0 || solution.tour[j] == 0 {
+            'search: for a_len in 1..=max_len {
+                for a_start in 1..n {
+                    if a_start + a_len > n || tour[a_start..a_start + a_len].contains(&0) {
                         continue;
                     }
-                    
-                    let delta = solution.swap_delta(instance, i, j);
-                    
-                    if delta < -1e-9 {
-                        if self.is_feasible_swap(instance, &solution.tour, i, j) {
-                            if self.first_improvement {
-                                solution.apply_swap(i, j);
-                                solution.cost += delta;
-                                improved = true;
-                                total_improved = true;
-                                break;
-                            } else if delta < best_delta {
-                                best_delta = delta;
-                                best_i = i;
-                                best_j = j;
+                    for b_len in 1..=max_len {
+                        for b_start in a_start + a_len..=n.saturating_sub(b_len) {
+                            if tour[b_start..b_start + b_len].contains(&0) {
+                                continue;
+                            }
+                            for &(reverse_a, reverse_b) in orientations {
+                                // Reversing a single node changes nothing
+                                if (reverse_a && a_len == 1) || (reverse_b && b_len == 1) {
+                                    continue;
+                                }
+                                let delta = evaluator.cross_exchange_delta(a_start, a_len, b_start, b_len, reverse_a, reverse_b);
+                                run.evaluated += 1;
+                                if delta >= -1e-9 || best.as_ref().is_some_and(|(d, _)| delta >= *d) {
+                                    continue;
+                                }
+                                if evaluator.cross_exchange_feasible(a_start, a_len, b_start, b_len, reverse_a, reverse_b) {
+                                    best = Some((delta, cross_exchange(tour, a_start, a_len, b_start, b_len, reverse_a, reverse_b)));
+                                    if self.first_improvement {
+                                        break 'search;
+                                    }
+                                }
                             }
                         }
                     }
                 }
-                if improved && self.first_improvement {
-                    break;
-                }
             }
             
-            if !self.first_improvement && best_delta < -1e-9 {
-                solution.apply_swap(best_i, best_j);
-                solution.cost += best_delta;
+            if let Some((delta, new_tour)) = best {
+                solution.tour = new_tour;
+                solution.cost += delta;
+                run.applied += 1;
                 improved = true;
                 total_improved = true;
             }
         }
         
         solution.validate(instance);
+        run.finish(self.name(), solution);
         total_improved
     }
     
     fn name(&self) -> &str {
-        "Swap"
+        "CROSS-Exchange"
     }
 }
 
  
 
-/// Node Relocation Local Search
+/// 2h-opt Local Search with load-aware move ordering
 /// 
-/// Removes a node and reinserts it at a better position.
-pub struct RelocationSearch {
-    /// Use first improvement
-    pub first_improvement: bool,
+/// Prices every 2-opt move and every Or-opt relocation of 1 to
+/// `max_segment_length` nodes (single node insertions being the "half"
+/// moves of 2h-opt) on the same tour, keeps the `ranked_moves` most
+/// improving ones by their true delta under the cost function and applies
+/// the best feasible one. Under
+/// `LinearLoad` or `Quadratic` costs the move saving the most distance is
+/// often not the one saving the most cost, as a reversal changes where heavy
+/// loads are carried.
+pub struct TwoHOptSearch {
+    /// Maximum Or-opt segment length (1 = node insertions only)
+    pub max_segment_length: usize,
+    /// Maximum number of moves applied per call
+    pub max_iterations: usize,
+    /// Number of best improving moves kept per pass; when all of them are
+    /// infeasible the pass falls back to a scan for the best feasible move
+    pub ranked_moves: usize,
 }
 
-impl RelocationSearch {
+impl TwoHOptSearch {
     pub fn new() -> Self {
-        RelocationSearch {
-            first_improvement: false,
-        }
-    }
-    
-    pub fn first_improvement() -> Self {
-        RelocationSearch {
-            first_improvement: true,
+        TwoHOptSearch {
+            max_segment_length: 3,
+            max_iterations: 50,
+            ranked_moves: 16,
         }
     }
     
-    /// Calculate relocation delta
-    fn relocation_delta(&self, instance: &PDTSPInstance, tour: &[usize], from: usize, to: usize) -> f64 {
-        if from == to || from + 1 == to {
-            return 0.0;
-        }
-        
+    /// Candidate 2-opt and Or-opt moves on `tour`, generated lazily
+    fn candidates<'t>(&self, tour: &'t [usize]) -> impl Iterator<Item = StandardMove> + 't {
         let n = tour.len();
-        let node = tour[from];
-        let prev_from = if from == 0 { n - 1 } else { from - 1 };
-        let next_from = (from + 1) % n;
-        
-        
-        let removal = -instance.distance(tour[prev_from], node)
-            - instance.distance(node, tour[next_from])
-            + instance.distance(tour[prev_from], tour[next_from]);
-        
-        
-        let adj_to = if to > from { to - 1 } else { to };
-        let prev_to = if adj_to == 0 { n - 2 } else { adj_to - 1 };
-        let next_to = adj_to;
-        
-        
-        let actual_prev = if prev_to == from { tour[prev_from] }
-            else if prev_to > from { tour[prev_to + 1] }
-            else { tour[prev_to] };
-        
-        let actual_next = if next_to == from { tour[next_from] }
-            else if next_to > from { tour[next_to + 1] }
-            else { tour[next_to] };
-        
-        
-        let insertion = instance.distance(actual_prev, node)
-            + instance.distance(node, actual_next)
-            - instance.distance(actual_prev, actual_next);
-        
-        removal + insertion
-    }
-    
-    /// Check if relocation maintains feasibility
-    fn is_feasible_relocation(&self, instance: &PDTSPInstance, tour: &[usize], from: usize, to: usize) -> bool {
-        let mut new_tour = tour.to_vec();
-        let node = new_tour.remove(from);
-        let insert_pos = if to > from { to - 1 } else { to };
-        new_tour.insert(insert_pos, node);
-        instance.is_feasible(&new_tour)
+        let two_opt = (0..n - 2)
+            .flat_map(move |i| (i + 2..n).filter(move |&j| i > 0 || j < n - 1).map(move |j| StandardMove::TwoOpt(i, j)));
+        let or_opt = (1..=self.max_segment_length.min(n - 2))
+            .flat_map(move |len| (1..=n - len).map(move |start| (start, len)))
+            .filter(move |&(start, len)| !tour[start..start + len].contains(&0))
+            .flat_map(move |(start, len)| {
+                (1..start).chain(start + len + 1..=n).map(move |to| StandardMove::OrOpt(start, len, to))
+            });
+        two_opt.chain(or_opt)
     }
+
 }
 
-impl Default for RelocationSearch {
+impl Default for TwoHOptSearch {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl LocalSearch for RelocationSearch {
+impl LocalSearch for TwoHOptSearch {
     fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
         let n = solution.tour.len();
-        if n < 3 { return false; }
+        if n < 4 { return false; }
         
-        let mut improved = true;
+        let mut run = OperatorRun::start(solution);
         let mut total_improved = false;
-        let mut iterations = 0;
-        let max_iterations = 20;
         
-        while improved && iterations < max_iterations {
-            improved = false;
-            let mut best_delta = 0.0;
-            let mut best_from = 0;
-            let mut best_to = 0;
-            iterations += 1;
-            
-            for from in 0..n {
-                
-                if solution.tour[from] == 0 {
-                    continue;
-                }
-                
-                for to in 0..n {
-                    if to == from || to == from + 1 {
-                        continue;
-                    }
-                    
-                    let delta = self.relocation_delta(instance, &solution.tour, from, to);
-                    
-                    if delta < -1e-9 {
-                        if self.is_feasible_relocation(instance, &solution.tour, from, to) {
-                            if self.first_improvement {
-                                solution.apply_insertion(from, to);
-                                solution.cost += delta;
-                                improved = true;
-                                total_improved = true;
-                                break;
-                            } else if delta < best_delta {
-                                best_delta = delta;
-                                best_from = from;
-                                best_to = to;
-                            }
-                        }
-                    }
-                }
-                if improved && self.first_improvement {
-                    break;
-                }
+        for _ in 0..self.max_iterations {
+            let evaluator = DeltaEvaluator::new(instance, &solution.tour);
+            let mut evaluated = 0;
+            let ranked = evaluator.best_moves(self.candidates(&solution.tour).inspect(|_| evaluated += 1), self.ranked_moves);
+            let full = ranked.len() == self.ranked_moves;
+            let mut found = ranked.into_iter().find(|(_, mv)| mv.is_feasible(&evaluator));
+            if found.is_none() && full {
+                found = evaluator.best_feasible_move(self.candidates(&solution.tour).inspect(|_| evaluated += 1));
             }
+            run.evaluated += evaluated;
             
-            if !self.first_improvement && best_delta < -1e-9 {
-                solution.apply_insertion(best_from, best_to);
-                solution.cost += best_delta;
-                improved = true;
-                total_improved = true;
-            }
+            let Some((delta, mv)) = found else {
+                break;
+            };
+            solution.apply_move(&mv);
+            solution.cost += delta;
+            run.applied += 1;
+            total_improved = true;
         }
         
         solution.validate(instance);
+        run.finish(self.name(), solution);
         total_improved
     }
     
     fn name(&self) -> &str {
-        "Relocation"
+        "2h-Opt"
     }
 }
 
  
 
-/// Variable Neighborhood Descent (VND)
+/// Load Shift Local Search
 /// 
-/// Applies multiple local search operators in a systematic way.
-pub struct VND {
-    /// List of local search operators
-    operators: Vec<Box<dyn LocalSearch + Send + Sync>>,
+/// Relocates the heaviest customers in the direction that lowers the load
+/// carried: pickups later in the tour and deliveries earlier, so the load
+/// over the nodes they jump over drops by their demand. Each move is priced
+/// by its full delta, distance included, so it only pays off when the load
+/// saving outweighs the detour; it targets load-dependent cost functions.
+pub struct LoadShiftSearch {
+    /// Share of the customers, heaviest first, that are relocated
+    pub heavy_fraction: f64,
+    /// Use first improvement
+    pub first_improvement: bool,
+}
+
+impl LoadShiftSearch {
+    pub fn new() -> Self {
+        LoadShiftSearch {
+            heavy_fraction: 0.25,
+            first_improvement: false,
+        }
+    }
+    
+    pub fn first_improvement() -> Self {
+        LoadShiftSearch {
+            first_improvement: true,
+            ..Self::new()
+        }
+    }
+}
+
+impl Default for LoadShiftSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSearch for LoadShiftSearch {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        let n = solution.tour.len();
+        if n < 4 { return false; }
+        
+        let demand = |node: usize| instance.nodes[node].demand;
+        let mut heavy: Vec<usize> = solution.tour.iter().copied().filter(|&node| node != 0 && demand(node) != 0).collect();
+        heavy.sort_by_key(|&node| std::cmp::Reverse(demand(node).abs()));
+        heavy.truncate(((heavy.len() as f64 * self.heavy_fraction).ceil() as usize).max(1));
+        
+        let mut run = OperatorRun::start(solution);
+        let mut improved = true;
+        let mut total_improved = false;
+        let mut iterations = 0;
+        let max_iterations = 20;
+        
+        while improved && iterations < max_iterations {
+            improved = false;
+            iterations += 1;
+            let evaluator = DeltaEvaluator::new(instance, &solution.tour);
+            let mut position = vec![usize::MAX; instance.dimension];
+            for (pos, &node) in solution.tour.iter().enumerate().skip(1) {
+                position[node] = pos;
+            }
+            let mut best: Option<(f64, StandardMove)> = None;
+            
+            'search: for &node in &heavy {
+                let from = position[node];
+                // Pickups move later, deliveries earlier
+                let targets = if demand(node) > 0 { from + 2..n + 1 } else { 1..from };
+                for to in targets {
+                    let mv = StandardMove::Insertion(from, to);
+                    let delta = mv.delta(&evaluator);
+                    run.evaluated += 1;
+                    if delta >= -1e-9 || best.is_some_and(|(d, _)| delta >= d) {
+                        continue;
+                    }
+                    if mv.is_feasible(&evaluator) {
+                        best = Some((delta, mv));
+                        if self.first_improvement {
+                            break 'search;
+                        }
+                    }
+                }
+            }
+            
+            if let Some((delta, mv)) = best {
+                solution.apply_move(&mv);
+                solution.cost += delta;
+                run.applied += 1;
+                improved = true;
+                total_improved = true;
+            }
+        }
+        
+        solution.validate(instance);
+        run.finish(self.name(), solution);
+        total_improved
+    }
+    
+    fn name(&self) -> &str {
+        "Load-Shift"
+    }
+}
+
+ 
+
+/// Node Swap Local Search
+/// 
+/// Swaps pairs of nodes to improve tour quality.
+pub struct SwapSearch {
+    /// Use first improvement
+    pub first_improvement: bool,
+}
+
+impl SwapSearch {
+    pub fn new() -> Self {
+        SwapSearch {
+            first_improvement: false,
+        }
+    }
+    
+    pub fn first_improvement() -> Self {
+        SwapSearch {
+            first_improvement: true,
+        }
+    }
+}
+
+impl Default for SwapSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSearch for SwapSearch {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        let n = solution.tour.len();
+        if n < 3 { return false; }
+        
+        let mut run = OperatorRun::start(solution);
+        let mut improved = true;
+        let mut total_improved = false;
+        let mut iterations = 0;
+        let max_iterations = 20;
+        
+        while improved && iterations < max_iterations {
+            improved = false;
+            let mut best_delta: f64 = 0.0;
+            let mut best_i = 0;
+            let mut best_j = 0;
+            iterations += 1;
+            let evaluator = DeltaEvaluator::new(instance, &solution.tour);
+            
+            'scan: for i in 1..n - 1 {
+                for j in i + 1..n {
+                    // Don't swap depot
+                    if solution.tour[i] == 0 || solution.tour[j] == 0 {
+                        continue;
+                    }
+                    
+                    let delta = evaluator.swap_delta(i, j);
+                    run.evaluated += 1;
+                    
+                    if delta < best_delta.min(-1e-9) && evaluator.swap_feasible(i, j) {
+                        best_delta = delta;
+                        best_i = i;
+                        best_j = j;
+                        if self.first_improvement {
+                            break 'scan;
+                        }
+                    }
+                }
+            }
+            
+            if best_delta < -1e-9 {
+                solution.apply_swap(best_i, best_j);
+                solution.cost += best_delta;
+                run.applied += 1;
+                improved = true;
+                total_improved = true;
+            }
+        }
+        
+        solution.validate(instance);
+        run.finish(self.name(), solution);
+        total_improved
+    }
+    
+    fn name(&self) -> &str {
+        "Swap"
+    }
+}
+
+ 
+
+/// Node Relocation Local Search
+/// 
+/// Removes a node and reinserts it at a better position.
+pub struct RelocationSearch {
+    /// Use first improvement
+    pub first_improvement: bool,
+}
+
+impl RelocationSearch {
+    pub fn new() -> Self {
+        RelocationSearch {
+            first_improvement: false,
+        }
+    }
+    
+    pub fn first_improvement() -> Self {
+        RelocationSearch {
+            first_improvement: true,
+        }
+    }
+}
+
+impl Default for RelocationSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSearch for RelocationSearch {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        let n = solution.tour.len();
+        if n < 3 { return false; }
+        
+        let mut run = OperatorRun::start(solution);
+        let mut improved = true;
+        let mut total_improved = false;
+        let mut iterations = 0;
+        let max_iterations = 20;
+        
+        while improved && iterations < max_iterations {
+            improved = false;
+            let mut best_delta: f64 = 0.0;
+            let mut best_from = 0;
+            let mut best_to = 0;
+            iterations += 1;
+            let evaluator = DeltaEvaluator::new(instance, &solution.tour);
+            
+            // Insert before any position after the depot, or at the end of the tour
+            'scan: for from in 0..n {
+                
+                if solution.tour[from] == 0 {
+                    continue;
+                }
+                
+                for to in 1..=n {
+                    if to == from || to == from + 1 {
+                        continue;
+                    }
+                    
+                    let delta = evaluator.relocation_delta(from, to);
+                    run.evaluated += 1;
+                    
+                    if delta < best_delta.min(-1e-9) && evaluator.relocation_feasible(from, to) {
+                        best_delta = delta;
+                        best_from = from;
+                        best_to = to;
+                        if self.first_improvement {
+                            break 'scan;
+                        }
+                    }
+                }
+            }
+            
+            if best_delta < -1e-9 {
+                solution.apply_insertion(best_from, best_to);
+                solution.cost += best_delta;
+                run.applied += 1;
+                improved = true;
+                total_improved = true;
+            }
+        }
+        
+        solution.validate(instance);
+        run.finish(self.name(), solution);
+        total_improved
+    }
+    
+    fn name(&self) -> &str {
+        "Relocation"
+    }
+}
+
+ 
+
+/// Ejection Chain Local Search
+/// 
+/// Removes a node and inserts it in place of another node, which is ejected
+/// and in turn takes the place of a third one, and so on up to `max_depth`
+/// ejections; the last ejected node is reinserted at its cheapest feasible
+/// position. The best improving chain is applied, which escapes local optima
+/// of single swap/relocate moves.
+pub struct EjectionChainSearch {
+    /// Maximum number of ejections in a chain
+    pub max_depth: usize,
+    /// Number of candidate nodes to eject at each level of the chain
+    pub candidates: usize,
+    /// Maximum number of improving chains applied per call
+    pub max_iterations: usize,
+}
+
+impl EjectionChainSearch {
+    pub fn new() -> Self {
+        EjectionChainSearch {
+            max_depth: 3,
+            candidates: 5,
+            max_iterations: 50,
+        }
+    }
+    
+    pub fn with_params(max_depth: usize, candidates: usize, max_iterations: usize) -> Self {
+        EjectionChainSearch {
+            max_depth,
+            candidates,
+            max_iterations,
+        }
+    }
+    
+    /// Insert `node` at its cheapest (by distance) feasible position in the
+    /// partial tour, returning the completed tour and its cost
+    fn close_chain(&self, instance: &PDTSPInstance, partial: &[usize], node: usize) -> Option<(Vec<usize>, f64)> {
+        let n = partial.len();
+        let mut positions: Vec<(f64, usize)> = (1..=n)
+            .map(|p| {
+                let prev = partial[p - 1];
+                let next = partial[p % n];
+                let delta = instance.distance(prev, node) + instance.distance(node, next) - instance.distance(prev, next);
+                (delta, p)
+            })
+            .collect();
+        positions.sort_by(|a, b| a.0.total_cmp(&b.0));
+        
+        let mut tour = partial.to_vec();
+        for (_, p) in positions {
+            tour.insert(p, node);
+            if instance.is_feasible(&tour) {
+                let cost = instance.tour_cost(&tour);
+                return Some((tour, cost));
+            }
+            tour.remove(p);
+        }
+        None
+    }
+    
+    /// Explore the chains continuing from `partial`, in which `ejected` is
+    /// still unplaced, and keep the cheapest completed tour in `best`;
+    /// `evaluated` counts the chains closed
+    fn extend_chain(
+        &self,
+        instance: &PDTSPInstance,
+        partial: &mut Vec<usize>,
+        ejected: usize,
+        depth: usize,
+        best: &mut Option<(Vec<usize>, f64)>,
+        evaluated: &mut u64,
+    ) {
+        *evaluated += 1;
+        if let Some((tour, cost)) = self.close_chain(instance, partial, ejected) {
+            if best.as_ref().is_none_or(|(_, best_cost)| cost < *best_cost) {
+                *best = Some((tour, cost));
+            }
+        }
+        if depth >= self.max_depth {
+            return;
+        }
+        
+        // Cheapest replacements of a node by the ejected one
+        let n = partial.len();
+        let mut replacements: Vec<(f64, usize)> = (1..n)
+            .filter(|&p| partial[p] != 0)
+            .map(|p| {
+                let prev = partial[p - 1];
+                let next = partial[(p + 1) % n];
+                let delta = instance.distance(prev, ejected) + instance.distance(ejected, next)
+                    - instance.distance(prev, partial[p]) - instance.distance(partial[p], next);
+                (delta, p)
+            })
+            .collect();
+        replacements.sort_by(|a, b| a.0.total_cmp(&b.0));
+        
+        for &(_, p) in replacements.iter().take(self.candidates) {
+            let next_ejected = std::mem::replace(&mut partial[p], ejected);
+            self.extend_chain(instance, partial, next_ejected, depth + 1, best, evaluated);
+            partial[p] = next_ejected;
+        }
+    }
+}
+
+impl Default for EjectionChainSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSearch for EjectionChainSearch {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        let n = solution.tour.len();
+        if n < 4 { return false; }
+        
+        let mut run = OperatorRun::start(solution);
+        let mut total_improved = false;
+        let mut current_cost = instance.tour_cost(&solution.tour);
+        
+        for _ in 0..self.max_iterations {
+            let mut best: Option<(Vec<usize>, f64)> = None;
+            
+            for start in 1..n {
+                if solution.tour[start] == 0 {
+                    continue;
+                }
+                let mut partial = solution.tour.clone();
+                let node = partial.remove(start);
+                self.extend_chain(instance, &mut partial, node, 1, &mut best, &mut run.evaluated);
+            }
+            
+            match best {
+                Some((tour, cost)) if cost < current_cost - 1e-9 => {
+                    solution.tour = tour;
+                    current_cost = cost;
+                    run.applied += 1;
+                    total_improved = true;
+                }
+                _ => break,
+            }
+        }
+        
+        solution.validate(instance);
+        run.finish(self.name(), solution);
+        total_improved
+    }
+    
+    fn name(&self) -> &str {
+        "EjectionChain"
+    }
+}
+
+ 
+
+/// Depot Reinsertion Local Search
+/// 
+/// Inserts an intermediate depot visit, which unloads the vehicle, at any
+/// position of the tour, or removes one. Visits are only added when they
+/// reduce the capacity violation of the tour, and removed when the violation
+/// does not grow and the tour gets cheaper, so instances whose pickups do
+/// not fit in a single loop become solvable while tours that never exceed
+/// the capacity are left unchanged.
+pub struct DepotReinsertionSearch {
+    /// Use first improvement
+    pub first_improvement: bool,
+}
+
+impl DepotReinsertionSearch {
+    pub fn new() -> Self {
+        DepotReinsertionSearch {
+            first_improvement: false,
+        }
+    }
+    
+    pub fn first_improvement() -> Self {
+        DepotReinsertionSearch {
+            first_improvement: true,
+        }
+    }
+}
+
+impl Default for DepotReinsertionSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSearch for DepotReinsertionSearch {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        let n = solution.tour.len();
+        if n < 3 || solution.tour[0] != 0 { return false; }
+        
+        let mut run = OperatorRun::start(solution);
+        let mut total_improved = false;
+        let max_iterations = n;
+        
+        for _ in 0..max_iterations {
+            let tour = &solution.tour;
+            let violation = instance.load_violation(tour.iter().copied());
+            if violation == 0 && !tour[1..].contains(&0) {
+                break;
+            }
+            
+            let current_cost = instance.tour_cost(tour);
+            let mut best: Option<(i64, f64, Vec<usize>)> = None;
+            let mut consider = |candidate_violation: i64, candidate: Vec<usize>| -> bool {
+                let cost = instance.tour_cost(&candidate);
+                let improves = candidate_violation < violation
+                    || (candidate_violation == violation && cost < current_cost - 1e-9);
+                let better = best.as_ref().is_none_or(|(v, c, _)| (candidate_violation, cost) < (*v, *c));
+                if improves && better {
+                    best = Some((candidate_violation, cost, candidate));
+                }
+                improves
+            };
+            
+            let mut found = false;
+            
+            // Remove a depot visit
+            for p in 1..tour.len() {
+                if tour[p] != 0 {
+                    continue;
+                }
+                let without = || tour[..p].iter().chain(&tour[p + 1..]).copied();
+                let candidate_violation = instance.load_violation(without());
+                run.evaluated += 1;
+                if candidate_violation <= violation && consider(candidate_violation, without().collect()) {
+                    found = true;
+                    if self.first_improvement {
+                        break;
+                    }
+                }
+            }
+            
+            // Insert a depot visit before position p, between two customers
+            if violation > 0 && !(found && self.first_improvement) {
+                for p in 2..tour.len() {
+                    if tour[p - 1] == 0 || tour[p] == 0 {
+                        continue;
+                    }
+                    let with = || tour[..p].iter().chain(std::iter::once(&0)).chain(&tour[p..]).copied();
+                    let candidate_violation = instance.load_violation(with());
+                    run.evaluated += 1;
+                    if candidate_violation < violation
+                        && consider(candidate_violation, with().collect())
+                        && self.first_improvement
+                    {
+                        break;
+                    }
+                }
+            }
+            
+            match best {
+                Some((_, _, tour)) => {
+                    solution.tour = tour;
+                    run.applied += 1;
+                    total_improved = true;
+                }
+                None => break,
+            }
+        }
+        
+        solution.validate(instance);
+        run.finish(self.name(), solution);
+        total_improved
+    }
+    
+    fn name(&self) -> &str {
+        "DepotReinsertion"
+    }
+}
+
+ 
+
+/// Operators of a configurable VND, by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VNDOperator {
+    TwoOpt,
+    Swap,
+    Relocation,
+    OrOpt,
+    DepotReinsertion,
+    CrossExchange,
+    TwoHOpt,
+    LoadShift,
+    EjectionChain,
+}
+
+impl VNDOperator {
+    pub const ALL: [VNDOperator; 9] = [
+        VNDOperator::TwoOpt,
+        VNDOperator::Swap,
+        VNDOperator::Relocation,
+        VNDOperator::OrOpt,
+        VNDOperator::DepotReinsertion,
+        VNDOperator::CrossExchange,
+        VNDOperator::TwoHOpt,
+        VNDOperator::LoadShift,
+        VNDOperator::EjectionChain,
+    ];
+    
+    /// Sequence of `VND::with_standard_operators`
+    pub const STANDARD: [VNDOperator; 5] = [
+        VNDOperator::TwoOpt,
+        VNDOperator::Swap,
+        VNDOperator::Relocation,
+        VNDOperator::OrOpt,
+        VNDOperator::DepotReinsertion,
+    ];
+    
+    /// Name in configuration files and on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            VNDOperator::TwoOpt => "two-opt",
+            VNDOperator::Swap => "swap",
+            VNDOperator::Relocation => "relocation",
+            VNDOperator::OrOpt => "or-opt",
+            VNDOperator::DepotReinsertion => "depot-reinsertion",
+            VNDOperator::CrossExchange => "cross-exchange",
+            VNDOperator::TwoHOpt => "two-h-opt",
+            VNDOperator::LoadShift => "load-shift",
+            VNDOperator::EjectionChain => "ejection-chain",
+        }
+    }
+    
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        Self::ALL.into_iter().find(|op| op.name() == name).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|op| op.name()).collect();
+            format!("Unknown VND operator {:?} (expected one of {})", name, names.join(", "))
+        })
+    }
+    
+    /// The operator with its VND settings (first improvement where available)
+    pub fn build(self) -> Box<dyn LocalSearch + Send + Sync> {
+        match self {
+            VNDOperator::TwoOpt => Box::new(TwoOptSearch::first_improvement().with_dont_look_bits(true)),
+            VNDOperator::Swap => Box::new(SwapSearch::first_improvement()),
+            VNDOperator::Relocation => Box::new(RelocationSearch::first_improvement()),
+            VNDOperator::OrOpt => Box::new(OrOptSearch::first_improvement()),
+            VNDOperator::DepotReinsertion => Box::new(DepotReinsertionSearch::first_improvement()),
+            VNDOperator::CrossExchange => Box::new(CrossExchangeSearch::first_improvement()),
+            VNDOperator::TwoHOpt => Box::new(TwoHOptSearch::new()),
+            VNDOperator::LoadShift => Box::new(LoadShiftSearch::first_improvement()),
+            VNDOperator::EjectionChain => Box::new(EjectionChainSearch::new()),
+        }
+    }
+}
+
+/// Order in which `VND` tries its operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VNDOrdering {
+    /// The order the operators were added in
+    #[default]
+    Fixed,
+    /// Most improvement per second first, over the recent calls of the
+    /// operators (untried operators keep their place in front)
+    Adaptive,
+}
+
+/// Weight of the latest call in the adaptive improvement rate of an operator
+const ADAPTIVE_RATE_WEIGHT: f64 = 0.3;
+
+/// Variable Neighborhood Descent (VND)
+/// 
+/// Applies multiple local search operators in a systematic way: the first
+/// operator that improves the solution sends the descent back to the first
+/// one. With adaptive ordering the operators are sorted again by their
+/// recent cost improvement per second before every restart, so the cheapest
+/// gains under the instance's cost function come first; the rates carry over
+/// between calls on the same `VND` (e.g. the descents of one ILS or GVNS run,
+/// both built from `VNDParams`).
+pub struct VND {
+    /// List of local search operators
+    operators: Vec<Box<dyn LocalSearch + Send + Sync>>,
+    /// Stop before the next operator once passed
+    pub deadline: Deadline,
+    pub ordering: VNDOrdering,
+    /// Moving average of the improvement per second of each operator
+    /// (infinite until its first call)
+    rates: std::sync::Mutex<Vec<f64>>,
 }
 
 impl VND {
     pub fn new() -> Self {
         VND {
             operators: Vec::new(),
+            deadline: Deadline::none(),
+            ordering: VNDOrdering::Fixed,
+            rates: Default::default(),
         }
     }
     
-    pub fn with_standard_operators() -> Self {
-        let operators: Vec<Box<dyn LocalSearch + Send + Sync>> = vec![
-            Box::new(TwoOptSearch::first_improvement()),
-            Box::new(SwapSearch::first_improvement()),
-            Box::new(RelocationSearch::first_improvement()),
-            Box::new(OrOptSearch::first_improvement()),
-        ];
-        
-        VND { operators }
+    pub fn with_standard_operators() -> Self {
+        Self::with_operators(&VNDOperator::STANDARD)
+    }
+    
+    /// VND over the given operators, in that order
+    pub fn with_operators(operators: &[VNDOperator]) -> Self {
+        let mut vnd = Self::new();
+        vnd.set_operators(operators);
+        vnd
+    }
+    
+    /// Stop the descent once `deadline` has passed
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = deadline;
+        self
+    }
+    
+    pub fn with_ordering(mut self, ordering: VNDOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+    
+    pub fn add_operator<L: LocalSearch + Send + Sync + 'static>(&mut self, op: L) {
+        self.operators.push(Box::new(op));
+        self.rates.get_mut().unwrap().push(f64::INFINITY);
+    }
+    
+    /// Replace the operators by the given ones, in that order
+    pub fn set_operators(&mut self, operators: &[VNDOperator]) {
+        self.operators = operators.iter().map(|op| op.build()).collect();
+        *self.rates.get_mut().unwrap() = vec![f64::INFINITY; operators.len()];
+    }
+    
+    /// Operator indices in the order the next descent tries them
+    fn order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.operators.len()).collect();
+        if self.ordering == VNDOrdering::Adaptive {
+            let rates = self.rates.lock().unwrap();
+            order.sort_by(|&a, &b| rates[b].total_cmp(&rates[a]));
+        }
+        order
     }
     
-    pub fn add_operator<L: LocalSearch + Send + Sync + 'static>(&mut self, op: L) {
-        self.operators.push(Box::new(op));
+    /// Names of the operators in the order the next descent tries them
+    pub fn operator_names(&self) -> Vec<&str> {
+        self.order().into_iter().map(|k| self.operators[k].name()).collect()
     }
 }
 
@@ -626,15 +1442,36 @@ impl Default for VND {
 
 impl LocalSearch for VND {
     fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        self.improve_until(instance, solution, Deadline::none())
+    }
+    
+    fn improve_until(&self, instance: &PDTSPInstance, solution: &mut Solution, deadline: Deadline) -> bool {
+        let deadline = self.deadline.earliest(deadline);
         let mut total_improved = false;
+        let mut order = self.order();
         let mut k = 0;
         let mut total_iterations = 0;
         let max_total_iterations = 100; // Prevent infinite loops
         
-        while k < self.operators.len() && total_iterations < max_total_iterations {
-            if self.operators[k].improve(instance, solution) {
+        while k < order.len() && total_iterations < max_total_iterations && !deadline.expired() {
+            let op = order[k];
+            let (cost, start) = (solution.cost, web_time::Instant::now());
+            let improved = self.operators[op].improve_until(instance, solution, deadline);
+            if self.ordering == VNDOrdering::Adaptive {
+                let rate = (cost - solution.cost).max(0.0) / start.elapsed().as_secs_f64().max(1e-6);
+                let mut rates = self.rates.lock().unwrap();
+                rates[op] = if rates[op].is_finite() {
+                    (1.0 - ADAPTIVE_RATE_WEIGHT) * rates[op] + ADAPTIVE_RATE_WEIGHT * rate
+                } else {
+                    rate
+                };
+            }
+            if improved {
                 total_improved = true;
                 k = 0; // Restart from first operator
+                if self.ordering == VNDOrdering::Adaptive {
+                    order = self.order();
+                }
             } else {
                 k += 1; // Move to next operator
             }
@@ -651,9 +1488,215 @@ impl LocalSearch for VND {
 
  
 
+/// Dynamically weighted penalty on capacity violations.
+///
+/// A search using it may move through tours that exceed the capacity (or go
+/// below zero load), scored as cost + weight × violation, where the
+/// violation is summed over the positions of the tour. Every
+/// `update_interval` iterations the weight is multiplied by `adjustment` if
+/// fewer than `target_feasible` of the current tours were feasible, and
+/// divided by it if clearly more were, as in the penalty management of
+/// hybrid genetic search for VRPs. Only feasible tours are returned.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CapacityPenalty {
+    /// Initial cost per unit of violation (0 = the mean arc length of the starting tour)
+    pub initial_weight: f64,
+    /// Factor by which the weight is increased or decreased
+    pub adjustment: f64,
+    /// Target fraction of feasible current tours
+    pub target_feasible: f64,
+    /// Number of iterations between weight updates
+    pub update_interval: usize,
+}
+
+impl CapacityPenalty {
+    pub fn new() -> Self {
+        CapacityPenalty {
+            initial_weight: 0.0,
+            adjustment: 1.2,
+            target_feasible: 0.2,
+            update_interval: 100,
+        }
+    }
+    
+    /// Starting weight for a search from `tour`
+    pub fn starting_weight(&self, instance: &PDTSPInstance, tour: &[usize]) -> f64 {
+        if self.initial_weight > 0.0 {
+            return self.initial_weight;
+        }
+        (instance.tour_length(tour) / tour.len().max(1) as f64).max(1e-6)
+    }
+    
+    /// Weight after a window in which `feasible_fraction` of the tours were feasible
+    pub fn adjust(&self, weight: f64, feasible_fraction: f64) -> f64 {
+        if feasible_fraction < self.target_feasible - 0.05 {
+            weight * self.adjustment
+        } else if feasible_fraction > self.target_feasible + 0.05 {
+            (weight / self.adjustment).max(1e-6)
+        } else {
+            weight
+        }
+    }
+}
+
+impl Default for CapacityPenalty {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Moves of the random neighborhood of `SimulatedAnnealing`,
+/// `ThresholdAccepting` and `RecordToRecord`
+const NEIGHBOR_MOVES: [&str; 4] = ["2-Opt", "Swap", "Relocation", "Or-Opt"];
+
+/// Random move `NEIGHBOR_MOVES[move_type]` of the tour of `evaluator`,
+/// which keeps the depot first and its intermediate visits in place (2-opt
+/// moves stay between two visits). Moves are drawn by position in O(1),
+/// priced and checked by the evaluator, and only applied when accepted.
+fn random_move(evaluator: &DeltaEvaluator, rng: &mut ChaCha8Rng, move_type: usize) -> Option<StandardMove> {
+    let tour = evaluator.tour();
+    let n = tour.len();
+    match move_type {
+        0 => {
+            let i = rng.gen_range(0..n - 2);
+            let j = rng.gen_range(i + 2..n);
+            (!evaluator.spans_depot(i + 1, j + 1)).then_some(StandardMove::TwoOpt(i, j))
+        }
+        1 => {
+            let i = rng.gen_range(1..n);
+            let j = rng.gen_range(1..n);
+            (i != j && tour[i] != 0 && tour[j] != 0).then_some(StandardMove::Swap(i, j))
+        }
+        2 => {
+            let from = rng.gen_range(1..n);
+            let to = rng.gen_range(1..=n);
+            (tour[from] != 0 && to != from && to != from + 1).then_some(StandardMove::Insertion(from, to))
+        }
+        _ => {
+            // Or-opt (segment of length 2)
+            if n < 4 {
+                return None;
+            }
+            let seg_start = rng.gen_range(1..n - 1);
+            let to = rng.gen_range(1..=n);
+            let outside = to < seg_start || to > seg_start + 2;
+            (outside && !tour[seg_start..seg_start + 2].contains(&0)).then_some(StandardMove::OrOpt(seg_start, 2, to))
+        }
+    }
+}
+
+/// Move of the `NEIGHBOR_MOVES` of `SimulatedAnnealing` on a `TwoLevelList`,
+/// by node: every move is a few path reversals of O(√n) each
+#[derive(Debug, Clone, Copy)]
+enum ListMove {
+    /// Reverse the path from the first node to the second
+    Reverse(usize, usize),
+    /// Exchange two customers
+    Swap(usize, usize),
+    /// Move the path from `start` to `end` between `after` and its
+    /// successor; `before` is its predecessor, where `undo` puts it back:
+    /// (start, end, after, before)
+    Segment(usize, usize, usize, usize),
+}
+
+impl ListMove {
+    /// Random move of type `NEIGHBOR_MOVES[move_type]` of a tour of every
+    /// node, with its distance delta. The depot is never moved.
+    fn random(list: &TwoLevelList, instance: &PDTSPInstance, rng: &mut ChaCha8Rng, move_type: usize) -> Option<(ListMove, f64)> {
+        let n = list.len();
+        let d = |a: usize, b: usize| instance.distance(a, b);
+        let (next, prev) = (|node: usize| list.next(node), |node: usize| list.prev(node));
+        match move_type {
+            0 => {
+                // Replace (a, a') and (b, b') by (a, b) and (a', b')
+                let (a, b) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                let (a2, b2) = (next(a), next(b));
+                if a == b || b == a2 || b2 == a {
+                    return None;
+                }
+                Some((ListMove::Reverse(a2, b), d(a, b) + d(a2, b2) - d(a, a2) - d(b, b2)))
+            }
+            1 => {
+                let (a, b) = (rng.gen_range(1..n), rng.gen_range(1..n));
+                if a == b {
+                    return None;
+                }
+                let (pa, na, pb, nb) = (prev(a), next(a), prev(b), next(b));
+                let delta = if na == b {
+                    d(pa, b) + d(a, nb) - d(pa, a) - d(b, nb)
+                } else if nb == a {
+                    d(pb, a) + d(b, na) - d(pb, b) - d(a, na)
+                } else {
+                    d(pa, b) + d(b, na) + d(pb, a) + d(a, nb) - d(pa, a) - d(a, na) - d(pb, b) - d(b, nb)
+                };
+                Some((ListMove::Swap(a, b), delta))
+            }
+            _ => {
+                // Relocation of a customer, or or-opt of two
+                let start = rng.gen_range(1..n);
+                let end = if move_type == 2 { start } else { next(start) };
+                let after = rng.gen_range(0..n);
+                let (before, following) = (prev(start), next(end));
+                if end == 0 || after == start || after == end || after == before {
+                    return None;
+                }
+                let next_after = next(after);
+                let delta = d(before, following) + d(after, start) + d(end, next_after)
+                    - d(before, start) - d(end, following) - d(after, next_after);
+                Some((ListMove::Segment(start, end, after, before), delta))
+            }
+        }
+    }
+    
+    fn apply(&self, list: &mut TwoLevelList) {
+        match *self {
+            ListMove::Reverse(a, b) => list.reverse(a, b),
+            ListMove::Swap(a, b) => Self::swap(list, a, b),
+            ListMove::Segment(start, end, after, _) => Self::move_segment(list, start, end, after),
+        }
+    }
+    
+    fn undo(&self, list: &mut TwoLevelList) {
+        match *self {
+            ListMove::Reverse(a, b) => list.reverse(b, a),
+            ListMove::Swap(a, b) => Self::swap(list, a, b),
+            ListMove::Segment(start, end, _, before) => Self::move_segment(list, start, end, before),
+        }
+    }
+    
+    /// `p a X b q` becomes `p b X a q` by reversing `a X b`, then `X`
+    fn swap(list: &mut TwoLevelList, a: usize, b: usize) {
+        if list.next(a) == b {
+            list.reverse(a, b);
+        } else if list.next(b) == a {
+            list.reverse(b, a);
+        } else {
+            list.reverse(a, b);
+            let (first, last) = (list.next(b), list.prev(a));
+            list.reverse(first, last);
+        }
+    }
+    
+    /// `p X Y b` becomes `p Y X b` (`Y` ending at `after`) by reversing `X`,
+    /// `Y`, then both
+    fn move_segment(list: &mut TwoLevelList, start: usize, end: usize, after: usize) {
+        let following = list.next(end);
+        list.reverse(start, end);
+        list.reverse(following, after);
+        list.reverse(end, following);
+    }
+}
+
 /// Simulated Annealing
 /// 
 /// Metaheuristic that accepts worse solutions with decreasing probability.
+/// Neighbors cost O(1) (see `random_move`), so the schedule can afford many
+/// iterations on large instances. With a `CapacityPenalty` it also walks
+/// through infeasible tours, pricing the change of load violation in O(1)
+/// as well. From `two_level_threshold` nodes on, Euclidean distance tours
+/// with on-demand distances are walked on a `TwoLevelList` instead (see
+/// `ListMove`), so applying a move costs O(√n) rather than O(n).
 pub struct SimulatedAnnealing {
     /// Initial temperature
     pub initial_temp: f64,
@@ -663,8 +1706,26 @@ pub struct SimulatedAnnealing {
     pub cooling_rate: f64,
     /// Iterations per temperature
     pub iterations_per_temp: usize,
+    /// Calibrate the temperatures on the instance instead of using the fixed ones
+    pub auto_temperature: bool,
+    /// Initial acceptance rate of uphill moves targeted by the calibration
+    pub target_acceptance: f64,
+    /// Acceptance rate of an average uphill move at the final temperature
+    pub final_acceptance: f64,
+    /// Number of random neighbors sampled by the calibration
+    pub calibration_samples: usize,
+    /// Stop once passed (checked after each temperature step)
+    pub deadline: Deadline,
+    /// Record the best tour at each improvement in `Solution::snapshots`
+    pub record_snapshots: bool,
     /// Random seed
     pub seed: u64,
+    /// Allow capacity violations during the search (`None` = feasible moves only)
+    pub penalty: Option<CapacityPenalty>,
+    /// Minimum tour length for the two-level list walk (0 = never)
+    pub two_level_threshold: usize,
+    /// Progress callback, invoked after each temperature step
+    pub on_progress: Option<ProgressHandler>,
 }
 
 impl SimulatedAnnealing {
@@ -674,7 +1735,16 @@ impl SimulatedAnnealing {
             final_temp: 0.1,
             cooling_rate: 0.995,
             iterations_per_temp: 100,
+            auto_temperature: false,
+            target_acceptance: 0.5,
+            final_acceptance: 1e-9,
+            calibration_samples: 200,
+            deadline: Deadline::none(),
+            record_snapshots: false,
             seed: 42,
+            penalty: None,
+            two_level_threshold: 1000,
+            on_progress: None,
         }
     }
     
@@ -684,117 +1754,168 @@ impl SimulatedAnnealing {
             final_temp,
             cooling_rate,
             iterations_per_temp,
-            seed: 42,
+            ..Self::new()
         }
     }
     
-    /// Generate a random neighbor solution
-    fn generate_neighbor(&self, instance: &PDTSPInstance, solution: &Solution, rng: &mut ChaCha8Rng) -> Option<(Vec<usize>, f64)> {
-        let n = solution.tour.len();
-        
-        
-        let move_type = rng.gen_range(0..4);
+    /// Calibrate the temperatures on the instance so that uphill moves are
+    /// initially accepted with probability `target_acceptance`
+    pub fn with_auto_temperature(mut self, target_acceptance: f64) -> Self {
+        self.auto_temperature = true;
+        self.target_acceptance = target_acceptance;
+        self
+    }
+    
+    /// Let the search cross infeasible tours under a dynamic penalty
+    pub fn with_penalty(mut self, penalty: CapacityPenalty) -> Self {
+        self.penalty = Some(penalty);
+        self
+    }
+    
+    /// Walk on a two-level list from `threshold` nodes on (0 = never)
+    pub fn with_two_level_threshold(mut self, threshold: usize) -> Self {
+        self.two_level_threshold = threshold;
+        self
+    }
+    
+    /// Whether the walk runs on a `TwoLevelList` (feasible moves only, see
+    /// `two_level_applicable`)
+    fn uses_two_level(&self, instance: &PDTSPInstance, solution: &Solution) -> bool {
+        self.penalty.is_none() && two_level_applicable(instance, solution, self.two_level_threshold)
+    }
+    
+    /// The annealing walk on a `TwoLevelList`: moves are priced in O(1) from
+    /// the successors of their nodes, and an accepted one is applied and
+    /// undone if the O(√n) load check fails. The best tour is only copied
+    /// out when the walk leaves it.
+    fn improve_two_level(&self, instance: &PDTSPInstance, solution: &mut Solution, rng: &mut ChaCha8Rng, initial_temp: f64, final_temp: f64, deadline: Deadline) -> bool {
+        let mut list = TwoLevelList::from_instance(instance, &solution.tour);
+        let mut current_cost = solution.cost;
+        let (mut best_tour, mut best_cost) = (solution.tour.clone(), solution.cost);
+        // Whether the current tour is the best one, and `best_tour` holds it
+        let (mut at_best, mut best_saved) = (true, true);
+        
+        let start = web_time::Instant::now();
+        let mut convergence = vec![(0.0, best_cost)];
+        let mut snapshots = Vec::new();
+        if self.record_snapshots {
+            snapshots.push((0.0, best_tour.clone()));
+        }
+        let mut temp = initial_temp;
+        let mut iterations = 0;
+        let mut move_stats = [(0u64, 0u64, 0.0f64, 0.0f64); NEIGHBOR_MOVES.len()];
         
-        match move_type {
-            0 => {
-                
-                let i = rng.gen_range(0..n - 2);
-                let j = rng.gen_range(i + 2..n);
-                
-                let mut new_tour = solution.tour.clone();
-                new_tour[i + 1..=j].reverse();
-                
-                if instance.is_feasible(&new_tour) {
-                    let delta = solution.two_opt_delta(instance, i, j);
-                    Some((new_tour, delta))
-                } else {
-                    None
-                }
-            }
-            1 => {
-                // Swap
-                let i = rng.gen_range(1..n);
-                let j = rng.gen_range(1..n);
-                if i == j || solution.tour[i] == 0 || solution.tour[j] == 0 {
-                    return None;
-                }
-                
-                let mut new_tour = solution.tour.clone();
-                new_tour.swap(i, j);
-                
-                if instance.is_feasible(&new_tour) {
-                    let delta = solution.swap_delta(instance, i, j);
-                    Some((new_tour, delta))
-                } else {
-                    None
-                }
-            }
-            2 => {
-                // Relocation
-                let from = rng.gen_range(1..n);
-                if solution.tour[from] == 0 {
-                    return None;
-                }
-                let to = rng.gen_range(0..n);
-                if to == from || to == from + 1 {
-                    return None;
-                }
-                
-                let mut new_tour = solution.tour.clone();
-                let node = new_tour.remove(from);
-                let insert_pos = if to > from { to - 1 } else { to };
-                new_tour.insert(insert_pos, node);
-                
-                if instance.is_feasible(&new_tour) {
-                    let new_cost = instance.tour_length(&new_tour);
-                    let delta = new_cost - solution.cost;
-                    Some((new_tour, delta))
-                } else {
-                    None
-                }
-            }
-            _ => {
-                // Or-opt (segment of length 2)
-                if n < 4 {
-                    return None;
-                }
-                let seg_start = rng.gen_range(1..n - 1);
-                if solution.tour[seg_start] == 0 {
-                    return None;
-                }
-                let insert_pos = rng.gen_range(0..n - 1);
-                if insert_pos >= seg_start && insert_pos <= seg_start + 2 {
-                    return None;
-                }
-                
-                let mut new_tour = Vec::new();
-                let segment: Vec<usize> = solution.tour[seg_start..seg_start + 2.min(n - seg_start)].to_vec();
+        while temp > final_temp && !deadline.expired() {
+            for _ in 0..self.iterations_per_temp {
+                let move_start = web_time::Instant::now();
+                let move_type = rng.gen_range(0..NEIGHBOR_MOVES.len());
+                let stats = &mut move_stats[move_type];
+                stats.0 += 1;
                 
-                for (i, &node) in solution.tour.iter().enumerate() {
-                    if i == insert_pos && insert_pos < seg_start {
-                        new_tour.extend(&segment);
-                    }
-                    if i < seg_start || i >= seg_start + segment.len() {
-                        new_tour.push(node);
-                    }
-                    if i == insert_pos && insert_pos > seg_start + segment.len() {
-                        new_tour.extend(&segment);
+                if let Some((mv, delta)) = ListMove::random(&list, instance, rng, move_type) {
+                    let accept = delta < 0.0 || rng.gen::<f64>() < (-delta / temp).exp();
+                    if accept {
+                        if at_best && !best_saved && delta >= 0.0 {
+                            best_tour = list.to_vec();
+                            best_saved = true;
+                        }
+                        mv.apply(&mut list);
+                        if list.load_feasible(instance) {
+                            stats.1 += 1;
+                            stats.2 -= delta;
+                            current_cost += delta;
+                            at_best = current_cost < best_cost;
+                            if at_best {
+                                best_cost = current_cost;
+                                best_saved = false;
+                                let elapsed = start.elapsed().as_secs_f64();
+                                convergence.push((elapsed, best_cost));
+                                if self.record_snapshots {
+                                    snapshots.push((elapsed, list.to_vec()));
+                                }
+                            }
+                        } else {
+                            mv.undo(&mut list);
+                        }
                     }
                 }
-                
-                if insert_pos >= solution.tour.len() - segment.len() {
-                    new_tour.extend(&segment);
-                }
-                
-                if new_tour.len() == solution.tour.len() && instance.is_feasible(&new_tour) {
-                    let new_cost = instance.tour_length(&new_tour);
-                    let delta = new_cost - solution.cost;
-                    Some((new_tour, delta))
-                } else {
-                    None
-                }
+                stats.3 += move_start.elapsed().as_secs_f64();
+                iterations += 1;
+            }
+            
+            notify(&self.on_progress, || {
+                ProgressEvent::new(self.name(), iterations, best_cost, start.elapsed().as_secs_f64())
+                    .with_temperature(temp)
+            });
+            temp *= self.cooling_rate;
+        }
+        
+        if !best_saved {
+            best_tour = list.to_vec();
+        }
+        let improved = best_cost < solution.cost - 1e-9;
+        solution.tour = best_tour;
+        solution.cost = instance.tour_cost(&solution.tour);
+        solution.iterations = Some(iterations);
+        solution.convergence = convergence;
+        solution.snapshots = snapshots;
+        for (name, (evaluated, applied, gain, time)) in NEIGHBOR_MOVES.iter().zip(move_stats) {
+            solution.statistics.record(&format!("SA-{}", name), evaluated, applied, gain, time);
+        }
+        solution.validate(instance);
+        improved
+    }
+    
+    /// Sample random neighbors of the starting solution and derive
+    /// (initial, final) temperatures from their uphill deltas: the initial
+    /// temperature is found by bisection so that the mean acceptance
+    /// probability exp(-delta / T) equals `target_acceptance`, and the final
+    /// one accepts an average uphill move with probability `final_acceptance`.
+    /// Returns `None` if no uphill move was sampled.
+    pub fn calibrate_temperatures(&self, instance: &PDTSPInstance, solution: &Solution, rng: &mut ChaCha8Rng) -> Option<(f64, f64)> {
+        if solution.tour.len() < 3 {
+            return None;
+        }
+        let evaluator = DeltaEvaluator::new(instance, &solution.tour);
+        let deltas: Vec<f64> = (0..self.calibration_samples * 5)
+            .filter_map(|_| {
+                let move_type = rng.gen_range(0..NEIGHBOR_MOVES.len());
+                random_move(&evaluator, rng, move_type)
+            })
+            .filter(|mv| self.penalty.is_some() || mv.is_feasible(&evaluator))
+            .map(|mv| mv.delta(&evaluator))
+            .filter(|&delta| delta > 1e-9 && delta.is_finite())
+            .take(self.calibration_samples)
+            .collect();
+        if deltas.is_empty() {
+            return None;
+        }
+        
+        let acceptance = |temp: f64| deltas.iter().map(|d| (-d / temp).exp()).sum::<f64>() / deltas.len() as f64;
+        let target = self.target_acceptance.clamp(1e-6, 1.0 - 1e-6);
+        let avg_delta = deltas.iter().sum::<f64>() / deltas.len() as f64;
+        
+        // Acceptance grows with the temperature: bracket the target, then bisect
+        let (mut low, mut high) = (avg_delta * 1e-6, avg_delta);
+        while acceptance(high) < target {
+            high *= 2.0;
+        }
+        for _ in 0..60 {
+            let mid = 0.5 * (low + high);
+            if acceptance(mid) < target {
+                low = mid;
+            } else {
+                high = mid;
             }
         }
+        
+        let final_temp = -avg_delta / self.final_acceptance.clamp(1e-12, 1.0 - 1e-6).ln();
+        Some((high, final_temp.min(high)))
+    }
+    
+    /// Receive a progress event after each temperature step
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+        self.on_progress = Some(ProgressHandler::new(callback));
     }
 }
 
@@ -806,6 +1927,11 @@ impl Default for SimulatedAnnealing {
 
 impl LocalSearch for SimulatedAnnealing {
     fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        self.improve_until(instance, solution, Deadline::none())
+    }
+    
+    fn improve_until(&self, instance: &PDTSPInstance, solution: &mut Solution, deadline: Deadline) -> bool {
+        let deadline = self.deadline.earliest(deadline);
         let n = solution.tour.len();
         if n < 3 { return false; }
         let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
@@ -815,26 +1941,67 @@ impl LocalSearch for SimulatedAnnealing {
         let mut best_tour = current_tour.clone();
         let mut best_cost = current_cost;
         
-        let mut temp = self.initial_temp;
+        // Penalty mode: the current tour may be infeasible, the best one may not
+        let mut weight = self.penalty.map_or(0.0, |penalty| penalty.starting_weight(instance, &current_tour));
+        let mut current_violation = 0;
+        let mut least_violated = None;
+        let (mut window, mut window_feasible) = (0, 0);
+        if self.penalty.is_some() {
+            current_violation = instance.load_violation(current_tour.iter().copied());
+            if !solution.feasible {
+                best_cost = f64::INFINITY;
+                least_violated = Some((current_violation, current_cost, current_tour.clone()));
+            }
+        }
+        
+        let (initial_temp, final_temp) = if self.auto_temperature {
+            self.calibrate_temperatures(instance, solution, &mut rng)
+                .unwrap_or((self.initial_temp, self.final_temp))
+        } else {
+            (self.initial_temp, self.final_temp)
+        };
+        if self.uses_two_level(instance, solution) {
+            return self.improve_two_level(instance, solution, &mut rng, initial_temp, final_temp, deadline);
+        }
+        
+        let start = web_time::Instant::now();
+        let mut convergence = Vec::new();
+        let mut snapshots = Vec::new();
+        if best_cost.is_finite() {
+            convergence.push((0.0, best_cost));
+            if self.record_snapshots {
+                snapshots.push((0.0, best_tour.clone()));
+            }
+        }
+        let mut temp = initial_temp;
         let mut iterations = 0;
+        // Per move type: (evaluated, applied, gain, time)
+        let mut move_stats = [(0u64, 0u64, 0.0f64, 0.0f64); NEIGHBOR_MOVES.len()];
         
-        while temp > self.final_temp {
+        // Moves are priced in O(1) on the current tour, with the change of
+        // load violation under a penalty (without one only feasible tours
+        // are visited). The evaluator is refreshed from the first changed
+        // position when a move is applied.
+        let mut evaluator = DeltaEvaluator::new(instance, &current_tour);
+        
+        while temp > final_temp && !deadline.expired() {
             for _ in 0..self.iterations_per_temp {
-                let total_profit = instance.tour_profit(&current_tour);
-                let temp_solution = Solution {
-                    tour: current_tour.clone(),
-                    cost: current_cost,
-                    feasible: true,
-                    algorithm: String::new(),
-                    computation_time: 0.0,
-                    iterations: None,
-                    total_profit,
-                    objective: total_profit as f64 - current_cost,
-                };
+                let move_start = web_time::Instant::now();
+                let move_type = rng.gen_range(0..NEIGHBOR_MOVES.len());
+                let stats = &mut move_stats[move_type];
+                stats.0 += 1;
                 
-                if let Some((new_tour, delta)) = self.generate_neighbor(instance, &temp_solution, &mut rng) {
-                    let new_cost = current_cost + delta;
-                    
+                let neighbor = random_move(&evaluator, &mut rng, move_type).and_then(|mv| {
+                    let delta = mv.delta(&evaluator);
+                    if self.penalty.is_none() {
+                        mv.is_feasible(&evaluator).then_some((mv, current_cost + delta, 0, delta))
+                    } else {
+                        let violation_delta = mv.violation_delta(&evaluator);
+                        Some((mv, current_cost + delta, current_violation + violation_delta, delta + weight * violation_delta as f64))
+                    }
+                });
+                
+                if let Some((mv, new_cost, new_violation, delta)) = neighbor {
                     // Accept if better or with probability
                     let accept = if delta < 0.0 {
                         true
@@ -844,42 +2011,306 @@ impl LocalSearch for SimulatedAnnealing {
                     };
                     
                     if accept {
-                        current_tour = new_tour;
+                        stats.1 += 1;
+                        stats.2 += current_cost - new_cost;
+                        let tables = evaluator.into_tables();
+                        mv.apply(&mut current_tour);
+                        evaluator = DeltaEvaluator::refresh(instance, &current_tour, tables, mv.first_changed());
                         current_cost = new_cost;
+                        current_violation = new_violation;
                         
-                        if current_cost < best_cost {
-                            best_tour = current_tour.clone();
+                        let feasible = self.penalty.is_none() || (current_violation == 0 && evaluator.tour_feasible());
+                        if !feasible {
+                            if let Some((violation, cost, tour)) = &mut least_violated {
+                                if (current_violation, current_cost) < (*violation, *cost) {
+                                    (*violation, *cost) = (current_violation, current_cost);
+                                    tour.clone_from(&current_tour);
+                                }
+                            }
+                        } else if current_cost < best_cost {
+                            best_tour.clone_from(&current_tour);
                             best_cost = current_cost;
+                            let elapsed = start.elapsed().as_secs_f64();
+                            convergence.push((elapsed, best_cost));
+                            if self.record_snapshots {
+                                snapshots.push((elapsed, best_tour.clone()));
+                            }
                         }
                     }
                 }
+                stats.3 += move_start.elapsed().as_secs_f64();
                 
                 iterations += 1;
+                
+                if let Some(penalty) = &self.penalty {
+                    window += 1;
+                    window_feasible += (current_violation == 0) as usize;
+                    if window >= penalty.update_interval.max(1) {
+                        weight = penalty.adjust(weight, window_feasible as f64 / window as f64);
+                        (window, window_feasible) = (0, 0);
+                    }
+                }
             }
             
+            notify(&self.on_progress, || {
+                ProgressEvent::new(self.name(), iterations, best_cost, start.elapsed().as_secs_f64())
+                    .with_temperature(temp)
+            });
             temp *= self.cooling_rate;
         }
-        
-        let improved = best_cost < solution.cost - 1e-9;
-        
-        solution.tour = best_tour;
-        solution.cost = best_cost;
-        solution.iterations = Some(iterations);
-        solution.validate(instance);
-        
-        improved
+        
+        if !best_cost.is_finite() {
+            // No feasible tour was reached: keep the least violated one
+            if let Some((violation, cost, tour)) = least_violated {
+                let initial_violation = instance.load_violation(solution.tour.iter().copied());
+                if violation < initial_violation {
+                    (best_tour, best_cost) = (tour, cost);
+                } else {
+                    best_cost = solution.cost;
+                }
+            }
+        }
+        let improved = best_cost < solution.cost - 1e-9 || (!solution.feasible && instance.is_feasible(&best_tour));
+        
+        solution.tour = best_tour;
+        solution.cost = best_cost;
+        solution.iterations = Some(iterations);
+        solution.convergence = convergence;
+        solution.snapshots = snapshots;
+        for (name, (evaluated, applied, gain, time)) in NEIGHBOR_MOVES.iter().zip(move_stats) {
+            solution.statistics.record(&format!("SA-{}", name), evaluated, applied, gain, time);
+        }
+        solution.validate(instance);
+        
+        improved
+    }
+    
+    fn name(&self) -> &str {
+        "SimulatedAnnealing"
+    }
+}
+
+// ==================== Threshold Accepting / Record-to-Record Travel ====================
+
+/// Walk over the random neighborhood of SA accepting, instead of by
+/// probability, every feasible neighbor whose cost is below
+/// `bound(iteration, current cost, best cost)`. Move statistics are recorded
+/// under `prefix`.
+fn threshold_walk(
+    instance: &PDTSPInstance,
+    solution: &mut Solution,
+    seed: u64,
+    max_iterations: usize,
+    deadline: Deadline,
+    prefix: &str,
+    bound: impl Fn(usize, f64, f64) -> f64,
+) -> bool {
+    if solution.tour.len() < 3 {
+        return false;
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut current_tour = solution.tour.clone();
+    let mut current_cost = instance.tour_cost(&current_tour);
+    let mut best_tour = current_tour.clone();
+    let mut best_cost = if solution.feasible { current_cost } else { f64::INFINITY };
+    // Refreshed when a move is applied, see `random_move`
+    let mut evaluator = DeltaEvaluator::new(instance, &current_tour);
+    
+    let start = web_time::Instant::now();
+    let mut convergence = Vec::new();
+    if best_cost.is_finite() {
+        convergence.push((0.0, best_cost));
+    }
+    let mut move_stats = [(0u64, 0u64, 0.0f64, 0.0f64); NEIGHBOR_MOVES.len()];
+    let mut iteration = 0;
+    
+    while iteration < max_iterations && !(iteration % 100 == 0 && deadline.expired()) {
+        let move_start = web_time::Instant::now();
+        let move_type = rng.gen_range(0..NEIGHBOR_MOVES.len());
+        let stats = &mut move_stats[move_type];
+        stats.0 += 1;
+        let neighbor = random_move(&evaluator, &mut rng, move_type)
+            .filter(|mv| mv.is_feasible(&evaluator))
+            .map(|mv| (mv, current_cost + mv.delta(&evaluator)));
+        if let Some((mv, new_cost)) = neighbor {
+            if new_cost < bound(iteration, current_cost, best_cost) {
+                stats.1 += 1;
+                stats.2 += current_cost - new_cost;
+                let tables = evaluator.into_tables();
+                mv.apply(&mut current_tour);
+                evaluator = DeltaEvaluator::refresh(instance, &current_tour, tables, mv.first_changed());
+                current_cost = new_cost;
+                if new_cost < best_cost - 1e-9 {
+                    best_tour.clone_from(&current_tour);
+                    best_cost = new_cost;
+                    convergence.push((start.elapsed().as_secs_f64(), best_cost));
+                }
+            }
+        }
+        stats.3 += move_start.elapsed().as_secs_f64();
+        iteration += 1;
+    }
+    
+    let improved = best_cost < solution.cost - 1e-9 || (!solution.feasible && best_cost.is_finite());
+    if best_cost.is_finite() {
+        solution.tour = best_tour;
+        solution.cost = best_cost;
+    }
+    solution.iterations = Some(iteration);
+    solution.convergence = convergence;
+    for (name, (evaluated, applied, gain, time)) in NEIGHBOR_MOVES.iter().zip(move_stats) {
+        solution.statistics.record(&format!("{}-{}", prefix, name), evaluated, applied, gain, time);
+    }
+    solution.validate(instance);
+    improved
+}
+
+/// Threshold Accepting
+/// 
+/// Deterministic variant of simulated annealing (Dueck & Scheuer): a
+/// neighbor is accepted when it is worse than the current tour by less than
+/// a threshold, which decreases linearly from `initial_threshold` to zero
+/// over the iterations.
+pub struct ThresholdAccepting {
+    /// Initial threshold (0 = the mean arc cost of the starting tour)
+    pub initial_threshold: f64,
+    /// Number of neighbors generated
+    pub max_iterations: usize,
+    /// Stop once passed
+    pub deadline: Deadline,
+    /// Random seed
+    pub seed: u64,
+}
+
+impl ThresholdAccepting {
+    pub fn new() -> Self {
+        ThresholdAccepting {
+            initial_threshold: 0.0,
+            max_iterations: 50_000,
+            deadline: Deadline::none(),
+            seed: 42,
+        }
+    }
+    
+    pub fn with_params(initial_threshold: f64, max_iterations: usize) -> Self {
+        ThresholdAccepting {
+            initial_threshold,
+            max_iterations,
+            ..Self::new()
+        }
+    }
+}
+
+impl Default for ThresholdAccepting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSearch for ThresholdAccepting {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        self.improve_until(instance, solution, Deadline::none())
+    }
+    
+    fn improve_until(&self, instance: &PDTSPInstance, solution: &mut Solution, deadline: Deadline) -> bool {
+        let deadline = self.deadline.earliest(deadline);
+        let initial = if self.initial_threshold > 0.0 {
+            self.initial_threshold
+        } else {
+            instance.tour_cost(&solution.tour) / solution.tour.len().max(1) as f64
+        };
+        let steps = self.max_iterations.max(1) as f64;
+        threshold_walk(instance, solution, self.seed, self.max_iterations, deadline, "TA", |iteration, current, _| {
+            current + initial * (1.0 - iteration as f64 / steps)
+        })
+    }
+    
+    fn name(&self) -> &str {
+        "ThresholdAccepting"
+    }
+}
+
+/// Record-to-Record Travel
+/// 
+/// Accepts any neighbor whose cost is within `deviation` (a fraction) of the
+/// best cost found so far, the record (Dueck).
+pub struct RecordToRecord {
+    /// Allowed deviation from the record, as a fraction of it
+    pub deviation: f64,
+    /// Number of neighbors generated
+    pub max_iterations: usize,
+    /// Stop once passed
+    pub deadline: Deadline,
+    /// Random seed
+    pub seed: u64,
+}
+
+impl RecordToRecord {
+    pub fn new() -> Self {
+        RecordToRecord {
+            deviation: 0.01,
+            max_iterations: 50_000,
+            deadline: Deadline::none(),
+            seed: 42,
+        }
+    }
+    
+    pub fn with_params(deviation: f64, max_iterations: usize) -> Self {
+        RecordToRecord {
+            deviation,
+            max_iterations,
+            ..Self::new()
+        }
+    }
+}
+
+impl Default for RecordToRecord {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSearch for RecordToRecord {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        self.improve_until(instance, solution, Deadline::none())
+    }
+    
+    fn improve_until(&self, instance: &PDTSPInstance, solution: &mut Solution, deadline: Deadline) -> bool {
+        let deadline = self.deadline.earliest(deadline);
+        let deviation = self.deviation;
+        threshold_walk(instance, solution, self.seed, self.max_iterations, deadline, "RRT", |_, current, best| {
+            // Without a feasible record yet, only improvements are accepted
+            if best.is_finite() { best * (1.0 + deviation) } else { current }
+        })
     }
     
     fn name(&self) -> &str {
-        "SimulatedAnnealing"
+        "RecordToRecord"
     }
 }
 
 // ==================== Tabu Search ====================
 
+/// Long-term memory strategy of `TabuSearch`, applied after
+/// `max_no_improve / 2` iterations without improvement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Diversification {
+    /// Short-term recency memory only
+    None,
+    /// For `tenure` iterations, penalize moves by how often they were applied
+    FrequencyPenalty,
+    /// Restart from a perturbed copy of the best solution
+    EliteRestart,
+}
+
 /// Tabu Search
 /// 
-/// Local search with memory to avoid cycling.
+/// Local search with memory to avoid cycling. Swap and 2-opt moves are scored
+/// in constant time by a `DeltaEvaluator`; the neighborhood is restricted to
+/// moves that bring a node next to one of its nearest neighbors, and can be
+/// sampled further, so that an iteration is near-linear in the tour length.
+/// A frequency memory of the applied moves drives the diversification.
 pub struct TabuSearch {
     /// Tabu tenure (how long a move stays tabu)
     pub tenure: usize,
@@ -887,6 +2318,23 @@ pub struct TabuSearch {
     pub max_iterations: usize,
     /// Maximum iterations without improvement
     pub max_no_improve: usize,
+    /// Size of the nearest-neighbor candidate lists (0 = all node pairs)
+    pub candidate_neighbors: usize,
+    /// Number of candidate moves sampled per iteration (0 = evaluate all)
+    pub sample_size: usize,
+    /// Random seed of the sampling and the restarts
+    pub seed: u64,
+    /// Long-term diversification strategy
+    pub diversification: Diversification,
+    /// Weight of the frequency penalty, in multiples of
+    /// `sqrt(n) * mean arc cost * move frequency`
+    pub frequency_weight: f64,
+    /// Number of double-bridge moves of an elite restart
+    pub restart_strength: usize,
+    /// Stop once passed
+    pub deadline: Deadline,
+    /// Progress callback, invoked after each iteration
+    pub on_progress: Option<ProgressHandler>,
 }
 
 impl TabuSearch {
@@ -895,6 +2343,14 @@ impl TabuSearch {
             tenure: 10,
             max_iterations: 1000,
             max_no_improve: 100,
+            candidate_neighbors: 10,
+            sample_size: 0,
+            seed: 42,
+            diversification: Diversification::FrequencyPenalty,
+            frequency_weight: 1.0,
+            restart_strength: 3,
+            deadline: Deadline::none(),
+            on_progress: None,
         }
     }
     
@@ -903,8 +2359,78 @@ impl TabuSearch {
             tenure,
             max_iterations,
             max_no_improve,
+            ..Self::new()
         }
     }
+    
+    /// Set the candidate list size (0 = all node pairs)
+    pub fn with_candidates(mut self, candidate_neighbors: usize) -> Self {
+        self.candidate_neighbors = candidate_neighbors;
+        self
+    }
+    
+    /// Evaluate only `sample_size` random candidate moves per iteration
+    pub fn with_sampling(mut self, sample_size: usize, seed: u64) -> Self {
+        self.sample_size = sample_size;
+        self.seed = seed;
+        self
+    }
+    
+    /// Set the diversification strategy
+    pub fn with_diversification(mut self, diversification: Diversification) -> Self {
+        self.diversification = diversification;
+        self
+    }
+    
+    /// Receive a progress event after each iteration
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+        self.on_progress = Some(ProgressHandler::new(callback));
+    }
+    
+    /// Swap and 2-opt moves of the neighborhood. With candidate lists, each
+    /// node `a` at position `i` is paired with its neighbors `b`: the 2-opt
+    /// move creating the arc between them, and the swaps placing `b` right
+    /// before or after `a`.
+    fn candidate_moves(&self, tour: &[usize], position: &[usize], neighbors: &[Vec<usize>]) -> Vec<StandardMove> {
+        let n = tour.len();
+        let mut moves = Vec::new();
+        
+        if self.candidate_neighbors == 0 {
+            for i in 1..n - 1 {
+                for j in i + 1..n {
+                    moves.push(StandardMove::Swap(i, j));
+                    if j > i + 1 {
+                        moves.push(StandardMove::TwoOpt(i, j));
+                    }
+                }
+            }
+            return moves;
+        }
+        
+        for (i, &a) in tour.iter().enumerate() {
+            for &b in &neighbors[a] {
+                let p = position[b];
+                if b == 0 || p == usize::MAX {
+                    continue;
+                }
+                if p > i + 1 {
+                    moves.push(StandardMove::TwoOpt(i, p));
+                } else if i > p + 1 {
+                    moves.push(StandardMove::TwoOpt(p, i));
+                }
+                if i == 0 {
+                    continue;
+                }
+                if i + 1 < n && p != i + 1 {
+                    moves.push(StandardMove::Swap(i + 1, p));
+                }
+                if i > 1 && p != i - 1 {
+                    moves.push(StandardMove::Swap(i - 1, p));
+                }
+            }
+        }
+        moves
+    }
 }
 
 impl Default for TabuSearch {
@@ -915,106 +2441,134 @@ impl Default for TabuSearch {
 
 impl LocalSearch for TabuSearch {
     fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        self.improve_until(instance, solution, Deadline::none())
+    }
+    
+    fn improve_until(&self, instance: &PDTSPInstance, solution: &mut Solution, deadline: Deadline) -> bool {
+        let deadline = self.deadline.earliest(deadline);
         let n = solution.tour.len();
         if n < 3 { return false; }
-        let n = solution.tour.len();
         
         // Tabu list: (node1, node2) -> expiry iteration
         let mut tabu_list: std::collections::HashMap<(usize, usize), usize> = std::collections::HashMap::new();
+        // Frequency memory: (node1, node2) -> number of times the move was applied
+        let mut frequency: std::collections::HashMap<(usize, usize), usize> = std::collections::HashMap::new();
+        let mut applied = 0;
+        let mut diversify_until = 0;
+        let neighbors = if self.candidate_neighbors > 0 {
+            instance.nearest_neighbors(self.candidate_neighbors)
+        } else {
+            Vec::new()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
         
-        let mut current_tour = solution.tour.clone();
-        let mut current_cost = solution.cost;
-        let mut best_tour = current_tour.clone();
+        let mut current = solution.clone();
+        let mut current_cost = instance.tour_cost(&current.tour);
+        let mut best_tour = current.tour.clone();
         let mut best_cost = current_cost;
+        let mut position = vec![usize::MAX; instance.dimension];
         
+        let start = web_time::Instant::now();
+        let mut convergence = vec![(0.0, best_cost)];
         let mut iteration = 0;
         let mut no_improve = 0;
-        
-        while iteration < self.max_iterations && no_improve < self.max_no_improve {
-            let mut best_move_delta = f64::INFINITY;
-            let mut best_move_i = 0;
-            let mut best_move_j = 0;
-            let mut best_move_type = 0; // 0 = swap, 1 = 2-opt
-            
-            // Evaluate all possible moves
-            for i in 1..n - 1 {
-                for j in i + 1..n {
-                    if current_tour[i] == 0 || current_tour[j] == 0 {
-                        continue;
-                    }
-                    
-                    // Check swap
-                    let mut test_tour = current_tour.clone();
-                    test_tour.swap(i, j);
-                    
-                    if instance.is_feasible(&test_tour) {
-                        let new_cost = instance.tour_length(&test_tour);
-                        let delta = new_cost - current_cost;
-                        
-                        let tabu_key = (current_tour[i].min(current_tour[j]), 
-                                       current_tour[i].max(current_tour[j]));
-                        let is_tabu = tabu_list.get(&tabu_key)
-                            .map(|&exp| exp > iteration)
-                            .unwrap_or(false);
-                        
-                        // Aspiration: accept if better than best known
-                        let accept = !is_tabu || new_cost < best_cost;
-                        
-                        if accept && delta < best_move_delta {
-                            best_move_delta = delta;
-                            best_move_i = i;
-                            best_move_j = j;
-                            best_move_type = 0;
-                        }
-                    }
-                    
-                    // Check 2-opt
-                    if j > i + 1 {
-                        let mut test_tour = current_tour.clone();
-                        test_tour[i + 1..=j].reverse();
-                        
-                        if instance.is_feasible(&test_tour) {
-                            let new_cost = instance.tour_length(&test_tour);
-                            let delta = new_cost - current_cost;
-                            
-                            let tabu_key = (current_tour[i].min(current_tour[j]), 
-                                           current_tour[i].max(current_tour[j]));
-                            let is_tabu = tabu_list.get(&tabu_key)
-                                .map(|&exp| exp > iteration)
-                                .unwrap_or(false);
-                            
-                            let accept = !is_tabu || new_cost < best_cost;
-                            
-                            if accept && delta < best_move_delta {
-                                best_move_delta = delta;
-                                best_move_i = i;
-                                best_move_j = j;
-                                best_move_type = 1;
-                            }
-                        }
+        // Per move type (swap, 2-opt): (evaluated, applied, gain, time)
+        let mut move_stats = [(0u64, 0u64, 0.0f64, 0.0f64); 2];
+        let kind = |mv: &StandardMove| matches!(mv, StandardMove::TwoOpt(..)) as usize;
+        
+        while iteration < self.max_iterations && no_improve < self.max_no_improve && !deadline.expired() {
+            let iteration_start = web_time::Instant::now();
+            let mut evaluated = [0u64; 2];
+            if no_improve > 0 && no_improve == self.max_no_improve / 2 {
+                match self.diversification {
+                    Diversification::None => {}
+                    Diversification::FrequencyPenalty => diversify_until = iteration + self.tenure,
+                    Diversification::EliteRestart => {
+                        current.tour = best_tour.clone();
+                        DoubleBridge.perturb(instance, &mut current.tour, self.restart_strength, &mut rng);
+                        current_cost = instance.tour_cost(&current.tour);
+                        tabu_list.clear();
                     }
                 }
             }
+            // Penalty per unit of relative frequency
+            let penalty_scale = if iteration < diversify_until && applied > 0 {
+                self.frequency_weight * (n as f64).sqrt() * current_cost / n as f64 / applied as f64
+            } else {
+                0.0
+            };
+            
+            for (pos, &node) in current.tour.iter().enumerate() {
+                position[node] = pos;
+            }
+            let mut moves = self.candidate_moves(&current.tour, &position, &neighbors);
+            if self.sample_size > 0 && moves.len() > self.sample_size {
+                moves.partial_shuffle(&mut rng, self.sample_size);
+                moves.truncate(self.sample_size);
+            }
             
-            // Apply best move
-            if best_move_delta < f64::INFINITY {
-                if best_move_type == 0 {
-                    let tabu_key = (current_tour[best_move_i].min(current_tour[best_move_j]),
-                                   current_tour[best_move_i].max(current_tour[best_move_j]));
-                    current_tour.swap(best_move_i, best_move_j);
-                    tabu_list.insert(tabu_key, iteration + self.tenure);
+            let tour = &current.tour;
+            let evaluator = DeltaEvaluator::new(instance, tour);
+            let tabu_key = |i: usize, j: usize| (tour[i].min(tour[j]), tour[i].max(tour[j]));
+            let mut best_move: Option<(f64, StandardMove)> = None;
+            let mut best_score = f64::INFINITY;
+            
+            for mv in moves {
+                let (i, j) = match mv {
+                    StandardMove::Swap(i, j) | StandardMove::TwoOpt(i, j) => (i, j),
+                    _ => continue,
+                };
+                // Depot visits stay in place; a 2-opt move may start at the first one
+                if tour[j] == 0 || (tour[i] == 0 && (i > 0 || matches!(mv, StandardMove::Swap(..)))) {
+                    continue;
+                }
+                
+                let delta = mv.delta(&evaluator);
+                evaluated[kind(&mv)] += 1;
+                let key = tabu_key(i, j);
+                let aspiration = current_cost + delta < best_cost - 1e-9;
+                let score = if aspiration || penalty_scale == 0.0 {
+                    delta
                 } else {
-                    let tabu_key = (current_tour[best_move_i].min(current_tour[best_move_j]),
-                                   current_tour[best_move_i].max(current_tour[best_move_j]));
-                    current_tour[best_move_i + 1..=best_move_j].reverse();
-                    tabu_list.insert(tabu_key, iteration + self.tenure);
+                    delta + penalty_scale * frequency.get(&key).copied().unwrap_or(0) as f64
+                };
+                if score >= best_score {
+                    continue;
+                }
+                
+                let is_tabu = tabu_list.get(&key)
+                    .map(|&exp| exp > iteration)
+                    .unwrap_or(false);
+                // Aspiration: accept if better than best known
+                if is_tabu && !aspiration {
+                    continue;
                 }
                 
-                current_cost += best_move_delta;
+                if mv.is_feasible(&evaluator) {
+                    best_move = Some((delta, mv));
+                    best_score = score;
+                }
+            }
+            
+            // Apply best move
+            if let Some((_, mv)) = best_move {
+                let key = match mv {
+                    StandardMove::Swap(i, j) | StandardMove::TwoOpt(i, j) => tabu_key(i, j),
+                    _ => unreachable!(),
+                };
+                current.apply_move(&mv);
+                tabu_list.insert(key, iteration + self.tenure);
+                *frequency.entry(key).or_insert(0) += 1;
+                applied += 1;
+                let previous_cost = current_cost;
+                current_cost = instance.tour_cost(&current.tour);
+                move_stats[kind(&mv)].1 += 1;
+                move_stats[kind(&mv)].2 += previous_cost - current_cost;
                 
                 if current_cost < best_cost - 1e-9 {
-                    best_tour = current_tour.clone();
+                    best_tour = current.tour.clone();
                     best_cost = current_cost;
+                    convergence.push((start.elapsed().as_secs_f64(), best_cost));
                     no_improve = 0;
                 } else {
                     no_improve += 1;
@@ -1023,7 +2577,18 @@ impl LocalSearch for TabuSearch {
                 no_improve += 1;
             }
             
+            // The iteration time is shared between the move types by their number of evaluations
+            let elapsed = iteration_start.elapsed().as_secs_f64();
+            let total = (evaluated[0] + evaluated[1]).max(1) as f64;
+            for (stats, count) in move_stats.iter_mut().zip(evaluated) {
+                stats.0 += count;
+                stats.3 += elapsed * count as f64 / total;
+            }
+            
             iteration += 1;
+            notify(&self.on_progress, || {
+                ProgressEvent::new(self.name(), iteration, best_cost, start.elapsed().as_secs_f64())
+            });
         }
         
         let improved = best_cost < solution.cost - 1e-9;
@@ -1031,6 +2596,10 @@ impl LocalSearch for TabuSearch {
         solution.tour = best_tour;
         solution.cost = best_cost;
         solution.iterations = Some(iteration);
+        solution.convergence = convergence;
+        for (name, (evaluated, applied, gain, time)) in ["Tabu-Swap", "Tabu-2-Opt"].iter().zip(move_stats) {
+            solution.statistics.record(name, evaluated, applied, gain, time);
+        }
         solution.validate(instance);
         
         improved
@@ -1046,68 +2615,104 @@ impl LocalSearch for TabuSearch {
 /// Iterated Local Search
 /// 
 /// Applies local search, then perturbation, then local search again.
+/// The perturbation operator is pluggable (double bridge by default) and its
+/// strength escalates while no improvement is found. The new local optimum
+/// replaces the current one according to the `acceptance` criterion.
 pub struct IteratedLocalSearch {
     /// Number of perturbation moves
     pub perturbation_strength: usize,
+    /// Upper bound of the adaptive perturbation strength
+    pub max_perturbation_strength: usize,
+    /// Non-improving iterations after which the strength is increased
+    pub escalate_after: usize,
+    /// Perturbation operator
+    pub perturbation: Box<dyn Perturbation + Send + Sync>,
+    /// Acceptance criterion of the new local optima
+    pub acceptance: Acceptance,
+    /// Annealing acceptance: initial temperature, as a fraction of the starting cost
+    pub acceptance_temperature: f64,
+    /// Late acceptance: length of the cost history
+    pub history_length: usize,
     /// Maximum iterations
     pub max_iterations: usize,
     /// Maximum iterations without improvement
     pub max_no_improve: usize,
+    /// Non-improving iterations after which the current tour is replaced by
+    /// an edge-frequency construction over the best local optima found
+    /// (0 = never)
+    pub restart_after: usize,
+    /// Stop once passed
+    pub deadline: Deadline,
+    /// Settings of the descent after every perturbation
+    pub vnd: VNDParams,
+    /// Record the best tour at each improvement in `Solution::snapshots`
+    pub record_snapshots: bool,
     /// Random seed
     pub seed: u64,
+    /// Progress callback, invoked after each iteration
+    pub on_progress: Option<ProgressHandler>,
 }
 
 impl IteratedLocalSearch {
     pub fn new() -> Self {
         IteratedLocalSearch {
             perturbation_strength: 3,
+            max_perturbation_strength: 8,
+            escalate_after: 5,
+            perturbation: Box::new(DoubleBridge),
+            acceptance: Acceptance::Better,
+            acceptance_temperature: 0.005,
+            history_length: 10,
             max_iterations: 100,
             max_no_improve: 20,
+            restart_after: 0,
+            deadline: Deadline::none(),
+            vnd: VNDParams::default(),
+            record_snapshots: false,
             seed: 42,
+            on_progress: None,
         }
     }
     
     pub fn with_params(perturbation_strength: usize, max_iterations: usize, max_no_improve: usize) -> Self {
         IteratedLocalSearch {
             perturbation_strength,
+            max_perturbation_strength: perturbation_strength.max(8),
             max_iterations,
             max_no_improve,
-            seed: 42,
+            ..Self::new()
         }
     }
     
-    /// Perturb solution by applying random moves
-    fn perturb(&self, instance: &PDTSPInstance, tour: &mut Vec<usize>, rng: &mut ChaCha8Rng) {
-        let n = tour.len();
-        
-        for _ in 0..self.perturbation_strength {
-            // Try random 2-opt or swap
-            if rng.gen_bool(0.5) {
-                // Random 2-opt
-                let i = rng.gen_range(0..n - 2);
-                let j = rng.gen_range(i + 2..n);
-                
-                let mut new_tour = tour.clone();
-                new_tour[i + 1..=j].reverse();
-                
-                if instance.is_feasible(&new_tour) {
-                    *tour = new_tour;
-                }
-            } else {
-                // Random swap
-                let i = rng.gen_range(1..n);
-                let j = rng.gen_range(1..n);
-                
-                if i != j && tour[i] != 0 && tour[j] != 0 {
-                    let mut new_tour = tour.clone();
-                    new_tour.swap(i, j);
-                    
-                    if instance.is_feasible(&new_tour) {
-                        *tour = new_tour;
-                    }
-                }
-            }
-        }
+    /// Use another perturbation operator
+    pub fn with_perturbation(mut self, perturbation: impl Perturbation + Send + Sync + 'static) -> Self {
+        self.perturbation = Box::new(perturbation);
+        self
+    }
+    
+    /// Use another acceptance criterion
+    pub fn with_acceptance(mut self, acceptance: Acceptance) -> Self {
+        self.acceptance = acceptance;
+        self
+    }
+    
+    /// Receive a progress event after each iteration
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+        self.on_progress = Some(ProgressHandler::new(callback));
+    }
+    
+    /// Perturbation strength after `no_improve` non-improving iterations:
+    /// one more move every `escalate_after` iterations, up to the maximum
+    fn strength(&self, no_improve: usize) -> usize {
+        let escalation = no_improve.checked_div(self.escalate_after).unwrap_or(0);
+        (self.perturbation_strength + escalation).min(self.max_perturbation_strength.max(self.perturbation_strength))
+    }
+    
+    /// Feasible local optimum built from the edges of the elite tours
+    fn elite_restart(&self, instance: &PDTSPInstance, elite: &SolutionPool, vnd: &VND, rng: &mut ChaCha8Rng) -> Option<Solution> {
+        let mut restart = EdgeFrequencyHeuristic::from_pool(elite, instance.dimension, rng.gen()).construct(instance);
+        vnd.improve(instance, &mut restart);
+        (restart.feasible && restart.tour.len() == instance.dimension).then_some(restart)
     }
 }
 
@@ -1119,10 +2724,16 @@ impl Default for IteratedLocalSearch {
 
 impl LocalSearch for IteratedLocalSearch {
     fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        self.improve_until(instance, solution, Deadline::none())
+    }
+    
+    fn improve_until(&self, instance: &PDTSPInstance, solution: &mut Solution, deadline: Deadline) -> bool {
+        let deadline = self.deadline.earliest(deadline);
         let n = solution.tour.len();
         if n < 3 { return false; }
         let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
-        let vnd = VND::with_standard_operators();
+        let mut vnd = VND::with_standard_operators().with_deadline(deadline);
+        self.vnd.apply(&mut vnd);
         
         // Apply initial local search
         vnd.improve(instance, solution);
@@ -1132,27 +2743,64 @@ impl LocalSearch for IteratedLocalSearch {
         
         let mut current_tour = solution.tour.clone();
         let mut current_cost = solution.cost;
+        let mut current_feasible = solution.feasible;
         
+        let start = web_time::Instant::now();
+        let mut convergence = vec![(0.0, best_cost)];
+        let mut snapshots = Vec::new();
+        if self.record_snapshots {
+            snapshots.push((0.0, best_tour.clone()));
+        }
         let mut no_improve = 0;
         let mut iteration = 0;
-        
-        while iteration < self.max_iterations && no_improve < self.max_no_improve {
+        // Perturbations: (accepted, cost decrease of the current tour, time)
+        let mut perturbation_stats = (0u64, 0.0f64, 0.0f64);
+        // Distinct local optima the restarts are built from
+        let mut elite = SolutionPool::new(10);
+        if self.restart_after > 0 {
+            elite.offer(solution);
+        }
+        let mut acceptance = self.acceptance.criterion(
+            current_cost,
+            self.max_iterations,
+            self.acceptance_temperature,
+            self.history_length,
+        );
+        
+        while iteration < self.max_iterations && no_improve < self.max_no_improve && !deadline.expired() {
             // Perturb current solution
+            let perturbation_start = web_time::Instant::now();
             let mut perturbed = current_tour.clone();
-            self.perturb(instance, &mut perturbed, &mut rng);
+            let strength = self.strength(no_improve);
+            self.perturbation.perturb(instance, &mut perturbed, strength, &mut rng);
+            perturbation_stats.2 += perturbation_start.elapsed().as_secs_f64();
             
             // Apply local search to perturbed solution
             let mut perturbed_solution = Solution::from_tour(instance, perturbed, "ILS-temp");
             vnd.improve(instance, &mut perturbed_solution);
+            solution.statistics.merge(&perturbed_solution.statistics);
+            if self.restart_after > 0 && perturbed_solution.feasible {
+                elite.offer(&perturbed_solution);
+            }
             
-            // Acceptance criterion (accept if better than current)
-            if perturbed_solution.cost < current_cost {
+            // Infeasible local optima are only accepted from an infeasible tour
+            if (perturbed_solution.feasible || !current_feasible)
+                && acceptance.accept(perturbed_solution.cost, current_cost, best_cost, &mut rng)
+            {
+                perturbation_stats.0 += 1;
+                perturbation_stats.1 += current_cost - perturbed_solution.cost;
                 current_tour = perturbed_solution.tour;
                 current_cost = perturbed_solution.cost;
+                current_feasible = perturbed_solution.feasible;
                 
                 if current_cost < best_cost - 1e-9 {
                     best_tour = current_tour.clone();
                     best_cost = current_cost;
+                    let elapsed = start.elapsed().as_secs_f64();
+                    convergence.push((elapsed, best_cost));
+                    if self.record_snapshots {
+                        snapshots.push((elapsed, best_tour.clone()));
+                    }
                     no_improve = 0;
                 } else {
                     no_improve += 1;
@@ -1161,7 +2809,28 @@ impl LocalSearch for IteratedLocalSearch {
                 no_improve += 1;
             }
             
+            if self.restart_after > 0 && no_improve > 0 && no_improve % self.restart_after == 0 {
+                if let Some(restart) = self.elite_restart(instance, &elite, &vnd, &mut rng) {
+                    current_tour = restart.tour;
+                    current_cost = restart.cost;
+                    current_feasible = true;
+                    if current_cost < best_cost - 1e-9 {
+                        best_tour = current_tour.clone();
+                        best_cost = current_cost;
+                        let elapsed = start.elapsed().as_secs_f64();
+                        convergence.push((elapsed, best_cost));
+                        if self.record_snapshots {
+                            snapshots.push((elapsed, best_tour.clone()));
+                        }
+                        no_improve = 0;
+                    }
+                }
+            }
+            
             iteration += 1;
+            notify(&self.on_progress, || {
+                ProgressEvent::new(self.name(), iteration, best_cost, start.elapsed().as_secs_f64())
+            });
         }
         
         let improved = best_cost < solution.cost - 1e-9;
@@ -1169,6 +2838,10 @@ impl LocalSearch for IteratedLocalSearch {
         solution.tour = best_tour;
         solution.cost = best_cost;
         solution.iterations = Some(iteration);
+        solution.convergence = convergence;
+        solution.snapshots = snapshots;
+        let (accepted, gain, time) = perturbation_stats;
+        solution.statistics.record("ILS-Perturbation", iteration as u64, accepted, gain, time);
         solution.validate(instance);
         
         improved
@@ -1185,7 +2858,7 @@ mod tests {
     use crate::instance::Node;
     
     fn create_test_instance() -> PDTSPInstance {
-        use crate::instance::CostFunction;
+        use crate::instance::DistanceMatrix;
         
         let nodes = vec![
             Node::new(0, 0.0, 0.0, 0, 0),
@@ -1194,25 +2867,14 @@ mod tests {
             Node::new(3, 1.0, 1.0, -2, 0),
         ];
         
-        let mut instance = PDTSPInstance {
-            cost_function: CostFunction::Distance,
-            alpha: 0.1,
-            beta: 0.5,
-            name: "test".to_string(),
-            comment: "test".to_string(),
-            dimension: 4,
-            capacity: 10,
-            nodes: nodes.clone(),
-            distance_matrix: Vec::new(),
-            return_depot_demand: 0,
-        };
+        let mut instance = PDTSPInstance::from_parts("test", 10, nodes.clone(), DistanceMatrix::default());
         
-        instance.distance_matrix = vec![vec![0.0; 4]; 4];
+        instance.distance_matrix = DistanceMatrix::new(4);
         for i in 0..4 {
             for j in 0..4 {
                 let dx = instance.nodes[i].x - instance.nodes[j].x;
                 let dy = instance.nodes[i].y - instance.nodes[j].y;
-                instance.distance_matrix[i][j] = (dx * dx + dy * dy).sqrt();
+                instance.distance_matrix.set(i, j, (dx * dx + dy * dy).sqrt());
             }
         }
         
@@ -1229,4 +2891,602 @@ mod tests {
         
         assert!(solution.feasible);
     }
+    
+    #[test]
+    fn test_two_opt_dont_look_bits_reaches_local_optimum() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        instance.nodes = (0..60).map(|i| {
+            let demand = if i == 0 { 0 } else if i % 2 == 1 { 2 } else { -2 };
+            Node::new(i, rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), demand, 0)
+        }).collect();
+        instance.dimension = 60;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        let initial = Solution::from_tour(&instance, (0..60).collect(), "test");
+        assert!(initial.feasible);
+        
+        // Don't-look bits are opt-in
+        assert!(!TwoOptSearch::first_improvement().dont_look_bits);
+        let search = TwoOptSearch::first_improvement().with_dont_look_bits(true);
+        let mut solution = initial.clone();
+        assert!(search.improve(&instance, &mut solution));
+        assert!(solution.feasible);
+        assert!(solution.cost < initial.cost);
+        
+        // Small tours scan all nodes by default: no improving feasible 2-opt move is left
+        assert_eq!(search.candidates(60), 0);
+        let evaluator = DeltaEvaluator::new(&instance, &solution.tour);
+        for i in 0..58 {
+            for j in i + 2..60 {
+                if i > 0 || j < 59 {
+                    assert!(evaluator.two_opt_delta(i, j) > -1e-6 || !evaluator.two_opt_feasible(i, j));
+                }
+            }
+        }
+        
+        // Large tours default to candidate lists
+        assert_eq!(search.candidates(TwoOptSearch::AUTO_CANDIDATE_THRESHOLD), TwoOptSearch::AUTO_CANDIDATES);
+        
+        let mut pruned = initial.clone();
+        search.with_candidates(8).improve(&instance, &mut pruned);
+        assert!(pruned.feasible && pruned.cost < initial.cost);
+    }
+    
+    #[test]
+    fn test_sa_temperature_calibration_scales_with_instance() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        instance.nodes = (0..12).map(|i| Node::new(i, rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0), 0, 0)).collect();
+        instance.dimension = 12;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        let solution = Solution::from_tour(&instance, (0..12).collect(), "test");
+        
+        let sa = SimulatedAnnealing::new().with_auto_temperature(0.5);
+        let (initial, final_temp) = sa.calibrate_temperatures(&instance, &solution, &mut ChaCha8Rng::seed_from_u64(1)).unwrap();
+        assert!(final_temp < initial);
+        
+        // Scaling every coordinate scales the calibrated temperatures alike
+        for node in instance.nodes.iter_mut() {
+            node.x *= 1000.0;
+            node.y *= 1000.0;
+        }
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        let scaled_solution = Solution::from_tour(&instance, (0..12).collect(), "test");
+        let (scaled, _) = sa.calibrate_temperatures(&instance, &scaled_solution, &mut ChaCha8Rng::seed_from_u64(1)).unwrap();
+        assert!((scaled / initial - 1000.0).abs() < 1e-3 * 1000.0);
+    }
+    
+    #[test]
+    fn test_or_opt_delta_forward_backward_reversed() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        instance.nodes = (0..9).map(|i| Node::new(i, rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0), 0, 0)).collect();
+        instance.dimension = 9;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        
+        let or_opt = OrOptSearch::new().with_reversal(true);
+        let tour: Vec<usize> = (0..9).collect();
+        let base = instance.tour_length(&tour);
+        let evaluator = DeltaEvaluator::new(&instance, &tour);
+        for seg_len in 1..=3 {
+            for seg_start in 1..=9 - seg_len {
+                for after in (0..9).filter(|&a| a + 1 < seg_start || a >= seg_start + seg_len) {
+                    for reversed in [false, true] {
+                        let delta = evaluator.or_opt_delta(seg_start, seg_len, after, reversed);
+                        let moved = relocate_segment(&tour, seg_start, seg_len, after, reversed);
+                        assert!((instance.tour_length(&moved) - base - delta).abs() < 1e-9);
+                    }
+                }
+            }
+        }
+        
+        let mut solution = Solution::from_tour(&instance, tour, "test");
+        assert!(or_opt.improve(&instance, &mut solution));
+        assert!(solution.cost < base);
+    }
+    
+    #[test]
+    fn test_cross_exchange_under_load_dependent_cost() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        let demands = [0, 3, -2, 4, -3, 2, -4, 1, -1, 0, 2, -2];
+        let mut improved = 0;
+        for seed in 0..20 {
+            let mut instance = create_test_instance();
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            instance.nodes = demands.iter().enumerate()
+                .map(|(i, &d)| Node::new(i, rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0), d, 0))
+                .collect();
+            instance.dimension = demands.len();
+            instance.capacity = 6;
+            instance.cost_function = CostFunction::Quadratic;
+            instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+            
+            let mut solution = Solution::from_tour(&instance, (0..demands.len()).collect(), "test");
+            assert!(solution.feasible);
+            // Start from a local optimum of the standard operators
+            VND::with_standard_operators().improve(&instance, &mut solution);
+            let local_optimum = solution.cost;
+            
+            if CrossExchangeSearch::new().improve(&instance, &mut solution) {
+                improved += 1;
+                assert!(solution.cost < local_optimum - 1e-9);
+            }
+            assert!(solution.feasible);
+            assert!((solution.cost - instance.tour_cost(&solution.tour)).abs() < 1e-9);
+            let mut sorted = solution.tour.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..demands.len()).collect::<Vec<_>>());
+        }
+        // Some local optima of 2-opt, swap, relocation and Or-opt are not
+        // CROSS-exchange optima
+        assert!(improved > 0);
+    }
+    
+    #[test]
+    fn test_load_aware_ranking_and_load_shift() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        // The vehicle leaves loaded, so pickups can move past deliveries
+        let demands = [6, 3, -2, 4, -3, 2, -4, 1, -1, 0, 2, -2];
+        let mut shifted = 0;
+        for seed in 0..20 {
+            let mut instance = create_test_instance();
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            instance.nodes = demands.iter().enumerate()
+                .map(|(i, &d)| Node::new(i, rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0), d, 0))
+                .collect();
+            instance.dimension = demands.len();
+            instance.capacity = 12;
+            instance.cost_function = CostFunction::Quadratic;
+            instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+            
+            // Moves are ranked by their true delta, load surcharge included
+            let tour: Vec<usize> = (0..demands.len()).collect();
+            let evaluator = DeltaEvaluator::new(&instance, &tour);
+            let ranked = evaluator.ranked_moves(TwoHOptSearch::new().candidates(&tour));
+            for pair in ranked.windows(2) {
+                assert!(pair[0].0 <= pair[1].0);
+            }
+            for (delta, mv) in &ranked {
+                let moved = mv.applied_to(&tour);
+                assert!((instance.tour_cost(&moved) - instance.tour_cost(&tour) - delta).abs() < 1e-6);
+            }
+            // The bounded heap keeps the best of them
+            let best = evaluator.best_moves(TwoHOptSearch::new().candidates(&tour), 3);
+            assert_eq!(best.len(), ranked.len().min(3));
+            for ((delta, _), (ranked_delta, _)) in best.iter().zip(&ranked) {
+                assert_eq!(delta, ranked_delta);
+            }
+            let feasible = ranked.iter().find(|(_, mv)| mv.is_feasible(&evaluator)).map(|(delta, _)| *delta);
+            assert_eq!(evaluator.best_feasible_move(TwoHOptSearch::new().candidates(&tour)).map(|(delta, _)| delta), feasible);
+            
+            let mut solution = Solution::from_tour(&instance, tour, "test");
+            let initial = solution.cost;
+            let shift = LoadShiftSearch { heavy_fraction: 1.0, ..LoadShiftSearch::new() };
+            if shift.improve(&instance, &mut solution) {
+                shifted += 1;
+                assert!(solution.cost < initial - 1e-9);
+            }
+            
+            // The best feasible move is applied until none is left, also
+            // when the ranked moves are all infeasible
+            let mut narrow = solution.clone();
+            TwoHOptSearch { ranked_moves: 1, ..TwoHOptSearch::new() }.improve(&instance, &mut narrow);
+            let evaluator = DeltaEvaluator::new(&instance, &narrow.tour);
+            assert!(evaluator.best_feasible_move(TwoHOptSearch::new().candidates(&narrow.tour)).is_none());
+            TwoHOptSearch::new().improve(&instance, &mut solution);
+            let evaluator = DeltaEvaluator::new(&instance, &solution.tour);
+            assert!(evaluator.ranked_moves(TwoHOptSearch::new().candidates(&solution.tour))
+                .iter().all(|(_, mv)| !mv.is_feasible(&evaluator)));
+            assert!(solution.feasible);
+            assert!((solution.cost - instance.tour_cost(&solution.tour)).abs() < 1e-9);
+            let mut sorted = solution.tour.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..demands.len()).collect::<Vec<_>>());
+        }
+        assert!(shifted > 0);
+    }
+    
+    #[test]
+    fn test_ejection_chain_keeps_feasible_permutation() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let demands = [0, 3, -2, 4, -3, 2, -4, 1, -1, 0, 2, -2];
+        instance.nodes = demands.iter().enumerate()
+            .map(|(i, &d)| Node::new(i, rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0), d, 0))
+            .collect();
+        instance.dimension = demands.len();
+        instance.capacity = 5;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        
+        let tour: Vec<usize> = (0..demands.len()).collect();
+        assert!(instance.is_feasible(&tour));
+        let mut solution = Solution::from_tour(&instance, tour, "test");
+        let initial_cost = solution.cost;
+        
+        assert!(EjectionChainSearch::new().improve(&instance, &mut solution));
+        assert!(solution.feasible);
+        assert!(solution.is_complete(&instance));
+        assert!(solution.cost < initial_cost);
+        assert!((solution.cost - instance.tour_cost(&solution.tour)).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_tabu_neighborhoods_and_diversification() {
+        use crate::instance::{CostFunction, DistanceMatrix, DistanceMode};
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let demands = [0, 3, -2, 4, -3, 2, -4, 1, -1, 0, 2, -2, 3, -3];
+        instance.nodes = demands.iter().enumerate()
+            .map(|(i, &d)| Node::new(i, rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0), d, 0))
+            .collect();
+        instance.dimension = demands.len();
+        instance.capacity = 5;
+        instance.cost_function = CostFunction::Quadratic;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        
+        let tour: Vec<usize> = (0..demands.len()).collect();
+        let initial = Solution::from_tour(&instance, tour, "test");
+        assert!(initial.feasible);
+        
+        for tabu in [
+            TabuSearch::new().with_candidates(0),
+            TabuSearch::new().with_candidates(4),
+            TabuSearch::new().with_candidates(4).with_sampling(10, 1),
+            TabuSearch::new().with_diversification(Diversification::None),
+            TabuSearch::new().with_diversification(Diversification::EliteRestart),
+        ] {
+            let mut solution = initial.clone();
+            tabu.improve(&instance, &mut solution);
+            assert!(solution.feasible);
+            assert!(solution.is_complete(&instance));
+            assert!(solution.cost < initial.cost);
+            assert!((solution.cost - instance.tour_cost(&solution.tour)).abs() < 1e-9);
+        }
+    }
+    
+    #[test]
+    fn test_depot_reinsertion_unloads_pickups() {
+        let text = "NAME: pickups\nDIMENSION: 4\nCAPACITY: 5\nNODE_COORD_SECTION\n1 0 0\n2 10 0\n3 10 10\n4 0 10\nDEMAND_SECTION\n1 0\n2 4\n3 4\n4 4\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let mut solution = Solution::from_tour(&instance, vec![0, 1, 2, 3], "test");
+        assert!(!solution.feasible);
+        
+        assert!(VND::with_standard_operators().improve(&instance, &mut solution));
+        assert!(solution.feasible);
+        assert!(solution.is_complete(&instance));
+        assert_eq!(solution.tour.iter().filter(|&&node| node == 0).count(), 3);
+        
+        // Depot visits that are no longer needed are dropped
+        let relaxed = PDTSPInstance { capacity: 12, ..instance.clone() };
+        let mut detour = Solution::from_tour(&relaxed, vec![0, 1, 0, 2, 3], "test");
+        assert!(DepotReinsertionSearch::new().improve(&relaxed, &mut detour));
+        assert_eq!(detour.tour, vec![0, 1, 2, 3]);
+        
+        let mut single = Solution::from_tour(&relaxed, vec![0, 1, 2, 3], "test");
+        assert!(!DepotReinsertionSearch::new().improve(&relaxed, &mut single));
+    }
+    
+    #[test]
+    fn test_sa_penalty_mode_reaches_feasible_tour() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        // Pickups and deliveries of 3 with capacity 3 must alternate
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        instance.nodes = (0..13).map(|i| {
+            let demand = if i == 0 { 0 } else if i % 2 == 1 { 3 } else { -3 };
+            Node::new(i, rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), demand, 0)
+        }).collect();
+        instance.dimension = 13;
+        instance.capacity = 3;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        let tour = vec![0, 1, 3, 5, 7, 9, 11, 2, 4, 6, 8, 10, 12];
+        
+        let mut plain = Solution::from_tour(&instance, tour.clone(), "test");
+        assert!(!plain.feasible);
+        let sa = SimulatedAnnealing::with_params(100.0, 1.0, 0.95, 50);
+        sa.improve(&instance, &mut plain);
+        assert!(!plain.feasible);
+        
+        let mut penalized = Solution::from_tour(&instance, tour, "test");
+        let sa = sa.with_penalty(CapacityPenalty::new());
+        assert!(sa.improve(&instance, &mut penalized));
+        assert!(penalized.feasible);
+        assert!(penalized.is_complete(&instance));
+        assert!((penalized.cost - instance.tour_cost(&penalized.tour)).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_random_moves_are_priced_in_place() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let demands = [0, 3, -2, 4, -3, 2, -4, 1, -1, 0, 2, -2];
+        instance.nodes = demands.iter().enumerate()
+            .map(|(i, &d)| Node::new(i, rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0), d, 0))
+            .collect();
+        instance.dimension = demands.len();
+        instance.capacity = 6;
+        instance.cost_function = CostFunction::Quadratic;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        
+        let tour: Vec<usize> = (0..demands.len()).collect();
+        let evaluator = DeltaEvaluator::new(&instance, &tour);
+        for _ in 0..500 {
+            let move_type = rng.gen_range(0..NEIGHBOR_MOVES.len());
+            let Some(mv) = random_move(&evaluator, &mut rng, move_type) else {
+                continue;
+            };
+            let moved = mv.applied_to(&tour);
+            assert_eq!(moved[0], 0);
+            assert!((mv.delta(&evaluator) - (instance.tour_cost(&moved) - instance.tour_cost(&tour))).abs() < 1e-6);
+            assert_eq!(mv.is_feasible(&evaluator), instance.is_feasible(&moved));
+        }
+        
+        let mut solution = Solution::from_tour(&instance, tour, "test");
+        let initial = solution.cost;
+        assert!(SimulatedAnnealing::with_params(10.0, 0.1, 0.95, 50).improve(&instance, &mut solution));
+        assert!(solution.feasible);
+        assert!(solution.cost < initial);
+    }
+    
+    #[test]
+    fn test_sa_iteration_cost_does_not_grow_with_size() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        // Customers on a circle in tour order (a local optimum), alternating
+        // pickups and deliveries; near zero temperature no move is accepted
+        let seconds_per_iteration = |n: usize, depot_visit: bool, penalty: bool| {
+            let mut instance = create_test_instance();
+            instance.nodes = (0..n).map(|i| {
+                let angle = i as f64 * std::f64::consts::TAU / n as f64;
+                let demand = if i == 0 { 0 } else if i % 2 == 1 { 1 } else { -1 };
+                Node::new(i, 1000.0 * angle.cos(), 1000.0 * angle.sin(), demand, 0)
+            }).collect();
+            instance.dimension = n;
+            instance.capacity = 2;
+            instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::OnDemand);
+            let mut tour: Vec<usize> = (0..n).collect();
+            if depot_visit {
+                // Unload before a pickup
+                tour.insert(n / 2 + 1, 0);
+            }
+            let initial = Solution::from_tour(&instance, tour, "test");
+            assert!(initial.feasible);
+            
+            let mut sa = SimulatedAnnealing::with_params(1e-9, 1e-10, 0.5, 5_000);
+            if penalty {
+                sa = sa.with_penalty(CapacityPenalty::new());
+            }
+            (0..3).map(|_| {
+                let mut solution = initial.clone();
+                let start = web_time::Instant::now();
+                sa.improve(&instance, &mut solution);
+                start.elapsed().as_secs_f64() / solution.iterations.unwrap() as f64
+            }).fold(f64::INFINITY, f64::min)
+        };
+        
+        for (depot_visit, penalty) in [(false, false), (false, true), (true, false), (true, true)] {
+            let (small, large) = (seconds_per_iteration(100, depot_visit, penalty), seconds_per_iteration(3200, depot_visit, penalty));
+            assert!(large < 4.0 * small, "depot visit {}, penalty {}: {:e}s vs {:e}s per iteration", depot_visit, penalty, large, small);
+        }
+    }
+    
+    #[test]
+    fn test_operator_statistics_are_recorded() {
+        let instance = create_test_instance();
+        let mut solution = Solution::from_tour(&instance, vec![0, 2, 1, 3], "test");
+        let initial_cost = solution.cost;
+        VND::with_standard_operators().improve(&instance, &mut solution);
+        
+        let stats = &solution.statistics;
+        for name in ["2-Opt-FI", "Swap", "Relocation", "Or-Opt"] {
+            assert!(stats.operator(name).is_some_and(|op| op.evaluated > 0), "{}", name);
+        }
+        let gain: f64 = stats.operators.iter().map(|op| op.gain).sum();
+        assert!((gain - (initial_cost - solution.cost)).abs() < 1e-9);
+        assert!(stats.operators.iter().all(|op| op.applied > 0 || op.gain == 0.0));
+        
+        let mut annealed = Solution::from_tour(&instance, vec![0, 2, 1, 3], "test");
+        SimulatedAnnealing::with_params(10.0, 1.0, 0.9, 20).improve(&instance, &mut annealed);
+        let evaluated: u64 = NEIGHBOR_MOVES.iter()
+            .map(|name| annealed.statistics.operator(&format!("SA-{}", name)).unwrap().evaluated)
+            .sum();
+        assert_eq!(evaluated as usize, annealed.iterations.unwrap());
+    }
+    
+    #[test]
+    fn test_vnd_operator_registry_and_adaptive_ordering() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        for op in VNDOperator::ALL {
+            assert_eq!(VNDOperator::from_name(op.name()), Ok(op));
+            assert_eq!(serde_json::to_string(&op).unwrap(), format!("\"{}\"", op.name()));
+        }
+        assert!(VNDOperator::from_name("three-opt").is_err());
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let demands = [0, 3, -2, 4, -3, 2, -4, 1, -1, 0, 2, -2];
+        instance.nodes = demands.iter().enumerate()
+            .map(|(i, &d)| Node::new(i, rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0), d, 0))
+            .collect();
+        instance.dimension = demands.len();
+        instance.capacity = 6;
+        instance.cost_function = CostFunction::Quadratic;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        
+        let operators = [VNDOperator::DepotReinsertion, VNDOperator::Swap, VNDOperator::TwoOpt];
+        let fixed = VND::with_operators(&operators);
+        let adaptive = VND::with_operators(&operators).with_ordering(VNDOrdering::Adaptive);
+        assert_eq!(fixed.operator_names(), adaptive.operator_names());
+        
+        let tour: Vec<usize> = (0..demands.len()).collect();
+        let mut solution = Solution::from_tour(&instance, tour.clone(), "test");
+        assert!(adaptive.improve(&instance, &mut solution));
+        assert!(solution.feasible);
+        // Depot visits never pay off on a feasible tour: once tried, the
+        // operator falls behind the ones that improved
+        assert_eq!(fixed.operator_names()[0], "DepotReinsertion");
+        assert_ne!(adaptive.operator_names()[0], "DepotReinsertion");
+        
+        let mut reference = Solution::from_tour(&instance, tour, "test");
+        fixed.improve(&instance, &mut reference);
+        assert!(reference.feasible);
+    }
+    
+    #[test]
+    fn test_ils_acceptance_criteria_keep_best_feasible() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        instance.nodes = (0..25).map(|i| {
+            let demand = if i == 0 { 0 } else if i % 2 == 1 { 2 } else { -2 };
+            Node::new(i, rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), demand, 0)
+        }).collect();
+        instance.dimension = 25;
+        instance.capacity = 6;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        let initial = Solution::from_tour(&instance, (0..25).collect(), "test");
+        assert!(initial.feasible);
+        
+        for acceptance in [Acceptance::Better, Acceptance::RandomWalk, Acceptance::Annealing, Acceptance::LateAcceptance] {
+            let ils = IteratedLocalSearch::with_params(3, 30, 30).with_acceptance(acceptance);
+            let mut solution = initial.clone();
+            ils.improve(&instance, &mut solution);
+            assert!(solution.feasible, "{:?}", acceptance);
+            assert!(solution.is_complete(&instance));
+            assert!(solution.cost < initial.cost);
+            assert!((solution.cost - instance.tour_cost(&solution.tour)).abs() < 1e-9);
+        }
+    }
+    
+    #[test]
+    fn test_threshold_accepting_and_record_to_record() {
+        use crate::instance::{DistanceMatrix, DistanceMode};
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        instance.nodes = (0..30).map(|i| {
+            let demand = if i == 0 { 0 } else if i % 2 == 1 { 2 } else { -2 };
+            Node::new(i, rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), demand, 0)
+        }).collect();
+        instance.dimension = 30;
+        instance.capacity = 6;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        let initial = Solution::from_tour(&instance, (0..30).collect(), "test");
+        assert!(initial.feasible);
+        
+        let searches: [Box<dyn LocalSearch>; 2] = [
+            Box::new(ThresholdAccepting::with_params(0.0, 5000)),
+            Box::new(RecordToRecord::with_params(0.02, 5000)),
+        ];
+        for search in searches {
+            let mut solution = initial.clone();
+            assert!(search.improve(&instance, &mut solution), "{}", search.name());
+            assert!(solution.feasible);
+            assert!(solution.is_complete(&instance));
+            assert!((solution.cost - instance.tour_cost(&solution.tour)).abs() < 1e-9);
+            assert_eq!(solution.iterations, Some(5000));
+            
+            // Deterministic for a given seed
+            let mut again = initial.clone();
+            search.improve(&instance, &mut again);
+            assert_eq!(again.tour, solution.tour);
+        }
+    }
+    
+    #[test]
+    fn test_two_opt_two_level_list_on_demand() {
+        use crate::instance::DistanceMatrix;
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(21);
+        instance.nodes = (0..400).map(|i| {
+            let demand = if i == 0 { 0 } else if i % 2 == 1 { 2 } else { -2 };
+            Node::new(i, rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), demand, 0)
+        }).collect();
+        instance.dimension = 400;
+        instance.capacity = 4;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::OnDemand);
+        let initial = Solution::from_tour(&instance, (0..400).collect(), "test");
+        assert!(initial.feasible);
+        
+        let search = TwoOptSearch::first_improvement().with_dont_look_bits(true).with_candidates(8).with_two_level_threshold(100);
+        assert!(search.uses_two_level(&instance, &initial));
+        // The list indexes each node once: intermediate depot visits are refused
+        let mut with_depot = initial.tour.clone();
+        with_depot.insert(201, 0);
+        let with_depot = Solution::from_tour(&instance, with_depot, "test");
+        assert!(with_depot.feasible);
+        assert!(!search.uses_two_level(&instance, &with_depot));
+        assert!(!SimulatedAnnealing::new().with_two_level_threshold(100).uses_two_level(&instance, &with_depot));
+        let mut solution = initial.clone();
+        assert!(search.improve(&instance, &mut solution));
+        assert!(solution.feasible && solution.is_complete(&instance));
+        assert_eq!(solution.tour[0], 0);
+        assert!(solution.cost < 0.5 * initial.cost);
+        assert!((solution.cost - instance.tour_cost(&solution.tour)).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn test_sa_two_level_walk() {
+        use crate::instance::DistanceMatrix;
+        
+        let mut instance = create_test_instance();
+        let mut rng = ChaCha8Rng::seed_from_u64(17);
+        instance.nodes = (0..300).map(|i| {
+            let demand = if i == 0 { 0 } else if i % 2 == 1 { 2 } else { -2 };
+            Node::new(i, rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), demand, 0)
+        }).collect();
+        instance.dimension = 300;
+        instance.capacity = 4;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::OnDemand);
+        let tour: Vec<usize> = (0..300).collect();
+        
+        // Deltas match the full cost, and undo restores the tour
+        let mut list = TwoLevelList::from_instance(&instance, &tour);
+        let mut current = tour.clone();
+        for _ in 0..2000 {
+            let move_type = rng.gen_range(0..NEIGHBOR_MOVES.len());
+            let Some((mv, delta)) = ListMove::random(&list, &instance, &mut rng, move_type) else {
+                continue;
+            };
+            mv.apply(&mut list);
+            let moved = list.to_vec();
+            assert_eq!(moved[0], 0);
+            assert!(instance.is_complete_tour(&moved));
+            assert!((instance.tour_cost(&moved) - instance.tour_cost(&current) - delta).abs() < 1e-6, "{:?}", mv);
+            if rng.gen_bool(0.5) {
+                mv.undo(&mut list);
+                assert_eq!(list.to_vec(), current);
+            } else {
+                current = moved;
+            }
+        }
+        
+        let initial = Solution::from_tour(&instance, tour, "test");
+        assert!(initial.feasible);
+        let sa = SimulatedAnnealing::with_params(50.0, 0.5, 0.95, 500).with_two_level_threshold(100);
+        assert!(sa.uses_two_level(&instance, &initial));
+        let mut solution = initial.clone();
+        assert!(sa.improve(&instance, &mut solution));
+        assert!(solution.feasible && solution.is_complete(&instance));
+        assert!(solution.cost < 0.5 * initial.cost);
+        // The tour copied out is the best one reached
+        assert!((solution.convergence.last().unwrap().1 - solution.cost).abs() < 1e-6);
+    }
 }
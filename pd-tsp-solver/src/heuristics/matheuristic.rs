@@ -0,0 +1,196 @@
+//! Subproblem reoptimization matheuristic.
+//!
+//! Local branching restricted to a neighborhood that an exact method can
+//! explore: most of the incumbent tour is fixed and a small set of free
+//! positions is reoptimized to optimality by the exact module
+//! (`exact::subproblem`). The free positions are either a window of
+//! consecutive nodes slid along the tour, or a node and its nearest
+//! neighbors within a radius, which may lie far apart in the tour. A pass
+//! solves one subproblem per window or per center node; passes repeat while
+//! they improve the tour. The `window-reopt` algorithm runs VND first, then
+//! this reoptimization.
+
+use crate::exact::subproblem::{reoptimize_positions, MAX_FREE_NODES};
+use crate::heuristics::local_search::LocalSearch;
+use crate::heuristics::statistics::OperatorRun;
+use crate::heuristics::stopping::Deadline;
+use crate::instance::PDTSPInstance;
+use crate::solution::Solution;
+
+/// How the free positions of a subproblem are chosen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FreeNodes {
+    /// `window` consecutive positions, the window advancing by `step`
+    Window,
+    /// A center node and up to `window - 1` of its nearest neighbors closer
+    /// than the radius, for every center node of the tour
+    Radius(f64),
+}
+
+/// Reoptimize windows of the tour exactly, keeping the rest fixed
+pub struct WindowReoptimization {
+    /// Free positions per subproblem (at most `MAX_FREE_NODES`)
+    pub window: usize,
+    /// Choice of the free positions
+    pub mode: FreeNodes,
+    /// Positions the window advances by (window mode); 0 means half a window
+    pub step: usize,
+    /// Maximum number of passes over the tour
+    pub max_passes: usize,
+    pub deadline: Deadline,
+}
+
+impl WindowReoptimization {
+    pub fn new() -> Self {
+        WindowReoptimization {
+            window: 8,
+            mode: FreeNodes::Window,
+            step: 0,
+            max_passes: 10,
+            deadline: Deadline::none(),
+        }
+    }
+
+    /// Free the nearest neighbors within `radius` of each node instead of
+    /// consecutive windows
+    pub fn with_radius(mut self, radius: f64) -> Self {
+        self.mode = FreeNodes::Radius(radius);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Free positions of the subproblems of one pass over `tour`
+    fn subproblems(&self, instance: &PDTSPInstance, tour: &[usize], neighbors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        let n = tour.len();
+        let window = self.window.clamp(1, MAX_FREE_NODES).min(n.saturating_sub(1));
+        if window == 0 {
+            return Vec::new();
+        }
+        match self.mode {
+            FreeNodes::Window => {
+                let step = if self.step == 0 { window.div_ceil(2) } else { self.step };
+                let mut starts: Vec<usize> = (1..n - window + 1).step_by(step).collect();
+                // The last window ends at the end of the tour
+                if starts.last() != Some(&(n - window)) {
+                    starts.push(n - window);
+                }
+                starts.into_iter().map(|start| (start..start + window).collect()).collect()
+            }
+            FreeNodes::Radius(radius) => {
+                let mut position = vec![usize::MAX; instance.dimension];
+                for (p, &node) in tour.iter().enumerate().skip(1) {
+                    position[node] = p;
+                }
+                tour[1..].iter().map(|&center| {
+                    let mut free = vec![position[center]];
+                    free.extend(neighbors[center].iter()
+                        .filter(|&&j| instance.distance(center, j) <= radius && position[j] != usize::MAX)
+                        .map(|&j| position[j])
+                        .take(window - 1));
+                    free
+                }).collect()
+            }
+        }
+    }
+}
+
+impl Default for WindowReoptimization {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSearch for WindowReoptimization {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        self.improve_until(instance, solution, Deadline::none())
+    }
+    
+    fn improve_until(&self, instance: &PDTSPInstance, solution: &mut Solution, deadline: Deadline) -> bool {
+        let deadline = self.deadline.earliest(deadline);
+        if solution.tour.len() < 3 {
+            return false;
+        }
+        let mut run = OperatorRun::start(solution);
+        let neighbors = match self.mode {
+            FreeNodes::Radius(_) => instance.nearest_neighbors(self.window.saturating_sub(1)),
+            FreeNodes::Window => Vec::new(),
+        };
+        let mut cost = instance.tour_cost(&solution.tour);
+        // Once the tour is feasible, every accepted tour must be: the DP
+        // keeps the capacity but not the commodities or route lengths
+        let mut feasible = instance.is_feasible(&solution.tour);
+        let mut total_improved = false;
+
+        for _ in 0..self.max_passes {
+            let mut improved = false;
+            for free in self.subproblems(instance, &solution.tour, &neighbors) {
+                if deadline.expired() {
+                    break;
+                }
+                run.evaluated += 1;
+                // Segments too wide for the exact solver are skipped
+                let Ok(Some(tour)) = reoptimize_positions(instance, &solution.tour, &free) else {
+                    continue;
+                };
+                let new_cost = instance.tour_cost(&tour);
+                if new_cost >= cost - 1e-9 {
+                    continue;
+                }
+                let new_feasible = instance.is_feasible(&tour);
+                if new_feasible || !feasible {
+                    solution.tour = tour;
+                    cost = new_cost;
+                    feasible = new_feasible;
+                    run.applied += 1;
+                    improved = true;
+                    total_improved = true;
+                }
+            }
+            if !improved || deadline.expired() {
+                break;
+            }
+        }
+
+        solution.validate(instance);
+        run.finish(self.name(), solution);
+        total_improved
+    }
+
+    fn name(&self) -> &str {
+        "SubproblemReopt"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact::dynamic_programming;
+    use crate::heuristics::construction::{ConstructionHeuristic, NearestNeighborHeuristic};
+
+    #[test]
+    fn test_window_reoptimization_improves_tours() {
+        let text = "NAME: w\nDIMENSION: 10\nCAPACITY: 8\nNODE_COORD_SECTION\n1 0 0\n2 3 0\n3 6 1\n4 5 5\n5 1 6\n6 -2 3\n7 2 2\n8 4 -3\n9 -4 -2\n10 7 -4\nDEMAND_SECTION\n1 0\n2 4\n3 -3\n4 5\n5 -4\n6 -2\n7 3\n8 -3\n9 2\n10 -2\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let optimum = dynamic_programming::solve(&instance).unwrap().cost;
+
+        for search in [WindowReoptimization::new(), WindowReoptimization::new().with_radius(6.0)] {
+            let mut solution = NearestNeighborHeuristic::new().construct(&instance);
+            assert!(solution.feasible);
+            let initial = solution.cost;
+            search.improve(&instance, &mut solution);
+            assert!(solution.feasible);
+            assert!(solution.cost <= initial + 1e-9);
+            assert!(solution.cost >= optimum - 1e-9);
+            assert!(solution.statistics.operators.iter().any(|op| op.name == "SubproblemReopt"));
+        }
+
+        // A window covering every customer is the Held-Karp solver
+        let mut solution = NearestNeighborHeuristic::new().construct(&instance);
+        WindowReoptimization { window: 9, ..WindowReoptimization::new() }.improve(&instance, &mut solution);
+        assert!((solution.cost - optimum).abs() < 1e-9);
+    }
+}
@@ -2,14 +2,38 @@
 //! 
 //! This module exports all construction and improvement heuristics.
 
+pub mod acceptance;
 pub mod construction;
 pub mod local_search;
+pub mod vns;
+pub mod perturbation;
+pub mod stopping;
+pub mod hybrid;
 pub mod genetic;
 pub mod aco;
 pub mod profit_density;
+pub mod pareto;
+pub mod pipeline;
+pub mod matheuristic;
+pub mod popmusic;
+pub mod repair;
+pub mod rng;
+pub mod statistics;
 
+pub use acceptance::*;
 pub use construction::*;
 pub use local_search::*;
+pub use vns::*;
+pub use perturbation::*;
+pub use stopping::*;
+pub use hybrid::*;
 pub use genetic::*;
 pub use aco::*;
 pub use profit_density::*;
+pub use pareto::*;
+pub use pipeline::*;
+pub use matheuristic::*;
+pub use popmusic::*;
+pub use repair::*;
+pub use rng::*;
+pub use statistics::*;
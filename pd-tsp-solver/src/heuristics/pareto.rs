@@ -0,0 +1,192 @@
+//! Bi-objective mode: travel cost against collected profit.
+//!
+//! Tours may leave customers out, trading profit for a shorter route. The
+//! `EpsilonConstraint` solver sweeps decreasing profit targets: starting from
+//! a tour visiting every customer, it drops customers until the profit of
+//! the tour reaches the next target, then improves the remaining tour with
+//! an iterated local search. Every tour found is offered to a `ParetoFront`,
+//! which keeps the non-dominated (cost, profit) pairs.
+
+use crate::instance::PDTSPInstance;
+use crate::solution::Solution;
+use crate::heuristics::local_search::{IteratedLocalSearch, LocalSearch};
+use crate::heuristics::stopping::Deadline;
+use serde::{Deserialize, Serialize};
+
+/// Feasible solutions no other solution of the set dominates (lower or equal
+/// cost with higher or equal profit), sorted by increasing profit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParetoFront {
+    solutions: Vec<Solution>,
+}
+
+impl ParetoFront {
+    pub fn new() -> Self {
+        ParetoFront { solutions: Vec::new() }
+    }
+
+    /// Add `solution` unless it is infeasible or dominated, removing the
+    /// solutions it dominates. Returns whether it was added.
+    pub fn insert(&mut self, solution: Solution) -> bool {
+        if !solution.feasible {
+            return false;
+        }
+        let (cost, profit) = (solution.cost, solution.total_profit);
+        if self.solutions.iter().any(|s| s.cost <= cost && s.total_profit >= profit) {
+            return false;
+        }
+        self.solutions.retain(|s| !(cost <= s.cost && profit >= s.total_profit));
+        let at = self.solutions.partition_point(|s| s.total_profit < profit);
+        self.solutions.insert(at, solution);
+        true
+    }
+
+    pub fn solutions(&self) -> &[Solution] {
+        &self.solutions
+    }
+
+    pub fn len(&self) -> usize {
+        self.solutions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty()
+    }
+
+    /// Area dominated by the front and bounded by `reference_cost` and zero
+    /// profit; larger is better when comparing fronts
+    pub fn hypervolume(&self, reference_cost: f64) -> f64 {
+        let mut previous = 0;
+        let mut volume = 0.0;
+        for s in self.solutions.iter().filter(|s| s.cost < reference_cost && s.total_profit > 0) {
+            volume += (reference_cost - s.cost) * (s.total_profit - previous) as f64;
+            previous = s.total_profit;
+        }
+        volume
+    }
+
+    /// One line per solution: profit, cost, visited customers and tour
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("profit,cost,customers,tour\n");
+        for s in &self.solutions {
+            let tour: Vec<String> = s.tour.iter().map(|n| n.to_string()).collect();
+            let customers = s.tour.iter().filter(|&&n| n != 0).count();
+            csv.push_str(&format!("{},{:.4},{},{}\n", s.total_profit, s.cost, customers, tour.join(" ")));
+        }
+        csv
+    }
+}
+
+/// Epsilon-constraint sweep over profit targets
+pub struct EpsilonConstraint {
+    /// Number of profit targets, evenly spaced from the total profit to zero
+    pub levels: usize,
+    /// Time limit of the whole sweep, shared evenly by the levels
+    pub time_limit: f64,
+    /// Seed of the iterated local search
+    pub seed: u64,
+}
+
+impl EpsilonConstraint {
+    pub fn new() -> Self {
+        EpsilonConstraint { levels: 10, time_limit: 10.0, seed: 42 }
+    }
+
+    pub fn with_params(levels: usize, time_limit: f64, seed: u64) -> Self {
+        EpsilonConstraint { levels: levels.max(1), time_limit, seed }
+    }
+
+    /// Pareto front of `instance` starting from `initial`, a feasible tour
+    /// visiting every customer
+    pub fn solve(&self, instance: &PDTSPInstance, initial: &Solution) -> ParetoFront {
+        let mut front = ParetoFront::new();
+        let total = instance.tour_profit(&initial.tour);
+        let share = self.time_limit / (self.levels + 1) as f64;
+
+        let mut current = Solution::from_tour(instance, initial.tour.clone(), "EpsilonConstraint");
+        self.polish(instance, &mut current, share, self.seed);
+        front.insert(current.clone());
+
+        for level in 1..=self.levels {
+            let target = (total as f64 * (self.levels - level) as f64 / self.levels as f64).ceil() as i32;
+            while current.total_profit > target {
+                match self.drop_customer(instance, &current.tour, current.total_profit - target) {
+                    Some(tour) => {
+                        current = Solution::from_tour(instance, tour, "EpsilonConstraint");
+                        front.insert(current.clone());
+                    }
+                    None => break,
+                }
+            }
+            self.polish(instance, &mut current, share, self.seed.wrapping_add(level as u64));
+            front.insert(current.clone());
+        }
+        front
+    }
+
+    /// Remove the customer with the largest detour saving per unit of profit
+    /// among those whose removal keeps the tour feasible and loses at most
+    /// `slack` profit
+    fn drop_customer(&self, instance: &PDTSPInstance, tour: &[usize], slack: i32) -> Option<Vec<usize>> {
+        let n = tour.len();
+        let mut candidates: Vec<(f64, usize)> = (1..n)
+            .filter(|&i| tour[i] != 0 && instance.nodes[tour[i]].profit <= slack)
+            .map(|i| {
+                let (prev, node, next) = (tour[i - 1], tour[i], tour[(i + 1) % n]);
+                let saving = instance.distance(prev, node) + instance.distance(node, next) - instance.distance(prev, next);
+                (saving / (instance.nodes[node].profit.max(0) as f64 + 1.0), i)
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        candidates.into_iter().find_map(|(_, i)| {
+            let mut reduced = tour.to_vec();
+            reduced.remove(i);
+            instance.is_feasible(&reduced).then_some(reduced)
+        })
+    }
+
+    fn polish(&self, instance: &PDTSPInstance, solution: &mut Solution, seconds: f64, seed: u64) {
+        let mut ils = IteratedLocalSearch::new();
+        ils.seed = seed;
+        ils.deadline = Deadline::after(seconds);
+        ils.improve(instance, solution);
+    }
+}
+
+impl Default for EpsilonConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epsilon_constraint_front() {
+        let text = "NAME: p\nDIMENSION: 5\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 1 1\n4 0 1\n5 20 20\nDEMAND_SECTION\n1 0\n2 2\n3 -2\n4 1\n5 -1\nEOF\n";
+        let mut instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        for (node, profit) in [(1, 5), (2, 5), (3, 5), (4, 5)] {
+            instance.nodes[node].profit = profit;
+        }
+        let initial = Solution::from_tour(&instance, vec![0, 1, 2, 3, 4], "test");
+        assert!(initial.feasible);
+
+        let front = EpsilonConstraint::with_params(4, 0.5, 1).solve(&instance, &initial);
+        let points: Vec<(i32, f64)> = front.solutions().iter().map(|s| (s.total_profit, s.cost)).collect();
+        assert_eq!(points.first().map(|p| p.0), Some(0));
+        assert_eq!(points.last().map(|p| p.0), Some(20));
+        // Sorted by profit, cost strictly increasing along the front
+        assert!(points.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1));
+        // The far customer is the first one dropped
+        assert!(front.solutions().iter().any(|s| s.total_profit == 15 && !s.tour.contains(&4)));
+
+        let mut copy = front.clone();
+        let dominated = Solution::from_tour(&instance, vec![0, 4, 1], "dominated");
+        assert!(!copy.insert(dominated));
+        assert!(front.hypervolume(100.0) > 0.0);
+        assert_eq!(front.to_csv().lines().count(), front.len() + 1);
+    }
+}
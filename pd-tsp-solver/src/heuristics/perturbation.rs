@@ -0,0 +1,231 @@
+//! Perturbation operators for Iterated Local Search.
+//!
+//! A perturbation must move the search far enough that the following local
+//! search does not simply undo it: random 2-opt or swap moves are usually
+//! repaired by the very same VND operators. The operators here change the
+//! tour in ways a single 2-opt/swap/relocation cannot revert.
+
+use crate::heuristics::construction::GreedyInsertionHeuristic;
+use crate::instance::PDTSPInstance;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+/// Number of attempts to find a feasible random move before giving up
+const MAX_ATTEMPTS: usize = 10;
+
+/// Trait for ILS perturbation operators
+pub trait Perturbation {
+    /// Perturb the tour in place; `strength` is the number of moves applied
+    fn perturb(&self, instance: &PDTSPInstance, tour: &mut Vec<usize>, strength: usize, rng: &mut ChaCha8Rng);
+    fn name(&self) -> &str;
+}
+
+/// Apply up to `strength` moves generated by `make_move`, each one only if it
+/// keeps the tour feasible (any move is accepted from an infeasible tour)
+fn apply_feasible_moves(
+    instance: &PDTSPInstance,
+    tour: &mut Vec<usize>,
+    strength: usize,
+    rng: &mut ChaCha8Rng,
+    make_move: impl Fn(&[usize], &mut ChaCha8Rng) -> Vec<usize>,
+) {
+    for _ in 0..strength {
+        let feasible = instance.is_feasible(tour);
+        for _ in 0..MAX_ATTEMPTS {
+            let candidate = make_move(tour, rng);
+            if !feasible || instance.is_feasible(&candidate) {
+                *tour = candidate;
+                break;
+            }
+        }
+    }
+}
+
+/// Double-bridge move: split the tour after the depot into A B C D and
+/// reconnect it as A C B D. Tours shorter than 5 nodes are returned unchanged.
+pub fn double_bridge(tour: &[usize], rng: &mut ChaCha8Rng) -> Vec<usize> {
+    let n = tour.len();
+    if n < 5 {
+        return tour.to_vec();
+    }
+    let mut cuts = rand::seq::index::sample(rng, n - 2, 3).into_vec();
+    cuts.sort_unstable();
+    let (a, b, c) = (cuts[0] + 2, cuts[1] + 2, cuts[2] + 2);
+    
+    let mut result = Vec::with_capacity(n);
+    result.extend_from_slice(&tour[..a]);
+    result.extend_from_slice(&tour[b..c]);
+    result.extend_from_slice(&tour[a..b]);
+    result.extend_from_slice(&tour[c..]);
+    result
+}
+
+/// Random double-bridge moves
+pub struct DoubleBridge;
+
+impl Perturbation for DoubleBridge {
+    fn perturb(&self, instance: &PDTSPInstance, tour: &mut Vec<usize>, strength: usize, rng: &mut ChaCha8Rng) {
+        apply_feasible_moves(instance, tour, strength, rng, double_bridge);
+    }
+    
+    fn name(&self) -> &str {
+        "DoubleBridge"
+    }
+}
+
+/// Reversal of random segments of at least `min_length` nodes
+pub struct SegmentReversal {
+    /// Minimum length of a reversed segment
+    pub min_length: usize,
+}
+
+impl SegmentReversal {
+    pub fn new() -> Self {
+        SegmentReversal { min_length: 3 }
+    }
+}
+
+impl Default for SegmentReversal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Perturbation for SegmentReversal {
+    fn perturb(&self, instance: &PDTSPInstance, tour: &mut Vec<usize>, strength: usize, rng: &mut ChaCha8Rng) {
+        let n = tour.len();
+        let min_length = self.min_length.max(2);
+        if n < min_length + 1 {
+            return;
+        }
+        apply_feasible_moves(instance, tour, strength, rng, |tour, rng| {
+            let i = rng.gen_range(1..=n - min_length);
+            let j = rng.gen_range(i + min_length - 1..n);
+            let mut result = tour.to_vec();
+            result[i..=j].reverse();
+            result
+        });
+    }
+    
+    fn name(&self) -> &str {
+        "SegmentReversal"
+    }
+}
+
+/// Removal of a random segment of `strength` customers, which are then
+/// greedily reinserted one by one (in random order) at their cheapest
+/// feasible position. The move is undone if it breaks a feasible tour.
+pub struct SegmentRemoval;
+
+impl Perturbation for SegmentRemoval {
+    fn perturb(&self, instance: &PDTSPInstance, tour: &mut Vec<usize>, strength: usize, rng: &mut ChaCha8Rng) {
+        let n = tour.len();
+        let length = strength.min(n.saturating_sub(2));
+        if length == 0 {
+            return;
+        }
+        
+        let original = tour.clone();
+        let start = rng.gen_range(1..=n - length);
+        let mut removed: Vec<usize> = tour.drain(start..start + length).filter(|&node| node != 0).collect();
+        removed.shuffle(rng);
+        
+        let greedy = GreedyInsertionHeuristic::new();
+        for node in removed {
+            match greedy.find_best_insertion(instance, tour, node) {
+                Some((pos, _)) => tour.insert(pos + 1, node),
+                // No feasible position: put the node back where it is cheapest
+                None => {
+                    let pos = (0..tour.len())
+                        .min_by(|&a, &b| {
+                            let cost = |p: usize| instance.distance(tour[p], node)
+                                + instance.distance(node, tour[(p + 1) % tour.len()]);
+                            cost(a).total_cmp(&cost(b))
+                        })
+                        .unwrap_or(0);
+                    tour.insert(pos + 1, node);
+                }
+            }
+        }
+        
+        if !instance.is_feasible(tour) && instance.is_feasible(&original) {
+            *tour = original;
+        }
+    }
+    
+    fn name(&self) -> &str {
+        "SegmentRemoval"
+    }
+}
+
+/// Random 2-opt and swap moves (the original ILS perturbation)
+pub struct RandomMoves;
+
+impl Perturbation for RandomMoves {
+    fn perturb(&self, instance: &PDTSPInstance, tour: &mut Vec<usize>, strength: usize, rng: &mut ChaCha8Rng) {
+        let n = tour.len();
+        if n < 3 {
+            return;
+        }
+        
+        for _ in 0..strength {
+            let mut new_tour = tour.clone();
+            if rng.gen_bool(0.5) {
+                let i = rng.gen_range(0..n - 2);
+                let j = rng.gen_range(i + 2..n);
+                new_tour[i + 1..=j].reverse();
+            } else {
+                let i = rng.gen_range(1..n);
+                let j = rng.gen_range(1..n);
+                if i == j || tour[i] == 0 || tour[j] == 0 {
+                    continue;
+                }
+                new_tour.swap(i, j);
+            }
+            if instance.is_feasible(&new_tour) {
+                *tour = new_tour;
+            }
+        }
+    }
+    
+    fn name(&self) -> &str {
+        "RandomMoves"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::{DistanceMatrix, DistanceMode, Node};
+
+    #[test]
+    fn test_perturbations_keep_feasible_permutations() {
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let demands = [0, 2, -1, 3, -2, 1, -3, 2, -2, 1, -1];
+        let nodes: Vec<Node> = demands.iter().enumerate()
+            .map(|(i, &d)| Node::new(i, rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), d, 0))
+            .collect();
+        let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        let instance = PDTSPInstance::from_parts("perturb", 4, nodes, distance_matrix);
+        let tour: Vec<usize> = (0..demands.len()).collect();
+        assert!(instance.is_feasible(&tour));
+        
+        let operators: Vec<Box<dyn Perturbation>> = vec![
+            Box::new(DoubleBridge),
+            Box::new(SegmentReversal::new()),
+            Box::new(SegmentRemoval),
+            Box::new(RandomMoves),
+        ];
+        for op in &operators {
+            for strength in 1..=4 {
+                let mut perturbed = tour.clone();
+                op.perturb(&instance, &mut perturbed, strength, &mut rng);
+                let mut sorted = perturbed.clone();
+                sorted.sort_unstable();
+                assert_eq!(sorted, tour, "{} broke the permutation", op.name());
+                assert_eq!(perturbed[0], 0);
+                assert!(instance.is_feasible(&perturbed), "{} made the tour infeasible", op.name());
+            }
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! Composable heuristic pipelines.
+//!
+//! A `Pipeline` is one construction stage followed by any number of
+//! improvement stages, each given a share of a global time limit as in the
+//! hybrid solver (which is the pipeline multi-start, VND, ILS). Stages are
+//! plain data, so a pipeline can be built in code,
+//!
+//! ```
+//! use pd_tsp_solver::heuristics::pipeline::{Construct, Improve, Pipeline};
+//!
+//! let pipeline = Pipeline::new()
+//!     .construct(Construct::MultiStart)
+//!     .improve(Improve::Vnd(Default::default()))
+//!     .improve(Improve::Ils(Default::default()));
+//! assert!(pipeline.validate().is_ok());
+//! ```
+//!
+//! or read from the `[pipeline]` section of a solver configuration file.
+//! Any `ConstructionHeuristic` or `LocalSearch` can also be plugged in as a
+//! stage (`Construct::custom`, `Improve::custom`); such stages only exist in
+//! code. Every stage receives the deadline of its share of the time limit.
+//!
+//! A `Solver` turns an instance into a solution within a time limit from a
+//! seed; pipelines and the hybrid solver are solvers.
+
+use crate::config::{ILSParams, SAParams, TabuParams, VNDParams};
+use crate::heuristics::construction::{
+    ConstructionHeuristic, GraspConstruction, GreedyInsertionHeuristic, MultiStartConstruction,
+    NearestNeighborHeuristic, RegretInsertionHeuristic, SavingsHeuristic,
+};
+use crate::heuristics::hybrid::{StageTimes, TimeBudget};
+use crate::heuristics::local_search::{IteratedLocalSearch, LocalSearch, SimulatedAnnealing, TabuSearch, VND};
+use crate::heuristics::rng::RngFactory;
+use crate::heuristics::stopping::Deadline;
+use crate::heuristics::vns::GeneralVNS;
+use crate::instance::PDTSPInstance;
+use crate::solution::Solution;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use web_time::Instant;
+
+/// A complete solver, run within a time limit from a seed
+pub trait Solver {
+    fn solve(&self, instance: &PDTSPInstance, time_limit: f64, seed: u64) -> Solution;
+    fn name(&self) -> &str;
+}
+
+/// Heuristic of a stage given in code, shared by the clones of its pipeline;
+/// two stages are equal when they share the same heuristic
+pub struct Custom<T: ?Sized>(pub Arc<T>);
+
+impl<T: ?Sized> Clone for Custom<T> {
+    fn clone(&self) -> Self {
+        Custom(Arc::clone(&self.0))
+    }
+}
+
+impl<T: ?Sized> PartialEq for Custom<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl std::fmt::Debug for Custom<dyn ConstructionHeuristic + Send + Sync> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Custom({})", self.0.name())
+    }
+}
+
+impl std::fmt::Debug for Custom<dyn LocalSearch + Send + Sync> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Custom({})", self.0.name())
+    }
+}
+
+/// Construction stage of a pipeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Construct {
+    /// Best tour of every construction heuristic
+    MultiStart,
+    /// Reactive GRASP with `restarts` randomized constructions
+    Grasp { restarts: usize },
+    NearestNeighbor,
+    GreedyInsertion,
+    Savings,
+    /// Regret-k insertion
+    Regret { k: usize },
+    /// Any construction heuristic (not serialized)
+    #[serde(skip)]
+    Custom(Custom<dyn ConstructionHeuristic + Send + Sync>),
+}
+
+impl Construct {
+    /// Stage running `heuristic`
+    pub fn custom(heuristic: Box<dyn ConstructionHeuristic + Send + Sync>) -> Self {
+        Construct::Custom(Custom(Arc::from(heuristic)))
+    }
+    
+    pub fn name(&self) -> &str {
+        match self {
+            Construct::MultiStart => "multi-start",
+            Construct::Grasp { .. } => "grasp",
+            Construct::NearestNeighbor => "nearest-neighbor",
+            Construct::GreedyInsertion => "greedy-insertion",
+            Construct::Savings => "savings",
+            Construct::Regret { .. } => "regret",
+            Construct::Custom(custom) => custom.0.name(),
+        }
+    }
+
+    fn run(&self, instance: &PDTSPInstance, rng: &RngFactory, deadline: Deadline) -> Solution {
+        match self {
+            Construct::MultiStart => MultiStartConstruction::with_all_heuristics_seeded(rng).construct_until(instance, deadline),
+            Construct::Grasp { restarts } => {
+                GraspConstruction::with_params(*restarts, rng.derive("grasp")).construct_until(instance, deadline)
+            }
+            Construct::NearestNeighbor => NearestNeighborHeuristic::new().construct_until(instance, deadline),
+            Construct::GreedyInsertion => GreedyInsertionHeuristic::new().construct_until(instance, deadline),
+            Construct::Savings => SavingsHeuristic::new().construct_until(instance, deadline),
+            Construct::Regret { k } => RegretInsertionHeuristic::new(*k).construct_until(instance, deadline),
+            Construct::Custom(custom) => custom.0.construct_until(instance, deadline),
+        }
+    }
+}
+
+/// Improvement stage of a pipeline; metaheuristic parameters left unset
+/// keep their defaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Improve {
+    /// VND, over the standard operators unless its parameters set others
+    Vnd(VNDParams),
+    Ils(ILSParams),
+    Sa(SAParams),
+    Tabu(TabuParams),
+    Vns,
+    /// Any local search (not serialized); the pipeline's VND settings do not
+    /// apply to it
+    #[serde(skip)]
+    Custom(Custom<dyn LocalSearch + Send + Sync>),
+}
+
+impl Improve {
+    /// Stage running `search`
+    pub fn custom(search: Box<dyn LocalSearch + Send + Sync>) -> Self {
+        Improve::Custom(Custom(Arc::from(search)))
+    }
+    
+    pub fn name(&self) -> &str {
+        match self {
+            Improve::Vnd(_) => "vnd",
+            Improve::Ils(_) => "ils",
+            Improve::Sa(_) => "sa",
+            Improve::Tabu(_) => "tabu",
+            Improve::Vns => "vns",
+            Improve::Custom(custom) => custom.0.name(),
+        }
+    }
+
+    fn run(&self, instance: &PDTSPInstance, solution: &mut Solution, rng: &RngFactory, deadline: Deadline, vnd_params: &VNDParams) {
+        match self {
+            Improve::Vnd(params) => {
+                let mut vnd = VND::with_standard_operators();
+                vnd_params.apply(&mut vnd);
+                params.apply(&mut vnd);
+                vnd.improve_until(instance, solution, deadline);
+            }
+            Improve::Ils(params) => {
+                let mut ils = IteratedLocalSearch::new();
+                ils.vnd = vnd_params.clone();
+                params.apply(&mut ils);
+                ils.seed = rng.derive("ils");
+                ils.improve_until(instance, solution, deadline);
+            }
+            Improve::Sa(params) => {
+                let mut sa = SimulatedAnnealing::new();
+                params.apply(&mut sa);
+                sa.seed = rng.derive("sa");
+                sa.improve_until(instance, solution, deadline);
+            }
+            Improve::Tabu(params) => {
+                let mut ts = TabuSearch::new();
+                params.apply(&mut ts);
+                ts.seed = rng.derive("tabu");
+                ts.improve_until(instance, solution, deadline);
+            }
+            Improve::Vns => {
+                let mut vns = GeneralVNS::new();
+                vns.vnd = vnd_params.clone();
+                vns.seed = rng.derive("vns");
+                vns.time_limit = deadline.remaining();
+                vns.improve(instance, solution);
+            }
+            Improve::Custom(custom) => {
+                custom.0.improve_until(instance, solution, deadline);
+            }
+        }
+    }
+}
+
+/// A construction stage followed by improvement stages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pipeline {
+    pub construction: Construct,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub improvements: Vec<Improve>,
+    /// Share of the time limit per stage, construction first (normalized by
+    /// their sum); equal shares when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budget: Vec<f64>,
+    /// VND settings of every stage that runs a descent (VND, ILS and VNS);
+    /// the parameters of a VND stage apply on top of them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vnd: Option<VNDParams>,
+}
+
+impl Pipeline {
+    /// Multi-start construction alone
+    pub fn new() -> Self {
+        Pipeline { construction: Construct::MultiStart, improvements: Vec::new(), budget: Vec::new(), vnd: None }
+    }
+
+    /// The hybrid solver: multi-start construction, VND and ILS
+    pub fn hybrid(budget: TimeBudget) -> Self {
+        let ils = ILSParams {
+            perturbation_strength: Some(4),
+            max_perturbation_strength: Some(8),
+            max_iterations: Some(50),
+            max_no_improve: Some(15),
+            ..Default::default()
+        };
+        Pipeline::new()
+            .improve(Improve::Vnd(Default::default()))
+            .improve(Improve::Ils(ils))
+            .with_budget(vec![budget.construction, budget.vnd, budget.ils])
+    }
+
+    pub fn construct(mut self, construction: Construct) -> Self {
+        self.construction = construction;
+        self
+    }
+
+    /// Append an improvement stage
+    pub fn improve(mut self, improvement: Improve) -> Self {
+        self.improvements.push(improvement);
+        self
+    }
+
+    pub fn with_budget(mut self, budget: Vec<f64>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub fn with_vnd(mut self, vnd: VNDParams) -> Self {
+        self.vnd = Some(vnd);
+        self
+    }
+
+    /// Check the stage parameters and the budget
+    pub fn validate(&self) -> Result<(), String> {
+        match self.construction {
+            Construct::Grasp { restarts: 0 } => return Err("GRASP needs at least one restart".to_string()),
+            Construct::Regret { k } if k < 2 => return Err(format!("Regret insertion needs k >= 2, got {}", k)),
+            _ => {}
+        }
+        if self.budget.is_empty() {
+            return Ok(());
+        }
+        let stages = self.improvements.len() + 1;
+        if self.budget.len() != stages {
+            return Err(format!("Pipeline has {} stages but {} budget fractions", stages, self.budget.len()));
+        }
+        if self.budget.iter().any(|f| !f.is_finite() || *f < 0.0) || self.budget.iter().sum::<f64>() <= 0.0 {
+            return Err("Budget fractions must be non-negative with a positive sum".to_string());
+        }
+        Ok(())
+    }
+
+    /// Cumulative end of each stage, as a fraction of the time limit
+    pub(crate) fn stage_ends(&self) -> Vec<f64> {
+        let stages = self.improvements.len() + 1;
+        let shares = if self.budget.len() == stages { self.budget.clone() } else { vec![1.0; stages] };
+        let total: f64 = shares.iter().sum();
+        let mut elapsed = 0.0;
+        let mut ends: Vec<f64> = shares.iter().map(|s| {
+            elapsed += s;
+            elapsed / total
+        }).collect();
+        ends[stages - 1] = 1.0;
+        ends
+    }
+
+    /// Run every stage within `time_limit` seconds and report the time spent
+    /// in each. The deadlines are cumulative, so time left over by a stage
+    /// goes to the following ones.
+    pub fn solve(&self, instance: &PDTSPInstance, time_limit: f64, seed: u64) -> (Solution, StageTimes) {
+        let start = Instant::now();
+        let ends = self.stage_ends();
+        let deadline = |k: usize| Deadline::after_from(start, ends[k] * time_limit);
+        let mut times = StageTimes::default();
+        let rng = RngFactory::new(seed);
+        let vnd = self.vnd.clone().unwrap_or_default();
+
+        let stage_start = Instant::now();
+        let mut solution = self.construction.run(instance, &rng, deadline(0));
+        times.stages.push(("construction".to_string(), stage_start.elapsed().as_secs_f64()));
+
+        for (k, improvement) in self.improvements.iter().enumerate() {
+            let stage_start = Instant::now();
+            improvement.run(instance, &mut solution, &rng, deadline(k + 1), &vnd);
+            times.stages.push((improvement.name().to_string(), stage_start.elapsed().as_secs_f64()));
+        }
+
+        solution.algorithm = "Pipeline".to_string();
+        solution.computation_time = start.elapsed().as_secs_f64();
+        (solution, times)
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Solver for Pipeline {
+    fn solve(&self, instance: &PDTSPInstance, time_limit: f64, seed: u64) -> Solution {
+        Pipeline::solve(self, instance, time_limit, seed).0
+    }
+    
+    fn name(&self) -> &str {
+        "Pipeline"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_config_and_solve() {
+        let pipeline: Pipeline = toml::from_str(
+            "construction = { type = \"regret\", k = 2 }\nbudget = [1.0, 1.0, 2.0]\n\n[[improvements]]\ntype = \"vnd\"\n\n[[improvements]]\ntype = \"ils\"\nmax_iterations = 5\n",
+        ).unwrap();
+        assert_eq!(pipeline.construction, Construct::Regret { k: 2 });
+        assert_eq!(pipeline.improvements[1], Improve::Ils(ILSParams { max_iterations: Some(5), ..Default::default() }));
+        assert_eq!(pipeline.stage_ends(), vec![0.25, 0.5, 1.0]);
+        let text = toml::to_string(&pipeline).unwrap();
+        assert_eq!(toml::from_str::<Pipeline>(&text).unwrap(), pipeline);
+
+        assert!(pipeline.clone().improve(Improve::Vns).validate().is_err());
+        assert!(Pipeline::new().construct(Construct::Regret { k: 1 }).validate().is_err());
+        assert!(toml::from_str::<Pipeline>("construction = { type = \"multi-start\" }\n\n[[improvements]]\ntype = \"ils\"\nstrength = 3\n").is_err());
+
+        let text = "NAME: p\nDIMENSION: 5\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 1 1\n4 0 1\n5 2 2\nDEMAND_SECTION\n1 0\n2 2\n3 -2\n4 1\n5 -1\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let (solution, times) = pipeline.solve(&instance, 1.0, 7);
+        assert!(solution.feasible);
+        let stages: Vec<&str> = times.stages.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(stages, ["construction", "vnd", "ils"]);
+    }
+
+    #[test]
+    fn test_custom_stages_and_deadlines() {
+        use std::sync::Mutex;
+
+        /// Records the time left to each call
+        struct Probe(Mutex<Vec<f64>>);
+
+        impl LocalSearch for Probe {
+            fn improve(&self, _: &PDTSPInstance, _: &mut Solution) -> bool {
+                false
+            }
+
+            fn improve_until(&self, _: &PDTSPInstance, _: &mut Solution, deadline: Deadline) -> bool {
+                self.0.lock().unwrap().push(deadline.remaining());
+                false
+            }
+
+            fn name(&self) -> &str {
+                "probe"
+            }
+        }
+
+        let text = "NAME: p\nDIMENSION: 5\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 1 1\n4 0 1\n5 2 2\nDEMAND_SECTION\n1 0\n2 2\n3 -2\n4 1\n5 -1\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let probe = Arc::new(Probe(Mutex::new(Vec::new())));
+        let pipeline = Pipeline::new()
+            .construct(Construct::custom(Box::new(NearestNeighborHeuristic::new())))
+            .improve(Improve::Custom(Custom(probe.clone())))
+            .improve(Improve::Vnd(Default::default()));
+        assert!(pipeline.validate().is_ok());
+        assert_eq!(pipeline.clone(), pipeline);
+        assert!(toml::to_string(&pipeline).is_err());
+
+        let (solution, times) = pipeline.solve(&instance, 3.0, 7);
+        assert!(solution.feasible);
+        let stages: Vec<&str> = times.stages.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(stages, ["construction", "probe", "vnd"]);
+        // The probe's stage ends two thirds into the time limit
+        let left = probe.0.lock().unwrap()[0];
+        assert!(left > 1.0 && left <= 2.0, "{}", left);
+
+        // GRASP stops restarting at its stage deadline
+        let grasp = Pipeline::new().construct(Construct::Grasp { restarts: usize::MAX });
+        let solver: &dyn Solver = &grasp;
+        let start = Instant::now();
+        assert!(solver.solve(&instance, 0.05, 7).feasible);
+        assert!(start.elapsed().as_secs_f64() < 5.0);
+        let hybrid: Box<dyn Solver> = Box::new(crate::heuristics::hybrid::HybridSolver::new());
+        assert_eq!(hybrid.solve(&instance, 0.2, 7).algorithm, "Hybrid");
+    }
+}
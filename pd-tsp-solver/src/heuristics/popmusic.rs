@@ -0,0 +1,315 @@
+//! POPMUSIC decomposition for large instances.
+//!
+//! Partial OPtimization Metaheuristic Under Special Intensification
+//! Conditions (Taillard & Voß): the parts of the solution are the nodes of
+//! the tour, and a subproblem gathers a seed node and its `r` nearest
+//! neighbors along the tour, i.e. a sub-path of `r` consecutive positions,
+//! around the seed. The sub-path is
+//! reoptimized as a small instance by any local search (VND by default, or
+//! ILS) and put back when that shortens the tour. Seeds whose subproblem
+//! fails to improve are dropped; an improvement brings back the seeds of its
+//! sub-path. The search ends when no seed is left, after a number of
+//! subproblems linear in the tour size in practice.
+//!
+//! The subproblem instance has the nodes of the sub-path plus a depot that
+//! stands for both ends: arcs leave it as from the node before the sub-path
+//! and enter it as into the node after, and its starting load is the load on
+//! entering the sub-path. Tour costs of the subproblem are those of the
+//! sub-path, load-dependent costs included, so the subproblem prices the
+//! change of the full tour cost, and its load checks cover the only loads
+//! that change. A sub-path is accepted from these alone, in time linear in
+//! its size; the full tour is checked again only under a route length limit,
+//! on a restricted arc graph or under a chance constraint.
+
+use crate::distance::DistanceMatrix;
+use crate::heuristics::local_search::{LocalSearch, VND};
+use crate::heuristics::statistics::OperatorRun;
+use crate::heuristics::stopping::Deadline;
+use crate::instance::{Node, PDTSPInstance};
+use crate::solution::Solution;
+
+/// POPMUSIC over sub-paths of the tour
+pub struct Popmusic {
+    /// Positions per subproblem (the `r` of POPMUSIC)
+    pub subproblem_size: usize,
+    /// Local search reoptimizing each subproblem
+    pub optimizer: Box<dyn LocalSearch + Send + Sync>,
+    pub deadline: Deadline,
+}
+
+impl Popmusic {
+    pub fn new() -> Self {
+        Popmusic {
+            subproblem_size: 30,
+            optimizer: Box::new(VND::with_standard_operators()),
+            deadline: Deadline::none(),
+        }
+    }
+
+    /// Reoptimize the subproblems with another local search
+    pub fn with_optimizer(mut self, optimizer: impl LocalSearch + Send + Sync + 'static) -> Self {
+        self.optimizer = Box::new(optimizer);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Positions `start..start + size` of the sub-path centered on `position`
+    fn sub_path(&self, position: usize, len: usize) -> (usize, usize) {
+        let size = self.subproblem_size.clamp(2, len - 1);
+        let start = position.saturating_sub(size / 2).clamp(1, len - size);
+        (start, size)
+    }
+}
+
+impl Default for Popmusic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Instance of the sub-path `tour[start..end]`: node `i` of the subproblem
+/// is `tour[start + i - 1]` and node 0 joins the nodes around the sub-path.
+/// `entry_loads` are the loads per commodity on entering the sub-path (see
+/// `PDTSPInstance::commodity_load_profile`).
+pub fn sub_path_instance(instance: &PDTSPInstance, tour: &[usize], start: usize, end: usize, entry_loads: Vec<i32>) -> PDTSPInstance {
+    let path = &tour[start..end];
+    let before = tour[start - 1];
+    let after = tour.get(end).copied();
+
+    let mut depot = instance.nodes[before].clone();
+    depot.demand = entry_loads.iter().sum();
+    depot.profit = 0;
+    depot.service_time = 0.0;
+    let mut nodes = vec![Node { id: 0, split_of: None, ..depot }];
+    nodes.extend(path.iter().enumerate().map(|(i, &node)| Node {
+        id: i + 1,
+        split_of: None,
+        ..instance.nodes[node].clone()
+    }));
+
+    let m = nodes.len();
+    let original = |i: usize| if i == 0 { before } else { path[i - 1] };
+    let mut distance_matrix = DistanceMatrix::new(m);
+    for i in 0..m {
+        for j in 0..m {
+            let distance = match (i, j) {
+                _ if i == j => 0.0,
+                // The last node of an open tour does not return
+                (_, 0) => after.map_or(if instance.open_tour { 0.0 } else { instance.distance(original(i), 0) },
+                    |after| instance.distance(original(i), after)),
+                _ => instance.distance(original(i), original(j)),
+            };
+            distance_matrix.set(i, j, distance);
+        }
+    }
+
+    let name = format!("{}-sub{}", instance.name, start);
+    let mut sub = PDTSPInstance {
+        cost_function: instance.cost_function,
+        alpha: instance.alpha,
+        beta: instance.beta,
+        vehicle: instance.vehicle.clone(),
+        ..PDTSPInstance::from_parts(name, instance.capacity, nodes, distance_matrix)
+    };
+    if let Some(commodities) = &instance.commodities {
+        let mut demands = vec![entry_loads];
+        demands.extend(path.iter().map(|&node| commodities.demands[node].clone()));
+        // Same commodities as the instance: cannot fail
+        sub.set_commodities(commodities.capacities.clone(), demands).ok();
+    }
+    sub
+}
+
+impl LocalSearch for Popmusic {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        self.improve_until(instance, solution, Deadline::none())
+    }
+    
+    fn improve_until(&self, instance: &PDTSPInstance, solution: &mut Solution, deadline: Deadline) -> bool {
+        let deadline = self.deadline.earliest(deadline);
+        let n = solution.tour.len();
+        if n < 4 {
+            return false;
+        }
+        let mut run = OperatorRun::start(solution);
+        let start_feasible = instance.is_feasible(&solution.tour);
+        // Route lengths, missing arcs and the chance constraint are not
+        // captured by the subproblem
+        let full_check = instance.has_route_limit() || instance.distance_matrix.is_restricted()
+            || instance.has_chance_constraint();
+        let mut total_improved = false;
+
+        // Seeds still to optimize, as a stack of nodes
+        let mut seeds: Vec<usize> = solution.tour[1..].iter().rev().copied().filter(|&node| node != 0).collect();
+        let mut pending = vec![false; instance.dimension];
+        seeds.iter().for_each(|&node| pending[node] = true);
+        let mut position = vec![usize::MAX; instance.dimension];
+        for (p, &node) in solution.tour.iter().enumerate() {
+            position[node] = p;
+        }
+        // Loads after each position; reordering a sub-path only changes the
+        // loads inside it
+        let mut loads = instance.commodity_load_profile(&solution.tour);
+        let demands = |node: usize| match &instance.commodities {
+            Some(commodities) => commodities.demands[node].clone(),
+            None => vec![instance.nodes[node].demand],
+        };
+
+        while let Some(seed) = seeds.pop() {
+            if deadline.expired() {
+                break;
+            }
+            pending[seed] = false;
+            let (start, size) = self.sub_path(position[seed], n);
+            let end = start + size;
+            // Sub-paths through an intermediate depot visit are left alone
+            if solution.tour[start..end].contains(&0) {
+                continue;
+            }
+            run.evaluated += 1;
+
+            let sub = sub_path_instance(instance, &solution.tour, start, end, loads[start - 1].clone());
+            let identity: Vec<usize> = (0..=size).collect();
+            let sub_cost = sub.tour_cost(&identity);
+            let mut sub_solution = Solution::from_tour(&sub, identity, "popmusic-subproblem");
+            if !self.optimizer.improve_until(&sub, &mut sub_solution, deadline) {
+                continue;
+            }
+            // The optimizer may insert depot visits, which are not part of
+            // the sub-path
+            let sub_tour = &sub_solution.tour;
+            if sub_tour.len() != size + 1 || sub_tour[0] != 0 || sub_tour[1..].contains(&0) {
+                continue;
+            }
+
+            // The subproblem prices the change of the tour cost, and the
+            // loads only change inside the sub-path
+            let delta = sub.tour_cost(sub_tour) - sub_cost;
+            if delta >= -1e-9 {
+                continue;
+            }
+            let path: Vec<usize> = sub_tour[1..].iter().map(|&i| solution.tour[start + i - 1]).collect();
+            let feasible = if !start_feasible {
+                true
+            } else if full_check {
+                let mut tour = solution.tour.clone();
+                tour[start..end].copy_from_slice(&path);
+                instance.is_feasible(&tour)
+            } else {
+                sub.is_feasible(sub_tour)
+            };
+            if feasible {
+                solution.tour[start..end].copy_from_slice(&path);
+                run.applied += 1;
+                total_improved = true;
+                for p in start..end {
+                    let node = solution.tour[p];
+                    position[node] = p;
+                    loads[p] = loads[p - 1].iter().zip(demands(node)).map(|(l, d)| l + d).collect();
+                    if !pending[node] {
+                        pending[node] = true;
+                        seeds.push(node);
+                    }
+                }
+            }
+        }
+
+        solution.validate(instance);
+        run.finish(self.name(), solution);
+        total_improved
+    }
+
+    fn name(&self) -> &str {
+        "POPMUSIC"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::construction::{ConstructionHeuristic, NearestNeighborHeuristic};
+    use crate::heuristics::local_search::IteratedLocalSearch;
+    use crate::instance::CostFunction;
+
+    fn instance(n: usize) -> PDTSPInstance {
+        let mut text = format!("NAME: pm\nDIMENSION: {}\nCAPACITY: 10\nNODE_COORD_SECTION\n", n);
+        for i in 0..n {
+            let (x, y) = ((i * 37 % 101) as f64, (i * 53 % 97) as f64);
+            text += &format!("{} {} {}\n", i + 1, x, y);
+        }
+        text += "DEMAND_SECTION\n1 0\n";
+        for i in 1..n {
+            text += &format!("{} {}\n", i + 1, if i % 2 == 1 { 2 } else { -2 });
+        }
+        PDTSPInstance::from_tsplib_str(&(text + "EOF\n")).unwrap()
+    }
+
+    #[test]
+    fn test_sub_path_instance_matches_tour_changes() {
+        let mut instance = instance(12);
+        let tour: Vec<usize> = (0..12).collect();
+        for cost_function in [CostFunction::Distance, CostFunction::Quadratic, CostFunction::LinearLoad] {
+            instance.cost_function = cost_function;
+            for (start, end) in [(1, 5), (4, 9), (7, 12)] {
+                let entry = instance.commodity_load_profile(&tour)[start - 1].clone();
+                let sub = sub_path_instance(&instance, &tour, start, end, entry);
+                let identity: Vec<usize> = (0..=end - start).collect();
+                assert!(sub.is_feasible(&identity));
+
+                // Changing the sub-path changes both costs alike
+                for (a, b) in [(1, 2), (1, 3)] {
+                    let mut sub_tour = identity.clone();
+                    sub_tour.swap(a, b);
+                    let mut full = tour.clone();
+                    full.swap(start + a - 1, start + b - 1);
+                    let sub_delta = sub.tour_cost(&sub_tour) - sub.tour_cost(&identity);
+                    let delta = instance.tour_cost(&full) - instance.tour_cost(&tour);
+                    assert!((sub_delta - delta).abs() < 1e-6);
+                    assert_eq!(sub.is_feasible(&sub_tour), instance.is_feasible(&full));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_popmusic_improves_tours() {
+        let instance = instance(60);
+        for search in [
+            Popmusic { subproblem_size: 12, ..Popmusic::new() },
+            Popmusic { subproblem_size: 12, ..Popmusic::new() }.with_optimizer(IteratedLocalSearch::with_params(2, 10, 5)),
+        ] {
+            let mut solution = NearestNeighborHeuristic::new().construct(&instance);
+            assert!(solution.feasible);
+            let initial = solution.cost;
+            search.improve(&instance, &mut solution);
+            assert!(solution.feasible);
+            assert!(instance.is_complete_tour(&solution.tour));
+            assert!(solution.cost <= initial + 1e-9);
+            assert!(solution.statistics.operators.iter().any(|op| op.name == "POPMUSIC"));
+        }
+    }
+
+    #[test]
+    fn test_popmusic_accepts_by_subproblem_delta() {
+        // Under load-dependent costs and a route length limit, the sub-paths
+        // accepted from the subproblem alone keep the tour feasible and
+        // cheaper
+        let mut instance = instance(60);
+        for (cost_function, limit) in [(CostFunction::Quadratic, None), (CostFunction::LinearLoad, None), (CostFunction::Distance, Some(1e9))] {
+            instance.cost_function = cost_function;
+            instance.max_route_length = limit;
+            let mut solution = NearestNeighborHeuristic::new().construct(&instance);
+            assert!(solution.feasible);
+            let initial = solution.cost;
+            let improved = Popmusic { subproblem_size: 10, ..Popmusic::new() }.improve(&instance, &mut solution);
+            assert!(solution.feasible);
+            assert!(instance.is_complete_tour(&solution.tour));
+            assert!((solution.cost - instance.tour_cost(&solution.tour)).abs() < 1e-6);
+            assert!(!improved || solution.cost < initial - 1e-9);
+        }
+    }
+}
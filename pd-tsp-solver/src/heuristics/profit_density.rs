@@ -37,7 +37,7 @@ impl Default for ProfitDensityHeuristic {
 
 impl ConstructionHeuristic for ProfitDensityHeuristic {
     fn construct(&self, instance: &PDTSPInstance) -> Solution {
-        let start = std::time::Instant::now();
+        let start = web_time::Instant::now();
 
         let mut tour = vec![0];
         let mut visited: HashSet<usize> = HashSet::new();
@@ -53,8 +53,7 @@ impl ConstructionHeuristic for ProfitDensityHeuristic {
 
             for candidate in 1..instance.dimension {
                 if visited.contains(&candidate) { continue; }
-                let new_load = current_load + instance.nodes[candidate].demand;
-                if new_load < 0 || new_load > instance.capacity { continue; }
+                if !instance.can_append(&tour, current_load, candidate) { continue; }
 
                 let sc = self.score(instance, current, candidate, current_load);
                 if sc < best_score {
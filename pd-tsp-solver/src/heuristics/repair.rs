@@ -0,0 +1,207 @@
+//! Repair of capacity-violating tours.
+//!
+//! Crossover children, perturbed tours or tours given by the user may let
+//! the load leave `[0, capacity]`. `make_feasible` first relocates single
+//! customers while a relocation reduces the total load violation, which
+//! keeps most of the tour; if that gets stuck, it ejects the customers whose
+//! removal reduces the violation most and reinserts them at their cheapest
+//! load-feasible positions. The result is checked with `is_feasible`, so a
+//! returned tour is feasible; otherwise the error says why none was found.
+//! Each relocation or ejection costs O(n²); `make_feasible_within` bounds
+//! their number for callers that repair many tours, such as the GA.
+
+use crate::heuristics::construction::GreedyInsertionHeuristic;
+use crate::instance::analysis::{feasibility_certificate, FeasibilityCertificate};
+use crate::instance::PDTSPInstance;
+use crate::solution::DeltaEvaluator;
+
+/// Maximum number of relocations tried by `make_feasible`
+pub const MAX_REPAIR_MOVES: usize = 10_000;
+
+/// Feasible tour as close as possible to `tour`, which must start at the
+/// depot and visit no customer twice. Missing customers are inserted and
+/// intermediate depot visits of an infeasible tour are dropped.
+pub fn make_feasible(instance: &PDTSPInstance, tour: &[usize]) -> Result<Vec<usize>, String> {
+    make_feasible_within(instance, tour, MAX_REPAIR_MOVES, usize::MAX)
+}
+
+/// `make_feasible` with at most `max_moves` relocations and `max_ejections`
+/// ejected customers; fails when the violation needs more
+pub fn make_feasible_within(
+    instance: &PDTSPInstance,
+    tour: &[usize],
+    max_moves: usize,
+    max_ejections: usize,
+) -> Result<Vec<usize>, String> {
+    if tour.first() != Some(&0) {
+        return Err("Tour does not start at the depot".to_string());
+    }
+    if instance.is_feasible_strict(tour) {
+        return Ok(tour.to_vec());
+    }
+    let mut seen = vec![false; instance.dimension];
+    let mut repaired = vec![0];
+    for &node in &tour[1..] {
+        if node >= instance.dimension {
+            return Err(format!("Node {} out of range 0..{}", node, instance.dimension));
+        }
+        if node != 0 && std::mem::replace(&mut seen[node], true) {
+            return Err(format!("Node {} visited more than once", node));
+        }
+        if node != 0 {
+            repaired.push(node);
+        }
+    }
+    let missing: Vec<usize> = (1..instance.dimension).filter(|&node| !seen[node]).collect();
+
+    relocate_violations(instance, &mut repaired, max_moves);
+    if !missing.is_empty() || instance.load_violation(repaired.iter().copied()) > 0 {
+        let Some(mut pending) = eject_violations(instance, &mut repaired, max_ejections) else {
+            return Err(format!("The load violation needs more than {} ejections", max_ejections));
+        };
+        pending.extend(missing);
+        // The largest demands have the fewest positions left: place first the
+        // largest one that fits somewhere
+        pending.sort_by_key(|&node| std::cmp::Reverse(instance.nodes[node].demand.abs()));
+        let insertion = GreedyInsertionHeuristic::new();
+        while !pending.is_empty() {
+            let fitting = pending.iter().enumerate().find_map(|(k, &node)| {
+                insertion.find_best_insertion(instance, &repaired, node).map(|(pos, _)| (k, pos))
+            });
+            let Some((k, pos)) = fitting else {
+                let reason = format!("No load-feasible position is left for nodes {:?}", pending);
+                return Err(explain(instance, reason));
+            };
+            repaired.insert(pos + 1, pending.remove(k));
+        }
+    }
+
+    if instance.is_feasible(&repaired) {
+        return Ok(repaired);
+    }
+    let broken: Vec<&str> = [
+        (instance.load_violation(repaired.iter().copied()) == 0, "the capacity"),
+        (instance.commodity_loads_feasible(&repaired), "a commodity capacity"),
+        (instance.arcs_exist(&repaired, !instance.open_tour), "the available arcs"),
+        (instance.route_lengths_feasible(&repaired), "the route length limit"),
+    ].into_iter().filter(|(ok, _)| !ok).map(|(_, what)| what).collect();
+    Err(explain(instance, format!("The repaired tour still breaks {}", broken.join(" and "))))
+}
+
+/// Prefix `reason` with the failed necessary condition of the instance, if
+/// any, which is then the actual cause
+fn explain(instance: &PDTSPInstance, reason: String) -> String {
+    match feasibility_certificate(instance) {
+        FeasibilityCertificate::Infeasible { reason: cause } => format!("Instance is infeasible: {}", cause),
+        _ => reason,
+    }
+}
+
+/// Relocate nodes while some relocation reduces the load violation (ties
+/// broken by cost), for tours without intermediate depot visits; at most
+/// `max_moves` relocations
+pub fn relocate_violations(instance: &PDTSPInstance, tour: &mut Vec<usize>, max_moves: usize) {
+    let violation = |load: i32| (load - instance.capacity).max(0) as i64 + (-load).max(0) as i64;
+    let n = tour.len();
+
+    for _ in 0..max_moves {
+        if instance.load_violation(tour.iter().copied()) == 0 {
+            break;
+        }
+        let mut loads = Vec::with_capacity(n);
+        let mut load = instance.starting_load();
+        loads.push(load);
+        for &node in &tour[1..] {
+            load += instance.nodes[node].demand;
+            loads.push(load);
+        }
+
+        // Moving the node at `from` before position `to` shifts the loads
+        // in between by its demand and gives it the load that precedes `to`
+        let evaluator = DeltaEvaluator::new(instance, tour);
+        let mut best: Option<(i64, f64, usize, usize)> = None;
+        let mut consider = |change: i64, from: usize, to: usize| {
+            if best.is_none_or(|(c, _, _, _)| change <= c) {
+                let delta = evaluator.relocation_delta(from, to);
+                if best.is_none_or(|(c, d, _, _)| change < c || delta < d) {
+                    best = Some((change, delta, from, to));
+                }
+            }
+        };
+        for from in 1..n {
+            let demand = instance.nodes[tour[from]].demand;
+            let mut shifted = -violation(loads[from]);
+            for to in from + 2..=n {
+                shifted += violation(loads[to - 1] - demand) - violation(loads[to - 1]);
+                let change = shifted + violation(loads[to - 1]);
+                consider(change, from, to);
+            }
+            let mut shifted = -violation(loads[from]);
+            for to in (1..from).rev() {
+                shifted += violation(loads[to] + demand) - violation(loads[to]);
+                let change = shifted + violation(loads[to - 1] + demand);
+                consider(change, from, to);
+            }
+        }
+
+        match best {
+            Some((change, _, from, to)) if change < 0 => {
+                let node = tour.remove(from);
+                tour.insert(if to > from { to - 1 } else { to }, node);
+            }
+            _ => break,
+        }
+    }
+}
+
+/// Remove, one at a time, the node whose removal most reduces the load
+/// violation until there is none left; returns the removed nodes, or None
+/// if more than `max_ejections` are needed
+fn eject_violations(instance: &PDTSPInstance, tour: &mut Vec<usize>, max_ejections: usize) -> Option<Vec<usize>> {
+    let mut ejected = Vec::new();
+    loop {
+        let current = instance.load_violation(tour.iter().copied());
+        if current == 0 || tour.len() < 2 {
+            return Some(ejected);
+        }
+        if ejected.len() >= max_ejections {
+            return None;
+        }
+        let without = |k: usize| tour.iter().enumerate().filter(move |&(i, _)| i != k).map(|(_, &node)| node);
+        let (k, _) = (1..tour.len())
+            .map(|k| (k, instance.load_violation(without(k))))
+            .min_by_key(|&(_, violation)| violation)
+            .unwrap();
+        ejected.push(tour.remove(k));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_feasible() {
+        let text = "NAME: r\nDIMENSION: 7\nCAPACITY: 6\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 2 0\n4 3 0\n5 3 1\n6 2 1\n7 1 1\nDEMAND_SECTION\n1 0\n2 -4\n3 -3\n4 4\n5 3\n6 2\n7 -2\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        // Deliveries before any pickup, then too much load
+        let tour = vec![0, 1, 2, 3, 4, 5, 6];
+        assert!(!instance.is_feasible(&tour));
+        let repaired = make_feasible(&instance, &tour).unwrap();
+        assert!(instance.is_feasible(&repaired));
+        let mut nodes = repaired.clone();
+        nodes.sort();
+        assert_eq!(nodes, (0..7).collect::<Vec<_>>());
+
+        // Missing customers are inserted, feasible tours are kept
+        assert!(instance.is_feasible(&make_feasible(&instance, &[0, 2, 1]).unwrap()));
+        assert_eq!(make_feasible(&instance, &repaired).unwrap(), repaired);
+        assert!(make_feasible(&instance, &[0, 1, 1]).is_err());
+        assert!(make_feasible_within(&instance, &tour, 0, 0).is_err());
+
+        let mut overloaded = instance.clone();
+        overloaded.nodes[3].demand = 9;
+        let error = make_feasible(&overloaded, &tour).unwrap_err();
+        assert!(error.contains("beyond the capacity"), "{}", error);
+    }
+}
@@ -0,0 +1,123 @@
+//! Deterministic random streams derived from a single run seed.
+//!
+//! Every stochastic component (randomized constructions, SA, tabu, ILS,
+//! GA, ...) takes its own seed from an `RngFactory` created once from the
+//! `--seed` of the run. Each component gets an independent sub-stream named
+//! after it, so that the same seed always reproduces the same run, and
+//! adding a component does not shift the streams of the others.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Source of per-component seeds for one run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RngFactory {
+    seed: u64,
+}
+
+impl RngFactory {
+    pub fn new(seed: u64) -> Self {
+        RngFactory { seed }
+    }
+
+    /// Run seed the factory was created from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Seed of the sub-stream named `component`
+    pub fn derive(&self, component: &str) -> u64 {
+        // FNV-1a of the name, mixed with the run seed by the splitmix64 finalizer
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in component.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        splitmix64(self.seed ^ hash)
+    }
+
+    /// Seed of the `index`-th sub-stream named `component` (restarts, workers, ...)
+    pub fn derive_indexed(&self, component: &str, index: u64) -> u64 {
+        splitmix64(self.derive(component).wrapping_add(index))
+    }
+
+    /// Generator of the sub-stream named `component`
+    pub fn rng(&self, component: &str) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.derive(component))
+    }
+
+    /// Factory for a composite component, whose parts derive their own streams
+    pub fn child(&self, component: &str) -> RngFactory {
+        RngFactory::new(self.derive(component))
+    }
+}
+
+impl Default for RngFactory {
+    fn default() -> Self {
+        Self::new(42)
+    }
+}
+
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::construction::{ConstructionHeuristic, MultiStartConstruction};
+    use crate::heuristics::local_search::{LocalSearch, SimulatedAnnealing, TabuSearch};
+    use crate::instance::PDTSPInstance;
+    use rand::Rng;
+
+    fn random_instance(n: usize, seed: u64) -> PDTSPInstance {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut coords = String::new();
+        let mut demands = String::new();
+        for i in 1..=n {
+            coords += &format!("{} {} {}\n", i, rng.gen_range(0..100), rng.gen_range(0..100));
+            let demand = if i == 1 { 0 } else if i % 2 == 0 { 3 } else { -3 };
+            demands += &format!("{} {}\n", i, demand);
+        }
+        let text = format!(
+            "NAME: rng\nDIMENSION: {}\nCAPACITY: 10\nNODE_COORD_SECTION\n{}DEMAND_SECTION\n{}EOF\n",
+            n, coords, demands
+        );
+        PDTSPInstance::from_tsplib_str(&text).unwrap()
+    }
+
+    #[test]
+    fn test_streams_are_deterministic_and_distinct() {
+        let factory = RngFactory::new(7);
+        assert_eq!(factory.derive("sa"), RngFactory::new(7).derive("sa"));
+        assert_ne!(factory.derive("sa"), factory.derive("tabu"));
+        assert_ne!(factory.derive("sa"), RngFactory::new(8).derive("sa"));
+        assert_ne!(factory.derive_indexed("grasp", 0), factory.derive_indexed("grasp", 1));
+        assert_ne!(factory.child("hybrid").derive("ils"), factory.derive("ils"));
+
+        let a: Vec<u32> = factory.rng("ga").sample_iter(rand::distributions::Standard).take(8).collect();
+        let b: Vec<u32> = factory.rng("ga").sample_iter(rand::distributions::Standard).take(8).collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_run() {
+        let instance = random_instance(31, 3);
+        let run = |seed: u64| {
+            let factory = RngFactory::new(seed);
+            let mut sol = MultiStartConstruction::with_all_heuristics_seeded(&factory).construct(&instance);
+            let mut sa = SimulatedAnnealing::new();
+            sa.seed = factory.derive("sa");
+            sa.improve(&instance, &mut sol);
+            let mut ts = TabuSearch::new();
+            ts.seed = factory.derive("tabu");
+            ts.max_iterations = 50;
+            ts.improve(&instance, &mut sol);
+            sol.tour
+        };
+        assert_eq!(run(5), run(5));
+    }
+}
@@ -0,0 +1,135 @@
+//! Operator-level statistics of the improvement methods.
+//!
+//! Every local search operator counts the moves it evaluates and applies,
+//! the cost it removes and the time it spends, and adds them to the
+//! `SearchStatistics` of the solution it improves. VND and ILS therefore
+//! report each of their operators; SA and Tabu report their move types
+//! (`SA-Swap`, `Tabu-2-Opt`, ...). The statistics are kept in the solution
+//! and in its run record, so that a tuning session can see which
+//! neighborhood does the work.
+
+use crate::solution::Solution;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Counters of one operator
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OperatorStatistics {
+    /// Operator name
+    pub name: String,
+    /// Number of moves evaluated
+    pub evaluated: u64,
+    /// Number of moves applied
+    pub applied: u64,
+    /// Total cost decrease of the applied moves (negative when they worsened the tour)
+    pub gain: f64,
+    /// Time spent in the operator, in seconds
+    pub time: f64,
+}
+
+/// Counters of the operators used on a solution, in order of first use
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchStatistics {
+    pub operators: Vec<OperatorStatistics>,
+}
+
+impl SearchStatistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the counters of a call of operator `name`
+    pub fn record(&mut self, name: &str, evaluated: u64, applied: u64, gain: f64, time: f64) {
+        let index = match self.operators.iter().position(|op| op.name == name) {
+            Some(index) => index,
+            None => {
+                self.operators.push(OperatorStatistics { name: name.to_string(), ..Default::default() });
+                self.operators.len() - 1
+            }
+        };
+        let op = &mut self.operators[index];
+        op.evaluated += evaluated;
+        op.applied += applied;
+        op.gain += gain;
+        op.time += time;
+    }
+
+    /// Add all the counters of `other`
+    pub fn merge(&mut self, other: &SearchStatistics) {
+        for op in &other.operators {
+            self.record(&op.name, op.evaluated, op.applied, op.gain, op.time);
+        }
+    }
+
+    pub fn operator(&self, name: &str) -> Option<&OperatorStatistics> {
+        self.operators.iter().find(|op| op.name == name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operators.is_empty()
+    }
+}
+
+impl fmt::Display for SearchStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<20} {:>12} {:>10} {:>14} {:>10}", "Operator", "Evaluated", "Applied", "Gain", "Time (s)")?;
+        for op in &self.operators {
+            writeln!(f, "{:<20} {:>12} {:>10} {:>14.2} {:>10.3}", op.name, op.evaluated, op.applied, op.gain, op.time)?;
+        }
+        Ok(())
+    }
+}
+
+/// Counters of one call of an operator, added to the solution by `finish`
+#[derive(Debug, Clone, Copy)]
+pub struct OperatorRun {
+    start: web_time::Instant,
+    initial_cost: f64,
+    pub evaluated: u64,
+    pub applied: u64,
+}
+
+impl OperatorRun {
+    /// Start counting a call on `solution`
+    pub fn start(solution: &Solution) -> Self {
+        OperatorRun {
+            start: web_time::Instant::now(),
+            initial_cost: solution.cost,
+            evaluated: 0,
+            applied: 0,
+        }
+    }
+
+    /// Record the call under `name`; the gain is the cost decrease of `solution`
+    pub fn finish(self, name: &str, solution: &mut Solution) {
+        let gain = if self.initial_cost.is_finite() && solution.cost.is_finite() {
+            self.initial_cost - solution.cost
+        } else {
+            0.0
+        };
+        let time = self.start.elapsed().as_secs_f64();
+        solution.statistics.record(name, self.evaluated, self.applied, gain, time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_merge() {
+        let mut stats = SearchStatistics::new();
+        stats.record("2-Opt-FI", 100, 3, 12.5, 0.01);
+        stats.record("Swap", 50, 0, 0.0, 0.02);
+        stats.record("2-Opt-FI", 20, 1, 2.5, 0.01);
+
+        let mut total = SearchStatistics::new();
+        total.record("Swap", 10, 1, 1.0, 0.0);
+        total.merge(&stats);
+        let two_opt = total.operator("2-Opt-FI").unwrap();
+        assert_eq!((two_opt.evaluated, two_opt.applied, two_opt.gain), (120, 4, 15.0));
+        assert_eq!(total.operator("Swap").unwrap().evaluated, 60);
+        assert_eq!(total.operators[0].name, "Swap");
+        assert!(total.to_string().contains("2-Opt-FI"));
+    }
+}
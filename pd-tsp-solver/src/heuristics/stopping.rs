@@ -0,0 +1,56 @@
+//! Stop criteria shared by the stages of a solver.
+//!
+//! A `Deadline` is a wall-clock instant after which a search must stop at its
+//! next checkpoint. Stages of a composite solver (e.g. Hybrid) receive
+//! successive deadlines carved out of a global time limit.
+
+use std::time::Duration;
+use web_time::Instant;
+
+/// Wall-clock stop criterion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Deadline {
+    at: Option<Instant>,
+}
+
+impl Deadline {
+    /// No deadline: the search stops on its own criteria only
+    pub fn none() -> Self {
+        Deadline { at: None }
+    }
+    
+    /// Deadline `seconds` from now (none if `seconds` is not finite)
+    pub fn after(seconds: f64) -> Self {
+        Self::after_from(Instant::now(), seconds)
+    }
+    
+    /// Deadline `seconds` after `start` (none if `seconds` is not finite)
+    pub fn after_from(start: Instant, seconds: f64) -> Self {
+        if !seconds.is_finite() {
+            return Self::none();
+        }
+        Deadline { at: Some(start + Duration::from_secs_f64(seconds.max(0.0))) }
+    }
+    
+    /// Whether the deadline has passed
+    #[inline]
+    pub fn expired(&self) -> bool {
+        self.at.is_some_and(|at| Instant::now() >= at)
+    }
+    
+    /// The earlier of two deadlines
+    pub fn earliest(self, other: Deadline) -> Self {
+        match (self.at, other.at) {
+            (Some(a), Some(b)) => Deadline { at: Some(a.min(b)) },
+            (a, b) => Deadline { at: a.or(b) },
+        }
+    }
+    
+    /// Seconds left before the deadline (infinite without deadline)
+    pub fn remaining(&self) -> f64 {
+        match self.at {
+            None => f64::INFINITY,
+            Some(at) => at.saturating_duration_since(Instant::now()).as_secs_f64(),
+        }
+    }
+}
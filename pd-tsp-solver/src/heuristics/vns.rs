@@ -0,0 +1,225 @@
+//! General Variable Neighborhood Search (GVNS) for PD-TSP.
+//!
+//! Unlike VND, which stops at the first joint local optimum of its operators,
+//! GVNS shakes the current solution in neighborhoods of increasing size
+//! (k random relocations / double-bridge moves) and descends again with VND.
+//! The neighborhood size goes back to 1 after every improvement, and the
+//! search restarts from a strongly shaken best solution after a number of
+//! unsuccessful cycles.
+
+use crate::config::VNDParams;
+use crate::heuristics::local_search::{LocalSearch, VND};
+use crate::heuristics::perturbation::double_bridge;
+use crate::instance::PDTSPInstance;
+use crate::progress::{notify, ProgressEvent, ProgressHandler};
+use crate::solution::Solution;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use web_time::Instant;
+
+/// General Variable Neighborhood Search
+pub struct GeneralVNS {
+    /// Largest shaking neighborhood (number of random moves)
+    pub k_max: usize,
+    /// Maximum number of shake + descent iterations
+    pub max_iterations: usize,
+    /// Number of full k cycles without improvement before a restart
+    pub restart_after: usize,
+    /// Time limit in seconds
+    pub time_limit: f64,
+    /// Random seed
+    pub seed: u64,
+    /// Settings of the descent after every shake
+    pub vnd: VNDParams,
+    /// Progress callback, invoked after each iteration
+    pub on_progress: Option<ProgressHandler>,
+}
+
+impl GeneralVNS {
+    pub fn new() -> Self {
+        GeneralVNS {
+            k_max: 5,
+            max_iterations: 500,
+            restart_after: 5,
+            time_limit: 60.0,
+            seed: 42,
+            vnd: VNDParams::default(),
+            on_progress: None,
+        }
+    }
+    
+    pub fn with_params(k_max: usize, max_iterations: usize, time_limit: f64) -> Self {
+        GeneralVNS {
+            k_max,
+            max_iterations,
+            time_limit,
+            ..Self::new()
+        }
+    }
+    
+    /// Receive a progress event after each iteration
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+        self.on_progress = Some(ProgressHandler::new(callback));
+    }
+    
+    /// Apply `k` random feasible moves (relocations or double bridges)
+    fn shake(&self, instance: &PDTSPInstance, tour: &[usize], k: usize, rng: &mut ChaCha8Rng) -> Vec<usize> {
+        let mut shaken = tour.to_vec();
+        let n = shaken.len();
+        
+        for _ in 0..k {
+            // A few attempts to find a move that keeps the tour feasible
+            for _ in 0..10 {
+                let candidate = if n >= 8 && rng.gen_bool(0.5) {
+                    double_bridge(&shaken, rng)
+                } else {
+                    random_relocation(&shaken, rng)
+                };
+                if instance.is_feasible(&candidate) || !instance.is_feasible(&shaken) {
+                    shaken = candidate;
+                    break;
+                }
+            }
+        }
+        
+        shaken
+    }
+}
+
+impl Default for GeneralVNS {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `candidate` is better than `current` (feasibility first, then cost)
+fn is_better(candidate: &Solution, current: &Solution) -> bool {
+    match (candidate.feasible, current.feasible) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => candidate.cost < current.cost - 1e-9,
+    }
+}
+
+/// Move a random customer to a random position
+fn random_relocation(tour: &[usize], rng: &mut ChaCha8Rng) -> Vec<usize> {
+    let mut result = tour.to_vec();
+    let from = rng.gen_range(1..result.len());
+    if result[from] == 0 {
+        return result;
+    }
+    let node = result.remove(from);
+    let to = rng.gen_range(1..=result.len());
+    result.insert(to, node);
+    result
+}
+
+impl LocalSearch for GeneralVNS {
+    fn improve(&self, instance: &PDTSPInstance, solution: &mut Solution) -> bool {
+        if solution.tour.len() < 4 { return false; }
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let mut vnd = VND::with_standard_operators();
+        self.vnd.apply(&mut vnd);
+        let start = Instant::now();
+        let initial = solution.clone();
+        
+        let mut current = solution.clone();
+        vnd.improve(instance, &mut current);
+        let mut best = current.clone();
+        
+        let mut convergence = vec![(0.0, best.cost)];
+        let mut iteration = 0;
+        let mut failed_cycles = 0;
+        
+        'search: while iteration < self.max_iterations {
+            let mut k = 1;
+            let mut cycle_improved = false;
+            
+            while k <= self.k_max {
+                if iteration >= self.max_iterations || start.elapsed().as_secs_f64() >= self.time_limit {
+                    break 'search;
+                }
+                
+                let shaken = self.shake(instance, &current.tour, k, &mut rng);
+                let mut candidate = Solution::from_tour(instance, shaken, "VNS-temp");
+                vnd.improve(instance, &mut candidate);
+                
+                if is_better(&candidate, &current) {
+                    current = candidate;
+                    k = 1;
+                    cycle_improved = true;
+                    if is_better(&current, &best) {
+                        best = current.clone();
+                        convergence.push((start.elapsed().as_secs_f64(), best.cost));
+                    }
+                } else {
+                    k += 1;
+                }
+                
+                iteration += 1;
+                notify(&self.on_progress, || {
+                    ProgressEvent::new(self.name(), iteration, best.cost, start.elapsed().as_secs_f64())
+                        .with_message(format!("k={}", k))
+                });
+            }
+            
+            if cycle_improved {
+                failed_cycles = 0;
+            } else {
+                failed_cycles += 1;
+            }
+            
+            // Restart from the best solution, shaken beyond the largest neighborhood
+            if failed_cycles >= self.restart_after {
+                let restarted = self.shake(instance, &best.tour, 2 * self.k_max, &mut rng);
+                current = Solution::from_tour(instance, restarted, "VNS-temp");
+                vnd.improve(instance, &mut current);
+                failed_cycles = 0;
+            }
+        }
+        
+        let improved = is_better(&best, &initial);
+        if improved {
+            solution.tour = best.tour;
+        }
+        solution.iterations = Some(iteration);
+        solution.convergence = convergence;
+        solution.validate(instance);
+        
+        improved
+    }
+    
+    fn name(&self) -> &str {
+        "GVNS"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::{DistanceMatrix, DistanceMode, Node};
+
+    #[test]
+    fn test_gvns_improves_on_vnd() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let demands = [0, 2, -1, 3, -2, 1, -3, 2, -2, 1, -1, 2, -2];
+        let nodes: Vec<Node> = demands.iter().enumerate()
+            .map(|(i, &d)| Node::new(i, rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), d, 0))
+            .collect();
+        let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        let instance = PDTSPInstance::from_parts("vns", 4, nodes, distance_matrix);
+        
+        let tour: Vec<usize> = (0..demands.len()).collect();
+        assert!(instance.is_feasible(&tour));
+        
+        let mut vnd_solution = Solution::from_tour(&instance, tour.clone(), "test");
+        VND::with_standard_operators().improve(&instance, &mut vnd_solution);
+        
+        let mut solution = Solution::from_tour(&instance, tour, "test");
+        GeneralVNS::with_params(4, 200, 10.0).improve(&instance, &mut solution);
+        
+        assert!(solution.feasible);
+        assert!(solution.is_complete(&instance));
+        assert!(solution.cost <= vnd_solution.cost + 1e-9);
+    }
+}
@@ -3,11 +3,17 @@
 //! This module handles the TSP-LIB format files used for the Pickup and Delivery TSP.
 //! It supports Euclidean 2D distances and manages node coordinates, demands, and capacity constraints.
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::path::Path;
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
+pub use crate::distance::{DistanceMatrix, DistanceMode, DistanceProvider, MetricReport, Rounding};
+use crate::distance::kdtree::KdTree;
+use crate::stochastic::ChanceConstraint;
+use crate::vehicle::VehicleProfile;
+
+pub mod analysis;
+
 /// Represents a node in the PD-TSP instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Node {
@@ -22,11 +28,17 @@ pub struct Node {
     pub demand: i32,
     /// Profit/value associated with this node (optional)
     pub profit: i32,
+    /// Time spent serving the node (at the depot: unloading on return)
+    #[serde(default)]
+    pub service_time: f64,
+    /// Customer this node is an extra visit of, after `split_demands`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split_of: Option<usize>,
 }
 
 impl Node {
     pub fn new(id: usize, x: f64, y: f64, demand: i32, profit: i32) -> Self {
-        Node { id, x, y, demand, profit }
+        Node { id, x, y, demand, profit, service_time: 0.0, split_of: None }
     }
     
     /// Check if this node is a pickup node (positive demand = load items)
@@ -60,7 +72,10 @@ pub struct PDTSPInstance {
     pub nodes: Vec<Node>,
     /// Precomputed distance matrix
     #[serde(skip)]
-    pub distance_matrix: Vec<Vec<f64>>,
+    pub distance_matrix: DistanceMatrix,
+    /// How distances and nearest-neighbor queries are served
+    #[serde(skip)]
+    pub backend: InstanceBackend,
     /// Demand at return depot (node n+1 in original file, applied when returning to depot)
     pub return_depot_demand: i32,
     /// Selected cost function for travel cost evaluation
@@ -69,6 +84,127 @@ pub struct PDTSPInstance {
     pub alpha: f64,
     /// Beta parameter for linear-load cost
     pub beta: f64,
+    /// Multi-commodity demands and capacities. When `None` (the default) the
+    /// instance is single-commodity and only the scalar `demand`/`capacity` are used.
+    #[serde(default)]
+    pub commodities: Option<Commodities>,
+    /// Vehicle profile applied on top of the cost function (`None` = plain travel cost)
+    #[serde(default)]
+    pub vehicle: Option<VehicleProfile>,
+    /// Maximum length of every route, from the depot to the next depot
+    /// visit or the return (`None` = unlimited)
+    #[serde(default)]
+    pub max_route_length: Option<f64>,
+    /// Open tour: the vehicle ends at the last node instead of returning to the depot
+    #[serde(default)]
+    pub open_tour: bool,
+    /// Position of the depot in the instance file (0-based). The depot is
+    /// always node 0 internally: it swaps places with the first file node.
+    #[serde(default)]
+    pub depot_index: usize,
+    /// Stochastic demands: a tour is feasible only if its capacity violation
+    /// probability over sampled scenarios stays within the constraint's bound
+    /// (`None` = deterministic demands). Not serialized; set it after any
+    /// change of the nodes, such as `split_demands`.
+    #[serde(skip)]
+    pub chance_constraint: Option<Arc<ChanceConstraint>>,
+}
+
+/// Per-commodity data for the multi-commodity extension.
+///
+/// Node `demand` fields hold the aggregate over all commodities, so the scalar
+/// load (and every load-dependent cost surcharge) is the total carried load;
+/// each commodity must additionally stay within `[0, capacities[k]]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Commodities {
+    /// Vehicle capacity for each commodity
+    pub capacities: Vec<i32>,
+    /// Demand vector of each node, indexed by node then commodity
+    pub demands: Vec<Vec<i32>>,
+}
+
+impl Commodities {
+    /// Number of commodities
+    pub fn len(&self) -> usize {
+        self.capacities.len()
+    }
+    
+    /// Whether there are no commodities
+    pub fn is_empty(&self) -> bool {
+        self.capacities.is_empty()
+    }
+}
+
+/// Instances with more nodes than this are loaded with the
+/// `OnDemandEuclidean` backend (a dense matrix would need n² × 8 bytes)
+pub const LARGE_INSTANCE_THRESHOLD: usize = 5000;
+
+/// Conventions of an instance file for the depot and its demands
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstanceDialect {
+    /// Detect the convention: Li & Lim when the file starts with the
+    /// `vehicles capacity speed` line, otherwise TSP-LIB with a return depot
+    /// when the last node has the coordinates of the first one
+    #[default]
+    Auto,
+    /// Mosheiov / Hernández-Pérez files: `DIMENSION` is n+1 and the last node
+    /// is the return depot, whose demand is the return adjustment
+    Mosheiov,
+    /// TSP-LIB CVRP-like files: node 1 is the depot (`DEPOT_SECTION`, if
+    /// any, must name it), every node is distinct and a missing depot
+    /// demand is zero
+    Tsplib,
+    /// Li & Lim PDPTW text files: a `vehicles capacity speed` line, then one
+    /// `id x y demand earliest latest service pickup delivery` line per node
+    /// with the depot as node 0 (time windows and pairings are ignored)
+    LiLim,
+}
+
+impl InstanceDialect {
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstanceDialect::Auto => "auto",
+            InstanceDialect::Mosheiov => "mosheiov",
+            InstanceDialect::Tsplib => "tsplib",
+            InstanceDialect::LiLim => "lilim",
+        }
+    }
+    
+    /// Whether `content` looks like a Li & Lim file
+    fn is_lilim(content: &str) -> bool {
+        let mut lines = content.lines().map(str::trim).filter(|line| !line.is_empty());
+        let header_ok = lines.next().is_some_and(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            fields.len() == 3 && fields.iter().all(|f| f.parse::<f64>().is_ok())
+        });
+        header_ok && lines.next().is_some_and(|line| line.split_whitespace().count() == 9)
+    }
+}
+
+/// How an instance serves distances and nearest-neighbor queries
+#[derive(Debug, Clone, Default)]
+pub enum InstanceBackend {
+    /// Distances read from `distance_matrix`; neighbor queries scan every node
+    #[default]
+    Precomputed,
+    /// Euclidean distances computed from the coordinates on each lookup and
+    /// neighbor queries answered by a k-d tree, without any O(n²) storage
+    OnDemandEuclidean { kdtree: Arc<KdTree> },
+}
+
+impl InstanceBackend {
+    /// On-demand backend over the coordinates of `nodes`
+    pub fn on_demand_euclidean(nodes: &[Node]) -> Self {
+        InstanceBackend::OnDemandEuclidean { kdtree: Arc::new(KdTree::from_nodes(nodes)) }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstanceBackend::Precomputed => "precomputed",
+            InstanceBackend::OnDemandEuclidean { .. } => "on-demand",
+        }
+    }
 }
 
 /// Cost function choices for travel cost
@@ -77,6 +213,8 @@ pub enum CostFunction {
     Distance,
     Quadratic,
     LinearLoad,
+    /// Schedule duration: travel time (the distance) plus service times
+    Duration,
 }
 
 impl PDTSPInstance {
@@ -85,6 +223,9 @@ impl PDTSPInstance {
     /// For PD-TSP, the depot demand represents the initial load.
     #[inline]
     pub fn starting_load(&self) -> i32 {
+        if let Some(commodities) = &self.commodities {
+            return commodities.demands[0].iter().map(|d| (*d).max(0)).sum();
+        }
         // Simply return the depot demand as the starting load
         // Positive = we start with items to deliver
         // Negative = we need to pick up items first (start at 0)
@@ -107,11 +248,247 @@ impl PDTSPInstance {
         (-self.nodes[0].demand).max(0)
     }
 
+    /// Whether the multi-commodity extension is active
+    #[inline]
+    pub fn is_multi_commodity(&self) -> bool {
+        self.commodities.is_some()
+    }
+    
+    /// Position in the instance file of internal node `node` (the depot and
+    /// the first file node swap places when the file names another depot)
+    pub fn file_index(&self, node: usize) -> usize {
+        if node == 0 {
+            self.depot_index
+        } else if node == self.depot_index {
+            0
+        } else {
+            node
+        }
+    }
+    
+    /// Whether feasibility also depends on sampled demand scenarios
+    #[inline]
+    pub fn has_chance_constraint(&self) -> bool {
+        self.chance_constraint.is_some()
+    }
+    
+    /// Whether a tour satisfies the chance constraint over its scenarios.
+    /// Always true without one.
+    pub fn chance_feasible(&self, tour: &[usize]) -> bool {
+        self.chance_constraint.as_ref().is_none_or(|chance| chance.scenarios_satisfied(self, tour))
+    }
+    
+    /// Whether routes have a maximum length
+    #[inline]
+    pub fn has_route_limit(&self) -> bool {
+        self.max_route_length.is_some()
+    }
+    
+    /// Length of each route of a tour: intermediate depot visits split it,
+    /// and the last route ends with the return to the depot (unless the tour
+    /// is open)
+    pub fn route_lengths(&self, tour: &[usize]) -> Vec<f64> {
+        let mut lengths = Vec::new();
+        let mut length = 0.0;
+        for i in 1..tour.len() {
+            length += self.distance(tour[i - 1], tour[i]);
+            if tour[i] == 0 {
+                lengths.push(length);
+                length = 0.0;
+            }
+        }
+        if let Some(&last) = tour.last() {
+            lengths.push(length + if self.open_tour { 0.0 } else { self.distance(last, 0) });
+        }
+        lengths
+    }
+    
+    /// Whether every route of a (possibly partial) tour respects the route
+    /// length limit. Always true without a limit.
+    pub fn route_lengths_feasible(&self, tour: &[usize]) -> bool {
+        match self.max_route_length {
+            None => true,
+            Some(limit) => self.route_lengths(tour).iter().all(|&length| length <= limit + 1e-9),
+        }
+    }
+    
+    /// Insert depot visits into `tour` so that no route exceeds the length
+    /// limit: before each customer that could not be reached and left for the
+    /// depot in time, the vehicle returns to the depot (and unloads there).
+    /// `None` when a customer is out of reach or the split tour violates the
+    /// capacity.
+    pub fn split_routes(&self, tour: &[usize]) -> Option<Vec<usize>> {
+        let Some(limit) = self.max_route_length else {
+            return Some(tour.to_vec());
+        };
+        let mut split = vec![0];
+        let mut length = 0.0;
+        for &node in tour.iter().skip(1) {
+            let last = split[split.len() - 1];
+            if node == 0 {
+                if last != 0 {
+                    split.push(0);
+                }
+                length = 0.0;
+                continue;
+            }
+            if last != 0 && length + self.distance(last, node) + self.distance(node, 0) > limit {
+                split.push(0);
+                length = 0.0;
+            }
+            let from = split[split.len() - 1];
+            length += self.distance(from, node);
+            if length + self.distance(node, 0) > limit {
+                return None;
+            }
+            split.push(node);
+        }
+        self.is_feasible(&split).then_some(split)
+    }
+    
+    /// Enable the multi-commodity extension. `demands[i][k]` is the demand of
+    /// node `i` for commodity `k`; node demands are replaced by their aggregate.
+    pub fn set_commodities(&mut self, capacities: Vec<i32>, demands: Vec<Vec<i32>>) -> Result<(), String> {
+        if capacities.is_empty() {
+            return Err("At least one commodity is required".to_string());
+        }
+        if demands.len() != self.dimension {
+            return Err(format!("Expected demands for {} nodes, got {}", self.dimension, demands.len()));
+        }
+        if let Some(i) = demands.iter().position(|d| d.len() != capacities.len()) {
+            return Err(format!("Node {} has {} commodity demands, expected {}", i, demands[i].len(), capacities.len()));
+        }
+        
+        for (node, d) in self.nodes.iter_mut().zip(&demands) {
+            node.demand = d.iter().sum();
+        }
+        self.commodities = Some(Commodities { capacities, demands });
+        Ok(())
+    }
+    
+    /// Per-commodity load after departing from the depot
+    pub fn commodity_starting_loads(&self) -> Vec<i32> {
+        match &self.commodities {
+            Some(c) => c.demands[0].iter().map(|d| (*d).max(0)).collect(),
+            None => vec![self.starting_load()],
+        }
+    }
+    
+    /// Per-commodity load profile along a tour (one load vector per visited node,
+    /// same convention as `check_feasibility_detailed`)
+    pub fn commodity_load_profile(&self, tour: &[usize]) -> Vec<Vec<i32>> {
+        let Some(commodities) = &self.commodities else {
+            return self.check_feasibility_detailed(tour).3.into_iter().map(|l| vec![l]).collect();
+        };
+        
+        let mut load = self.commodity_starting_loads();
+        let mut profile = Vec::with_capacity(tour.len());
+        profile.push(load.clone());
+        
+        for &node_id in tour.iter().skip(1) {
+            if node_id == 0 {
+                load.iter_mut().for_each(|l| *l = 0);
+            } else {
+                for (l, d) in load.iter_mut().zip(&commodities.demands[node_id]) {
+                    *l += d;
+                }
+            }
+            profile.push(load.clone());
+        }
+        
+        profile
+    }
+    
+    /// Check the per-commodity capacities along a (partial) tour.
+    /// Always true for single-commodity instances.
+    pub fn commodity_loads_feasible(&self, tour: &[usize]) -> bool {
+        let Some(commodities) = &self.commodities else {
+            return true;
+        };
+        
+        let mut load = self.commodity_starting_loads();
+        for &node_id in tour.iter().skip(1) {
+            for (k, l) in load.iter_mut().enumerate() {
+                if node_id == 0 {
+                    *l = 0;
+                } else {
+                    *l += commodities.demands[node_id][k];
+                }
+                if *l < 0 || *l > commodities.capacities[k] {
+                    return false;
+                }
+            }
+        }
+        
+        true
+    }
+    
+    /// Whether `node` can be appended to the partial `tour` whose current
+    /// (aggregate) load is `load`, returning to the depot within the route
+    /// length limit. Only the multi-commodity case walks the whole tour.
+    pub fn can_append(&self, tour: &[usize], load: i32, node: usize) -> bool {
+        let new_load = load + self.nodes[node].demand;
+        if new_load < 0 || new_load > self.capacity {
+            return false;
+        }
+        if tour.last().is_some_and(|&last| !self.has_arc(last, node)) {
+            return false;
+        }
+        if let Some(limit) = self.max_route_length {
+            let start = tour.iter().rposition(|&n| n == 0).unwrap_or(0);
+            let route = &tour[start..];
+            let length: f64 = route.windows(2).map(|w| self.distance(w[0], w[1])).sum::<f64>()
+                + route.last().map_or(0.0, |&last| self.distance(last, node))
+                + if self.open_tour { 0.0 } else { self.distance(node, 0) };
+            if length > limit {
+                return false;
+            }
+        }
+        if !self.is_multi_commodity() {
+            return true;
+        }
+        
+        let mut extended = Vec::with_capacity(tour.len() + 1);
+        extended.extend_from_slice(tour);
+        extended.push(node);
+        self.commodity_loads_feasible(&extended)
+    }
+
     /// Parse a PD-TSP instance from a TSP-LIB format file
+    /// (or from the JSON format when the extension is `.json`)
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
-        let file = File::open(&path)
+        Self::from_file_with_dialect(path, InstanceDialect::Auto)
+    }
+    
+    /// Parse an instance file with the given depot convention (ignored for
+    /// JSON files). Li & Lim instances are named after the file.
+    pub fn from_file_with_dialect<P: AsRef<Path>>(path: P, dialect: InstanceDialect) -> Result<Self, String> {
+        let content = std::fs::read_to_string(&path)
             .map_err(|e| format!("Cannot open file: {}", e))?;
-        let reader = BufReader::new(file);
+        if path.as_ref().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            return Self::from_json(&content);
+        }
+        
+        let mut instance = Self::from_str_with_dialect(&content, dialect)?;
+        if instance.name.is_empty() {
+            if let Some(stem) = path.as_ref().file_stem() {
+                instance.name = stem.to_string_lossy().into_owned();
+            }
+        }
+        Ok(instance)
+    }
+    
+    /// Parse a PD-TSP instance from the content of a TSP-LIB file, without
+    /// any file access (used by the WebAssembly API)
+    pub fn from_tsplib_str(content: &str) -> Result<Self, String> {
+        Self::from_str_with_dialect(content, InstanceDialect::Auto)
+    }
+    
+    /// Parse the content of an instance file with the given depot convention
+    pub fn from_str_with_dialect(content: &str, dialect: InstanceDialect) -> Result<Self, String> {
+        if dialect == InstanceDialect::LiLim || (dialect == InstanceDialect::Auto && InstanceDialect::is_lilim(content)) {
+            return Self::from_lilim_str(content);
+        }
         
         let mut name = String::new();
         let mut comment = String::new();
@@ -119,11 +496,15 @@ impl PDTSPInstance {
         let mut capacity = 0i32;
         let mut coords: Vec<(usize, f64, f64)> = Vec::new();
         let mut demands: Vec<(usize, i32)> = Vec::new();
+        let mut commodity_capacities: Vec<i32> = Vec::new();
+        let mut commodity_demands: Vec<(usize, Vec<i32>)> = Vec::new();
+        let mut depots: Vec<i64> = Vec::new();
+        let mut max_route_length = None;
+        let mut service_times: Vec<(usize, f64)> = Vec::new();
         
         let mut section = String::new();
         
-        for line in reader.lines() {
-            let line = line.map_err(|e| format!("Read error: {}", e))?;
+        for line in content.lines() {
             let line = line.trim();
             
             if line.is_empty() || line == "EOF" {
@@ -152,6 +533,19 @@ impl PDTSPInstance {
             if line.starts_with("EDGE_WEIGHT_TYPE:") {
                 continue;
             }
+            if line.starts_with("DISTANCE:") {
+                let limit: f64 = line.replace("DISTANCE:", "").trim()
+                    .parse().map_err(|_| "Invalid route length limit")?;
+                max_route_length = Some(limit);
+                continue;
+            }
+            if line.starts_with("COMMODITY_CAPACITY:") {
+                commodity_capacities = line.replace("COMMODITY_CAPACITY:", "")
+                    .split_whitespace()
+                    .map(|c| c.parse().map_err(|_| format!("Invalid commodity capacity: {}", c)))
+                    .collect::<Result<_, _>>()?;
+                continue;
+            }
             
             
             if line.starts_with("NODE_COORD_SECTION") {
@@ -162,10 +556,22 @@ impl PDTSPInstance {
                 section = "display".to_string();
                 continue;
             }
+            if line.starts_with("COMMODITY_DEMAND_SECTION") {
+                section = "commodity_demands".to_string();
+                continue;
+            }
             if line.starts_with("DEMAND_SECTION") {
                 section = "demands".to_string();
                 continue;
             }
+            if line.starts_with("DEPOT_SECTION") {
+                section = "depots".to_string();
+                continue;
+            }
+            if line.starts_with("SERVICE_TIME_SECTION") {
+                section = "service_times".to_string();
+                continue;
+            }
             
             
             match section.as_str() {
@@ -186,17 +592,65 @@ impl PDTSPInstance {
                         demands.push((id, demand));
                     }
                 }
+                "commodity_demands" => {
+                    let parts: Vec<&str> = line.split_whitespace().collect();
+                    if parts.len() >= 2 {
+                        let id: usize = parts[0].parse().map_err(|_| "Invalid node id")?;
+                        let values: Vec<i32> = parts[1..].iter()
+                            .map(|d| d.parse().map_err(|_| format!("Invalid commodity demand: {}", d)))
+                            .collect::<Result<_, _>>()?;
+                        commodity_demands.push((id, values));
+                    }
+                }
+                "service_times" => {
+                    let parts: Vec<&str> = line.split_whitespace().collect();
+                    if parts.len() >= 2 {
+                        let id: usize = parts[0].parse().map_err(|_| "Invalid node id")?;
+                        let time: f64 = parts[1].parse().map_err(|_| "Invalid service time")?;
+                        service_times.push((id, time));
+                    }
+                }
+                "depots" => {
+                    depots.extend(line.split_whitespace()
+                        .map(|id| id.parse::<i64>().map_err(|_| format!("Invalid depot: {}", id)))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into_iter()
+                        .filter(|&id| id >= 0));
+                }
                 _ => {}
             }
         }
         
         
-        let has_duplicate_depot = if coords.len() >= 2 {
-            let first = &coords[0];
-            let last = &coords[coords.len() - 1];
-            (first.1 - last.1).abs() < 1e-6 && (first.2 - last.2).abs() < 1e-6
-        } else {
-            false
+        if depots.len() > 1 {
+            return Err(format!("Only one depot is supported, DEPOT_SECTION names {} nodes", depots.len()));
+        }
+        let depot = depots.first().map_or(1, |&id| id as usize);
+        if depot != 1 && dialect == InstanceDialect::Mosheiov {
+            return Err(format!("A Mosheiov instance has its depot at node 1, DEPOT_SECTION names node {}", depot));
+        }
+        // Position of the depot among the nodes of the file
+        let depot_index = match coords.iter().position(|c| c.0 == depot) {
+            Some(index) => index,
+            None if depot == 1 => 0,
+            None => return Err(format!("DEPOT_SECTION names node {}, which has no coordinates", depot)),
+        };
+        let has_duplicate_depot = depot == 1 && match dialect {
+            InstanceDialect::Mosheiov => {
+                if coords.len() < 2 || coords.len() != dimension {
+                    return Err(format!(
+                        "A Mosheiov instance needs DIMENSION ({}) nodes including the return depot, found {}",
+                        dimension, coords.len()
+                    ));
+                }
+                true
+            }
+            InstanceDialect::Tsplib | InstanceDialect::LiLim => false,
+            InstanceDialect::Auto => coords.len() >= 2 && {
+                let first = &coords[0];
+                let last = &coords[coords.len() - 1];
+                (first.1 - last.1).abs() < 1e-6 && (first.2 - last.2).abs() < 1e-6
+            },
         };
 
         // Determine actual number of nodes to load and the return-depot demand
@@ -213,9 +667,9 @@ impl PDTSPInstance {
             // No explicit return-depot entry: the instance is already balanced
             // Calculate return_depot_demand as the negative of the total customer demand
             // to ensure the vehicle ends with 0 load
-            let depot_demand = demands.iter().find(|(id, _)| *id == 1).map(|(_, d)| *d).unwrap_or(0);
+            let depot_demand = demands.iter().find(|(id, _)| *id == depot).map(|(_, d)| *d).unwrap_or(0);
             let customer_demands_sum: i32 = demands.iter()
-                .filter(|(id, _)| *id > 1)
+                .filter(|(id, _)| *id != depot)
                 .map(|(_, d)| *d)
                 .sum();
             let return_demand = -(depot_demand + customer_demands_sum);
@@ -223,65 +677,431 @@ impl PDTSPInstance {
         };
 
         let mut nodes = Vec::with_capacity(actual_dimension);
+        coords.truncate(actual_dimension);
+        if depot_index < coords.len() {
+            coords.swap(0, depot_index);
+        }
 
-        for (id, x, y) in coords.iter().take(actual_dimension) {
+        for (index, (id, x, y)) in coords.iter().enumerate() {
             let file_demand = demands.iter()
                 .find(|(did, _)| *did == *id)
                 .map(|(_, d)| *d)
                 .unwrap_or(0);
 
-            // Preserve the file demand for the depot (id==1) and customers alike.
+            // Preserve the file demand for the depot and customers alike.
             let internal_demand = file_demand;
-            nodes.push(Node::new(id - 1, *x, *y, internal_demand, 0));
+            let mut node = Node::new(index, *x, *y, internal_demand, 0);
+            if let Some((_, time)) = service_times.iter().find(|(sid, _)| sid == id) {
+                node.service_time = *time;
+            }
+            nodes.push(node);
         }
 
-        let distance_matrix = Self::compute_distance_matrix(&nodes);
+        let (distance_matrix, backend) = Self::default_distances(&nodes);
 
-        Ok(PDTSPInstance {
-            name,
+        let mut instance = PDTSPInstance {
             comment,
-            dimension: actual_dimension,
-            capacity,
-            nodes,
-            distance_matrix,
+            backend,
             return_depot_demand,
-            cost_function: CostFunction::Distance,
-            alpha: 0.1,
-            beta: 0.5,
+            max_route_length,
+            depot_index,
+            ..PDTSPInstance::from_parts(name, capacity, nodes, distance_matrix)
+        };
+
+        if !commodity_capacities.is_empty() {
+            let per_node: Vec<Vec<i32>> = instance.nodes.iter()
+                .map(|node| commodity_demands.iter()
+                    .find(|(id, _)| *id == coords[node.id].0)
+                    .map(|(_, d)| d.clone())
+                    .unwrap_or_else(|| vec![0; commodity_capacities.len()]))
+                .collect();
+            instance.set_commodities(commodity_capacities, per_node)?;
+        }
+
+        Ok(instance)
+    }
+    
+    /// Parse a Li & Lim PDPTW instance as a single-commodity PD-TSP: node
+    /// coordinates, demands (positive at pickups, negative at deliveries) and
+    /// service times are kept, time windows and pairings are dropped
+    pub fn from_lilim_str(content: &str) -> Result<Self, String> {
+        let mut lines = content.lines().map(str::trim).filter(|line| !line.is_empty());
+        let header: Vec<&str> = lines.next().ok_or("Empty Li & Lim file")?.split_whitespace().collect();
+        if header.len() < 2 {
+            return Err("The first line must give the number of vehicles and the capacity".to_string());
+        }
+        let capacity: i32 = header[1].parse().map_err(|_| format!("Invalid capacity: {}", header[1]))?;
+        
+        let mut nodes = Vec::new();
+        for line in lines {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 4 {
+                return Err(format!("Invalid Li & Lim line: {}", line));
+            }
+            let id: usize = parts[0].parse().map_err(|_| format!("Invalid node id: {}", parts[0]))?;
+            if id != nodes.len() {
+                return Err(format!("Node {} found where node {} was expected", id, nodes.len()));
+            }
+            let x: f64 = parts[1].parse().map_err(|_| "Invalid x coordinate")?;
+            let y: f64 = parts[2].parse().map_err(|_| "Invalid y coordinate")?;
+            let demand: i32 = parts[3].parse().map_err(|_| "Invalid demand")?;
+            let mut node = Node::new(id, x, y, if id == 0 { 0 } else { demand }, 0);
+            if let Some(service) = parts.get(6) {
+                node.service_time = service.parse().map_err(|_| format!("Invalid service time: {}", service))?;
+            }
+            nodes.push(node);
+        }
+        if nodes.is_empty() {
+            return Err("No nodes in Li & Lim file".to_string());
+        }
+        
+        let return_depot_demand = -nodes.iter().map(|node| node.demand).sum::<i32>();
+        let (distance_matrix, backend) = Self::default_distances(&nodes);
+        Ok(PDTSPInstance {
+            comment: "Li & Lim PDPTW (time windows and pairings ignored)".to_string(),
+            backend,
+            return_depot_demand,
+            ..PDTSPInstance::from_parts(String::new(), capacity, nodes, distance_matrix)
         })
     }
+    
+    /// Serialize the instance (nodes with profits, capacity, cost function
+    /// settings, commodities and vehicle profile) to JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Cannot serialize instance: {}", e))
+    }
+    
+    /// Parse an instance written by `to_json`; the distance matrix is rebuilt
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let mut instance: PDTSPInstance = serde_json::from_str(content)
+            .map_err(|e| format!("Invalid instance JSON: {}", e))?;
+        
+        if instance.nodes.len() != instance.dimension {
+            return Err(format!("Dimension is {} but {} nodes are given", instance.dimension, instance.nodes.len()));
+        }
+        if let Some(commodities) = instance.commodities.take() {
+            instance.set_commodities(commodities.capacities, commodities.demands)?;
+        }
+        (instance.distance_matrix, instance.backend) = Self::default_distances(&instance.nodes);
+        
+        Ok(instance)
+    }
+    
+    /// Save the instance as JSON
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        std::fs::write(path, self.to_json()?)
+            .map_err(|e| format!("Cannot write instance: {}", e))
+    }
 
-    /// Compute travel cost according to the selected cost function stored in the instance
+    /// Compute travel cost according to the selected cost function stored in the instance,
+    /// converted by the vehicle profile when one is attached
     pub fn tour_cost(&self, tour: &[usize]) -> f64 {
+        let base = self.base_tour_cost(tour);
+        match &self.vehicle {
+            None => base,
+            Some(vehicle) => vehicle.tour_cost(base, self.tour_load_distance(tour)),
+        }
+    }
+    
+    /// Travel cost under the selected cost function, ignoring any vehicle profile
+    pub fn base_tour_cost(&self, tour: &[usize]) -> f64 {
         match self.cost_function {
             CostFunction::Distance => self.tour_length(tour),
             CostFunction::Quadratic => self.tour_cost_quadratic(tour),
             CostFunction::LinearLoad => self.tour_cost_linear_load(tour, self.alpha),
+            CostFunction::Duration => self.tour_duration(tour),
         }
     }
     
-    /// Compute Euclidean distance matrix
-    fn compute_distance_matrix(nodes: &[Node]) -> Vec<Vec<f64>> {
-        let n = nodes.len();
-        let mut matrix = vec![vec![0.0; n]; n];
-        
-        for i in 0..n {
-            for j in 0..n {
-                if i != j {
-                    let dx = nodes[i].x - nodes[j].x;
-                    let dy = nodes[i].y - nodes[j].y;
-                    matrix[i][j] = (dx * dx + dy * dy).sqrt();
-                }
+    /// Total service time of a tour: every node reached by an arc is served,
+    /// including the depot when the tour returns to it
+    pub fn tour_service_time(&self, tour: &[usize]) -> f64 {
+        let skip = usize::from(self.open_tour);
+        tour.iter().skip(skip).map(|&n| self.nodes[n].service_time).sum()
+    }
+    
+    /// Duration of the schedule of a tour: travel time at unit speed plus
+    /// service times. Without time windows the vehicle never waits.
+    pub fn tour_duration(&self, tour: &[usize]) -> f64 {
+        self.tour_length(tour) + self.tour_service_time(tour)
+    }
+    
+    /// Whether any node has a service time
+    pub fn has_service_times(&self) -> bool {
+        self.nodes.iter().any(|n| n.service_time != 0.0)
+    }
+    
+    /// Give every customer the service time `time` (the depot keeps its own)
+    pub fn set_service_time(&mut self, time: f64) {
+        for node in self.nodes.iter_mut().skip(1) {
+            node.service_time = time;
+        }
+    }
+    
+    /// Serve large demands over several visits: every customer whose demand
+    /// exceeds `max_part` in magnitude (the capacity by default) keeps a
+    /// share of it and gets extra visits at the same location for the rest,
+    /// the shares as even as possible. Profit and service time stay with the
+    /// first visit. Returns the number of visits added.
+    pub fn split_demands(&mut self, max_part: Option<i32>) -> Result<usize, String> {
+        if self.is_multi_commodity() {
+            return Err("Split demands are not supported with several commodities".to_string());
+        }
+        if self.has_chance_constraint() {
+            return Err("Split demands before setting the chance constraint".to_string());
+        }
+        let max_part = max_part.unwrap_or(self.capacity);
+        if max_part <= 0 {
+            return Err(format!("Split demand size must be positive, got {}", max_part));
+        }
+        
+        let mut added = 0;
+        for node in 1..self.dimension {
+            let demand = self.nodes[node].demand;
+            let parts = (demand.abs() + max_part - 1) / max_part;
+            if parts <= 1 {
+                continue;
+            }
+            let (base, extra) = (demand.abs() / parts, demand.abs() % parts);
+            let share = |k: i32| demand.signum() * (base + i32::from(k < extra));
+            self.nodes[node].demand = share(0);
+            for k in 1..parts {
+                let (x, y) = (self.nodes[node].x, self.nodes[node].y);
+                let mut visit = Node::new(self.nodes.len(), x, y, share(k), 0);
+                visit.split_of = Some(node);
+                self.nodes.push(visit);
+                added += 1;
+            }
+        }
+        if added == 0 {
+            return Ok(0);
+        }
+        
+        // Visits of the same customer are at distance zero from each other;
+        // the distances keep their source (road matrix, restricted arcs, ...)
+        self.dimension = self.nodes.len();
+        let original: Vec<usize> = (0..self.dimension).map(|node| self.original_node(node)).collect();
+        self.distance_matrix = self.distance_matrix.with_visits(&original);
+        if let InstanceBackend::OnDemandEuclidean { .. } = self.backend {
+            self.backend = InstanceBackend::on_demand_euclidean(&self.nodes);
+        }
+        Ok(added)
+    }
+    
+    /// Whether some customers are served over several visits
+    pub fn has_split_demands(&self) -> bool {
+        self.nodes.iter().any(|n| n.split_of.is_some())
+    }
+    
+    /// Customer served at internal node `node`: itself, unless it is an
+    /// extra visit added by `split_demands`
+    #[inline]
+    pub fn original_node(&self, node: usize) -> usize {
+        self.nodes[node].split_of.unwrap_or(node)
+    }
+    
+    /// Tour with every extra visit replaced by its customer
+    pub fn original_tour(&self, tour: &[usize]) -> Vec<usize> {
+        tour.iter().map(|&node| self.original_node(node)).collect()
+    }
+    
+    /// Sum over arcs of carried load × arc length (including the return arc)
+    pub fn tour_load_distance(&self, tour: &[usize]) -> f64 {
+        if tour.len() < 2 {
+            return 0.0;
+        }
+        
+        let mut total = 0.0;
+        let mut load = self.starting_load() as f64;
+        for i in 0..tour.len() - 1 {
+            total += load.abs() * self.distance(tour[i], tour[i + 1]);
+            if tour[i + 1] == 0 {
+                load = 0.0;
+            } else {
+                load += self.nodes[tour[i + 1]].demand as f64;
             }
         }
+        if !self.open_tour {
+            total += load.abs() * self.distance(tour[tour.len() - 1], tour[0]);
+        }
+        
+        total
+    }
+    
+    /// Emissions estimate of a tour under the attached vehicle profile
+    /// (`None` when no vehicle is attached)
+    pub fn tour_emissions(&self, tour: &[usize]) -> Option<f64> {
+        self.vehicle.as_ref()
+            .map(|v| v.emissions(self.tour_length(tour), self.tour_load_distance(tour)))
+    }
+    
+    /// Attach a vehicle profile; its capacity (if any) replaces the instance capacity
+    pub fn set_vehicle_profile(&mut self, profile: VehicleProfile) {
+        if let Some(capacity) = profile.capacity {
+            self.capacity = capacity;
+        }
+        self.vehicle = Some(profile);
+    }
+    
+    /// Decompose the cost of a tour into its components
+    pub fn cost_breakdown(&self, tour: &[usize]) -> CostBreakdown {
+        let distance = self.tour_length(tour);
+        let base = self.base_tour_cost(tour);
+        let load_distance = self.tour_load_distance(tour);
+        let (fixed_cost, scaled_base, load_cost) = match &self.vehicle {
+            None => (0.0, base, 0.0),
+            Some(v) => (v.fixed_cost, v.cost_per_distance * base, v.cost_per_load_unit * load_distance),
+        };
         
-        matrix
+        CostBreakdown {
+            vehicle: self.vehicle.as_ref().map(|v| v.name.clone()),
+            distance,
+            load_surcharge: base - distance,
+            load_distance,
+            fixed_cost,
+            travel_cost: scaled_base,
+            load_cost,
+            total: fixed_cost + scaled_base + load_cost,
+            emissions: self.vehicle.as_ref().map(|v| v.emissions(distance, load_distance)),
+        }
+    }
+    
+    /// Compute Euclidean distance matrix
+    fn compute_distance_matrix(nodes: &[Node]) -> DistanceMatrix {
+        DistanceMatrix::from_nodes(nodes, DistanceMode::Dense)
+    }
+    
+    /// Build an instance from its nodes and distances. Every other field gets
+    /// its default: distance cost (alpha 0.1, beta 0.5), no commodities,
+    /// vehicle profile, route limit or chance constraint, closed tour with the
+    /// depot first. Override them with struct update syntax.
+    pub fn from_parts(name: impl Into<String>, capacity: i32, nodes: Vec<Node>, distance_matrix: DistanceMatrix) -> Self {
+        PDTSPInstance {
+            name: name.into(),
+            comment: String::new(),
+            dimension: nodes.len(),
+            capacity,
+            nodes,
+            distance_matrix,
+            backend: Default::default(),
+            return_depot_demand: 0,
+            cost_function: CostFunction::Distance,
+            alpha: 0.1,
+            beta: 0.5,
+            commodities: None,
+            vehicle: None,
+            max_route_length: None,
+            open_tour: false,
+            depot_index: 0,
+            chance_constraint: None,
+        }
+    }
+
+    /// Dense Euclidean matrix, or on-demand distances with a k-d tree above
+    /// `LARGE_INSTANCE_THRESHOLD` nodes
+    fn default_distances(nodes: &[Node]) -> (DistanceMatrix, InstanceBackend) {
+        if nodes.len() > LARGE_INSTANCE_THRESHOLD {
+            (DistanceMatrix::from_nodes(nodes, DistanceMode::OnDemand), InstanceBackend::on_demand_euclidean(nodes))
+        } else {
+            (Self::compute_distance_matrix(nodes), InstanceBackend::Precomputed)
+        }
+    }
+    
+    /// Rebuild the Euclidean distance matrix with the given storage mode
+    /// (e.g. `DenseF32` or `OnDemand` to reduce memory on large instances).
+    /// `OnDemand` selects the `OnDemandEuclidean` backend.
+    pub fn set_distance_mode(&mut self, mode: DistanceMode) {
+        self.distance_matrix = DistanceMatrix::from_nodes_rounded(&self.nodes, mode, self.distance_matrix.rounding());
+        self.backend = if mode == DistanceMode::OnDemand {
+            InstanceBackend::on_demand_euclidean(&self.nodes)
+        } else {
+            InstanceBackend::Precomputed
+        };
+    }
+    
+    /// Switch to on-demand Euclidean distances with k-d tree neighbor queries
+    pub fn use_on_demand_backend(&mut self) {
+        self.set_distance_mode(DistanceMode::OnDemand);
+    }
+    
+    /// Switch to a dense precomputed Euclidean matrix
+    pub fn use_precomputed_backend(&mut self) {
+        self.set_distance_mode(DistanceMode::Dense);
+    }
+    
+    /// Rebuild the Euclidean distances with the given rounding, keeping the
+    /// storage mode (TSPLIB `nint` to compare with integer-rounded optima)
+    pub fn set_rounding(&mut self, rounding: Rounding) -> Result<(), String> {
+        let mode = self.distance_matrix.mode();
+        if mode == DistanceMode::External {
+            return Err("Cannot round the distances of an external provider".to_string());
+        }
+        self.distance_matrix = DistanceMatrix::from_nodes_rounded(&self.nodes, mode, rounding);
+        Ok(())
+    }
+    
+    /// Triangle-inequality and symmetry report of the distances
+    pub fn analyze_metric(&self) -> MetricReport {
+        self.distance_matrix.analyze_metric()
+    }
+    
+    /// Replace the Euclidean distances with an external provider
+    /// (e.g. road-network distances); it must cover every node
+    pub fn set_distance_provider(&mut self, provider: Arc<dyn DistanceProvider>) -> Result<(), String> {
+        if provider.node_count() != self.dimension {
+            return Err(format!(
+                "Distance provider '{}' covers {} nodes, instance has {}",
+                provider.name(), provider.node_count(), self.dimension
+            ));
+        }
+        self.distance_matrix = DistanceMatrix::from_provider(provider);
+        self.backend = InstanceBackend::Precomputed;
+        Ok(())
     }
     
     /// Get the distance between two nodes
     #[inline]
     pub fn distance(&self, i: usize, j: usize) -> f64 {
-        self.distance_matrix[i][j]
+        self.distance_matrix.get(i, j)
+    }
+    
+    /// Whether the arc from `i` to `j` may be travelled (always true unless
+    /// distances come from a sparse neighbor graph)
+    #[inline]
+    pub fn has_arc(&self, i: usize, j: usize) -> bool {
+        !self.distance_matrix.is_restricted() || self.distance(i, j).is_finite()
+    }
+    
+    /// Whether every arc of the tour exists, including the return arc if `closed`
+    pub fn arcs_exist(&self, tour: &[usize], closed: bool) -> bool {
+        if !self.distance_matrix.is_restricted() || tour.len() < 2 {
+            return true;
+        }
+        tour.windows(2).all(|w| self.has_arc(w[0], w[1]))
+            && (!closed || self.has_arc(tour[tour.len() - 1], tour[0]))
+    }
+    
+    /// The `k` nearest other nodes of every node, closest first
+    /// (unreachable nodes are left out)
+    pub fn nearest_neighbors(&self, k: usize) -> Vec<Vec<usize>> {
+        if let InstanceBackend::OnDemandEuclidean { kdtree } = &self.backend {
+            return (0..self.dimension).map(|i| kdtree.nearest_to(i, k)).collect();
+        }
+        (0..self.dimension).map(|i| {
+            let mut others: Vec<(f64, usize)> = (0..self.dimension)
+                .filter(|&j| j != i)
+                .map(|j| (self.distance(i, j), j))
+                .filter(|(d, _)| d.is_finite())
+                .collect();
+            let k = k.min(others.len());
+            if k < others.len() {
+                others.select_nth_unstable_by(k, |a, b| a.0.total_cmp(&b.0));
+                others.truncate(k);
+            }
+            others.sort_by(|a, b| a.0.total_cmp(&b.0));
+            others.into_iter().map(|(_, j)| j).collect()
+        }).collect()
     }
     
     /// Get the number of customer nodes (excluding depot)
@@ -335,7 +1155,48 @@ impl PDTSPInstance {
         // The depot can receive up to its capacity (absolute value of its negative demand)
         // For Mosheiov instances, the final load should be depositable at depot
         // Since all load can be deposited at depot at the end, we just need load >= 0
-        load >= 0
+        load >= 0 && self.commodity_loads_feasible(tour) && self.arcs_exist(tour, !self.open_tour)
+            && self.route_lengths_feasible(tour) && self.chance_feasible(tour)
+    }
+    
+    /// Whether the tour starts at the depot and visits every customer exactly
+    /// once (intermediate depot visits are allowed)
+    pub fn is_complete_tour(&self, tour: &[usize]) -> bool {
+        if tour.first() != Some(&0) {
+            return false;
+        }
+        let mut visits = vec![0usize; self.dimension];
+        for &node in tour.iter().filter(|&&node| node != 0) {
+            match visits.get_mut(node) {
+                Some(count) if *count == 0 => *count = 1,
+                _ => return false,
+            }
+        }
+        visits[1..].iter().all(|&count| count == 1)
+    }
+    
+    /// `is_feasible` for tours that must serve every customer: duplicate,
+    /// missing and unknown nodes make the tour infeasible too. `is_feasible`
+    /// alone accepts tours that skip customers, as profit variants build.
+    pub fn is_feasible_strict(&self, tour: &[usize]) -> bool {
+        self.is_complete_tour(tour) && self.is_feasible(tour)
+    }
+    
+    /// Total amount by which the load leaves `[0, capacity]` along a tour
+    /// (0 for tours that respect the capacity; intermediate depot visits
+    /// unload the vehicle)
+    pub fn load_violation(&self, tour: impl IntoIterator<Item = usize>) -> i64 {
+        let mut load = self.starting_load();
+        let mut violation = 0i64;
+        for node in tour.into_iter().skip(1) {
+            if node == 0 {
+                load = 0;
+            } else {
+                load += self.nodes[node].demand;
+            }
+            violation += (load - self.capacity).max(0) as i64 + (-load).max(0) as i64;
+        }
+        violation
     }
     
     /// Check tour feasibility with detailed information
@@ -367,10 +1228,73 @@ impl PDTSPInstance {
 
         // Implicit return to depot: final load can be deposited at depot
         // so we just need it to be non-negative
-        let feasible = max_load <= self.capacity && min_load >= 0 && load >= 0;
+        let feasible = max_load <= self.capacity && min_load >= 0 && load >= 0
+            && self.commodity_loads_feasible(tour);
         (feasible, max_load, min_load, load_profile)
     }
 
+    /// Every reason why `tour` is infeasible, in tour order: empty if and
+    /// only if `is_feasible_strict` holds. Loads are not simulated when the
+    /// tour has unknown nodes.
+    pub fn explain_infeasibility(&self, tour: &[usize]) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        match tour.first() {
+            Some(0) => {}
+            first => violations.push(Violation::WrongStart { node: first.copied() }),
+        }
+        let mut visits = vec![0usize; self.dimension];
+        for (position, &node) in tour.iter().enumerate() {
+            if node >= self.dimension {
+                violations.push(Violation::UnknownNode { position, node });
+            } else if node != 0 {
+                visits[node] += 1;
+                if visits[node] > 1 {
+                    violations.push(Violation::DuplicateNode { position, node });
+                }
+            }
+        }
+        violations.extend((1..self.dimension).filter(|&node| visits[node] == 0).map(|node| Violation::MissingNode { node }));
+        if tour.is_empty() || violations.iter().any(|v| matches!(v, Violation::UnknownNode { .. })) {
+            return violations;
+        }
+        
+        let mut load = self.starting_load();
+        for (position, &node) in tour.iter().enumerate().skip(1) {
+            load = if node == 0 { 0 } else { load + self.nodes[node].demand };
+            if load > self.capacity {
+                violations.push(Violation::Overload { position, node, load, capacity: self.capacity });
+            } else if load < 0 {
+                violations.push(Violation::NegativeLoad { position, node, load });
+            }
+        }
+        if let Some(commodities) = &self.commodities {
+            for (position, loads) in self.commodity_load_profile(tour).into_iter().enumerate().skip(1) {
+                for (commodity, (&load, &capacity)) in loads.iter().zip(&commodities.capacities).enumerate() {
+                    if load < 0 || load > capacity {
+                        violations.push(Violation::CommodityLoad { position, node: tour[position], commodity, load, capacity });
+                    }
+                }
+            }
+        }
+        if self.distance_matrix.is_restricted() {
+            let closing = (!self.open_tour && tour.len() > 1).then(|| (tour[tour.len() - 1], tour[0]));
+            let arcs = tour.windows(2).map(|w| (w[0], w[1])).chain(closing);
+            violations.extend(arcs.filter(|&(from, to)| !self.has_arc(from, to)).map(|(from, to)| Violation::MissingArc { from, to }));
+        }
+        if let Some(limit) = self.max_route_length {
+            for (route, length) in self.route_lengths(tour).into_iter().enumerate() {
+                if length > limit + 1e-9 {
+                    violations.push(Violation::RouteLength { route, length, limit });
+                }
+            }
+        }
+        if let Some(chance) = self.chance_constraint.as_ref().filter(|chance| !chance.scenarios_satisfied(self, tour)) {
+            let probability = chance.scenarios.violation_probability(self, tour);
+            violations.push(Violation::ChanceConstraint { probability, epsilon: chance.epsilon });
+        }
+        violations
+    }
+    
     /// Check partial tour feasibility: ensure that during the partial tour the load
     /// never goes below 0 or above capacity. Unlike `is_feasible`, this does NOT
     /// require the final load to be zero (useful for construction heuristics testing
@@ -396,7 +1320,7 @@ impl PDTSPInstance {
             }
         }
 
-        true
+        self.commodity_loads_feasible(tour) && self.arcs_exist(tour, false)
     }
     
     /// Calculate total tour length (linear distance)
@@ -410,7 +1334,9 @@ impl PDTSPInstance {
             length += self.distance(tour[i], tour[i + 1]);
         }
         
-        length += self.distance(tour[tour.len() - 1], tour[0]);
+        if !self.open_tour {
+            length += self.distance(tour[tour.len() - 1], tour[0]);
+        }
         
         length
     }
@@ -419,13 +1345,81 @@ impl PDTSPInstance {
     pub fn tour_profit(&self, tour: &[usize]) -> i32 {
         tour.iter().filter(|&&n| n != 0).map(|&n| self.nodes[n].profit).sum()
     }
+    
+    /// Randomized variant of the instance (a scenario). Every coordinate
+    /// moves uniformly by up to `coordinate_noise` times the extent of the
+    /// instance on its axis, and every customer demand is scaled by a uniform
+    /// factor in `[1 - demand_noise, 1 + demand_noise]`, rounded, keeping its
+    /// sign and at most the capacity. The depot demand is kept and the return
+    /// depot demand absorbs the change of the net demand. Euclidean distances
+    /// are rebuilt with the same storage and rounding; external providers
+    /// are kept as they are.
+    pub fn perturb(&self, seed: u64, coordinate_noise: f64, demand_noise: f64) -> PDTSPInstance {
+        use rand::prelude::*;
+        use rand_chacha::ChaCha8Rng;
+        
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut scenario = self.clone();
+        let extent = |axis: fn(&Node) -> f64| {
+            let (lo, hi) = self.nodes.iter().map(axis).fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+            if hi > lo { hi - lo } else { 0.0 }
+        };
+        let (dx, dy) = (coordinate_noise * extent(|n| n.x), coordinate_noise * extent(|n| n.y));
+        if coordinate_noise > 0.0 {
+            for node in scenario.nodes.iter_mut() {
+                node.x += dx * rng.gen_range(-1.0..=1.0);
+                node.y += dy * rng.gen_range(-1.0..=1.0);
+            }
+        }
+        
+        let mut scale = |demand: i32, capacity: i32| {
+            if demand == 0 || demand_noise <= 0.0 {
+                return demand;
+            }
+            let factor = 1.0 + demand_noise * rng.gen_range(-1.0..=1.0);
+            let magnitude = ((demand.abs() as f64 * factor).round() as i32).clamp(1, capacity.max(1));
+            magnitude * demand.signum()
+        };
+        let net: i32 = self.nodes.iter().skip(1).map(|n| n.demand).sum();
+        if let Some(commodities) = &self.commodities {
+            let mut demands = commodities.demands.clone();
+            for row in demands.iter_mut().skip(1) {
+                for (d, &capacity) in row.iter_mut().zip(&commodities.capacities) {
+                    *d = scale(*d, capacity);
+                }
+            }
+            // Same shape as the current commodities: cannot fail
+            scenario.set_commodities(commodities.capacities.clone(), demands).ok();
+        } else {
+            for node in scenario.nodes.iter_mut().skip(1) {
+                node.demand = scale(node.demand, self.capacity);
+            }
+        }
+        let new_net: i32 = scenario.nodes.iter().skip(1).map(|n| n.demand).sum();
+        scenario.return_depot_demand -= new_net - net;
+        
+        if coordinate_noise > 0.0 {
+            let mode = self.distance_matrix.mode();
+            if mode != DistanceMode::External {
+                scenario.distance_matrix = DistanceMatrix::from_nodes_rounded(&scenario.nodes, mode, self.distance_matrix.rounding());
+                if let InstanceBackend::OnDemandEuclidean { .. } = scenario.backend {
+                    scenario.backend = InstanceBackend::on_demand_euclidean(&scenario.nodes);
+                }
+            }
+        }
+        scenario
+    }
+
+    /// Whether some node carries a profit, so that the objective rewards
+    /// visiting it and a tour may leave unprofitable customers out
+    pub fn has_profits(&self) -> bool {
+        self.nodes.iter().any(|n| n.profit != 0)
+    }
 
     /// Assign random profits to customer nodes if none are present.
     /// Profits are integers in [10, max_profit] (clamped to 100). Deterministic via seed.
     pub fn assign_random_profits(&mut self, seed: u64, max_profit: i32) {
-        
-        let any_profit = self.nodes.iter().any(|n| n.profit != 0);
-        if any_profit {
+        if self.has_profits() {
             return;
         }
 
@@ -470,9 +1464,11 @@ impl PDTSPInstance {
         }
 
         // Return arc to depot
-        let dist = self.distance(tour[tour.len() - 1], tour[0]);
-        let surcharge = self.alpha * load + self.beta * load * load;
-        cost += dist + surcharge;
+        if !self.open_tour {
+            let dist = self.distance(tour[tour.len() - 1], tour[0]);
+            let surcharge = self.alpha * load + self.beta * load * load;
+            cost += dist + surcharge;
+        }
 
         cost
     }
@@ -504,9 +1500,11 @@ impl PDTSPInstance {
         }
 
         // Return arc to depot
-        let dist = self.distance(tour[tour.len() - 1], tour[0]);
-        let surcharge = alpha * load.abs();
-        cost += dist + surcharge;
+        if !self.open_tour {
+            let dist = self.distance(tour[tour.len() - 1], tour[0]);
+            let surcharge = alpha * load.abs();
+            cost += dist + surcharge;
+        }
 
         cost
     }
@@ -525,14 +1523,17 @@ impl PDTSPInstance {
             .sum();
         
         
-        let mut distances: Vec<f64> = Vec::new();
+        // Accumulated pair by pair: storing the n²/2 distances does not fit on large instances
+        let (mut total_distance, mut max_distance, mut pairs) = (0.0, 0.0f64, 0usize);
         for i in 0..self.dimension {
             for j in i+1..self.dimension {
-                distances.push(self.distance(i, j));
+                let d = self.distance(i, j);
+                total_distance += d;
+                max_distance = max_distance.max(d);
+                pairs += 1;
             }
         }
-        let avg_distance = distances.iter().sum::<f64>() / distances.len() as f64;
-        let max_distance = distances.iter().cloned().fold(0.0, f64::max);
+        let avg_distance = total_distance / pairs as f64;
         
         let total_profit: i32 = self.nodes.iter().map(|n| n.profit).sum();
 
@@ -551,6 +1552,104 @@ impl PDTSPInstance {
     }
 }
 
+/// One reason a tour is infeasible or malformed, see `explain_infeasibility`.
+/// Positions index the tour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Violation {
+    /// The tour is empty or does not start at the depot
+    WrongStart { node: Option<usize> },
+    /// A node outside `0..dimension`
+    UnknownNode { position: usize, node: usize },
+    /// Second or later visit of a customer
+    DuplicateNode { position: usize, node: usize },
+    /// A customer the tour never visits
+    MissingNode { node: usize },
+    /// The load after visiting `node` exceeds the capacity
+    Overload { position: usize, node: usize, load: i32, capacity: i32 },
+    /// The load after visiting `node` is negative: more was delivered than carried
+    NegativeLoad { position: usize, node: usize, load: i32 },
+    /// The load of a commodity after visiting `node` leaves `[0, capacity]`
+    CommodityLoad { position: usize, node: usize, commodity: usize, load: i32, capacity: i32 },
+    /// The arc from `from` to `to` does not exist
+    MissingArc { from: usize, to: usize },
+    /// Route `route` (0-based, split by depot visits) exceeds the length limit
+    RouteLength { route: usize, length: f64, limit: f64 },
+    /// The capacity is hit in a fraction `probability` of the demand
+    /// scenarios, above the allowed `epsilon`
+    ChanceConstraint { probability: f64, epsilon: f64 },
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::WrongStart { node: None } => write!(f, "empty tour"),
+            Violation::WrongStart { node: Some(node) } => write!(f, "tour starts at node {} instead of the depot", node),
+            Violation::UnknownNode { position, node } => write!(f, "position {}: unknown node {}", position, node),
+            Violation::DuplicateNode { position, node } => write!(f, "position {}: node {} visited again", position, node),
+            Violation::MissingNode { node } => write!(f, "node {} never visited", node),
+            Violation::Overload { position, node, load, capacity } => {
+                write!(f, "position {} (node {}): load {} above the capacity {}", position, node, load, capacity)
+            }
+            Violation::NegativeLoad { position, node, load } => {
+                write!(f, "position {} (node {}): load {} below zero", position, node, load)
+            }
+            Violation::CommodityLoad { position, node, commodity, load, capacity } => write!(
+                f, "position {} (node {}): load {} of commodity {} outside [0, {}]",
+                position, node, load, commodity, capacity
+            ),
+            Violation::MissingArc { from, to } => write!(f, "no arc from node {} to node {}", from, to),
+            Violation::RouteLength { route, length, limit } => {
+                write!(f, "route {}: length {:.2} above the limit {:.2}", route, length, limit)
+            }
+            Violation::ChanceConstraint { probability, epsilon } => write!(
+                f, "capacity exceeded in {:.1}% of the demand scenarios, above the allowed {:.1}%",
+                probability * 100.0, epsilon * 100.0
+            ),
+        }
+    }
+}
+
+/// Components of the cost of a tour
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostBreakdown {
+    /// Vehicle profile name, if one is attached
+    pub vehicle: Option<String>,
+    /// Plain Euclidean tour length
+    pub distance: f64,
+    /// Load-dependent surcharge of the cost function
+    pub load_surcharge: f64,
+    /// Sum of carried load × arc length
+    pub load_distance: f64,
+    /// Fixed vehicle cost
+    pub fixed_cost: f64,
+    /// Base travel cost scaled by the vehicle cost per distance
+    pub travel_cost: f64,
+    /// Vehicle cost for the load-distance product
+    pub load_cost: f64,
+    /// Total cost (equals `tour_cost`)
+    pub total: f64,
+    /// Emissions estimate, if a vehicle profile is attached
+    pub emissions: Option<f64>,
+}
+
+impl std::fmt::Display for CostBreakdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Cost breakdown{}:", self.vehicle.as_ref().map(|v| format!(" ({})", v)).unwrap_or_default())?;
+        writeln!(f, "  Distance: {:.2}", self.distance)?;
+        writeln!(f, "  Load surcharge: {:.2}", self.load_surcharge)?;
+        writeln!(f, "  Load x distance: {:.2}", self.load_distance)?;
+        writeln!(f, "  Fixed cost: {:.2}", self.fixed_cost)?;
+        writeln!(f, "  Travel cost: {:.2}", self.travel_cost)?;
+        writeln!(f, "  Load cost: {:.2}", self.load_cost)?;
+        write!(f, "  Total: {:.2}", self.total)?;
+        if let Some(emissions) = self.emissions {
+            write!(f, "\n  Emissions: {:.2}", emissions)?;
+        }
+        Ok(())
+    }
+}
+
 /// Statistics about a PD-TSP instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstanceStatistics {
@@ -584,6 +1683,7 @@ impl std::fmt::Display for InstanceStatistics {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::distance::graph::{NeighborGraph, RestrictedArcs};
     
     #[test]
     fn test_node_types() {
@@ -609,7 +1709,329 @@ mod tests {
         ];
         let matrix = PDTSPInstance::compute_distance_matrix(&nodes);
         
-        assert!((matrix[0][1] - 5.0).abs() < 1e-10);
-        assert!((matrix[1][0] - 5.0).abs() < 1e-10);
+        assert!((matrix.get(0, 1) - 5.0).abs() < 1e-10);
+        assert!((matrix.get(1, 0) - 5.0).abs() < 1e-10);
+    }
+    
+    #[test]
+    fn test_multi_commodity_feasibility() {
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 1.0, 0.0, 0, 0),
+            Node::new(2, 2.0, 0.0, 0, 0),
+            Node::new(3, 3.0, 0.0, 0, 0),
+        ];
+        let distance_matrix = PDTSPInstance::compute_distance_matrix(&nodes);
+        let mut instance = PDTSPInstance::from_parts("mc", 10, nodes, distance_matrix);
+        
+        // Commodity 0 capacity 3, commodity 1 capacity 5
+        instance.set_commodities(
+            vec![3, 5],
+            vec![vec![0, 0], vec![3, 2], vec![-3, 1], vec![0, -3]],
+        ).unwrap();
+        
+        assert_eq!(instance.nodes[1].demand, 5);
+        assert!(instance.is_feasible(&[0, 1, 2, 3]));
+        assert_eq!(instance.commodity_load_profile(&[0, 1, 2, 3]), vec![vec![0, 0], vec![3, 2], vec![0, 3], vec![0, 0]]);
+        
+        // Aggregate load stays within capacity but commodity 0 goes negative
+        assert!(!instance.is_feasible(&[0, 2, 1, 3]));
+        assert!(instance.can_append(&[0, 1], 5, 2));
+        assert!(!instance.can_append(&[0], 0, 2));
+        
+        assert!(instance.set_commodities(vec![1], vec![vec![0]; 3]).is_err());
+    }
+    
+    #[test]
+    fn test_vehicle_profile_cost() {
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 3.0, 0.0, 2, 0),
+            Node::new(2, 3.0, 4.0, -2, 0),
+        ];
+        let distance_matrix = PDTSPInstance::compute_distance_matrix(&nodes);
+        let mut instance = PDTSPInstance::from_parts("vehicle", 10, nodes, distance_matrix);
+        let tour = [0, 1, 2];
+        assert!((instance.tour_cost(&tour) - 12.0).abs() < 1e-9);
+        
+        // Load 2 is carried only on the 4-unit arc 1 -> 2
+        assert!((instance.tour_load_distance(&tour) - 8.0).abs() < 1e-9);
+        
+        instance.set_vehicle_profile(VehicleProfile {
+            name: "truck".to_string(),
+            capacity: Some(1),
+            fixed_cost: 10.0,
+            cost_per_distance: 2.0,
+            cost_per_load_unit: 0.5,
+            emission_factor: 0.5,
+            load_emission_factor: 0.25,
+        });
+        assert_eq!(instance.capacity, 1);
+        assert!((instance.tour_cost(&tour) - 38.0).abs() < 1e-9);
+        
+        let breakdown = instance.cost_breakdown(&tour);
+        assert!((breakdown.total - instance.tour_cost(&tour)).abs() < 1e-9);
+        assert!((breakdown.load_cost - 4.0).abs() < 1e-9);
+        
+        // 0.5 × 12 + 0.25 × 8
+        assert_eq!(instance.tour_emissions(&tour), Some(8.0));
+        assert_eq!(breakdown.emissions, Some(8.0));
+    }
+    
+    #[test]
+    fn test_json_round_trip() {
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 3.0, 0.0, 2, 0),
+            Node::new(2, 3.0, 4.0, -2, 0),
+        ];
+        let distance_matrix = PDTSPInstance::compute_distance_matrix(&nodes);
+        let mut instance = PDTSPInstance {
+            cost_function: CostFunction::Quadratic,
+            alpha: 0.2,
+            beta: 0.3,
+            ..PDTSPInstance::from_parts("json", 4, nodes, distance_matrix)
+        };
+        instance.assign_random_profits(1, 50);
+        
+        let loaded = PDTSPInstance::from_json(&instance.to_json().unwrap()).unwrap();
+        let tour = [0, 1, 2];
+        assert_eq!(loaded.cost_function, CostFunction::Quadratic);
+        assert_eq!(loaded.tour_profit(&tour), instance.tour_profit(&tour));
+        assert!((loaded.tour_cost(&tour) - instance.tour_cost(&tour)).abs() < 1e-12);
+    }
+    
+    #[test]
+    fn test_large_instance_uses_on_demand_backend() {
+        let n = LARGE_INSTANCE_THRESHOLD + 1;
+        let mut coords = String::new();
+        let mut demands = String::new();
+        for i in 1..=n {
+            coords += &format!("{} {} {}\n", i, (i * 7919) % 1000, (i * 104729) % 997);
+            demands += &format!("{} {}\n", i, if i == 1 { 0 } else if i % 2 == 0 { 1 } else { -1 });
+        }
+        let text = format!("NAME: big\nDIMENSION: {}\nCAPACITY: 5\nNODE_COORD_SECTION\n{}DEMAND_SECTION\n{}EOF\n", n, coords, demands);
+        let mut instance = PDTSPInstance::from_tsplib_str(&text).unwrap();
+        
+        assert_eq!(instance.backend.name(), "on-demand");
+        assert_eq!(instance.distance_matrix.mode(), DistanceMode::OnDemand);
+        assert!(instance.distance_matrix.memory_bytes() < n * 64);
+        
+        let neighbors = instance.nearest_neighbors(8);
+        for i in (0..n).step_by(500) {
+            let mut brute: Vec<f64> = (0..n).filter(|&j| j != i).map(|j| instance.distance(i, j)).collect();
+            brute.sort_by(f64::total_cmp);
+            let found: Vec<f64> = neighbors[i].iter().map(|&j| instance.distance(i, j)).collect();
+            assert_eq!(found, brute[..8].to_vec());
+        }
+        
+        // Road distances are not Euclidean: neighbor queries must scan them
+        instance.set_distance_provider(Arc::new(DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::OnDemand))).unwrap();
+        assert_eq!(instance.backend.name(), "precomputed");
+    }
+    
+    #[test]
+    fn test_instance_dialects() {
+        // Mosheiov: the last node is the return depot, even when its
+        // coordinates are written with a different precision
+        let mosheiov = "NAME: m\nDIMENSION: 5\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 2 0\n4 3 0\n5 0.001 0\nDEMAND_SECTION\n1 3\n2 2\n3 -4\n4 1\n5 -2\nEOF\n";
+        let instance = PDTSPInstance::from_str_with_dialect(mosheiov, InstanceDialect::Mosheiov).unwrap();
+        assert_eq!((instance.dimension, instance.return_depot_demand, instance.starting_load()), (4, -2, 3));
+        assert_eq!(PDTSPInstance::from_tsplib_str(mosheiov).unwrap().dimension, 5);
+        let exact = mosheiov.replace("5 0.001 0", "5 0 0");
+        assert_eq!(PDTSPInstance::from_tsplib_str(&exact).unwrap().dimension, 4);
+        let short = mosheiov.replace("DIMENSION: 5", "DIMENSION: 6");
+        assert!(PDTSPInstance::from_str_with_dialect(&short, InstanceDialect::Mosheiov).is_err());
+        
+        // TSP-LIB: a customer at the depot location is not a return depot,
+        // and a missing depot demand is zero
+        let tsplib = "NAME: t\nTYPE: CVRP\nDIMENSION: 4\nCAPACITY: 5\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 2 0\n4 0 0\nDEMAND_SECTION\n2 2\n3 -1\n4 -1\nDEPOT_SECTION\n1\n-1\nEOF\n";
+        let instance = PDTSPInstance::from_str_with_dialect(tsplib, InstanceDialect::Tsplib).unwrap();
+        assert_eq!((instance.dimension, instance.nodes[0].demand, instance.nodes[3].demand), (4, 0, -1));
+        assert_eq!(instance.return_depot_demand, 0);
+        assert_eq!(PDTSPInstance::from_tsplib_str(tsplib).unwrap().dimension, 3);
+        let moved = tsplib.replace("DEPOT_SECTION\n1\n", "DEPOT_SECTION\n5\n");
+        assert!(PDTSPInstance::from_str_with_dialect(&moved, InstanceDialect::Tsplib).is_err());
+        
+        // Li & Lim: detected automatically, depot demand dropped
+        let lilim = "25 200 1\n0 40 50 0 0 1236 0 0 0\n1 45 68 10 912 967 90 0 3\n2 45 70 -20 825 870 90 4 0\n3 42 66 -10 65 146 90 1 0\n4 42 68 20 727 782 90 0 2\n";
+        for dialect in [InstanceDialect::Auto, InstanceDialect::LiLim] {
+            let instance = PDTSPInstance::from_str_with_dialect(lilim, dialect).unwrap();
+            assert_eq!((instance.dimension, instance.capacity), (5, 200));
+            let demands: Vec<i32> = instance.nodes.iter().map(|n| n.demand).collect();
+            assert_eq!(demands, vec![0, 10, -20, -10, 20]);
+            assert!(instance.is_feasible(&[0, 1, 3, 4, 2]));
+            assert!(!instance.is_feasible(&[0, 2, 1, 3, 4]));
+        }
+        assert!(PDTSPInstance::from_lilim_str("25 200 1\n0 40 50 0 0 1236 0 0 0\n2 45 70 -20 825 870 90 4 0\n").is_err());
+    }
+    
+    #[test]
+    fn test_route_length_limit() {
+        let text = "NAME: r\nDIMENSION: 4\nCAPACITY: 5\nDISTANCE: 30\nNODE_COORD_SECTION\n1 0 0\n2 10 0\n3 10 10\n4 0 10\nDEMAND_SECTION\n1 0\n2 2\n3 1\n4 -1\nEOF\n";
+        let mut instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        assert_eq!(instance.max_route_length, Some(30.0));
+        assert_eq!(instance.route_lengths(&[0, 1, 2, 3]), vec![40.0]);
+        assert!(!instance.is_feasible(&[0, 1, 2, 3]));
+        assert!(instance.can_append(&[0], 0, 1));
+        assert!(!instance.can_append(&[0, 1], 2, 2));
+        assert!(instance.can_append(&[0, 1, 0], 0, 2));
+        
+        // Depot returns unload the vehicle, so the delivery of node 3 cannot
+        // follow a split
+        assert_eq!(instance.split_routes(&[0, 1, 2, 3]), None);
+        instance.nodes[3].demand = 1;
+        let split = instance.split_routes(&[0, 1, 2, 3]).unwrap();
+        assert_eq!(split, vec![0, 1, 0, 2, 0, 3]);
+        assert!(instance.is_feasible(&split));
+        assert!(instance.route_lengths(&split).iter().all(|&l| l <= 30.0));
+        
+        instance.max_route_length = None;
+        assert!(instance.is_feasible(&[0, 1, 2, 3]));
+        assert_eq!(instance.split_routes(&[0, 1, 2, 3]), Some(vec![0, 1, 2, 3]));
+    }
+    
+    #[test]
+    fn test_service_times_and_duration() {
+        let text = "NAME: s\nDIMENSION: 3\nCAPACITY: 5\nNODE_COORD_SECTION\n1 0 0\n2 3 0\n3 3 4\nDEMAND_SECTION\n1 0\n2 2\n3 -2\nSERVICE_TIME_SECTION\n1 1\n2 10\n3 5\nEOF\n";
+        let mut instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        assert_eq!(instance.nodes[2].service_time, 5.0);
+        assert!(instance.has_service_times());
+        let tour = [0, 1, 2];
+        assert_eq!(instance.tour_cost(&tour), 12.0);
+        instance.cost_function = CostFunction::Duration;
+        assert_eq!(instance.tour_service_time(&tour), 16.0);
+        assert_eq!(instance.tour_cost(&tour), 28.0);
+        assert_eq!(crate::evaluation::evaluate(&instance, &tour).cost, 28.0);
+        assert_eq!(crate::verification::reference_evaluate(&instance, &tour).cost, 28.0);
+        
+        instance.set_service_time(2.0);
+        assert_eq!(instance.nodes[0].service_time, 1.0);
+        assert_eq!(instance.tour_duration(&tour), 17.0);
+        
+        let lilim = PDTSPInstance::from_lilim_str("25 200 1\n0 40 50 0 0 1236 0 0 0\n1 45 68 -10 912 967 90 11 0\n2 45 70 10 825 870 90 0 1\n").unwrap();
+        assert_eq!(lilim.nodes[1].service_time, 90.0);
+    }
+    
+    #[test]
+    fn test_depot_section_and_open_tours() {
+        let text = "NAME: d\nDIMENSION: 4\nCAPACITY: 5\nNODE_COORD_SECTION\n1 1 0\n2 2 0\n3 0 0\n4 5 0\nDEMAND_SECTION\n1 2\n2 -1\n3 0\n4 -1\nDEPOT_SECTION\n3\n-1\nEOF\n";
+        let mut instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        assert_eq!(instance.depot_index, 2);
+        let coords: Vec<(f64, i32)> = instance.nodes.iter().map(|n| (n.x, n.demand)).collect();
+        assert_eq!(coords, vec![(0.0, 0), (2.0, -1), (1.0, 2), (5.0, -1)]);
+        assert_eq!((instance.file_index(0), instance.file_index(2), instance.file_index(3)), (2, 0, 3));
+        assert_eq!(instance.return_depot_demand, 0);
+        
+        let tour = [0, 2, 1, 3];
+        assert!(instance.is_feasible(&tour));
+        assert_eq!(instance.tour_length(&tour), 10.0);
+        instance.open_tour = true;
+        assert_eq!(instance.tour_length(&tour), 5.0);
+        instance.cost_function = CostFunction::Quadratic;
+        let cost = instance.tour_cost(&tour);
+        assert!((crate::evaluation::evaluate(&instance, &tour).cost - cost).abs() < 1e-9);
+        assert!((crate::verification::reference_evaluate(&instance, &tour).cost - cost).abs() < 1e-9);
+        
+        instance.cost_function = CostFunction::Distance;
+        let optimum = crate::exact::dynamic_programming::solve(&instance).unwrap();
+        assert_eq!(optimum.cost, 5.0);
+        assert!(crate::exact::bounds::lagrangian_1tree_bound(&instance) <= 5.0 + 1e-9);
+    }
+    
+    #[test]
+    fn test_split_demands() {
+        let text = "NAME: big\nDIMENSION: 4\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 3 0\n3 3 4\n4 0 4\nDEMAND_SECTION\n1 0\n2 13\n3 -13\n4 0\nEOF\n";
+        let mut instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        instance.nodes[1].profit = 7;
+        assert!(!instance.is_feasible(&[0, 1, 2, 3]));
+        assert!(instance.split_demands(Some(0)).is_err());
+        
+        assert_eq!(instance.split_demands(None).unwrap(), 2);
+        assert_eq!(instance.dimension, 6);
+        let demands: Vec<i32> = instance.nodes.iter().map(|n| n.demand).collect();
+        assert_eq!(demands, vec![0, 7, -7, 0, 6, -6]);
+        assert_eq!(instance.nodes[4].split_of, Some(1));
+        assert_eq!((instance.nodes[1].profit, instance.nodes[4].profit), (7, 0));
+        assert_eq!(instance.distance(1, 4), 0.0);
+        assert_eq!(instance.distance(4, 3), 5.0);
+        
+        let tour = [0, 1, 2, 4, 5, 3];
+        assert!(instance.is_feasible(&tour));
+        assert_eq!(instance.tour_length(&tour), 22.0);
+        assert_eq!(instance.original_tour(&tour), vec![0, 1, 2, 1, 2, 3]);
+        assert_eq!(instance.split_demands(None).unwrap(), 0);
+        
+        // Road distances and missing arcs survive the split
+        let mut graph = NeighborGraph::new(4);
+        for (i, j, length) in [(0, 1, 3.5), (1, 2, 4.5), (2, 3, 3.0), (3, 0, 4.0), (2, 1, 4.5), (1, 3, 9.0)] {
+            graph.add_arc(i, j, length);
+        }
+        for matrix in [
+            DistanceMatrix::from_rows((0..4).map(|i| (0..4).map(|j| (i * 4 + j) as f64).collect()).collect()).unwrap(),
+            DistanceMatrix::from_provider(Arc::new(RestrictedArcs::new(&graph))),
+        ] {
+            let mut instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+            instance.distance_matrix = matrix.clone();
+            instance.split_demands(None).unwrap();
+            assert_eq!(instance.distance_matrix.mode(), matrix.mode());
+            assert_eq!(instance.distance_matrix.is_restricted(), matrix.is_restricted());
+            for i in 0..6 {
+                for j in 0..6 {
+                    let (a, b) = (instance.original_node(i), instance.original_node(j));
+                    let expected = if a == b { 0.0 } else { matrix.get(a, b) };
+                    assert_eq!(instance.distance(i, j), expected);
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn test_explain_infeasibility() {
+        let text = "NAME: e\nDIMENSION: 4\nCAPACITY: 5\nNODE_COORD_SECTION\n1 0 0\n2 3 0\n3 3 4\n4 0 4\nDEMAND_SECTION\n1 0\n2 4\n3 -3\n4 3\nEOF\n";
+        let mut instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        assert!(instance.explain_infeasibility(&[0, 1, 2, 3]).is_empty());
+        
+        assert_eq!(instance.explain_infeasibility(&[0, 2, 1, 3]), vec![
+            Violation::NegativeLoad { position: 1, node: 2, load: -3 },
+        ]);
+        assert_eq!(instance.explain_infeasibility(&[0, 1, 3, 2]), vec![
+            Violation::Overload { position: 2, node: 3, load: 7, capacity: 5 },
+        ]);
+        assert_eq!(instance.explain_infeasibility(&[1, 1, 7]), vec![
+            Violation::WrongStart { node: Some(1) },
+            Violation::DuplicateNode { position: 1, node: 1 },
+            Violation::UnknownNode { position: 2, node: 7 },
+            Violation::MissingNode { node: 2 },
+            Violation::MissingNode { node: 3 },
+        ]);
+        
+        instance.max_route_length = Some(10.0);
+        let violations = instance.explain_infeasibility(&[0, 1, 2, 3]);
+        assert_eq!(violations, vec![Violation::RouteLength { route: 0, length: 14.0, limit: 10.0 }]);
+        assert_eq!(violations[0].to_string(), "route 0: length 14.00 above the limit 10.00");
+        let mut solution = crate::solution::Solution::from_tour(&instance, vec![0, 1, 2, 3], "test");
+        solution.validate(&instance);
+        assert!(!solution.feasible && solution.violations == violations);
+    }
+    
+    #[test]
+    fn test_strict_feasibility() {
+        let text = "NAME: p\nDIMENSION: 4\nCAPACITY: 5\nNODE_COORD_SECTION\n1 0 0\n2 3 0\n3 3 4\n4 0 4\nDEMAND_SECTION\n1 0\n2 2\n3 -2\n4 0\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        // Loads stay in range although node 3 is skipped or node 1 repeated
+        for tour in [vec![0, 1, 2], vec![0, 1, 2, 1, 2], vec![0, 1, 2, 3, 3]] {
+            assert!(instance.is_feasible(&tour));
+            assert!(!instance.is_feasible_strict(&tour));
+        }
+        assert!(instance.is_feasible_strict(&[0, 1, 2, 3]));
+        assert!(instance.is_feasible_strict(&[0, 1, 2, 0, 3]));
+        assert!(!instance.is_complete_tour(&[0, 1, 2, 9]));
+        
+        let mut solution = crate::solution::Solution::from_tour(&instance, vec![0, 1, 2, 1, 2], "test");
+        assert!(solution.feasible);
+        solution.validate(&instance);
+        assert!(!solution.feasible);
+        assert_eq!(solution.violations[0], Violation::DuplicateNode { position: 3, node: 1 });
     }
 }
@@ -0,0 +1,275 @@
+//! Structural analysis of an instance.
+//!
+//! `analyze` gathers what the `analyze` subcommand reports: the pickup and
+//! delivery balance, how tight the capacity is for random visiting orders,
+//! a certificate on whether a feasible single tour can exist, the spatial
+//! clustering of the customers and the metric properties of the distances.
+//! The result serializes to JSON, so instance sets can be profiled by scripts.
+
+use super::{InstanceStatistics, MetricReport, PDTSPInstance};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Demands of the customers (depot excluded)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DemandBalance {
+    pub pickups: usize,
+    pub deliveries: usize,
+    pub neutral: usize,
+    /// Sum of the pickup demands
+    pub total_pickup: i32,
+    /// Sum of the delivery demands, as a positive quantity
+    pub total_delivery: i32,
+    /// Net customer demand (pickups minus deliveries)
+    pub net: i32,
+    /// Load of the vehicle when it leaves the depot
+    pub starting_load: i32,
+    pub mean_abs_demand: f64,
+    pub max_abs_demand: i32,
+}
+
+/// Load span of random customer orders: the highest minus the lowest load
+/// along the order (starting load included), relative to the capacity. A
+/// ratio above 1 means no starting load could make that order feasible.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapacityTightness {
+    pub samples: usize,
+    pub mean_peak_ratio: f64,
+    pub max_peak_ratio: f64,
+    /// Fraction of the random orders that are feasible as they are
+    pub feasible_fraction: f64,
+}
+
+/// Whether a feasible single tour can exist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum FeasibilityCertificate {
+    /// A feasible visiting order, depot first
+    Feasible { tour: Vec<usize> },
+    /// A necessary condition fails
+    Infeasible { reason: String },
+    /// The necessary conditions hold but no feasible order was found
+    Unknown,
+}
+
+/// Full analysis of an instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceAnalysis {
+    pub statistics: InstanceStatistics,
+    pub demand: DemandBalance,
+    pub tightness: CapacityTightness,
+    pub feasibility: FeasibilityCertificate,
+    /// Clark-Evans ratio of the customer locations: mean nearest-neighbor
+    /// distance over its expectation for uniformly random points (< 1
+    /// clustered, about 1 random, > 1 regular); `None` without spread
+    pub clustering: Option<f64>,
+    pub metric: MetricReport,
+}
+
+impl InstanceAnalysis {
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Cannot serialize analysis: {}", e))
+    }
+}
+
+/// Analyze `instance` with 1000 random orders for the capacity tightness
+pub fn analyze(instance: &PDTSPInstance) -> InstanceAnalysis {
+    analyze_with(instance, 1000, 0)
+}
+
+/// Analyze `instance`, sampling `samples` random orders with `seed`
+pub fn analyze_with(instance: &PDTSPInstance, samples: usize, seed: u64) -> InstanceAnalysis {
+    InstanceAnalysis {
+        statistics: instance.statistics(),
+        demand: demand_balance(instance),
+        tightness: capacity_tightness(instance, samples, seed),
+        feasibility: feasibility_certificate(instance),
+        clustering: clustering(instance),
+        metric: instance.analyze_metric(),
+    }
+}
+
+pub fn demand_balance(instance: &PDTSPInstance) -> DemandBalance {
+    let customers = || instance.nodes.iter().skip(1).map(|node| node.demand);
+    let total_pickup = customers().filter(|&d| d > 0).sum();
+    let total_delivery = -customers().filter(|&d| d < 0).sum::<i32>();
+    let count = instance.nodes.len().saturating_sub(1);
+    DemandBalance {
+        pickups: customers().filter(|&d| d > 0).count(),
+        deliveries: customers().filter(|&d| d < 0).count(),
+        neutral: customers().filter(|&d| d == 0).count(),
+        total_pickup,
+        total_delivery,
+        net: total_pickup - total_delivery,
+        starting_load: instance.starting_load(),
+        mean_abs_demand: if count > 0 { customers().map(|d| d.abs() as f64).sum::<f64>() / count as f64 } else { 0.0 },
+        max_abs_demand: customers().map(i32::abs).max().unwrap_or(0),
+    }
+}
+
+pub fn capacity_tightness(instance: &PDTSPInstance, samples: usize, seed: u64) -> CapacityTightness {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut order: Vec<usize> = (0..instance.dimension).collect();
+    let capacity = instance.capacity.max(1) as f64;
+    let (mut total, mut max, mut feasible) = (0.0, 0.0f64, 0usize);
+    for _ in 0..samples {
+        order[1..].shuffle(&mut rng);
+        let mut load = instance.starting_load();
+        let (mut low, mut high) = (load, load);
+        for &node in &order[1..] {
+            load += instance.nodes[node].demand;
+            low = low.min(load);
+            high = high.max(load);
+        }
+        let ratio = (high - low) as f64 / capacity;
+        total += ratio;
+        max = max.max(ratio);
+        if instance.is_feasible(&order) {
+            feasible += 1;
+        }
+    }
+    CapacityTightness {
+        samples,
+        mean_peak_ratio: if samples > 0 { total / samples as f64 } else { 0.0 },
+        max_peak_ratio: max,
+        feasible_fraction: if samples > 0 { feasible as f64 / samples as f64 } else { 0.0 },
+    }
+}
+
+/// Check the necessary conditions (every demand and the starting and final
+/// loads fit in the vehicle), then look for a feasible order greedily: the
+/// next customer is one that keeps the load in range, preferring the load
+/// closest to half the capacity, or else the largest demand
+pub fn feasibility_certificate(instance: &PDTSPInstance) -> FeasibilityCertificate {
+    let capacity = instance.capacity;
+    let start = instance.starting_load();
+    if start > capacity {
+        return FeasibilityCertificate::Infeasible {
+            reason: format!("Starting load {} exceeds the capacity {}", start, capacity),
+        };
+    }
+    if let Some(node) = instance.nodes.iter().skip(1).find(|node| node.demand.abs() > capacity) {
+        return FeasibilityCertificate::Infeasible {
+            reason: format!("Node {} has demand {} beyond the capacity {}", node.id, node.demand, capacity),
+        };
+    }
+    let end = start + instance.nodes.iter().skip(1).map(|node| node.demand).sum::<i32>();
+    if end < 0 || end > capacity {
+        return FeasibilityCertificate::Infeasible {
+            reason: format!("The load after the last customer is {}, outside [0, {}]", end, capacity),
+        };
+    }
+
+    let rules: [fn(i32, i32, i32) -> i64; 2] = [
+        |load, demand, capacity| (2 * (load + demand) - capacity).abs() as i64,
+        |_, demand, _| -(demand.abs() as i64),
+    ];
+    for rule in rules {
+        let mut remaining: Vec<usize> = (1..instance.dimension).collect();
+        let mut tour = vec![0];
+        let mut load = start;
+        while let Some(k) = (0..remaining.len())
+            .filter(|&k| (0..=capacity).contains(&(load + instance.nodes[remaining[k]].demand)))
+            .min_by_key(|&k| rule(load, instance.nodes[remaining[k]].demand, capacity))
+        {
+            let node = remaining.swap_remove(k);
+            load += instance.nodes[node].demand;
+            tour.push(node);
+        }
+        if remaining.is_empty() && instance.is_feasible(&tour) {
+            return FeasibilityCertificate::Feasible { tour };
+        }
+    }
+    FeasibilityCertificate::Unknown
+}
+
+/// Clark-Evans ratio of the customer coordinates (see `InstanceAnalysis`)
+pub fn clustering(instance: &PDTSPInstance) -> Option<f64> {
+    let customers = instance.nodes.get(1..)?;
+    if customers.len() < 2 {
+        return None;
+    }
+    let (min_x, max_x) = customers.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), n| (lo.min(n.x), hi.max(n.x)));
+    let (min_y, max_y) = customers.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), n| (lo.min(n.y), hi.max(n.y)));
+    let area = (max_x - min_x) * (max_y - min_y);
+    if area <= 0.0 {
+        return None;
+    }
+
+    let neighbors = instance.nearest_neighbors(2);
+    let mut total = 0.0;
+    for node in customers.iter() {
+        // Nearest other customer (the depot is not part of the pattern)
+        let Some(&other) = neighbors[node.id].iter().find(|&&j| j != 0) else {
+            continue;
+        };
+        total += (node.x - instance.nodes[other].x).hypot(node.y - instance.nodes[other].y);
+    }
+    let observed = total / customers.len() as f64;
+    let expected = 0.5 * (area / customers.len() as f64).sqrt();
+    Some(observed / expected)
+}
+
+impl fmt::Display for FeasibilityCertificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeasibilityCertificate::Feasible { .. } => write!(f, "feasible (greedy order found)"),
+            FeasibilityCertificate::Infeasible { reason } => write!(f, "infeasible: {}", reason),
+            FeasibilityCertificate::Unknown => write!(f, "unknown (necessary conditions hold, no order found)"),
+        }
+    }
+}
+
+impl fmt::Display for InstanceAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.statistics)?;
+        let demand = &self.demand;
+        writeln!(f, "\nDemand Balance:")?;
+        writeln!(f, "  Pickup nodes: {} (total: {})", demand.pickups, demand.total_pickup)?;
+        writeln!(f, "  Delivery nodes: {} (total: {})", demand.deliveries, demand.total_delivery)?;
+        writeln!(f, "  Neutral nodes: {}", demand.neutral)?;
+        writeln!(f, "  Net demand: {} (starting load: {})", demand.net, demand.starting_load)?;
+        writeln!(f, "  Mean |demand|: {:.2}, max: {}", demand.mean_abs_demand, demand.max_abs_demand)?;
+        let tightness = &self.tightness;
+        writeln!(f, "\nCapacity Tightness ({} random orders):", tightness.samples)?;
+        writeln!(f, "  Load span / capacity: mean {:.2}, max {:.2}", tightness.mean_peak_ratio, tightness.max_peak_ratio)?;
+        writeln!(f, "  Feasible random orders: {:.1}%", 100.0 * tightness.feasible_fraction)?;
+        writeln!(f, "  Single tour: {}", self.feasibility)?;
+        writeln!(f, "\nSpatial Distribution:")?;
+        match self.clustering {
+            Some(ratio) => writeln!(f, "  Clark-Evans ratio: {:.3}", ratio)?,
+            None => writeln!(f, "  Clark-Evans ratio: n/a")?,
+        }
+        writeln!(f, "\nMetric Analysis:")?;
+        for line in self.metric.to_string().lines() {
+            writeln!(f, "  {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analysis_certificates() {
+        let text = "NAME: tiny\nDIMENSION: 5\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 10 0\n3 0 10\n4 10 10\n5 5 5\nDEMAND_SECTION\n1 0\n2 -6\n3 8\n4 -2\n5 0\nEOF\n";
+        let mut instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let analysis = analyze_with(&instance, 50, 1);
+        assert_eq!((analysis.demand.pickups, analysis.demand.deliveries, analysis.demand.neutral), (1, 2, 1));
+        assert_eq!(analysis.demand.net, 0);
+        let FeasibilityCertificate::Feasible { tour } = &analysis.feasibility else {
+            panic!("expected a feasible order, got {:?}", analysis.feasibility);
+        };
+        assert!(instance.is_feasible(tour));
+        assert!(analysis.tightness.feasible_fraction > 0.0 && analysis.tightness.feasible_fraction < 1.0);
+        assert!(analysis.clustering.is_some());
+        assert!(analysis.to_json().unwrap().contains("\"status\": \"feasible\""));
+
+        instance.capacity = 7;
+        assert!(matches!(feasibility_certificate(&instance), FeasibilityCertificate::Infeasible { .. }));
+    }
+}
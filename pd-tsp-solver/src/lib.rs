@@ -35,11 +35,32 @@
 //! ```
 
 pub mod instance;
+pub mod distance;
+pub mod vehicle;
 pub mod solution;
+pub mod pool;
+pub mod tour;
+pub mod stochastic;
+pub mod portfolio;
+pub mod evaluation;
 pub mod heuristics;
 pub mod exact;
 pub mod benchmark;
+pub mod campaign;
+pub mod best_known;
 pub mod visualization;
+pub mod run_info;
+pub mod dashboard;
+pub mod progress;
+pub mod verification;
+pub mod experiment;
+pub mod config;
+pub mod tuning;
+pub mod run_record;
+pub mod wasm;
+pub mod server;
+pub mod store;
 
 pub use instance::PDTSPInstance;
 pub use solution::Solution;
+pub use run_info::RunInfo;
@@ -2,21 +2,53 @@
 //! 
 //! A comprehensive solver for the Pickup and Delivery Traveling Salesman Problem.
 
-use clap::{Parser, Subcommand, ValueEnum};
-use pd_tsp_solver::instance::PDTSPInstance;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use tracing_subscriber::EnvFilter;
+use std::io::IsTerminal;
+use pd_tsp_solver::instance::{analysis, InstanceDialect, PDTSPInstance, Rounding, Violation};
 use pd_tsp_solver::solution::Solution;
+use pd_tsp_solver::pool::SolutionPool;
+use pd_tsp_solver::stochastic::{simulate, ChanceConstraint, DemandDistribution, StochasticDemands};
 use pd_tsp_solver::heuristics::construction::*;
 use pd_tsp_solver::heuristics::local_search::*;
+use pd_tsp_solver::heuristics::vns::GeneralVNS;
+use pd_tsp_solver::heuristics::acceptance::Acceptance;
+use pd_tsp_solver::heuristics::hybrid::TimeBudget;
+use pd_tsp_solver::heuristics::matheuristic::WindowReoptimization;
+use pd_tsp_solver::heuristics::popmusic::Popmusic;
 use pd_tsp_solver::heuristics::genetic::{GeneticAlgorithm, GAConfig, MemeticAlgorithm};
-use pd_tsp_solver::heuristics::aco::{AntColonyOptimization, ACOConfig, MaxMinAntSystem};
+use pd_tsp_solver::heuristics::aco::{AntColonyOptimization, ACOConfig, MaxMinAntSystem, PheromoneStrategy};
 use pd_tsp_solver::heuristics::profit_density::ProfitDensityHeuristic;
-use pd_tsp_solver::exact::{GurobiSolver, GurobiConfig};
-use pd_tsp_solver::benchmark::{Benchmark, BenchmarkConfig, load_instances_from_dir};
+use pd_tsp_solver::heuristics::pareto::EpsilonConstraint;
+use pd_tsp_solver::exact::{GurobiSolver, GurobiConfig, dynamic_programming};
+use pd_tsp_solver::best_known::{problem_key, BestKnownRegistry};
+use pd_tsp_solver::campaign::CampaignSpec;
+use pd_tsp_solver::benchmark::{Benchmark, BenchmarkConfig, JobKind, EXACT_KEY, GVNS_KEY, parse_algorithm_selection, evaluate_robustness, load_instances_from_dir, restart_until_budget};
+use pd_tsp_solver::heuristics::stopping::Deadline;
+use pd_tsp_solver::heuristics::rng::RngFactory;
 use pd_tsp_solver::visualization::Visualizer;
+use pd_tsp_solver::run_info::RunInfo;
+use pd_tsp_solver::run_record::{self, RunRecord};
+use pd_tsp_solver::vehicle::VehicleProfile;
+use pd_tsp_solver::dashboard::{Dashboard, DashboardRun};
+use pd_tsp_solver::verification;
+use pd_tsp_solver::experiment::{run_algorithm, Experiment, ExperimentSpec};
+use pd_tsp_solver::config::SolverConfig;
+use pd_tsp_solver::portfolio::{InstanceFeatures, Portfolio};
+use pd_tsp_solver::tuning::{Tuner, TuningSpec};
+use pd_tsp_solver::server::{self, SolverService};
+use pd_tsp_solver::store;
+use pd_tsp_solver::distance::road::RoadMatrix;
+use pd_tsp_solver::distance::graph::{CompletionMethod, NeighborGraph, RestrictedArcs, ShortestPathCompletion};
+use pd_tsp_solver::distance::DistanceProvider;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Instant;
 
+/// Violations of an infeasible tour listed in the results
+const MAX_VIOLATIONS_SHOWN: usize = 10;
+
 #[derive(Parser)]
 #[command(name = "pd-tsp-solver")]
 #[command(author = "M2 AI2D Student")]
@@ -25,107 +57,450 @@ use std::time::Instant;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    
+    /// Format of the log events written to stderr; levels are set per module
+    /// with RUST_LOG (e.g. `RUST_LOG=pd_tsp_solver::heuristics::genetic=debug`)
+    #[arg(long, global = true, value_enum, default_value = "pretty")]
+    log_format: LogFormat,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum LogFormat {
+    /// Human-readable lines
+    Pretty,
+    /// One JSON object per event, with its fields and spans
+    Json,
 }
 
+// Parsed once at startup: the size of the `Solve` variant does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
-    Solve {
+    /// Solve an instance with one algorithm
+    Solve(SolveOptions),
+    
+    /// Run benchmarks on a directory of instances
+    Benchmark(BenchmarkOptions),
+    
+    /// Analyze an instance
+    Analyze {
+        /// Path to the instance file
         #[arg(short, long)]
         instance: PathBuf,
         
-        /// Algorithm to use
-        #[arg(short, long, value_enum, default_value = "hybrid")]
-        algorithm: Algorithm,
-        
-        /// Cost function: distance, quadratic, or linear-load
-        #[arg(long, value_enum, default_value = "distance")]
-        cost_function: CostFunction,
+        /// Rounding of Euclidean distances (nint: TSPLIB integer distances)
+        #[arg(long, value_enum, default_value = "exact")]
+        rounding: DistanceRounding,
         
-        /// Alpha parameter: linear weight applied to absolute load (used by linear-load
-        /// and as the linear term in quadratic cost)
-        #[arg(long, default_value = "0.1")]
-        alpha: f64,
-
-        /// Beta parameter: quadratic weight applied to load^2 (used by quadratic cost)
-        #[arg(long, default_value = "0.0")]
-        beta: f64,
+        /// Depot convention of the instance file
+        #[arg(long, value_enum, default_value = "auto")]
+        dialect: Dialect,
         
-        /// Time limit in seconds
-        #[arg(short, long, default_value = "60")]
-        time_limit: f64,
+        /// Print the analysis as JSON (without the quick solution estimates)
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Compare algorithms on an instance
+    Compare {
+        /// Path to the instance file
+        #[arg(short, long)]
+        instance: PathBuf,
         
-        /// Random seed
-        #[arg(short, long, default_value = "42")]
-        seed: u64,
+        /// Number of runs
+        #[arg(short, long, default_value = "10")]
+        runs: usize,
         
-        /// Output solution to file
+        /// Output CSV file
         #[arg(short, long)]
         output: Option<PathBuf>,
         
-        /// Generate SVG visualization
+        /// Output HTML dashboard file
         #[arg(long)]
-        visualize: bool,
+        html: Option<PathBuf>,
         
-        /// Verbose output
-        #[arg(short, long)]
-        verbose: bool,
-        /// Maximum random profit to assign (10..=max). 0 means keep existing profits.
-        #[arg(long, default_value = "200")]
-        max_profit: i32,
+        /// Give every algorithm the same wall-clock budget (seconds) per run,
+        /// restarting it with new seeds until the budget is spent
+        #[arg(long)]
+        time_budget: Option<f64>,
+        
+        /// Compare only these algorithms (local-search, sa, tabu, ils, gvns,
+        /// ga, memetic, aco; vnd is accepted for local-search) [default: all]
+        #[arg(long, value_delimiter = ',')]
+        algorithms: Vec<String>,
     },
     
-    /// Run benchmarks on a directory of instances
-    Benchmark {
-        /// Directory containing instance files
+    /// Evaluate a fixed solution on randomly perturbed variants of an instance
+    Robustness {
+        /// Path to the instance file
         #[arg(short, long)]
-        dir: PathBuf,
+        instance: PathBuf,
         
-        /// Output directory for results
-        #[arg(short, long, default_value = "results")]
-        output: PathBuf,
+        /// Solution JSON (as written by `solve -o`); solved with `--algorithm` if absent
+        #[arg(long)]
+        solution: Option<PathBuf>,
         
-        /// Number of runs per algorithm
-        #[arg(short, long, default_value = "5")]
-        runs: usize,
+        /// Algorithm producing the solution when none is given (experiment names: vnd, ils, ...)
+        #[arg(short, long, default_value = "vnd")]
+        algorithm: String,
         
-        /// Time limit per run
+        /// Time limit of that algorithm
         #[arg(short, long, default_value = "60")]
         time_limit: f64,
         
-        /// Run exact solver (requires Gurobi)
-        #[arg(long)]
-        exact: bool,
+        /// Number of perturbed scenarios
+        #[arg(long, default_value = "100")]
+        scenarios: usize,
         
-        /// Exact solver time limit
-        #[arg(long, default_value = "300")]
-        exact_time_limit: f64,
+        /// Maximum coordinate displacement, as a fraction of the instance extent
+        #[arg(long, default_value = "0.0")]
+        coordinate_noise: f64,
         
-        /// Maximum instance size
-        #[arg(long)]
-        max_size: Option<usize>,
-    },
-    
-    /// Analyze an instance
-    Analyze {
-        /// Path to the instance file
+        /// Maximum relative change of each demand
+        #[arg(long, default_value = "0.1")]
+        demand_noise: f64,
+        
+        /// Seed of the first scenario (and of the algorithm)
+        #[arg(short, long, default_value = "0")]
+        seed: u64,
+        
+        /// Output CSV file, one line per scenario
         #[arg(short, long)]
-        instance: PathBuf,
+        output: Option<PathBuf>,
     },
     
-    /// Compare algorithms on an instance
-    Compare {
+    /// Pareto front of travel cost against collected profit (epsilon-constraint sweep)
+    Pareto {
         /// Path to the instance file
         #[arg(short, long)]
         instance: PathBuf,
         
-        /// Number of runs
-        #[arg(short, long, default_value = "10")]
-        runs: usize,
+        /// Algorithm producing the starting tour over every customer (experiment names: vnd, ils, ...)
+        #[arg(short, long, default_value = "vnd")]
+        algorithm: String,
         
-        /// Output CSV file
+        /// Time limit of the sweep (the starting tour gets the same budget)
+        #[arg(short, long, default_value = "30")]
+        time_limit: f64,
+        
+        /// Number of profit targets, evenly spaced from the total profit to zero
+        #[arg(long, default_value = "10")]
+        levels: usize,
+        
+        /// Maximum random profit to assign when the instance has none (10..=max)
+        #[arg(long, default_value = "100")]
+        max_profit: i32,
+        
+        /// Random seed
+        #[arg(short, long, default_value = "0")]
+        seed: u64,
+        
+        /// Output CSV file, one line per solution of the front
         #[arg(short, long)]
         output: Option<PathBuf>,
+        
+        /// Save a plot of the front as SVG
+        #[arg(long)]
+        svg: Option<PathBuf>,
     },
+    
+    /// Run an experiment spec and regenerate every report artifact
+    ReportRun {
+        /// Experiment spec (TOML)
+        #[arg(short, long)]
+        spec: PathBuf,
+    },
+    
+    /// Tune the parameters of a metaheuristic on training instances
+    Tune {
+        /// Tuning spec (TOML)
+        #[arg(short, long)]
+        spec: PathBuf,
+        
+        /// Directory of training instances (overrides the spec's patterns)
+        #[arg(short, long)]
+        instances: Option<PathBuf>,
+        
+        /// Output file of the best configuration
+        #[arg(short, long, default_value = "tuned.toml")]
+        output: PathBuf,
+    },
+    
+    /// Serve a JSON HTTP API to submit instances, run asynchronous solves
+    /// and poll their progress (requires the `server` feature)
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+
+        /// Number of jobs solved at the same time (one per core if omitted)
+        #[arg(long)]
+        workers: Option<usize>,
+    },
+}
+
+/// Options of the `solve` command
+#[derive(Args)]
+struct SolveOptions {
+    #[arg(short, long)]
+    instance: PathBuf,
+    
+    /// Algorithm to use
+    #[arg(short, long, value_enum, default_value = "hybrid")]
+    algorithm: Algorithm,
+    
+    /// Cost function: distance, quadratic, or linear-load [default: distance]
+    #[arg(long, value_enum)]
+    cost_function: Option<CostFunction>,
+    
+    /// Alpha parameter: linear weight applied to absolute load (used by linear-load
+    /// and as the linear term in quadratic cost) [default: 0.1]
+    #[arg(long)]
+    alpha: Option<f64>,
+
+    /// Beta parameter: quadratic weight applied to load^2 (used by quadratic cost) [default: 0]
+    #[arg(long)]
+    beta: Option<f64>,
+    
+    /// Time limit in seconds [default: 60]
+    #[arg(short, long)]
+    time_limit: Option<f64>,
+    
+    /// Random seed [default: 42]
+    #[arg(short, long)]
+    seed: Option<u64>,
+    
+    /// Output solution to file
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    
+    /// Format of the output file
+    #[arg(long, value_enum, default_value = "json")]
+    format: OutputFormat,
+    
+    /// Keep the best N distinct solutions of the run and write them all to
+    /// the output file (JSON `pool` array, or one `<name>.poolK.tour` per
+    /// alternate with the tour format)
+    #[arg(long, default_value = "1")]
+    pool_size: usize,
+    
+    /// Generate SVG visualization
+    #[arg(long)]
+    visualize: bool,
+    
+    /// Write an interactive HTML view of the tour (pan/zoom, node tooltips, loads)
+    #[arg(long)]
+    html: Option<PathBuf>,
+    
+    /// Write an animated SVG of the best tour along the search (SA, ILS, GA,
+    /// memetic), with its frames in a `<name>_frames` directory
+    #[arg(long)]
+    animate: Option<PathBuf>,
+    
+    /// Write the nodes and tour as GeoJSON (coordinates read as longitude/latitude)
+    #[arg(long)]
+    geojson: Option<PathBuf>,
+    
+    /// Verbose output
+    #[arg(short, long)]
+    verbose: bool,
+    /// Maximum random profit to assign (10..=max). 0 means keep existing profits.
+    #[arg(long, default_value = "200")]
+    max_profit: i32,
+    
+    /// Vehicle profile (JSON) applied to the cost evaluation
+    #[arg(long)]
+    vehicle_profile: Option<PathBuf>,
+    
+    /// Save the prepared instance (with profits and cost settings) as JSON
+    #[arg(long)]
+    save_instance: Option<PathBuf>,
+    
+    /// Cross-check the final solution against the reference evaluator
+    #[arg(long)]
+    verify: bool,
+    
+    /// How Euclidean distances are served (auto: on demand above 5000 nodes)
+    #[arg(long, value_enum, default_value = "auto")]
+    backend: Backend,
+    
+    /// Rounding of Euclidean distances (nint: TSPLIB integer distances)
+    #[arg(long, value_enum, default_value = "exact")]
+    rounding: DistanceRounding,
+    
+    /// Depot convention of the instance file
+    #[arg(long, value_enum, default_value = "auto")]
+    dialect: Dialect,
+    
+    /// Normally distributed demands with this relative standard deviation:
+    /// tours must respect the capacity in all but a fraction --chance-epsilon
+    /// of 200 sampled scenarios, and the final tour is evaluated by
+    /// Monte-Carlo simulation over 1000 scenarios
+    #[arg(long)]
+    demand_std: Option<f64>,
+    
+    /// Largest accepted capacity violation probability under --demand-std
+    #[arg(long, default_value_t = 0.05)]
+    chance_epsilon: f64,
+    
+    /// Maximum length of every route between depot visits (overrides the DISTANCE header)
+    #[arg(long)]
+    max_route_length: Option<f64>,
+    
+    /// Service time of every customer (overrides the SERVICE_TIME_SECTION)
+    #[arg(long)]
+    service_time: Option<f64>,
+    
+    /// Open tour: the vehicle does not return to the depot after the last customer
+    #[arg(long)]
+    open_tour: bool,
+    
+    /// Serve demands larger than this size (the capacity if omitted) over several visits
+    #[arg(long, value_name = "SIZE")]
+    split_demand: Option<Option<i32>>,
+    
+    /// Road-network matrix (CSV or Parquet, keyed by node id) replacing Euclidean distances
+    #[arg(long)]
+    road_matrix: Option<PathBuf>,
+    
+    /// Column of the road matrix used as travel cost
+    #[arg(long, value_enum, default_value = "distance")]
+    road_metric: RoadMetric,
+    
+    /// Arc list (CSV or Parquet) of a sparse neighbor graph: only these arcs exist
+    #[arg(long)]
+    arc_graph: Option<PathBuf>,
+    
+    /// How arcs missing from the neighbor graph are handled
+    #[arg(long, value_enum, default_value = "restricted")]
+    arc_mode: ArcMode,
+    
+    /// Hybrid: fractions of the time limit for construction, VND and ILS
+    /// (unused when the config file sets a pipeline)
+    #[arg(long, value_delimiter = ',', default_value = "0.2,0.3,0.5")]
+    hybrid_budget: Vec<f64>,
+    
+    /// Simulated annealing: calibrate the temperatures on the instance
+    #[arg(long)]
+    auto_temp: bool,
+    
+    /// Simulated annealing: cross capacity-infeasible tours under an adaptive
+    /// penalty (SA only; the other algorithms stay within the capacity)
+    #[arg(long)]
+    penalty: bool,
+    
+    /// ILS: acceptance criterion of the new local optima (default: better)
+    #[arg(long, value_enum)]
+    acceptance: Option<IlsAcceptance>,
+    
+    /// VND, ejection-chain, window-reopt, ILS, VNS and hybrid: operator sequence of
+    /// the descent (two-opt, swap, relocation, or-opt, depot-reinsertion,
+    /// cross-exchange, two-h-opt, load-shift, ejection-chain)
+    #[arg(long, value_delimiter = ',')]
+    vnd_operators: Vec<String>,
+    
+    /// VND, ejection-chain, window-reopt, ILS, VNS and hybrid: try the operators of
+    /// the descent by recent improvement per second
+    #[arg(long)]
+    adaptive_vnd: bool,
+    
+    /// Auto: algorithm selection table learned by `benchmark --portfolio`
+    /// (without it, auto runs hybrid)
+    #[arg(long)]
+    portfolio: Option<PathBuf>,
+    
+    /// Solver configuration (TOML or YAML), e.g. the output of `tune`;
+    /// command-line options take precedence over it
+    #[arg(long)]
+    config: Option<PathBuf>,
+    
+    /// Append a JSON Lines record of the run to this file
+    #[arg(long)]
+    record: Option<PathBuf>,
+}
+
+/// Options of the `benchmark` command
+#[derive(Args)]
+struct BenchmarkOptions {
+    /// Directory containing instance files
+    #[arg(short, long, required_unless_present = "campaign")]
+    dir: Option<PathBuf>,
+    
+    /// Campaign spec (TOML): instance globs, algorithms with their own
+    /// parameters, seeds or run counts and time limits, replacing the
+    /// instance directory, algorithm selection and run settings
+    #[arg(long, conflicts_with_all = ["dir", "runs", "time_limit", "exact", "config", "algorithms"])]
+    campaign: Option<PathBuf>,
+    
+    /// Output directory for results [default: the campaign's, or results]
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    
+    /// Number of runs per algorithm, with seeds 0..runs [default: 5]
+    #[arg(short, long)]
+    runs: Option<usize>,
+    
+    /// Time limit per run [default: 60]
+    #[arg(short, long)]
+    time_limit: Option<f64>,
+    
+    /// Run exact solver (requires Gurobi)
+    #[arg(long)]
+    exact: bool,
+    
+    /// Exact solver time limit
+    #[arg(long, default_value = "300")]
+    exact_time_limit: f64,
+    
+    /// Maximum instance size
+    #[arg(long)]
+    max_size: Option<usize>,
+    
+    /// Also write an HTML dashboard (dashboard.html) to the output directory
+    #[arg(long)]
+    html: bool,
+    
+    /// Relative gap to the best known cost defining the time-to-target plots
+    #[arg(long, default_value = "0.01")]
+    target_gap: f64,
+    
+    /// Best known solutions registry, read at start and updated with every improvement
+    #[arg(long, default_value = "best_known.json")]
+    best_known: PathBuf,
+    
+    /// Solver configuration (TOML or YAML): cost function, seeds and
+    /// algorithm parameters; command-line options take precedence over it
+    #[arg(long)]
+    config: Option<PathBuf>,
+    
+    /// Worker threads running the (instance, algorithm, seed) jobs in parallel (0 = one per CPU)
+    #[arg(short, long, default_value = "1")]
+    jobs: usize,
+    
+    /// Unrecorded runs of the construction and local search algorithms on
+    /// the first instance, made before the measured runs
+    #[arg(long, default_value = "1")]
+    warmup: usize,
+    
+    /// Learn an algorithm selection table for `solve -a auto` from the
+    /// results and write it to this file
+    #[arg(long)]
+    portfolio: Option<PathBuf>,
+    
+    /// Run only these algorithms (construction, local-search, sa, tabu,
+    /// ils, ga, memetic, aco, mmas, exact) [default: all]
+    #[arg(long, value_delimiter = ',')]
+    algorithms: Vec<String>,
+    
+    /// Append the runs to this SQLite result database (requires the
+    /// `sqlite` feature)
+    #[arg(long)]
+    store: Option<PathBuf>,
+    
+    /// Pool the routes of every run of an instance and add the best
+    /// combination of pooled routes, found by set partitioning
+    #[arg(long)]
+    set_partitioning: bool,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
@@ -140,20 +515,37 @@ enum Algorithm {
     Sweep,
     /// Regret Insertion
     Regret,
+    /// GRASP construction with reactive alpha
+    Grasp,
     /// Cluster-First algorithm
     ClusterFirst,
+    /// MST + odd-degree matching tour with capacity repair
+    Christofides,
     /// Multi-start construction
     MultiStart,
     /// 2-Opt local search
     TwoOpt,
     /// Variable Neighborhood Descent
     Vnd,
+    /// VND extended with ejection chains
+    EjectionChain,
+    /// VND, then exact reoptimization of sliding windows of the tour by
+    /// dynamic programming
+    WindowReopt,
+    /// POPMUSIC: VND on sub-paths of a nearest neighbor tour, for large instances
+    Popmusic,
     /// Simulated Annealing
     Sa,
+    /// Threshold Accepting
+    ThresholdAccepting,
+    /// Record-to-Record Travel
+    RecordToRecord,
     /// Tabu Search
     Tabu,
     /// Iterated Local Search
     Ils,
+    /// General Variable Neighborhood Search
+    Vns,
     /// Genetic Algorithm
     Ga,
     /// Memetic Algorithm
@@ -162,12 +554,28 @@ enum Algorithm {
     Aco,
     /// Max-Min Ant System
     Mmas,
+    /// Rank-based Ant System
+    RankAs,
+    /// Elitist Ant System
+    ElitistAs,
     /// Hybrid (best combination)
     Hybrid,
     /// Profit-density construction heuristic
     ProfitDensity,
     /// Exact solver (Gurobi)
     Exact,
+    /// Exact dynamic programming (Held-Karp, small instances only)
+    Dp,
+    /// Algorithm picked from the instance features (see --portfolio)
+    Auto,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum OutputFormat {
+    /// Full solution as JSON
+    Json,
+    /// TSPLIB .tour file (Concorde, LKH, ...)
+    Tour,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
@@ -178,48 +586,198 @@ enum CostFunction {
     Quadratic,
     /// Linear load-dependent: distance + alpha * |W| (additive surcharge)
     LinearLoad,
+    /// Schedule duration: distance (travel time) + service times
+    Duration,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum ArcMode {
+    /// Missing arcs are forbidden
+    Restricted,
+    /// Missing arcs follow all-pairs shortest paths (Floyd-Warshall)
+    FloydWarshall,
+    /// Missing arcs follow shortest paths computed on demand (Dijkstra)
+    Dijkstra,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum Backend {
+    /// Precomputed matrix, on demand above the large-instance threshold
+    Auto,
+    /// Full precomputed distance matrix
+    Precomputed,
+    /// Distances computed on demand, k-d tree for neighbor queries
+    OnDemand,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum DistanceRounding {
+    /// Exact floating-point distances
+    Exact,
+    /// Nearest integer (TSPLIB EUC_2D)
+    Nint,
+    /// Next integer up (TSPLIB CEIL_2D)
+    Ceil,
+}
+
+impl From<DistanceRounding> for Rounding {
+    fn from(rounding: DistanceRounding) -> Self {
+        match rounding {
+            DistanceRounding::Exact => Rounding::Exact,
+            DistanceRounding::Nint => Rounding::Nint,
+            DistanceRounding::Ceil => Rounding::Ceil,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum Dialect {
+    /// Li & Lim if the file looks like one, else a return depot when the
+    /// last node has the depot coordinates
+    Auto,
+    /// Mosheiov / Hernández-Pérez: the last node is the return depot
+    Mosheiov,
+    /// TSPLIB CVRP-like: node 1 is the depot, no return copy
+    Tsplib,
+    /// Li & Lim PDPTW text format (time windows and pairs ignored)
+    Lilim,
+}
+
+impl From<Dialect> for InstanceDialect {
+    fn from(dialect: Dialect) -> Self {
+        match dialect {
+            Dialect::Auto => InstanceDialect::Auto,
+            Dialect::Mosheiov => InstanceDialect::Mosheiov,
+            Dialect::Tsplib => InstanceDialect::Tsplib,
+            Dialect::Lilim => InstanceDialect::LiLim,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum IlsAcceptance {
+    /// Strict improvements of the current tour
+    Better,
+    /// Every new local optimum
+    RandomWalk,
+    /// Worse local optima with a decreasing probability
+    Annealing,
+    /// Late acceptance hill climbing
+    LateAcceptance,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum RoadMetric {
+    /// Travel distance
+    Distance,
+    /// Travel time
+    Time,
 }
 
 fn main() {
-    env_logger::init();
-    
     let cli = Cli::parse();
+    let verbose = matches!(&cli.command, Commands::Solve(options) if options.verbose);
+    init_logging(cli.log_format, verbose);
     
     match cli.command {
-        Commands::Solve { instance, algorithm, cost_function, alpha, beta, time_limit, seed, output, visualize, verbose, max_profit } => {
-            solve_instance(&instance, algorithm, cost_function, alpha, beta, time_limit, seed, output, visualize, verbose, max_profit);
+        Commands::Solve(options) => solve_instance(options),
+        
+        Commands::Benchmark(options) => run_benchmark(options),
+        
+        Commands::Analyze { instance, rounding, dialect, json } => {
+            analyze_instance(&instance, rounding, dialect, json);
+        }
+        
+        Commands::Compare { instance, runs, output, html, time_budget, algorithms } => {
+            compare_algorithms(&instance, runs, output, html, time_budget, &algorithms);
         }
         
-        Commands::Benchmark { dir, output, runs, time_limit, exact, exact_time_limit, max_size } => {
-            run_benchmark(&dir, &output, runs, time_limit, exact, exact_time_limit, max_size);
+        Commands::Robustness { instance, solution, algorithm, time_limit, scenarios, coordinate_noise, demand_noise, seed, output } => {
+            robustness(&instance, solution.as_ref(), &algorithm, time_limit, scenarios, coordinate_noise, demand_noise, seed, output.as_ref());
         }
         
-        Commands::Analyze { instance } => {
-            analyze_instance(&instance);
+        Commands::Pareto { instance, algorithm, time_limit, levels, max_profit, seed, output, svg } => {
+            pareto(&instance, &algorithm, time_limit, levels, max_profit, seed, output.as_ref(), svg.as_ref());
         }
         
-        Commands::Compare { instance, runs, output } => {
-            compare_algorithms(&instance, runs, output);
+        Commands::ReportRun { spec } => {
+            report_run(&spec);
+        }
+        
+        Commands::Tune { spec, instances, output } => {
+            tune(&spec, instances.as_deref(), &output);
+        }
+        
+        Commands::Serve { addr, workers } => {
+            println!("Serving the solver API on http://{}", addr);
+            let service = workers.map_or_else(SolverService::new, SolverService::with_workers);
+            if let Err(e) = server::serve(service, &addr) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
         }
     }
 }
 
-fn solve_instance(
-    path: &PathBuf,
-    algorithm: Algorithm,
-    cost_function: CostFunction,
-    alpha: f64,
-    beta: f64,
-    time_limit: f64,
-    seed: u64,
-    output: Option<PathBuf>,
-    visualize: bool,
-    verbose: bool,
-    max_profit: i32,
-) {
+/// Whether `path` is an instance saved as JSON (see `--save-instance`)
+fn is_json_instance(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Install the `tracing` subscriber: RUST_LOG wins, otherwise info level
+/// (debug with `-v`, which includes the solvers' progress events)
+fn init_logging(format: LogFormat, verbose: bool) {
+    let default = if verbose { "warn,pd_tsp_solver=debug" } else { "warn,pd_tsp_solver=info" };
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default));
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal());
+    match format {
+        LogFormat::Pretty => subscriber.init(),
+        LogFormat::Json => subscriber.json().with_current_span(true).init(),
+    }
+}
+
+/// Whether the algorithm builds its descents from the VND settings
+fn uses_vnd_settings(algorithm: Algorithm) -> bool {
+    matches!(algorithm, Algorithm::Vnd | Algorithm::EjectionChain | Algorithm::WindowReopt
+        | Algorithm::Ils | Algorithm::Vns | Algorithm::Hybrid)
+}
+
+fn solve_instance(options: SolveOptions) {
+    let SolveOptions { instance: path, algorithm, cost_function, alpha, beta, time_limit, seed, output, format, pool_size, visualize, html, animate, geojson, verbose, max_profit, vehicle_profile, save_instance, verify, backend, rounding, dialect, demand_std, chance_epsilon, max_route_length, service_time, open_tour, split_demand, road_matrix, road_metric, arc_graph, arc_mode, hybrid_budget, auto_temp, penalty, acceptance, vnd_operators, adaptive_vnd, portfolio, config, record } = options;
+    let path = &path;
+    let mut params = load_solver_config(config.as_ref());
+    if penalty && algorithm != Algorithm::Sa {
+        eprintln!("Warning: --penalty only applies to the sa algorithm and is ignored by {:?}", algorithm);
+    }
+    if (!vnd_operators.is_empty() || adaptive_vnd) && !uses_vnd_settings(algorithm) {
+        eprintln!("Warning: --vnd-operators and --adaptive-vnd only apply to algorithms that run a VND and are ignored by {:?}", algorithm);
+    }
+    if !vnd_operators.is_empty() || adaptive_vnd {
+        let vnd = params.vnd.get_or_insert_with(Default::default);
+        if !vnd_operators.is_empty() {
+            match vnd_operators.iter().map(|name| VNDOperator::from_name(name)).collect() {
+                Ok(operators) => vnd.operators = Some(operators),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        if adaptive_vnd {
+            vnd.ordering = Some(VNDOrdering::Adaptive);
+        }
+    }
+    let cost_function = cost_function
+        .or_else(|| params.cost_function.as_deref().and_then(|name| CostFunction::from_str(name, true).ok()));
+    let time_limit = time_limit.or(params.time_limit).unwrap_or(60.0);
+    let seed = seed.or(params.seed()).unwrap_or(42);
+    
     println!("Loading instance from {:?}...", path);
     
-    let mut instance = match PDTSPInstance::from_file(path) {
+    let mut instance = match PDTSPInstance::from_file_with_dialect(path, dialect.into()) {
         Ok(inst) => inst,
         Err(e) => {
             eprintln!("Error loading instance: {}", e);
@@ -227,33 +785,179 @@ fn solve_instance(
         }
     };
 
-    
+    // A JSON instance keeps its saved profits and cost settings unless the
+    // command line or the config overrides them; `assign_random_profits`
+    // leaves instances that already have profits untouched
+    let saved = is_json_instance(path);
     if max_profit > 0 {
         instance.assign_random_profits(seed, max_profit);
     }
+    match cost_function {
+        Some(cost_function) => instance.cost_function = match cost_function {
+            CostFunction::Distance => pd_tsp_solver::instance::CostFunction::Distance,
+            CostFunction::Quadratic => pd_tsp_solver::instance::CostFunction::Quadratic,
+            CostFunction::LinearLoad => pd_tsp_solver::instance::CostFunction::LinearLoad,
+            CostFunction::Duration => pd_tsp_solver::instance::CostFunction::Duration,
+        },
+        None if !saved => instance.cost_function = pd_tsp_solver::instance::CostFunction::Distance,
+        None => {}
+    }
+    instance.alpha = alpha.or(params.alpha).unwrap_or(if saved { instance.alpha } else { 0.1 });
+    instance.beta = beta.or(params.beta).unwrap_or(if saved { instance.beta } else { 0.0 });
+    let (cost_function, alpha, beta) = (instance.cost_function, instance.alpha, instance.beta);
+    
+    let run_info = RunInfo::capture(seed, &format!(
+        "solve {:?} {:?} {:?} alpha={} beta={} time_limit={} max_profit={} vehicle={:?} config={:?}",
+        path, algorithm, cost_function, alpha, beta, time_limit, max_profit, vehicle_profile, config
+    ));
     
     if verbose {
         println!("{}", instance.statistics());
         println!("Cost function: {:?}", cost_function);
         match cost_function {
-            CostFunction::Quadratic => println!("Alpha (linear weight): {}, Beta (quadratic weight): {}", alpha, beta),
-            CostFunction::LinearLoad => println!("Alpha (linear load weight): {}", alpha),
+            pd_tsp_solver::instance::CostFunction::Quadratic => println!("Alpha (linear weight): {}, Beta (quadratic weight): {}", alpha, beta),
+            pd_tsp_solver::instance::CostFunction::LinearLoad => println!("Alpha (linear load weight): {}", alpha),
             _ => {}
         }
     }
+    if let Some(limit) = max_route_length.or(params.max_route_length) {
+        instance.max_route_length = Some(limit);
+    }
+    if let Some(time) = service_time.or(params.service_time) {
+        instance.set_service_time(time);
+    }
+    instance.open_tour |= open_tour;
+    if verbose && instance.depot_index != 0 {
+        println!("Depot: node {} of the file (internal node 0)", instance.depot_index + 1);
+    }
+    
+    match backend {
+        Backend::Auto => {}
+        Backend::Precomputed => instance.use_precomputed_backend(),
+        Backend::OnDemand => instance.use_on_demand_backend(),
+    }
+    if let Err(e) = instance.set_rounding(rounding.into()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    if verbose {
+        println!("Distance backend: {}", instance.backend.name());
+        println!("Distance rounding: {}", instance.distance_matrix.rounding().name());
+    }
     
+    if let Some(matrix_path) = &road_matrix {
+        let metric = match road_metric {
+            RoadMetric::Distance => pd_tsp_solver::distance::road::RoadMetric::Distance,
+            RoadMetric::Time => pd_tsp_solver::distance::road::RoadMetric::Time,
+        };
+        let loaded = RoadMatrix::from_file(matrix_path, &instance.nodes, metric)
+            .and_then(|road| instance.set_distance_provider(Arc::new(road)));
+        match loaded {
+            Ok(()) => println!("Road matrix: {:?} ({:?})", matrix_path, road_metric),
+            Err(e) => {
+                eprintln!("Error loading road matrix: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
     
-    instance.cost_function = match cost_function {
-        CostFunction::Distance => pd_tsp_solver::instance::CostFunction::Distance,
-        CostFunction::Quadratic => pd_tsp_solver::instance::CostFunction::Quadratic,
-        CostFunction::LinearLoad => pd_tsp_solver::instance::CostFunction::LinearLoad,
-    };
-    instance.alpha = alpha;
-    instance.beta = beta;
+    if let Some(graph_path) = &arc_graph {
+        let loaded = NeighborGraph::from_file(graph_path, &instance.nodes).and_then(|graph| {
+            println!("Neighbor graph: {} arcs ({:?})", graph.arc_count(), arc_mode);
+            let provider: Arc<dyn DistanceProvider> = match arc_mode {
+                ArcMode::Restricted => Arc::new(RestrictedArcs::new(&graph)),
+                ArcMode::FloydWarshall => Arc::new(ShortestPathCompletion::new(graph, CompletionMethod::FloydWarshall)),
+                ArcMode::Dijkstra => Arc::new(ShortestPathCompletion::new(graph, CompletionMethod::Dijkstra)),
+            };
+            instance.set_distance_provider(provider)
+        });
+        if let Err(e) = loaded {
+            eprintln!("Error loading neighbor graph: {}", e);
+            std::process::exit(1);
+        }
+    }
+    
+    // After the distances are set, which the extra visits copy
+    if let Some(size) = split_demand.or(params.split_demand.map(Some)) {
+        match instance.split_demands(size) {
+            Ok(0) => {}
+            Ok(added) => println!("Split demands: {} extra visits", added),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(node) = instance.nodes.iter().skip(1).find(|n| n.demand.abs() > instance.capacity) {
+        eprintln!(
+            "Warning: node {} has demand {} beyond the capacity {}; no tour is feasible without --split-demand",
+            node.id, node.demand, instance.capacity
+        );
+    }
+    
+    if let Some(profile_path) = &vehicle_profile {
+        match VehicleProfile::from_file(profile_path) {
+            Ok(profile) => {
+                println!("Vehicle profile: {}", profile.name);
+                instance.set_vehicle_profile(profile);
+            }
+            Err(e) => {
+                eprintln!("Error loading vehicle profile: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    
+    // Once the nodes are final, since the scenarios are indexed by node
+    if let Some(relative_std) = demand_std {
+        if !(relative_std >= 0.0 && (0.0..=1.0).contains(&chance_epsilon)) {
+            eprintln!("Error: --demand-std must be non-negative and --chance-epsilon in [0, 1]");
+            std::process::exit(1);
+        }
+        let demands = StochasticDemands::uniform(&instance, DemandDistribution::Normal { relative_std });
+        instance.chance_constraint = Some(Arc::new(ChanceConstraint::new(&instance, &demands, 200, seed, chance_epsilon)));
+        println!("Chance constraint: capacity violation probability at most {} (200 scenarios)", chance_epsilon);
+    }
+    
+    if let Some(instance_path) = &save_instance {
+        match instance.save_to_file(instance_path) {
+            Ok(()) => println!("Instance saved to {:?}", instance_path),
+            Err(e) => eprintln!("Error saving instance: {}", e),
+        }
+    }
 
+    let algorithm = if algorithm == Algorithm::Auto {
+        let table = match &portfolio {
+            Some(path) => Portfolio::load(path).unwrap_or_else(|e| {
+                eprintln!("Error loading portfolio: {}", e);
+                std::process::exit(1);
+            }),
+            None => Portfolio::new(),
+        };
+        let features = InstanceFeatures::extract(&instance);
+        let name = table.select_for(&features);
+        if verbose {
+            println!("Instance features: {:?}", features);
+        }
+        println!("Auto selection: {}", name);
+        match Algorithm::from_str(name, true) {
+            Ok(selected) if selected != Algorithm::Auto => selected,
+            _ => {
+                eprintln!("Portfolio selects unknown algorithm '{}'", name);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        algorithm
+    };
+    
+    let _span = tracing::info_span!("solve", instance = %instance.name, algorithm = ?algorithm, seed).entered();
     println!("Solving with {:?} algorithm...", algorithm);
     let start = Instant::now();
+    let rng = RngFactory::new(seed);
+    // Best tours along the search feed the animation and the solution pool
+    let record_snapshots = animate.is_some() || pool_size > 1;
     
+    let mut stage_times = None;
     let solution = match algorithm {
         Algorithm::Nn => {
             let nn = NearestNeighborHeuristic::new();
@@ -265,13 +969,18 @@ fn solve_instance(
             greedy.construct(&instance)
         }
         
+        Algorithm::Grasp => {
+            let grasp = GraspConstruction::with_params(20, rng.derive("grasp"));
+            grasp.construct(&instance)
+        }
+        
         Algorithm::Savings => {
             let savings = SavingsHeuristic::new();
             savings.construct(&instance)
         }
         
         Algorithm::Sweep => {
-            let sweep = SweepHeuristic::new();
+            let sweep = SweepHeuristic::rotated();
             sweep.construct(&instance)
         }
         
@@ -285,8 +994,13 @@ fn solve_instance(
             cluster.construct(&instance)
         }
         
+        Algorithm::Christofides => {
+            let christofides = ChristofidesHeuristic::new();
+            christofides.construct(&instance)
+        }
+        
         Algorithm::MultiStart => {
-            let multi = MultiStartConstruction::with_all_heuristics();
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
             multi.construct(&instance)
         }
         
@@ -296,7 +1010,7 @@ fn solve_instance(
         }
         
         Algorithm::TwoOpt => {
-            let multi = MultiStartConstruction::with_all_heuristics();
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
             let mut sol = multi.construct(&instance);
             let two_opt = TwoOptSearch::new();
             two_opt.improve(&instance, &mut sol);
@@ -304,106 +1018,205 @@ fn solve_instance(
         }
         
         Algorithm::Vnd => {
-            let multi = MultiStartConstruction::with_all_heuristics();
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
             let mut sol = multi.construct(&instance);
-            let vnd = VND::with_standard_operators();
+            let mut vnd = VND::with_standard_operators();
+            params.apply_vnd(&mut vnd);
             vnd.improve(&instance, &mut sol);
             sol.algorithm = "VND".to_string();
             sol
         }
         
+        Algorithm::EjectionChain => {
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
+            let mut sol = multi.construct(&instance);
+            let mut vnd = VND::with_standard_operators();
+            params.apply_vnd(&mut vnd);
+            vnd.add_operator(EjectionChainSearch::new());
+            vnd.improve(&instance, &mut sol);
+            sol.algorithm = "EjectionChain".to_string();
+            sol
+        }
+        
+        Algorithm::WindowReopt => {
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
+            let mut sol = multi.construct(&instance);
+            let deadline = Deadline::after(time_limit);
+            let mut vnd = VND::with_standard_operators().with_deadline(deadline);
+            params.apply_vnd(&mut vnd);
+            vnd.improve(&instance, &mut sol);
+            WindowReoptimization::new().with_deadline(deadline).improve(&instance, &mut sol);
+            sol.algorithm = "WindowReopt".to_string();
+            sol
+        }
+        
+        Algorithm::Popmusic => {
+            let mut sol = NearestNeighborHeuristic::new().construct(&instance);
+            Popmusic::new().with_deadline(Deadline::after(time_limit)).improve(&instance, &mut sol);
+            sol.algorithm = "POPMUSIC".to_string();
+            sol
+        }
+        
         Algorithm::Sa => {
-            let multi = MultiStartConstruction::with_all_heuristics();
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
             let mut sol = multi.construct(&instance);
             let mut sa = SimulatedAnnealing::new();
-            sa.seed = seed;
+            params.apply_sa(&mut sa);
+            sa.seed = rng.derive("sa");
+            sa.auto_temperature |= auto_temp;
+            if penalty {
+                sa.penalty.get_or_insert_with(CapacityPenalty::new);
+            }
+            sa.record_snapshots = record_snapshots;
             sa.improve(&instance, &mut sol);
             sol.algorithm = "SimulatedAnnealing".to_string();
             sol
         }
         
+        Algorithm::ThresholdAccepting => {
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
+            let mut sol = multi.construct(&instance);
+            let mut ta = ThresholdAccepting::new();
+            ta.seed = rng.derive("threshold-accepting");
+            ta.deadline = Deadline::after(time_limit);
+            ta.improve(&instance, &mut sol);
+            sol.algorithm = "ThresholdAccepting".to_string();
+            sol
+        }
+        
+        Algorithm::RecordToRecord => {
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
+            let mut sol = multi.construct(&instance);
+            let mut rrt = RecordToRecord::new();
+            rrt.seed = rng.derive("record-to-record");
+            rrt.deadline = Deadline::after(time_limit);
+            rrt.improve(&instance, &mut sol);
+            sol.algorithm = "RecordToRecord".to_string();
+            sol
+        }
+        
         Algorithm::Tabu => {
-            let multi = MultiStartConstruction::with_all_heuristics();
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
             let mut sol = multi.construct(&instance);
-            let ts = TabuSearch::new();
+            let mut ts = TabuSearch::new();
+            params.apply_tabu(&mut ts);
+            ts.seed = rng.derive("tabu");
             ts.improve(&instance, &mut sol);
             sol.algorithm = "TabuSearch".to_string();
             sol
         }
         
         Algorithm::Ils => {
-            let multi = MultiStartConstruction::with_all_heuristics();
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
             let mut sol = multi.construct(&instance);
             let mut ils = IteratedLocalSearch::new();
-            ils.seed = seed;
+            params.apply_ils(&mut ils);
+            if let Some(acceptance) = acceptance {
+                ils.acceptance = match acceptance {
+                    IlsAcceptance::Better => Acceptance::Better,
+                    IlsAcceptance::RandomWalk => Acceptance::RandomWalk,
+                    IlsAcceptance::Annealing => Acceptance::Annealing,
+                    IlsAcceptance::LateAcceptance => Acceptance::LateAcceptance,
+                };
+            }
+            ils.seed = rng.derive("ils");
+            ils.record_snapshots = record_snapshots;
             ils.improve(&instance, &mut sol);
             sol.algorithm = "ILS".to_string();
             sol
         }
         
+        Algorithm::Vns => {
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
+            let mut sol = multi.construct(&instance);
+            let mut vns = GeneralVNS::new();
+            params.apply_vns(&mut vns);
+            vns.seed = rng.derive("vns");
+            vns.time_limit = time_limit;
+            vns.improve(&instance, &mut sol);
+            sol.algorithm = "GVNS".to_string();
+            sol
+        }
+        
         Algorithm::Ga => {
-            let config = GAConfig {
-                seed,
+            let mut config = GAConfig {
                 population_size: 50,
                 max_generations: 200,
-                time_limit: time_limit,
                 ..Default::default()
             };
+            params.apply_ga(&mut config);
+            config.seed = rng.derive("ga");
+            config.time_limit = time_limit;
+            config.record_snapshots = record_snapshots;
             let mut ga = GeneticAlgorithm::new(instance.clone(), config);
             ga.run()
         }
         
         Algorithm::Memetic => {
-            let config = GAConfig {
-                seed,
-                time_limit: time_limit,
-                ..Default::default()
-            };
+            let mut config = GAConfig::default();
+            params.apply_ga(&mut config);
+            config.seed = rng.derive("memetic");
+            config.time_limit = time_limit;
+            config.record_snapshots = record_snapshots;
             let mut ma = MemeticAlgorithm::with_config(instance.clone(), config);
             ma.run()
         }
         
         Algorithm::Aco => {
-            let config = ACOConfig {
-                seed,
+            let mut config = ACOConfig {
                 max_iterations: 200,
                 ..Default::default()
             };
+            params.apply_aco(&mut config);
+            config.seed = rng.derive("aco");
             let mut aco = AntColonyOptimization::new(instance.clone(), config);
             aco.run()
         }
         
         Algorithm::Mmas => {
-            let config = ACOConfig {
-                seed,
+            let mut config = ACOConfig {
                 max_iterations: 200,
                 ..Default::default()
             };
+            params.apply_aco(&mut config);
+            config.seed = rng.derive("aco");
             let mut mmas = MaxMinAntSystem::new(instance.clone(), config);
             mmas.run()
         }
         
+        Algorithm::RankAs | Algorithm::ElitistAs => {
+            let strategy = if matches!(algorithm, Algorithm::RankAs) {
+                PheromoneStrategy::RankBased
+            } else {
+                PheromoneStrategy::Elitist
+            };
+            let mut config = ACOConfig {
+                max_iterations: 200,
+                ..Default::default()
+            };
+            params.apply_aco(&mut config);
+            config.seed = rng.derive("aco");
+            config.strategy = strategy;
+            let mut aco = AntColonyOptimization::new(instance.clone(), config);
+            aco.run()
+        }
+        
         Algorithm::Hybrid => {
-            
-            let multi = MultiStartConstruction::with_all_heuristics();
-            let mut sol = multi.construct(&instance);
-            
-            
-            let vnd = VND::with_standard_operators();
-            vnd.improve(&instance, &mut sol);
-            
-            
-            let mut ils = IteratedLocalSearch::with_params(4, 50, 15);
-            ils.seed = seed;
-            ils.improve(&instance, &mut sol);
-            
+            let budget = TimeBudget::from_fractions(&hybrid_budget).unwrap_or_else(|e| {
+                eprintln!("Invalid hybrid budget: {}", e);
+                std::process::exit(1);
+            });
+            // A pipeline from the config file replaces the default stages
+            let pipeline = params.pipeline(budget);
+            let (mut sol, times) = pipeline.solve(&instance, time_limit, seed);
             sol.algorithm = "Hybrid".to_string();
+            stage_times = Some(times);
             sol
         }
         
         Algorithm::Exact => {
             let warm_start = {
-                let multi = MultiStartConstruction::with_all_heuristics();
+                let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng);
                 let mut sol = multi.construct(&instance);
                 let vnd = VND::with_standard_operators();
                 vnd.improve(&instance, &mut sol);
@@ -432,13 +1245,62 @@ fn solve_instance(
                 }
             }
         }
+        
+        Algorithm::Dp => {
+            match dynamic_programming::solve(&instance) {
+                Ok(sol) => sol,
+                Err(e) => {
+                    eprintln!("Dynamic programming error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        
+        Algorithm::Auto => unreachable!("auto is resolved before solving"),
     };
     
     let elapsed = start.elapsed();
     
     
-    let final_solution = solution;
+    let mut final_solution = solution;
+    // A tour too long for the route limit may still be split by depot returns
+    if !final_solution.feasible && instance.has_route_limit() {
+        if let Some(split) = instance.split_routes(&final_solution.tour) {
+            println!("Route length limit: tour split into {} routes", instance.route_lengths(&split).len());
+            final_solution.tour = split;
+            final_solution.validate(&instance);
+        }
+    }
+    final_solution.run_info = Some(run_info);
+    // Solvers only check the loads; a tour missing customers is not a solution
+    final_solution.feasible &= instance.is_complete_tour(&final_solution.tour);
+    final_solution.violations = instance.explain_infeasibility(&final_solution.tour);
+    // With profits, skipping a customer is a choice of the objective, not a violation
+    let skipped = if instance.has_profits() {
+        let before = final_solution.violations.len();
+        final_solution.violations.retain(|violation| !matches!(violation, Violation::MissingNode { .. }));
+        before - final_solution.violations.len()
+    } else {
+        0
+    };
     
+    let mut pool = SolutionPool::new(pool_size);
+    pool.offer_run(&instance, &final_solution);
+    if animate.is_none() {
+        final_solution.snapshots.clear();
+    }
+    
+    if verify {
+        let issues = verification::verify(&instance, &final_solution);
+        if !issues.is_empty() {
+            eprintln!("Verification failed for {}:", final_solution.algorithm);
+            for issue in &issues {
+                eprintln!("  - {}", issue);
+            }
+            std::process::exit(1);
+        }
+        println!("Verification: passed");
+    }
     
     println!("\n========== Results ==========");
     println!("Algorithm: {}", final_solution.algorithm);
@@ -447,26 +1309,123 @@ fn solve_instance(
     println!("Total profit: {}", final_solution.total_profit);
     println!("Objective (profit - travel_cost): {:.2}", final_solution.objective);
     println!("Feasible: {}", final_solution.feasible);
+    if skipped > 0 {
+        println!("Customers skipped: {}", skipped);
+    }
+    for violation in final_solution.violations.iter().take(MAX_VIOLATIONS_SHOWN) {
+        println!("  - {}", violation);
+    }
+    if final_solution.violations.len() > MAX_VIOLATIONS_SHOWN {
+        println!("  ... {} more violations", final_solution.violations.len() - MAX_VIOLATIONS_SHOWN);
+    }
+    if let Some(emissions) = instance.tour_emissions(&final_solution.tour) {
+        println!("Emissions: {:.2}", emissions);
+    }
     println!("Time: {:.4}s", elapsed.as_secs_f64());
+    if let Some(times) = &stage_times {
+        println!("Stage times: {}", times);
+    }
     if let Some(iter) = final_solution.iterations {
         println!("Iterations: {}", iter);
     }
+    if pool_size > 1 {
+        let costs: Vec<String> = pool.members().iter().map(|member| format!("{:.2}", member.cost)).collect();
+        println!("Solution pool: {} distinct solutions (costs {})", pool.len(), costs.join(", "));
+    }
+    if verbose || instance.vehicle.is_some() {
+        println!("{}", instance.cost_breakdown(&final_solution.tour));
+    }
+    
+    if let Some(relative_std) = demand_std {
+        let demands = StochasticDemands::uniform(&instance, DemandDistribution::Normal { relative_std });
+        println!("\nStochastic demands (relative std {}):", relative_std);
+        for line in simulate(&instance, &final_solution.tour, &demands, 1000, seed).to_string().lines() {
+            println!("  {}", line);
+        }
+    }
     
     if verbose {
         println!("\nTour: {:?}", final_solution.tour);
+        if instance.has_split_demands() {
+            println!("Customers: {:?}", instance.original_tour(&final_solution.tour));
+        }
         let profile = final_solution.load_profile(&instance);
         println!("Load profile: {:?}", profile);
         println!("Max load: {}", final_solution.max_load(&instance));
         println!("Min load: {}", final_solution.min_load(&instance));
+        if !final_solution.statistics.is_empty() {
+            println!("\nOperator statistics:\n{}", final_solution.statistics);
+        }
     }
     
     
     if let Some(out_path) = output {
-        let json = serde_json::to_string_pretty(&final_solution).unwrap();
-        std::fs::write(&out_path, json).expect("Failed to write output");
+        let alternates = pool.members().iter().filter(|member| member.tour != final_solution.tour);
+        let content = match format {
+            OutputFormat::Json if pool_size > 1 => {
+                let mut value = serde_json::to_value(&final_solution).unwrap();
+                value["pool"] = serde_json::to_value(pool.members()).unwrap();
+                serde_json::to_string_pretty(&value).unwrap()
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(&final_solution).unwrap(),
+            OutputFormat::Tour => {
+                for (k, member) in alternates.enumerate() {
+                    let pool_path = out_path.with_extension(format!("pool{}.tour", k + 1));
+                    std::fs::write(&pool_path, member.to_tsplib_tour(&instance, &instance.name)).expect("Failed to write pool tour");
+                    println!("Alternate solution saved to {:?}", pool_path);
+                }
+                final_solution.to_tsplib_tour(&instance, &instance.name)
+            }
+        };
+        std::fs::write(&out_path, content).expect("Failed to write output");
         println!("\nSolution saved to {:?}", out_path);
     }
     
+    if let Some(record_path) = record {
+        let snapshot = serde_json::json!({
+            "algorithm": format!("{:?}", algorithm),
+            "cost_function": instance.cost_function,
+            "alpha": alpha,
+            "beta": beta,
+            "time_limit": time_limit,
+            "max_profit": max_profit,
+            "vehicle_profile": vehicle_profile,
+            "solver": params,
+        });
+        let entry = RunRecord::from_solution(&instance, &final_solution, Some(seed), snapshot);
+        run_record::append_jsonl(&record_path, &[entry]).expect("Failed to write run record");
+        println!("Run record appended to {:?}", record_path);
+    }
+    
+    if let Some(html_path) = html {
+        let page = Visualizer::new().generate_html(&instance, &final_solution);
+        std::fs::write(&html_path, page).expect("Failed to write HTML view");
+        println!("Interactive view saved to {:?}", html_path);
+    }
+    
+    if let Some(geojson_path) = geojson {
+        let collection = Visualizer::new().export_geojson(&instance, &final_solution);
+        std::fs::write(&geojson_path, collection).expect("Failed to write GeoJSON");
+        println!("GeoJSON saved to {:?}", geojson_path);
+    }
+    
+    if let Some(animation_path) = animate {
+        if final_solution.snapshots.is_empty() {
+            println!("No search snapshots recorded by {}: the animation shows the final tour only", final_solution.algorithm);
+        }
+        let viz = Visualizer::new();
+        viz.save_svg(&viz.generate_animation(&instance, &final_solution, 0.5), &animation_path)
+            .expect("Failed to save animation");
+        let stem = animation_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let frames_dir = animation_path.with_file_name(format!("{}_frames", stem));
+        std::fs::create_dir_all(&frames_dir).expect("Failed to create frame directory");
+        let frames = viz.generate_animation_frames(&instance, &final_solution);
+        for (i, frame) in frames.iter().enumerate() {
+            viz.save_svg(frame, frames_dir.join(format!("frame_{:04}.svg", i))).expect("Failed to save frame");
+        }
+        println!("Animation saved to {:?} ({} frames in {:?})", animation_path, frames.len(), frames_dir);
+    }
+    
     
     if visualize {
         let viz = Visualizer::new();
@@ -492,26 +1451,97 @@ fn solve_instance(
                 println!("PNG conversion failed ({}). Saved load SVG to {:?}", e, load_svg_path);
             }
         }
+        
+        if !final_solution.convergence.is_empty() {
+            let trace_svg = viz.generate_convergence_svg(&[(final_solution.algorithm.as_str(), &final_solution.convergence)]);
+            let trace_svg_path = path.with_extension("convergence.svg");
+            viz.save_svg(&trace_svg, &trace_svg_path).expect("Failed to save convergence SVG");
+            println!("Convergence plot saved to {:?}", trace_svg_path);
+        }
     }
 }
 
-fn run_benchmark(
-    dir: &PathBuf,
-    output: &PathBuf,
-    runs: usize,
-    time_limit: f64,
-    exact: bool,
-    exact_time_limit: f64,
-    max_size: Option<usize>,
-) {
-    println!("Loading instances from {:?}...", dir);
+fn run_benchmark(options: BenchmarkOptions) {
+    let BenchmarkOptions { dir, campaign, output, runs, time_limit, exact, exact_time_limit, max_size, html, target_gap, best_known, config, jobs, warmup, portfolio, algorithms, store, set_partitioning } = options;
+    let campaign = campaign.map(|path| CampaignSpec::from_file(&path).unwrap_or_else(|e| {
+        eprintln!("Error loading campaign: {}", e);
+        std::process::exit(1);
+    }));
+    let output = output.or_else(|| campaign.as_ref().and_then(CampaignSpec::output_dir))
+        .unwrap_or_else(|| PathBuf::from("results"));
+    let output = &output;
+    let known: Vec<&str> = JobKind::ALL.iter().map(|kind| kind.key()).chain([EXACT_KEY]).collect();
+    let algorithms = match parse_algorithm_selection(&algorithms, &known) {
+        Ok(algorithms) => algorithms,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    if algorithms.iter().any(|key| key == EXACT_KEY) && !exact {
+        eprintln!("Selecting the exact solver requires --exact");
+        std::process::exit(1);
+    }
+    // Fail before the runs rather than after them
+    if store.is_some() && !cfg!(feature = "sqlite") {
+        eprintln!("--store requires the `sqlite` feature");
+        std::process::exit(1);
+    }
+    
+    let runs_config = match &campaign {
+        Some(campaign) => campaign.benchmark_config().unwrap_or_else(|e| {
+            eprintln!("Invalid campaign: {}", e);
+            std::process::exit(1);
+        }),
+        None => {
+            let mut params = load_solver_config(config.as_ref());
+            // An explicit run count replaces the configured seeds
+            if runs.is_some() {
+                params.seeds = None;
+            }
+            BenchmarkConfig {
+                num_runs: runs.unwrap_or(5),
+                time_limit: time_limit.or(params.time_limit).unwrap_or(60.0),
+                run_exact: exact,
+                exact_time_limit,
+                solver: params,
+                algorithms,
+                ..Default::default()
+            }
+        }
+    };
+    let params = &runs_config.solver;
     
-    let mut instances = load_instances_from_dir(dir);
+    let mut instances = match (&campaign, &dir) {
+        (Some(campaign), _) => {
+            let paths = campaign.instance_paths().unwrap_or_else(|e| {
+                eprintln!("Invalid campaign: {}", e);
+                std::process::exit(1);
+            });
+            println!("Loading {} campaign instances...", paths.len());
+            paths.iter().map(|path| PDTSPInstance::from_file(path).unwrap_or_else(|e| {
+                eprintln!("Error loading instance {:?}: {}", path, e);
+                std::process::exit(1);
+            })).collect()
+        }
+        (None, Some(dir)) => {
+            println!("Loading instances from {:?}...", dir);
+            load_instances_from_dir(dir)
+        }
+        (None, None) => unreachable!("clap requires --dir without --campaign"),
+    };
     
     if let Some(max) = max_size {
         instances.retain(|i| i.dimension <= max);
     }
     
+    for instance in &mut instances {
+        if let Err(e) = params.apply_cost_function(instance) {
+            eprintln!("Invalid solver config: {}", e);
+            std::process::exit(1);
+        }
+    }
+    
     println!("Found {} instances", instances.len());
     
     if instances.is_empty() {
@@ -521,23 +1551,34 @@ fn run_benchmark(
     
     
     std::fs::create_dir_all(output).expect("Failed to create output directory");
+    // A registry that cannot be read would be replaced by this session's results
+    if let Err(e) = BestKnownRegistry::load(&best_known) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
     
     let config = BenchmarkConfig {
-        num_runs: runs,
-        time_limit,
-        run_exact: exact,
-        exact_time_limit,
         output_dir: output.to_string_lossy().to_string(),
-        ..Default::default()
+        best_known_path: Some(best_known.clone()),
+        parallel: jobs != 1,
+        jobs,
+        warmup_runs: warmup,
+        set_partitioning,
+        ..runs_config
     };
     
     let mut benchmark = Benchmark::new(config);
     
-    for (i, instance) in instances.iter().enumerate() {
-        println!("\n[{}/{}] Processing {} (n={})...", 
-            i + 1, instances.len(), instance.name, instance.dimension);
-        
-        benchmark.run_full_benchmark(instance);
+    if jobs == 1 {
+        for (i, instance) in instances.iter().enumerate() {
+            println!("\n[{}/{}] Processing {} (n={})...", 
+                i + 1, instances.len(), instance.name, instance.dimension);
+            
+            benchmark.run_full_benchmark(instance);
+        }
+    } else {
+        println!("\nRunning {} instances in parallel...", instances.len());
+        benchmark.run_on_instances(&instances);
     }
     
     
@@ -549,6 +1590,22 @@ fn run_benchmark(
     benchmark.export_statistics_csv(&stats_path).expect("Failed to export statistics");
     println!("Statistics exported to {:?}", stats_path);
     
+    let runs_path = output.join("runs.jsonl");
+    benchmark.export_run_records(&runs_path).expect("Failed to export run records");
+    println!("Run records exported to {:?}", runs_path);
+    
+    if let Some(store_path) = &store {
+        match store::save_benchmark(store_path, &benchmark) {
+            Ok(count) => println!("{} runs stored in {:?}", count, store_path),
+            Err(e) => eprintln!("Error storing results: {}", e),
+        }
+    }
+    
+    println!("Best known solutions ({} problems) saved to {:?}", benchmark.registry().len(), best_known);
+    
+    let profiles = benchmark.export_performance_profiles(output, target_gap).expect("Failed to export performance profiles");
+    println!("Performance profiles exported to {:?}", profiles);
+    
     
     let report = benchmark.generate_report();
     println!("\n{}", report);
@@ -556,69 +1613,67 @@ fn run_benchmark(
     let report_path = output.join("report.txt");
     std::fs::write(&report_path, &report).expect("Failed to save report");
     println!("Report saved to {:?}", report_path);
+    
+    if html {
+        let mut dashboard = Dashboard::from_results("PD-TSP Benchmark", benchmark.results());
+        dashboard.run_info = Some(benchmark.run_info().clone());
+        // The best known tour of each instance, linked relatively as in `compare`
+        let thumbs_dir = output.join("dashboard_tours");
+        std::fs::create_dir_all(&thumbs_dir).expect("Failed to create thumbnail directory");
+        let viz = Visualizer::new();
+        for instance in &instances {
+            let Some(best) = benchmark.registry().get(&problem_key(instance)).filter(|best| !best.tour.is_empty()) else {
+                continue;
+            };
+            let sol = Solution::from_tour(instance, best.tour.clone(), &best.algorithm);
+            let file_name = format!("{}.svg", instance.name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+            viz.save_svg(&viz.generate_svg(instance, &sol), thumbs_dir.join(&file_name)).expect("Failed to save thumbnail");
+            dashboard.add_thumbnail(&format!("{}: {} ({:.2})", instance.name, best.algorithm, sol.cost), &format!("dashboard_tours/{}", file_name));
+        }
+        let html_path = output.join("dashboard.html");
+        dashboard.save(&html_path).expect("Failed to save dashboard");
+        println!("Dashboard saved to {:?}", html_path);
+    }
+    
+    if let Some(portfolio_path) = &portfolio {
+        let table = Portfolio::train(&instances, benchmark.results());
+        let mut wins: Vec<(&str, usize)> = table.wins().into_iter().collect();
+        wins.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        let wins: Vec<String> = wins.iter().map(|(algorithm, count)| format!("{} {}", algorithm, count)).collect();
+        match table.save(portfolio_path) {
+            Ok(()) => println!("Portfolio ({} instances: {}) saved to {:?}", table.entries.len(), wins.join(", "), portfolio_path),
+            Err(e) => eprintln!("Error saving portfolio: {}", e),
+        }
+    }
 }
 
-fn analyze_instance(path: &PathBuf) {
-    let instance = match PDTSPInstance::from_file(path) {
+fn analyze_instance(path: &PathBuf, rounding: DistanceRounding, dialect: Dialect, json: bool) {
+    let mut instance = match PDTSPInstance::from_file_with_dialect(path, dialect.into()) {
         Ok(inst) => inst,
         Err(e) => {
             eprintln!("Error loading instance: {}", e);
             std::process::exit(1);
         }
     };
-    
-    println!("========== Instance Analysis ==========\n");
-    println!("{}", instance.statistics());
-    
-    
-    let pickups: Vec<_> = instance.nodes.iter().filter(|n| n.demand < 0).collect();
-    let deliveries: Vec<_> = instance.nodes.iter().filter(|n| n.demand > 0).collect();
-    let neutrals: Vec<_> = instance.nodes.iter().filter(|n| n.demand == 0 && n.id != 0).collect();
-    
-    println!("\nDemand Distribution:");
-    println!("  Pickup nodes: {} (total: {})", 
-        pickups.len(),
-        pickups.iter().map(|n| -n.demand).sum::<i32>());
-    println!("  Delivery nodes: {} (total: {})",
-        deliveries.len(),
-        deliveries.iter().map(|n| n.demand).sum::<i32>());
-    println!("  Neutral nodes: {}", neutrals.len());
-    
-    
-    let all_demands: Vec<i32> = instance.nodes.iter()
-        .filter(|n| n.demand != 0 && n.id != 0)
-        .map(|n| n.demand.abs())
-        .collect();
-    
-    if !all_demands.is_empty() {
-        let avg_demand = all_demands.iter().sum::<i32>() as f64 / all_demands.len() as f64;
-        let max_demand = *all_demands.iter().max().unwrap();
-        let min_demand = *all_demands.iter().min().unwrap();
-        
-        println!("\nDemand Statistics (absolute):");
-        println!("  Average: {:.2}", avg_demand);
-        println!("  Min: {}", min_demand);
-        println!("  Max: {}", max_demand);
-        println!("  Capacity utilization ratio: {:.2}%", 
-            avg_demand / instance.capacity as f64 * 100.0);
+    if let Err(e) = instance.set_rounding(rounding.into()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
     }
     
-    
-    let mut all_distances: Vec<f64> = Vec::new();
-    for i in 0..instance.dimension {
-        for j in i + 1..instance.dimension {
-            all_distances.push(instance.distance(i, j));
+    let report = analysis::analyze(&instance);
+    if json {
+        match report.to_json() {
+            Ok(text) => println!("{}", text),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
         }
+        return;
     }
     
-    let avg_dist = all_distances.iter().sum::<f64>() / all_distances.len() as f64;
-    let min_dist = all_distances.iter().cloned().fold(f64::INFINITY, f64::min);
-    let max_dist = all_distances.iter().cloned().fold(0.0, f64::max);
-    
-    println!("\nDistance Statistics:");
-    println!("  Average: {:.2}", avg_dist);
-    println!("  Min: {:.2}", min_dist);
-    println!("  Max: {:.2}", max_dist);
+    println!("========== Instance Analysis ==========\n");
+    print!("{}", report);
     
     
     let nn = NearestNeighborHeuristic::new();
@@ -634,7 +1689,25 @@ fn analyze_instance(path: &PathBuf) {
     println!("  Multi-Start + VND: {:.2} (feasible: {})", multi_sol.cost, multi_sol.feasible);
 }
 
-fn compare_algorithms(path: &PathBuf, runs: usize, output: Option<PathBuf>) {
+/// Run of a compared algorithm from a seed, within a deadline
+type CompareSolver = Box<dyn Fn(&PDTSPInstance, u64, Deadline) -> Solution>;
+
+fn compare_algorithms(path: &PathBuf, runs: usize, output: Option<PathBuf>, html: Option<PathBuf>, time_budget: Option<f64>, algorithms: &[String]) {
+    let compared = compared_algorithms();
+    let known: Vec<&str> = compared.iter().map(|(key, _, _)| *key).collect();
+    let selection = match parse_algorithm_selection(algorithms, &known) {
+        Ok(selection) => selection,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    if time_budget.is_some_and(|budget| !(budget > 0.0 && budget.is_finite())) {
+        eprintln!("The time budget must be a positive number of seconds");
+        std::process::exit(1);
+    }
+
     let instance = match PDTSPInstance::from_file(path) {
         Ok(inst) => inst,
         Err(e) => {
@@ -643,87 +1716,57 @@ fn compare_algorithms(path: &PathBuf, runs: usize, output: Option<PathBuf>) {
         }
     };
     
-    println!("Comparing algorithms on {} (n={})...\n", instance.name, instance.dimension);
+    println!("Comparing algorithms on {} (n={})...", instance.name, instance.dimension);
+    if let Some(budget) = time_budget {
+        println!("Time budget: {}s per run, fast algorithms are restarted until it is spent", budget);
+    }
+    println!();
     
+    // Best cost found versus time of the budgeted runs
+    let mut traces = String::from("algorithm,run,time,best_cost\n");
     let mut results: Vec<(String, Vec<f64>, Vec<f64>)> = Vec::new();
+    let mut dashboard = Dashboard::new(&format!("PD-TSP Comparison - {}", instance.name));
+    let mut best_solutions: Vec<(String, Solution)> = Vec::new();
     
     
-    let algorithms: Vec<(&str, Box<dyn Fn(&PDTSPInstance, u64) -> Solution>)> = vec![
-        ("MultiStart+VND", Box::new(|inst: &PDTSPInstance, _seed: u64| {
-            let multi = MultiStartConstruction::with_all_heuristics();
-            let mut sol = multi.construct(inst);
-            let vnd = VND::with_standard_operators();
-            vnd.improve(inst, &mut sol);
-            sol
-        })),
-        ("SA", Box::new(|inst: &PDTSPInstance, seed: u64| {
-            let multi = MultiStartConstruction::with_all_heuristics();
-            let mut sol = multi.construct(inst);
-            let mut sa = SimulatedAnnealing::new();
-            sa.seed = seed;
-            sa.improve(inst, &mut sol);
-            sol
-        })),
-        ("Tabu", Box::new(|inst: &PDTSPInstance, _seed: u64| {
-            let multi = MultiStartConstruction::with_all_heuristics();
-            let mut sol = multi.construct(inst);
-            let ts = TabuSearch::new();
-            ts.improve(inst, &mut sol);
-            sol
-        })),
-        ("ILS", Box::new(|inst: &PDTSPInstance, seed: u64| {
-            let multi = MultiStartConstruction::with_all_heuristics();
-            let mut sol = multi.construct(inst);
-            let mut ils = IteratedLocalSearch::new();
-            ils.seed = seed;
-            ils.improve(inst, &mut sol);
-            sol
-        })),
-        ("GA", Box::new(|inst: &PDTSPInstance, seed: u64| {
-            let config = GAConfig {
-                seed,
-                population_size: 50,
-                max_generations: 100,
-                time_limit: 60.0,
-                ..Default::default()
-            };
-            let mut ga = GeneticAlgorithm::new(inst.clone(), config);
-            ga.run()
-        })),
-        ("MA", Box::new(|inst: &PDTSPInstance, seed: u64| {
-            let config = GAConfig {
-                seed,
-                population_size: 30,
-                max_generations: 50,
-                time_limit: 60.0,
-                ..Default::default()
-            };
-            let mut ma = MemeticAlgorithm::with_config(inst.clone(), config);
-            ma.run()
-        })),
-        ("ACO", Box::new(|inst: &PDTSPInstance, seed: u64| {
-            let config = ACOConfig {
-                seed,
-                num_ants: 15,
-                max_iterations: 50,
-                ..Default::default()
-            };
-            let mut aco = AntColonyOptimization::new(inst.clone(), config);
-            aco.run()
-        })),
-    ];
-    
-    for (name, solver) in &algorithms {
+    for (key, name, solver) in &compared {
+        if !selection.is_empty() && !selection.iter().any(|k| k == key) {
+            continue;
+        }
         let mut costs = Vec::new();
         let mut times = Vec::new();
         
         print!("Testing {}... ", name);
         std::io::Write::flush(&mut std::io::stdout()).unwrap();
         
+        let mut best: Option<Solution> = None;
+        
         for seed in 0..runs as u64 {
             let start = Instant::now();
-            let sol = solver(&instance, seed);
+            let sol = match time_budget {
+                Some(budget) => restart_until_budget(budget, seed, |s, deadline| solver(&instance, s, deadline)),
+                None => solver(&instance, seed, Deadline::none()),
+            };
             let elapsed = start.elapsed().as_secs_f64();
+            if time_budget.is_some() {
+                for &(time, cost) in &sol.convergence {
+                    traces.push_str(&format!("{},{},{:.4},{:.2}\n", name, seed, time, cost));
+                }
+            }
+            
+            dashboard.add_run(DashboardRun {
+                algorithm: name.to_string(),
+                instance: instance.name.clone(),
+                run: seed as usize,
+                cost: sol.cost,
+                time: elapsed,
+                feasible: sol.feasible,
+                convergence: sol.convergence.clone(),
+            });
+            
+            if sol.feasible && best.as_ref().is_none_or(|b| sol.cost < b.cost) {
+                best = Some(sol.clone());
+            }
             
             if sol.feasible {
                 costs.push(sol.cost);
@@ -743,6 +1786,9 @@ fn compare_algorithms(path: &PathBuf, runs: usize, output: Option<PathBuf>) {
         }
         
         results.push((name.to_string(), costs, times));
+        if let Some(best) = best {
+            best_solutions.push((name.to_string(), best));
+        }
     }
     
     
@@ -776,5 +1822,322 @@ fn compare_algorithms(path: &PathBuf, runs: usize, output: Option<PathBuf>) {
         
         std::fs::write(&out_path, csv).expect("Failed to write CSV");
         println!("\nResults exported to {:?}", out_path);
+        
+        if time_budget.is_some() {
+            let stem = out_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let trace_path = out_path.with_file_name(format!("{}_trace.csv", stem));
+            std::fs::write(&trace_path, &traces).expect("Failed to write trace CSV");
+            println!("Best-found-vs-time traces exported to {:?}", trace_path);
+        }
+    }
+    
+    if let Some(html_path) = html {
+        // Tour thumbnails go to a sibling directory and are linked relatively
+        let stem = html_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let thumbs_name = format!("{}_tours", stem);
+        let thumbs_dir = html_path.with_file_name(&thumbs_name);
+        std::fs::create_dir_all(&thumbs_dir).expect("Failed to create thumbnail directory");
+        
+        let viz = Visualizer::new();
+        for (name, sol) in &best_solutions {
+            let file_name = format!("{}.svg", name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+            let svg = viz.generate_svg(&instance, sol);
+            viz.save_svg(&svg, thumbs_dir.join(&file_name)).expect("Failed to save thumbnail");
+            dashboard.add_thumbnail(&format!("{} ({:.2})", name, sol.cost), &format!("{}/{}", thumbs_name, file_name));
+        }
+        
+        let traces: Vec<(&str, &[(f64, f64)])> = best_solutions.iter()
+            .filter(|(_, sol)| !sol.convergence.is_empty())
+            .map(|(name, sol)| (name.as_str(), sol.convergence.as_slice()))
+            .collect();
+        if !traces.is_empty() {
+            let svg = viz.generate_convergence_svg(&traces);
+            viz.save_svg(&svg, thumbs_dir.join("convergence.svg")).expect("Failed to save convergence plot");
+            dashboard.add_thumbnail("Convergence (best runs)", &format!("{}/convergence.svg", thumbs_name));
+        }
+        
+        // The best tours of the four best algorithms, side by side
+        let mut ranked: Vec<Solution> = best_solutions.iter()
+            .map(|(name, sol)| Solution { algorithm: name.clone(), ..sol.clone() })
+            .collect();
+        ranked.sort_by(|a, b| a.cost.total_cmp(&b.cost));
+        if ranked.len() >= 2 {
+            ranked.truncate(4);
+            let svg = viz.generate_comparison_svg(&instance, &ranked);
+            viz.save_svg(&svg, thumbs_dir.join("comparison.svg")).expect("Failed to save comparison figure");
+            dashboard.add_thumbnail("Best tours compared", &format!("{}/comparison.svg", thumbs_name));
+        }
+        
+        dashboard.run_info = Some(RunInfo::capture(0, &format!("compare {:?} runs={} time_budget={:?}", path, runs, time_budget)));
+        dashboard.save(&html_path).expect("Failed to save dashboard");
+        println!("Dashboard saved to {:?}", html_path);
+    }
+}
+
+/// Algorithms of the `compare` command, in comparison order, with their
+/// selection key (shared with `benchmark`, see `JobKind::key`)
+fn compared_algorithms() -> Vec<(&'static str, &'static str, CompareSolver)> {
+    vec![
+        (JobKind::LocalSearch.key(), "MultiStart+VND", Box::new(|inst: &PDTSPInstance, seed: u64, deadline: Deadline| {
+            let rng = RngFactory::new(seed);
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng).with_deadline(deadline);
+            let mut sol = multi.construct(inst);
+            let vnd = VND::with_standard_operators().with_deadline(deadline);
+            vnd.improve(inst, &mut sol);
+            sol
+        })),
+        (JobKind::SimulatedAnnealing.key(), "SA", Box::new(|inst: &PDTSPInstance, seed: u64, deadline: Deadline| {
+            let rng = RngFactory::new(seed);
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng).with_deadline(deadline);
+            let mut sol = multi.construct(inst);
+            let mut sa = SimulatedAnnealing::new();
+            sa.seed = rng.derive("sa");
+            sa.deadline = deadline;
+            sa.improve(inst, &mut sol);
+            sol
+        })),
+        (JobKind::TabuSearch.key(), "Tabu", Box::new(|inst: &PDTSPInstance, seed: u64, deadline: Deadline| {
+            let rng = RngFactory::new(seed);
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng).with_deadline(deadline);
+            let mut sol = multi.construct(inst);
+            let mut ts = TabuSearch::new();
+            ts.seed = rng.derive("tabu");
+            ts.deadline = deadline;
+            ts.improve(inst, &mut sol);
+            sol
+        })),
+        (JobKind::IteratedLocalSearch.key(), "ILS", Box::new(|inst: &PDTSPInstance, seed: u64, deadline: Deadline| {
+            let rng = RngFactory::new(seed);
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng).with_deadline(deadline);
+            let mut sol = multi.construct(inst);
+            let mut ils = IteratedLocalSearch::new();
+            ils.seed = rng.derive("ils");
+            ils.deadline = deadline;
+            ils.improve(inst, &mut sol);
+            sol
+        })),
+        (GVNS_KEY, "GVNS", Box::new(|inst: &PDTSPInstance, seed: u64, deadline: Deadline| {
+            let rng = RngFactory::new(seed);
+            let multi = MultiStartConstruction::with_all_heuristics_seeded(&rng).with_deadline(deadline);
+            let mut sol = multi.construct(inst);
+            let mut vns = GeneralVNS::new();
+            vns.seed = rng.derive("vns");
+            vns.time_limit = vns.time_limit.min(deadline.remaining());
+            vns.improve(inst, &mut sol);
+            sol
+        })),
+        (JobKind::Genetic.key(), "GA", Box::new(|inst: &PDTSPInstance, seed: u64, deadline: Deadline| {
+            let rng = RngFactory::new(seed);
+            let config = GAConfig {
+                seed: rng.derive("ga"),
+                population_size: 50,
+                max_generations: 100,
+                time_limit: deadline.remaining().min(60.0),
+                ..Default::default()
+            };
+            let mut ga = GeneticAlgorithm::new(inst.clone(), config);
+            ga.run()
+        })),
+        (JobKind::Memetic.key(), "MA", Box::new(|inst: &PDTSPInstance, seed: u64, deadline: Deadline| {
+            let rng = RngFactory::new(seed);
+            let config = GAConfig {
+                seed: rng.derive("memetic"),
+                population_size: 30,
+                max_generations: 50,
+                time_limit: deadline.remaining().min(60.0),
+                ..Default::default()
+            };
+            let mut ma = MemeticAlgorithm::with_config(inst.clone(), config);
+            ma.run()
+        })),
+        (JobKind::AntColony.key(), "ACO", Box::new(|inst: &PDTSPInstance, seed: u64, deadline: Deadline| {
+            let rng = RngFactory::new(seed);
+            let config = ACOConfig {
+                seed: rng.derive("aco"),
+                num_ants: 15,
+                max_iterations: 50,
+                time_limit: deadline.remaining().min(60.0),
+                ..Default::default()
+            };
+            let mut aco = AntColonyOptimization::new(inst.clone(), config);
+            aco.run()
+        })),
+    ]
+}
+
+/// Load a solver configuration, or the defaults when no file is given
+fn load_solver_config(path: Option<&PathBuf>) -> SolverConfig {
+    match path {
+        Some(path) => SolverConfig::from_file(path).unwrap_or_else(|e| {
+            eprintln!("Error loading solver config: {}", e);
+            std::process::exit(1);
+        }),
+        None => SolverConfig::default(),
+    }
+}
+
+fn report_run(spec_path: &PathBuf) {
+    let spec = match ExperimentSpec::from_file(spec_path) {
+        Ok(spec) => spec,
+        Err(e) => {
+            eprintln!("Error loading experiment spec: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    println!("Running experiment '{}' from {:?}...", spec.name, spec_path);
+    let mut experiment = Experiment::new(spec);
+    if let Err(e) = experiment.run() {
+        eprintln!("Experiment failed: {}", e);
+        std::process::exit(1);
+    }
+    
+    match experiment.write_artifacts() {
+        Ok(paths) => {
+            println!("\nArtifacts:");
+            for path in paths {
+                println!("  {:?}", path);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error writing artifacts: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn robustness(
+    path: &PathBuf,
+    solution: Option<&PathBuf>,
+    algorithm: &str,
+    time_limit: f64,
+    scenarios: usize,
+    coordinate_noise: f64,
+    demand_noise: f64,
+    seed: u64,
+    output: Option<&PathBuf>,
+) {
+    let instance = match PDTSPInstance::from_file(path) {
+        Ok(inst) => inst,
+        Err(e) => {
+            eprintln!("Error loading instance: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    let tour: Result<Vec<usize>, String> = match solution {
+        Some(solution_path) => std::fs::read_to_string(solution_path)
+            .map_err(|e| format!("Cannot open solution: {}", e))
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).map_err(|e| format!("Invalid solution JSON: {}", e)))
+            .and_then(|value| serde_json::from_value(value["tour"].clone()).map_err(|e| format!("Invalid solution tour: {}", e))),
+        None => run_algorithm(algorithm, &instance, seed, time_limit).map(|solution| solution.tour),
+    };
+    let tour = match tour {
+        Ok(tour) if !tour.is_empty() && tour.iter().all(|&node| node < instance.dimension) => tour,
+        Ok(_) => {
+            eprintln!("Error: the tour does not match the instance");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    let report = evaluate_robustness(&instance, &tour, scenarios, seed, coordinate_noise, demand_noise);
+    println!("{}", report);
+    if let Some(out_path) = output {
+        std::fs::write(out_path, report.to_csv()).expect("Failed to write output");
+        println!("Scenario results saved to {:?}", out_path);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn pareto(
+    path: &PathBuf,
+    algorithm: &str,
+    time_limit: f64,
+    levels: usize,
+    max_profit: i32,
+    seed: u64,
+    output: Option<&PathBuf>,
+    svg: Option<&PathBuf>,
+) {
+    let mut instance = match PDTSPInstance::from_file(path) {
+        Ok(inst) => inst,
+        Err(e) => {
+            eprintln!("Error loading instance: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if max_profit > 0 {
+        instance.assign_random_profits(seed, max_profit);
+    }
+    
+    let initial = match run_algorithm(algorithm, &instance, seed, time_limit) {
+        Ok(solution) if solution.feasible => solution,
+        Ok(_) => {
+            eprintln!("Error: {} found no feasible starting tour", algorithm);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    let front = EpsilonConstraint::with_params(levels, time_limit, seed).solve(&instance, &initial);
+    println!("Pareto front: {} solutions", front.len());
+    println!("{:>8}  {:>12}  {:>9}", "profit", "cost", "customers");
+    for solution in front.solutions() {
+        let customers = solution.tour.iter().filter(|&&n| n != 0).count();
+        println!("{:>8}  {:>12.2}  {:>9}", solution.total_profit, solution.cost, customers);
+    }
+    
+    if let Some(out_path) = output {
+        std::fs::write(out_path, front.to_csv()).expect("Failed to write output");
+        println!("Front saved to {:?}", out_path);
+    }
+    if let Some(svg_path) = svg {
+        let viz = Visualizer::new();
+        viz.save_svg(&viz.generate_pareto_front_svg(&front), svg_path).expect("Failed to save SVG");
+        println!("Front plot saved to {:?}", svg_path);
+    }
+}
+
+fn tune(spec_path: &PathBuf, instances: Option<&Path>, output: &PathBuf) {
+    let spec = match TuningSpec::from_file(spec_path) {
+        Ok(spec) => spec,
+        Err(e) => {
+            eprintln!("Error loading tuning spec: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    println!("Tuning {} ({:?}, {} samples, {}s per run)...", spec.algorithm, spec.method, spec.samples, spec.budget);
+    let result = Tuner::from_spec(spec, instances).and_then(|mut tuner| tuner.run());
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Tuning failed: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    println!("\nBest configuration (score {:.4}, default {:.4}, {} runs):", result.score, result.default_score, result.runs);
+    if result.values.is_empty() {
+        println!("  (defaults)");
+    }
+    for (name, value) in &result.values {
+        println!("  {} = {}", name, value);
+    }
+    
+    match result.config.save(output) {
+        Ok(()) => println!("Configuration saved to {:?}", output),
+        Err(e) => {
+            eprintln!("Error saving configuration: {}", e);
+            std::process::exit(1);
+        }
     }
 }
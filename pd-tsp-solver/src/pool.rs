@@ -0,0 +1,405 @@
+//! Pool of the best distinct solutions found by a run.
+//!
+//! A `SolutionPool` keeps up to `capacity` solutions, best first. Two tours
+//! count as the same alternative when their broken-pairs distance (number of
+//! arcs of one that the other does not use) is below a fraction of the tour
+//! length: a new solution close to a pool member replaces it only if it is
+//! better, so the pool holds genuinely different alternates instead of small
+//! variations of the best tour.
+//!
+//! A `RoutePool` collects the routes (customers served between two depot
+//! visits) of the tours of many runs, keeping the cheapest order of each
+//! customer set. Its set partitioning post-optimization selects the cheapest
+//! routes serving every customer exactly once, which can combine the best
+//! routes of different runs.
+
+use std::collections::HashMap;
+
+use crate::exact::set_partitioning::{solve_set_partitioning, DEFAULT_MAX_NODES};
+use crate::heuristics::stopping::Deadline;
+use crate::instance::PDTSPInstance;
+use crate::solution::Solution;
+
+/// The best distinct solutions seen so far
+#[derive(Debug, Clone)]
+pub struct SolutionPool {
+    /// Maximum number of solutions kept
+    pub capacity: usize,
+    /// Minimum broken-pairs distance between members, as a fraction of the
+    /// number of arcs (at least one arc always differs)
+    pub min_distance: f64,
+    members: Vec<Solution>,
+}
+
+impl SolutionPool {
+    pub fn new(capacity: usize) -> Self {
+        SolutionPool {
+            capacity,
+            min_distance: 0.1,
+            members: Vec::new(),
+        }
+    }
+
+    /// Set the minimum distance between members (fraction of the arcs)
+    pub fn with_min_distance(mut self, min_distance: f64) -> Self {
+        self.min_distance = min_distance;
+        self
+    }
+
+    /// Offer a solution; returns whether it entered the pool
+    pub fn offer(&mut self, solution: &Solution) -> bool {
+        if solution.tour.is_empty() || self.capacity == 0 {
+            return false;
+        }
+        let threshold = ((self.min_distance * solution.tour.len() as f64).ceil() as usize).max(1);
+        let close: Vec<usize> = (0..self.members.len())
+            .filter(|&k| broken_pairs_distance(&self.members[k].tour, &solution.tour) < threshold)
+            .collect();
+        if close.iter().any(|&k| !is_better(solution, &self.members[k])) {
+            return false;
+        }
+
+        for &k in close.iter().rev() {
+            self.members.remove(k);
+        }
+        let mut member = solution.clone();
+        member.snapshots.clear();
+        member.convergence.clear();
+        let position = self.members.iter().position(|m| is_better(&member, m)).unwrap_or(self.members.len());
+        if position >= self.capacity {
+            return false;
+        }
+        self.members.insert(position, member);
+        self.members.truncate(self.capacity);
+        true
+    }
+
+    /// Offer the final solution of a run and the best tours recorded along
+    /// its search (`Solution::snapshots`)
+    pub fn offer_run(&mut self, instance: &PDTSPInstance, solution: &Solution) {
+        self.offer(solution);
+        for (_, tour) in &solution.snapshots {
+            self.offer(&Solution::from_tour(instance, tour.clone(), &solution.algorithm));
+        }
+    }
+
+    /// Members, best first
+    pub fn members(&self) -> &[Solution] {
+        &self.members
+    }
+
+    pub fn best(&self) -> Option<&Solution> {
+        self.members.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn into_solutions(self) -> Vec<Solution> {
+        self.members
+    }
+
+    /// Fraction of the members using each edge (see `edge_frequencies`)
+    pub fn edge_frequencies(&self, dimension: usize) -> EdgeFrequencies {
+        edge_frequencies(self.members.iter().map(|m| m.tour.as_slice()), dimension)
+    }
+}
+
+impl Default for SolutionPool {
+    fn default() -> Self {
+        Self::new(5)
+    }
+}
+
+/// A route of a tour: the customers served between two depot visits
+#[derive(Debug, Clone, PartialEq)]
+pub struct PooledRoute {
+    /// Customers in visiting order, without the depot
+    pub nodes: Vec<usize>,
+    /// Cost of the route inside a joined tour, back to the depot. The fixed
+    /// cost of the vehicle is left out: the joined tour pays it once
+    pub cost: f64,
+    /// Same, for the last route of an open tour, which ends at its last
+    /// customer (equal to `cost` for closed tours)
+    pub open_cost: f64,
+}
+
+/// Routes of the tours seen so far, the cheapest order of each customer set
+#[derive(Debug, Clone, Default)]
+pub struct RoutePool {
+    routes: Vec<PooledRoute>,
+    /// Index of the route of each sorted customer set
+    index: HashMap<Vec<usize>, usize>,
+}
+
+impl RoutePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the routes of a tour, split at its depot visits (and first at the
+    /// route length limit, if any); returns the number of new or cheaper routes
+    pub fn offer_tour(&mut self, instance: &PDTSPInstance, tour: &[usize]) -> usize {
+        let tour = instance.split_routes(tour).unwrap_or_else(|| tour.to_vec());
+        let start = usize::from(tour.first() == Some(&0));
+        tour[start..].split(|&node| node == 0)
+            .filter(|route| !route.is_empty())
+            .filter(|route| self.offer_route(instance, route))
+            .count()
+    }
+
+    /// Add one route (customers without the depot) if it is feasible on its
+    /// own and cheaper than the pooled order of its customers
+    pub fn offer_route(&mut self, instance: &PDTSPInstance, nodes: &[usize]) -> bool {
+        let mut tour = Vec::with_capacity(nodes.len() + 1);
+        tour.push(0);
+        tour.extend_from_slice(nodes);
+        if !instance.is_feasible(&tour) || !instance.route_lengths_feasible(&tour) {
+            return false;
+        }
+        let (cost, open_cost) = route_costs(instance, &tour);
+        let mut key = nodes.to_vec();
+        key.sort_unstable();
+        match self.index.get(&key) {
+            Some(&i) if cost >= self.routes[i].cost - 1e-9 => false,
+            Some(&i) => {
+                self.routes[i] = PooledRoute { nodes: nodes.to_vec(), cost, open_cost };
+                true
+            }
+            None => {
+                self.index.insert(key, self.routes.len());
+                self.routes.push(PooledRoute { nodes: nodes.to_vec(), cost, open_cost });
+                true
+            }
+        }
+    }
+
+    pub fn routes(&self) -> &[PooledRoute] {
+        &self.routes
+    }
+
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Set partitioning post-optimization: the cheapest pooled routes serving
+    /// every customer exactly once, joined by depot visits into one tour.
+    /// Returns `None` if the routes cannot cover the customers or the joined
+    /// tour is infeasible (routes after the first start empty, while the
+    /// pooled routes were checked with the starting load).
+    ///
+    /// For open tours, only the last route skips its return to the depot:
+    /// each route also gets an open column at `open_cost`, covering an extra
+    /// element that exactly one selected route must cover, and that route is
+    /// placed last.
+    pub fn best_combination(&self, instance: &PDTSPInstance, deadline: Deadline) -> Option<Solution> {
+        let customers = instance.dimension - 1;
+        let mut columns: Vec<Vec<usize>> = Vec::new();
+        let mut costs = Vec::new();
+        // Route and whether it ends the open tour, for each column
+        let mut origin = Vec::new();
+        for (r, route) in self.routes.iter().enumerate() {
+            let column: Vec<usize> = route.nodes.iter().map(|&node| node - 1).collect();
+            if instance.open_tour {
+                columns.push(column.iter().copied().chain([customers]).collect());
+                costs.push(route.open_cost);
+                origin.push((r, true));
+            }
+            columns.push(column);
+            costs.push(route.cost);
+            origin.push((r, false));
+        }
+        let elements = customers + usize::from(instance.open_tour);
+        let result = solve_set_partitioning(&columns, &costs, elements, DEFAULT_MAX_NODES, deadline)?;
+
+        let mut selected: Vec<(usize, bool)> = result.columns.iter().map(|&c| origin[c]).collect();
+        selected.sort_by_key(|&(r, last)| (last, r));
+        let mut tour = vec![0];
+        for (r, _) in selected {
+            if tour.len() > 1 {
+                tour.push(0);
+            }
+            tour.extend_from_slice(&self.routes[r].nodes);
+        }
+        if !instance.is_feasible_strict(&tour) || !instance.route_lengths_feasible(&tour) {
+            return None;
+        }
+        let mut solution = Solution::from_tour(instance, tour, "SetPartitioning");
+        solution.iterations = Some(result.nodes);
+        Some(solution)
+    }
+}
+
+/// Costs of a route (depot first) as part of a joined tour: followed by a
+/// depot visit, and as the last route of the tour. The fixed cost of the
+/// vehicle is left out.
+fn route_costs(instance: &PDTSPInstance, tour: &[usize]) -> (f64, f64) {
+    let fixed = instance.vehicle.as_ref().map_or(0.0, |vehicle| vehicle.fixed_cost);
+    let last = instance.tour_cost(tour) - fixed;
+    if !instance.open_tour {
+        return (last, last);
+    }
+    let mut closed = tour.to_vec();
+    closed.push(0);
+    (instance.tour_cost(&closed) - fixed, last)
+}
+
+/// Feasible solutions first, then lower cost
+fn is_better(a: &Solution, b: &Solution) -> bool {
+    (a.feasible && !b.feasible) || (a.feasible == b.feasible && a.cost < b.cost - 1e-9)
+}
+
+/// Number of arcs of tour `a` (closing arc included) that tour `b` does not
+/// use in either direction
+pub fn broken_pairs_distance(a: &[usize], b: &[usize]) -> usize {
+    let size = a.iter().chain(b).max().map_or(0, |&m| m + 1);
+    let mut next = vec![usize::MAX; size];
+    let mut prev = vec![usize::MAX; size];
+    for (k, &node) in b.iter().enumerate() {
+        let succ = b[(k + 1) % b.len()];
+        next[node] = succ;
+        prev[succ] = node;
+    }
+    (0..a.len())
+        .filter(|&k| {
+            let (u, v) = (a[k], a[(k + 1) % a.len()]);
+            next[u] != v && prev[u] != v
+        })
+        .count()
+}
+
+/// Fraction of the tours using each edge in either direction (closing arc
+/// included). Only the edges of the tours are stored, so the memory grows
+/// with the number of tours times their length instead of `dimension`²
+#[derive(Debug, Clone, Default)]
+pub struct EdgeFrequencies {
+    frequency: HashMap<(usize, usize), f64>,
+}
+
+impl EdgeFrequencies {
+    /// Frequency of the edge `{u, v}` (0 for edges of no tour)
+    pub fn get(&self, u: usize, v: usize) -> f64 {
+        self.frequency.get(&(u.min(v), u.max(v))).copied().unwrap_or(0.0)
+    }
+
+    /// Number of distinct edges used by the tours
+    pub fn len(&self) -> usize {
+        self.frequency.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frequency.is_empty()
+    }
+}
+
+/// Edge frequencies of `tours`, ignoring nodes beyond `dimension`
+pub fn edge_frequencies<'a>(tours: impl IntoIterator<Item = &'a [usize]>, dimension: usize) -> EdgeFrequencies {
+    let mut frequency = HashMap::new();
+    let mut count = 0;
+    for tour in tours {
+        count += 1;
+        for k in 0..tour.len() {
+            let (u, v) = (tour[k], tour[(k + 1) % tour.len()]);
+            if u < dimension && v < dimension && u != v {
+                *frequency.entry((u.min(v), u.max(v))).or_insert(0.0) += 1.0;
+            }
+        }
+    }
+    if count > 0 {
+        frequency.values_mut().for_each(|f| *f /= count as f64);
+    }
+    EdgeFrequencies { frequency }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solution(tour: Vec<usize>, cost: f64) -> Solution {
+        Solution { tour, cost, feasible: true, ..Solution::new() }
+    }
+
+    #[test]
+    fn test_broken_pairs_distance() {
+        let tour: Vec<usize> = (0..8).collect();
+        assert_eq!(broken_pairs_distance(&tour, &tour), 0);
+        // Reversal and rotation keep every arc
+        assert_eq!(broken_pairs_distance(&tour, &[0, 7, 6, 5, 4, 3, 2, 1]), 0);
+        // A 2-opt move breaks two arcs
+        assert_eq!(broken_pairs_distance(&tour, &[0, 1, 5, 4, 3, 2, 6, 7]), 2);
+    }
+
+    #[test]
+    fn test_pool_keeps_best_distinct_solutions() {
+        let mut pool = SolutionPool::new(2).with_min_distance(0.3);
+        let base: Vec<usize> = (0..10).collect();
+        assert!(pool.offer(&solution(base.clone(), 100.0)));
+        // Two arcs away from the best: a variation, and a worse one
+        assert!(!pool.offer(&solution(vec![0, 1, 3, 2, 4, 5, 6, 7, 8, 9], 105.0)));
+        assert_eq!(pool.len(), 1);
+        // A better variation replaces it
+        assert!(pool.offer(&solution(vec![0, 1, 3, 2, 4, 5, 6, 7, 8, 9], 95.0)));
+        assert_eq!(pool.len(), 1);
+
+        let distinct = vec![0, 5, 1, 6, 2, 7, 3, 8, 4, 9];
+        assert!(pool.offer(&solution(distinct.clone(), 120.0)));
+        assert!(!pool.offer(&solution(vec![0, 9, 8, 7, 6, 4, 2, 5, 3, 1], 130.0)));
+        let costs: Vec<f64> = pool.members().iter().map(|s| s.cost).collect();
+        assert_eq!(costs, vec![95.0, 120.0]);
+        assert_eq!(pool.best().unwrap().cost, 95.0);
+
+        let mut infeasible = solution(vec![0, 2, 4, 6, 8, 1, 3, 5, 7, 9], 10.0);
+        infeasible.feasible = false;
+        assert!(!pool.offer(&infeasible));
+    }
+
+    #[test]
+    fn test_route_pool_combines_routes_of_different_tours() {
+        let text = "NAME: routes\nDIMENSION: 5\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 10 0\n3 11 0\n4 -10 0\n5 -11 0\nDEMAND_SECTION\n1 0\n2 2\n3 -2\n4 1\n5 -1\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+
+        // Each tour has one feasible route, serving the other side in the
+        // wrong order
+        let mut pool = RoutePool::new();
+        assert_eq!(pool.offer_tour(&instance, &[0, 1, 2, 0, 4, 3]), 1);
+        assert_eq!(pool.offer_tour(&instance, &[0, 2, 1, 0, 3, 4]), 1);
+        assert!(!pool.offer_route(&instance, &[1, 2]));
+        assert_eq!(pool.len(), 2);
+
+        let best = pool.best_combination(&instance, Deadline::none()).unwrap();
+        assert_eq!(best.tour, vec![0, 1, 2, 0, 3, 4]);
+        assert!(best.feasible);
+        assert!((best.cost - 44.0).abs() < 1e-9);
+
+        // Customers 3 and 4 alone cannot be completed
+        let mut partial = RoutePool::new();
+        partial.offer_tour(&instance, &[0, 1, 2, 0, 4, 3]);
+        assert!(partial.best_combination(&instance, Deadline::none()).is_none());
+    }
+
+    #[test]
+    fn test_route_pool_prices_open_tours_like_the_joined_tour() {
+        let text = "NAME: routes\nDIMENSION: 5\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 10 0\n3 11 0\n4 -20 0\n5 -21 0\nDEMAND_SECTION\n1 0\n2 2\n3 -2\n4 1\n5 -1\nEOF\n";
+        let mut instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        instance.open_tour = true;
+
+        let mut pool = RoutePool::new();
+        assert!(pool.offer_route(&instance, &[3, 4]));
+        assert!(pool.offer_route(&instance, &[1, 2]));
+        assert_eq!((pool.routes()[0].cost, pool.routes()[0].open_cost), (42.0, 21.0));
+
+        // Only the far route may skip its return, so it comes last
+        let best = pool.best_combination(&instance, Deadline::none()).unwrap();
+        assert_eq!(best.tour, vec![0, 1, 2, 0, 3, 4]);
+        assert!((best.cost - 43.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,232 @@
+//! Algorithm selection from instance features.
+//!
+//! A `Portfolio` is a table of training instances, each described by a few
+//! `InstanceFeatures` and labelled with the algorithm that did best on it in
+//! a benchmark. A new instance gets the algorithm of its nearest training
+//! instance in feature space, which is what `solve --algorithm auto` runs.
+//! The table is learned from the results of `benchmark --portfolio` and
+//! stored as JSON.
+
+use crate::benchmark::AlgorithmResult;
+use crate::instance::analysis::{capacity_tightness, clustering};
+use crate::instance::PDTSPInstance;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Algorithm chosen when the portfolio is empty
+pub const DEFAULT_ALGORITHM: &str = "hybrid";
+
+/// Features the selection is based on
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InstanceFeatures {
+    /// Number of nodes, depot included
+    pub size: usize,
+    /// Mean load span of random orders relative to the capacity
+    pub capacity_tightness: f64,
+    /// Coefficient of variation of the customer demand magnitudes
+    pub demand_variation: f64,
+    /// Clark-Evans ratio of the customer locations (1 without spread)
+    pub dispersion: f64,
+}
+
+impl InstanceFeatures {
+    pub fn extract(instance: &PDTSPInstance) -> Self {
+        let demands: Vec<f64> = instance.nodes.iter().skip(1).map(|node| node.demand.abs() as f64).collect();
+        let count = demands.len().max(1) as f64;
+        let mean = demands.iter().sum::<f64>() / count;
+        let variance = demands.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / count;
+        InstanceFeatures {
+            size: instance.dimension,
+            capacity_tightness: capacity_tightness(instance, 200, 0).mean_peak_ratio,
+            demand_variation: if mean > 0.0 { variance.sqrt() / mean } else { 0.0 },
+            dispersion: clustering(instance).unwrap_or(1.0),
+        }
+    }
+
+    /// Distance between feature vectors; the size is compared on a log scale
+    /// so that every feature varies on about the same range
+    pub fn distance(&self, other: &InstanceFeatures) -> f64 {
+        let size = (self.size.max(1) as f64).ln() - (other.size.max(1) as f64).ln();
+        (size.powi(2)
+            + (self.capacity_tightness - other.capacity_tightness).powi(2)
+            + (self.demand_variation - other.demand_variation).powi(2)
+            + (self.dispersion - other.dispersion).powi(2))
+        .sqrt()
+    }
+}
+
+/// Training instance of a portfolio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortfolioEntry {
+    pub instance: String,
+    pub features: InstanceFeatures,
+    /// Name of the best algorithm, as accepted by `--algorithm`
+    pub algorithm: String,
+    /// Mean cost of its runs
+    pub mean_cost: f64,
+}
+
+/// Learned table of the best algorithm per training instance
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Portfolio {
+    pub entries: Vec<PortfolioEntry>,
+}
+
+impl Portfolio {
+    pub fn new() -> Self {
+        Portfolio { entries: Vec::new() }
+    }
+
+    /// Learn the table from benchmark results: on each instance, the
+    /// algorithm with the lowest mean cost among those whose runs are all
+    /// feasible. Construction heuristics and operators without a
+    /// command-line algorithm are left out.
+    pub fn train(instances: &[PDTSPInstance], results: &[AlgorithmResult]) -> Self {
+        let mut entries = Vec::new();
+        for instance in instances {
+            // Per algorithm: total cost, runs, all runs feasible
+            let mut runs: BTreeMap<&str, (f64, usize, bool)> = BTreeMap::new();
+            for result in results.iter().filter(|r| r.instance == instance.name) {
+                let Some(algorithm) = benchmark_algorithm(&result.algorithm) else {
+                    continue;
+                };
+                let entry = runs.entry(algorithm).or_insert((0.0, 0, true));
+                entry.0 += result.cost;
+                entry.1 += 1;
+                entry.2 &= result.feasible;
+            }
+            let best = runs.into_iter()
+                .filter(|(_, (_, _, feasible))| *feasible)
+                .map(|(algorithm, (total, count, _))| (algorithm, total / count as f64))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((algorithm, mean_cost)) = best {
+                entries.push(PortfolioEntry {
+                    instance: instance.name.clone(),
+                    features: InstanceFeatures::extract(instance),
+                    algorithm: algorithm.to_string(),
+                    mean_cost,
+                });
+            }
+        }
+        Portfolio { entries }
+    }
+
+    /// Algorithm of the training instance nearest to `features`
+    pub fn select_for(&self, features: &InstanceFeatures) -> &str {
+        self.entries.iter()
+            .min_by(|a, b| a.features.distance(features).total_cmp(&b.features.distance(features)))
+            .map_or(DEFAULT_ALGORITHM, |entry| entry.algorithm.as_str())
+    }
+
+    /// Algorithm to run on `instance`
+    pub fn select(&self, instance: &PDTSPInstance) -> &str {
+        self.select_for(&InstanceFeatures::extract(instance))
+    }
+
+    /// How often each algorithm is the best one of the table
+    pub fn wins(&self) -> HashMap<&str, usize> {
+        let mut wins = HashMap::new();
+        for entry in &self.entries {
+            *wins.entry(entry.algorithm.as_str()).or_insert(0) += 1;
+        }
+        wins
+    }
+
+    /// Load a portfolio saved as JSON
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        serde_json::from_str(&text).map_err(|e| format!("Invalid portfolio {:?}: {}", path, e))
+    }
+
+    /// Write the portfolio as pretty-printed JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to write {:?}: {}", path.as_ref(), e))
+    }
+}
+
+/// Command-line name of the algorithm behind a benchmark result name
+/// ("SA-run3", "TabuSearch", "Savings + VND", ...)
+pub fn benchmark_algorithm(name: &str) -> Option<&'static str> {
+    if let Some((_, search)) = name.rsplit_once(" + ") {
+        return match search {
+            "VND" => Some("vnd"),
+            "2-Opt" => Some("two-opt"),
+            _ => None,
+        };
+    }
+    let base = name.split_once("-run").map_or(name, |(base, _)| base);
+    match base {
+        "SA" => Some("sa"),
+        "TabuSearch" => Some("tabu"),
+        "ILS" => Some("ils"),
+        "GA" => Some("ga"),
+        "MA" => Some("memetic"),
+        "ACO" => Some("aco"),
+        "MMAS" => Some("mmas"),
+        "Hybrid" => Some("hybrid"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(algorithm: &str, instance: &str, cost: f64, feasible: bool) -> AlgorithmResult {
+        AlgorithmResult {
+            algorithm: algorithm.to_string(),
+            instance: instance.to_string(),
+            dimension: 5,
+            capacity: 10,
+            cost,
+            feasible,
+            time: 1.0,
+            cpu_time: None,
+            peak_rss: None,
+            iterations: None,
+            gap_to_best: None,
+            lower_bound: None,
+            gap_to_bound: None,
+            emissions: None,
+            convergence: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_portfolio_training_and_selection() {
+        let small = PDTSPInstance::from_tsplib_str(
+            "NAME: small\nDIMENSION: 5\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 1 1\n4 0 1\n5 2 2\nDEMAND_SECTION\n1 0\n2 2\n3 -2\n4 1\n5 -1\nEOF\n",
+        ).unwrap();
+        let mut text = "NAME: large\nDIMENSION: 40\nCAPACITY: 10\nNODE_COORD_SECTION\n".to_string();
+        text.extend((1..=40).map(|i| format!("{} {} {}\n", i, i * 7 % 40, i * 13 % 40)));
+        text.push_str("DEMAND_SECTION\n");
+        text.extend((1..=40).map(|i| format!("{} {}\n", i, if i == 1 { 0 } else if i % 2 == 0 { 3 } else { -3 })));
+        let large = PDTSPInstance::from_tsplib_str(&(text + "EOF\n")).unwrap();
+
+        let results = vec![
+            result("SA-run0", "small", 10.0, true),
+            result("SA-run1", "small", 14.0, true),
+            result("ILS-run0", "small", 11.0, true),
+            result("TabuSearch", "small", 9.0, false),
+            result("NearestNeighbor", "small", 5.0, true),
+            result("MA-run0", "large", 100.0, true),
+            result("Savings + VND", "large", 120.0, true),
+        ];
+        let portfolio = Portfolio::train(&[small.clone(), large.clone()], &results);
+        let chosen: Vec<&str> = portfolio.entries.iter().map(|e| e.algorithm.as_str()).collect();
+        assert_eq!(chosen, ["ils", "memetic"]);
+        assert_eq!(portfolio.entries[0].mean_cost, 11.0);
+
+        assert_eq!(portfolio.select(&small), "ils");
+        assert_eq!(portfolio.select(&large), "memetic");
+        assert_eq!(Portfolio::new().select(&small), DEFAULT_ALGORITHM);
+        assert_eq!(portfolio.wins().get("ils"), Some(&1));
+        let json = serde_json::to_string(&portfolio).unwrap();
+        assert_eq!(serde_json::from_str::<Portfolio>(&json).unwrap().select(&large), "memetic");
+        assert_eq!(benchmark_algorithm("Regret-2 + Or-Opt"), None);
+    }
+}
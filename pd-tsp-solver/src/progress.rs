@@ -0,0 +1,145 @@
+//! Structured progress reporting for long-running solvers.
+//!
+//! Solvers accept an optional callback receiving a `ProgressEvent` at each
+//! iteration (or generation / temperature step), so that GUIs and services
+//! can display progress instead of parsing stdout.
+
+use std::sync::Mutex;
+
+/// Progress snapshot emitted by a solver
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressEvent {
+    /// Name of the emitting algorithm
+    pub algorithm: String,
+    /// Current iteration
+    pub iteration: usize,
+    /// Best cost found so far
+    pub best_cost: f64,
+    /// Elapsed time in seconds
+    pub elapsed: f64,
+    /// Current temperature (simulated annealing)
+    pub temperature: Option<f64>,
+    /// Current generation (genetic algorithms)
+    pub generation: Option<usize>,
+    /// Free-form status message
+    pub message: Option<String>,
+}
+
+impl ProgressEvent {
+    pub fn new(algorithm: &str, iteration: usize, best_cost: f64, elapsed: f64) -> Self {
+        ProgressEvent {
+            algorithm: algorithm.to_string(),
+            iteration,
+            best_cost,
+            elapsed,
+            temperature: None,
+            generation: None,
+            message: None,
+        }
+    }
+
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_generation(mut self, generation: usize) -> Self {
+        self.generation = Some(generation);
+        self
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+impl std::fmt::Display for ProgressEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] iter {}  best {:.3}  elapsed {:.2}s", self.algorithm, self.iteration, self.best_cost, self.elapsed)?;
+        if let Some(generation) = self.generation {
+            write!(f, "  gen {}", generation)?;
+        }
+        if let Some(temperature) = self.temperature {
+            write!(f, "  temp {:.3}", temperature)?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, "  {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Boxed progress callback
+pub type ProgressCallback = Box<dyn FnMut(ProgressEvent) + Send>;
+
+/// Holds a progress callback behind a mutex so that solvers working through
+/// `&self` (e.g. `LocalSearch::improve`) can still invoke an `FnMut`, while
+/// remaining `Send + Sync`.
+pub struct ProgressHandler {
+    callback: Mutex<ProgressCallback>,
+}
+
+impl ProgressHandler {
+    pub fn new(callback: impl FnMut(ProgressEvent) + Send + 'static) -> Self {
+        ProgressHandler { callback: Mutex::new(Box::new(callback)) }
+    }
+
+    /// Deliver an event to the callback
+    pub fn emit(&self, event: ProgressEvent) {
+        if let Ok(mut callback) = self.callback.lock() {
+            callback(event);
+        }
+    }
+}
+
+/// Emit an event to the handler if one is set, and as a `tracing` debug
+/// event when enabled; the event is only built when needed
+#[inline]
+pub fn notify(handler: &Option<ProgressHandler>, event: impl FnOnce() -> ProgressEvent) {
+    let traced = tracing::enabled!(tracing::Level::DEBUG);
+    if handler.is_none() && !traced {
+        return;
+    }
+    let event = event();
+    if traced {
+        trace_event(&event);
+    }
+    if let Some(handler) = handler {
+        handler.emit(event);
+    }
+}
+
+/// Record a progress event as a `tracing` event with one field per value
+pub fn trace_event(event: &ProgressEvent) {
+    tracing::debug!(
+        algorithm = %event.algorithm,
+        iteration = event.iteration,
+        best_cost = event.best_cost,
+        elapsed = event.elapsed,
+        temperature = event.temperature,
+        generation = event.generation,
+        status = event.message.as_deref(),
+        "progress"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_progress_handler_collects_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let handler = Some(ProgressHandler::new(move |e| sink.lock().unwrap().push(e)));
+
+        notify(&handler, || ProgressEvent::new("SA", 1, 10.0, 0.5).with_temperature(100.0));
+        notify(&None, || panic!("event must not be built without a handler"));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].temperature, Some(100.0));
+    }
+}
@@ -0,0 +1,128 @@
+//! Run metadata attached to every artifact produced by the solver.
+//!
+//! A `RunInfo` records which code and configuration produced a solution,
+//! benchmark export or figure, so that files found later can be traced back.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Metadata describing a single solver run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunInfo {
+    /// Crate version (from Cargo.toml)
+    pub crate_version: String,
+    /// Git commit hash of the source tree ("unknown" if unavailable)
+    pub git_hash: String,
+    /// Cargo features enabled in this build
+    pub features: Vec<String>,
+    /// Random seed used for the run
+    pub seed: u64,
+    /// Digest of the run configuration (FNV-1a, hex)
+    pub config_digest: String,
+    /// Start time of the run (RFC 3339)
+    pub start_time: String,
+    /// Host the run was executed on
+    pub hostname: String,
+}
+
+impl RunInfo {
+    /// Capture run metadata now. `config` is any textual description of the
+    /// configuration (typically a `Debug` dump); only its digest is stored.
+    pub fn capture(seed: u64, config: &str) -> Self {
+        RunInfo {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: git_hash(),
+            features: enabled_features(),
+            seed,
+            config_digest: config_digest(config),
+            start_time: chrono::Local::now().to_rfc3339(),
+            hostname: hostname(),
+        }
+    }
+
+    /// One-line summary used in report and figure footers
+    pub fn summary(&self) -> String {
+        let features = if self.features.is_empty() {
+            "none".to_string()
+        } else {
+            self.features.join("+")
+        };
+        format!(
+            "pd-tsp-solver v{} ({}) | features: {} | seed: {} | config: {} | {} @ {}",
+            self.crate_version, self.git_hash, features, self.seed,
+            self.config_digest, self.start_time, self.hostname
+        )
+    }
+
+    /// Write this metadata as JSON next to an exported file
+    /// (`results.csv` -> `results.csv.run.json`).
+    pub fn write_sidecar<P: AsRef<Path>>(&self, artifact: P) -> std::io::Result<()> {
+        let mut sidecar = artifact.as_ref().as_os_str().to_owned();
+        sidecar.push(".run.json");
+        let json = serde_json::to_string_pretty(self)
+            .map_err(std::io::Error::other)?;
+        std::fs::write(sidecar, json)
+    }
+}
+
+/// Stable 64-bit FNV-1a digest of a configuration string
+pub fn config_digest(config: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in config.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Git hash embedded by `build.rs` ("unknown" outside a git checkout)
+fn git_hash() -> String {
+    env!("PDTSP_GIT_HASH").to_string()
+}
+
+fn enabled_features() -> Vec<String> {
+    [
+        ("gurobi", cfg!(feature = "gurobi")),
+        ("osrm", cfg!(feature = "osrm")),
+        ("parquet", cfg!(feature = "parquet")),
+        ("resvg", cfg!(feature = "resvg")),
+        ("server", cfg!(feature = "server")),
+        ("sqlite", cfg!(feature = "sqlite")),
+        ("wasm", cfg!(feature = "wasm")),
+    ]
+    .iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
+
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_digest_is_stable() {
+        assert_eq!(config_digest(""), "cbf29ce484222325");
+        assert_eq!(config_digest("seed=42"), config_digest("seed=42"));
+        assert_ne!(config_digest("seed=42"), config_digest("seed=43"));
+
+        let info = RunInfo::capture(7, "cfg");
+        assert_eq!(info.seed, 7);
+        assert!(info.summary().contains("seed: 7"));
+        assert!(!info.git_hash.is_empty());
+        assert_eq!(info.features.is_empty(), !cfg!(any(
+            feature = "gurobi", feature = "osrm", feature = "parquet", feature = "resvg",
+            feature = "server", feature = "sqlite", feature = "wasm"
+        )));
+    }
+}
@@ -0,0 +1,165 @@
+//! Machine-readable run records.
+//!
+//! A `RunRecord` holds everything needed to analyze one algorithm run after
+//! the fact: the instance, algorithm, seed, a snapshot of the configuration,
+//! the cost, profit and objective, feasibility, time, iterations, the
+//! convergence trace and the operator statistics. Records are stored as JSON Lines (one record per line),
+//! which `solve --record` appends to and `benchmark` writes as `runs.jsonl`.
+//!
+//! JSON has no infinity, so the cost and objective of runs that found no
+//! solution are written as `null` and read back as infinite.
+
+use crate::heuristics::statistics::SearchStatistics;
+use crate::instance::PDTSPInstance;
+use crate::solution::Solution;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+/// One algorithm run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Instance name
+    pub instance: String,
+    /// Instance dimension
+    pub dimension: usize,
+    /// Algorithm label
+    pub algorithm: String,
+    /// Seed of the run, for stochastic algorithms
+    pub seed: Option<u64>,
+    /// Configuration the run used (cost function, time limit, parameter overrides, ...)
+    pub config: serde_json::Value,
+    /// Travel cost
+    #[serde(serialize_with = "finite_or_null", deserialize_with = "null_as_infinity")]
+    pub cost: f64,
+    /// Total profit collected
+    pub profit: i32,
+    /// Objective value Z = profit - cost
+    #[serde(serialize_with = "finite_or_null", deserialize_with = "null_as_neg_infinity")]
+    pub objective: f64,
+    /// Whether the solution is feasible
+    pub feasible: bool,
+    /// Wall-clock time in seconds
+    pub time: f64,
+    /// Number of iterations (if applicable)
+    pub iterations: Option<usize>,
+    /// Convergence trace as (elapsed seconds, best cost) points
+    #[serde(default)]
+    pub convergence: Vec<(f64, f64)>,
+    /// Digest of the run configuration, as in the run's `RunInfo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_digest: Option<String>,
+    /// Operator-level statistics of the improvement methods
+    #[serde(default, skip_serializing_if = "SearchStatistics::is_empty")]
+    pub statistics: SearchStatistics,
+}
+
+impl RunRecord {
+    /// Record of a solution found on an instance
+    pub fn from_solution(instance: &PDTSPInstance, solution: &Solution, seed: Option<u64>, config: serde_json::Value) -> Self {
+        RunRecord {
+            instance: instance.name.clone(),
+            dimension: instance.dimension,
+            algorithm: solution.algorithm.clone(),
+            seed,
+            config,
+            cost: solution.cost,
+            profit: solution.total_profit,
+            objective: solution.objective,
+            feasible: solution.feasible,
+            time: solution.computation_time,
+            iterations: solution.iterations,
+            convergence: solution.convergence.clone(),
+            config_digest: solution.run_info.as_ref().map(|info| info.config_digest.clone()),
+            statistics: solution.statistics.clone(),
+        }
+    }
+}
+
+fn finite_or_null<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    if value.is_finite() {
+        serializer.serialize_f64(*value)
+    } else {
+        serializer.serialize_none()
+    }
+}
+
+fn null_as_infinity<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::INFINITY))
+}
+
+fn null_as_neg_infinity<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::NEG_INFINITY))
+}
+
+/// Append records to a JSON Lines file, creating it if needed
+pub fn append_jsonl<P: AsRef<Path>>(path: P, records: &[RunRecord]) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    for record in records {
+        let line = serde_json::to_string(record).map_err(std::io::Error::other)?;
+        writeln!(file, "{}", line)?;
+    }
+    Ok(())
+}
+
+/// Write records to a JSON Lines file, replacing its content
+pub fn write_jsonl<P: AsRef<Path>>(path: P, records: &[RunRecord]) -> std::io::Result<()> {
+    std::fs::File::create(&path)?;
+    append_jsonl(path, records)
+}
+
+/// Read a JSON Lines file of records; blank lines are skipped
+pub fn read_jsonl<P: AsRef<Path>>(path: P) -> Result<Vec<RunRecord>, String> {
+    let file = std::fs::File::open(&path)
+        .map_err(|e| format!("Failed to open {:?}: {}", path.as_ref(), e))?;
+    let mut records = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .map_err(|e| format!("{:?} line {}: {}", path.as_ref(), i + 1, e))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let record = |algorithm: &str, cost: f64| RunRecord {
+            instance: "n20mosA".to_string(),
+            dimension: 20,
+            algorithm: algorithm.to_string(),
+            seed: Some(3),
+            config: serde_json::json!({ "cost_function": "quadratic", "sa": { "cooling_rate": 0.99 } }),
+            cost,
+            profit: 120,
+            objective: 120.0 - cost,
+            feasible: cost.is_finite(),
+            time: 0.5,
+            iterations: Some(1000),
+            convergence: vec![(0.1, 4100.0), (0.4, 4000.0)],
+            config_digest: None,
+            statistics: SearchStatistics::new(),
+        };
+        let records = [record("SA", 4000.0), record("GA", f64::INFINITY)];
+
+        let path = std::env::temp_dir().join("pdtsp_run_records_test.jsonl");
+        write_jsonl(&path, &records[..1]).unwrap();
+        append_jsonl(&path, &records[1..]).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let loaded = read_jsonl(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(text.lines().count(), 2);
+        assert_eq!(loaded[0], records[0]);
+        assert_eq!(loaded[1].cost, f64::INFINITY);
+        assert_eq!(loaded[1].objective, f64::NEG_INFINITY);
+    }
+}
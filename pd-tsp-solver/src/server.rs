@@ -0,0 +1,421 @@
+//! Long-running solver service.
+//!
+//! `SolverService` keeps submitted instances and solve jobs in memory; jobs
+//! are queued and run by a fixed pool of worker threads, each recording the
+//! progress events of its solver (see `progress`), so clients can poll it
+//! while it runs. A job whose solver panics is marked failed. `serve` exposes
+//! the service over HTTP with JSON bodies (requires the `server` feature):
+//!
+//! | Method | Path                   | Body / result                                        |
+//! |--------|------------------------|------------------------------------------------------|
+//! | POST   | `/instances`           | TSP-LIB text or instance JSON → `{"id", "name", "dimension"}` |
+//! | POST   | `/jobs`                | `{"instance": id, "algorithm", "time_limit", ...}` → `{"id"}` |
+//! | GET    | `/jobs`                | status of every job                                  |
+//! | GET    | `/jobs/{id}`           | status and last progress event                       |
+//! | GET    | `/jobs/{id}/solution`  | best solution once the job is done                   |
+//!
+//! Job options are those of the browser API (`wasm::SolveOptions`); SA, tabu
+//! search, ILS and GA report progress, the other algorithms only their end.
+
+use crate::heuristics::construction::{ConstructionHeuristic, MultiStartConstruction};
+use crate::heuristics::genetic::{GAConfig, GeneticAlgorithm};
+use crate::heuristics::local_search::{IteratedLocalSearch, LocalSearch, SimulatedAnnealing, TabuSearch};
+use crate::heuristics::rng::RngFactory;
+use crate::heuristics::stopping::Deadline;
+use crate::instance::PDTSPInstance;
+use crate::progress::ProgressEvent;
+use crate::solution::Solution;
+use crate::wasm::{configure, run, SolveOptions};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// Request body of `POST /jobs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRequest {
+    /// Id of a submitted instance
+    pub instance: usize,
+    /// Algorithm, budget and cost settings
+    #[serde(flatten)]
+    pub options: SolveOptions,
+}
+
+/// State of a job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+struct Job {
+    instance: usize,
+    algorithm: String,
+    time_limit: f64,
+    status: JobStatus,
+    error: Option<String>,
+    progress: Option<ProgressEvent>,
+    /// Instance with the job's cost settings, to evaluate the solution
+    configured: PDTSPInstance,
+    solution: Option<Solution>,
+}
+
+#[derive(Default)]
+struct State {
+    instances: Vec<PDTSPInstance>,
+    jobs: Vec<Job>,
+}
+
+/// A job waiting for a worker
+struct QueuedJob {
+    id: usize,
+    configured: PDTSPInstance,
+    options: SolveOptions,
+}
+
+/// Instances and solve jobs shared between the HTTP handler and the solver threads
+#[derive(Clone)]
+pub struct SolverService {
+    state: Arc<Mutex<State>>,
+    queue: Sender<QueuedJob>,
+}
+
+impl Default for SolverService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SolverService {
+    /// Service with one worker per available core
+    pub fn new() -> Self {
+        Self::with_workers(std::thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    /// Service running at most `workers` jobs at a time; the others wait in
+    /// the queue. The workers stop once every clone of the service is dropped.
+    pub fn with_workers(workers: usize) -> Self {
+        let state = Arc::new(Mutex::new(State::default()));
+        let (queue, jobs) = mpsc::channel();
+        let jobs = Arc::new(Mutex::new(jobs));
+        for _ in 0..workers.max(1) {
+            let state = Arc::clone(&state);
+            let jobs = Arc::clone(&jobs);
+            std::thread::spawn(move || worker(state, jobs));
+        }
+        Self { state, queue }
+    }
+
+    /// Store an instance given as TSP-LIB text or as instance JSON; returns its id
+    pub fn add_instance(&self, content: &str) -> Result<usize, String> {
+        let instance = if content.trim_start().starts_with('{') {
+            PDTSPInstance::from_json(content)?
+        } else {
+            PDTSPInstance::from_tsplib_str(content)?
+        };
+        let mut state = self.state.lock().unwrap();
+        state.instances.push(instance);
+        Ok(state.instances.len() - 1)
+    }
+
+    /// Queue a solve for the worker pool; returns the job id
+    pub fn start_job(&self, request: JobRequest) -> Result<usize, String> {
+        let mut state = self.state.lock().unwrap();
+        let mut configured = state.instances.get(request.instance)
+            .ok_or_else(|| format!("Unknown instance {}", request.instance))?
+            .clone();
+        configure(&mut configured, &request.options)?;
+        state.jobs.push(Job {
+            instance: request.instance,
+            algorithm: request.options.algorithm.clone(),
+            time_limit: request.options.time_limit,
+            status: JobStatus::Queued,
+            error: None,
+            progress: None,
+            configured: configured.clone(),
+            solution: None,
+        });
+        let id = state.jobs.len() - 1;
+        drop(state);
+
+        self.queue
+            .send(QueuedJob { id, configured, options: request.options })
+            .map_err(|_| "The solver workers have stopped".to_string())?;
+        Ok(id)
+    }
+
+    /// Status of a job, or None if it does not exist
+    pub fn job_status(&self, id: usize) -> Option<JobStatus> {
+        self.state.lock().unwrap().jobs.get(id).map(|job| job.status.clone())
+    }
+
+    fn job_json(id: usize, job: &Job) -> Value {
+        json!({
+            "id": id,
+            "instance": job.instance,
+            "algorithm": job.algorithm,
+            "time_limit": job.time_limit,
+            "status": job.status,
+            "error": job.error,
+            "progress": job.progress.as_ref().map(|event| json!({
+                "iteration": event.iteration,
+                "best_cost": finite(event.best_cost),
+                "elapsed": event.elapsed,
+                "temperature": event.temperature,
+                "generation": event.generation,
+            })),
+        })
+    }
+
+    fn solution_json(job: &Job) -> Option<Value> {
+        let solution = job.solution.as_ref()?;
+        Some(json!({
+            "algorithm": solution.algorithm,
+            "tour": solution.tour,
+            "cost": finite(solution.cost),
+            "profit": solution.total_profit,
+            "objective": finite(solution.objective),
+            "feasible": solution.feasible,
+            "load_profile": solution.load_profile(&job.configured),
+            "time": solution.computation_time,
+        }))
+    }
+
+    /// Answer a request: returns the HTTP status code and the JSON body
+    pub fn handle(&self, method: &str, url: &str, body: &str) -> (u16, String) {
+        let path: Vec<&str> = url.split('?').next().unwrap_or("").split('/').filter(|s| !s.is_empty()).collect();
+        let error = |code: u16, message: String| (code, json!({ "error": message }).to_string());
+
+        match (method, path.as_slice()) {
+            ("POST", ["instances"]) => match self.add_instance(body) {
+                Ok(id) => {
+                    let state = self.state.lock().unwrap();
+                    let instance = &state.instances[id];
+                    (201, json!({ "id": id, "name": instance.name, "dimension": instance.dimension }).to_string())
+                }
+                Err(e) => error(400, e),
+            },
+            ("POST", ["jobs"]) => {
+                let request = match serde_json::from_str::<JobRequest>(body) {
+                    Ok(request) => request,
+                    Err(e) => return error(400, format!("Invalid job request: {}", e)),
+                };
+                match self.start_job(request) {
+                    Ok(id) => (202, json!({ "id": id }).to_string()),
+                    Err(e) => error(400, e),
+                }
+            }
+            ("GET", ["jobs"]) => {
+                let state = self.state.lock().unwrap();
+                let jobs: Vec<Value> = state.jobs.iter().enumerate().map(|(id, job)| Self::job_json(id, job)).collect();
+                (200, Value::from(jobs).to_string())
+            }
+            ("GET", ["jobs", id]) | ("GET", ["jobs", id, "solution"]) => {
+                let state = self.state.lock().unwrap();
+                let Some((id, job)) = id.parse::<usize>().ok().and_then(|id| Some((id, state.jobs.get(id)?))) else {
+                    return error(404, format!("Unknown job {}", id));
+                };
+                if path.len() == 2 {
+                    return (200, Self::job_json(id, job).to_string());
+                }
+                match Self::solution_json(job) {
+                    Some(solution) => (200, solution.to_string()),
+                    None => error(409, format!("Job {} is {:?}", id, job.status)),
+                }
+            }
+            _ => error(404, format!("No route for {} {}", method, url)),
+        }
+    }
+}
+
+/// Worker loop: take queued jobs until the service is dropped
+fn worker(state: Arc<Mutex<State>>, jobs: Arc<Mutex<Receiver<QueuedJob>>>) {
+    loop {
+        let Ok(QueuedJob { id, configured, options }) = jobs.lock().unwrap().recv() else {
+            return;
+        };
+        state.lock().unwrap().jobs[id].status = JobStatus::Running;
+        let progress_state = Arc::clone(&state);
+        let on_progress = move |event: ProgressEvent| {
+            if let Ok(mut state) = progress_state.lock() {
+                state.jobs[id].progress = Some(event);
+            }
+        };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| solve_with_progress(&configured, &options, on_progress)))
+            .unwrap_or_else(|payload| {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown error".to_string());
+                Err(format!("Solver panicked: {}", message))
+            });
+        let mut state = state.lock().unwrap();
+        let job = &mut state.jobs[id];
+        match result {
+            Ok(solution) => {
+                job.status = JobStatus::Done;
+                job.solution = Some(solution);
+            }
+            Err(e) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(e);
+            }
+        }
+    }
+}
+
+fn finite(value: f64) -> Option<f64> {
+    Some(value).filter(|v| v.is_finite())
+}
+
+/// Run the job's algorithm, forwarding the progress events of the solvers
+/// that emit them
+fn solve_with_progress(
+    instance: &PDTSPInstance,
+    options: &SolveOptions,
+    on_progress: impl FnMut(ProgressEvent) + Send + 'static,
+) -> Result<Solution, String> {
+    let rng = RngFactory::new(options.seed);
+    let initial = || MultiStartConstruction::with_all_heuristics_seeded(&rng).construct(instance);
+    let solution = match options.algorithm.as_str() {
+        "sa" => {
+            let mut sol = initial();
+            let mut sa = SimulatedAnnealing::new();
+            sa.seed = rng.derive("sa");
+            sa.deadline = Deadline::after(options.time_limit);
+            sa.set_progress_callback(on_progress);
+            sa.improve(instance, &mut sol);
+            sol.algorithm = "SimulatedAnnealing".to_string();
+            sol
+        }
+        "tabu" => {
+            let mut sol = initial();
+            let mut ts = TabuSearch::new();
+            ts.seed = rng.derive("tabu");
+            ts.deadline = Deadline::after(options.time_limit);
+            ts.set_progress_callback(on_progress);
+            ts.improve(instance, &mut sol);
+            sol.algorithm = "TabuSearch".to_string();
+            sol
+        }
+        "ils" => {
+            let mut sol = initial();
+            let mut ils = IteratedLocalSearch::new();
+            ils.seed = rng.derive("ils");
+            ils.deadline = Deadline::after(options.time_limit);
+            ils.set_progress_callback(on_progress);
+            ils.improve(instance, &mut sol);
+            sol.algorithm = "ILS".to_string();
+            sol
+        }
+        "ga" => {
+            let config = GAConfig { seed: rng.derive("ga"), time_limit: options.time_limit, ..Default::default() };
+            let mut ga = GeneticAlgorithm::new(instance.clone(), config);
+            ga.set_progress_callback(on_progress);
+            ga.run()
+        }
+        _ => run(instance, options)?,
+    };
+    Ok(solution)
+}
+
+/// Serve the HTTP API on `addr` (e.g. `127.0.0.1:8080`) until the process stops
+#[cfg(feature = "server")]
+pub fn serve(service: SolverService, addr: &str) -> Result<(), String> {
+    let server = tiny_http::Server::http(addr).map_err(|e| format!("Cannot listen on {}: {}", addr, e))?;
+    let content_type = tiny_http::Header::from_bytes("Content-Type", "application/json").unwrap();
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let (status, json) = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => service.handle(request.method().as_str(), request.url(), &body),
+            Err(e) => (400, json!({ "error": format!("Cannot read body: {}", e) }).to_string()),
+        };
+        tracing::info!(method = %request.method(), url = request.url(), status, "Request");
+        let response = tiny_http::Response::from_string(json)
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        if let Err(e) = request.respond(response) {
+            tracing::warn!(error = %e, "Cannot send response");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "server"))]
+pub fn serve(_service: SolverService, _addr: &str) -> Result<(), String> {
+    Err("The serve command requires the `server` feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wasm::parse_options;
+
+    const TSP: &str = "NAME: tiny\nDIMENSION: 4\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 10 0\n3 10 10\n4 0 10\nDEMAND_SECTION\n1 0\n2 4\n3 -4\n4 0\nEOF\n";
+
+    #[test]
+    fn test_service_routes() {
+        let service = SolverService::new();
+        let (code, body) = service.handle("POST", "/instances", TSP);
+        assert_eq!(code, 201);
+        assert!(body.contains("\"dimension\":4"));
+
+        let (code, body) = service.handle("POST", "/jobs", r#"{ "instance": 0, "algorithm": "sa", "time_limit": 0.3 }"#);
+        assert_eq!(code, 202);
+        let id: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(id["id"], 0);
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+        while matches!(service.job_status(0), Some(JobStatus::Queued | JobStatus::Running)) && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        let (code, body) = service.handle("GET", "/jobs/0", "");
+        let job: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!((code, job["status"].as_str()), (200, Some("done")));
+        assert!(job["progress"]["iteration"].is_u64());
+
+        let (code, body) = service.handle("GET", "/jobs/0/solution", "");
+        let solution: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(code, 200);
+        assert_eq!(solution["tour"].as_array().unwrap().len(), 4);
+        assert_eq!(solution["feasible"], true);
+
+        assert_eq!(service.handle("POST", "/jobs", r#"{ "instance": 5 }"#).0, 400);
+        assert_eq!(service.handle("GET", "/jobs/9", "").0, 404);
+        assert_eq!(service.handle("DELETE", "/jobs/0", "").0, 404);
+    }
+
+    #[test]
+    fn test_panicking_job_fails() {
+        let service = SolverService::with_workers(1);
+        service.add_instance(TSP).unwrap();
+        service.state.lock().unwrap().instances[0].nodes.clear();
+        let id = service.start_job(JobRequest {
+            instance: 0,
+            options: parse_options(r#"{ "algorithm": "nn" }"#).unwrap(),
+        }).unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+        while matches!(service.job_status(id), Some(JobStatus::Queued | JobStatus::Running)) && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(service.job_status(id), Some(JobStatus::Failed));
+        let (_, body) = service.handle("GET", "/jobs/0", "");
+        assert!(body.contains("Solver panicked"));
+
+        // The worker survives the panic
+        assert_eq!(service.handle("POST", "/instances", TSP).0, 201);
+        let id = service.start_job(JobRequest { instance: 1, options: parse_options("{}").unwrap() }).unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+        while matches!(service.job_status(id), Some(JobStatus::Queued | JobStatus::Running)) && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(service.job_status(id), Some(JobStatus::Done));
+    }
+}
@@ -3,9 +3,11 @@
 //! This module provides data structures and methods for representing,
 //! manipulating, and evaluating solutions to the PD-TSP.
 
-use crate::instance::PDTSPInstance;
+use crate::heuristics::statistics::SearchStatistics;
+use crate::instance::{CostFunction, PDTSPInstance, Violation};
+use crate::run_info::RunInfo;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::BinaryHeap;
 
 /// Represents a solution to the PD-TSP
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,8 +26,31 @@ pub struct Solution {
     pub algorithm: String,
     /// Computation time in seconds
     pub computation_time: f64,
+    /// CPU time in seconds of the thread that ran the search, when measured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_time: Option<f64>,
+    /// Growth of the resident set size of the process from the start of the
+    /// run to its peak in MiB, when measured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_rss: Option<f64>,
     /// Number of iterations (if applicable)
     pub iterations: Option<usize>,
+    /// Metadata of the run that produced this solution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_info: Option<RunInfo>,
+    /// Convergence trace as (elapsed seconds, best cost) points, recorded by metaheuristics
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub convergence: Vec<(f64, f64)>,
+    /// Best tours along the search as (elapsed seconds, tour) points, one per
+    /// convergence point, recorded by SA, ILS and GA when requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub snapshots: Vec<(f64, Vec<usize>)>,
+    /// Moves evaluated and applied, gain and time of each improvement operator
+    #[serde(default, skip_serializing_if = "SearchStatistics::is_empty")]
+    pub statistics: SearchStatistics,
+    /// Why the tour is infeasible or malformed, as found by the last `validate`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<Violation>,
 }
 
 impl Solution {
@@ -37,9 +62,16 @@ impl Solution {
             feasible: false,
             algorithm: String::new(),
             computation_time: 0.0,
+            cpu_time: None,
+            peak_rss: None,
             iterations: None,
             total_profit: 0,
             objective: f64::NEG_INFINITY,
+            run_info: None,
+            convergence: Vec::new(),
+            snapshots: Vec::new(),
+            statistics: SearchStatistics::new(),
+            violations: Vec::new(),
         }
     }
     
@@ -56,29 +88,34 @@ impl Solution {
             feasible,
             algorithm: algorithm.to_string(),
             computation_time: 0.0,
+            cpu_time: None,
+            peak_rss: None,
             iterations: None,
             total_profit,
             objective,
+            run_info: None,
+            convergence: Vec::new(),
+            snapshots: Vec::new(),
+            statistics: SearchStatistics::new(),
+            violations: Vec::new(),
         }
     }
     
-    /// Validate and update solution properties
+    /// Validate and update solution properties, recording the violations.
+    /// Feasibility is strict: the tour must visit every customer once.
     pub fn validate(&mut self, instance: &PDTSPInstance) {
         let travel_cost = instance.tour_cost(&self.tour);
         self.cost = travel_cost;
-        self.feasible = instance.is_feasible(&self.tour);
+        self.feasible = instance.is_feasible_strict(&self.tour);
+        self.violations = instance.explain_infeasibility(&self.tour);
         self.total_profit = instance.tour_profit(&self.tour);
         self.objective = self.total_profit as f64 - travel_cost;
     }
     
-    /// Check if all nodes are visited exactly once
+    /// Check if the tour starts at the depot and visits every customer
+    /// exactly once (intermediate depot visits are allowed)
     pub fn is_complete(&self, instance: &PDTSPInstance) -> bool {
-        if self.tour.len() != instance.dimension {
-            return false;
-        }
-        
-        let unique: HashSet<usize> = self.tour.iter().cloned().collect();
-        unique.len() == instance.dimension && self.tour[0] == 0
+        instance.is_complete_tour(&self.tour)
     }
     
     /// Get the position of a node in the tour
@@ -107,66 +144,63 @@ impl Solution {
         })
     }
     
-    /// Calculate the delta cost of swapping two nodes
+    /// Cost delta of swapping the nodes at positions `i` and `j`. Each call
+    /// builds a `DeltaEvaluator` in O(n log n); a search pricing many moves on
+    /// the same tour should build one and query it instead.
     pub fn swap_delta(&self, instance: &PDTSPInstance, i: usize, j: usize) -> f64 {
         if i == j || self.tour.len() < 4 {
             return 0.0;
         }
-
-        
-        let mut new_tour = self.tour.clone();
-        new_tour.swap(i, j);
-        let old_cost = instance.tour_cost(&self.tour);
-        let new_cost = instance.tour_cost(&new_tour);
-        new_cost - old_cost
+        DeltaEvaluator::new(instance, &self.tour).swap_delta(i, j)
     }
     
-    /// Calculate the delta cost of a 2-opt move
+    /// Cost delta of the 2-opt move reversing positions `i+1..=j` (`i < j`),
+    /// see `swap_delta`
     pub fn two_opt_delta(&self, instance: &PDTSPInstance, i: usize, j: usize) -> f64 {
         let n = self.tour.len();
         if i >= j || j >= n {
             return 0.0;
         }
-
-        
-        let mut new_tour = self.tour.clone();
-        new_tour[i + 1..=j].reverse();
-        let old_cost = instance.tour_cost(&self.tour);
-        let new_cost = instance.tour_cost(&new_tour);
-        new_cost - old_cost
+        DeltaEvaluator::new(instance, &self.tour).two_opt_delta(i, j)
     }
     
     /// Apply a 2-opt move (reverse segment between i+1 and j)
     pub fn apply_two_opt(&mut self, i: usize, j: usize) {
-        self.tour[i + 1..=j].reverse();
+        self.apply_move(&StandardMove::TwoOpt(i, j));
     }
     
     /// Apply a swap move
     pub fn apply_swap(&mut self, i: usize, j: usize) {
-        self.tour.swap(i, j);
+        self.apply_move(&StandardMove::Swap(i, j));
     }
     
     /// Apply an insertion move (remove node at from_pos and insert at to_pos)
     pub fn apply_insertion(&mut self, from_pos: usize, to_pos: usize) {
-        let node = self.tour.remove(from_pos);
-        let insert_pos = if to_pos > from_pos { to_pos - 1 } else { to_pos };
-        self.tour.insert(insert_pos, node);
+        self.apply_move(&StandardMove::Insertion(from_pos, to_pos));
     }
     
-    /// Calculate insertion delta (remove from from_pos, insert at to_pos)
+    /// Cost delta of the insertion move of `apply_insertion`, see `swap_delta`
     pub fn insertion_delta(&self, instance: &PDTSPInstance, from_pos: usize, to_pos: usize) -> f64 {
         if from_pos == to_pos || from_pos + 1 == to_pos {
             return 0.0;
         }
-        
-        let mut new_tour: Vec<usize> = self.tour.clone();
-        let node = new_tour.remove(from_pos);
-        let insert_pos = if to_pos > from_pos { to_pos - 1 } else { to_pos };
-        new_tour.insert(insert_pos, node);
-
-        let old_cost = instance.tour_cost(&self.tour);
-        let new_cost = instance.tour_cost(&new_tour);
-        new_cost - old_cost
+        DeltaEvaluator::new(instance, &self.tour).relocation_delta(from_pos, to_pos)
+    }
+    
+    /// Cost delta of any move, see `swap_delta`
+    pub fn move_delta(&self, instance: &PDTSPInstance, mv: &impl Move) -> f64 {
+        mv.delta(&DeltaEvaluator::new(instance, &self.tour))
+    }
+    
+    /// Whether the tour after a move is feasible, see `swap_delta`
+    pub fn move_feasible(&self, instance: &PDTSPInstance, mv: &impl Move) -> bool {
+        mv.is_feasible(&DeltaEvaluator::new(instance, &self.tour))
+    }
+    
+    /// Apply a move to the tour. The cost and feasibility are left as they
+    /// were: call `validate` once the moves are done.
+    pub fn apply_move(&mut self, mv: &impl Move) {
+        mv.apply(&mut self.tour);
     }
     
     /// Get load profile along the tour (including return to depot)
@@ -196,6 +230,19 @@ impl Solution {
         profile
     }
     
+    /// Per-commodity load profile along the tour (including return to depot).
+    /// Single-commodity instances yield one-element load vectors.
+    pub fn commodity_load_profile(&self, instance: &PDTSPInstance) -> Vec<Vec<i32>> {
+        if self.tour.is_empty() {
+            return Vec::new();
+        }
+        
+        let mut profile = instance.commodity_load_profile(&self.tour);
+        let num_commodities = profile[0].len();
+        profile.push(vec![0; num_commodities]);
+        profile
+    }
+    
     /// Get maximum load during tour
     pub fn max_load(&self, instance: &PDTSPInstance) -> i32 {
         self.load_profile(instance).into_iter().max().unwrap_or(0)
@@ -205,6 +252,174 @@ impl Solution {
     pub fn min_load(&self, instance: &PDTSPInstance) -> i32 {
         self.load_profile(instance).into_iter().min().unwrap_or(0)
     }
+    
+    /// Evaluate inserting `node` (not yet in the tour) at every position and
+    /// return the cheapest insertion that keeps the tour feasible.
+    pub fn cheapest_feasible_insertion(&self, instance: &PDTSPInstance, node: usize) -> Option<InsertionImpact> {
+        self.evaluate_insertions(instance, node)
+            .into_iter()
+            .filter(|impact| impact.feasible)
+            .min_by(|a, b| a.cost_delta.partial_cmp(&b.cost_delta).unwrap())
+    }
+    
+    /// Impact of adding `node` to the tour: the cheapest feasible insertion if
+    /// one exists, otherwise the cheapest insertion overall (with `feasible = false`).
+    /// Returns `None` if the node is unknown or already in the tour.
+    pub fn insertion_impact(&self, instance: &PDTSPInstance, node: usize) -> Option<InsertionImpact> {
+        let impacts = self.evaluate_insertions(instance, node);
+        let feasible_best = impacts.iter()
+            .filter(|impact| impact.feasible)
+            .min_by(|a, b| a.cost_delta.partial_cmp(&b.cost_delta).unwrap());
+        
+        feasible_best
+            .or_else(|| impacts.iter().min_by(|a, b| a.cost_delta.partial_cmp(&b.cost_delta).unwrap()))
+            .cloned()
+    }
+    
+    /// Evaluate all insertion positions of `node` (after the depot). The
+    /// node is appended to the tour once and each insertion priced as its
+    /// relocation by `DeltaEvaluator` (O(1) per position on its fast path);
+    /// peak loads come from prefix tables of the current loads, which the
+    /// node's demand shifts up to the next depot visit.
+    fn evaluate_insertions(&self, instance: &PDTSPInstance, node: usize) -> Vec<InsertionImpact> {
+        if node == 0 || node >= instance.dimension || self.tour.is_empty() || self.tour.contains(&node) {
+            return Vec::new();
+        }
+        
+        let n = self.tour.len();
+        let mut extended = self.tour.clone();
+        extended.push(node);
+        let appended_delta = instance.tour_cost(&extended) - instance.tour_cost(&self.tour);
+        let evaluator = DeltaEvaluator::new(instance, &extended);
+        
+        // Peaks over positions 1..p, over p up to the next depot visit, and
+        // from the next depot visit at or after p to the end
+        let (_, current_peak, _, loads) = instance.check_feasibility_detailed(&self.tour);
+        let mut before = vec![0; n + 1];
+        for p in 1..n {
+            before[p + 1] = before[p].max(loads[p]);
+        }
+        let (mut route, mut after, mut suffix) = (vec![i32::MIN; n + 1], vec![i32::MIN; n + 1], i32::MIN);
+        for p in (1..n).rev() {
+            suffix = suffix.max(loads[p]);
+            if self.tour[p] == 0 {
+                after[p] = suffix;
+            } else {
+                route[p] = route[p + 1].max(loads[p]);
+                after[p] = after[p + 1];
+            }
+        }
+        
+        let demand = instance.nodes[node].demand;
+        (1..=n).map(|position| {
+            let new_peak = before[position]
+                .max(loads[position - 1] + demand)
+                .max(route[position].saturating_add(demand))
+                .max(after[position]);
+            let (cost_delta, feasible) = if position == n {
+                (appended_delta, evaluator.tour_feasible())
+            } else {
+                (appended_delta + evaluator.relocation_delta(n, position), evaluator.relocation_feasible(n, position))
+            };
+            InsertionImpact {
+                position,
+                cost_delta,
+                new_peak_load: new_peak,
+                peak_load_increase: new_peak - current_peak,
+                feasible,
+            }
+        }).collect()
+    }
+    
+    /// Export the tour in TSPLIB `.tour` format (1-indexed node IDs of the
+    /// instance file, `TOUR_SECTION` terminated by -1), as read by Concorde,
+    /// LKH, etc. The extra visits of split demands are written with the ID
+    /// of their customer, which then appears once per visit.
+    pub fn to_tsplib_tour(&self, instance: &PDTSPInstance, name: &str) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("NAME : {}.tour\n", name));
+        out.push_str(&format!("COMMENT : {} (cost {:.2})\n", self.algorithm, self.cost));
+        out.push_str("TYPE : TOUR\n");
+        out.push_str(&format!("DIMENSION : {}\n", self.tour.len()));
+        out.push_str("TOUR_SECTION\n");
+        for &node in &self.tour {
+            out.push_str(&format!("{}\n", instance.file_index(instance.original_node(node)) + 1));
+        }
+        out.push_str("-1\nEOF\n");
+        out
+    }
+    
+    /// Parse a TSPLIB `.tour` file for the given instance (node IDs of the
+    /// instance file). A customer with split demands may appear once per
+    /// visit; its occurrences take its visits in order. The depot may appear
+    /// several times, its later occurrences being intermediate depot visits.
+    /// The tour is rotated so that it starts at the depot.
+    pub fn from_tsplib_tour(instance: &PDTSPInstance, content: &str) -> Result<Self, String> {
+        let mut dimension: Option<usize> = None;
+        let mut in_section = false;
+        let mut tour: Vec<usize> = Vec::new();
+        // Visits of each customer of the instance file, the customer first
+        let customers = instance.nodes.iter().filter(|node| node.split_of.is_none()).count();
+        let mut visits: Vec<Vec<usize>> = (0..customers).map(|node| vec![node]).collect();
+        for node in customers..instance.dimension {
+            visits[instance.original_node(node)].push(node);
+        }
+        let mut used = vec![0; customers];
+        
+        'lines: for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            
+            if !in_section {
+                if line.starts_with("TOUR_SECTION") {
+                    in_section = true;
+                } else if line.starts_with("DIMENSION") {
+                    let value = line.split(':').nth(1).unwrap_or("").trim();
+                    dimension = Some(value.parse().map_err(|_| format!("Invalid dimension: {}", value))?);
+                } else if line == "EOF" {
+                    break;
+                }
+                continue;
+            }
+            
+            for token in line.split_whitespace() {
+                if token == "-1" || token == "EOF" {
+                    break 'lines;
+                }
+                let id: usize = token.parse().map_err(|_| format!("Invalid node id: {}", token))?;
+                if id == 0 || id > customers {
+                    return Err(format!("Node id {} out of range 1..={}", id, customers));
+                }
+                let customer = instance.file_index(id - 1);
+                if customer == 0 {
+                    tour.push(0);
+                    continue;
+                }
+                let Some(&node) = visits[customer].get(used[customer]) else {
+                    return Err(format!("Node {} appears more than {} times", id, visits[customer].len()));
+                };
+                used[customer] += 1;
+                tour.push(node);
+            }
+        }
+        
+        if !in_section {
+            return Err("Missing TOUR_SECTION".to_string());
+        }
+        if let Some(dim) = dimension {
+            if dim != tour.len() {
+                return Err(format!("DIMENSION is {} but tour has {} nodes", dim, tour.len()));
+            }
+        }
+        
+        if let Some(depot_pos) = tour.iter().position(|&n| n == 0) {
+            tour.rotate_left(depot_pos);
+        }
+        
+        Ok(Solution::from_tour(instance, tour, "tsplib-tour"))
+    }
 }
 
 impl Default for Solution {
@@ -226,37 +441,929 @@ impl std::fmt::Display for Solution {
     }
 }
 
-/// Represents a move in local search
-#[derive(Debug, Clone, Copy)]
-pub enum Move {
+/// Effect of inserting a new node into an existing tour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InsertionImpact {
+    /// Position in the tour at which the node is inserted
+    pub position: usize,
+    /// Change in tour cost under the instance's active cost function
+    pub cost_delta: f64,
+    /// Peak load of the tour after insertion
+    pub new_peak_load: i32,
+    /// Increase of the peak load compared to the current tour
+    pub peak_load_increase: i32,
+    /// Whether the resulting tour is feasible (`PDTSPInstance::is_feasible`)
+    pub feasible: bool,
+}
+
+/// New loads `offset + scale * L_q` of the positions `q` in `a..b`, as
+/// `(a, b, offset, scale)`
+type LoadRange = (usize, usize, i32, i32);
+
+/// Load change of a move: the positions `a..b` whose loads are replaced, and
+/// the ranges giving their new loads (empty intervals are padding)
+struct LoadChange {
+    removed: [(usize, usize); 2],
+    ranges: [LoadRange; 3],
+}
+
+impl LoadChange {
+    fn new(removed: &[(usize, usize)], ranges: &[LoadRange]) -> Self {
+        let mut change = LoadChange { removed: [(0, 0); 2], ranges: [(0, 0, 0, 1); 3] };
+        change.removed[..removed.len()].copy_from_slice(removed);
+        change.ranges[..ranges.len()].copy_from_slice(ranges);
+        change
+    }
+}
+
+/// Load tables of a `DeltaEvaluator`, kept between evaluators so that
+/// `DeltaEvaluator::refresh` only recomputes what a move changed
+#[derive(Debug, Clone, Default)]
+pub struct EvaluatorTables {
+    loads: Vec<i32>,
+    /// Prefix sums over `q < k` of `L_q`, `L_q²` and `|L_q|`
+    sum: Vec<f64>,
+    sum_sq: Vec<f64>,
+    sum_abs: Vec<f64>,
+    /// Sparse tables: `min_table[p][q]` is the min of `L[q..q + 2^p]`
+    min_table: Vec<Vec<i32>>,
+    max_table: Vec<Vec<i32>>,
+    /// Prefix count over `1 <= q < k` of loads outside `[0, capacity]`
+    violations: Vec<usize>,
+    /// Prefix sums over `1 <= q < k` of the amount by which `L_q` leaves `[0, capacity]`
+    excess: Vec<i64>,
+    /// Prefix count over `1 <= q < k` of intermediate depot visits, and their positions
+    depots: Vec<usize>,
+    depot_positions: Vec<usize>,
+    /// Prefix sums over `q < k` of `d(t[q+1], t[q]) - d(t[q], t[q+1])`
+    reversal: Vec<f64>,
+}
+
+/// Constant-time evaluation of swap, 2-opt, relocation and or-opt moves on a
+/// fixed tour.
+///
+/// Arc `k` (from `tour[k]` to its successor) carries the prefix load `L_k`,
+/// which an intermediate depot visit resets to 0. A swap of positions
+/// `i < j` shifts `L_i..L_{j-1}` by the same amount, and a 2-opt move
+/// reversing `i+1..=j` maps `L_q` to `L_i + L_j - L_q` for `q` in `i+1..j`.
+/// Moving a segment shifts the loads of the nodes it jumps over by its net
+/// demand, and its own loads by a constant (or mirrors them when it is
+/// reinserted reversed); across a depot visit the shifts stop at the next
+/// one. Prefix sums of `L`, `L²` and `|L|` then give the load-dependent
+/// surcharge of the moved range in O(1), and sparse tables of the range
+/// min/max loads give its feasibility. Building costs O(n log n), and
+/// `refresh` updates the tables after a move in O(n) plus O(log n) per
+/// changed load.
+///
+/// Moves that reverse or move an intermediate depot visit, multi-commodity
+/// instances, route length limits, chance constraints and restricted arc
+/// sets fall back to
+/// re-evaluating the whole tour, as do costs under a vehicle profile or on
+/// open tours.
+pub struct DeltaEvaluator<'a> {
+    instance: &'a PDTSPInstance,
+    tour: &'a [usize],
+    /// Whether loads and feasibility follow the prefix-load model
+    fast_feasibility: bool,
+    /// Whether the cost is the plain distance plus load surcharge
+    fast_cost: bool,
+    tables: EvaluatorTables,
+}
+
+impl<'a> DeltaEvaluator<'a> {
+    pub fn new(instance: &'a PDTSPInstance, tour: &'a [usize]) -> Self {
+        Self::refresh(instance, tour, EvaluatorTables::default(), 0)
+    }
+    
+    /// Evaluator of `tour` reusing the tables of an evaluator of a tour of
+    /// the same length that agrees with it on the positions before `from`
+    /// (see `StandardMove::first_changed`). Only the entries depending on
+    /// later positions are recomputed, and the sparse tables only where the
+    /// loads changed.
+    pub fn refresh(instance: &'a PDTSPInstance, tour: &'a [usize], mut tables: EvaluatorTables, from: usize) -> Self {
+        let n = tour.len();
+        let fast_feasibility = n >= 2
+            && tour[0] == 0
+            && tour.iter().all(|&node| node < instance.dimension)
+            && !instance.is_multi_commodity()
+            && !instance.has_route_limit()
+            && !instance.has_chance_constraint()
+            && !instance.distance_matrix.is_restricted();
+        let fast_cost = fast_feasibility && instance.vehicle.is_none() && !instance.open_tour;
+        if !fast_feasibility {
+            // Forces a full rebuild on the next refresh
+            tables.loads.clear();
+            return DeltaEvaluator { instance, tour, fast_feasibility, fast_cost, tables };
+        }
+        
+        let from = if tables.loads.len() == n { from.min(n) } else { 0 };
+        let t = &mut tables;
+        
+        // Loads, and the positions `from..changed_to` where they changed
+        t.loads.resize(n, 0);
+        let mut changed_to = if from == 0 { n } else { from };
+        let mut load = if from == 0 { instance.starting_load() } else { t.loads[from - 1] };
+        for (q, &node) in tour.iter().enumerate().skip(from.max(1)) {
+            load = if node == 0 { 0 } else { load + instance.nodes[node].demand };
+            if t.loads[q] != load {
+                t.loads[q] = load;
+                changed_to = changed_to.max(q + 1);
+            }
+        }
+        if from == 0 {
+            t.loads[0] = instance.starting_load();
+        }
+        
+        let start = from.saturating_sub(1);
+        for prefix in [&mut t.sum, &mut t.sum_sq, &mut t.sum_abs, &mut t.reversal] {
+            prefix.resize(n + 1, 0.0);
+            prefix[0] = 0.0;
+        }
+        for prefix in [&mut t.violations, &mut t.depots] {
+            prefix.resize(n + 1, 0);
+            prefix[0] = 0;
+        }
+        t.excess.resize(n + 1, 0);
+        t.excess[0] = 0;
+        t.depot_positions.truncate(t.depots[start]);
+        for q in start..n {
+            let l = t.loads[q] as f64;
+            t.sum[q + 1] = t.sum[q] + l;
+            t.sum_sq[q + 1] = t.sum_sq[q] + l * l;
+            t.sum_abs[q + 1] = t.sum_abs[q] + l.abs();
+            let excess = if q >= 1 { (t.loads[q] - instance.capacity).max(0) + (-t.loads[q]).max(0) } else { 0 };
+            t.violations[q + 1] = t.violations[q] + (excess > 0) as usize;
+            t.excess[q + 1] = t.excess[q] + excess as i64;
+            let depot = q >= 1 && tour[q] == 0;
+            t.depots[q + 1] = t.depots[q] + depot as usize;
+            if depot {
+                t.depot_positions.push(q);
+            }
+            t.reversal[q + 1] = t.reversal[q] + if q + 1 < n {
+                instance.distance(tour[q + 1], tour[q]) - instance.distance(tour[q], tour[q + 1])
+            } else {
+                0.0
+            };
+        }
+        
+        // Entry `q` of level `p` covers `L[q..q + 2^p]`: only those meeting
+        // `from..changed_to` change
+        let levels = (usize::BITS - n.leading_zeros()) as usize;
+        for table in [&mut t.min_table, &mut t.max_table] {
+            table.resize_with(levels, Vec::new);
+            table[0].resize(n, 0);
+            table[0][from..changed_to].copy_from_slice(&t.loads[from..changed_to]);
+        }
+        for level in 1..levels {
+            let width = 1 << level;
+            let len = n + 1 - width;
+            let range = if changed_to > from { (from + 1).saturating_sub(width)..changed_to.min(len) } else { 0..0 };
+            let (done, rest) = t.min_table.split_at_mut(level);
+            let (prev, next) = (&done[level - 1], &mut rest[0]);
+            next.resize(len, 0);
+            for q in range.clone() {
+                next[q] = prev[q].min(prev[q + width / 2]);
+            }
+            let (done, rest) = t.max_table.split_at_mut(level);
+            let (prev, next) = (&done[level - 1], &mut rest[0]);
+            next.resize(len, 0);
+            for q in range {
+                next[q] = prev[q].max(prev[q + width / 2]);
+            }
+        }
+        
+        DeltaEvaluator { instance, tour, fast_feasibility, fast_cost, tables }
+    }
+    
+    /// Tables of the evaluator, for `refresh` after the tour is changed
+    pub fn into_tables(self) -> EvaluatorTables {
+        self.tables
+    }
+    
+    /// Whether positions `a..b` hold an intermediate depot visit
+    pub fn spans_depot(&self, a: usize, b: usize) -> bool {
+        let (a, b) = (a.max(1), b.min(self.tour.len()));
+        if a >= b {
+            return false;
+        }
+        if self.fast_feasibility {
+            self.tables.depots[b] > self.tables.depots[a]
+        } else {
+            self.tour[a..b].contains(&0)
+        }
+    }
+    
+    /// Position of the first intermediate depot visit after `q`, or the tour length
+    fn next_depot(&self, q: usize) -> usize {
+        let t = &self.tables;
+        t.depot_positions.get(t.depots[q + 1]).copied().unwrap_or(self.tour.len())
+    }
+    
+    /// Whether the tour itself is feasible
+    pub fn tour_feasible(&self) -> bool {
+        if self.fast_feasibility {
+            self.tables.violations[self.tour.len()] == 0
+        } else {
+            self.instance.is_feasible(self.tour)
+        }
+    }
+    
+    /// Total load violation of the tour (see `PDTSPInstance::load_violation`)
+    pub fn violation(&self) -> i64 {
+        if self.fast_feasibility {
+            self.tables.excess[self.tour.len()]
+        } else {
+            self.instance.load_violation(self.tour.iter().copied())
+        }
+    }
+    
+    /// Min and max of the loads `L[a..b]` (`a < b`)
+    fn load_range(&self, a: usize, b: usize) -> (i32, i32) {
+        let level = (usize::BITS - 1 - (b - a).leading_zeros()) as usize;
+        let width = 1 << level;
+        let t = &self.tables;
+        (
+            t.min_table[level][a].min(t.min_table[level][b - width]),
+            t.max_table[level][a].max(t.max_table[level][b - width]),
+        )
+    }
+    
+    /// Surcharge of the arcs `a..b` when their loads become `offset + scale * L_q`
+    /// (`scale` is 1 or -1)
+    fn range_surcharge(&self, a: usize, b: usize, offset: f64, scale: f64) -> f64 {
+        if a >= b {
+            return 0.0;
+        }
+        let t = &self.tables;
+        let m = (b - a) as f64;
+        let s1 = t.sum[b] - t.sum[a];
+        let s2 = t.sum_sq[b] - t.sum_sq[a];
+        let linear = m * offset + scale * s1;
+        
+        match self.instance.cost_function {
+            // Moves keep the visited nodes, hence the service time
+            CostFunction::Distance | CostFunction::Duration => 0.0,
+            CostFunction::Quadratic => {
+                let square = m * offset * offset + 2.0 * offset * scale * s1 + s2;
+                self.instance.alpha * linear + self.instance.beta * square
+            }
+            CostFunction::LinearLoad => {
+                let absolute = if offset == 0.0 && scale > 0.0 {
+                    t.sum_abs[b] - t.sum_abs[a]
+                } else {
+                    let (lo, hi) = self.load_range(a, b);
+                    let (lo, hi) = if scale > 0.0 { (lo, hi) } else { (-hi, -lo) };
+                    if offset + lo as f64 >= 0.0 {
+                        linear
+                    } else if offset + hi as f64 <= 0.0 {
+                        -linear
+                    } else {
+                        t.loads[a..b].iter().map(|&l| (offset + scale * l as f64).abs()).sum()
+                    }
+                };
+                self.instance.alpha * absolute
+            }
+        }
+    }
+    
+    /// Total amount by which the loads `offset + scale * L_q` for `q` in
+    /// `a..b` leave `[0, capacity]`. O(1) unless the range crosses a bound.
+    fn range_violation(&self, a: usize, b: usize, offset: i32, scale: i32) -> i64 {
+        if a >= b {
+            return 0;
+        }
+        let capacity = self.instance.capacity;
+        let (lo, hi) = self.load_range(a, b);
+        let (lo, hi) = if scale > 0 { (lo, hi) } else { (-hi, -lo) };
+        let m = (b - a) as i64;
+        let total = || m * offset as i64 + scale as i64 * (self.tables.sum[b] - self.tables.sum[a]).round() as i64;
+        if offset + lo >= 0 && offset + hi <= capacity {
+            0
+        } else if offset + hi <= 0 {
+            -total()
+        } else if offset + lo >= capacity {
+            total() - m * capacity as i64
+        } else {
+            self.tables.loads[a..b].iter()
+                .map(|&l| {
+                    let load = offset + scale * l;
+                    ((load - capacity).max(0) + (-load).max(0)) as i64
+                })
+                .sum()
+        }
+    }
+    
+    /// Surcharge delta of a load change
+    fn change_surcharge(&self, change: &LoadChange) -> f64 {
+        change.ranges.iter()
+            .map(|&(a, b, offset, scale)| self.range_surcharge(a, b, offset as f64, scale as f64))
+            .sum::<f64>()
+            - change.removed.iter().map(|&(a, b)| self.range_surcharge(a, b, 0.0, 1.0)).sum::<f64>()
+    }
+    
+    /// Whether every load outside the replaced positions fits the capacity
+    /// and so do the new loads of the change
+    fn change_feasible(&self, change: &LoadChange) -> bool {
+        let violations = &self.tables.violations;
+        let inside: usize = change.removed.iter().filter(|(a, b)| a < b).map(|&(a, b)| violations[b] - violations[a]).sum();
+        if violations[self.tour.len()] != inside {
+            return false;
+        }
+        change.ranges.iter().all(|&(a, b, offset, scale)| {
+            if a >= b {
+                return true;
+            }
+            let (lo, hi) = self.load_range(a, b);
+            let (lo, hi) = if scale > 0 { (lo, hi) } else { (-hi, -lo) };
+            offset + lo >= 0 && offset + hi <= self.instance.capacity
+        })
+    }
+    
+    /// Load violation delta of a load change
+    fn change_violation(&self, change: &LoadChange) -> i64 {
+        let excess = &self.tables.excess;
+        change.ranges.iter().map(|&(a, b, offset, scale)| self.range_violation(a, b, offset, scale)).sum::<i64>()
+            - change.removed.iter().filter(|(a, b)| a < b).map(|&(a, b)| excess[b] - excess[a]).sum::<i64>()
+    }
+    
+    /// Load violation delta of replacing the tour by `moved`, in O(n)
+    fn full_violation_delta(&self, moved: &[usize]) -> i64 {
+        self.instance.load_violation(moved.iter().copied()) - self.violation()
+    }
+    
+    fn swapped(&self, i: usize, j: usize) -> Vec<usize> {
+        let mut new_tour = self.tour.to_vec();
+        new_tour.swap(i, j);
+        new_tour
+    }
+    
+    fn reversed(&self, i: usize, j: usize) -> Vec<usize> {
+        let mut new_tour = self.tour.to_vec();
+        new_tour[i + 1..=j].reverse();
+        new_tour
+    }
+    
+    /// Load change of swapping the customers at positions `0 < i < j`. Across
+    /// a depot visit each node's demand shifts the loads up to the next one.
+    fn swap_change(&self, i: usize, j: usize) -> Option<LoadChange> {
+        let t = self.tour;
+        if !self.fast_feasibility || i == 0 || t[i] == 0 || t[j] == 0 {
+            return None;
+        }
+        let shift = self.instance.nodes[t[j]].demand - self.instance.nodes[t[i]].demand;
+        Some(if !self.spans_depot(i + 1, j) {
+            LoadChange::new(&[(i, j)], &[(i, j, shift, 1)])
+        } else {
+            let (next_i, next_j) = (self.next_depot(i), self.next_depot(j));
+            LoadChange::new(&[(i, next_i), (j, next_j)], &[(i, next_i, shift, 1), (j, next_j, -shift, 1)])
+        })
+    }
+    
+    /// Cost delta of swapping the nodes at positions `i` and `j`
+    pub fn swap_delta(&self, i: usize, j: usize) -> f64 {
+        let (i, j) = (i.min(j), i.max(j));
+        let n = self.tour.len();
+        if i == j || j >= n {
+            return 0.0;
+        }
+        let Some(change) = self.swap_change(i, j).filter(|_| self.fast_cost) else {
+            return self.instance.tour_cost(&self.swapped(i, j)) - self.instance.tour_cost(self.tour);
+        };
+        
+        let t = self.tour;
+        let d = |a: usize, b: usize| self.instance.distance(a, b);
+        let (a, b) = (t[i], t[j]);
+        let (prev, next) = (t[i - 1], t[(j + 1) % n]);
+        let arcs = if j == i + 1 {
+            d(prev, b) + d(b, a) + d(a, next) - d(prev, a) - d(a, b) - d(b, next)
+        } else {
+            let (after_a, before_b) = (t[i + 1], t[j - 1]);
+            d(prev, b) + d(b, after_a) + d(before_b, a) + d(a, next)
+                - d(prev, a) - d(a, after_a) - d(before_b, b) - d(b, next)
+        };
+        arcs + self.change_surcharge(&change)
+    }
+    
+    /// Whether swapping the nodes at positions `i` and `j` gives a feasible tour
+    pub fn swap_feasible(&self, i: usize, j: usize) -> bool {
+        let (i, j) = (i.min(j), i.max(j));
+        if i == j || j >= self.tour.len() {
+            return self.tour_feasible();
+        }
+        match self.swap_change(i, j) {
+            Some(change) => self.change_feasible(&change),
+            None => self.instance.is_feasible(&self.swapped(i, j)),
+        }
+    }
+    
+    /// Load violation delta of swapping the nodes at positions `i` and `j`
+    pub fn swap_violation_delta(&self, i: usize, j: usize) -> i64 {
+        let (i, j) = (i.min(j), i.max(j));
+        if i == j || j >= self.tour.len() {
+            return 0;
+        }
+        match self.swap_change(i, j) {
+            Some(change) => self.change_violation(&change),
+            None => self.full_violation_delta(&self.swapped(i, j)),
+        }
+    }
+    
+    /// Load change of reversing positions `i+1..=j`, which must not hold a depot visit
+    fn two_opt_change(&self, i: usize, j: usize) -> Option<LoadChange> {
+        (self.fast_feasibility && !self.spans_depot(i + 1, j + 1)).then(|| {
+            let mirror = self.tables.loads[i] + self.tables.loads[j];
+            LoadChange::new(&[(i + 1, j)], &[(i + 1, j, mirror, -1)])
+        })
+    }
+    
+    /// Cost delta of the 2-opt move reversing positions `i+1..=j` (`i < j`)
+    pub fn two_opt_delta(&self, i: usize, j: usize) -> f64 {
+        let n = self.tour.len();
+        if i + 1 >= j || j >= n {
+            return 0.0;
+        }
+        let Some(change) = self.two_opt_change(i, j).filter(|_| self.fast_cost) else {
+            return self.instance.tour_cost(&self.reversed(i, j)) - self.instance.tour_cost(self.tour);
+        };
+        
+        let t = self.tour;
+        let d = |a: usize, b: usize| self.instance.distance(a, b);
+        let next = t[(j + 1) % n];
+        let arcs = d(t[i], t[j]) + d(t[i + 1], next) - d(t[i], t[i + 1]) - d(t[j], next)
+            + self.tables.reversal[j] - self.tables.reversal[i + 1];
+        arcs + self.change_surcharge(&change)
+    }
+    
+    /// Whether the 2-opt move reversing positions `i+1..=j` gives a feasible tour
+    pub fn two_opt_feasible(&self, i: usize, j: usize) -> bool {
+        if i >= j || j >= self.tour.len() {
+            return self.tour_feasible();
+        }
+        match self.two_opt_change(i, j) {
+            Some(change) => self.change_feasible(&change),
+            None => self.instance.is_feasible(&self.reversed(i, j)),
+        }
+    }
+    
+    /// Load violation delta of the 2-opt move reversing positions `i+1..=j`
+    pub fn two_opt_violation_delta(&self, i: usize, j: usize) -> i64 {
+        if i + 1 >= j || j >= self.tour.len() {
+            return 0;
+        }
+        match self.two_opt_change(i, j) {
+            Some(change) => self.change_violation(&change),
+            None => self.full_violation_delta(&self.reversed(i, j)),
+        }
+    }
+    
+    /// Load change of a segment move (see `or_opt_delta`). The segment must
+    /// not hold a depot visit; when the move crosses one, the shifts of the
+    /// nodes around both places of the segment stop at the next visit.
+    fn segment_change(&self, seg_start: usize, seg_len: usize, after: usize, reversed: bool) -> Option<LoadChange> {
+        let (s, e) = (seg_start, seg_start + seg_len - 1);
+        if !self.fast_feasibility || s == 0 || self.spans_depot(s, e + 1) {
+            return None;
+        }
+        let l = &self.tables.loads;
+        let demand = l[e] - l[s - 1];
+        // New loads of the segment when the load before it is `base`
+        let segment = |base: i32| if reversed {
+            (s - 1, e, base + l[e], -1)
+        } else {
+            (s, e + 1, base - l[s - 1], 1)
+        };
+        Some(if after > e {
+            if !self.spans_depot(e + 1, after + 1) {
+                LoadChange::new(&[(s, after + 1)], &[segment(l[after] - demand), (e + 1, after + 1, -demand, 1)])
+            } else {
+                let (jumped, shifted) = (self.next_depot(e), self.next_depot(after));
+                LoadChange::new(
+                    &[(s, jumped), (after + 1, shifted)],
+                    &[segment(l[after]), (e + 1, jumped, -demand, 1), (after + 1, shifted, demand, 1)],
+                )
+            }
+        } else if !self.spans_depot(after + 1, s) {
+            LoadChange::new(&[(after + 1, e + 1)], &[segment(l[after]), (after + 1, s, demand, 1)])
+        } else {
+            let (jumped, shifted) = (self.next_depot(after), self.next_depot(e));
+            LoadChange::new(
+                &[(after + 1, jumped), (s, shifted)],
+                &[segment(l[after]), (after + 1, jumped, demand, 1), (e + 1, shifted, -demand, 1)],
+            )
+        })
+    }
+    
+    /// Cost delta of moving the segment `tour[seg_start..seg_start + seg_len]`
+    /// between `tour[after]` and its successor (`after` outside the segment and
+    /// not `seg_start - 1`), reversed or not
+    pub fn or_opt_delta(&self, seg_start: usize, seg_len: usize, after: usize, reversed: bool) -> f64 {
+        let Some(change) = self.segment_change(seg_start, seg_len, after, reversed).filter(|_| self.fast_cost) else {
+            let moved = relocate_segment(self.tour, seg_start, seg_len, after, reversed);
+            return self.instance.tour_cost(&moved) - self.instance.tour_cost(self.tour);
+        };
+        
+        let t = self.tour;
+        let n = t.len();
+        let d = |a: usize, b: usize| self.instance.distance(a, b);
+        let (s, e) = (seg_start, seg_start + seg_len - 1);
+        let (prev, next) = (t[s - 1], t[(e + 1) % n]);
+        let (first, last) = (t[s], t[e]);
+        let (a, b) = (t[after], t[(after + 1) % n]);
+        
+        let removal = d(prev, next) - d(prev, first) - d(last, next);
+        let insertion = if reversed {
+            // Inner arcs are traversed backwards (matters for asymmetric distances)
+            d(a, last) + d(first, b) + self.tables.reversal[e] - self.tables.reversal[s]
+        } else {
+            d(a, first) + d(last, b)
+        } - d(a, b);
+        removal + insertion + self.change_surcharge(&change)
+    }
+    
+    /// Whether the segment move of `or_opt_delta` gives a feasible tour
+    pub fn or_opt_feasible(&self, seg_start: usize, seg_len: usize, after: usize, reversed: bool) -> bool {
+        match self.segment_change(seg_start, seg_len, after, reversed) {
+            Some(change) => self.change_feasible(&change),
+            None => self.instance.is_feasible(&relocate_segment(self.tour, seg_start, seg_len, after, reversed)),
+        }
+    }
+    
+    /// Load violation delta of the segment move of `or_opt_delta`
+    pub fn or_opt_violation_delta(&self, seg_start: usize, seg_len: usize, after: usize, reversed: bool) -> i64 {
+        match self.segment_change(seg_start, seg_len, after, reversed) {
+            Some(change) => self.change_violation(&change),
+            None => self.full_violation_delta(&relocate_segment(self.tour, seg_start, seg_len, after, reversed)),
+        }
+    }
+    
+    /// Segment move equivalent to `Solution::apply_insertion(from, to)`
+    fn relocation_as_segment(&self, from: usize, to: usize) -> Option<usize> {
+        (from > 0 && to > 0 && to != from && to != from + 1 && to <= self.tour.len()).then_some(to - 1)
+    }
+    
+    /// New loads of a CROSS-exchange, as for `segment_change`
+    fn cross_ranges(&self, a: (usize, usize, bool), b: (usize, usize, bool)) -> [LoadRange; 3] {
+        let l = &self.tables.loads;
+        let ((s1, e1, reverse_a), (s2, e2, reverse_b)) = ((a.0, a.0 + a.1 - 1, a.2), (b.0, b.0 + b.1 - 1, b.2));
+        let (demand_a, demand_b) = (l[e1] - l[s1 - 1], l[e2] - l[s2 - 1]);
+        let segment_b = if reverse_b {
+            (s2 - 1, e2, l[s1 - 1] + l[e2], -1)
+        } else {
+            (s2, e2 + 1, l[s1 - 1] - l[s2 - 1], 1)
+        };
+        // Load on reaching segment A in its new place
+        let before_a = l[s2 - 1] + demand_b - demand_a;
+        let segment_a = if reverse_a {
+            (s1 - 1, e1, before_a + l[e1], -1)
+        } else {
+            (s1, e1 + 1, before_a - l[s1 - 1], 1)
+        };
+        [segment_b, (e1 + 1, s2, demand_b - demand_a, 1), segment_a]
+    }
+    
+    /// Load change of a CROSS-exchange whose span holds no depot visit
+    fn cross_change(&self, a: (usize, usize, bool), b: (usize, usize, bool)) -> Option<LoadChange> {
+        let (s1, e2) = (a.0, b.0 + b.1 - 1);
+        (self.fast_feasibility && s1 > 0 && !self.spans_depot(s1, e2 + 1))
+            .then(|| LoadChange::new(&[(s1, e2 + 1)], &self.cross_ranges(a, b)))
+    }
+    
+    /// Cost delta of exchanging the segments `tour[a_start..a_start + a_len]`
+    /// and `tour[b_start..b_start + b_len]` (`a_start + a_len <= b_start`),
+    /// each reversed or not (see `cross_exchange`)
+    pub fn cross_exchange_delta(&self, a_start: usize, a_len: usize, b_start: usize, b_len: usize, reverse_a: bool, reverse_b: bool) -> f64 {
+        let change = self.cross_change((a_start, a_len, reverse_a), (b_start, b_len, reverse_b));
+        let Some(change) = change.filter(|_| self.fast_cost) else {
+            let crossed = cross_exchange(self.tour, a_start, a_len, b_start, b_len, reverse_a, reverse_b);
+            return self.instance.tour_cost(&crossed) - self.instance.tour_cost(self.tour);
+        };
+        
+        let t = self.tour;
+        let n = t.len();
+        let d = |a: usize, b: usize| self.instance.distance(a, b);
+        let (s1, e1, s2, e2) = (a_start, a_start + a_len - 1, b_start, b_start + b_len - 1);
+        let (prev, next) = (t[s1 - 1], t[(e2 + 1) % n]);
+        let ends = |s: usize, e: usize, reversed: bool| if reversed { (t[e], t[s]) } else { (t[s], t[e]) };
+        let ((a_first, a_last), (b_first, b_last)) = (ends(s1, e1, reverse_a), ends(s2, e2, reverse_b));
+        // Inner arcs of a reversed segment are traversed backwards
+        let reversal = &self.tables.reversal;
+        let inner = |s: usize, e: usize, reversed: bool| if reversed { reversal[e] - reversal[s] } else { 0.0 };
+        
+        let arcs = if e1 + 1 == s2 {
+            d(prev, b_first) + d(b_last, a_first) + d(a_last, next)
+                - d(prev, t[s1]) - d(t[e1], t[s2]) - d(t[e2], next)
+        } else {
+            d(prev, b_first) + d(b_last, t[e1 + 1]) + d(t[s2 - 1], a_first) + d(a_last, next)
+                - d(prev, t[s1]) - d(t[e1], t[e1 + 1]) - d(t[s2 - 1], t[s2]) - d(t[e2], next)
+        } + inner(s1, e1, reverse_a) + inner(s2, e2, reverse_b);
+        arcs + self.change_surcharge(&change)
+    }
+    
+    /// Whether the move of `cross_exchange_delta` gives a feasible tour
+    pub fn cross_exchange_feasible(&self, a_start: usize, a_len: usize, b_start: usize, b_len: usize, reverse_a: bool, reverse_b: bool) -> bool {
+        match self.cross_change((a_start, a_len, reverse_a), (b_start, b_len, reverse_b)) {
+            Some(change) => self.change_feasible(&change),
+            None => self.instance.is_feasible(&cross_exchange(self.tour, a_start, a_len, b_start, b_len, reverse_a, reverse_b)),
+        }
+    }
+    
+    /// Cost delta of removing the node at `from` and inserting it before
+    /// position `to` (as `Solution::apply_insertion`)
+    pub fn relocation_delta(&self, from: usize, to: usize) -> f64 {
+        if from == to || from + 1 == to {
+            return 0.0;
+        }
+        match self.relocation_as_segment(from, to) {
+            Some(after) => self.or_opt_delta(from, 1, after, false),
+            None => self.instance.tour_cost(&self.relocated(from, to)) - self.instance.tour_cost(self.tour),
+        }
+    }
+    
+    /// Whether the move of `relocation_delta` gives a feasible tour
+    pub fn relocation_feasible(&self, from: usize, to: usize) -> bool {
+        match self.relocation_as_segment(from, to) {
+            Some(after) => self.or_opt_feasible(from, 1, after, false),
+            None => self.instance.is_feasible(&self.relocated(from, to)),
+        }
+    }
+    
+    /// Load violation delta of the move of `relocation_delta`
+    pub fn relocation_violation_delta(&self, from: usize, to: usize) -> i64 {
+        if from == to || from + 1 == to {
+            return 0;
+        }
+        match self.relocation_as_segment(from, to) {
+            Some(after) => self.or_opt_violation_delta(from, 1, after, false),
+            None => self.full_violation_delta(&self.relocated(from, to)),
+        }
+    }
+    
+    /// Improving moves among `moves`, best first by their delta under the
+    /// instance's cost function. Under load-dependent costs the shortest
+    /// move is often not the cheapest, since a reversal or a relocation also
+    /// changes where the heavy loads are carried: ranking by the full delta
+    /// (O(1) per move on the fast path) avoids chasing distance gains.
+    pub fn ranked_moves<M: Move>(&self, moves: impl IntoIterator<Item = M>) -> Vec<(f64, M)> {
+        self.best_moves(moves, usize::MAX)
+    }
+    
+    /// The `k` most improving moves among `moves`, best first. The moves are
+    /// priced one at a time and only the `k` best are kept (in a bounded
+    /// heap), so a lazy iterator over a quadratic neighborhood needs O(k)
+    /// memory.
+    pub fn best_moves<M: Move>(&self, moves: impl IntoIterator<Item = M>, k: usize) -> Vec<(f64, M)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut heap: BinaryHeap<RankedMove<M>> = BinaryHeap::new();
+        for mv in moves {
+            let delta = mv.delta(self);
+            if delta >= -1e-9 || (heap.len() == k && heap.peek().is_some_and(|worst| delta >= worst.0)) {
+                continue;
+            }
+            heap.push(RankedMove(delta, mv));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec().into_iter().map(|RankedMove(delta, mv)| (delta, mv)).collect()
+    }
+    
+    /// Most improving feasible move among `moves`. Feasibility is only
+    /// checked for moves better than the best feasible one so far.
+    pub fn best_feasible_move<M: Move>(&self, moves: impl IntoIterator<Item = M>) -> Option<(f64, M)> {
+        let mut best: Option<(f64, M)> = None;
+        for mv in moves {
+            let delta = mv.delta(self);
+            if delta < -1e-9 && best.as_ref().is_none_or(|(d, _)| delta < *d) && mv.is_feasible(self) {
+                best = Some((delta, mv));
+            }
+        }
+        best
+    }
+    
+    /// Instance the moves are evaluated on
+    pub fn instance(&self) -> &'a PDTSPInstance {
+        self.instance
+    }
+    
+    /// Tour the moves are evaluated on
+    pub fn tour(&self) -> &'a [usize] {
+        self.tour
+    }
+    
+    fn relocated(&self, from: usize, to: usize) -> Vec<usize> {
+        let mut new_tour = self.tour.to_vec();
+        let node = new_tour.remove(from);
+        let insert_pos = if to > from { to - 1 } else { to };
+        new_tour.insert(insert_pos, node);
+        new_tour
+    }
+}
+
+/// Move ordered by its delta alone, for the bounded heap of
+/// `DeltaEvaluator::best_moves` (the largest delta is on top)
+struct RankedMove<M>(f64, M);
+
+impl<M> PartialEq for RankedMove<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.total_cmp(&other.0).is_eq()
+    }
+}
+
+impl<M> Eq for RankedMove<M> {}
+
+impl<M> PartialOrd for RankedMove<M> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<M> Ord for RankedMove<M> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Tour obtained by moving the segment `tour[seg_start..seg_start + seg_len]`
+/// after position `after`, reversed or not
+pub fn relocate_segment(tour: &[usize], seg_start: usize, seg_len: usize, after: usize, reversed: bool) -> Vec<usize> {
+    let mut segment: Vec<usize> = tour[seg_start..seg_start + seg_len].to_vec();
+    if reversed {
+        segment.reverse();
+    }
+    
+    let mut new_tour = Vec::with_capacity(tour.len());
+    for (i, &node) in tour.iter().enumerate() {
+        if i < seg_start || i >= seg_start + seg_len {
+            new_tour.push(node);
+        }
+        if i == after {
+            new_tour.extend(&segment);
+        }
+    }
+    new_tour
+}
+
+/// Tour obtained by exchanging the segments `tour[a_start..a_start + a_len]`
+/// and `tour[b_start..b_start + b_len]` (`a_start + a_len <= b_start`), each
+/// reversed or not
+pub fn cross_exchange(tour: &[usize], a_start: usize, a_len: usize, b_start: usize, b_len: usize, reverse_a: bool, reverse_b: bool) -> Vec<usize> {
+    let segment = |start: usize, len: usize, reversed: bool| {
+        let mut nodes = tour[start..start + len].to_vec();
+        if reversed {
+            nodes.reverse();
+        }
+        nodes
+    };
+    let mut new_tour = Vec::with_capacity(tour.len());
+    new_tour.extend_from_slice(&tour[..a_start]);
+    new_tour.extend(segment(b_start, b_len, reverse_b));
+    new_tour.extend_from_slice(&tour[a_start + a_len..b_start]);
+    new_tour.extend(segment(a_start, a_len, reverse_a));
+    new_tour.extend_from_slice(&tour[b_start + b_len..]);
+    new_tour
+}
+
+/// A local search move: a change of a tour that can be priced and checked
+/// before it is applied.
+///
+/// Only `apply` is required. The default `delta`, `is_feasible` and
+/// `violation_delta` apply the move to a copy of the tour and re-evaluate it
+/// in O(n); moves with a cheaper evaluation override them, typically with
+/// the O(1) queries of the `DeltaEvaluator` (as `StandardMove` does). A custom neighborhood
+/// implements this trait and is searched like the built-in ones:
+///
+/// ```
+/// use pd_tsp_solver::solution::{DeltaEvaluator, Move};
+///
+/// /// Reverse the whole tour after the depot
+/// struct Reverse;
+///
+/// impl Move for Reverse {
+///     fn apply(&self, tour: &mut Vec<usize>) {
+///         tour[1..].reverse();
+///     }
+/// }
+///
+/// # let instance = pd_tsp_solver::PDTSPInstance::from_tsplib_str("NAME: m\nDIMENSION: 3\nCAPACITY: 5\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 0 1\nDEMAND_SECTION\n1 0\n2 1\n3 -1\nEOF\n").unwrap();
+/// let tour = vec![0, 1, 2];
+/// let evaluator = DeltaEvaluator::new(&instance, &tour);
+/// assert!(Reverse.delta(&evaluator).abs() < 1e-9);
+/// assert!(!Reverse.is_feasible(&evaluator));
+/// ```
+pub trait Move {
+    /// Change the tour
+    fn apply(&self, tour: &mut Vec<usize>);
+    
+    /// Change of the tour cost made by the move
+    fn delta(&self, evaluator: &DeltaEvaluator) -> f64 {
+        let instance = evaluator.instance();
+        instance.tour_cost(&self.applied_to(evaluator.tour())) - instance.tour_cost(evaluator.tour())
+    }
+    
+    /// Whether the tour after the move is feasible
+    fn is_feasible(&self, evaluator: &DeltaEvaluator) -> bool {
+        evaluator.instance().is_feasible(&self.applied_to(evaluator.tour()))
+    }
+    
+    /// Change of the load violation (see `PDTSPInstance::load_violation`)
+    /// made by the move, for searches that cross infeasible tours
+    fn violation_delta(&self, evaluator: &DeltaEvaluator) -> i64 {
+        let instance = evaluator.instance();
+        instance.load_violation(self.applied_to(evaluator.tour())) - evaluator.violation()
+    }
+    
+    /// Copy of `tour` after the move
+    fn applied_to(&self, tour: &[usize]) -> Vec<usize> {
+        let mut moved = tour.to_vec();
+        self.apply(&mut moved);
+        moved
+    }
+}
+
+/// The moves of the built-in searches, by tour positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StandardMove {
+    /// Swap the nodes at two positions
     Swap(usize, usize),
+    /// Reverse positions `i+1..=j` (`i < j`)
     TwoOpt(usize, usize),
+    /// Remove the node at `from` and insert it before position `to`
     Insertion(usize, usize),
-    OrOpt(usize, usize, usize), // segment start, length, insertion position
+    /// Move the segment of a length from a start position before position
+    /// `to`, outside the segment: (start, length, to)
+    OrOpt(usize, usize, usize),
 }
 
-impl Move {
-    pub fn delta(&self, solution: &Solution, instance: &PDTSPInstance) -> f64 {
+impl StandardMove {
+    /// First tour position changed by the move, for `DeltaEvaluator::refresh`
+    pub fn first_changed(&self) -> usize {
         match *self {
-            Move::Swap(i, j) => solution.swap_delta(instance, i, j),
-            Move::TwoOpt(i, j) => solution.two_opt_delta(instance, i, j),
-            Move::Insertion(from, to) => solution.insertion_delta(instance, from, to),
-            Move::OrOpt(_, _, _) => 0.0, // Computed separately
+            StandardMove::Swap(i, j) => i.min(j),
+            StandardMove::TwoOpt(i, _) => i + 1,
+            StandardMove::Insertion(from, to) => from.min(to),
+            StandardMove::OrOpt(start, _, to) => start.min(to),
         }
     }
-    
-    pub fn apply(&self, solution: &mut Solution) {
+}
+
+impl Move for StandardMove {
+    fn apply(&self, tour: &mut Vec<usize>) {
         match *self {
-            Move::Swap(i, j) => solution.apply_swap(i, j),
-            Move::TwoOpt(i, j) => solution.apply_two_opt(i, j),
-            Move::Insertion(from, to) => solution.apply_insertion(from, to),
-            Move::OrOpt(start, len, to) => {
-                
-                let segment: Vec<usize> = solution.tour.drain(start..start + len).collect();
+            StandardMove::Swap(i, j) => tour.swap(i, j),
+            StandardMove::TwoOpt(i, j) => tour[i + 1..=j].reverse(),
+            StandardMove::Insertion(from, to) => {
+                let node = tour.remove(from);
+                let insert_pos = if to > from { to - 1 } else { to };
+                tour.insert(insert_pos, node);
+            }
+            StandardMove::OrOpt(start, len, to) => {
+                let segment: Vec<usize> = tour.drain(start..start + len).collect();
                 let insert_pos = if to > start { to - len } else { to };
-                for (i, node) in segment.into_iter().enumerate() {
-                    solution.tour.insert(insert_pos + i, node);
-                }
+                tour.splice(insert_pos..insert_pos, segment);
+            }
+        }
+    }
+    
+    fn delta(&self, evaluator: &DeltaEvaluator) -> f64 {
+        match *self {
+            StandardMove::Swap(i, j) => evaluator.swap_delta(i, j),
+            StandardMove::TwoOpt(i, j) => evaluator.two_opt_delta(i, j),
+            StandardMove::Insertion(from, to) => evaluator.relocation_delta(from, to),
+            StandardMove::OrOpt(start, len, to) if to > 0 && (to < start || to > start + len) => {
+                evaluator.or_opt_delta(start, len, to - 1, false)
+            }
+            StandardMove::OrOpt(..) => {
+                let instance = evaluator.instance();
+                instance.tour_cost(&self.applied_to(evaluator.tour())) - instance.tour_cost(evaluator.tour())
+            }
+        }
+    }
+    
+    fn is_feasible(&self, evaluator: &DeltaEvaluator) -> bool {
+        match *self {
+            StandardMove::Swap(i, j) => evaluator.swap_feasible(i, j),
+            StandardMove::TwoOpt(i, j) => evaluator.two_opt_feasible(i, j),
+            StandardMove::Insertion(from, to) => evaluator.relocation_feasible(from, to),
+            StandardMove::OrOpt(start, len, to) if to > 0 && (to < start || to > start + len) => {
+                evaluator.or_opt_feasible(start, len, to - 1, false)
+            }
+            StandardMove::OrOpt(..) => evaluator.instance().is_feasible(&self.applied_to(evaluator.tour())),
+        }
+    }
+    
+    fn violation_delta(&self, evaluator: &DeltaEvaluator) -> i64 {
+        match *self {
+            StandardMove::Swap(i, j) => evaluator.swap_violation_delta(i, j),
+            StandardMove::TwoOpt(i, j) => evaluator.two_opt_violation_delta(i, j),
+            StandardMove::Insertion(from, to) => evaluator.relocation_violation_delta(from, to),
+            StandardMove::OrOpt(start, len, to) if to > 0 && (to < start || to > start + len) => {
+                evaluator.or_opt_violation_delta(start, len, to - 1, false)
+            }
+            StandardMove::OrOpt(..) => {
+                let instance = evaluator.instance();
+                instance.load_violation(self.applied_to(evaluator.tour())) - evaluator.violation()
             }
         }
     }
@@ -273,4 +1380,274 @@ mod tests {
         assert!(!sol.feasible);
         assert_eq!(sol.cost, f64::INFINITY);
     }
+    
+    fn create_test_instance() -> PDTSPInstance {
+        use crate::instance::{DistanceMatrix, DistanceMode, Node};
+        
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 1.0, 0.0, 5, 0),
+            Node::new(2, 2.0, 0.0, -3, 0),
+            Node::new(3, 1.0, 1.0, -2, 0),
+        ];
+        let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        PDTSPInstance::from_parts("test", 10, nodes, distance_matrix)
+    }
+    
+    #[test]
+    fn test_tsplib_tour_round_trip() {
+        let instance = create_test_instance();
+        let sol = Solution::from_tour(&instance, vec![0, 1, 2, 3], "test");
+        let text = sol.to_tsplib_tour(&instance, "test");
+        assert!(text.contains("TOUR_SECTION\n1\n2\n3\n4\n-1"));
+        
+        let parsed = Solution::from_tsplib_tour(&instance, &text).unwrap();
+        assert_eq!(parsed.tour, sol.tour);
+        
+        let rotated = "TOUR_SECTION\n3 4\n1 2\n-1\n";
+        assert_eq!(Solution::from_tsplib_tour(&instance, rotated).unwrap().tour, vec![0, 1, 2, 3]);
+        assert!(Solution::from_tsplib_tour(&instance, "TOUR_SECTION\n1 5\n-1").is_err());
+        
+        // File ids follow the instance file when it names another depot
+        let text = "NAME: d\nDIMENSION: 4\nCAPACITY: 5\nNODE_COORD_SECTION\n1 1 0\n2 2 0\n3 0 0\n4 5 0\nDEMAND_SECTION\n1 2\n2 -1\n3 0\n4 -1\nDEPOT_SECTION\n3\n-1\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let sol = Solution::from_tour(&instance, vec![0, 2, 1, 3], "test");
+        let exported = sol.to_tsplib_tour(&instance, "d");
+        assert!(exported.contains("TOUR_SECTION\n3\n1\n2\n4\n-1"), "{}", exported);
+        assert_eq!(Solution::from_tsplib_tour(&instance, &exported).unwrap().tour, sol.tour);
+        
+        // Intermediate depot visits repeat the depot id; customers may not repeat
+        let sol = Solution::from_tour(&instance, vec![0, 2, 0, 1, 3], "test");
+        let exported = sol.to_tsplib_tour(&instance, "d");
+        assert!(exported.contains("TOUR_SECTION\n3\n1\n3\n2\n4\n-1"), "{}", exported);
+        assert_eq!(Solution::from_tsplib_tour(&instance, &exported).unwrap().tour, sol.tour);
+        assert!(Solution::from_tsplib_tour(&instance, "TOUR_SECTION\n3 1 3 1 4\n-1").is_err());
+        
+        // Extra visits of split demands are written as their customer
+        let mut split = instance.clone();
+        split.split_demands(Some(1)).unwrap();
+        assert_eq!(split.dimension, 5);
+        let sol = Solution::from_tour(&split, vec![0, 4, 2, 1, 3], "test");
+        let exported = sol.to_tsplib_tour(&split, "d");
+        let file_id = |node: usize| split.file_index(split.original_node(node)) + 1;
+        let ids: Vec<String> = sol.tour.iter().map(|&node| file_id(node).to_string()).collect();
+        assert!(exported.contains(&format!("TOUR_SECTION\n{}\n-1", ids.join("\n"))), "{}", exported);
+        assert!(ids.iter().all(|id| id.parse::<usize>().unwrap() <= 4));
+        let parsed = Solution::from_tsplib_tour(&split, &exported).unwrap();
+        assert_eq!(split.original_tour(&parsed.tour), split.original_tour(&sol.tour));
+        assert!((parsed.cost - sol.cost).abs() < 1e-9);
+        assert!(Solution::from_tsplib_tour(&split, "TOUR_SECTION\n3 1 1 1 2 4\n-1").is_err());
+    }
+    
+    #[test]
+    fn test_insertion_impact() {
+        let instance = create_test_instance();
+        let sol = Solution::from_tour(&instance, vec![0, 1, 2], "test");
+        
+        // Node 3 delivers 2 units: it must come after the pickup at node 1
+        let best = sol.cheapest_feasible_insertion(&instance, 3).unwrap();
+        assert!(best.feasible);
+        assert!(best.position >= 2);
+        
+        let mut tour = sol.tour.clone();
+        tour.insert(best.position, 3);
+        assert!((instance.tour_cost(&tour) - sol.cost - best.cost_delta).abs() < 1e-9);
+        assert_eq!(best.new_peak_load, 5);
+        
+        assert_eq!(sol.insertion_impact(&instance, 3), Some(best));
+        assert!(sol.insertion_impact(&instance, 1).is_none());
+        
+        // Every position agrees with the full evaluation, across a depot
+        // visit and under a route length limit the loads alone miss
+        let mut instance = instance;
+        instance.max_route_length = Some(4.5);
+        let sol = Solution::from_tour(&instance, vec![0, 1, 2, 0], "test");
+        let impacts = sol.evaluate_insertions(&instance, 3);
+        assert_eq!(impacts.len(), 4);
+        let mut too_long = 0;
+        for impact in &impacts {
+            let mut tour = sol.tour.clone();
+            tour.insert(impact.position, 3);
+            let (loads_feasible, peak, _, _) = instance.check_feasibility_detailed(&tour);
+            assert!((instance.tour_cost(&tour) - sol.cost - impact.cost_delta).abs() < 1e-9);
+            assert_eq!(impact.new_peak_load, peak);
+            assert_eq!(impact.feasible, instance.is_feasible(&tour));
+            too_long += (loads_feasible && !impact.feasible) as usize;
+        }
+        assert!(too_long > 0);
+    }
+    
+    #[test]
+    fn test_delta_evaluator_matches_full_evaluation() {
+        use crate::instance::{CostFunction, DistanceMatrix, DistanceMode, Node};
+        use rand::prelude::*;
+        use rand_chacha::ChaCha8Rng;
+        
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 4.0, 1.0, 3, 0),
+            Node::new(2, 2.0, 5.0, -2, 0),
+            Node::new(3, 6.0, 3.0, 2, 0),
+            Node::new(4, 1.0, 3.0, -3, 0),
+            Node::new(5, 5.0, 6.0, 1, 0),
+            Node::new(6, 3.0, 2.0, -1, 0),
+            Node::new(7, 7.0, 0.0, 2, 0),
+        ];
+        let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        let mut instance = PDTSPInstance {
+            alpha: 0.3,
+            beta: 0.2,
+            ..PDTSPInstance::from_parts("delta", 4, nodes, distance_matrix)
+        };
+        
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        for cost_function in [CostFunction::Distance, CostFunction::Quadratic, CostFunction::LinearLoad] {
+            instance.cost_function = cost_function;
+            for _ in 0..20 {
+                let mut tour: Vec<usize> = (1..8).collect();
+                tour.shuffle(&mut rng);
+                tour.insert(0, 0);
+                let evaluator = DeltaEvaluator::new(&instance, &tour);
+                let cost = instance.tour_cost(&tour);
+                
+                for i in 0..8 {
+                    for j in i + 1..8 {
+                        let mut swapped = tour.clone();
+                        swapped.swap(i, j);
+                        assert!((evaluator.swap_delta(i, j) - (instance.tour_cost(&swapped) - cost)).abs() < 1e-9);
+                        assert_eq!(evaluator.swap_feasible(i, j), instance.is_feasible(&swapped));
+                        
+                        let mut reversed = tour.clone();
+                        reversed[i + 1..=j].reverse();
+                        assert!((evaluator.two_opt_delta(i, j) - (instance.tour_cost(&reversed) - cost)).abs() < 1e-9);
+                        assert_eq!(evaluator.two_opt_feasible(i, j), instance.is_feasible(&reversed));
+                    }
+                }
+                
+                for from in 1..8 {
+                    for to in (1..=8).filter(|&to| to != from && to != from + 1) {
+                        let mut relocated = tour.clone();
+                        let node = relocated.remove(from);
+                        relocated.insert(if to > from { to - 1 } else { to }, node);
+                        assert!((evaluator.relocation_delta(from, to) - (instance.tour_cost(&relocated) - cost)).abs() < 1e-9);
+                        assert_eq!(evaluator.relocation_feasible(from, to), instance.is_feasible(&relocated));
+                    }
+                }
+                
+                for seg_len in 2..=3 {
+                    for seg_start in 1..=8 - seg_len {
+                        for after in (0..8).filter(|&a| a + 1 < seg_start || a >= seg_start + seg_len) {
+                            for reversed in [false, true] {
+                                let moved = relocate_segment(&tour, seg_start, seg_len, after, reversed);
+                                let delta = evaluator.or_opt_delta(seg_start, seg_len, after, reversed);
+                                assert!((delta - (instance.tour_cost(&moved) - cost)).abs() < 1e-9);
+                                assert_eq!(evaluator.or_opt_feasible(seg_start, seg_len, after, reversed), instance.is_feasible(&moved));
+                                if !reversed {
+                                    let mv = StandardMove::OrOpt(seg_start, seg_len, after + 1);
+                                    assert_eq!(mv.applied_to(&tour), moved);
+                                    assert!((mv.delta(&evaluator) - delta).abs() < 1e-9);
+                                    assert_eq!(mv.is_feasible(&evaluator), instance.is_feasible(&moved));
+                                    let solution = Solution::from_tour(&instance, tour.clone(), "test");
+                                    assert!((solution.move_delta(&instance, &mv) - delta).abs() < 1e-9);
+                                }
+                            }
+                        }
+                    }
+                }
+                
+                for (a_len, b_len) in [(1, 1), (1, 3), (2, 2), (3, 1)] {
+                    for a_start in 1..8 {
+                        for b_start in (a_start + a_len..8).filter(|&b| b + b_len <= 8) {
+                            for (reverse_a, reverse_b) in [(false, false), (true, false), (false, true), (true, true)] {
+                                let crossed = cross_exchange(&tour, a_start, a_len, b_start, b_len, reverse_a, reverse_b);
+                                let delta = evaluator.cross_exchange_delta(a_start, a_len, b_start, b_len, reverse_a, reverse_b);
+                                assert!((delta - (instance.tour_cost(&crossed) - cost)).abs() < 1e-9);
+                                assert_eq!(
+                                    evaluator.cross_exchange_feasible(a_start, a_len, b_start, b_len, reverse_a, reverse_b),
+                                    instance.is_feasible(&crossed)
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn test_delta_evaluator_depot_visits_violations_and_refresh() {
+        use crate::instance::{CostFunction, DistanceMatrix, DistanceMode, Node};
+        use rand::prelude::*;
+        use rand_chacha::ChaCha8Rng;
+        
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        let demands = [0, 3, -2, 2, -3, 1, -1, 2, -2];
+        let nodes: Vec<Node> = demands.iter().enumerate()
+            .map(|(i, &d)| Node::new(i, rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0), d, 0))
+            .collect();
+        let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        let mut instance = PDTSPInstance {
+            alpha: 0.3,
+            beta: 0.2,
+            ..PDTSPInstance::from_parts("depots", 3, nodes, distance_matrix)
+        };
+        
+        for cost_function in [CostFunction::Distance, CostFunction::Quadratic, CostFunction::LinearLoad] {
+            instance.cost_function = cost_function;
+            for _ in 0..20 {
+                // Customers with two intermediate depot visits
+                let mut tour: Vec<usize> = (1..9).chain([0, 0]).collect();
+                tour.shuffle(&mut rng);
+                tour.insert(0, 0);
+                let n = tour.len();
+                let evaluator = DeltaEvaluator::new(&instance, &tour);
+                let cost = instance.tour_cost(&tour);
+                let violation = instance.load_violation(tour.iter().copied());
+                assert_eq!(evaluator.violation(), violation);
+                assert_eq!(evaluator.tour_feasible(), instance.is_feasible(&tour));
+                
+                let mut moves = Vec::new();
+                for i in 0..n {
+                    for j in i + 1..n {
+                        moves.push(StandardMove::Swap(i, j));
+                        moves.push(StandardMove::TwoOpt(i, j));
+                    }
+                    for to in 1..=n {
+                        moves.push(StandardMove::Insertion(i, to));
+                        if i + 2 <= n {
+                            moves.push(StandardMove::OrOpt(i, 2, to));
+                        }
+                    }
+                }
+                for mv in moves {
+                    if let StandardMove::Insertion(0, _) | StandardMove::OrOpt(0, ..) = mv {
+                        continue;
+                    }
+                    if let StandardMove::OrOpt(start, len, to) = mv {
+                        if to >= start && to <= start + len {
+                            continue;
+                        }
+                    }
+                    let moved = mv.applied_to(&tour);
+                    assert!((mv.delta(&evaluator) - (instance.tour_cost(&moved) - cost)).abs() < 1e-9, "{:?}", mv);
+                    assert_eq!(mv.is_feasible(&evaluator), instance.is_feasible(&moved), "{:?}", mv);
+                    assert_eq!(mv.violation_delta(&evaluator), instance.load_violation(moved.iter().copied()) - violation, "{:?}", mv);
+                    
+                    // Refreshing after the move matches a fresh build
+                    if moved[0] != 0 {
+                        continue;
+                    }
+                    let tables = DeltaEvaluator::new(&instance, &tour).into_tables();
+                    let refreshed = DeltaEvaluator::refresh(&instance, &moved, tables, mv.first_changed()).into_tables();
+                    let fresh = DeltaEvaluator::new(&instance, &moved).into_tables();
+                    assert_eq!(refreshed.loads, fresh.loads);
+                    assert_eq!((&refreshed.min_table, &refreshed.max_table), (&fresh.min_table, &fresh.max_table));
+                    assert_eq!((&refreshed.sum, &refreshed.sum_sq, &refreshed.sum_abs), (&fresh.sum, &fresh.sum_sq, &fresh.sum_abs));
+                    assert_eq!((&refreshed.violations, &refreshed.excess), (&fresh.violations, &fresh.excess));
+                    assert_eq!((&refreshed.depots, &refreshed.depot_positions), (&fresh.depots, &fresh.depot_positions));
+                    assert_eq!(refreshed.reversal, fresh.reversal);
+                }
+            }
+        }
+    }
 }
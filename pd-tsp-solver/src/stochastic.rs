@@ -0,0 +1,326 @@
+//! Stochastic demands.
+//!
+//! Each customer demand can follow a distribution around its nominal value
+//! (`DemandDistribution`). A tour is then evaluated by Monte-Carlo
+//! simulation over sampled demand scenarios: the probability that some
+//! capacity limit is hit along the tour, and the expected cost of the
+//! detour-to-depot recourse repairing those failures. The scenarios are
+//! sampled once (`DemandScenarios`), so every tour is compared on the same
+//! random numbers, and a `ChanceConstraint` is a deterministic feasibility
+//! test. Set on an instance (`PDTSPInstance::chance_constraint`), it is part
+//! of `is_feasible` and so of every search.
+//!
+//! Only the aggregate (single-commodity) demand is simulated.
+
+use crate::instance::PDTSPInstance;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// Distribution of a customer demand around its nominal value. Realized
+/// demands are rounded and keep the sign of the nominal demand (a pickup
+/// never becomes a delivery); they are zero rather than change sign.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum DemandDistribution {
+    /// The nominal demand
+    Fixed,
+    /// Normal, with standard deviation `relative_std` times the nominal magnitude
+    Normal { relative_std: f64 },
+    /// Nominal magnitude plus `offsets[k]` with probability `probabilities[k]`
+    Discrete { offsets: Vec<i32>, probabilities: Vec<f64> },
+}
+
+impl DemandDistribution {
+    /// Realized demand for a nominal demand
+    pub fn sample(&self, nominal: i32, rng: &mut ChaCha8Rng) -> i32 {
+        let magnitude = nominal.abs() as f64;
+        let realized = match self {
+            DemandDistribution::Fixed => return nominal,
+            DemandDistribution::Normal { relative_std } => {
+                // Box-Muller transform
+                let (u1, u2): (f64, f64) = (1.0 - rng.gen::<f64>(), rng.gen());
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                magnitude + relative_std * magnitude * z
+            }
+            DemandDistribution::Discrete { offsets, probabilities } => {
+                let mut draw = rng.gen::<f64>() * probabilities.iter().sum::<f64>();
+                let k = probabilities.iter().position(|&p| {
+                    draw -= p;
+                    draw < 0.0
+                });
+                magnitude + k.and_then(|k| offsets.get(k)).copied().unwrap_or(0) as f64
+            }
+        };
+        (realized.round().max(0.0) as i32) * nominal.signum()
+    }
+}
+
+/// Demand distribution of every node (the depot demand stays fixed)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StochasticDemands {
+    pub distributions: Vec<DemandDistribution>,
+}
+
+impl StochasticDemands {
+    /// Same distribution for every customer of `instance`
+    pub fn uniform(instance: &PDTSPInstance, distribution: DemandDistribution) -> Self {
+        let mut distributions = vec![distribution; instance.dimension];
+        if let Some(depot) = distributions.first_mut() {
+            *depot = DemandDistribution::Fixed;
+        }
+        StochasticDemands { distributions }
+    }
+
+    /// Set the distribution of one node
+    pub fn with_node(mut self, node: usize, distribution: DemandDistribution) -> Self {
+        if node > 0 && node < self.distributions.len() {
+            self.distributions[node] = distribution;
+        }
+        self
+    }
+
+    /// Sample `samples` demand scenarios of `instance`
+    pub fn sample_scenarios(&self, instance: &PDTSPInstance, samples: usize, seed: u64) -> DemandScenarios {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let demands = (0..samples)
+            .map(|_| {
+                instance.nodes.iter().enumerate().map(|(i, node)| {
+                    match self.distributions.get(i) {
+                        Some(distribution) if i > 0 => distribution.sample(node.demand, &mut rng),
+                        _ => node.demand,
+                    }
+                }).collect()
+            })
+            .collect();
+        DemandScenarios { demands }
+    }
+}
+
+/// Result of a Monte-Carlo evaluation of a tour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub samples: usize,
+    /// Fraction of the scenarios in which some capacity limit is hit
+    pub violation_probability: f64,
+    /// Mean number of recourse detours per scenario
+    pub expected_failures: f64,
+    /// Mean cost of the recourse detours
+    pub expected_recourse_cost: f64,
+    /// Planned travel cost plus the expected recourse cost
+    pub expected_total_cost: f64,
+}
+
+impl std::fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Scenarios: {}", self.samples)?;
+        writeln!(f, "Capacity violation probability: {:.2}%", 100.0 * self.violation_probability)?;
+        writeln!(f, "Expected recourse detours: {:.3}", self.expected_failures)?;
+        writeln!(f, "Expected recourse cost: {:.2}", self.expected_recourse_cost)?;
+        write!(f, "Expected total cost: {:.2}", self.expected_total_cost)
+    }
+}
+
+/// Sampled demands, one vector indexed by node per scenario
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemandScenarios {
+    pub demands: Vec<Vec<i32>>,
+}
+
+impl DemandScenarios {
+    pub fn len(&self) -> usize {
+        self.demands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.demands.is_empty()
+    }
+
+    /// Number of detours and their cost when following `tour` in scenario
+    /// `k`. Detour-to-depot recourse: before a pickup that would overflow,
+    /// the vehicle goes to the depot and back to unload; before a delivery
+    /// it cannot serve, it goes to fetch the missing goods. Depot visits of
+    /// the tour empty the vehicle, as in `PDTSPInstance::is_feasible`.
+    pub fn recourse(&self, instance: &PDTSPInstance, tour: &[usize], k: usize) -> (usize, f64) {
+        let demands = &self.demands[k];
+        let (mut failures, mut cost) = (0, 0.0);
+        let mut load = instance.starting_load();
+        for &node in tour.iter().skip(1) {
+            if node == 0 {
+                load = 0;
+                continue;
+            }
+            let next = load + demands[node];
+            if (0..=instance.capacity).contains(&next) {
+                load = next;
+                continue;
+            }
+            failures += 1;
+            cost += instance.distance(node, 0) + instance.distance(0, node);
+            load = demands[node].clamp(0, instance.capacity);
+        }
+        (failures, cost)
+    }
+
+    /// Monte-Carlo evaluation of `tour` over the scenarios
+    pub fn evaluate(&self, instance: &PDTSPInstance, tour: &[usize]) -> SimulationReport {
+        let samples = self.len().max(1) as f64;
+        let (mut violated, mut failures, mut cost) = (0usize, 0usize, 0.0);
+        for k in 0..self.len() {
+            let (f, c) = self.recourse(instance, tour, k);
+            violated += usize::from(f > 0);
+            failures += f;
+            cost += c;
+        }
+        SimulationReport {
+            samples: self.len(),
+            violation_probability: violated as f64 / samples,
+            expected_failures: failures as f64 / samples,
+            expected_recourse_cost: cost / samples,
+            expected_total_cost: instance.tour_cost(tour) + cost / samples,
+        }
+    }
+
+    /// Fraction of the scenarios in which `tour` hits a capacity limit
+    pub fn violation_probability(&self, instance: &PDTSPInstance, tour: &[usize]) -> f64 {
+        let violated = (0..self.len()).filter(|&k| !self.is_feasible(instance, tour, k)).count();
+        violated as f64 / self.len().max(1) as f64
+    }
+
+    /// Whether `tour` respects the capacity in scenario `k`
+    pub fn is_feasible(&self, instance: &PDTSPInstance, tour: &[usize], k: usize) -> bool {
+        let demands = &self.demands[k];
+        let mut load = instance.starting_load();
+        tour.iter().skip(1).all(|&node| {
+            load = if node == 0 { 0 } else { load + demands[node] };
+            (0..=instance.capacity).contains(&load)
+        })
+    }
+}
+
+/// Chance constraint: a tour is accepted when its capacity violation
+/// probability over fixed sampled scenarios is at most `epsilon`
+#[derive(Debug, Clone)]
+pub struct ChanceConstraint {
+    pub scenarios: DemandScenarios,
+    pub epsilon: f64,
+}
+
+impl ChanceConstraint {
+    pub fn new(instance: &PDTSPInstance, demands: &StochasticDemands, samples: usize, seed: u64, epsilon: f64) -> Self {
+        ChanceConstraint { scenarios: demands.sample_scenarios(instance, samples, seed), epsilon }
+    }
+
+    /// Whether `tour` satisfies the chance constraint (and the nominal capacity)
+    pub fn is_satisfied(&self, instance: &PDTSPInstance, tour: &[usize]) -> bool {
+        instance.is_feasible(tour) && self.scenarios_satisfied(instance, tour)
+    }
+
+    /// Whether at most a fraction `epsilon` of the scenarios hit a capacity
+    /// limit along `tour`
+    pub fn scenarios_satisfied(&self, instance: &PDTSPInstance, tour: &[usize]) -> bool {
+        // Stop as soon as too many scenarios fail
+        let allowed = (self.epsilon * self.scenarios.len() as f64).floor() as usize;
+        let mut violated = 0;
+        for k in 0..self.scenarios.len() {
+            if !self.scenarios.is_feasible(instance, tour, k) {
+                violated += 1;
+                if violated > allowed {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Monte-Carlo evaluation of `tour` with `samples` scenarios
+pub fn simulate(instance: &PDTSPInstance, tour: &[usize], demands: &StochasticDemands, samples: usize, seed: u64) -> SimulationReport {
+    demands.sample_scenarios(instance, samples, seed).evaluate(instance, tour)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulation_and_chance_constraint() {
+        let text = "NAME: s\nDIMENSION: 5\nCAPACITY: 6\nNODE_COORD_SECTION\n1 0 0\n2 3 0\n3 3 4\n4 0 4\n5 1 2\nDEMAND_SECTION\n1 0\n2 5\n3 -4\n4 4\n5 -5\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let tour = vec![0, 1, 2, 3, 4];
+        assert!(instance.is_feasible(&tour));
+
+        // Fixed demands: the nominal answer
+        let fixed = StochasticDemands::uniform(&instance, DemandDistribution::Fixed);
+        assert_eq!(simulate(&instance, &tour, &fixed, 20, 1).violation_probability, 0.0);
+        let overflow = simulate(&instance, &[0, 1, 3, 2, 4], &fixed, 20, 1);
+        assert_eq!(overflow.violation_probability, 1.0);
+        // Node 3 overflows and is served after unloading at the depot, then
+        // node 4 lacks the goods left there: two detours
+        assert_eq!(overflow.expected_failures, 2.0);
+        assert!((overflow.expected_recourse_cost - 8.0 - 2.0 * 5f64.sqrt()).abs() < 1e-9);
+
+        let noisy = StochasticDemands::uniform(&instance, DemandDistribution::Normal { relative_std: 0.3 });
+        let report = simulate(&instance, &tour, &noisy, 500, 1);
+        assert!(report.violation_probability > 0.0 && report.violation_probability < 1.0);
+        assert!(report.expected_total_cost > instance.tour_cost(&tour));
+        let scenarios = noisy.sample_scenarios(&instance, 500, 1);
+        assert_eq!(scenarios.violation_probability(&instance, &tour), report.violation_probability);
+        assert!(scenarios.demands.iter().all(|d| d[1] >= 0 && d[2] <= 0 && d[0] == 0));
+
+        let strict = ChanceConstraint::new(&instance, &noisy, 500, 1, 0.0);
+        let loose = ChanceConstraint::new(&instance, &noisy, 500, 1, 1.0);
+        assert!(!strict.is_satisfied(&instance, &tour));
+        assert!(loose.is_satisfied(&instance, &tour));
+        assert!(!loose.is_satisfied(&instance, &[0, 1, 3, 2, 4]));
+
+        // Discrete: demand of node 1 is 5 or 7 with equal probability
+        let discrete = fixed.with_node(1, DemandDistribution::Discrete { offsets: vec![0, 2], probabilities: vec![0.5, 0.5] });
+        let p = simulate(&instance, &tour, &discrete, 2000, 3).violation_probability;
+        assert!((p - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_chance_constraint_in_feasibility_checks() {
+        use crate::heuristics::local_search::{LocalSearch, VND};
+        use crate::heuristics::popmusic::Popmusic;
+        use crate::instance::Violation;
+        use crate::solution::{DeltaEvaluator, Solution};
+        use std::sync::Arc;
+
+        let text = "NAME: s\nDIMENSION: 6\nCAPACITY: 6\nNODE_COORD_SECTION\n1 0 0\n2 3 0\n3 3 4\n4 0 4\n5 1 2\n6 2 1\nDEMAND_SECTION\n1 0\n2 5\n3 -4\n4 4\n5 -5\n6 1\nEOF\n";
+        let mut instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let tour = vec![0, 1, 2, 3, 4, 5];
+        assert!(instance.is_feasible(&tour));
+        let noisy = StochasticDemands::uniform(&instance, DemandDistribution::Normal { relative_std: 0.3 });
+        let p = noisy.sample_scenarios(&instance, 200, 1).violation_probability(&instance, &tour);
+        assert!(p > 0.0);
+
+        // Below the tour's violation probability, it is no longer feasible
+        // for the instance, its evaluator or its evaluation
+        instance.chance_constraint = Some(Arc::new(ChanceConstraint::new(&instance, &noisy, 200, 1, p / 2.0)));
+        assert!(!instance.is_feasible(&tour));
+        assert_eq!(instance.explain_infeasibility(&tour), vec![Violation::ChanceConstraint { probability: p, epsilon: p / 2.0 }]);
+        assert!(!DeltaEvaluator::new(&instance, &tour).tour_feasible());
+        assert!(!crate::evaluation::evaluate(&instance, &tour).feasible);
+        assert!(instance.split_demands(None).is_err());
+
+        // At its probability, it is; a descent keeps the constraint
+        let chance = Arc::new(ChanceConstraint::new(&instance, &noisy, 200, 1, p));
+        instance.chance_constraint = Some(chance.clone());
+        assert!(instance.is_feasible(&tour) && DeltaEvaluator::new(&instance, &tour).tour_feasible());
+        assert!(instance.explain_infeasibility(&tour).is_empty());
+        let mut solution = Solution::from_tour(&instance, tour.clone(), "test");
+        VND::with_standard_operators().improve(&instance, &mut solution);
+        assert!(solution.feasible && chance.is_satisfied(&instance, &solution.tour));
+        assert!(chance.scenarios.violation_probability(&instance, &solution.tour) <= p);
+
+        // POPMUSIC accepts sub-paths from a subproblem without the constraint,
+        // so it must check the full tour
+        let mut solution = Solution::from_tour(&instance, tour, "test");
+        let mut popmusic = Popmusic::new();
+        popmusic.subproblem_size = 4;
+        popmusic.improve(&instance, &mut solution);
+        assert!(solution.feasible && chance.is_satisfied(&instance, &solution.tour));
+    }
+}
@@ -0,0 +1,343 @@
+//! SQLite store of benchmark results.
+//!
+//! A campaign spread over many benchmark sessions accumulates its runs in a
+//! single database instead of one directory of CSV files per session. The
+//! store has four tables:
+//!
+//! - `instances`: name, dimension and capacity of each instance;
+//! - `configs`: each distinct configuration snapshot (JSON), by digest;
+//! - `runs`: one row per run, with its session, algorithm, seed, cost,
+//!   times, memory and bounds; infinite costs are stored as `NULL`;
+//! - `traces`: the convergence trace of each run, one row per point.
+//!
+//! The query helpers cover the usual questions; anything else can be asked
+//! with any SQLite client. Requires the `sqlite` feature.
+
+use crate::benchmark::Benchmark;
+
+use std::path::Path;
+
+/// One stored run, as returned by `ResultStore::runs`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredRun {
+    pub id: i64,
+    /// Start time of the benchmark session that made the run
+    pub session: String,
+    pub instance: String,
+    pub algorithm: String,
+    pub seed: Option<u64>,
+    /// Travel cost, infinite when no solution was found
+    pub cost: f64,
+    pub feasible: bool,
+    pub time: f64,
+    pub cpu_time: Option<f64>,
+    pub peak_rss: Option<f64>,
+    pub gap_to_bound: Option<f64>,
+}
+
+/// Aggregate of the runs of a solver on an instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredSummary {
+    pub instance: String,
+    /// Algorithm name without its "-runN" suffix
+    pub solver: String,
+    pub runs: usize,
+    pub feasible: usize,
+    /// Average and best feasible cost (`None` without feasible run)
+    pub avg_cost: Option<f64>,
+    pub best_cost: Option<f64>,
+    pub avg_time: f64,
+}
+
+#[cfg(feature = "sqlite")]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS instances (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    dimension INTEGER NOT NULL,
+    capacity INTEGER NOT NULL,
+    UNIQUE (name, dimension, capacity)
+);
+CREATE TABLE IF NOT EXISTS configs (
+    id INTEGER PRIMARY KEY,
+    digest TEXT NOT NULL UNIQUE,
+    config TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    session TEXT NOT NULL,
+    git_hash TEXT NOT NULL,
+    instance_id INTEGER NOT NULL REFERENCES instances(id),
+    config_id INTEGER NOT NULL REFERENCES configs(id),
+    algorithm TEXT NOT NULL,
+    solver TEXT NOT NULL,
+    seed INTEGER,
+    cost REAL,
+    profit INTEGER NOT NULL,
+    feasible INTEGER NOT NULL,
+    time REAL NOT NULL,
+    cpu_time REAL,
+    peak_rss REAL,
+    iterations INTEGER,
+    lower_bound REAL,
+    gap_to_best REAL,
+    gap_to_bound REAL
+);
+CREATE INDEX IF NOT EXISTS runs_by_instance ON runs (instance_id, solver);
+CREATE TABLE IF NOT EXISTS traces (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    position INTEGER NOT NULL,
+    time REAL NOT NULL,
+    cost REAL NOT NULL,
+    PRIMARY KEY (run_id, position)
+);
+";
+
+/// Result database of a benchmark campaign
+#[cfg(feature = "sqlite")]
+pub struct ResultStore {
+    conn: rusqlite::Connection,
+}
+
+#[cfg(feature = "sqlite")]
+fn sql_error(e: rusqlite::Error) -> String {
+    format!("SQLite error: {}", e)
+}
+
+#[cfg(feature = "sqlite")]
+impl ResultStore {
+    /// Open the database at `path`, creating it and its tables if needed
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let conn = rusqlite::Connection::open(&path)
+            .map_err(|e| format!("Cannot open {:?}: {}", path.as_ref(), e))?;
+        Self::with_connection(conn)
+    }
+
+    /// Database held in memory, for tests and one-off analyses
+    pub fn in_memory() -> Result<Self, String> {
+        Self::with_connection(rusqlite::Connection::open_in_memory().map_err(sql_error)?)
+    }
+
+    fn with_connection(conn: rusqlite::Connection) -> Result<Self, String> {
+        conn.execute_batch(SCHEMA).map_err(sql_error)?;
+        Ok(ResultStore { conn })
+    }
+
+    /// Store every run of a benchmark session in one transaction; returns
+    /// the number of runs stored
+    pub fn insert_benchmark(&mut self, benchmark: &Benchmark) -> Result<usize, String> {
+        use rusqlite::{params, OptionalExtension};
+
+        let records = benchmark.run_records();
+        let results = benchmark.results();
+        if records.len() != results.len() {
+            return Err("The run records do not match the results".to_string());
+        }
+        let info = benchmark.run_info();
+        let finite = |value: f64| value.is_finite().then_some(value);
+
+        let tx = self.conn.transaction().map_err(sql_error)?;
+        for (record, result) in records.iter().zip(results) {
+            tx.execute(
+                "INSERT OR IGNORE INTO instances (name, dimension, capacity) VALUES (?1, ?2, ?3)",
+                params![record.instance, record.dimension as i64, result.capacity],
+            ).map_err(sql_error)?;
+            let instance_id: i64 = tx.query_row(
+                "SELECT id FROM instances WHERE name = ?1 AND dimension = ?2 AND capacity = ?3",
+                params![record.instance, record.dimension as i64, result.capacity],
+                |row| row.get(0),
+            ).map_err(sql_error)?;
+
+            let config = record.config.to_string();
+            let digest = crate::run_info::config_digest(&config);
+            let config_id: Option<i64> = tx.query_row(
+                "SELECT id FROM configs WHERE digest = ?1", params![digest], |row| row.get(0),
+            ).optional().map_err(sql_error)?;
+            let config_id = match config_id {
+                Some(id) => id,
+                None => {
+                    tx.execute("INSERT INTO configs (digest, config) VALUES (?1, ?2)", params![digest, config])
+                        .map_err(sql_error)?;
+                    tx.last_insert_rowid()
+                }
+            };
+
+            tx.execute(
+                "INSERT INTO runs (session, git_hash, instance_id, config_id, algorithm, solver, seed, cost, profit,
+                    feasible, time, cpu_time, peak_rss, iterations, lower_bound, gap_to_best, gap_to_bound)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+                params![
+                    info.start_time,
+                    info.git_hash,
+                    instance_id,
+                    config_id,
+                    record.algorithm,
+                    crate::benchmark::solver_name(&record.algorithm),
+                    record.seed.map(|seed| seed as i64),
+                    finite(record.cost),
+                    record.profit,
+                    record.feasible,
+                    record.time,
+                    result.cpu_time,
+                    result.peak_rss,
+                    record.iterations.map(|n| n as i64),
+                    result.lower_bound.and_then(finite),
+                    result.gap_to_best.and_then(finite),
+                    result.gap_to_bound.and_then(finite),
+                ],
+            ).map_err(sql_error)?;
+            let run_id = tx.last_insert_rowid();
+
+            let mut insert_point = tx.prepare_cached(
+                "INSERT INTO traces (run_id, position, time, cost) VALUES (?1, ?2, ?3, ?4)",
+            ).map_err(sql_error)?;
+            for (position, &(time, cost)) in record.convergence.iter().enumerate() {
+                insert_point.execute(params![run_id, position as i64, time, cost]).map_err(sql_error)?;
+            }
+        }
+        tx.commit().map_err(sql_error)?;
+        Ok(records.len())
+    }
+
+    /// Number of stored runs
+    pub fn run_count(&self) -> Result<usize, String> {
+        self.conn.query_row("SELECT COUNT(*) FROM runs", [], |row| row.get::<_, i64>(0))
+            .map(|n| n as usize)
+            .map_err(sql_error)
+    }
+
+    /// Stored runs, optionally restricted to an instance and a solver
+    /// (algorithm name without "-runN"), in insertion order
+    pub fn runs(&self, instance: Option<&str>, solver: Option<&str>) -> Result<Vec<StoredRun>, String> {
+        let mut statement = self.conn.prepare(
+            "SELECT r.id, r.session, i.name, r.algorithm, r.seed, r.cost, r.feasible, r.time,
+                    r.cpu_time, r.peak_rss, r.gap_to_bound
+             FROM runs r JOIN instances i ON i.id = r.instance_id
+             WHERE (?1 IS NULL OR i.name = ?1) AND (?2 IS NULL OR r.solver = ?2)
+             ORDER BY r.id",
+        ).map_err(sql_error)?;
+        let rows = statement.query_map(rusqlite::params![instance, solver], |row| {
+            Ok(StoredRun {
+                id: row.get(0)?,
+                session: row.get(1)?,
+                instance: row.get(2)?,
+                algorithm: row.get(3)?,
+                seed: row.get::<_, Option<i64>>(4)?.map(|seed| seed as u64),
+                cost: row.get::<_, Option<f64>>(5)?.unwrap_or(f64::INFINITY),
+                feasible: row.get(6)?,
+                time: row.get(7)?,
+                cpu_time: row.get(8)?,
+                peak_rss: row.get(9)?,
+                gap_to_bound: row.get(10)?,
+            })
+        }).map_err(sql_error)?;
+        rows.collect::<Result<_, _>>().map_err(sql_error)
+    }
+
+    /// Runs, feasible runs, average and best feasible cost and average time
+    /// of every (instance, solver) pair over all sessions
+    pub fn summary(&self) -> Result<Vec<StoredSummary>, String> {
+        let mut statement = self.conn.prepare(
+            "SELECT i.name, r.solver, COUNT(*), SUM(r.feasible),
+                    AVG(CASE WHEN r.feasible THEN r.cost END),
+                    MIN(CASE WHEN r.feasible THEN r.cost END),
+                    AVG(r.time)
+             FROM runs r JOIN instances i ON i.id = r.instance_id
+             GROUP BY i.name, r.solver
+             ORDER BY i.name, r.solver",
+        ).map_err(sql_error)?;
+        let rows = statement.query_map([], |row| {
+            Ok(StoredSummary {
+                instance: row.get(0)?,
+                solver: row.get(1)?,
+                runs: row.get::<_, i64>(2)? as usize,
+                feasible: row.get::<_, i64>(3)? as usize,
+                avg_cost: row.get(4)?,
+                best_cost: row.get(5)?,
+                avg_time: row.get(6)?,
+            })
+        }).map_err(sql_error)?;
+        rows.collect::<Result<_, _>>().map_err(sql_error)
+    }
+
+    /// Best feasible cost of every instance and the algorithm that found it
+    pub fn best_costs(&self) -> Result<Vec<(String, String, f64)>, String> {
+        let mut statement = self.conn.prepare(
+            "SELECT i.name, r.algorithm, MIN(r.cost)
+             FROM runs r JOIN instances i ON i.id = r.instance_id
+             WHERE r.feasible AND r.cost IS NOT NULL
+             GROUP BY i.name
+             ORDER BY i.name",
+        ).map_err(sql_error)?;
+        let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(sql_error)?;
+        rows.collect::<Result<_, _>>().map_err(sql_error)
+    }
+
+    /// Convergence trace of a run as (elapsed seconds, best cost) points
+    pub fn trace(&self, run_id: i64) -> Result<Vec<(f64, f64)>, String> {
+        let mut statement = self.conn.prepare(
+            "SELECT time, cost FROM traces WHERE run_id = ?1 ORDER BY position",
+        ).map_err(sql_error)?;
+        let rows = statement.query_map([run_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(sql_error)?;
+        rows.collect::<Result<_, _>>().map_err(sql_error)
+    }
+}
+
+/// Append the runs of a benchmark session to the store at `path`; returns
+/// the number of runs stored
+#[cfg(feature = "sqlite")]
+pub fn save_benchmark<P: AsRef<Path>>(path: P, benchmark: &Benchmark) -> Result<usize, String> {
+    ResultStore::open(path)?.insert_benchmark(benchmark)
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn save_benchmark<P: AsRef<Path>>(_path: P, _benchmark: &Benchmark) -> Result<usize, String> {
+    Err("Result stores require the `sqlite` feature".to_string())
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::benchmark::BenchmarkConfig;
+    use crate::instance::PDTSPInstance;
+
+    #[test]
+    fn test_store_sessions_and_queries() {
+        let text = "NAME: s\nDIMENSION: 5\nCAPACITY: 10\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 1 1\n4 0 1\n5 2 2\nDEMAND_SECTION\n1 0\n2 2\n3 -2\n4 1\n5 -1\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let mut benchmark = Benchmark::new(BenchmarkConfig {
+            num_runs: 2,
+            time_limit: 0.05,
+            algorithms: vec!["construction".to_string(), "sa".to_string()],
+            ..Default::default()
+        });
+        benchmark.run_full_benchmark(&instance);
+        let runs = benchmark.results().len();
+
+        let mut store = ResultStore::in_memory().unwrap();
+        assert_eq!(store.insert_benchmark(&benchmark).unwrap(), runs);
+        // A second session adds runs, not instances or configs
+        store.insert_benchmark(&benchmark).unwrap();
+        assert_eq!(store.run_count().unwrap(), 2 * runs);
+        let count = |table: &str| store.conn
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0))
+            .unwrap();
+        assert_eq!((count("instances"), count("configs")), (1, 1));
+
+        let sa = store.runs(Some("s"), Some("SA")).unwrap();
+        assert_eq!(sa.len(), 2 * 2);
+        assert_eq!(sa[1].seed, Some(1));
+        assert!(sa.iter().all(|run| run.feasible && run.cost.is_finite()));
+        assert!(!store.trace(sa[0].id).unwrap().is_empty());
+
+        let summary = store.summary().unwrap();
+        let sa_summary = summary.iter().find(|s| s.solver == "SA").unwrap();
+        assert_eq!((sa_summary.runs, sa_summary.feasible), (4, 4));
+        let best = store.best_costs().unwrap();
+        assert_eq!(best.len(), 1);
+        let best_known = benchmark.results().iter().filter(|r| r.feasible).map(|r| r.cost).fold(f64::INFINITY, f64::min);
+        assert_eq!(best[0].2, best_known);
+    }
+}
@@ -0,0 +1,411 @@
+//! Tour representations for reversal-heavy searches.
+//!
+//! `TourRep` abstracts what a 2-opt style search needs from a cyclic tour:
+//! successors and predecessors, betweenness and the reversal of a path.
+//! `ArrayTour` keeps the tour in a vector with a position index, so queries
+//! are O(1) and a reversal is linear in the length of the reversed path.
+//! `TwoLevelList` cuts the tour into about √n segments, each with its own
+//! reversal bit: queries stay O(1) and a reversal costs O(√n), so a descent
+//! on tens of thousands of nodes is no longer dominated by moving array
+//! elements. Its segments also carry load summaries, which check the
+//! capacity of the whole tour in O(√n).
+//!
+//! Both convert back to a `Vec<usize>` starting at the depot.
+
+use crate::instance::PDTSPInstance;
+
+/// Cyclic tour supporting path reversal
+pub trait TourRep {
+    /// Number of nodes in the tour
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Node following `node`
+    fn next(&self, node: usize) -> usize;
+    /// Node preceding `node`
+    fn prev(&self, node: usize) -> usize;
+    /// Whether `b` lies on the path from `a` to `c`, both included
+    fn between(&self, a: usize, b: usize, c: usize) -> bool;
+    /// Reverse the path from `a` to `b`, in the direction of the tour
+    fn reverse(&mut self, a: usize, b: usize);
+    /// The tour as a sequence starting at node 0
+    fn to_vec(&self) -> Vec<usize>;
+}
+
+/// Node-indexed position table of a tour
+fn index_table(tour: &[usize]) -> Vec<usize> {
+    let size = tour.iter().max().map_or(0, |&m| m + 1);
+    let mut table = vec![usize::MAX; size];
+    for (k, &node) in tour.iter().enumerate() {
+        table[node] = k;
+    }
+    table
+}
+
+/// Whether `b` is between `a` and `c` given their keys in tour order
+fn key_between<K: PartialOrd>(a: K, b: K, c: K) -> bool {
+    if a <= c {
+        a <= b && b <= c
+    } else {
+        b >= a || b <= c
+    }
+}
+
+/// Tour stored as an array with the position of each node
+#[derive(Debug, Clone)]
+pub struct ArrayTour {
+    tour: Vec<usize>,
+    position: Vec<usize>,
+}
+
+impl ArrayTour {
+    pub fn new(tour: Vec<usize>) -> Self {
+        let position = index_table(&tour);
+        ArrayTour { tour, position }
+    }
+}
+
+impl TourRep for ArrayTour {
+    fn len(&self) -> usize {
+        self.tour.len()
+    }
+
+    fn next(&self, node: usize) -> usize {
+        self.tour[(self.position[node] + 1) % self.tour.len()]
+    }
+
+    fn prev(&self, node: usize) -> usize {
+        let n = self.tour.len();
+        self.tour[(self.position[node] + n - 1) % n]
+    }
+
+    fn between(&self, a: usize, b: usize, c: usize) -> bool {
+        key_between(self.position[a], self.position[b], self.position[c])
+    }
+
+    fn reverse(&mut self, a: usize, b: usize) {
+        let n = self.tour.len();
+        let (i, j) = (self.position[a], self.position[b]);
+        let length = (j + n - i) % n + 1;
+        for k in 0..length / 2 {
+            let (x, y) = ((i + k) % n, (j + n - k) % n);
+            self.tour.swap(x, y);
+            self.position[self.tour[x]] = x;
+            self.position[self.tour[y]] = y;
+        }
+    }
+
+    fn to_vec(&self) -> Vec<usize> {
+        let start = self.position.first().copied().filter(|&p| p != usize::MAX).unwrap_or(0);
+        self.tour[start..].iter().chain(&self.tour[..start]).copied().collect()
+    }
+}
+
+/// Segment of a `TwoLevelList`, with the extreme partial sums of the node
+/// demands read forwards and backwards
+#[derive(Debug, Clone)]
+struct Segment {
+    nodes: Vec<usize>,
+    reversed: bool,
+    net: i32,
+    forward: (i32, i32),
+    backward: (i32, i32),
+}
+
+impl Segment {
+    fn new(nodes: Vec<usize>, demands: &[i32]) -> Self {
+        let demand = |node: usize| demands.get(node).copied().unwrap_or(0);
+        let extremes = |order: &mut dyn Iterator<Item = &usize>| {
+            let (mut sum, mut low, mut high) = (0, i32::MAX, i32::MIN);
+            for &node in order {
+                sum += demand(node);
+                low = low.min(sum);
+                high = high.max(sum);
+            }
+            (low, high)
+        };
+        let forward = extremes(&mut nodes.iter());
+        let backward = extremes(&mut nodes.iter().rev());
+        let net = nodes.iter().map(|&node| demand(node)).sum();
+        Segment { nodes, reversed: false, net, forward, backward }
+    }
+
+    fn first(&self) -> usize {
+        if self.reversed { self.nodes[self.nodes.len() - 1] } else { self.nodes[0] }
+    }
+
+    /// Lowest and highest partial sums of the demands in tour order
+    fn extremes(&self) -> (i32, i32) {
+        if self.reversed { self.backward } else { self.forward }
+    }
+}
+
+/// Two-level doubly-linked list: the tour is a sequence of segments, each
+/// read forwards or backwards, so that reversing a path only splits two
+/// segments and reverses the order of those in between
+#[derive(Debug, Clone)]
+pub struct TwoLevelList {
+    segments: Vec<Segment>,
+    /// Segment ids in tour order
+    order: Vec<usize>,
+    /// Position of each segment in `order`
+    rank: Vec<usize>,
+    segment_of: Vec<usize>,
+    index_of: Vec<usize>,
+    /// Node demands (empty without load summaries)
+    demands: Vec<i32>,
+    /// Segment size of a rebuild
+    target: usize,
+    len: usize,
+}
+
+impl TwoLevelList {
+    pub fn new(tour: &[usize]) -> Self {
+        Self::with_demands(tour, Vec::new())
+    }
+
+    /// List whose segments summarize the demands of the instance (the depot
+    /// counting as zero), for `load_feasible`
+    pub fn from_instance(instance: &PDTSPInstance, tour: &[usize]) -> Self {
+        let mut demands: Vec<i32> = instance.nodes.iter().map(|node| node.demand).collect();
+        if let Some(depot) = demands.first_mut() {
+            *depot = 0;
+        }
+        Self::with_demands(tour, demands)
+    }
+
+    fn with_demands(tour: &[usize], demands: Vec<i32>) -> Self {
+        let mut list = TwoLevelList {
+            segments: Vec::new(),
+            order: Vec::new(),
+            rank: Vec::new(),
+            segment_of: Vec::new(),
+            index_of: Vec::new(),
+            demands,
+            target: 1,
+            len: 0,
+        };
+        list.rebuild(tour);
+        list
+    }
+
+    /// Cut `tour` into segments of about √n nodes
+    fn rebuild(&mut self, tour: &[usize]) {
+        self.len = tour.len();
+        self.target = ((tour.len() as f64).sqrt().ceil() as usize).max(1);
+        self.segments = tour.chunks(self.target).map(|chunk| Segment::new(chunk.to_vec(), &self.demands)).collect();
+        self.order = (0..self.segments.len()).collect();
+        self.rank = self.order.clone();
+        let size = tour.iter().max().map_or(0, |&m| m + 1);
+        self.segment_of = vec![usize::MAX; size];
+        self.index_of = vec![usize::MAX; size];
+        for id in 0..self.segments.len() {
+            self.index_segment(id);
+        }
+    }
+
+    fn index_segment(&mut self, id: usize) {
+        for (i, &node) in self.segments[id].nodes.iter().enumerate() {
+            self.segment_of[node] = id;
+            self.index_of[node] = i;
+        }
+    }
+
+    fn update_ranks(&mut self, from: usize, to: usize) {
+        for rank in from..to {
+            self.rank[self.order[rank]] = rank;
+        }
+    }
+
+    /// Offset of `node` from the start of its segment, in tour order
+    fn offset(&self, node: usize) -> usize {
+        let segment = &self.segments[self.segment_of[node]];
+        let i = self.index_of[node];
+        if segment.reversed { segment.nodes.len() - 1 - i } else { i }
+    }
+
+    /// Split the segment of `node` so that `node` starts a segment
+    fn split_before(&mut self, node: usize) {
+        let id = self.segment_of[node];
+        let k = self.offset(node);
+        if k == 0 {
+            return;
+        }
+        let segment = &mut self.segments[id];
+        let mut nodes = std::mem::take(&mut segment.nodes);
+        if segment.reversed {
+            nodes.reverse();
+        }
+        let tail = nodes.split_off(k);
+        self.segments[id] = Segment::new(nodes, &self.demands);
+        let new_id = self.segments.len();
+        self.segments.push(Segment::new(tail, &self.demands));
+        self.rank.push(0);
+        let rank = self.rank[id];
+        self.order.insert(rank + 1, new_id);
+        self.update_ranks(rank + 1, self.order.len());
+        self.index_segment(id);
+        self.index_segment(new_id);
+    }
+
+    /// Whether the load stays within `[0, capacity]` after each node, the
+    /// vehicle leaving the depot with its starting load (single-commodity
+    /// model without intermediate depot visits)
+    pub fn load_feasible(&mut self, instance: &PDTSPInstance) -> bool {
+        if self.is_empty() || self.segment_of.first().is_none_or(|&id| id == usize::MAX) {
+            return false;
+        }
+        self.split_before(0);
+        let start = self.rank[self.segment_of[0]];
+        let mut load = instance.starting_load();
+        for k in 0..self.order.len() {
+            let segment = &self.segments[self.order[(start + k) % self.order.len()]];
+            let (low, high) = segment.extremes();
+            if load + low < 0 || load + high > instance.capacity {
+                return false;
+            }
+            load += segment.net;
+        }
+        true
+    }
+}
+
+impl TourRep for TwoLevelList {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn next(&self, node: usize) -> usize {
+        let id = self.segment_of[node];
+        let segment = &self.segments[id];
+        let i = self.index_of[node];
+        if !segment.reversed && i + 1 < segment.nodes.len() {
+            return segment.nodes[i + 1];
+        }
+        if segment.reversed && i > 0 {
+            return segment.nodes[i - 1];
+        }
+        self.segments[self.order[(self.rank[id] + 1) % self.order.len()]].first()
+    }
+
+    fn prev(&self, node: usize) -> usize {
+        let id = self.segment_of[node];
+        let segment = &self.segments[id];
+        let i = self.index_of[node];
+        if !segment.reversed && i > 0 {
+            return segment.nodes[i - 1];
+        }
+        if segment.reversed && i + 1 < segment.nodes.len() {
+            return segment.nodes[i + 1];
+        }
+        let previous = &self.segments[self.order[(self.rank[id] + self.order.len() - 1) % self.order.len()]];
+        if previous.reversed { previous.nodes[0] } else { previous.nodes[previous.nodes.len() - 1] }
+    }
+
+    fn between(&self, a: usize, b: usize, c: usize) -> bool {
+        let key = |node: usize| (self.rank[self.segment_of[node]], self.offset(node));
+        key_between(key(a), key(b), key(c))
+    }
+
+    fn reverse(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        let after = self.next(b);
+        let (from, to) = if after == a {
+            // The path is the whole tour: reverse every segment
+            (0, self.order.len() - 1)
+        } else {
+            self.split_before(a);
+            self.split_before(after);
+            let (from, to) = (self.rank[self.segment_of[a]], self.rank[self.segment_of[b]]);
+            if from <= to {
+                (from, to)
+            } else {
+                // The path wraps around the end of the order
+                self.order.rotate_left(from);
+                self.update_ranks(0, self.order.len());
+                (0, to + self.order.len() - from)
+            }
+        };
+        self.order[from..=to].reverse();
+        for rank in from..=to {
+            let id = self.order[rank];
+            self.segments[id].reversed = !self.segments[id].reversed;
+            self.rank[id] = rank;
+        }
+
+        // Splits multiply the segments: start over from balanced ones
+        if self.order.len() > 4 * self.target {
+            let tour = self.to_vec();
+            self.rebuild(&tour);
+        }
+    }
+
+    fn to_vec(&self) -> Vec<usize> {
+        let mut tour = Vec::with_capacity(self.len);
+        let Some(&id) = self.segment_of.first().filter(|&&id| id != usize::MAX) else {
+            return tour;
+        };
+        let (start, offset) = (self.rank[id], self.offset(0));
+        for k in 0..=self.order.len() {
+            let segment = &self.segments[self.order[(start + k) % self.order.len()]];
+            let mut nodes: Vec<usize> = segment.nodes.clone();
+            if segment.reversed {
+                nodes.reverse();
+            }
+            let range = match k {
+                0 => offset..nodes.len(),
+                _ if k == self.order.len() => 0..offset,
+                _ => 0..nodes.len(),
+            };
+            tour.extend_from_slice(&nodes[range]);
+        }
+        tour
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_two_level_list_matches_array() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let tour: Vec<usize> = (0..97).collect();
+        let mut array = ArrayTour::new(tour.clone());
+        let mut list = TwoLevelList::new(&tour);
+
+        for _ in 0..500 {
+            let a = rng.gen_range(0..97);
+            let b = rng.gen_range(0..97);
+            array.reverse(a, b);
+            list.reverse(a, b);
+            assert_eq!(list.to_vec(), array.to_vec());
+
+            let (x, y, z) = (rng.gen_range(0..97), rng.gen_range(0..97), rng.gen_range(0..97));
+            assert_eq!(list.next(x), array.next(x));
+            assert_eq!(list.prev(x), array.prev(x));
+            assert_eq!(list.between(x, y, z), array.between(x, y, z));
+        }
+        assert_eq!(list.to_vec()[0], 0);
+        assert_eq!(list.len(), 97);
+    }
+
+    #[test]
+    fn test_load_feasible_matches_instance() {
+        let text = "NAME: loads\nDIMENSION: 9\nCAPACITY: 4\nNODE_COORD_SECTION\n1 0 0\n2 1 0\n3 2 0\n4 3 0\n5 4 0\n6 5 0\n7 6 0\n8 7 0\n9 8 0\nDEMAND_SECTION\n1 0\n2 3\n3 -3\n4 3\n5 -3\n6 2\n7 2\n8 -2\n9 -2\nEOF\n";
+        let instance = PDTSPInstance::from_tsplib_str(text).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let tour: Vec<usize> = (0..9).collect();
+        let mut list = TwoLevelList::from_instance(&instance, &tour);
+        for _ in 0..200 {
+            list.reverse(rng.gen_range(0..9), rng.gen_range(0..9));
+            let sequence = list.to_vec();
+            assert_eq!(list.load_feasible(&instance), instance.is_feasible(&sequence), "{:?}", sequence);
+        }
+    }
+}
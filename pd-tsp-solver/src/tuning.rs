@@ -0,0 +1,493 @@
+//! Automatic parameter tuning.
+//!
+//! A `TuningSpec` (TOML) declares the algorithm to tune, its training
+//! instances, a per-run time budget and a parameter space. `Tuner::run`
+//! samples configurations from the space and races them on the instances,
+//! either exhaustively (`random`) or by successive halving, where every
+//! round evaluates the surviving configurations on twice as many instances
+//! and keeps the better half. The default configuration always takes part in
+//! the final round, so the result is never worse than the defaults on the
+//! training set.
+//!
+//! ```toml
+//! algorithm = "sa"
+//! instances = ["../benchmark_n20/*.tsp"]
+//! budget = 5.0
+//! method = "successive-halving"
+//! samples = 16
+//!
+//! [parameters]
+//! cooling_rate = { min = 0.9, max = 0.999 }
+//! iterations_per_temp = { min = 20, max = 500, log = true, integer = true }
+//! auto_temperature = { values = [true, false] }
+//! ```
+//!
+//! Configurations are scored by their mean cost ratio to the best cost any
+//! configuration reached on each instance. The winner is written as a
+//! `SolverConfig` file that `solve --config` loads.
+
+use crate::config::SolverConfig;
+use crate::experiment::{parse_cost_function, run_algorithm_with_config};
+use crate::instance::PDTSPInstance;
+
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Algorithms that can be tuned, with the `SolverConfig` section of their parameters
+pub const TUNABLE: &[(&str, &str)] = &[
+    ("sa", "sa"),
+    ("ils", "ils"),
+    ("ga", "ga"),
+    ("memetic", "ga"),
+    ("aco", "aco"),
+    ("mmas", "aco"),
+    ("rank-as", "aco"),
+    ("elitist-as", "aco"),
+];
+
+/// Search strategy over the sampled configurations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TuningMethod {
+    /// Evaluate every configuration on every instance
+    Random,
+    /// Race the configurations, halving them while doubling the instances
+    SuccessiveHalving,
+}
+
+/// Range or set of values of one parameter
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParameterRange {
+    /// Lower bound of a numeric range
+    pub min: Option<f64>,
+    /// Upper bound of a numeric range
+    pub max: Option<f64>,
+    /// Sample the range uniformly on a log scale
+    #[serde(default)]
+    pub log: bool,
+    /// Round sampled values to integers
+    #[serde(default)]
+    pub integer: bool,
+    /// Explicit values to choose from, instead of a range
+    #[serde(default)]
+    pub values: Vec<toml::Value>,
+}
+
+impl ParameterRange {
+    fn validate(&self, name: &str) -> Result<(), String> {
+        if !self.values.is_empty() {
+            return Ok(());
+        }
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min <= max => {
+                if self.log && min <= 0.0 {
+                    return Err(format!("Parameter '{}': a log range needs min > 0", name));
+                }
+                Ok(())
+            }
+            (Some(_), Some(_)) => Err(format!("Parameter '{}': min is above max", name)),
+            _ => Err(format!("Parameter '{}' needs either values or min and max", name)),
+        }
+    }
+
+    /// Draw one value
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> toml::Value {
+        if !self.values.is_empty() {
+            return self.values[rng.gen_range(0..self.values.len())].clone();
+        }
+        let (min, max) = (self.min.unwrap_or(0.0), self.max.unwrap_or(0.0));
+        let x = if min == max {
+            min
+        } else if self.log {
+            rng.gen_range(min.ln()..=max.ln()).exp()
+        } else {
+            rng.gen_range(min..=max)
+        };
+        if self.integer {
+            toml::Value::Integer(x.round().clamp(min.ceil(), max.floor()) as i64)
+        } else {
+            toml::Value::Float(x)
+        }
+    }
+}
+
+fn default_budget() -> f64 {
+    5.0
+}
+
+fn default_method() -> TuningMethod {
+    TuningMethod::SuccessiveHalving
+}
+
+fn default_samples() -> usize {
+    16
+}
+
+fn default_cost_function() -> String {
+    "distance".to_string()
+}
+
+fn default_alpha() -> f64 {
+    0.1
+}
+
+/// Declarative description of a tuning run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TuningSpec {
+    /// Algorithm to tune (see `TUNABLE`)
+    pub algorithm: String,
+    /// Training instance files or glob patterns
+    #[serde(default)]
+    pub instances: Vec<String>,
+    /// Time budget per run in seconds
+    #[serde(default = "default_budget")]
+    pub budget: f64,
+    /// Search strategy
+    #[serde(default = "default_method")]
+    pub method: TuningMethod,
+    /// Number of sampled configurations, besides the default one
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+    /// Seed of the sampling and of the runs
+    #[serde(default)]
+    pub seed: u64,
+    /// Cost function: "distance", "quadratic" or "linear-load"
+    #[serde(default = "default_cost_function")]
+    pub cost_function: String,
+    /// Linear load weight
+    #[serde(default = "default_alpha")]
+    pub alpha: f64,
+    /// Quadratic load weight
+    #[serde(default)]
+    pub beta: f64,
+    /// Parameter space, keyed by the parameter names of the algorithm's section
+    pub parameters: BTreeMap<String, ParameterRange>,
+    /// Directory of the spec file, against which relative paths are resolved
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+impl TuningSpec {
+    /// Parse and validate a spec from TOML text
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let spec: TuningSpec = toml::from_str(text)
+            .map_err(|e| format!("Invalid tuning spec: {}", e))?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Load a spec; relative paths in it are resolved against its directory
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {:?}: {}", path.as_ref(), e))?;
+        let mut spec = Self::from_toml(&text)?;
+        spec.base_dir = path.as_ref().parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(spec)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.section()?;
+        parse_cost_function(&self.cost_function)?;
+        if self.budget.is_nan() || self.budget <= 0.0 {
+            return Err(format!("Budget must be positive, got {}", self.budget));
+        }
+        if self.parameters.is_empty() {
+            return Err("Spec needs at least one parameter".to_string());
+        }
+        for (name, range) in &self.parameters {
+            range.validate(name)?;
+        }
+        // Catch misspelled parameters and mistyped values before any run
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        for _ in 0..4 {
+            self.to_config(&self.sample(&mut rng))?;
+        }
+        Ok(())
+    }
+
+    /// `SolverConfig` section holding the parameters of the algorithm
+    fn section(&self) -> Result<&'static str, String> {
+        TUNABLE.iter()
+            .find(|(name, _)| *name == self.algorithm)
+            .map(|(_, section)| *section)
+            .ok_or_else(|| {
+                let names: Vec<&str> = TUNABLE.iter().map(|(name, _)| *name).collect();
+                format!("Algorithm '{}' cannot be tuned (expected one of {})", self.algorithm, names.join(", "))
+            })
+    }
+
+    /// Draw one value per parameter
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> toml::Table {
+        self.parameters.iter()
+            .map(|(name, range)| (name.clone(), range.sample(rng)))
+            .collect()
+    }
+
+    /// Solver configuration setting the given parameter values
+    pub fn to_config(&self, values: &toml::Table) -> Result<SolverConfig, String> {
+        let mut root = toml::Table::new();
+        root.insert(self.section()?.to_string(), toml::Value::Table(values.clone()));
+        toml::Value::Table(root).try_into()
+            .map_err(|e| format!("Invalid parameters for {}: {}", self.algorithm, e))
+    }
+
+    /// Expand the instance patterns into a sorted list of files
+    pub fn instance_paths(&self) -> Result<Vec<PathBuf>, String> {
+        let mut paths = Vec::new();
+        for pattern in &self.instances {
+            let full = if Path::new(pattern).is_absolute() {
+                PathBuf::from(pattern)
+            } else {
+                self.base_dir.join(pattern)
+            };
+            let matches: Vec<PathBuf> = glob::glob(&full.to_string_lossy())
+                .map_err(|e| format!("Invalid instance pattern '{}': {}", pattern, e))?
+                .filter_map(Result::ok)
+                .filter(|p| p.is_file())
+                .collect();
+            if matches.is_empty() {
+                return Err(format!("Instance pattern '{}' matches no file", pattern));
+            }
+            paths.extend(matches);
+        }
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+}
+
+/// A sampled configuration and its results
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// Parameter values (empty for the default configuration)
+    pub values: toml::Table,
+    /// Cost on each training instance, None if not evaluated
+    pub costs: Vec<Option<f64>>,
+}
+
+/// Outcome of a tuning run
+#[derive(Debug, Clone)]
+pub struct TuningResult {
+    /// Best configuration found
+    pub config: SolverConfig,
+    /// Its parameter values
+    pub values: toml::Table,
+    /// Its mean cost ratio to the best known cost per instance (1.0 is best everywhere)
+    pub score: f64,
+    /// Score of the default configuration
+    pub default_score: f64,
+    /// Number of algorithm runs
+    pub runs: usize,
+}
+
+/// Races sampled configurations of an algorithm on training instances
+pub struct Tuner {
+    spec: TuningSpec,
+    instances: Vec<PDTSPInstance>,
+    candidates: Vec<Candidate>,
+    runs: usize,
+}
+
+impl Tuner {
+    /// Create a tuner on the given training instances; the cost function of
+    /// the spec is applied to them
+    pub fn new(spec: TuningSpec, instances: Vec<PDTSPInstance>) -> Result<Self, String> {
+        if instances.is_empty() {
+            return Err("Tuning needs at least one training instance".to_string());
+        }
+        let cost_function = parse_cost_function(&spec.cost_function)?;
+        let mut instances = instances;
+        for instance in &mut instances {
+            instance.cost_function = cost_function;
+            instance.alpha = spec.alpha;
+            instance.beta = spec.beta;
+        }
+        // A random instance order keeps the first rounds of successive
+        // halving from always seeing the same (smallest) instances
+        let mut rng = ChaCha8Rng::seed_from_u64(spec.seed);
+        instances.shuffle(&mut rng);
+
+        let mut candidates = vec![Candidate { values: toml::Table::new(), costs: Vec::new() }];
+        for _ in 0..spec.samples {
+            candidates.push(Candidate { values: spec.sample(&mut rng), costs: Vec::new() });
+        }
+        for candidate in &mut candidates {
+            candidate.costs = vec![None; instances.len()];
+        }
+
+        Ok(Tuner { spec, instances, candidates, runs: 0 })
+    }
+
+    /// Load the training instances of the spec, or of `dir` if given
+    pub fn from_spec(spec: TuningSpec, dir: Option<&Path>) -> Result<Self, String> {
+        let instances = match dir {
+            Some(dir) => crate::benchmark::load_instances_from_dir(dir),
+            None => spec.instance_paths()?
+                .iter()
+                .map(|path| {
+                    let mut instance = PDTSPInstance::from_file(path)?;
+                    if let Some(stem) = path.file_stem() {
+                        instance.name = stem.to_string_lossy().to_string();
+                    }
+                    Ok(instance)
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+        };
+        Self::new(spec, instances)
+    }
+
+    /// Evaluated configurations, the default one first
+    pub fn candidates(&self) -> &[Candidate] {
+        &self.candidates
+    }
+
+    /// Run the tuning and return the best configuration
+    pub fn run(&mut self) -> Result<TuningResult, String> {
+        let n = self.instances.len();
+        let mut alive: Vec<usize> = (0..self.candidates.len()).collect();
+
+        if self.spec.method == TuningMethod::SuccessiveHalving {
+            let mut used = 1;
+            while alive.len() > 1 && used < n {
+                self.evaluate(&alive, used);
+                alive = self.ranked(&alive, used);
+                alive.truncate(alive.len().div_ceil(2));
+                used = (2 * used).min(n);
+            }
+        }
+        // The default configuration is the reference of the final round
+        if !alive.contains(&0) {
+            alive.push(0);
+        }
+        self.evaluate(&alive, n);
+
+        let best = self.ranked(&alive, n)[0];
+        let score = self.score(best, n);
+        let default_score = self.score(0, n);
+        let values = self.candidates[best].values.clone();
+        Ok(TuningResult {
+            config: self.spec.to_config(&values)?,
+            values,
+            score,
+            default_score,
+            runs: self.runs,
+        })
+    }
+
+    /// Run the candidates on the first `count` instances they have not seen
+    fn evaluate(&mut self, candidates: &[usize], count: usize) {
+        for &c in candidates {
+            for i in 0..count {
+                if self.candidates[c].costs[i].is_some() {
+                    continue;
+                }
+                let config = match self.spec.to_config(&self.candidates[c].values) {
+                    Ok(config) => config,
+                    Err(_) => {
+                        self.candidates[c].costs[i] = Some(f64::INFINITY);
+                        continue;
+                    }
+                };
+                let instance = &self.instances[i];
+                let seed = self.spec.seed.wrapping_add(i as u64);
+                let cost = match run_algorithm_with_config(&self.spec.algorithm, instance, seed, self.spec.budget, &config) {
+                    Ok(sol) if sol.feasible => sol.cost,
+                    _ => f64::INFINITY,
+                };
+                self.runs += 1;
+                self.candidates[c].costs[i] = Some(cost);
+                tracing::info!(config = c, instance = %instance.name, cost, "Tuning run");
+            }
+        }
+    }
+
+    /// Candidates sorted by score on the first `count` instances
+    fn ranked(&self, candidates: &[usize], count: usize) -> Vec<usize> {
+        let mut sorted = candidates.to_vec();
+        sorted.sort_by(|&a, &b| self.score(a, count).total_cmp(&self.score(b, count)));
+        sorted
+    }
+
+    /// Mean ratio of a candidate's costs to the best cost reached on each of
+    /// the first `count` instances; instances nobody solved are skipped
+    fn score(&self, candidate: usize, count: usize) -> f64 {
+        let mut total = 0.0;
+        let mut solved = 0;
+        for i in 0..count {
+            let best = self.candidates.iter()
+                .filter_map(|c| c.costs[i])
+                .fold(f64::INFINITY, f64::min);
+            if !best.is_finite() {
+                continue;
+            }
+            let cost = self.candidates[candidate].costs[i].unwrap_or(f64::INFINITY);
+            total += if best > 0.0 { cost / best } else if cost <= best { 1.0 } else { f64::INFINITY };
+            solved += 1;
+        }
+        if solved == 0 { f64::INFINITY } else { total / solved as f64 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::{DistanceMatrix, DistanceMode, Node};
+
+    #[test]
+    fn test_tuning_spec_and_race() {
+        let spec = TuningSpec::from_toml(r#"
+            algorithm = "aco"
+            budget = 1.0
+            samples = 3
+            seed = 5
+
+            [parameters]
+            num_ants = { min = 2, max = 8, integer = true }
+            alpha = { min = 0.5, max = 2.0, log = true }
+            use_local_search = { values = [false] }
+            max_iterations = { values = [10] }
+        "#).unwrap();
+
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for _ in 0..20 {
+            let values = spec.sample(&mut rng);
+            let ants = values["num_ants"].as_integer().unwrap();
+            let alpha = values["alpha"].as_float().unwrap();
+            assert!((2..=8).contains(&ants));
+            assert!((0.5..=2.0).contains(&alpha));
+            let config = spec.to_config(&values).unwrap();
+            assert_eq!(config.aco.unwrap().num_ants, Some(ants as usize));
+        }
+
+        // Unknown parameters and untunable algorithms are rejected up front
+        assert!(TuningSpec::from_toml("algorithm = \"aco\"\n[parameters]\nants = { values = [3] }").is_err());
+        assert!(TuningSpec::from_toml("algorithm = \"tabu\"\n[parameters]\nx = { values = [3] }").is_err());
+
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 2.0, 0.0, 2, 0),
+            Node::new(2, 2.0, 2.0, -1, 0),
+            Node::new(3, 0.0, 3.0, 1, 0),
+            Node::new(4, -2.0, 1.0, -2, 0),
+        ];
+        let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        let instance = PDTSPInstance {
+            beta: 0.0,
+            ..PDTSPInstance::from_parts("tune", 3, nodes, distance_matrix)
+        };
+
+        let mut tuner = Tuner::new(spec, vec![instance.clone(), instance]).unwrap();
+        let result = tuner.run().unwrap();
+        assert!(result.score.is_finite() && result.score >= 1.0);
+        assert!(result.score <= result.default_score + 1e-9);
+        // Successive halving keeps 2 of the 4 configurations after one
+        // instance, plus the default one if it was dropped
+        assert!(result.runs == 6 || result.runs == 7);
+        assert_eq!(tuner.candidates().len(), 4);
+    }
+}
@@ -0,0 +1,93 @@
+//! Vehicle profiles for heterogeneous cost evaluation.
+//!
+//! A `VehicleProfile` describes the economics of one vehicle type. When attached
+//! to an instance it overrides the capacity (if given) and turns the base travel
+//! cost of the selected cost function into a monetary cost, so that the same
+//! tour can be compared across vehicle types.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Economic and physical characteristics of a vehicle type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VehicleProfile {
+    /// Name of the vehicle type
+    #[serde(default)]
+    pub name: String,
+    /// Vehicle capacity (overrides the instance capacity when set)
+    #[serde(default)]
+    pub capacity: Option<i32>,
+    /// Fixed cost charged once per tour
+    #[serde(default)]
+    pub fixed_cost: f64,
+    /// Cost per unit of base travel cost (distance plus load surcharge)
+    #[serde(default = "default_cost_per_distance")]
+    pub cost_per_distance: f64,
+    /// Cost per unit of load carried over one unit of distance
+    #[serde(default)]
+    pub cost_per_load_unit: f64,
+    /// Emissions per unit of distance (e.g. kg CO2 per km)
+    #[serde(default)]
+    pub emission_factor: f64,
+    /// Additional emissions per unit of load carried over one unit of distance
+    #[serde(default)]
+    pub load_emission_factor: f64,
+}
+
+fn default_cost_per_distance() -> f64 {
+    1.0
+}
+
+impl Default for VehicleProfile {
+    fn default() -> Self {
+        VehicleProfile {
+            name: "default".to_string(),
+            capacity: None,
+            fixed_cost: 0.0,
+            cost_per_distance: 1.0,
+            cost_per_load_unit: 0.0,
+            emission_factor: 0.0,
+            load_emission_factor: 0.0,
+        }
+    }
+}
+
+impl VehicleProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a profile from a JSON file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Cannot open vehicle profile: {}", e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid vehicle profile: {}", e))
+    }
+
+    /// Total cost of a tour with the given base travel cost and
+    /// load-distance product (sum of load × arc length)
+    #[inline]
+    pub fn tour_cost(&self, base_cost: f64, load_distance: f64) -> f64 {
+        self.fixed_cost + self.cost_per_distance * base_cost + self.cost_per_load_unit * load_distance
+    }
+
+    /// Emissions of a tour with the given length and load-distance product
+    #[inline]
+    pub fn emissions(&self, distance: f64, load_distance: f64) -> f64 {
+        self.emission_factor * distance + self.load_emission_factor * load_distance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_defaults_from_json() {
+        let profile: VehicleProfile = serde_json::from_str(r#"{"name": "van", "fixed_cost": 50.0}"#).unwrap();
+        assert_eq!(profile.cost_per_distance, 1.0);
+        assert_eq!(profile.capacity, None);
+        assert_eq!(profile.tour_cost(100.0, 0.0), 150.0);
+    }
+}
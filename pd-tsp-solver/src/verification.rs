@@ -0,0 +1,201 @@
+//! Independent verification of solutions.
+//!
+//! `reference_evaluate` re-simulates a tour from scratch: it walks every arc
+//! of the closed tour, keeps the carried load (per commodity) explicitly and
+//! accumulates the cost arc by arc, with no caching and no shared code with
+//! the incremental evaluations used by the solvers. `verify` compares a
+//! solution's reported values against it, to catch delta-accumulation and
+//! feasibility bugs.
+
+use crate::instance::{CostFunction, PDTSPInstance};
+use crate::solution::Solution;
+
+/// Tolerance on the cost comparison, relative to the reference cost
+const COST_TOLERANCE: f64 = 1e-6;
+
+/// Values obtained by re-simulating a tour
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceEvaluation {
+    /// Travel cost under the instance's cost function and vehicle profile
+    pub cost: f64,
+    /// Whether every load stays within capacity, every arc exists and every
+    /// route respects the length limit
+    pub feasible: bool,
+    /// Whether the tour starts at the depot and visits every node exactly once
+    pub complete: bool,
+    /// Total profit collected
+    pub profit: i32,
+}
+
+/// Re-simulate a tour and compute its cost, feasibility and profit
+pub fn reference_evaluate(instance: &PDTSPInstance, tour: &[usize]) -> ReferenceEvaluation {
+    let mut visits = vec![0usize; instance.dimension];
+    let mut in_range = true;
+    for &node in tour {
+        if node < instance.dimension {
+            visits[node] += 1;
+        } else {
+            in_range = false;
+        }
+    }
+    let complete = in_range
+        && tour.first() == Some(&0)
+        && visits.iter().all(|&v| v == 1);
+
+    if !in_range || tour.is_empty() {
+        return ReferenceEvaluation { cost: f64::INFINITY, feasible: false, complete, profit: 0 };
+    }
+
+    // Aggregate load, plus per-commodity loads when the instance has several
+    let mut commodity_loads: Vec<i32> = match &instance.commodities {
+        Some(c) => c.demands[0].iter().map(|&d| d.max(0)).collect(),
+        None => Vec::new(),
+    };
+    let mut load = match &instance.commodities {
+        Some(_) => commodity_loads.iter().sum(),
+        None => instance.nodes[0].demand.max(0),
+    };
+
+    let mut feasible = tour[0] == 0;
+    let mut travel = 0.0;
+    let mut load_distance = 0.0;
+    let mut route = 0.0;
+    let mut profit = 0;
+
+    // Walk the closed tour: arc k goes from tour[k] to tour[k + 1], the last
+    // arc returns to tour[0] (open tours stop at their last node)
+    for k in 0..tour.len() {
+        if instance.open_tour && k + 1 == tour.len() {
+            if instance.max_route_length.is_some_and(|limit| route > limit + 1e-9) {
+                feasible = false;
+            }
+            break;
+        }
+        let from = tour[k];
+        let to = if k + 1 < tour.len() { tour[k + 1] } else { tour[0] };
+        let dist = instance.distance(from, to);
+        let carried = load as f64;
+        if !dist.is_finite() {
+            feasible = false;
+        }
+
+        travel += dist + match instance.cost_function {
+            CostFunction::Distance => 0.0,
+            CostFunction::Quadratic => instance.alpha * carried + instance.beta * carried * carried,
+            CostFunction::LinearLoad => instance.alpha * carried.abs(),
+            CostFunction::Duration => instance.nodes[to].service_time,
+        };
+        load_distance += carried.abs() * dist;
+        route += dist;
+        if to == 0 {
+            if instance.max_route_length.is_some_and(|limit| route > limit + 1e-9) {
+                feasible = false;
+            }
+            route = 0.0;
+        }
+
+        if k + 1 == tour.len() {
+            break;
+        }
+
+        // Service the next node; a depot visit unloads everything
+        if to == 0 {
+            load = 0;
+            commodity_loads.iter_mut().for_each(|l| *l = 0);
+        } else {
+            load += instance.nodes[to].demand;
+            profit += instance.nodes[to].profit;
+            if let Some(c) = &instance.commodities {
+                for (l, d) in commodity_loads.iter_mut().zip(&c.demands[to]) {
+                    *l += d;
+                }
+            }
+        }
+        if load < 0 || load > instance.capacity {
+            feasible = false;
+        }
+        if let Some(c) = &instance.commodities {
+            if commodity_loads.iter().zip(&c.capacities).any(|(l, cap)| *l < 0 || l > cap) {
+                feasible = false;
+            }
+        }
+    }
+
+    let cost = match &instance.vehicle {
+        None => travel,
+        Some(v) => v.fixed_cost + v.cost_per_distance * travel + v.cost_per_load_unit * load_distance,
+    };
+
+    ReferenceEvaluation { cost, feasible, complete, profit }
+}
+
+/// Cross-check a solution against the reference evaluator.
+/// Returns the list of discrepancies (empty if the solution checks out).
+pub fn verify(instance: &PDTSPInstance, solution: &Solution) -> Vec<String> {
+    let reference = reference_evaluate(instance, &solution.tour);
+    let mut issues = Vec::new();
+
+    if !reference.complete {
+        issues.push(format!(
+            "tour is not a permutation starting at the depot ({} of {} nodes)",
+            solution.tour.len(), instance.dimension
+        ));
+    }
+    let tolerance = COST_TOLERANCE * reference.cost.abs().max(1.0);
+    let cost_matches = solution.cost == reference.cost || (solution.cost - reference.cost).abs() <= tolerance;
+    if !cost_matches {
+        issues.push(format!("reported cost {:.6} differs from reference {:.6}", solution.cost, reference.cost));
+    }
+    let reference_feasible = reference.feasible && reference.complete;
+    if solution.feasible != reference_feasible {
+        issues.push(format!(
+            "reported feasible = {} but reference says {}",
+            solution.feasible, reference_feasible
+        ));
+    }
+    if solution.total_profit != reference.profit {
+        issues.push(format!("reported profit {} differs from reference {}", solution.total_profit, reference.profit));
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::{DistanceMatrix, DistanceMode, Node};
+
+    #[test]
+    fn test_reference_agrees_with_instance() {
+        let nodes = vec![
+            Node::new(0, 0.0, 0.0, 0, 0),
+            Node::new(1, 3.0, 0.0, 3, 10),
+            Node::new(2, 3.0, 4.0, -2, 20),
+            Node::new(3, 0.0, 4.0, -1, 30),
+        ];
+        let distance_matrix = DistanceMatrix::from_nodes(&nodes, DistanceMode::Dense);
+        let mut instance = PDTSPInstance {
+            cost_function: CostFunction::Quadratic,
+            alpha: 0.3,
+            beta: 0.2,
+            ..PDTSPInstance::from_parts("verify", 3, nodes, distance_matrix)
+        };
+
+        let solution = Solution::from_tour(&instance, vec![0, 1, 2, 3], "test");
+        assert!(verify(&instance, &solution).is_empty());
+
+        // Infeasible order: delivering before picking up
+        let solution = Solution::from_tour(&instance, vec![0, 2, 1, 3], "test");
+        assert!(!solution.feasible);
+        assert!(verify(&instance, &solution).is_empty());
+
+        // A drifted cost and a partial tour are both reported
+        let mut drifted = Solution::from_tour(&instance, vec![0, 1, 2], "test");
+        drifted.cost -= 1.0;
+        assert_eq!(verify(&instance, &drifted).len(), 3);
+
+        instance.cost_function = CostFunction::LinearLoad;
+        let solution = Solution::from_tour(&instance, vec![0, 1, 3, 2], "test");
+        assert!(verify(&instance, &solution).is_empty());
+    }
+}
@@ -2,11 +2,14 @@
 //! 
 //! Generates SVG visualizations of tours and exports for plotting.
 
+use crate::heuristics::pareto::ParetoFront;
 use crate::instance::PDTSPInstance;
 use crate::solution::Solution;
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
+#[cfg(all(not(feature = "resvg"), not(target_arch = "wasm32")))]
 use std::process::Command;
 #[cfg(feature = "resvg")]
 use resvg::usvg;
@@ -19,6 +22,56 @@ use resvg::tiny_skia::{Pixmap, Transform};
 #[cfg(feature = "resvg")]
 use resvg::usvg::TreeParsing;
 
+/// Pan/zoom, tooltips and toggles of the page built by `generate_html`
+const HTML_SCRIPT: &str = r#"<script>
+const svg = document.getElementById('tour');
+const vb = svg.viewBox.baseVal;
+const home = [vb.x, vb.y, vb.width, vb.height];
+const tip = document.getElementById('tip');
+let drag = null;
+
+function toUser(e) {
+    const r = svg.getBoundingClientRect();
+    return { x: vb.x + (e.clientX - r.left) / r.width * vb.width, y: vb.y + (e.clientY - r.top) / r.height * vb.height };
+}
+svg.addEventListener('wheel', e => {
+    e.preventDefault();
+    const k = e.deltaY < 0 ? 0.8 : 1.25;
+    const p = toUser(e);
+    vb.x = p.x - (p.x - vb.x) * k;
+    vb.y = p.y - (p.y - vb.y) * k;
+    vb.width *= k;
+    vb.height *= k;
+});
+svg.addEventListener('mousedown', e => { drag = { x: e.clientX, y: e.clientY }; svg.style.cursor = 'grabbing'; });
+window.addEventListener('mouseup', () => { drag = null; svg.style.cursor = 'grab'; });
+svg.addEventListener('mousemove', e => {
+    if (!drag) return;
+    const r = svg.getBoundingClientRect();
+    vb.x -= (e.clientX - drag.x) * vb.width / r.width;
+    vb.y -= (e.clientY - drag.y) * vb.height / r.height;
+    drag = { x: e.clientX, y: e.clientY };
+});
+document.getElementById('reset').addEventListener('click', () => { [vb.x, vb.y, vb.width, vb.height] = home; });
+
+svg.querySelectorAll('circle').forEach(c => {
+    c.addEventListener('mouseenter', () => {
+        const d = c.dataset;
+        tip.textContent = `Node ${d.id} | demand ${d.demand} | profit ${d.profit} | load on departure ${d.load}`;
+        tip.style.display = 'block';
+    });
+    c.addEventListener('mousemove', e => { tip.style.left = (e.pageX + 12) + 'px'; tip.style.top = (e.pageY + 12) + 'px'; });
+    c.addEventListener('mouseleave', () => { tip.style.display = 'none'; });
+});
+
+for (const [box, target] of [['show-labels', 'labels'], ['show-loads', 'loads'], ['show-profile', 'profile']]) {
+    document.getElementById(box).addEventListener('change', e => {
+        document.getElementById(target).style.display = e.target.checked ? '' : 'none';
+    });
+}
+</script>
+"#;
+
 /// SVG visualization generator
 pub struct Visualizer {
     /// Canvas width
@@ -47,9 +100,16 @@ impl Visualizer {
         Self::default()
     }
     
-    /// Generate SVG visualization of a solution
+    /// Generate SVG visualization of a solution. Each edge is colored by the
+    /// load carried on it, from empty to the vehicle capacity (see the
+    /// colorbar); overloaded or negative loads are drawn dashed in black.
+    /// Node circles grow with |demand|, and when the instance has profits
+    /// each customer is labelled with its profit.
     pub fn generate_svg(&self, instance: &PDTSPInstance, solution: &Solution) -> String {
         let mut svg = String::new();
+        let max_demand = instance.nodes.iter().map(|n| n.demand.abs()).max().unwrap_or(0);
+        let radius = |id: usize| if id == 0 { self.node_radius } else { self.demand_radius(instance.nodes[id].demand, max_demand) };
+        let with_profits = instance.nodes.iter().any(|n| n.profit != 0);
         
         let (min_x, max_x, min_y, max_y) = self.get_bounds(instance);
         
@@ -65,19 +125,26 @@ impl Visualizer {
     .depot {{ fill: #e74c3c; stroke: #c0392b; stroke-width: 2; }}
     .pickup {{ fill: #2ecc71; stroke: #27ae60; stroke-width: 2; }}
     .delivery {{ fill: #f39c12; stroke: #d68910; stroke-width: 2; }}
-    .edge {{ stroke: #34495e; stroke-width: 2; fill: none; }}
+    .edge {{ stroke-width: {stroke:.2}; fill: none; }}
+    .overload {{ stroke: #000000; stroke-dasharray: 6,4; }}
     .label {{ font-family: Arial; font-size: 10px; fill: #2c3e50; }}
+    .profit {{ font-family: Arial; font-size: 9px; fill: #8e44ad; }}
     .title {{ font-family: Arial; font-size: 14px; fill: #2c3e50; font-weight: bold; }}
 </style>
 <rect width="100%" height="100%" fill="#ecf0f1"/>
 "##,
-            self.width, self.height, self.width, self.height
+            self.width, self.height, self.width, self.height, stroke = self.edge_width()
         ));
         
+        let profit_label = if with_profits {
+            format!(" | Profit: {} | Objective: {:.2}", solution.total_profit, solution.objective)
+        } else {
+            String::new()
+        };
         svg.push_str(&format!(
-            r##"<text x="{}" y="25" class="title">Instance: {} | Cost: {:.2} | Feasible: {}</text>
+            r##"<text x="{}" y="25" class="title">Instance: {} | Cost: {:.2}{}{} | Feasible: {}</text>
 "##,
-            self.margin, instance.name, solution.cost, solution.feasible
+            self.margin, instance.name, solution.cost, Self::emissions_label(instance, solution), profit_label, solution.feasible
         ));
         
         let transform = |x: f64, y: f64| -> (f64, f64) {
@@ -87,6 +154,7 @@ impl Visualizer {
         };
         
         if solution.tour.len() > 1 {
+            let profile = solution.load_profile(instance);
             for i in 0..solution.tour.len() {
                 let from = solution.tour[i];
                 let to = solution.tour[(i + 1) % solution.tour.len()];
@@ -94,45 +162,45 @@ impl Visualizer {
                 let (x1, y1) = transform(instance.nodes[from].x, instance.nodes[from].y);
                 let (x2, y2) = transform(instance.nodes[to].x, instance.nodes[to].y);
                 
+                // The edge leaving tour position i carries the load after visiting it
+                let load = profile.get(i).copied().unwrap_or(0);
+                let overloaded = load < 0 || load > instance.capacity;
+                let color = Self::load_color(load as f64 / instance.capacity.max(1) as f64);
                 svg.push_str(&format!(
-                    r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" class="edge" marker-end="url(#arrow)"/>
+                    r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" class="edge{}" stroke="{}"><title>load {}</title></line>
 "#,
-                    x1, y1, x2, y2
+                    x1, y1, x2, y2, if overloaded { " overload" } else { "" }, color, load
                 ));
+                svg.push_str(&self.arrowhead((x1, y1), (x2, y2), radius(to), if overloaded { "#000000" } else { &color }));
             }
         }
         
-        svg.push_str(r##"<defs>
-<marker id="arrow" markerWidth="10" markerHeight="10" refX="9" refY="3" orient="auto" markerUnits="strokeWidth">
-<path d="M0,0 L0,6 L9,3 z" fill="#34495e"/>
-</marker>
-</defs>
-"##);
+        svg.push_str(&self.load_colorbar(instance.capacity));
         
         for node in &instance.nodes {
             let (x, y) = transform(node.x, node.y);
             
-            let class = if node.id == 0 {
-                "depot"
-            } else if node.demand < 0 {
-                "pickup"
-            } else if node.demand > 0 {
-                "delivery"
-            } else {
-                "node"
-            };
+            let r = radius(node.id);
             
             svg.push_str(&format!(
-                r##"<circle cx="{:.2}" cy="{:.2}" r="{}" class="{}"/>
+                r##"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" class="{}"><title>Node {} | demand {} | profit {}</title></circle>
 "##,
-                x, y, self.node_radius, class
+                x, y, r, Self::node_class(instance, node.id), node.id, node.demand, node.profit
             ));
             
             svg.push_str(&format!(
                 r##"<text x="{:.2}" y="{:.2}" class="label" text-anchor="middle">{}</text>
 "##,
-                x, y - self.node_radius - 3.0, node.id
+                x, y - r - 3.0, node.id
             ));
+            
+            if with_profits && node.id != 0 {
+                svg.push_str(&format!(
+                    r##"<text x="{:.2}" y="{:.2}" class="profit" text-anchor="middle">+{}</text>
+"##,
+                    x, y + r + 10.0, node.profit
+                ));
+            }
         }
         
         let legend_y = self.height - 30.0;
@@ -149,11 +217,437 @@ impl Visualizer {
             self.margin + 160.0, legend_y, self.margin + 180.0, legend_y + 12.0
         ));
         
+        svg.push_str(&Self::run_info_footer(solution, self.margin, self.height - 6.0));
         svg.push_str("</svg>");
         
         svg
     }
     
+    /// Edge width scaled with the canvas, so that edges and arrows stay
+    /// readable on large figures
+    fn edge_width(&self) -> f64 {
+        (self.width.min(self.height) / 400.0).max(1.0)
+    }
+    
+    /// Color of a load as a fraction of the capacity, from blue (empty)
+    /// through yellow to red (full)
+    fn load_color(fraction: f64) -> String {
+        let stops = [(0.0, (52.0, 152.0, 219.0)), (0.5, (241.0, 196.0, 15.0)), (1.0, (231.0, 76.0, 60.0))];
+        let t = if fraction.is_finite() { fraction.clamp(0.0, 1.0) } else { 1.0 };
+        let k = if t <= 0.5 { 0 } else { 1 };
+        let ((t0, c0), (t1, c1)) = (stops[k], stops[k + 1]);
+        let u = (t - t0) / (t1 - t0);
+        let mix = |a: f64, b: f64| (a + (b - a) * u).round() as u8;
+        format!("#{:02x}{:02x}{:02x}", mix(c0.0, c1.0), mix(c0.1, c1.1), mix(c0.2, c1.2))
+    }
+    
+    /// Radius of a customer circle, growing with |demand| from half to the
+    /// full `node_radius` at the largest demand of the instance
+    fn demand_radius(&self, demand: i32, max_demand: i32) -> f64 {
+        if max_demand == 0 {
+            return self.node_radius;
+        }
+        self.node_radius * (0.5 + 0.5 * demand.abs() as f64 / max_demand as f64)
+    }
+    
+    /// Filled arrowhead pointing at `to`, stopped short of the node circle
+    fn arrowhead(&self, from: (f64, f64), to: (f64, f64), radius: f64, color: &str) -> String {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        let size = 4.0 * self.edge_width();
+        if length <= radius + size {
+            return String::new();
+        }
+        let (ux, uy) = (dx / length, dy / length);
+        let tip = (to.0 - ux * radius, to.1 - uy * radius);
+        let base = (tip.0 - ux * size * 1.5, tip.1 - uy * size * 1.5);
+        let (px, py) = (-uy * size * 0.6, ux * size * 0.6);
+        format!(
+            "<polygon points=\"{:.2},{:.2} {:.2},{:.2} {:.2},{:.2}\" fill=\"{}\"/>\n",
+            tip.0, tip.1, base.0 + px, base.1 + py, base.0 - px, base.1 - py, color
+        )
+    }
+    
+    /// Vertical colorbar of the edge loads, in the top right corner
+    fn load_colorbar(&self, capacity: i32) -> String {
+        let (x, y, w, h) = (self.width - self.margin + 15.0, self.margin, 12.0, (self.height - 2.0 * self.margin) / 3.0);
+        format!(
+            r##"<defs>
+<linearGradient id="load-gradient" x1="0" y1="1" x2="0" y2="0">
+<stop offset="0" stop-color="{}"/>
+<stop offset="0.5" stop-color="{}"/>
+<stop offset="1" stop-color="{}"/>
+</linearGradient>
+</defs>
+<rect x="{:.2}" y="{:.2}" width="{}" height="{:.2}" fill="url(#load-gradient)" stroke="#2c3e50" stroke-width="0.5"/>
+<text x="{:.2}" y="{:.2}" class="label" text-anchor="middle">{}</text>
+<text x="{:.2}" y="{:.2}" class="label" text-anchor="middle">0</text>
+<text x="{:.2}" y="{:.2}" class="label" text-anchor="middle" transform="rotate(-90 {:.2} {:.2})">Load</text>
+"##,
+            Self::load_color(0.0), Self::load_color(0.5), Self::load_color(1.0),
+            x, y, w, h,
+            x + w / 2.0, y - 4.0, capacity,
+            x + w / 2.0, y + h + 12.0,
+            x - 6.0, y + h / 2.0, x - 6.0, y + h / 2.0
+        )
+    }
+    
+    /// Generate an interactive HTML page of a solution: the tour as an inline
+    /// SVG with pan (drag) and zoom (wheel), node tooltips (id, demand, profit,
+    /// load on departure), and toggles for the node labels, the load carried
+    /// on each edge and the load profile chart. Node labels start hidden
+    /// above 150 nodes, where they would cover the tour.
+    pub fn generate_html(&self, instance: &PDTSPInstance, solution: &Solution) -> String {
+        let n = instance.nodes.len();
+        let transform = self.projection(instance);
+        let radius = self.scaled_node_radius(n);
+        
+        // Load carried on the edge leaving each tour position
+        let profile = solution.load_profile(instance);
+        let mut departure_load: Vec<Option<i32>> = vec![None; n];
+        for (i, &node) in solution.tour.iter().enumerate() {
+            if departure_load[node].is_none() {
+                departure_load[node] = profile.get(i).copied();
+            }
+        }
+        
+        let mut edges = String::new();
+        let mut loads = String::new();
+        if solution.tour.len() > 1 {
+            for i in 0..solution.tour.len() {
+                let from = solution.tour[i];
+                let to = solution.tour[(i + 1) % solution.tour.len()];
+                let (x1, y1) = transform(instance.nodes[from].x, instance.nodes[from].y);
+                let (x2, y2) = transform(instance.nodes[to].x, instance.nodes[to].y);
+                edges.push_str(&format!(
+                    "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" class=\"edge\" marker-end=\"url(#arrow)\"/>\n",
+                    x1, y1, x2, y2
+                ));
+                loads.push_str(&format!(
+                    "<text x=\"{:.2}\" y=\"{:.2}\" class=\"load\" text-anchor=\"middle\">{}</text>\n",
+                    (x1 + x2) / 2.0, (y1 + y2) / 2.0, profile.get(i).copied().unwrap_or(0)
+                ));
+            }
+        }
+        
+        let mut nodes = String::new();
+        let mut labels = String::new();
+        for node in &instance.nodes {
+            let (x, y) = transform(node.x, node.y);
+            let class = Self::node_class(instance, node.id);
+            let load = departure_load.get(node.id).copied().flatten()
+                .map_or_else(|| "not visited".to_string(), |l| l.to_string());
+            nodes.push_str(&format!(
+                "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" class=\"{}\" data-id=\"{}\" data-demand=\"{}\" data-profit=\"{}\" data-load=\"{}\"/>\n",
+                x, y, radius, class, node.id, node.demand, node.profit, load
+            ));
+            labels.push_str(&format!(
+                "<text x=\"{:.2}\" y=\"{:.2}\" class=\"label\" text-anchor=\"middle\">{}</text>\n",
+                x, y - radius - 2.0, node.id
+            ));
+        }
+        
+        let chart = self.generate_load_profile_svg(instance, solution);
+        let chart = chart.split_once("?>").map_or(chart.as_str(), |(_, svg)| svg.trim_start());
+        let title = crate::dashboard::escape_html(&instance.name);
+        
+        let mut html = format!(r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<title>{title}</title>
+<style>
+    body {{ font-family: Arial, sans-serif; margin: 1em 2em; color: #2c3e50; background: #ecf0f1; }}
+    #tour {{ background: white; border: 1px solid #bdc3c7; cursor: grab; max-width: 100%; height: auto; }}
+    .node {{ fill: #3498db; stroke: #2c3e50; stroke-width: 1; }}
+    .depot {{ fill: #e74c3c; stroke: #c0392b; stroke-width: 1; }}
+    .pickup {{ fill: #2ecc71; stroke: #27ae60; stroke-width: 1; }}
+    .delivery {{ fill: #f39c12; stroke: #d68910; stroke-width: 1; }}
+    circle:hover {{ stroke: #000; stroke-width: 2; }}
+    .edge {{ stroke: #34495e; stroke-width: 1.5; fill: none; }}
+    .label {{ font-size: 9px; fill: #2c3e50; pointer-events: none; }}
+    .load {{ font-size: 9px; fill: #8e44ad; font-weight: bold; pointer-events: none; }}
+    #tip {{ position: absolute; display: none; background: #2c3e50; color: white; padding: 4px 8px; border-radius: 3px; font-size: 12px; pointer-events: none; }}
+    .controls label {{ margin-right: 1.5em; }}
+    footer {{ font-size: 0.8em; color: #7f8c8d; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>Cost: {cost:.2}{emissions} | Profit: {profit} | Feasible: {feasible} | Algorithm: {algorithm}</p>
+<p class="controls">
+<label><input type="checkbox" id="show-labels"{labels_checked}> Node labels</label>
+<label><input type="checkbox" id="show-loads"> Edge loads</label>
+<label><input type="checkbox" id="show-profile"> Load profile</label>
+<button id="reset">Reset view</button>
+<span style="color: #7f8c8d">Drag to pan, scroll to zoom</span>
+</p>
+<svg id="tour" xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">
+<defs>
+<marker id="arrow" markerWidth="10" markerHeight="10" refX="9" refY="3" orient="auto" markerUnits="strokeWidth">
+<path d="M0,0 L0,6 L9,3 z" fill="#34495e"/>
+</marker>
+</defs>
+<g id="edges">
+{edges}</g>
+<g id="loads" style="display: none">
+{loads}</g>
+<g id="nodes">
+{nodes}</g>
+<g id="labels"{labels_style}>
+{labels}</g>
+</svg>
+<div id="profile" style="display: none">
+{chart}
+</div>
+<div id="tip"></div>
+"##,
+            title = title,
+            cost = solution.cost,
+            emissions = Self::emissions_label(instance, solution),
+            profit = solution.total_profit,
+            feasible = solution.feasible,
+            algorithm = crate::dashboard::escape_html(&solution.algorithm),
+            labels_checked = if n <= 150 { " checked" } else { "" },
+            labels_style = if n <= 150 { "" } else { " style=\"display: none\"" },
+            width = self.width,
+            height = self.height,
+            edges = edges,
+            loads = loads,
+            nodes = nodes,
+            labels = labels,
+            chart = chart,
+        );
+        
+        if let Some(info) = &solution.run_info {
+            html.push_str(&format!("<footer>{}</footer>\n", crate::dashboard::escape_html(&info.summary())));
+        }
+        html.push_str(HTML_SCRIPT);
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+    
+    /// Generate a figure comparing tours side by side: one panel per solution
+    /// (two per row) at the same scale, captioned with the algorithm, cost and
+    /// profit. Edges shared by all solutions are drawn in grey, the others
+    /// are highlighted. Best read with 2 to 4 solutions.
+    pub fn generate_comparison_svg(&self, instance: &PDTSPInstance, solutions: &[Solution]) -> String {
+        let cols = solutions.len().clamp(1, 2);
+        let rows = solutions.len().div_ceil(2).max(1);
+        let panel = Visualizer {
+            width: self.width / 2.0,
+            height: self.height / 2.0,
+            margin: self.margin / 2.0,
+            node_radius: self.node_radius / 2.0,
+        };
+        let (width, height) = (panel.width * cols as f64, panel.height * rows as f64 + 30.0);
+        let transform = panel.projection(instance);
+        
+        let edge_sets: Vec<HashSet<(usize, usize)>> = solutions.iter()
+            .map(|solution| Self::undirected_edges(&solution.tour).collect())
+            .collect();
+        
+        let mut svg = format!(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">
+<style>
+    .node {{ fill: #3498db; stroke: #2c3e50; stroke-width: 1; }}
+    .depot {{ fill: #e74c3c; stroke: #c0392b; stroke-width: 1; }}
+    .pickup {{ fill: #2ecc71; stroke: #27ae60; stroke-width: 1; }}
+    .delivery {{ fill: #f39c12; stroke: #d68910; stroke-width: 1; }}
+    .shared {{ stroke: #95a5a6; stroke-width: 1.5; }}
+    .differs {{ stroke: #8e44ad; stroke-width: 2.5; }}
+    .frame {{ fill: white; stroke: #bdc3c7; }}
+    .title {{ font-family: Arial; font-size: 14px; fill: #2c3e50; font-weight: bold; }}
+    .caption {{ font-family: Arial; font-size: 11px; fill: #2c3e50; }}
+</style>
+<rect width="100%" height="100%" fill="#ecf0f1"/>
+<text x="{}" y="20" class="title">{} | grey: edges shared by all tours, purple: edges that differ</text>
+"##,
+            width, height, width, height, self.margin / 2.0, instance.name
+        );
+        
+        for (k, solution) in solutions.iter().enumerate() {
+            let (x0, y0) = ((k % cols) as f64 * panel.width, 30.0 + (k / cols) as f64 * panel.height);
+            svg.push_str(&format!(
+                "<g transform=\"translate({:.2},{:.2})\">\n<rect x=\"2\" y=\"2\" width=\"{:.2}\" height=\"{:.2}\" class=\"frame\"/>\n",
+                x0, y0, panel.width - 4.0, panel.height - 4.0
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{:.2}\" y=\"18\" class=\"caption\">{} | Cost: {:.2} | Profit: {}{}</text>\n",
+                panel.margin, solution.algorithm, solution.cost, solution.total_profit,
+                if solution.feasible { "" } else { " | infeasible" }
+            ));
+            for (a, b) in Self::undirected_edges(&solution.tour) {
+                let class = if edge_sets.iter().all(|edges| edges.contains(&(a, b))) { "shared" } else { "differs" };
+                let (x1, y1) = transform(instance.nodes[a].x, instance.nodes[a].y);
+                let (x2, y2) = transform(instance.nodes[b].x, instance.nodes[b].y);
+                svg.push_str(&format!(
+                    "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" class=\"{}\"/>\n",
+                    x1, y1, x2, y2, class
+                ));
+            }
+            svg.push_str(&panel.node_circles(instance, &transform));
+            svg.push_str("</g>\n");
+        }
+        
+        svg.push_str("</svg>");
+        svg
+    }
+    
+    /// Undirected edges of a closed tour, as (smaller, larger) node pairs
+    fn undirected_edges(tour: &[usize]) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let n = if tour.len() < 2 { 0 } else { tour.len() };
+        (0..n).map(move |i| {
+            let (a, b) = (tour[i], tour[(i + 1) % n]);
+            (a.min(b), a.max(b))
+        })
+    }
+    
+    /// Generate an animated SVG of the search: one frame per best tour
+    /// recorded in `solution.snapshots` (see `record_snapshots` on SA, ILS
+    /// and GA), shown `frame_seconds` each, the last one held longer. Viewers
+    /// without SMIL animation support show the final tour.
+    pub fn generate_animation(&self, instance: &PDTSPInstance, solution: &Solution, frame_seconds: f64) -> String {
+        let tours = Self::animation_tours(solution);
+        let transform = self.projection(instance);
+        let slots = tours.len() + 2;
+        let total = frame_seconds * slots as f64;
+        
+        let mut svg = self.animation_header();
+        for (i, (time, tour)) in tours.iter().enumerate() {
+            let last = i + 1 == tours.len();
+            let begin = i as f64 / slots as f64;
+            let end = if last { 1.0 } else { (i + 1) as f64 / slots as f64 };
+            svg.push_str(&format!(
+                "<g visibility=\"{}\">\n<animate attributeName=\"visibility\" values=\"hidden;visible;hidden\" keyTimes=\"0;{:.4};{:.4}\" calcMode=\"discrete\" dur=\"{:.2}s\" repeatCount=\"indefinite\"/>\n",
+                if last { "visible" } else { "hidden" }, begin, end, total
+            ));
+            svg.push_str(&self.tour_edges(instance, tour, &transform));
+            svg.push_str(&Self::frame_caption(instance, solution, *time, tour, i, tours.len(), self.margin));
+            svg.push_str("</g>\n");
+        }
+        svg.push_str(&self.node_circles(instance, &transform));
+        svg.push_str("</svg>");
+        svg
+    }
+    
+    /// Generate the frames of `generate_animation` as standalone SVGs, e.g. to
+    /// assemble a GIF with an external tool
+    pub fn generate_animation_frames(&self, instance: &PDTSPInstance, solution: &Solution) -> Vec<String> {
+        let tours = Self::animation_tours(solution);
+        let transform = self.projection(instance);
+        tours.iter().enumerate().map(|(i, (time, tour))| {
+            let mut svg = self.animation_header();
+            svg.push_str(&self.tour_edges(instance, tour, &transform));
+            svg.push_str(&Self::frame_caption(instance, solution, *time, tour, i, tours.len(), self.margin));
+            svg.push_str(&self.node_circles(instance, &transform));
+            svg.push_str("</svg>");
+            svg
+        }).collect()
+    }
+    
+    /// Tours of an animation: the snapshots, then the final tour if the
+    /// search changed it after the last snapshot (e.g. the memetic VND)
+    fn animation_tours(solution: &Solution) -> Vec<(f64, &[usize])> {
+        let mut tours: Vec<(f64, &[usize])> = solution.snapshots.iter()
+            .map(|(time, tour)| (*time, tour.as_slice()))
+            .collect();
+        if tours.last().is_none_or(|(_, tour)| *tour != solution.tour.as_slice()) {
+            tours.push((solution.computation_time, &solution.tour));
+        }
+        tours
+    }
+    
+    /// SVG prologue of an animation frame
+    fn animation_header(&self) -> String {
+        format!(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">
+<style>
+    .node {{ fill: #3498db; stroke: #2c3e50; stroke-width: 1; }}
+    .depot {{ fill: #e74c3c; stroke: #c0392b; stroke-width: 1; }}
+    .pickup {{ fill: #2ecc71; stroke: #27ae60; stroke-width: 1; }}
+    .delivery {{ fill: #f39c12; stroke: #d68910; stroke-width: 1; }}
+    .edge {{ stroke: #34495e; stroke-width: 1.5; fill: none; }}
+    .title {{ font-family: Arial; font-size: 14px; fill: #2c3e50; font-weight: bold; }}
+</style>
+<defs>
+<marker id="arrow" markerWidth="10" markerHeight="10" refX="9" refY="3" orient="auto" markerUnits="strokeWidth">
+<path d="M0,0 L0,6 L9,3 z" fill="#34495e"/>
+</marker>
+</defs>
+<rect width="100%" height="100%" fill="#ecf0f1"/>
+"##,
+            self.width, self.height, self.width, self.height
+        )
+    }
+    
+    /// Title of an animation frame
+    fn frame_caption(instance: &PDTSPInstance, solution: &Solution, time: f64, tour: &[usize], frame: usize, frames: usize, x: f64) -> String {
+        format!(
+            "<text x=\"{}\" y=\"25\" class=\"title\">{} | t = {:.2}s | Cost: {:.2} | Frame {}/{}</text>\n",
+            x, solution.algorithm, time, instance.tour_cost(tour), frame + 1, frames
+        )
+    }
+    
+    /// Arrowed edges of a closed tour
+    fn tour_edges(&self, instance: &PDTSPInstance, tour: &[usize], transform: &impl Fn(f64, f64) -> (f64, f64)) -> String {
+        let mut edges = String::new();
+        if tour.len() < 2 {
+            return edges;
+        }
+        for i in 0..tour.len() {
+            let from = &instance.nodes[tour[i]];
+            let to = &instance.nodes[tour[(i + 1) % tour.len()]];
+            let (x1, y1) = transform(from.x, from.y);
+            let (x2, y2) = transform(to.x, to.y);
+            edges.push_str(&format!(
+                "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" class=\"edge\" marker-end=\"url(#arrow)\"/>\n",
+                x1, y1, x2, y2
+            ));
+        }
+        edges
+    }
+    
+    /// Node circles styled by depot, pickup or delivery
+    fn node_circles(&self, instance: &PDTSPInstance, transform: &impl Fn(f64, f64) -> (f64, f64)) -> String {
+        let radius = self.scaled_node_radius(instance.nodes.len());
+        instance.nodes.iter().map(|node| {
+            let (x, y) = transform(node.x, node.y);
+            format!("<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" class=\"{}\"/>\n", x, y, radius, Self::node_class(instance, node.id))
+        }).collect()
+    }
+    
+    /// CSS class of a node: depot, pickup (positive demand), delivery or node
+    fn node_class(instance: &PDTSPInstance, id: usize) -> &'static str {
+        let node = &instance.nodes[id];
+        if id == 0 {
+            "depot"
+        } else if node.is_pickup() {
+            "pickup"
+        } else if node.is_delivery() {
+            "delivery"
+        } else {
+            "node"
+        }
+    }
+    
+    /// Node radius shrunk on large instances so nodes do not overlap
+    fn scaled_node_radius(&self, n: usize) -> f64 {
+        (self.node_radius * (150.0 / n.max(1) as f64).sqrt()).clamp(2.0, self.node_radius)
+    }
+    
+    /// Map instance coordinates to the canvas, with a common scale on both axes
+    fn projection(&self, instance: &PDTSPInstance) -> impl Fn(f64, f64) -> (f64, f64) {
+        let (min_x, max_x, min_y, max_y) = self.get_bounds(instance);
+        let scale_x = (self.width - 2.0 * self.margin) / (max_x - min_x).max(1.0);
+        let scale_y = (self.height - 2.0 * self.margin) / (max_y - min_y).max(1.0);
+        let scale = scale_x.min(scale_y);
+        let (margin, height) = (self.margin, self.height);
+        move |x: f64, y: f64| (margin + (x - min_x) * scale, height - margin - (y - min_y) * scale)
+    }
+    
     /// Generate load profile SVG
     pub fn generate_load_profile_svg(&self, instance: &PDTSPInstance, solution: &Solution) -> String {
         let load_profile = solution.load_profile(instance);
@@ -242,11 +736,304 @@ impl Visualizer {
             ));
         }
         
+        svg.push_str(&Self::run_info_footer(solution, margin, height - 6.0));
         svg.push_str("</svg>");
         
         svg
     }
     
+    /// Generate a best-cost-over-time SVG for one or more labelled convergence traces
+    pub fn generate_convergence_svg(&self, runs: &[(&str, &[(f64, f64)])]) -> String {
+        let mut svg = String::new();
+        
+        let width = self.width;
+        let height = 400.0;
+        let margin = 60.0;
+        let palette = ["#3498db", "#e74c3c", "#2ecc71", "#9b59b6", "#f39c12", "#1abc9c", "#34495e"];
+        
+        let points = runs.iter().flat_map(|(_, trace)| trace.iter()).filter(|(_, c)| c.is_finite());
+        let (mut t_max, mut c_min, mut c_max) = (0.0f64, f64::INFINITY, f64::NEG_INFINITY);
+        for &(t, c) in points {
+            t_max = t_max.max(t);
+            c_min = c_min.min(c);
+            c_max = c_max.max(c);
+        }
+        if c_min > c_max {
+            c_min = 0.0;
+            c_max = 1.0;
+        }
+        let t_range = if t_max > 0.0 { t_max } else { 1.0 };
+        let c_range = if c_max > c_min { c_max - c_min } else { 1.0 };
+        
+        svg.push_str(&format!(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">
+<style>
+    .axis {{ stroke: #2c3e50; stroke-width: 1; }}
+    .label {{ font-family: Arial; font-size: 12px; fill: #2c3e50; }}
+    .title {{ font-family: Arial; font-size: 14px; fill: #2c3e50; font-weight: bold; }}
+</style>
+<rect width="100%" height="100%" fill="#ecf0f1"/>
+<text x="{}" y="25" class="title">Convergence - best cost over time</text>
+<line x1="{}" y1="{}" x2="{}" y2="{}" class="axis"/>
+<line x1="{}" y1="{}" x2="{}" y2="{}" class="axis"/>
+<text x="{}" y="{}" class="label">0s</text>
+<text x="{}" y="{}" class="label" text-anchor="end">{:.2}s</text>
+<text x="5" y="{}" class="label">{:.2}</text>
+<text x="5" y="{}" class="label">{:.2}</text>
+"##,
+            width, height, width, height,
+            margin,
+            margin, height - margin, width - margin, height - margin,
+            margin, margin, margin, height - margin,
+            margin, height - margin + 18.0,
+            width - margin, height - margin + 18.0, t_max,
+            margin + 4.0, c_max,
+            height - margin, c_min
+        ));
+        
+        let to_xy = |t: f64, c: f64| -> (f64, f64) {
+            let x = margin + t / t_range * (width - 2.0 * margin);
+            let y = height - margin - (c - c_min) / c_range * (height - 2.0 * margin);
+            (x, y)
+        };
+        
+        for (i, (label, trace)) in runs.iter().enumerate() {
+            let color = palette[i % palette.len()];
+            
+            // Best cost is constant between improvements, so draw a step curve
+            let mut path = String::new();
+            let mut last_y = None;
+            for &(t, c) in trace.iter().filter(|(_, c)| c.is_finite()) {
+                let (x, y) = to_xy(t, c);
+                match last_y {
+                    None => path.push_str(&format!("M {:.2} {:.2}", x, y)),
+                    Some(prev) => path.push_str(&format!(" L {:.2} {:.2} L {:.2} {:.2}", x, prev, x, y)),
+                }
+                last_y = Some(y);
+            }
+            if let Some(y) = last_y {
+                path.push_str(&format!(" L {:.2} {:.2}", width - margin, y));
+                svg.push_str(&format!(
+                    r##"<path d="{}" fill="none" stroke="{}" stroke-width="2"/>
+"##,
+                    path, color
+                ));
+            }
+            
+            svg.push_str(&format!(
+                r##"<text x="{}" y="{}" class="label" style="fill: {}">{}</text>
+"##,
+                width - margin - 140.0, margin + i as f64 * 16.0, color, label
+            ));
+        }
+        
+        svg.push_str("</svg>");
+        
+        svg
+    }
+    
+    /// Generate an SVG of a Pareto front: travel cost against collected
+    /// profit, one point per solution joined by the attainment staircase
+    pub fn generate_pareto_front_svg(&self, front: &ParetoFront) -> String {
+        let width = self.width;
+        let height = 400.0;
+        let margin = 60.0;
+        
+        let solutions = front.solutions();
+        let c_max = solutions.iter().map(|s| s.cost).fold(0.0f64, f64::max).max(1.0);
+        let p_max = solutions.iter().map(|s| s.total_profit).max().unwrap_or(0).max(1) as f64;
+        let to_xy = |cost: f64, profit: i32| -> (f64, f64) {
+            let x = margin + cost / c_max * (width - 2.0 * margin);
+            let y = height - margin - profit as f64 / p_max * (height - 2.0 * margin);
+            (x, y)
+        };
+        
+        let mut svg = format!(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">
+<style>
+    .axis {{ stroke: #2c3e50; stroke-width: 1; }}
+    .front {{ stroke: #3498db; stroke-width: 2; fill: none; }}
+    .point {{ fill: #e74c3c; stroke: #c0392b; stroke-width: 1; }}
+    .label {{ font-family: Arial; font-size: 12px; fill: #2c3e50; }}
+    .title {{ font-family: Arial; font-size: 14px; fill: #2c3e50; font-weight: bold; }}
+</style>
+<rect width="100%" height="100%" fill="#ecf0f1"/>
+<text x="{}" y="25" class="title">Pareto front - {} solutions</text>
+<line x1="{}" y1="{}" x2="{}" y2="{}" class="axis"/>
+<line x1="{}" y1="{}" x2="{}" y2="{}" class="axis"/>
+<text x="{}" y="{}" class="label">cost 0</text>
+<text x="{}" y="{}" class="label" text-anchor="end">{:.2}</text>
+<text x="5" y="{}" class="label">profit {}</text>
+<text x="5" y="{}" class="label">0</text>
+"##,
+            width, height, width, height,
+            margin, solutions.len(),
+            margin, height - margin, width - margin, height - margin,
+            margin, margin, margin, height - margin,
+            margin, height - margin + 18.0,
+            width - margin, height - margin + 18.0, c_max,
+            margin - 8.0, p_max,
+            height - margin
+        );
+        
+        // A point dominates the region above-left of it: draw the staircase
+        let mut path = String::new();
+        let mut last_y = None;
+        for s in solutions {
+            let (x, y) = to_xy(s.cost, s.total_profit);
+            match last_y {
+                None => path.push_str(&format!("M {:.2} {:.2}", x, y)),
+                Some(prev) => path.push_str(&format!(" L {:.2} {:.2} L {:.2} {:.2}", x, prev, x, y)),
+            }
+            last_y = Some(y);
+        }
+        if !path.is_empty() {
+            svg.push_str(&format!("<path d=\"{}\" class=\"front\"/>\n", path));
+        }
+        for s in solutions {
+            let (x, y) = to_xy(s.cost, s.total_profit);
+            svg.push_str(&format!(
+                r##"<circle cx="{:.2}" cy="{:.2}" r="4" class="point"><title>cost {:.2}, profit {}</title></circle>
+"##,
+                x, y, s.cost, s.total_profit
+            ));
+        }
+        
+        svg.push_str("</svg>");
+        
+        svg
+    }
+    
+    /// Generate a Dolan-Moré performance profile SVG: for each solver, the
+    /// fraction of problems solved within a factor tau of the best solver.
+    /// `profiles` holds the sorted performance ratios of each solver.
+    pub fn generate_performance_profile_svg(&self, profiles: &[(String, Vec<f64>)]) -> String {
+        let tau_max = profiles.iter()
+            .flat_map(|(_, ratios)| ratios.iter())
+            .filter(|r| r.is_finite())
+            .fold(1.0f64, |a, &b| a.max(b));
+        // Leave room to the right of the last step
+        let tau_max = if tau_max > 1.0 { 1.0 + (tau_max - 1.0) * 1.05 } else { 1.1 };
+        
+        self.step_chart_svg(
+            "Performance profile - fraction of problems within tau of the best",
+            profiles, 1.0, tau_max,
+        )
+    }
+    
+    /// Generate a time-to-target plot: for each solver, the empirical CDF of
+    /// the time its runs took to reach the target cost. `times` holds the
+    /// sorted times of each solver, infinite for the runs that never reached
+    /// the target, so a curve tops out at the solver's success rate.
+    pub fn generate_time_to_target_svg(&self, times: &[(String, Vec<f64>)], target_gap: f64) -> String {
+        let t_max = times.iter()
+            .flat_map(|(_, t)| t.iter())
+            .filter(|t| t.is_finite())
+            .fold(0.0f64, |a, &b| a.max(b));
+        let t_max = if t_max > 0.0 { t_max * 1.05 } else { 1.0 };
+        
+        self.step_chart_svg(
+            &format!("Time to target (best + {:.1}%) - fraction of runs, time in seconds", target_gap * 100.0),
+            times, 0.0, t_max,
+        )
+    }
+    
+    /// Step curves rising by 1/len at each sorted x value of a series, on
+    /// [x_min, x_max] x [0, 1]; infinite values never rise
+    fn step_chart_svg(&self, title: &str, series: &[(String, Vec<f64>)], x_min: f64, x_max: f64) -> String {
+        let mut svg = String::new();
+        
+        let width = self.width;
+        let height = 400.0;
+        let margin = 60.0;
+        let palette = ["#3498db", "#e74c3c", "#2ecc71", "#9b59b6", "#f39c12", "#1abc9c", "#34495e"];
+        
+        svg.push_str(&format!(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">
+<style>
+    .axis {{ stroke: #2c3e50; stroke-width: 1; }}
+    .label {{ font-family: Arial; font-size: 12px; fill: #2c3e50; }}
+    .title {{ font-family: Arial; font-size: 14px; fill: #2c3e50; font-weight: bold; }}
+</style>
+<rect width="100%" height="100%" fill="#ecf0f1"/>
+<text x="{}" y="25" class="title">{}</text>
+<line x1="{}" y1="{}" x2="{}" y2="{}" class="axis"/>
+<line x1="{}" y1="{}" x2="{}" y2="{}" class="axis"/>
+<text x="{}" y="{}" class="label">{}</text>
+<text x="{}" y="{}" class="label" text-anchor="end">{:.3}</text>
+<text x="5" y="{}" class="label">1.0</text>
+<text x="5" y="{}" class="label">0.0</text>
+"##,
+            width, height, width, height,
+            margin, title,
+            margin, height - margin, width - margin, height - margin,
+            margin, margin, margin, height - margin,
+            margin, height - margin + 18.0, x_min,
+            width - margin, height - margin + 18.0, x_max,
+            margin + 4.0,
+            height - margin
+        ));
+        
+        let to_xy = |x: f64, rho: f64| -> (f64, f64) {
+            let x = margin + (x - x_min) / (x_max - x_min) * (width - 2.0 * margin);
+            let y = height - margin - rho * (height - 2.0 * margin);
+            (x, y)
+        };
+        
+        for (i, (label, values)) in series.iter().enumerate() {
+            let color = palette[i % palette.len()];
+            let total = values.len().max(1) as f64;
+            
+            // rho jumps by 1/total at each value
+            let (x0, y0) = to_xy(x_min, 0.0);
+            let mut path = format!("M {:.2} {:.2}", x0, y0);
+            let mut last_y = y0;
+            for (k, &v) in values.iter().enumerate().filter(|(_, v)| v.is_finite()) {
+                let (x, y) = to_xy(v, (k + 1) as f64 / total);
+                path.push_str(&format!(" L {:.2} {:.2} L {:.2} {:.2}", x, last_y, x, y));
+                last_y = y;
+            }
+            path.push_str(&format!(" L {:.2} {:.2}", width - margin, last_y));
+            svg.push_str(&format!(
+                r##"<path d="{}" fill="none" stroke="{}" stroke-width="2"/>
+"##,
+                path, color
+            ));
+            
+            svg.push_str(&format!(
+                r##"<text x="{}" y="{}" class="label" style="fill: {}">{}</text>
+"##,
+                width - margin - 140.0, height - margin - 10.0 - i as f64 * 16.0, color, label
+            ));
+        }
+        
+        svg.push_str("</svg>");
+        
+        svg
+    }
+    
+    /// " | CO2: x" title suffix when the instance has a vehicle profile
+    fn emissions_label(instance: &PDTSPInstance, solution: &Solution) -> String {
+        instance.tour_emissions(&solution.tour)
+            .map(|e| format!(" | CO2: {:.2}", e))
+            .unwrap_or_default()
+    }
+    
+    /// Footer line with the run metadata of the solution (empty if none)
+    fn run_info_footer(solution: &Solution, x: f64, y: f64) -> String {
+        match &solution.run_info {
+            Some(info) => format!(
+                r##"<text x="{}" y="{}" font-family="Arial" font-size="8px" fill="#7f8c8d">{}</text>
+"##,
+                x, y, info.summary()
+            ),
+            None => String::new(),
+        }
+    }
+    
     /// Save SVG to file
     pub fn save_svg<P: AsRef<Path>>(&self, svg: &str, path: P) -> std::io::Result<()> {
         let mut file = File::create(path)?;
@@ -262,7 +1049,7 @@ impl Visualizer {
         #[cfg(feature = "resvg")]
         {
             // parse
-            let mut opt = usvg::Options::default();
+            let opt = usvg::Options::default();
             // keep default DPI and font dirs
             let rtree = usvg::Tree::from_str(svg, &opt).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("usvg parse error: {}", e)))?;
             // try to infer canvas size from SVG header (width/height attributes), fallback to 800x800
@@ -280,10 +1067,18 @@ impl Visualizer {
             }
             let mut pixmap = Pixmap::new(w.max(1), h.max(1)).ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "Failed to create pixmap"))?;
             render(&rtree, FitTo::Original, Transform::default(), pixmap.as_mut()).ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "resvg render failed"))?;
-            pixmap.save_png(path).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("save_png failed: {}", e)))?;
-            return Ok(());
+            pixmap.save_png(path).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("save_png failed: {}", e)))
         }
 
+        #[cfg(not(feature = "resvg"))]
+        {
+            Self::save_png_with_converters(svg, path)
+        }
+    }
+    
+    /// Fallback PNG export: write a temporary SVG and try external converters
+    #[cfg(all(not(feature = "resvg"), not(target_arch = "wasm32")))]
+    fn save_png_with_converters(svg: &str, path: &Path) -> std::io::Result<()> {
         // Fallback: write temporary svg and try external converters
         let tmp_svg = path.with_extension("svg.tmp");
         {
@@ -319,12 +1114,18 @@ impl Visualizer {
         let _ = std::fs::remove_file(&tmp_svg);
         Err(std::io::Error::new(std::io::ErrorKind::Other, "No SVG->PNG converter succeeded (tried resvg, rsvg-convert, magick, inkscape)"))
     }
+    
+    /// No external processes can be spawned in the browser
+    #[cfg(all(not(feature = "resvg"), target_arch = "wasm32"))]
+    fn save_png_with_converters(_svg: &str, _path: &Path) -> std::io::Result<()> {
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "PNG export needs the resvg feature on wasm32"))
+    }
 
     /// Render an SVG string directly to PNG file using available renderer.
     pub fn svg_to_png_file(svg: &str, out: &Path) -> Result<(), Box<dyn std::error::Error>> {
         #[cfg(feature = "resvg")]
         {
-            let mut opt = usvg::Options::default();
+            let opt = usvg::Options::default();
             let rtree = usvg::Tree::from_str(svg, &opt)?;
             // infer width/height from svg text
             let mut w = 800u32;
@@ -342,26 +1143,13 @@ impl Visualizer {
             let mut pixmap = Pixmap::new(w.max(1), h.max(1)).ok_or("Failed to create pixmap")?;
             render(&rtree, FitTo::Original, Transform::default(), pixmap.as_mut()).ok_or("resvg render failed")?;
             pixmap.save_png(out)?;
-            return Ok(());
+            Ok(())
         }
 
-        // Fallback: write svg to file and attempt external commands
-        std::fs::write(out.with_extension("svg.tmp"), svg)?;
-        let tmp = out.with_extension("svg.tmp");
-        if Command::new("rsvg-convert").args(&["-o", out.to_string_lossy().as_ref(), tmp.to_string_lossy().as_ref()]).status().is_ok() {
-            let _ = std::fs::remove_file(&tmp);
-            return Ok(());
-        }
-        if Command::new("magick").args(&["convert", tmp.to_string_lossy().as_ref(), out.to_string_lossy().as_ref()]).status().is_ok() {
-            let _ = std::fs::remove_file(&tmp);
-            return Ok(());
-        }
-        if Command::new("inkscape").args(&[tmp.to_string_lossy().as_ref(), "--export-type=png", "--export-filename", out.to_string_lossy().as_ref()]).status().is_ok() {
-            let _ = std::fs::remove_file(&tmp);
-            return Ok(());
+        #[cfg(not(feature = "resvg"))]
+        {
+            Ok(Self::save_png_with_converters(svg, out)?)
         }
-        let _ = std::fs::remove_file(&tmp);
-        Err("No converter available".into())
     }
     
     /// Get coordinate bounds
@@ -381,6 +1169,55 @@ impl Visualizer {
         (min_x, max_x, min_y, max_y)
     }
     
+    /// Export a solution as a GeoJSON FeatureCollection, for instances whose
+    /// coordinates are geographic (x = longitude, y = latitude, in decimal
+    /// degrees) and can be laid over a map with Leaflet, QGIS or geojson.io.
+    /// Each node is a Point with its id, kind, demand, profit and the load on
+    /// departure (for visited nodes); the tour is a closed LineString carrying
+    /// the algorithm, cost, profit and feasibility.
+    pub fn export_geojson(&self, instance: &PDTSPInstance, solution: &Solution) -> String {
+        let profile = solution.load_profile(instance);
+        let mut departure_load = vec![None; instance.nodes.len()];
+        for (i, &node) in solution.tour.iter().enumerate() {
+            departure_load[node] = profile.get(i).copied();
+        }
+        
+        let mut features: Vec<serde_json::Value> = instance.nodes.iter().map(|node| {
+            serde_json::json!({
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [node.x, node.y] },
+                "properties": {
+                    "id": node.id,
+                    "kind": Self::node_class(instance, node.id),
+                    "demand": node.demand,
+                    "profit": node.profit,
+                    "load": departure_load[node.id],
+                },
+            })
+        }).collect();
+        
+        if solution.tour.len() > 1 {
+            let mut line: Vec<[f64; 2]> = solution.tour.iter()
+                .map(|&n| [instance.nodes[n].x, instance.nodes[n].y])
+                .collect();
+            line.push(line[0]);
+            features.push(serde_json::json!({
+                "type": "Feature",
+                "geometry": { "type": "LineString", "coordinates": line },
+                "properties": {
+                    "instance": instance.name,
+                    "algorithm": solution.algorithm,
+                    "cost": if solution.cost.is_finite() { Some(solution.cost) } else { None },
+                    "profit": solution.total_profit,
+                    "feasible": solution.feasible,
+                },
+            }));
+        }
+        
+        let collection = serde_json::json!({ "type": "FeatureCollection", "features": features });
+        serde_json::to_string_pretty(&collection).unwrap_or_default()
+    }
+    
     /// Export data for external plotting (e.g., matplotlib)
     pub fn export_plot_data(&self, instance: &PDTSPInstance, solution: &Solution) -> String {
         let mut data = String::new();
@@ -388,6 +1225,9 @@ impl Visualizer {
         data.push_str("# PD-TSP Solution Data\n");
         data.push_str(&format!("# Instance: {}\n", instance.name));
         data.push_str(&format!("# Cost: {:.2}\n", solution.cost));
+        if let Some(emissions) = instance.tour_emissions(&solution.tour) {
+            data.push_str(&format!("# Emissions: {:.2}\n", emissions));
+        }
         data.push_str(&format!("# Feasible: {}\n\n", solution.feasible));
         
         data.push_str("# Nodes: id, x, y, demand\n");
@@ -428,7 +1268,7 @@ pub fn generate_comparison_data(_instance: &PDTSPInstance, solutions: &[Solution
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::instance::Node;
+    use crate::instance::{DistanceMatrix, DistanceMode, Node};
     
     fn create_test_instance() -> PDTSPInstance {
         let nodes = vec![
@@ -437,20 +1277,7 @@ mod tests {
             Node::new(2, 0.0, 1.0, -5, 0),
         ];
         
-        use crate::instance::CostFunction;
-        
-        PDTSPInstance {
-            cost_function: CostFunction::Distance,
-            alpha: 0.1,
-            beta: 0.5,
-            name: "test".to_string(),
-            comment: "test".to_string(),
-            dimension: 3,
-            capacity: 10,
-            nodes,
-            distance_matrix: vec![vec![0.0; 3]; 3],
-            return_depot_demand: 0,
-        }
+        PDTSPInstance::from_parts("test", 10, nodes, DistanceMatrix::new(3))
     }
     
     #[test]
@@ -464,4 +1291,128 @@ mod tests {
         assert!(svg.contains("svg"));
         assert!(svg.contains("test"));
     }
+    
+    #[test]
+    fn test_load_colored_edges() {
+        let instance = create_test_instance();
+        let viz = Visualizer::new();
+        assert_eq!(Visualizer::load_color(0.0), "#3498db");
+        assert_eq!(Visualizer::load_color(1.0), "#e74c3c");
+        assert_eq!(Visualizer::load_color(2.0), Visualizer::load_color(1.0));
+        
+        // Loads 5, 0, 0 on the edges of [0, 1, 2]; [0, 2, 1] goes negative
+        let svg = viz.generate_svg(&instance, &Solution::from_tour(&instance, vec![0, 1, 2], "test"));
+        assert!(svg.contains(&format!("stroke=\"{}\"><title>load 5</title>", Visualizer::load_color(0.5))));
+        assert_eq!(svg.matches("<polygon").count(), 3);
+        assert!(svg.contains("load-gradient") && !svg.contains(" overload\""));
+        let svg = viz.generate_svg(&instance, &Solution::from_tour(&instance, vec![0, 2, 1], "test"));
+        assert_eq!(svg.matches("class=\"edge overload\"").count(), 1);
+    }
+    
+    #[test]
+    fn test_node_classes_and_profit_labels() {
+        let mut instance = create_test_instance();
+        instance.nodes.push(Node::new(3, 1.0, 1.0, 2, 0));
+        instance.dimension = 4;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        let viz = Visualizer::new();
+        let solution = Solution::from_tour(&instance, vec![0, 1, 3, 2], "test");
+        
+        // Node 1 picks up 5 units, node 2 delivers 5, node 3 picks up 2
+        let svg = viz.generate_svg(&instance, &solution);
+        assert!(svg.contains("r=\"8.00\" class=\"pickup\"><title>Node 1 "));
+        assert!(svg.contains("r=\"8.00\" class=\"delivery\"><title>Node 2 "));
+        assert!(svg.contains("r=\"5.60\" class=\"pickup\"><title>Node 3 "));
+        assert!(!svg.contains("class=\"profit\"") && !svg.contains("Objective"));
+        
+        instance.nodes[1].profit = 30;
+        instance.nodes[3].profit = 12;
+        let svg = viz.generate_svg(&instance, &Solution::from_tour(&instance, vec![0, 1, 3, 2], "test"));
+        assert!(svg.contains("class=\"profit\" text-anchor=\"middle\">+30</text>"));
+        assert!(svg.contains(">+12</text>") && svg.contains(">+0</text>"));
+        assert!(svg.contains("| Profit: 42 | Objective:"));
+    }
+    
+    #[test]
+    fn test_geojson_export() {
+        let instance = create_test_instance();
+        let solution = Solution::from_tour(&instance, vec![0, 1, 2], "test");
+        let geojson: serde_json::Value = serde_json::from_str(&Visualizer::new().export_geojson(&instance, &solution)).unwrap();
+        
+        assert_eq!(geojson["type"], "FeatureCollection");
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 4);
+        assert_eq!(features[1]["geometry"]["coordinates"], serde_json::json!([1.0, 0.0]));
+        assert_eq!(features[1]["properties"]["kind"], "pickup");
+        assert_eq!(features[1]["properties"]["load"], 5);
+        assert_eq!(features[2]["properties"]["kind"], "delivery");
+        assert_eq!(features[3]["geometry"]["type"], "LineString");
+        assert_eq!(features[3]["geometry"]["coordinates"].as_array().unwrap().len(), 4);
+        assert_eq!(features[3]["properties"]["algorithm"], "test");
+    }
+    
+    #[test]
+    fn test_interactive_html() {
+        let instance = create_test_instance();
+        let solution = Solution::from_tour(&instance, vec![0, 1, 2], "test");
+        
+        let html = Visualizer::new().generate_html(&instance, &solution);
+        
+        assert!(html.starts_with("<!DOCTYPE html>") && html.contains("<script>"));
+        assert_eq!(html.matches("data-load=").count(), 3);
+        assert_eq!(html.matches("class=\"load\"").count(), 3);
+        // Node 1 picks up 5, node 2 delivers them
+        assert!(html.contains("data-id=\"1\" data-demand=\"5\" data-profit=\"0\" data-load=\"5\""));
+        assert!(html.contains("data-id=\"2\" data-demand=\"-5\" data-profit=\"0\" data-load=\"0\""));
+        assert!(!html.contains("<?xml"));
+    }
+    
+    #[test]
+    fn test_comparison_svg() {
+        let mut instance = create_test_instance();
+        instance.nodes.push(Node::new(3, 1.0, 1.0, 0, 0));
+        instance.dimension = 4;
+        instance.distance_matrix = DistanceMatrix::from_nodes(&instance.nodes, DistanceMode::Dense);
+        let a = Solution::from_tour(&instance, vec![0, 1, 3, 2], "A");
+        let b = Solution::from_tour(&instance, vec![0, 1, 2, 3], "B");
+        
+        let svg = Visualizer::new().generate_comparison_svg(&instance, &[a, b]);
+        
+        // The tours share edges 0-1 and 2-3, in either direction
+        assert_eq!(svg.matches("class=\"shared\"").count(), 4);
+        assert_eq!(svg.matches("class=\"differs\"").count(), 4);
+        assert_eq!(svg.matches("<circle").count(), 8);
+        assert!(svg.contains("A | Cost:") && svg.contains("B | Cost:"));
+    }
+    
+    #[test]
+    fn test_animation() {
+        let instance = create_test_instance();
+        let mut solution = Solution::from_tour(&instance, vec![0, 1, 2], "SA");
+        solution.snapshots = vec![(0.0, vec![0, 2, 1]), (0.5, vec![0, 1, 2])];
+        let viz = Visualizer::new();
+        
+        let svg = viz.generate_animation(&instance, &solution, 0.5);
+        assert_eq!(svg.matches("<animate ").count(), 2);
+        assert_eq!(svg.matches("<g visibility=\"visible\">").count(), 1);
+        assert!(svg.contains("Frame 2/2") && svg.contains("dur=\"2.00s\""));
+        
+        // A final tour changed after the last snapshot gets its own frame
+        solution.tour = vec![0, 2, 1];
+        let frames = viz.generate_animation_frames(&instance, &solution);
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|f| f.ends_with("</svg>") && f.matches("<line").count() == 3));
+    }
+    
+    #[test]
+    fn test_convergence_svg() {
+        let viz = Visualizer::new();
+        let sa = [(0.0, 120.0), (0.5, 100.0), (2.0, 90.0)];
+        let ga = [(0.0, 130.0), (1.0, 95.0)];
+        let svg = viz.generate_convergence_svg(&[("SA", &sa), ("GA", &ga)]);
+        
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert!(svg.contains(">SA</text>") && svg.contains(">GA</text>"));
+        assert!(svg.contains("2.00s"));
+    }
 }
@@ -0,0 +1,207 @@
+//! Browser-facing API.
+//!
+//! `solve_from_string` takes the text of a TSP-LIB instance and a JSON object
+//! of options, solves it without any file access and returns the solution as
+//! JSON. With the `wasm` feature the function is exported to JavaScript via
+//! `wasm-bindgen`; build with
+//!
+//! ```text
+//! wasm-pack build --target web -- --features wasm
+//! ```
+//!
+//! Only the constructions, VND and simulated annealing are offered: they run
+//! on a single thread, which is all the browser provides.
+
+use crate::heuristics::construction::{
+    ConstructionHeuristic, GreedyInsertionHeuristic, MultiStartConstruction, NearestNeighborHeuristic,
+    SavingsHeuristic, SweepHeuristic,
+};
+use crate::heuristics::local_search::{LocalSearch, SimulatedAnnealing, VND};
+use crate::heuristics::rng::RngFactory;
+use crate::heuristics::stopping::Deadline;
+use crate::instance::{CostFunction, PDTSPInstance};
+use crate::solution::Solution;
+use crate::visualization::Visualizer;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Options of `solve_from_string`; every field is optional
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolveOptions {
+    /// nn, greedy, savings, sweep, multistart, vnd or sa
+    pub algorithm: String,
+    /// distance, quadratic or linear-load
+    pub cost_function: String,
+    /// Load weight of the quadratic and linear-load cost functions
+    pub alpha: f64,
+    /// Quadratic load weight
+    pub beta: f64,
+    /// Time limit of simulated annealing (seconds)
+    pub time_limit: f64,
+    /// Random seed
+    pub seed: u64,
+    /// Assign random profits in [10, max_profit] when greater than 0
+    pub max_profit: i32,
+    /// Also return an SVG drawing of the tour
+    pub svg: bool,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions {
+            algorithm: "vnd".to_string(),
+            cost_function: "distance".to_string(),
+            alpha: 0.1,
+            beta: 0.0,
+            time_limit: 5.0,
+            seed: 42,
+            max_profit: 0,
+            svg: false,
+        }
+    }
+}
+
+/// Solution returned by `solve_from_string`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolveResult {
+    pub algorithm: String,
+    pub tour: Vec<usize>,
+    /// Travel cost; `null` when no tour was found
+    pub cost: Option<f64>,
+    pub profit: i32,
+    pub objective: Option<f64>,
+    pub feasible: bool,
+    /// Load after leaving the depot and each tour position
+    pub load_profile: Vec<i32>,
+    /// Computation time in seconds
+    pub time: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub svg: Option<String>,
+}
+
+/// Apply the cost function, load weights and random profits of `options`
+pub fn configure(instance: &mut PDTSPInstance, options: &SolveOptions) -> Result<(), String> {
+    instance.cost_function = match options.cost_function.as_str() {
+        "distance" => CostFunction::Distance,
+        "quadratic" => CostFunction::Quadratic,
+        "linear-load" | "linear_load" => CostFunction::LinearLoad,
+        "duration" => CostFunction::Duration,
+        other => return Err(format!("Unknown cost function: {}", other)),
+    };
+    instance.alpha = options.alpha;
+    instance.beta = options.beta;
+    if options.max_profit > 0 {
+        instance.assign_random_profits(options.seed, options.max_profit);
+    }
+    Ok(())
+}
+
+/// Run the algorithm of `options` on a configured instance
+pub fn run(instance: &PDTSPInstance, options: &SolveOptions) -> Result<Solution, String> {
+    let rng = RngFactory::new(options.seed);
+    let solution = match options.algorithm.as_str() {
+        "nn" => NearestNeighborHeuristic::new().construct(instance),
+        "greedy" => GreedyInsertionHeuristic::new().construct(instance),
+        "savings" => SavingsHeuristic::new().construct(instance),
+        "sweep" => SweepHeuristic::rotated().construct(instance),
+        "multistart" => MultiStartConstruction::with_all_heuristics_seeded(&rng).construct(instance),
+        "vnd" => {
+            let mut sol = MultiStartConstruction::with_all_heuristics_seeded(&rng).construct(instance);
+            VND::with_standard_operators().improve(instance, &mut sol);
+            sol.algorithm = "VND".to_string();
+            sol
+        }
+        "sa" => {
+            let mut sol = MultiStartConstruction::with_all_heuristics_seeded(&rng).construct(instance);
+            let mut sa = SimulatedAnnealing::new();
+            sa.seed = rng.derive("sa");
+            sa.deadline = Deadline::after(options.time_limit);
+            sa.improve(instance, &mut sol);
+            sol.algorithm = "SimulatedAnnealing".to_string();
+            sol
+        }
+        other => return Err(format!("Unknown algorithm: {}", other)),
+    };
+    Ok(solution)
+}
+
+/// Parse `SolveOptions` from JSON; an empty string means the defaults
+pub fn parse_options(options_json: &str) -> Result<SolveOptions, String> {
+    if options_json.trim().is_empty() {
+        return Ok(SolveOptions::default());
+    }
+    serde_json::from_str(options_json).map_err(|e| format!("Invalid options: {}", e))
+}
+
+/// Solve a TSP-LIB instance given as text; `options_json` is a `SolveOptions`
+/// object (an empty string means the defaults)
+pub fn solve_json(tsp_text: &str, options_json: &str) -> Result<String, String> {
+    let options = parse_options(options_json)?;
+    let mut instance = PDTSPInstance::from_tsplib_str(tsp_text)?;
+    configure(&mut instance, &options)?;
+    let solution = run(&instance, &options)?;
+
+    let result = SolveResult {
+        algorithm: solution.algorithm.clone(),
+        tour: solution.tour.clone(),
+        cost: Some(solution.cost).filter(|c| c.is_finite()),
+        profit: solution.total_profit,
+        objective: Some(solution.objective).filter(|o| o.is_finite()),
+        feasible: solution.feasible,
+        load_profile: solution.load_profile(&instance),
+        time: solution.computation_time,
+        svg: options.svg.then(|| Visualizer::new().generate_svg(&instance, &solution)),
+    };
+    serde_json::to_string(&result).map_err(|e| e.to_string())
+}
+
+/// JavaScript entry point: `solve_from_string(tspText, optionsJson)` returns
+/// the solution JSON or throws the error message
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn solve_from_string(tsp_text: &str, options_json: &str) -> Result<String, JsValue> {
+    solve_json(tsp_text, options_json).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TSP: &str = "NAME: tiny
+DIMENSION: 4
+CAPACITY: 10
+EDGE_WEIGHT_TYPE: EUC_2D
+NODE_COORD_SECTION
+1 0 0
+2 10 0
+3 10 10
+4 0 10
+DEMAND_SECTION
+1 0
+2 4
+3 -4
+4 0
+EOF
+";
+
+    #[test]
+    fn test_solve_from_text() {
+        let result: SolveResult = serde_json::from_str(&solve_json(TSP, "").unwrap()).unwrap();
+        assert_eq!(result.algorithm, "VND");
+        assert!(result.feasible);
+        assert_eq!(result.tour[0], 0);
+        assert_eq!(result.tour.len(), 4);
+        assert!((result.cost.unwrap() - 40.0).abs() < 1e-6);
+
+        let options = r#"{ "algorithm": "sa", "time_limit": 0.2, "max_profit": 50, "svg": true }"#;
+        let result: SolveResult = serde_json::from_str(&solve_json(TSP, options).unwrap()).unwrap();
+        assert!(result.feasible && result.profit > 0);
+        assert!(result.svg.unwrap().starts_with("<?xml"));
+
+        assert!(solve_json(TSP, r#"{ "algorithm": "gurobi" }"#).unwrap_err().contains("Unknown algorithm"));
+        assert!(solve_json(TSP, "{ nope").unwrap_err().contains("Invalid options"));
+    }
+}